@@ -481,7 +481,7 @@ EOF
 Development tools (since -d was provided):
   * mysql client
   * python3 (boto3, ruff, yamllint, ...)
-  * python database drivers (mysql-connector-python, pymysql, sqlalchemy, clickhouse_driver)
+  * python database drivers (mysql-connector-python, pymysql, sqlalchemy, clickhouse_driver, psycopg2)
 EOF
 	fi
 
@@ -687,7 +687,7 @@ if [[ "$INSTALL_DEV_TOOLS" == "true" ]]; then
 	fi
 	python3 -m pip install --quiet boto3 "moto[all]" shfmt-py toml yamllint ruff
 	# drivers
-	python3 -m pip install --quiet pymysql sqlalchemy clickhouse_driver
+	python3 -m pip install --quiet pymysql sqlalchemy clickhouse_driver psycopg2-binary
 	# sqllogic dependencies
 	python3 -m pip install --quiet mysql-connector-python==8.0.30
 fi
@@ -33,7 +33,9 @@ use databend_common_storage::DataOperator;
 use databend_common_tracing::set_panic_hook;
 use databend_enterprise_background_service::get_background_service_handler;
 use databend_query::clusters::ClusterDiscovery;
+use databend_query::dynamic_tables::DynamicTableScheduler;
 use databend_query::local;
+use databend_query::pipes::PipeScheduler;
 use databend_query::servers::admin::AdminService;
 use databend_query::servers::flight::FlightService;
 use databend_query::servers::metrics::MetricService;
@@ -42,8 +44,10 @@ use databend_query::servers::HttpHandler;
 use databend_query::servers::HttpHandlerKind;
 use databend_query::servers::MySQLHandler;
 use databend_query::servers::MySQLTlsConfig;
+use databend_query::servers::PostgresHandler;
 use databend_query::servers::Server;
 use databend_query::servers::ShutdownHandle;
+use databend_query::tasks::TaskScheduler;
 use databend_query::GlobalServices;
 use log::info;
 
@@ -178,6 +182,32 @@ pub async fn start_services(conf: &InnerConfig) -> Result<(), MainError> {
         );
     }
 
+    // PostgreSQL handler.
+    {
+        let hostname = conf.query.postgres_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.postgres_handler_port);
+        let tcp_keepalive_timeout_secs = conf.query.postgres_handler_tcp_keepalive_timeout_secs;
+        let tls_config = MySQLTlsConfig::new(
+            conf.query.postgres_tls_server_cert.clone(),
+            conf.query.postgres_tls_server_key.clone(),
+        );
+
+        let mut handler = PostgresHandler::create(tcp_keepalive_timeout_secs, tls_config)
+            .with_context(make_error)?;
+        let listening = handler
+            .start(listening.parse().with_context(make_error)?)
+            .await
+            .with_context(make_error)?;
+        shutdown_handle.add_service("PostgresHandler", handler);
+
+        info!(
+            "Listening for PostgreSQL compatibility protocol: {}, Usage: psql -U root -h {} -p {}",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
     // ClickHouse HTTP handler.
     {
         let hostname = conf.query.clickhouse_http_handler_host.clone();
@@ -260,6 +290,17 @@ pub async fn start_services(conf: &InnerConfig) -> Result<(), MainError> {
         info!("Listening for FlightSQL API: {}", listening);
     }
 
+    // Auto-ingest pipes.
+    PipeScheduler::start(conf.clone());
+
+    // Built-in task scheduler, tasks are run by the cloud control plane if it is configured.
+    if conf.query.cloud_control_grpc_server_address.is_none() {
+        TaskScheduler::start(conf.clone());
+    }
+
+    // Keep the dynamic tables refreshed.
+    DynamicTableScheduler::start(conf.clone());
+
     // Print information to users.
     println!("Databend Query");
 
@@ -367,6 +408,15 @@ pub async fn start_services(conf: &InnerConfig) -> Result<(), MainError> {
         "    connect via: mysql -u${{USER}} -p${{PASSWORD}} -h{} -P{}",
         conf.query.mysql_handler_host, conf.query.mysql_handler_port
     );
+    println!("PostgreSQL");
+    println!(
+        "    listened at {}:{}",
+        conf.query.postgres_handler_host, conf.query.postgres_handler_port
+    );
+    println!(
+        "    connect via: psql -U ${{USER}} -h {} -p {}",
+        conf.query.postgres_handler_host, conf.query.postgres_handler_port
+    );
     println!("Clickhouse(http)");
     println!(
         "    listened at {}:{}",
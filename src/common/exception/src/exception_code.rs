@@ -183,6 +183,16 @@ build_exceptions! {
     ///
     /// For example: try to with 3 columns into a table with 4 columns.
     TableSchemaMismatch(1303),
+    /// ConstraintViolation is used when the data violates a constraint of the table.
+    ///
+    /// For example: insert a row with a duplicated primary key.
+    ConstraintViolation(1304),
+    /// UnknownConstraint is used when the constraint does not exist in the table.
+    UnknownConstraint(1305),
+    /// ConstraintAlreadyExists is used when a constraint with the same name exists.
+    ConstraintAlreadyExists(1306),
+    /// ColumnReferencedByConstraint is used when altering a column referenced by a constraint.
+    ColumnReferencedByConstraint(1307),
 
     // License related errors starts here
 
@@ -327,6 +337,15 @@ build_exceptions! {
     IllegalConnection(2511),
     ConnectionAlreadyExists(2512),
 
+    // Pipe error codes.
+    UnknownPipe(2513),
+    PipeAlreadyExists(2514),
+    IllegalPipe(2515),
+
+    // Task error codes.
+    UnknownTask(2516),
+    TaskAlreadyExists(2517),
+
     // User defined function error codes.
     IllegalUDFFormat(2601),
     UnknownUDF(2602),
@@ -383,6 +402,9 @@ build_exceptions! {
     OnlySupportAsciiChars(2802),
     WrongValueForVariable(2803),
 
+    // Prepared statement error codes.
+    UnknownPreparedStatement(2804),
+
     // Tenant quota error codes.
     IllegalTenantQuotaFormat(2901),
     TenantQuotaUnknown(2902),
@@ -425,6 +447,8 @@ build_exceptions! {
     CurrentTransactionIsAborted(4002),
     TransactionTimeout(4003),
     InvalidSessionState(4004),
+    UnknownSavepoint(4005),
+    NoActiveTransaction(4006),
 
     // recluster error codes
     NoNeedToCompact(4012),
@@ -1879,154 +1879,24 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
         let table_id = req.table_id;
         debug!(req :? =(&table_id); "SchemaApi: {}", func_name!());
 
-        let tenant_dbname_tbname = &req.name_ident;
-
         let mut trials = txn_backoff(None, func_name!());
         loop {
             trials.next().unwrap()?.await;
 
-            // Get db by name to ensure presence
-
-            let (db_meta_seq, db_meta) =
-                get_db_by_id_or_err(self, req.db_id, "commit_table_meta").await?;
-            let db_id = req.db_id;
-
-            // Get table by tenant,db_id, table_name to assert presence.
-
-            let dbid_tbname = DBIdTableName {
-                db_id,
-                table_name: tenant_dbname_tbname.table_name.clone(),
-            };
-
-            let (dbid_tbname_seq, _table_id) = get_u64_value(self, &dbid_tbname).await?;
-
-            // get table id list from _fd_table_id_list/db_id/table_name
-
-            let orphan_dbid_tbname_idlist = TableIdHistoryIdent {
-                database_id: db_id,
-                table_name: req.orphan_table_name.clone().unwrap(),
-            };
-            let dbid_tbname_idlist = TableIdHistoryIdent {
-                database_id: db_id,
-                table_name: tenant_dbname_tbname.table_name.clone(),
-            };
-
-            let keys = vec![
-                orphan_dbid_tbname_idlist.to_string_key(),
-                dbid_tbname_idlist.to_string_key(),
-            ];
-
-            let mut data = {
-                let values = self.mget_kv(&keys).await?;
-                keys.iter()
-                    .zip(values.into_iter())
-                    .map(|(k, v)| TxnGetResponse::new(k, v.map(pb::SeqV::from)))
-                    .collect::<Vec<_>>()
-            };
-
-            let orphan_tb_id_list = {
-                let d = data.remove(0);
-                let (k, v) = deserialize_struct_get_response::<TableIdHistoryIdent>(d)?;
-                assert_eq!(orphan_dbid_tbname_idlist, k);
-
-                v.unwrap_or_default()
-            };
-            if orphan_tb_id_list.data.id_list.len() != 1 {
-                error!("table {:?} orphan list is empty", tenant_dbname_tbname);
-                let exist_err = CommitTableMetaError::new(
-                    tenant_dbname_tbname.table_name.clone(),
-                    "orphan list length != 1".to_string(),
-                );
-                return Err(KVAppError::AppError(AppError::from(exist_err)));
-            }
-
-            let mut tb_id_list = {
-                let d = data.remove(0);
-                let (k, v) = deserialize_struct_get_response::<TableIdHistoryIdent>(d)?;
-                assert_eq!(dbid_tbname_idlist, k);
-
-                v.unwrap_or_default()
-            };
-
-            if tb_id_list.data.id_list.last() != req.prev_table_id.as_ref() {
-                error!(
-                    "table {:?} table id list has been changed",
-                    tenant_dbname_tbname
-                );
-                let exist_err = CommitTableMetaError::new(
-                    tenant_dbname_tbname.table_name.clone(),
-                    "prev_table_id has been changed".to_string(),
-                );
-                return Err(KVAppError::AppError(AppError::from(exist_err)));
-            }
-
-            let table_id = match orphan_tb_id_list.data.id_list.last() {
-                Some(table_id) => *table_id,
-                None => {
-                    return Err(KVAppError::AppError(AppError::UndropTableHasNoHistory(
-                        UndropTableHasNoHistory::new(&tenant_dbname_tbname.table_name),
-                    )));
-                }
-            };
-            tb_id_list.data.id_list.push(table_id);
+            let mut txn = TxnRequest::default();
+            construct_commit_table_meta_txn_operations(self, &req, None, &mut txn).await?;
 
-            // get tb_meta of the last table id
-            let tbid = TableId { table_id };
-            let (tb_meta_seq, tb_meta) = self.get_pb_seq_and_value(&tbid).await?;
+            let (succ, _responses) = send_txn(self, txn).await?;
 
             debug!(
-                ident :% =(&tbid),
-                name :% =(tenant_dbname_tbname);
+                name :? =(&req.name_ident),
+                id :? =(&table_id),
+                succ = succ;
                 "commit_table_meta"
             );
 
-            {
-                // reset drop on time
-                let mut tb_meta = tb_meta.unwrap();
-                // undrop a table with no drop_on time
-                if tb_meta.drop_on.is_none() {
-                    return Err(KVAppError::AppError(AppError::UndropTableWithNoDropTime(
-                        UndropTableWithNoDropTime::new(&tenant_dbname_tbname.table_name),
-                    )));
-                }
-                tb_meta.drop_on = None;
-
-                let txn_req = TxnRequest::new(
-                    vec![
-                        // db has not to change, i.e., no new table is created.
-                        // Renaming db is OK and does not affect the seq of db_meta.
-                        txn_cond_seq(&DatabaseId { db_id }, Eq, db_meta_seq),
-                        // still this table id
-                        txn_cond_seq(&dbid_tbname, Eq, dbid_tbname_seq),
-                        // table is not changed
-                        txn_cond_seq(&tbid, Eq, tb_meta_seq),
-                        txn_cond_seq(&orphan_dbid_tbname_idlist, Eq, orphan_tb_id_list.seq),
-                        txn_cond_seq(&dbid_tbname_idlist, Eq, tb_id_list.seq),
-                    ],
-                    vec![
-                        // Changing a table in a db has to update the seq of db_meta,
-                        // to block the batch-delete-tables when deleting a db.
-                        txn_op_put(&DatabaseId { db_id }, serialize_struct(&db_meta)?), /* (db_id) -> db_meta */
-                        txn_op_put(&dbid_tbname, serialize_u64(table_id)?), /* (tenant, db_id, tb_name) -> tb_id */
-                        // txn_op_put(&dbid_tbname_idlist, serialize_struct(&tb_id_list)?)?, // _fd_table_id_list/db_id/table_name -> tb_id_list
-                        txn_op_put(&tbid, serialize_struct(&tb_meta)?), /* (tenant, db_id, tb_id) -> tb_meta */
-                        txn_op_del(&orphan_dbid_tbname_idlist),         // del orphan table idlist
-                        txn_op_put(&dbid_tbname_idlist, serialize_struct(&tb_id_list.data)?), /* _fd_table_id_list/db_id/table_name -> tb_id_list */
-                    ],
-                );
-
-                let (succ, _responses) = send_txn(self, txn_req).await?;
-
-                debug!(
-                    name :? =(tenant_dbname_tbname),
-                    id :? =(&tbid),
-                    succ = succ;
-                    "commit_table_meta"
-                );
-
-                if succ {
-                    return Ok(CommitTableMetaReply {});
-                }
+            if succ {
+                return Ok(CommitTableMetaReply {});
             }
         }
     }
@@ -2211,212 +2081,14 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
         &self,
         req: UpdateMultiTableMetaReq,
     ) -> Result<UpdateMultiTableMetaResult, KVAppError> {
-        let UpdateMultiTableMetaReq {
-            mut update_table_metas,
-            copied_files,
-            update_stream_metas,
-            deduplicated_labels,
-            update_temp_tables: _,
-        } = req;
-
-        let mut tbl_seqs = HashMap::new();
-        let mut txn = TxnRequest::default();
-        let mut mismatched_tbs = vec![];
-        let tid_vec = update_table_metas
-            .iter()
-            .map(|req| {
-                TableId {
-                    table_id: req.0.table_id,
-                }
-                .to_string_key()
-            })
-            .collect::<Vec<_>>();
-        let mut tb_meta_vec: Vec<(u64, Option<TableMeta>)> = mget_pb_values(self, &tid_vec).await?;
-        for (req, (tb_meta_seq, table_meta)) in
-            update_table_metas.iter().zip(tb_meta_vec.iter_mut())
-        {
-            let req_seq = req.0.seq;
-
-            if *tb_meta_seq == 0 || table_meta.is_none() {
-                return Err(KVAppError::AppError(AppError::UnknownTableId(
-                    UnknownTableId::new(req.0.table_id, "update_multi_table_meta"),
-                )));
-            }
-            if req_seq.match_seq(tb_meta_seq).is_err() {
-                mismatched_tbs.push((
-                    req.0.table_id,
-                    *tb_meta_seq,
-                    std::mem::take(table_meta).unwrap(),
-                ));
-            }
-        }
-
-        if !mismatched_tbs.is_empty() {
-            return Ok(Err(mismatched_tbs));
-        }
-
-        let mut new_table_meta_map: BTreeMap<u64, TableMeta> = BTreeMap::new();
-        for (req, (tb_meta_seq, table_meta)) in
-            update_table_metas.iter_mut().zip(tb_meta_vec.iter())
-        {
-            let tbid = TableId {
-                table_id: req.0.table_id,
-            };
-            // `update_table_meta` MUST NOT modify `shared_by` field
-            let table_meta = table_meta.as_ref().unwrap();
-
-            if let Some(virtual_schema) = &mut req.0.new_table_meta.virtual_schema {
-                if virtual_schema.fields.len() > VIRTUAL_COLUMNS_LIMIT {
-                    return Err(KVAppError::AppError(AppError::VirtualColumnTooMany(
-                        VirtualColumnTooMany::new(req.0.table_id, VIRTUAL_COLUMNS_LIMIT),
-                    )));
-                }
-                for virtual_field in virtual_schema.fields.iter_mut() {
-                    if !matches!(
-                        virtual_field.column_id,
-                        VIRTUAL_COLUMN_ID_START..=VIRTUAL_COLUMNS_ID_UPPER
-                    ) {
-                        return Err(KVAppError::AppError(AppError::VirtualColumnIdOutBound(
-                            VirtualColumnIdOutBound::new(
-                                virtual_field.column_id,
-                                VIRTUAL_COLUMN_ID_START,
-                                VIRTUAL_COLUMNS_ID_UPPER,
-                            ),
-                        )));
-                    }
-                    virtual_field.data_types.dedup();
-                }
-            }
-            let mut new_table_meta = req.0.new_table_meta.clone();
-            new_table_meta.shared_by = table_meta.shared_by.clone();
-
-            tbl_seqs.insert(req.0.table_id, *tb_meta_seq);
-            txn.condition.push(txn_cond_seq(&tbid, Eq, *tb_meta_seq));
-            txn.if_then
-                .push(txn_op_put(&tbid, serialize_struct(&new_table_meta)?));
-            txn.else_then.push(TxnOp {
-                request: Some(Request::Get(TxnGetRequest {
-                    key: tbid.to_string_key(),
-                })),
-            });
-
-            new_table_meta_map.insert(req.0.table_id, new_table_meta);
-        }
-
-        // `remove_table_copied_files` and `upsert_table_copied_file_info`
-        // all modify `TableCopiedFileInfo`,
-        // so there used to has `TableCopiedFileLockKey` in these two functions
-        // to protect TableCopiedFileInfo modification.
-        // In issue: https://github.com/datafuselabs/databend/issues/8897,
-        // there is chance that if copy files concurrently, `upsert_table_copied_file_info`
-        // may return `TxnRetryMaxTimes`.
-        // So now, in case that `TableCopiedFileInfo` has expire time, remove `TableCopiedFileLockKey`
-        // in each function. In this case there is chance that some `TableCopiedFileInfo` may not be
-        // removed in `remove_table_copied_files`, but these data can be purged in case of expire time.
-
-        for (table_id, req) in copied_files {
-            let tbid = TableId { table_id };
-
-            let table_meta_seq = tbl_seqs[&tbid.table_id];
-            txn.condition.push(txn_cond_eq_seq(&tbid, table_meta_seq));
-
-            for (file_name, file_info) in req.file_info {
-                let key = TableCopiedFileNameIdent {
-                    table_id: tbid.table_id,
-                    file: file_name,
-                };
-
-                if req.insert_if_not_exists {
-                    txn.condition.push(txn_cond_eq_seq(&key, 0));
-                }
-                txn.if_then.push(txn_op_put_pb(&key, &file_info, req.ttl)?)
-            }
-        }
-
-        let sid_vec = update_stream_metas
-            .iter()
-            .map(|req| {
-                TableId {
-                    table_id: req.stream_id,
-                }
-                .to_string_key()
-            })
-            .collect::<Vec<_>>();
-        let stream_meta_vec: Vec<(u64, Option<TableMeta>)> = mget_pb_values(self, &sid_vec).await?;
-        for (req, (stream_meta_seq, stream_meta)) in
-            update_stream_metas.iter().zip(stream_meta_vec.into_iter())
-        {
-            let stream_id = TableId {
-                table_id: req.stream_id,
-            };
-
-            if stream_meta_seq == 0 || stream_meta.is_none() {
-                return Err(KVAppError::AppError(AppError::UnknownStreamId(
-                    UnknownStreamId::new(req.stream_id, "update_multi_table_meta"),
-                )));
-            }
-
-            if req.seq.match_seq(&stream_meta_seq).is_err() {
-                return Err(KVAppError::AppError(AppError::from(
-                    StreamVersionMismatched::new(
-                        req.stream_id,
-                        req.seq,
-                        stream_meta_seq,
-                        "update_multi_table_meta",
-                    ),
-                )));
-            }
-
-            let mut new_stream_meta = stream_meta.unwrap();
-            new_stream_meta.options = req.options.clone();
-            new_stream_meta.updated_on = Utc::now();
-
-            txn.condition
-                .push(txn_cond_seq(&stream_id, Eq, stream_meta_seq));
-            txn.if_then
-                .push(txn_op_put(&stream_id, serialize_struct(&new_stream_meta)?));
-        }
-
-        for deduplicated_label in deduplicated_labels {
-            txn.if_then
-                .push(build_upsert_table_deduplicated_label(deduplicated_label));
-        }
-        let (succ, responses) = send_txn(self, txn).await?;
-        if succ {
-            return Ok(Ok(UpdateTableMetaReply {}));
-        }
-        let mut mismatched_tbs = vec![];
-        for (resp, req) in responses.iter().zip(update_table_metas.iter()) {
-            let Some(Response::Get(get_resp)) = &resp.response else {
-                unreachable!(
-                    "internal error: expect some TxnGetResponseGet, but got {:?}",
-                    resp.response
-                )
-            };
-            // deserialize table version info
-            let (tb_meta_seq, table_meta): (_, TableMeta) = if let Some(seq_v) = &get_resp.value {
-                (seq_v.seq, deserialize_struct(&seq_v.data)?)
-            } else {
-                return Err(KVAppError::AppError(AppError::UnknownTableId(
-                    UnknownTableId::new(req.0.table_id, "update_multi_table_meta"),
-                )));
-            };
+        let mut trials = txn_backoff(None, func_name!());
+        loop {
+            trials.next().unwrap()?.await;
 
-            // check table version
-            if req.0.seq.match_seq(&tb_meta_seq).is_err() {
-                mismatched_tbs.push((req.0.table_id, tb_meta_seq, table_meta));
+            if let Some(res) = try_update_multi_table_meta(self, req.clone()).await? {
+                return Ok(res);
             }
         }
-
-        if mismatched_tbs.is_empty() {
-            // if all table version does match, but tx failed, we don't know why, just return error
-            Err(KVAppError::AppError(AppError::from(
-                MultiStmtTxnCommitFailed::new("update_multi_table_meta"),
-            )))
-        } else {
-            // up layer will retry
-            Ok(Err(mismatched_tbs))
-        }
     }
 
     #[logcall::logcall]
@@ -3459,6 +3131,383 @@ async fn construct_drop_table_txn_operations(
     Ok((tb_id_seq, table_id))
 }
 
+/// Build the txn conditions and operations to make a table created as orphan visible.
+///
+/// If the table is updated in the same txn, `new_table_meta` is the meta it is updated to.
+async fn construct_commit_table_meta_txn_operations(
+    kv_api: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+    req: &CommitTableMetaReq,
+    new_table_meta: Option<&TableMeta>,
+    txn: &mut TxnRequest,
+) -> Result<(), KVAppError> {
+    let tenant_dbname_tbname = &req.name_ident;
+
+    // Get db by name to ensure presence
+
+    let (db_meta_seq, db_meta) =
+        get_db_by_id_or_err(kv_api, req.db_id, "commit_table_meta").await?;
+    let db_id = req.db_id;
+
+    // Get table by tenant,db_id, table_name to assert presence.
+
+    let dbid_tbname = DBIdTableName {
+        db_id,
+        table_name: tenant_dbname_tbname.table_name.clone(),
+    };
+
+    let (dbid_tbname_seq, _table_id) = get_u64_value(kv_api, &dbid_tbname).await?;
+
+    // get table id list from _fd_table_id_list/db_id/table_name
+
+    let orphan_dbid_tbname_idlist = TableIdHistoryIdent {
+        database_id: db_id,
+        table_name: req.orphan_table_name.clone().unwrap(),
+    };
+    let dbid_tbname_idlist = TableIdHistoryIdent {
+        database_id: db_id,
+        table_name: tenant_dbname_tbname.table_name.clone(),
+    };
+
+    let keys = vec![
+        orphan_dbid_tbname_idlist.to_string_key(),
+        dbid_tbname_idlist.to_string_key(),
+    ];
+
+    let mut data = {
+        let values = kv_api.mget_kv(&keys).await?;
+        keys.iter()
+            .zip(values.into_iter())
+            .map(|(k, v)| TxnGetResponse::new(k, v.map(pb::SeqV::from)))
+            .collect::<Vec<_>>()
+    };
+
+    let orphan_tb_id_list = {
+        let d = data.remove(0);
+        let (k, v) = deserialize_struct_get_response::<TableIdHistoryIdent>(d)?;
+        assert_eq!(orphan_dbid_tbname_idlist, k);
+
+        v.unwrap_or_default()
+    };
+    if orphan_tb_id_list.data.id_list.len() != 1 {
+        error!("table {:?} orphan list is empty", tenant_dbname_tbname);
+        let exist_err = CommitTableMetaError::new(
+            tenant_dbname_tbname.table_name.clone(),
+            "orphan list length != 1".to_string(),
+        );
+        return Err(KVAppError::AppError(AppError::from(exist_err)));
+    }
+
+    let mut tb_id_list = {
+        let d = data.remove(0);
+        let (k, v) = deserialize_struct_get_response::<TableIdHistoryIdent>(d)?;
+        assert_eq!(dbid_tbname_idlist, k);
+
+        v.unwrap_or_default()
+    };
+
+    if tb_id_list.data.id_list.last() != req.prev_table_id.as_ref() {
+        error!(
+            "table {:?} table id list has been changed",
+            tenant_dbname_tbname
+        );
+        let exist_err = CommitTableMetaError::new(
+            tenant_dbname_tbname.table_name.clone(),
+            "prev_table_id has been changed".to_string(),
+        );
+        return Err(KVAppError::AppError(AppError::from(exist_err)));
+    }
+
+    let table_id = match orphan_tb_id_list.data.id_list.last() {
+        Some(table_id) => *table_id,
+        None => {
+            return Err(KVAppError::AppError(AppError::UndropTableHasNoHistory(
+                UndropTableHasNoHistory::new(&tenant_dbname_tbname.table_name),
+            )));
+        }
+    };
+    tb_id_list.data.id_list.push(table_id);
+
+    // get tb_meta of the last table id
+    let tbid = TableId { table_id };
+    let (tb_meta_seq, tb_meta) = kv_api.get_pb_seq_and_value(&tbid).await?;
+
+    debug!(
+        ident :% =(&tbid),
+        name :% =(tenant_dbname_tbname);
+        "commit_table_meta"
+    );
+
+    // undrop a table with no drop_on time
+    if tb_meta.as_ref().and_then(|meta| meta.drop_on).is_none() {
+        return Err(KVAppError::AppError(AppError::UndropTableWithNoDropTime(
+            UndropTableWithNoDropTime::new(&tenant_dbname_tbname.table_name),
+        )));
+    }
+    // reset drop on time, of the meta the table is updated to in the same txn if any
+    let mut tb_meta = match new_table_meta {
+        Some(new_table_meta) => new_table_meta.clone(),
+        None => tb_meta.unwrap(),
+    };
+    tb_meta.drop_on = None;
+
+    txn.condition.extend(vec![
+        // db has not to change, i.e., no new table is created.
+        // Renaming db is OK and does not affect the seq of db_meta.
+        txn_cond_seq(&DatabaseId { db_id }, Eq, db_meta_seq),
+        // still this table id
+        txn_cond_seq(&dbid_tbname, Eq, dbid_tbname_seq),
+        // table is not changed
+        txn_cond_seq(&tbid, Eq, tb_meta_seq),
+        txn_cond_seq(&orphan_dbid_tbname_idlist, Eq, orphan_tb_id_list.seq),
+        txn_cond_seq(&dbid_tbname_idlist, Eq, tb_id_list.seq),
+    ]);
+    txn.if_then.extend(vec![
+        // Changing a table in a db has to update the seq of db_meta,
+        // to block the batch-delete-tables when deleting a db.
+        txn_op_put(&DatabaseId { db_id }, serialize_struct(&db_meta)?), /* (db_id) -> db_meta */
+        txn_op_put(&dbid_tbname, serialize_u64(table_id)?), /* (tenant, db_id, tb_name) -> tb_id */
+        txn_op_put(&tbid, serialize_struct(&tb_meta)?),     /* (tenant, db_id, tb_id) -> tb_meta */
+        txn_op_del(&orphan_dbid_tbname_idlist),             // del orphan table idlist
+        txn_op_put(&dbid_tbname_idlist, serialize_struct(&tb_id_list.data)?), /* _fd_table_id_list/db_id/table_name -> tb_id_list */
+    ]);
+    Ok(())
+}
+
+/// Update the tables, streams and copied files of `req` in one txn.
+///
+/// Returns `None` if the txn fails for the tables dropped or created, which needs to be retried.
+async fn try_update_multi_table_meta(
+    kv_api: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+    req: UpdateMultiTableMetaReq,
+) -> Result<Option<UpdateMultiTableMetaResult>, KVAppError> {
+    let UpdateMultiTableMetaReq {
+        mut update_table_metas,
+        copied_files,
+        update_stream_metas,
+        deduplicated_labels,
+        update_temp_tables: _,
+        drop_tables,
+        commit_tables,
+    } = req;
+
+    let mut tbl_seqs = HashMap::new();
+    let mut txn = TxnRequest::default();
+    let mut mismatched_tbs = vec![];
+    let tid_vec = update_table_metas
+        .iter()
+        .map(|req| {
+            TableId {
+                table_id: req.0.table_id,
+            }
+            .to_string_key()
+        })
+        .collect::<Vec<_>>();
+    let mut tb_meta_vec: Vec<(u64, Option<TableMeta>)> = mget_pb_values(kv_api, &tid_vec).await?;
+    for (req, (tb_meta_seq, table_meta)) in update_table_metas.iter().zip(tb_meta_vec.iter_mut()) {
+        let req_seq = req.0.seq;
+
+        if *tb_meta_seq == 0 || table_meta.is_none() {
+            return Err(KVAppError::AppError(AppError::UnknownTableId(
+                UnknownTableId::new(req.0.table_id, "update_multi_table_meta"),
+            )));
+        }
+        if req_seq.match_seq(tb_meta_seq).is_err() {
+            mismatched_tbs.push((
+                req.0.table_id,
+                *tb_meta_seq,
+                std::mem::take(table_meta).unwrap(),
+            ));
+        }
+    }
+
+    if !mismatched_tbs.is_empty() {
+        return Ok(Some(Err(mismatched_tbs)));
+    }
+
+    let mut new_table_meta_map: BTreeMap<u64, TableMeta> = BTreeMap::new();
+    for (req, (tb_meta_seq, table_meta)) in update_table_metas.iter_mut().zip(tb_meta_vec.iter()) {
+        let tbid = TableId {
+            table_id: req.0.table_id,
+        };
+        // `update_table_meta` MUST NOT modify `shared_by` field
+        let table_meta = table_meta.as_ref().unwrap();
+
+        if let Some(virtual_schema) = &mut req.0.new_table_meta.virtual_schema {
+            if virtual_schema.fields.len() > VIRTUAL_COLUMNS_LIMIT {
+                return Err(KVAppError::AppError(AppError::VirtualColumnTooMany(
+                    VirtualColumnTooMany::new(req.0.table_id, VIRTUAL_COLUMNS_LIMIT),
+                )));
+            }
+            for virtual_field in virtual_schema.fields.iter_mut() {
+                if !matches!(
+                    virtual_field.column_id,
+                    VIRTUAL_COLUMN_ID_START..=VIRTUAL_COLUMNS_ID_UPPER
+                ) {
+                    return Err(KVAppError::AppError(AppError::VirtualColumnIdOutBound(
+                        VirtualColumnIdOutBound::new(
+                            virtual_field.column_id,
+                            VIRTUAL_COLUMN_ID_START,
+                            VIRTUAL_COLUMNS_ID_UPPER,
+                        ),
+                    )));
+                }
+                virtual_field.data_types.dedup();
+            }
+        }
+        let mut new_table_meta = req.0.new_table_meta.clone();
+        new_table_meta.shared_by = table_meta.shared_by.clone();
+
+        tbl_seqs.insert(req.0.table_id, *tb_meta_seq);
+        txn.condition.push(txn_cond_seq(&tbid, Eq, *tb_meta_seq));
+        txn.if_then
+            .push(txn_op_put(&tbid, serialize_struct(&new_table_meta)?));
+        txn.else_then.push(TxnOp {
+            request: Some(Request::Get(TxnGetRequest {
+                key: tbid.to_string_key(),
+            })),
+        });
+
+        new_table_meta_map.insert(req.0.table_id, new_table_meta);
+    }
+
+    // `remove_table_copied_files` and `upsert_table_copied_file_info`
+    // all modify `TableCopiedFileInfo`,
+    // so there used to has `TableCopiedFileLockKey` in these two functions
+    // to protect TableCopiedFileInfo modification.
+    // In issue: https://github.com/datafuselabs/databend/issues/8897,
+    // there is chance that if copy files concurrently, `upsert_table_copied_file_info`
+    // may return `TxnRetryMaxTimes`.
+    // So now, in case that `TableCopiedFileInfo` has expire time, remove `TableCopiedFileLockKey`
+    // in each function. In this case there is chance that some `TableCopiedFileInfo` may not be
+    // removed in `remove_table_copied_files`, but these data can be purged in case of expire time.
+
+    for (table_id, req) in copied_files {
+        let tbid = TableId { table_id };
+
+        let table_meta_seq = tbl_seqs[&tbid.table_id];
+        txn.condition.push(txn_cond_eq_seq(&tbid, table_meta_seq));
+
+        for (file_name, file_info) in req.file_info {
+            let key = TableCopiedFileNameIdent {
+                table_id: tbid.table_id,
+                file: file_name,
+            };
+
+            if req.insert_if_not_exists {
+                txn.condition.push(txn_cond_eq_seq(&key, 0));
+            }
+            txn.if_then.push(txn_op_put_pb(&key, &file_info, req.ttl)?)
+        }
+    }
+
+    let sid_vec = update_stream_metas
+        .iter()
+        .map(|req| {
+            TableId {
+                table_id: req.stream_id,
+            }
+            .to_string_key()
+        })
+        .collect::<Vec<_>>();
+    let stream_meta_vec: Vec<(u64, Option<TableMeta>)> = mget_pb_values(kv_api, &sid_vec).await?;
+    for (req, (stream_meta_seq, stream_meta)) in
+        update_stream_metas.iter().zip(stream_meta_vec.into_iter())
+    {
+        let stream_id = TableId {
+            table_id: req.stream_id,
+        };
+
+        if stream_meta_seq == 0 || stream_meta.is_none() {
+            return Err(KVAppError::AppError(AppError::UnknownStreamId(
+                UnknownStreamId::new(req.stream_id, "update_multi_table_meta"),
+            )));
+        }
+
+        if req.seq.match_seq(&stream_meta_seq).is_err() {
+            return Err(KVAppError::AppError(AppError::from(
+                StreamVersionMismatched::new(
+                    req.stream_id,
+                    req.seq,
+                    stream_meta_seq,
+                    "update_multi_table_meta",
+                ),
+            )));
+        }
+
+        let mut new_stream_meta = stream_meta.unwrap();
+        new_stream_meta.options = req.options.clone();
+        new_stream_meta.updated_on = Utc::now();
+
+        txn.condition
+            .push(txn_cond_seq(&stream_id, Eq, stream_meta_seq));
+        txn.if_then
+            .push(txn_op_put(&stream_id, serialize_struct(&new_stream_meta)?));
+    }
+
+    // The tables dropped are dropped before the tables created, which may replace them.
+    for req in &drop_tables {
+        construct_drop_table_txn_operations(
+            kv_api,
+            req.table_name.clone(),
+            &req.tenant,
+            req.tb_id,
+            req.db_id,
+            req.if_exists,
+            true,
+            &mut txn,
+        )
+        .await?;
+    }
+    for req in &commit_tables {
+        let new_table_meta = new_table_meta_map.get(&req.table_id);
+        construct_commit_table_meta_txn_operations(kv_api, req, new_table_meta, &mut txn).await?;
+    }
+
+    for deduplicated_label in deduplicated_labels {
+        txn.if_then
+            .push(build_upsert_table_deduplicated_label(deduplicated_label));
+    }
+    let (succ, responses) = send_txn(kv_api, txn).await?;
+    if succ {
+        return Ok(Some(Ok(UpdateTableMetaReply {})));
+    }
+    let mut mismatched_tbs = vec![];
+    for (resp, req) in responses.iter().zip(update_table_metas.iter()) {
+        let Some(Response::Get(get_resp)) = &resp.response else {
+            unreachable!(
+                "internal error: expect some TxnGetResponseGet, but got {:?}",
+                resp.response
+            )
+        };
+        // deserialize table version info
+        let (tb_meta_seq, table_meta): (_, TableMeta) = if let Some(seq_v) = &get_resp.value {
+            (seq_v.seq, deserialize_struct(&seq_v.data)?)
+        } else {
+            return Err(KVAppError::AppError(AppError::UnknownTableId(
+                UnknownTableId::new(req.0.table_id, "update_multi_table_meta"),
+            )));
+        };
+
+        // check table version
+        if req.0.seq.match_seq(&tb_meta_seq).is_err() {
+            mismatched_tbs.push((req.0.table_id, tb_meta_seq, table_meta));
+        }
+    }
+
+    if !mismatched_tbs.is_empty() {
+        // up layer will retry
+        Ok(Some(Err(mismatched_tbs)))
+    } else if !drop_tables.is_empty() || !commit_tables.is_empty() {
+        // the tables dropped or created may be changed concurrently, retry with their latest versions
+        Ok(None)
+    } else {
+        // if all table version does match, but tx failed, we don't know why, just return error
+        Err(KVAppError::AppError(AppError::from(
+            MultiStmtTxnCommitFailed::new("update_multi_table_meta"),
+        )))
+    }
+}
+
 async fn drop_database_meta(
     kv_api: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
     tenant_dbname: &DatabaseNameIdent,
@@ -5301,6 +5301,91 @@ impl SchemaApiTestSuite {
             mt.commit_table_meta(commit_table_req).await?;
         }
 
+        // tables dropped and created with update_multi_table_meta are committed all or nothing
+        {
+            use databend_common_meta_app::app_error::AppError;
+
+            let name_ident = |table_name: &str| TableNameIdent {
+                tenant: tenant.clone(),
+                db_name: db_name.to_string(),
+                table_name: table_name.to_string(),
+            };
+
+            let dropped_resp = mt
+                .create_table(CreateTableReq {
+                    create_option: CreateOption::Create,
+                    name_ident: name_ident("tb_dropped"),
+                    table_meta: table_meta(created_on),
+                    as_dropped: false,
+                })
+                .await?;
+            let drop_table_req = DropTableByIdReq {
+                if_exists: false,
+                tenant: tenant.clone(),
+                tb_id: dropped_resp.table_id,
+                table_name: "tb_dropped".to_string(),
+                db_id: *db_id,
+                db_name: db_name.to_string(),
+                engine: "JSON".to_string(),
+                session_id: "".to_string(),
+            };
+
+            let created_resp = mt
+                .create_table(CreateTableReq {
+                    create_option: CreateOption::Create,
+                    name_ident: name_ident("tb_created"),
+                    table_meta: drop_table_meta(created_on),
+                    as_dropped: true,
+                })
+                .await?;
+            let commit_table_req = CommitTableMetaReq {
+                name_ident: name_ident("tb_created"),
+                db_id: created_resp.db_id,
+                table_id: created_resp.table_id,
+                prev_table_id: created_resp.prev_table_id,
+                orphan_table_name: created_resp.orphan_table_name.clone(),
+            };
+
+            // the table can not be created, the table is not dropped either
+            let resp = mt
+                .update_multi_table_meta(UpdateMultiTableMetaReq {
+                    drop_tables: vec![drop_table_req.clone()],
+                    commit_tables: vec![CommitTableMetaReq {
+                        prev_table_id: Some(1111),
+                        ..commit_table_req.clone()
+                    }],
+                    ..Default::default()
+                })
+                .await;
+            assert!(matches!(
+                resp.unwrap_err(),
+                KVAppError::AppError(AppError::CommitTableMetaError(_))
+            ));
+            mt.get_table(GetTableReq::new(&tenant, db_name, "tb_dropped"))
+                .await?;
+            assert!(mt
+                .get_table(GetTableReq::new(&tenant, db_name, "tb_created"))
+                .await
+                .is_err());
+
+            mt.update_multi_table_meta(UpdateMultiTableMetaReq {
+                drop_tables: vec![drop_table_req],
+                commit_tables: vec![commit_table_req],
+                ..Default::default()
+            })
+            .await?
+            .unwrap();
+            assert!(mt
+                .get_table(GetTableReq::new(&tenant, db_name, "tb_dropped"))
+                .await
+                .is_err());
+            let got = mt
+                .get_table(GetTableReq::new(&tenant, db_name, "tb_created"))
+                .await?;
+            assert_eq!(got.ident.table_id, created_resp.table_id);
+            assert!(got.meta.drop_on.is_none());
+        }
+
         // verify the orphan table id list will be vacuum
         {
             // use a new tenant and db do test
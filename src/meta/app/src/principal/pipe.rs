@@ -0,0 +1,34 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::DateTime;
+use chrono::Utc;
+
+/// A pipe keeps loading new files from a stage into a table,
+/// by running the `COPY INTO <table>` statement it is defined with.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub struct PipeInfo {
+    pub name: String,
+    /// The `COPY INTO <table>` statement in SQL text, re-parsed on every ingestion.
+    pub copy_stmt: String,
+    /// If true, the pipe polls its stage for new files periodically,
+    /// otherwise files are only loaded by `ALTER PIPE ... REFRESH`.
+    pub auto_ingest: bool,
+    pub execution_paused: bool,
+    pub comment: String,
+    /// The role creating the pipe, the files are ingested with the privileges of the role.
+    pub owner: String,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,202 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::DateTime;
+use chrono::Utc;
+use cron::Schedule;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum TaskSchedule {
+    /// Run every `secs` seconds plus `millis` milliseconds.
+    Interval { secs: u64, millis: u64 },
+    /// Run at the times matched by the cron expression, in `time_zone` or UTC.
+    Cron {
+        expr: String,
+        time_zone: Option<String>,
+    },
+}
+
+impl TaskSchedule {
+    /// Returns the first scheduled time strictly after `last`,
+    /// or None if the cron expression or the time zone can not be parsed.
+    pub fn next_time_after(&self, last: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            TaskSchedule::Interval { secs, millis } => {
+                let interval = chrono::Duration::seconds(*secs as i64)
+                    + chrono::Duration::milliseconds(*millis as i64);
+                Some(last + interval)
+            }
+            TaskSchedule::Cron { expr, time_zone } => {
+                let schedule = Schedule::from_str(expr).ok()?;
+                let tz = match time_zone {
+                    Some(tz) => tz.parse::<chrono_tz::Tz>().ok()?,
+                    None => chrono_tz::UTC,
+                };
+                let upcoming = schedule.after(&last.with_timezone(&tz)).next()?;
+                Some(upcoming.with_timezone(&Utc))
+            }
+        }
+    }
+}
+
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Default,
+    num_derive::FromPrimitive,
+)]
+pub enum TaskStatus {
+    #[default]
+    Suspended = 0,
+    Started = 1,
+}
+
+/// A task defined by `CREATE TASK`, run by the built-in task scheduler
+/// when the cloud control plane is not configured.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct TaskInfo {
+    pub task_id: u64,
+    pub task_name: String,
+    /// The SQL text of the task, as displayed by `DESC TASK`.
+    pub query_text: String,
+    /// The statements of a `BEGIN ... END` script block, run one by one.
+    /// Empty if the task is a single statement, which is `query_text` itself.
+    pub script_sqls: Vec<String>,
+    /// A boolean expression evaluated before each run, the run is skipped if it is not true.
+    pub when_condition: Option<String>,
+    /// Names of the predecessor tasks, a task with predecessors has no schedule of its own.
+    pub after: Vec<String>,
+    pub comment: Option<String>,
+    pub owner: String,
+    pub schedule_options: Option<TaskSchedule>,
+    pub warehouse: Option<String>,
+    pub suspend_task_after_num_failures: Option<u64>,
+    pub error_integration: Option<String>,
+    pub session_params: BTreeMap<String, String>,
+    pub status: TaskStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_suspended_at: Option<DateTime<Utc>>,
+}
+
+impl TaskInfo {
+    /// The statements to run, in order.
+    pub fn statements(&self) -> Vec<String> {
+        if self.script_sqls.is_empty() {
+            vec![self.query_text.clone()]
+        } else {
+            self.script_sqls.clone()
+        }
+    }
+}
+
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Default,
+    num_derive::FromPrimitive,
+)]
+pub enum TaskRunState {
+    #[default]
+    Scheduled = 0,
+    Executing = 1,
+    Succeeded = 2,
+    Failed = 3,
+    Cancelled = 4,
+}
+
+/// One run of a task, kept in the meta-service for `task_history`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct TaskRun {
+    /// Unique id of the run, also the key of the run in the meta-service.
+    pub run_id: String,
+    pub task_id: u64,
+    pub task_name: String,
+    pub query_text: String,
+    pub condition_text: String,
+    pub owner: String,
+    pub comment: Option<String>,
+    pub schedule_options: Option<TaskSchedule>,
+    pub warehouse: Option<String>,
+    pub attempt_number: u32,
+    pub state: TaskRunState,
+    pub error_code: i64,
+    pub error_message: Option<String>,
+    /// Id of the last query run by the task.
+    pub query_id: String,
+    /// Id of the root task of the task graph this run belongs to.
+    pub root_task_id: u64,
+    pub session_params: BTreeMap<String, String>,
+    pub scheduled_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    use crate::principal::TaskSchedule;
+
+    #[test]
+    fn test_task_schedule_next_time() {
+        let last = Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap();
+
+        let interval = TaskSchedule::Interval {
+            secs: 60,
+            millis: 500,
+        };
+        assert_eq!(
+            interval.next_time_after(last),
+            Some(last + chrono::Duration::milliseconds(60_500))
+        );
+
+        let cron = TaskSchedule::Cron {
+            expr: "0 30 * * * *".to_string(),
+            time_zone: None,
+        };
+        assert_eq!(
+            cron.next_time_after(last),
+            Some(Utc.with_ymd_and_hms(2025, 3, 1, 10, 30, 0).unwrap())
+        );
+
+        let cron = TaskSchedule::Cron {
+            expr: "0 0 12 * * *".to_string(),
+            time_zone: Some("Asia/Shanghai".to_string()),
+        };
+        assert_eq!(
+            cron.next_time_after(last),
+            Some(Utc.with_ymd_and_hms(2025, 3, 2, 4, 0, 0).unwrap())
+        );
+
+        let invalid = TaskSchedule::Cron {
+            expr: "not a cron".to_string(),
+            time_zone: None,
+        };
+        assert_eq!(invalid.next_time_after(last), None);
+    }
+}
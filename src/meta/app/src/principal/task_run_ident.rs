@@ -0,0 +1,84 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tenant_key::ident::TIdent;
+
+/// Defines the meta-service key for a task run.
+pub type TaskRunIdent = TIdent<Resource>;
+
+pub use kvapi_impl::Resource;
+
+mod kvapi_impl {
+
+    use databend_common_exception::ErrorCode;
+    use databend_common_meta_kvapi::kvapi;
+
+    use crate::principal::TaskRun;
+    use crate::principal::TaskRunIdent;
+    use crate::tenant_key::errors::ExistError;
+    use crate::tenant_key::errors::UnknownError;
+    use crate::tenant_key::resource::TenantResource;
+
+    pub struct Resource;
+
+    impl TenantResource for Resource {
+        const PREFIX: &'static str = "__fd_task_runs";
+        const TYPE: &'static str = "TaskRunIdent";
+        const HAS_TENANT: bool = true;
+        type ValueType = TaskRun;
+    }
+
+    impl kvapi::Value for TaskRun {
+        type KeyType = TaskRunIdent;
+        fn dependency_keys(&self, _key: &Self::KeyType) -> impl IntoIterator<Item = String> {
+            []
+        }
+    }
+
+    impl kvapi::ValueWithName for TaskRun {
+        fn name(&self) -> &str {
+            &self.run_id
+        }
+    }
+
+    impl From<ExistError<Resource>> for ErrorCode {
+        fn from(err: ExistError<Resource>) -> Self {
+            ErrorCode::TaskAlreadyExists(err.to_string())
+        }
+    }
+
+    impl From<UnknownError<Resource>> for ErrorCode {
+        fn from(err: UnknownError<Resource>) -> Self {
+            ErrorCode::UnknownTask(err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_kvapi::kvapi::Key;
+
+    use crate::principal::task_run_ident::TaskRunIdent;
+    use crate::tenant::Tenant;
+
+    #[test]
+    fn test_task_run_ident() {
+        let tenant = Tenant::new_literal("test");
+        let ident = TaskRunIdent::new(tenant, "run1");
+
+        let key = ident.to_string_key();
+        assert_eq!(key, "__fd_task_runs/test/run1");
+        assert_eq!(ident, TaskRunIdent::from_str_key(&key).unwrap());
+    }
+}
@@ -16,6 +16,9 @@ use std::str::FromStr;
 
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use hmac::Hmac;
+use hmac::Mac;
+use rand::RngCore;
 use sha2::Digest;
 use sha2::Sha256;
 
@@ -101,10 +104,78 @@ pub enum AuthInfo {
         hash_value: Vec<u8>,
         hash_method: PasswordHashMethod,
         need_change: bool,
+        /// The SCRAM-SHA-256 verifier of the password, used by the postgres protocol.
+        /// `None` if the password was set before the verifier was introduced.
+        #[serde(default)]
+        scram_verifier: Option<ScramVerifier>,
     },
     JWT,
 }
 
+/// SCRAM-SHA-256 verifier of a password, see RFC 5802 and RFC 7677.
+///
+/// Only the salted keys are stored, the server can check the proof of the client
+/// without knowing the password, and the client can check the signature of the server.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ScramVerifier {
+    pub iterations: u32,
+    pub salt: Vec<u8>,
+    pub stored_key: Vec<u8>,
+    pub server_key: Vec<u8>,
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut m = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    m.update(message);
+    m.finalize().into_bytes().to_vec()
+}
+
+impl ScramVerifier {
+    pub const ITERATIONS: u32 = 4096;
+    pub const SALT_LEN: usize = 16;
+
+    /// Create the verifier of the password with a random salt.
+    pub fn create(password: &str) -> ScramVerifier {
+        let mut salt = vec![0; Self::SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        ScramVerifier::create_with_salt(password, salt, Self::ITERATIONS)
+    }
+
+    pub fn create_with_salt(password: &str, salt: Vec<u8>, iterations: u32) -> ScramVerifier {
+        // SaltedPassword := Hi(Normalize(password), salt, i)
+        // The password is not normalized with SASLprep, which keeps ASCII passwords unchanged.
+        let mut salted_password = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, iterations, &mut salted_password);
+        let client_key = hmac_sha256(&salted_password, b"Client Key");
+        ScramVerifier {
+            iterations,
+            salt,
+            stored_key: Sha256::digest(client_key).to_vec(),
+            server_key: hmac_sha256(&salted_password, b"Server Key"),
+        }
+    }
+
+    /// Check the `ClientProof` sent by the client against the `AuthMessage` of the exchange.
+    pub fn verify_client_proof(&self, auth_message: &[u8], client_proof: &[u8]) -> bool {
+        // ClientKey := ClientProof XOR HMAC(StoredKey, AuthMessage)
+        let client_signature = hmac_sha256(&self.stored_key, auth_message);
+        if client_proof.len() != client_signature.len() {
+            return false;
+        }
+        let client_key = client_proof
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        Sha256::digest(client_key).as_slice() == self.stored_key.as_slice()
+    }
+
+    /// The `ServerSignature` that proves the server knows the verifier of the password.
+    pub fn server_signature(&self, auth_message: &[u8]) -> Vec<u8> {
+        hmac_sha256(&self.server_key, auth_message)
+    }
+}
+
 fn calc_sha1(v: &[u8]) -> [u8; 20] {
     let mut m = ::sha1::Sha1::new();
     m.update(v);
@@ -131,6 +202,7 @@ impl AuthInfo {
                         hash_value: method.hash(p.as_bytes()),
                         hash_method: method,
                         need_change,
+                        scram_verifier: None,
                     })
                 }
                 None => Err(ErrorCode::AuthenticateFailure("need password".to_string())),
@@ -164,11 +236,49 @@ impl AuthInfo {
             AuthInfo::Password {
                 hash_value,
                 hash_method,
+                scram_verifier,
                 ..
             } => AuthInfo::Password {
                 hash_value: hash_value.clone(),
                 hash_method: *hash_method,
                 need_change,
+                scram_verifier: scram_verifier.clone(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    // attach the SCRAM verifier of the plain text password, if `AuthInfo` is a password.
+    pub fn with_scram_verifier(self, password: &str) -> AuthInfo {
+        match self {
+            AuthInfo::Password {
+                hash_value,
+                hash_method,
+                need_change,
+                ..
+            } => AuthInfo::Password {
+                hash_value,
+                hash_method,
+                need_change,
+                scram_verifier: Some(ScramVerifier::create(password)),
+            },
+            _ => self,
+        }
+    }
+
+    // create `AuthInfo` without the SCRAM verifier, to compare the password only.
+    pub fn without_scram_verifier(&self) -> AuthInfo {
+        match self {
+            AuthInfo::Password {
+                hash_value,
+                hash_method,
+                need_change,
+                ..
+            } => AuthInfo::Password {
+                hash_value: hash_value.clone(),
+                hash_method: *hash_method,
+                need_change: *need_change,
+                scram_verifier: None,
             },
             _ => self.clone(),
         }
@@ -241,6 +351,13 @@ impl AuthInfo {
         }
     }
 
+    pub fn get_scram_verifier(&self) -> Option<&ScramVerifier> {
+        match self {
+            AuthInfo::Password { scram_verifier, .. } => scram_verifier.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn get_password_type(&self) -> Option<PasswordHashMethod> {
         match self {
             AuthInfo::Password {
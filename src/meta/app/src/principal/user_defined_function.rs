@@ -43,6 +43,8 @@ pub struct UDFScript {
     pub arg_types: Vec<DataType>,
     pub return_type: DataType,
     pub runtime_version: String,
+    pub imports: Vec<String>,
+    pub packages: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -55,6 +57,44 @@ pub struct UDAFScript {
     pub state_fields: Vec<DataField>,
     pub return_type: DataType,
     pub runtime_version: String,
+    // stage files importable as modules by the script
+    pub imports: Vec<String>,
+    // packages the script requires from its runtime
+    pub packages: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UDAFServer {
+    pub address: String,
+    pub language: String,
+    // aggregate function input types
+    pub arg_types: Vec<DataType>,
+    // aggregate function state fields, exchanged with the server as a struct column
+    pub state_fields: Vec<DataField>,
+    pub return_type: DataType,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UDTFServer {
+    pub address: String,
+    pub handler: String,
+    pub language: String,
+    pub arg_types: Vec<DataType>,
+    // columns of the returned table, the server returns a list of them for each input row
+    pub return_fields: Vec<DataField>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UDTFScript {
+    pub code: String,
+    pub handler: String,
+    pub language: String,
+    pub arg_types: Vec<DataType>,
+    // columns of the returned table, the handler returns a list of them for each input row
+    pub return_fields: Vec<DataField>,
+    pub runtime_version: String,
+    pub imports: Vec<String>,
+    pub packages: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -63,6 +103,9 @@ pub enum UDFDefinition {
     UDFServer(UDFServer),
     UDFScript(UDFScript),
     UDAFScript(UDAFScript),
+    UDAFServer(UDAFServer),
+    UDTFServer(UDTFServer),
+    UDTFScript(UDTFScript),
 }
 
 impl UDFDefinition {
@@ -72,6 +115,9 @@ impl UDFDefinition {
             Self::UDFServer(_) => "UDFServer",
             Self::UDFScript(_) => "UDFScript",
             Self::UDAFScript(_) => "UDAFScript",
+            Self::UDAFServer(_) => "UDAFServer",
+            Self::UDTFServer(_) => "UDTFServer",
+            Self::UDTFScript(_) => "UDTFScript",
         }
     }
 
@@ -81,6 +127,9 @@ impl UDFDefinition {
             Self::UDFServer(_) => false,
             Self::UDFScript(_) => false,
             Self::UDAFScript(_) => true,
+            Self::UDAFServer(_) => true,
+            Self::UDTFServer(_) => false,
+            Self::UDTFScript(_) => false,
         }
     }
 
@@ -90,6 +139,9 @@ impl UDFDefinition {
             Self::UDFServer(x) => x.language.as_str(),
             Self::UDFScript(x) => x.language.as_str(),
             Self::UDAFScript(x) => x.language.as_str(),
+            Self::UDAFServer(x) => x.language.as_str(),
+            Self::UDTFServer(x) => x.language.as_str(),
+            Self::UDTFScript(x) => x.language.as_str(),
         }
     }
 }
@@ -163,6 +215,8 @@ impl UserDefinedFunction {
                 arg_types,
                 return_type,
                 runtime_version: runtime_version.to_string(),
+                imports: vec![],
+                packages: vec![],
             }),
             created_on: Utc::now(),
         }
@@ -210,6 +264,8 @@ impl Display for UDFDefinition {
                 handler,
                 language,
                 runtime_version,
+                imports,
+                packages,
             }) => {
                 for (i, item) in arg_types.iter().enumerate() {
                     if i > 0 {
@@ -219,8 +275,10 @@ impl Display for UDFDefinition {
                 }
                 write!(
                     f,
-                    ") RETURNS {return_type} LANGUAGE {language} RUNTIME_VERSION = {runtime_version} HANDLER = {handler} AS $${code}$$"
+                    ") RETURNS {return_type} LANGUAGE {language} RUNTIME_VERSION = {runtime_version}"
                 )?;
+                write_script_options(f, imports, packages)?;
+                write!(f, " HANDLER = {handler} AS $${code}$$")?;
             }
             UDFDefinition::UDAFScript(UDAFScript {
                 code,
@@ -229,6 +287,8 @@ impl Display for UDFDefinition {
                 return_type,
                 language,
                 runtime_version,
+                imports,
+                packages,
             }) => {
                 for (i, item) in arg_types.iter().enumerate() {
                     if i > 0 {
@@ -243,9 +303,116 @@ impl Display for UDFDefinition {
                     }
                     write!(f, "{} {}", item.name(), item.data_type())?;
                 }
-                write!(f, " }} RETURNS {return_type} LANGUAGE {language} RUNTIME_VERSION = {runtime_version} AS $${code}$$")?;
+                write!(
+                    f,
+                    " }} RETURNS {return_type} LANGUAGE {language} RUNTIME_VERSION = {runtime_version}"
+                )?;
+                write_script_options(f, imports, packages)?;
+                write!(f, " AS $${code}$$")?;
+            }
+            UDFDefinition::UDAFServer(UDAFServer {
+                address,
+                arg_types,
+                state_fields,
+                return_type,
+                language,
+            }) => {
+                for (i, item) in arg_types.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, ") STATE {{ ")?;
+                for (i, item) in state_fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} {}", item.name(), item.data_type())?;
+                }
+                write!(
+                    f,
+                    " }} RETURNS {return_type} LANGUAGE {language} ADDRESS = {address}"
+                )?;
+            }
+            UDFDefinition::UDTFServer(UDTFServer {
+                address,
+                arg_types,
+                return_fields,
+                handler,
+                language,
+            }) => {
+                for (i, item) in arg_types.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, ") RETURNS ")?;
+                write_table_fields(f, return_fields)?;
+                write!(
+                    f,
+                    " LANGUAGE {language} HANDLER = {handler} ADDRESS = {address}"
+                )?;
+            }
+            UDFDefinition::UDTFScript(UDTFScript {
+                code,
+                arg_types,
+                return_fields,
+                handler,
+                language,
+                runtime_version,
+                imports,
+                packages,
+            }) => {
+                for (i, item) in arg_types.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, ") RETURNS ")?;
+                write_table_fields(f, return_fields)?;
+                write!(
+                    f,
+                    " LANGUAGE {language} RUNTIME_VERSION = {runtime_version}"
+                )?;
+                write_script_options(f, imports, packages)?;
+                write!(f, " HANDLER = {handler} AS $${code}$$")?;
             }
         }
         Ok(())
     }
 }
+
+fn write_script_options(
+    f: &mut Formatter,
+    imports: &[String],
+    packages: &[String],
+) -> std::fmt::Result {
+    for (name, items) in [("IMPORTS", imports), ("PACKAGES", packages)] {
+        if items.is_empty() {
+            continue;
+        }
+        write!(f, " {name} = (")?;
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "'{item}'")?;
+        }
+        write!(f, ")")?;
+    }
+    Ok(())
+}
+
+fn write_table_fields(f: &mut Formatter, fields: &[DataField]) -> std::fmt::Result {
+    write!(f, "TABLE (")?;
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{} {}", field.name(), field.data_type())?;
+    }
+    write!(f, ")")
+}
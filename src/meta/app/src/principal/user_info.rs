@@ -134,18 +134,7 @@ impl UserInfo {
     }
 
     pub fn update_auth_need_change_password(&mut self) {
-        if let AuthInfo::Password {
-            hash_value,
-            hash_method,
-            ..
-        } = self.auth_info.clone()
-        {
-            self.auth_info = AuthInfo::Password {
-                hash_value,
-                hash_method,
-                need_change: true,
-            };
-        }
+        self.auth_info = self.auth_info.create_with_need_change(true);
     }
 
     pub fn update_user_time(&mut self) {
@@ -775,6 +775,12 @@ pub struct UpdateMultiTableMetaReq {
     pub update_stream_metas: Vec<UpdateStreamMetaReq>,
     pub deduplicated_labels: Vec<String>,
     pub update_temp_tables: Vec<UpdateTempTableReq>,
+    // the tables dropped in an explicit transaction, they are dropped
+    // before the tables created in it are committed.
+    pub drop_tables: Vec<DropTableByIdReq>,
+    // the tables created in an explicit transaction, they are made visible
+    // in the same meta transaction as the changes of the other tables.
+    pub commit_tables: Vec<CommitTableMetaReq>,
 }
 
 impl UpdateMultiTableMetaReq {
@@ -784,6 +790,8 @@ impl UpdateMultiTableMetaReq {
             && self.update_stream_metas.is_empty()
             && self.deduplicated_labels.is_empty()
             && self.update_temp_tables.is_empty()
+            && self.drop_tables.is_empty()
+            && self.commit_tables.is_empty()
     }
 }
 
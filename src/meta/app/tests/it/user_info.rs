@@ -38,6 +38,7 @@ fn test_user_info() -> Result<()> {
             hash_value: Vec::from("pwd"),
             hash_method: PasswordHashMethod::Sha256,
             need_change: false,
+            scram_verifier: None,
         },
     };
 
@@ -48,6 +49,7 @@ fn test_user_info() -> Result<()> {
         hash_value: Vec::from("pwd"),
         hash_method: PasswordHashMethod::Sha256,
         need_change: false,
+        scram_verifier: None,
     });
     expect.created_on = DateTime::<Utc>::default();
     expect.update_on = DateTime::<Utc>::default();
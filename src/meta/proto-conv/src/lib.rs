@@ -77,12 +77,14 @@ mod least_visible_time_from_to_protobuf_impl;
 mod lock_from_to_protobuf_impl;
 mod owner_from_to_protobuf_impl;
 mod ownership_from_to_protobuf_impl;
+mod pipe_from_to_protobuf_impl;
 mod procedure_from_to_protobuf_impl;
 mod role_from_to_protobuf_impl;
 mod schema_from_to_protobuf_impl;
 mod sequence_from_to_protobuf_impl;
 mod stage_from_to_protobuf_impl;
 mod table_from_to_protobuf_impl;
+mod task_from_to_protobuf_impl;
 mod tenant_quota_from_to_protobuf_impl;
 mod tident_from_to_protobuf_impl;
 mod token_from_to_protobuf_impl;
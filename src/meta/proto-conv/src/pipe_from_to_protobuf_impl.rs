@@ -0,0 +1,70 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This mod is the key point about compatibility.
+//! Everytime update anything in this file, update the `VER` and let the tests pass.
+
+use chrono::DateTime;
+use chrono::Utc;
+use databend_common_meta_app::principal as mt;
+use databend_common_protos::pb;
+
+use crate::reader_check_msg;
+use crate::FromToProto;
+use crate::Incompatible;
+use crate::MIN_READER_VER;
+use crate::VER;
+
+impl FromToProto for mt::PipeInfo {
+    type PB = pb::PipeInfo;
+    fn get_pb_ver(p: &Self::PB) -> u64 {
+        p.ver
+    }
+    fn from_pb(p: Self::PB) -> Result<Self, Incompatible>
+    where Self: Sized {
+        reader_check_msg(p.ver, p.min_reader_ver)?;
+
+        Ok(Self {
+            name: p.name,
+            copy_stmt: p.copy_stmt,
+            auto_ingest: p.auto_ingest,
+            execution_paused: p.execution_paused,
+            comment: p.comment,
+            created_on: DateTime::<Utc>::from_pb(p.created_on)?,
+            updated_on: match p.updated_on {
+                Some(t) => Some(DateTime::<Utc>::from_pb(t)?),
+                None => None,
+            },
+            owner: p.owner,
+        })
+    }
+
+    fn to_pb(&self) -> Result<Self::PB, Incompatible> {
+        Ok(Self::PB {
+            ver: VER,
+            min_reader_ver: MIN_READER_VER,
+            name: self.name.clone(),
+            copy_stmt: self.copy_stmt.clone(),
+            auto_ingest: self.auto_ingest,
+            execution_paused: self.execution_paused,
+            comment: self.comment.clone(),
+            created_on: self.created_on.to_pb()?,
+            updated_on: match &self.updated_on {
+                Some(t) => Some(t.to_pb()?),
+                None => None,
+            },
+            owner: self.owner.clone(),
+        })
+    }
+}
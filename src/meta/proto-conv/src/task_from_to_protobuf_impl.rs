@@ -0,0 +1,209 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This mod is the key point about compatibility.
+//! Everytime update anything in this file, update the `VER` and let the tests pass.
+
+use chrono::DateTime;
+use chrono::Utc;
+use databend_common_meta_app::principal as mt;
+use databend_common_protos::pb;
+use num::FromPrimitive;
+
+use crate::reader_check_msg;
+use crate::FromToProto;
+use crate::Incompatible;
+use crate::MIN_READER_VER;
+use crate::VER;
+
+impl FromToProto for mt::TaskSchedule {
+    type PB = pb::TaskSchedule;
+
+    fn get_pb_ver(_: &Self::PB) -> u64 {
+        VER
+    }
+
+    fn from_pb(p: Self::PB) -> Result<Self, Incompatible>
+    where Self: Sized {
+        match p.schedule {
+            Some(pb::task_schedule::Schedule::Interval(pb::task_schedule::Interval {
+                secs,
+                millis,
+            })) => Ok(mt::TaskSchedule::Interval { secs, millis }),
+            Some(pb::task_schedule::Schedule::Cron(pb::task_schedule::Cron {
+                expr,
+                time_zone,
+            })) => Ok(mt::TaskSchedule::Cron { expr, time_zone }),
+            None => Err(Incompatible::new("TaskSchedule cannot be None".to_string())),
+        }
+    }
+
+    fn to_pb(&self) -> Result<Self::PB, Incompatible> {
+        let schedule = match self {
+            mt::TaskSchedule::Interval { secs, millis } => {
+                pb::task_schedule::Schedule::Interval(pb::task_schedule::Interval {
+                    secs: *secs,
+                    millis: *millis,
+                })
+            }
+            mt::TaskSchedule::Cron { expr, time_zone } => {
+                pb::task_schedule::Schedule::Cron(pb::task_schedule::Cron {
+                    expr: expr.clone(),
+                    time_zone: time_zone.clone(),
+                })
+            }
+        };
+        Ok(pb::TaskSchedule {
+            schedule: Some(schedule),
+        })
+    }
+}
+
+impl FromToProto for mt::TaskInfo {
+    type PB = pb::TaskInfo;
+    fn get_pb_ver(p: &Self::PB) -> u64 {
+        p.ver
+    }
+    fn from_pb(p: Self::PB) -> Result<Self, Incompatible>
+    where Self: Sized {
+        reader_check_msg(p.ver, p.min_reader_ver)?;
+
+        Ok(Self {
+            task_id: p.task_id,
+            task_name: p.task_name,
+            query_text: p.query_text,
+            script_sqls: p.script_sqls,
+            when_condition: p.when_condition,
+            after: p.after,
+            comment: p.comment,
+            owner: p.owner,
+            schedule_options: match p.schedule_options {
+                Some(s) => Some(mt::TaskSchedule::from_pb(s)?),
+                None => None,
+            },
+            warehouse: p.warehouse,
+            suspend_task_after_num_failures: p.suspend_task_after_num_failures,
+            error_integration: p.error_integration,
+            session_params: p.session_params,
+            status: FromPrimitive::from_i32(p.status)
+                .ok_or_else(|| Incompatible::new(format!("invalid TaskStatus: {}", p.status)))?,
+            created_at: DateTime::<Utc>::from_pb(p.created_at)?,
+            updated_at: DateTime::<Utc>::from_pb(p.updated_at)?,
+            last_suspended_at: match p.last_suspended_at {
+                Some(t) => Some(DateTime::<Utc>::from_pb(t)?),
+                None => None,
+            },
+        })
+    }
+
+    fn to_pb(&self) -> Result<Self::PB, Incompatible> {
+        Ok(Self::PB {
+            ver: VER,
+            min_reader_ver: MIN_READER_VER,
+            task_id: self.task_id,
+            task_name: self.task_name.clone(),
+            query_text: self.query_text.clone(),
+            script_sqls: self.script_sqls.clone(),
+            when_condition: self.when_condition.clone(),
+            after: self.after.clone(),
+            comment: self.comment.clone(),
+            owner: self.owner.clone(),
+            schedule_options: match &self.schedule_options {
+                Some(s) => Some(s.to_pb()?),
+                None => None,
+            },
+            warehouse: self.warehouse.clone(),
+            suspend_task_after_num_failures: self.suspend_task_after_num_failures,
+            error_integration: self.error_integration.clone(),
+            session_params: self.session_params.clone(),
+            status: self.status as i32,
+            created_at: self.created_at.to_pb()?,
+            updated_at: self.updated_at.to_pb()?,
+            last_suspended_at: match &self.last_suspended_at {
+                Some(t) => Some(t.to_pb()?),
+                None => None,
+            },
+        })
+    }
+}
+
+impl FromToProto for mt::TaskRun {
+    type PB = pb::TaskRun;
+    fn get_pb_ver(p: &Self::PB) -> u64 {
+        p.ver
+    }
+    fn from_pb(p: Self::PB) -> Result<Self, Incompatible>
+    where Self: Sized {
+        reader_check_msg(p.ver, p.min_reader_ver)?;
+
+        Ok(Self {
+            run_id: p.run_id,
+            task_id: p.task_id,
+            task_name: p.task_name,
+            query_text: p.query_text,
+            condition_text: p.condition_text,
+            owner: p.owner,
+            comment: p.comment,
+            schedule_options: match p.schedule_options {
+                Some(s) => Some(mt::TaskSchedule::from_pb(s)?),
+                None => None,
+            },
+            warehouse: p.warehouse,
+            attempt_number: p.attempt_number,
+            state: FromPrimitive::from_i32(p.state)
+                .ok_or_else(|| Incompatible::new(format!("invalid TaskRunState: {}", p.state)))?,
+            error_code: p.error_code,
+            error_message: p.error_message,
+            query_id: p.query_id,
+            root_task_id: p.root_task_id,
+            session_params: p.session_params,
+            scheduled_at: DateTime::<Utc>::from_pb(p.scheduled_at)?,
+            completed_at: match p.completed_at {
+                Some(t) => Some(DateTime::<Utc>::from_pb(t)?),
+                None => None,
+            },
+        })
+    }
+
+    fn to_pb(&self) -> Result<Self::PB, Incompatible> {
+        Ok(Self::PB {
+            ver: VER,
+            min_reader_ver: MIN_READER_VER,
+            run_id: self.run_id.clone(),
+            task_id: self.task_id,
+            task_name: self.task_name.clone(),
+            query_text: self.query_text.clone(),
+            condition_text: self.condition_text.clone(),
+            owner: self.owner.clone(),
+            comment: self.comment.clone(),
+            schedule_options: match &self.schedule_options {
+                Some(s) => Some(s.to_pb()?),
+                None => None,
+            },
+            warehouse: self.warehouse.clone(),
+            attempt_number: self.attempt_number,
+            state: self.state as i32,
+            error_code: self.error_code,
+            error_message: self.error_message.clone(),
+            query_id: self.query_id.clone(),
+            root_task_id: self.root_task_id,
+            session_params: self.session_params.clone(),
+            scheduled_at: self.scheduled_at.to_pb()?,
+            completed_at: match &self.completed_at {
+                Some(t) => Some(t.to_pb()?),
+                None => None,
+            },
+        })
+    }
+}
@@ -16,6 +16,7 @@ use chrono::DateTime;
 use chrono::Utc;
 use databend_common_expression::infer_schema_type;
 use databend_common_expression::types::DataType;
+use databend_common_expression::DataField;
 use databend_common_expression::TableDataType;
 use databend_common_expression::TableField;
 use databend_common_meta_app::principal as mt;
@@ -135,6 +136,8 @@ impl FromToProto for mt::UDFScript {
             handler: p.handler,
             language: p.language,
             runtime_version: p.runtime_version,
+            imports: p.imports,
+            packages: p.packages,
         })
     }
 
@@ -169,6 +172,8 @@ impl FromToProto for mt::UDFScript {
             arg_types,
             return_type: Some(return_type),
             runtime_version: self.runtime_version.clone(),
+            imports: self.imports.clone(),
+            packages: self.packages.clone(),
         })
     }
 }
@@ -205,6 +210,8 @@ impl FromToProto for mt::UDAFScript {
             language: p.language,
             runtime_version: p.runtime_version,
             state_fields,
+            imports: p.imports,
+            packages: p.packages,
         })
     }
 
@@ -257,10 +264,222 @@ impl FromToProto for mt::UDAFScript {
             arg_types,
             state_fields,
             return_type: Some(return_type),
+            imports: self.imports.clone(),
+            packages: self.packages.clone(),
         })
     }
 }
 
+impl FromToProto for mt::UDAFServer {
+    type PB = pb::UdafServer;
+    fn get_pb_ver(p: &Self::PB) -> u64 {
+        p.ver
+    }
+    fn from_pb(p: pb::UdafServer) -> Result<Self, Incompatible> {
+        reader_check_msg(p.ver, p.min_reader_ver)?;
+
+        let arg_types = p
+            .arg_types
+            .into_iter()
+            .map(|arg_type| Ok((&TableDataType::from_pb(arg_type)?).into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let state_fields = p
+            .state_fields
+            .into_iter()
+            .map(|field| TableField::from_pb(field).map(|field| (&field).into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let return_type = (&TableDataType::from_pb(p.return_type.ok_or_else(|| {
+            Incompatible::new("UDAFServer.return_type can not be None".to_string())
+        })?)?)
+            .into();
+
+        Ok(mt::UDAFServer {
+            address: p.address,
+            language: p.language,
+            arg_types,
+            state_fields,
+            return_type,
+        })
+    }
+
+    fn to_pb(&self) -> Result<pb::UdafServer, Incompatible> {
+        let mut arg_types = Vec::with_capacity(self.arg_types.len());
+        for arg_type in self.arg_types.iter() {
+            let arg_type = infer_schema_type(arg_type)
+                .map_err(|e| {
+                    Incompatible::new(format!(
+                        "Convert DataType to TableDataType failed: {}",
+                        e.message()
+                    ))
+                })?
+                .to_pb()?;
+            arg_types.push(arg_type);
+        }
+
+        let state_fields = self
+            .state_fields
+            .iter()
+            .map(|field| {
+                TableField::new(
+                    field.name(),
+                    infer_schema_type(field.data_type()).map_err(|e| {
+                        Incompatible::new(format!(
+                            "Convert DataType to TableDataType failed: {}",
+                            e.message()
+                        ))
+                    })?,
+                )
+                .to_pb()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let return_type = infer_schema_type(&self.return_type)
+            .map_err(|e| {
+                Incompatible::new(format!(
+                    "Convert DataType to TableDataType failed: {}",
+                    e.message()
+                ))
+            })?
+            .to_pb()?;
+
+        Ok(pb::UdafServer {
+            ver: VER,
+            min_reader_ver: MIN_READER_VER,
+            address: self.address.clone(),
+            language: self.language.clone(),
+            arg_types,
+            state_fields,
+            return_type: Some(return_type),
+        })
+    }
+}
+
+impl FromToProto for mt::UDTFServer {
+    type PB = pb::UdtfServer;
+    fn get_pb_ver(p: &Self::PB) -> u64 {
+        p.ver
+    }
+    fn from_pb(p: pb::UdtfServer) -> Result<Self, Incompatible> {
+        reader_check_msg(p.ver, p.min_reader_ver)?;
+
+        let arg_types = p
+            .arg_types
+            .into_iter()
+            .map(|arg_type| Ok((&TableDataType::from_pb(arg_type)?).into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let return_fields = p
+            .return_fields
+            .into_iter()
+            .map(|field| TableField::from_pb(field).map(|field| (&field).into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(mt::UDTFServer {
+            address: p.address,
+            handler: p.handler,
+            language: p.language,
+            arg_types,
+            return_fields,
+        })
+    }
+
+    fn to_pb(&self) -> Result<pb::UdtfServer, Incompatible> {
+        Ok(pb::UdtfServer {
+            ver: VER,
+            min_reader_ver: MIN_READER_VER,
+            address: self.address.clone(),
+            handler: self.handler.clone(),
+            language: self.language.clone(),
+            arg_types: arg_types_to_pb(&self.arg_types)?,
+            return_fields: fields_to_pb(&self.return_fields)?,
+        })
+    }
+}
+
+impl FromToProto for mt::UDTFScript {
+    type PB = pb::UdtfScript;
+    fn get_pb_ver(p: &Self::PB) -> u64 {
+        p.ver
+    }
+    fn from_pb(p: pb::UdtfScript) -> Result<Self, Incompatible> {
+        reader_check_msg(p.ver, p.min_reader_ver)?;
+
+        let arg_types = p
+            .arg_types
+            .into_iter()
+            .map(|arg_type| Ok((&TableDataType::from_pb(arg_type)?).into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let return_fields = p
+            .return_fields
+            .into_iter()
+            .map(|field| TableField::from_pb(field).map(|field| (&field).into()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(mt::UDTFScript {
+            code: p.code,
+            handler: p.handler,
+            language: p.language,
+            arg_types,
+            return_fields,
+            runtime_version: p.runtime_version,
+            imports: p.imports,
+            packages: p.packages,
+        })
+    }
+
+    fn to_pb(&self) -> Result<pb::UdtfScript, Incompatible> {
+        Ok(pb::UdtfScript {
+            ver: VER,
+            min_reader_ver: MIN_READER_VER,
+            code: self.code.clone(),
+            handler: self.handler.clone(),
+            language: self.language.clone(),
+            arg_types: arg_types_to_pb(&self.arg_types)?,
+            return_fields: fields_to_pb(&self.return_fields)?,
+            runtime_version: self.runtime_version.clone(),
+            imports: self.imports.clone(),
+            packages: self.packages.clone(),
+        })
+    }
+}
+
+fn arg_types_to_pb(arg_types: &[DataType]) -> Result<Vec<pb::DataType>, Incompatible> {
+    arg_types
+        .iter()
+        .map(|arg_type| {
+            infer_schema_type(arg_type)
+                .map_err(|e| {
+                    Incompatible::new(format!(
+                        "Convert DataType to TableDataType failed: {}",
+                        e.message()
+                    ))
+                })?
+                .to_pb()
+        })
+        .collect()
+}
+
+fn fields_to_pb(fields: &[DataField]) -> Result<Vec<pb::DataField>, Incompatible> {
+    fields
+        .iter()
+        .map(|field| {
+            TableField::new(
+                field.name(),
+                infer_schema_type(field.data_type()).map_err(|e| {
+                    Incompatible::new(format!(
+                        "Convert DataType to TableDataType failed: {}",
+                        e.message()
+                    ))
+                })?,
+            )
+            .to_pb()
+        })
+        .collect()
+}
+
 impl FromToProto for mt::UserDefinedFunction {
     type PB = pb::UserDefinedFunction;
     fn get_pb_ver(p: &Self::PB) -> u64 {
@@ -281,6 +500,15 @@ impl FromToProto for mt::UserDefinedFunction {
             Some(pb::user_defined_function::Definition::UdafScript(udaf_script)) => {
                 mt::UDFDefinition::UDAFScript(mt::UDAFScript::from_pb(udaf_script)?)
             }
+            Some(pb::user_defined_function::Definition::UdafServer(udaf_server)) => {
+                mt::UDFDefinition::UDAFServer(mt::UDAFServer::from_pb(udaf_server)?)
+            }
+            Some(pb::user_defined_function::Definition::UdtfServer(udtf_server)) => {
+                mt::UDFDefinition::UDTFServer(mt::UDTFServer::from_pb(udtf_server)?)
+            }
+            Some(pb::user_defined_function::Definition::UdtfScript(udtf_script)) => {
+                mt::UDFDefinition::UDTFScript(mt::UDTFScript::from_pb(udtf_script)?)
+            }
             None => {
                 return Err(Incompatible::new(
                     "UserDefinedFunction.definition cannot be None".to_string(),
@@ -313,6 +541,15 @@ impl FromToProto for mt::UserDefinedFunction {
             mt::UDFDefinition::UDAFScript(udaf_script) => {
                 pb::user_defined_function::Definition::UdafScript(udaf_script.to_pb()?)
             }
+            mt::UDFDefinition::UDAFServer(udaf_server) => {
+                pb::user_defined_function::Definition::UdafServer(udaf_server.to_pb()?)
+            }
+            mt::UDFDefinition::UDTFServer(udtf_server) => {
+                pb::user_defined_function::Definition::UdtfServer(udtf_server.to_pb()?)
+            }
+            mt::UDFDefinition::UDTFScript(udtf_script) => {
+                pb::user_defined_function::Definition::UdtfScript(udtf_script.to_pb()?)
+            }
         };
 
         Ok(pb::UserDefinedFunction {
@@ -51,12 +51,19 @@ impl FromToProto for mt::principal::AuthInfo {
                 hash_value,
                 hash_method,
                 need_change,
+                scram_verifier,
             })) => Ok(mt::principal::AuthInfo::Password {
                 hash_value,
                 hash_method: FromPrimitive::from_i32(hash_method).ok_or_else(|| {
                     Incompatible::new(format!("invalid PasswordHashMethod: {}", hash_method))
                 })?,
                 need_change: need_change.unwrap_or_default(),
+                scram_verifier: scram_verifier.map(|v| mt::principal::ScramVerifier {
+                    iterations: v.iterations,
+                    salt: v.salt,
+                    stored_key: v.stored_key,
+                    server_key: v.server_key,
+                }),
             }),
             None => Err(Incompatible::new("AuthInfo cannot be None".to_string())),
         }
@@ -72,10 +79,19 @@ impl FromToProto for mt::principal::AuthInfo {
                 hash_value,
                 hash_method,
                 need_change,
+                scram_verifier,
             } => Some(pb::auth_info::Info::Password(pb::auth_info::Password {
                 hash_value: hash_value.clone(),
                 hash_method: *hash_method as i32,
                 need_change: Some(*need_change),
+                scram_verifier: scram_verifier.as_ref().map(|v| {
+                    pb::auth_info::password::ScramVerifier {
+                        iterations: v.iterations,
+                        salt: v.salt.clone(),
+                        stored_key: v.stored_key.clone(),
+                        server_key: v.server_key.clone(),
+                    }
+                }),
             })),
         };
         Ok(pb::AuthInfo {
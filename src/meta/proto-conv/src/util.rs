@@ -152,6 +152,13 @@ const META_CHANGE_LOG: &[(u64, &str)] = &[
     (120, "2025-02-11: Add: Add new UserPrivilege CreateWarehouse and new OwnershipObject::Warehouse"),
     (121, "2025-03-03: Add: Add new FileFormat AvroFileFormatParams"),
     (122, "2025-03-11: Add: table_meta and virtual_data_schema"),
+    (123, "2025-03-17: Add: pipe.proto: PipeInfo"),
+    (124, "2025-03-20: Add: udf.proto: UDFScript and UDAFScript add imports and packages"),
+    (125, "2025-03-24: Add: udf.proto: UDAFServer"),
+    (126, "2025-03-27: Add: udf.proto: UDTFServer and UDTFScript"),
+    (127, "2025-04-02: Add: task.proto: TaskInfo and TaskRun"),
+    (128, "2025-04-08: Add: user.proto: AuthInfo.Password add scram_verifier"),
+    (129, "2025-04-14: Add: pipe.proto: PipeInfo add owner"),
     // Dear developer:
     //      If you're gonna add a new metadata version, you'll have to add a test for it.
     //      You could just copy an existing test file(e.g., `../tests/it/v024_table_meta.rs`)
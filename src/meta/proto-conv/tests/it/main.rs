@@ -120,3 +120,10 @@ mod v119_virtual_column;
 mod v120_warehouse_ownershipobject;
 mod v121_avro_format_params;
 mod v122_virtual_schema;
+mod v123_pipe;
+mod v124_udf_script_imports;
+mod v125_udaf_server;
+mod v126_udtf;
+mod v127_task;
+mod v128_user_scram_verifier;
+mod v129_pipe_owner;
@@ -53,6 +53,7 @@ fn test_decode_v50_user_info() -> anyhow::Result<()> {
             .to_vec(),
             hash_method: databend_common_meta_app::principal::PasswordHashMethod::DoubleSha1,
             need_change: false,
+            scram_verifier: None,
         },
         grants: databend_common_meta_app::principal::UserGrantSet::new(
             vec![databend_common_meta_app::principal::GrantEntry::new(
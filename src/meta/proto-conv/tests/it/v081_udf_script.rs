@@ -114,6 +114,8 @@ fn test_decode_udf_script() -> anyhow::Result<()> {
             arg_types: vec![DataType::Number(NumberDataType::Int32)],
             return_type: DataType::Number(NumberDataType::Float32),
             runtime_version: "3.12.2".to_string(),
+            imports: vec![],
+            packages: vec![],
         }),
         created_on: DateTime::<Utc>::default(),
     };
@@ -58,6 +58,7 @@ fn test_decode_v102_user_must_change_password() -> anyhow::Result<()> {
             .to_vec(),
             hash_method: databend_common_meta_app::principal::PasswordHashMethod::DoubleSha1,
             need_change: true,
+            scram_verifier: None,
         },
         grants: databend_common_meta_app::principal::UserGrantSet::new(
             vec![databend_common_meta_app::principal::GrantEntry::new(
@@ -0,0 +1,112 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use chrono::TimeZone;
+use chrono::Utc;
+use databend_common_meta_app::principal as mt;
+use fastrace::func_name;
+
+use crate::common;
+
+// These bytes are built when a new version in introduced,
+// and are kept for backward compatibility test.
+//
+// *************************************************************
+// * These messages should never be updated,                   *
+// * only be added when a new version is added,                *
+// * or be removed when an old version is no longer supported. *
+// *************************************************************
+//
+#[test]
+fn test_decode_v127_task_info() -> anyhow::Result<()> {
+    let task_info_v127 = vec![
+        8, 1, 18, 5, 116, 97, 115, 107, 49, 26, 23, 73, 78, 83, 69, 82, 84, 32, 73, 78, 84, 79, 32,
+        116, 49, 32, 83, 69, 76, 69, 67, 84, 32, 49, 42, 5, 49, 32, 62, 32, 48, 50, 5, 116, 97,
+        115, 107, 48, 58, 7, 99, 111, 109, 109, 101, 110, 116, 66, 5, 114, 111, 108, 101, 49, 74,
+        20, 18, 18, 10, 11, 48, 32, 48, 32, 42, 32, 42, 32, 42, 32, 42, 18, 3, 85, 84, 67, 88, 3,
+        106, 15, 10, 8, 116, 105, 109, 101, 122, 111, 110, 101, 18, 3, 85, 84, 67, 112, 1, 122, 23,
+        50, 48, 49, 52, 45, 49, 49, 45, 50, 56, 32, 49, 50, 58, 48, 48, 58, 48, 57, 32, 85, 84, 67,
+        130, 1, 23, 50, 48, 49, 52, 45, 49, 49, 45, 50, 57, 32, 49, 50, 58, 48, 48, 58, 48, 57, 32,
+        85, 84, 67, 160, 6, 127, 168, 6, 24,
+    ];
+
+    let want = || mt::TaskInfo {
+        task_id: 1,
+        task_name: "task1".to_string(),
+        query_text: "INSERT INTO t1 SELECT 1".to_string(),
+        script_sqls: vec![],
+        when_condition: Some("1 > 0".to_string()),
+        after: vec!["task0".to_string()],
+        comment: Some("comment".to_string()),
+        owner: "role1".to_string(),
+        schedule_options: Some(mt::TaskSchedule::Cron {
+            expr: "0 0 * * * *".to_string(),
+            time_zone: Some("UTC".to_string()),
+        }),
+        warehouse: None,
+        suspend_task_after_num_failures: Some(3),
+        error_integration: None,
+        session_params: BTreeMap::from([("timezone".to_string(), "UTC".to_string())]),
+        status: mt::TaskStatus::Started,
+        created_at: Utc.with_ymd_and_hms(2014, 11, 28, 12, 0, 9).unwrap(),
+        updated_at: Utc.with_ymd_and_hms(2014, 11, 29, 12, 0, 9).unwrap(),
+        last_suspended_at: None,
+    };
+
+    common::test_pb_from_to(func_name!(), want())?;
+    common::test_load_old(func_name!(), task_info_v127.as_slice(), 127, want())
+}
+
+#[test]
+fn test_decode_v127_task_run() -> anyhow::Result<()> {
+    let task_run_v127 = vec![
+        10, 4, 114, 117, 110, 49, 16, 1, 26, 5, 116, 97, 115, 107, 49, 34, 34, 66, 69, 71, 73, 78,
+        32, 73, 78, 83, 69, 82, 84, 32, 73, 78, 84, 79, 32, 116, 49, 32, 83, 69, 76, 69, 67, 84,
+        32, 49, 59, 32, 69, 78, 68, 42, 5, 49, 32, 62, 32, 48, 50, 5, 114, 111, 108, 101, 49, 66,
+        7, 10, 5, 8, 60, 16, 244, 3, 74, 3, 119, 104, 49, 80, 1, 88, 3, 96, 238, 7, 106, 5, 101,
+        114, 114, 111, 114, 114, 4, 113, 105, 100, 49, 120, 1, 138, 1, 23, 50, 48, 49, 52, 45, 49,
+        49, 45, 50, 56, 32, 49, 50, 58, 48, 48, 58, 48, 57, 32, 85, 84, 67, 146, 1, 23, 50, 48, 49,
+        52, 45, 49, 49, 45, 50, 56, 32, 49, 50, 58, 48, 48, 58, 49, 48, 32, 85, 84, 67, 160, 6,
+        127, 168, 6, 24,
+    ];
+
+    let want = || mt::TaskRun {
+        run_id: "run1".to_string(),
+        task_id: 1,
+        task_name: "task1".to_string(),
+        query_text: "BEGIN INSERT INTO t1 SELECT 1; END".to_string(),
+        condition_text: "1 > 0".to_string(),
+        owner: "role1".to_string(),
+        comment: None,
+        schedule_options: Some(mt::TaskSchedule::Interval {
+            secs: 60,
+            millis: 500,
+        }),
+        warehouse: Some("wh1".to_string()),
+        attempt_number: 1,
+        state: mt::TaskRunState::Failed,
+        error_code: 1006,
+        error_message: Some("error".to_string()),
+        query_id: "qid1".to_string(),
+        root_task_id: 1,
+        session_params: BTreeMap::new(),
+        scheduled_at: Utc.with_ymd_and_hms(2014, 11, 28, 12, 0, 9).unwrap(),
+        completed_at: Some(Utc.with_ymd_and_hms(2014, 11, 28, 12, 0, 10).unwrap()),
+    };
+
+    common::test_pb_from_to(func_name!(), want())?;
+    common::test_load_old(func_name!(), task_run_v127.as_slice(), 127, want())
+}
@@ -0,0 +1,52 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fastrace::func_name;
+
+use crate::common;
+
+// These bytes are built when a new version in introduced,
+// and are kept for backward compatibility test.
+//
+// *************************************************************
+// * These messages should never be updated,                   *
+// * only be added when a new version is added,                *
+// * or be removed when an old version is no longer supported. *
+// *************************************************************
+//
+// The message bytes are built from the output of `proto_conv::test_build_pb_buf()`
+#[test]
+fn test_decode_v128_user_scram_verifier() -> anyhow::Result<()> {
+    let bytes: Vec<u8> = vec![
+        18, 54, 10, 13, 116, 101, 115, 116, 95, 112, 97, 115, 115, 119, 111, 114, 100, 16, 1, 24,
+        0, 34, 33, 8, 128, 32, 18, 4, 115, 97, 108, 116, 26, 10, 115, 116, 111, 114, 101, 100, 95,
+        107, 101, 121, 34, 10, 115, 101, 114, 118, 101, 114, 95, 107, 101, 121, 160, 6, 128, 1,
+        168, 6, 24,
+    ];
+
+    let want = || databend_common_meta_app::principal::AuthInfo::Password {
+        hash_value: b"test_password".to_vec(),
+        hash_method: databend_common_meta_app::principal::PasswordHashMethod::DoubleSha1,
+        need_change: false,
+        scram_verifier: Some(databend_common_meta_app::principal::ScramVerifier {
+            iterations: 4096,
+            salt: b"salt".to_vec(),
+            stored_key: b"stored_key".to_vec(),
+            server_key: b"server_key".to_vec(),
+        }),
+    };
+
+    common::test_pb_from_to(func_name!(), want())?;
+    common::test_load_old(func_name!(), bytes.as_slice(), 128, want())
+}
@@ -1303,6 +1303,9 @@ impl Display for WindowSpec {
                 WindowFrameUnits::Range => {
                     write!(f, "RANGE")?;
                 }
+                WindowFrameUnits::Groups => {
+                    write!(f, "GROUPS")?;
+                }
             }
 
             let format_frame = |frame: &WindowFrameBound| -> String {
@@ -1319,25 +1322,47 @@ impl Display for WindowSpec {
                 " BETWEEN {} AND {}",
                 format_frame(&frame.start_bound),
                 format_frame(&frame.end_bound)
-            )?
+            )?;
+            match frame.exclusion {
+                WindowFrameExclusion::NoOthers => {}
+                WindowFrameExclusion::CurrentRow => write!(f, " EXCLUDE CURRENT ROW")?,
+                WindowFrameExclusion::Group => write!(f, " EXCLUDE GROUP")?,
+                WindowFrameExclusion::Ties => write!(f, " EXCLUDE TIES")?,
+            }
         }
         write!(f, ")")?;
         Ok(())
     }
 }
 
-/// `RANGE UNBOUNDED PRECEDING` or `ROWS BETWEEN 5 PRECEDING AND CURRENT ROW`.
+/// `RANGE UNBOUNDED PRECEDING`, `ROWS BETWEEN 5 PRECEDING AND CURRENT ROW`
+/// or `GROUPS BETWEEN 1 PRECEDING AND 1 FOLLOWING EXCLUDE TIES`.
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
 pub struct WindowFrame {
     pub units: WindowFrameUnits,
     pub start_bound: WindowFrameBound,
     pub end_bound: WindowFrameBound,
+    pub exclusion: WindowFrameExclusion,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumAsInner, Drive, DriveMut)]
 pub enum WindowFrameUnits {
     Rows,
     Range,
+    Groups,
+}
+
+/// The `EXCLUDE` clause of [WindowFrame], `NoOthers` is the default
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Drive, DriveMut)]
+pub enum WindowFrameExclusion {
+    /// `EXCLUDE NO OTHERS`
+    NoOthers,
+    /// `EXCLUDE CURRENT ROW`
+    CurrentRow,
+    /// `EXCLUDE GROUP`
+    Group,
+    /// `EXCLUDE TIES`
+    Ties,
 }
 
 /// Specifies [WindowFrame]'s `start_bound` and `end_bound`
@@ -1837,6 +1862,25 @@ impl ExprReplacer {
                 self.replace_table_table_reference(&mut join.right);
             }
             TableReference::Location { .. } => (),
+            TableReference::MatchRecognize {
+                table,
+                match_recognize,
+                ..
+            } => {
+                self.replace_table_table_reference(table);
+                for expr in match_recognize.partition_by.iter_mut() {
+                    self.replace_expr(expr);
+                }
+                for order_by in match_recognize.order_by.iter_mut() {
+                    self.replace_expr(&mut order_by.expr);
+                }
+                for measure in match_recognize.measures.iter_mut() {
+                    self.replace_expr(&mut measure.expr);
+                }
+                for definition in match_recognize.define.iter_mut() {
+                    self.replace_expr(&mut definition.condition);
+                }
+            }
         }
     }
 
@@ -563,6 +563,7 @@ impl Display for Indirection {
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
 pub enum TimeTravelPoint {
     Snapshot(String),
+    Version(u64),
     Timestamp(Box<Expr>),
     Offset(Box<Expr>),
     Stream {
@@ -578,6 +579,9 @@ impl Display for TimeTravelPoint {
             TimeTravelPoint::Snapshot(sid) => {
                 write!(f, "(SNAPSHOT => '{sid}')")?;
             }
+            TimeTravelPoint::Version(version) => {
+                write!(f, "(VERSION => {version})")?;
+            }
             TimeTravelPoint::Timestamp(ts) => {
                 write!(f, "(TIMESTAMP => {ts})")?;
             }
@@ -667,6 +671,181 @@ impl Display for Unpivot {
     }
 }
 
+/// `MATCH_RECOGNIZE ( ... )` row pattern recognition clause
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct MatchRecognize {
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    pub measures: Vec<MatchMeasure>,
+    pub rows_per_match: RowsPerMatch,
+    pub after_match_skip: AfterMatchSkip,
+    pub pattern: MatchPattern,
+    pub define: Vec<MatchDefinition>,
+}
+
+impl Display for MatchRecognize {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "MATCH_RECOGNIZE(")?;
+        if !self.partition_by.is_empty() {
+            write!(f, "PARTITION BY ")?;
+            write_comma_separated_list(f, &self.partition_by)?;
+            write!(f, " ")?;
+        }
+        if !self.order_by.is_empty() {
+            write!(f, "ORDER BY ")?;
+            write_comma_separated_list(f, &self.order_by)?;
+            write!(f, " ")?;
+        }
+        if !self.measures.is_empty() {
+            write!(f, "MEASURES ")?;
+            write_comma_separated_list(f, &self.measures)?;
+            write!(f, " ")?;
+        }
+        write!(
+            f,
+            "{} {} PATTERN ({}) DEFINE ",
+            self.rows_per_match, self.after_match_skip, self.pattern
+        )?;
+        write_comma_separated_list(f, &self.define)?;
+        write!(f, ")")
+    }
+}
+
+/// An item of the `MEASURES` list: `expr AS alias`
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct MatchMeasure {
+    pub expr: Expr,
+    pub alias: Identifier,
+}
+
+impl Display for MatchMeasure {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} AS {}", self.expr, self.alias)
+    }
+}
+
+/// An item of the `DEFINE` list: `variable AS condition`
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct MatchDefinition {
+    pub name: Identifier,
+    pub condition: Expr,
+}
+
+impl Display for MatchDefinition {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} AS {}", self.name, self.condition)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Drive, DriveMut)]
+pub enum RowsPerMatch {
+    OneRow,
+    AllRows,
+}
+
+impl Display for RowsPerMatch {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            RowsPerMatch::OneRow => write!(f, "ONE ROW PER MATCH"),
+            RowsPerMatch::AllRows => write!(f, "ALL ROWS PER MATCH"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Drive, DriveMut)]
+pub enum AfterMatchSkip {
+    /// `AFTER MATCH SKIP PAST LAST ROW`
+    PastLastRow,
+    /// `AFTER MATCH SKIP TO NEXT ROW`
+    ToNextRow,
+    /// `AFTER MATCH SKIP TO FIRST variable`
+    ToFirst(Identifier),
+    /// `AFTER MATCH SKIP TO [LAST] variable`
+    ToLast(Identifier),
+}
+
+impl Display for AfterMatchSkip {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "AFTER MATCH SKIP ")?;
+        match self {
+            AfterMatchSkip::PastLastRow => write!(f, "PAST LAST ROW"),
+            AfterMatchSkip::ToNextRow => write!(f, "TO NEXT ROW"),
+            AfterMatchSkip::ToFirst(name) => write!(f, "TO FIRST {name}"),
+            AfterMatchSkip::ToLast(name) => write!(f, "TO LAST {name}"),
+        }
+    }
+}
+
+/// The row pattern of `MATCH_RECOGNIZE`, a regular expression over pattern variables
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub enum MatchPattern {
+    /// A pattern variable
+    Symbol(Identifier),
+    /// `^`, matches the start of a partition
+    Start,
+    /// `$`, matches the end of a partition
+    End,
+    Concat(Vec<MatchPattern>),
+    Alternation(Vec<MatchPattern>),
+    /// `pattern*`, `pattern+`, `pattern?` or `pattern{min, max}`, reluctant if followed by `?`
+    Repetition {
+        pattern: Box<MatchPattern>,
+        min: u64,
+        max: Option<u64>,
+        greedy: bool,
+    },
+    Group(Box<MatchPattern>),
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MatchPattern::Symbol(name) => write!(f, "{name}"),
+            MatchPattern::Start => write!(f, "^"),
+            MatchPattern::End => write!(f, "$"),
+            MatchPattern::Concat(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{pattern}")?;
+                }
+                Ok(())
+            }
+            MatchPattern::Alternation(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{pattern}")?;
+                }
+                Ok(())
+            }
+            MatchPattern::Repetition {
+                pattern,
+                min,
+                max,
+                greedy,
+            } => {
+                write!(f, "{pattern}")?;
+                match (min, max) {
+                    (0, None) => write!(f, "*")?,
+                    (1, None) => write!(f, "+")?,
+                    (0, Some(1)) => write!(f, "?")?,
+                    (min, None) => write!(f, "{{{min},}}")?,
+                    (min, Some(max)) if min == max => write!(f, "{{{min}}}")?,
+                    (min, Some(max)) => write!(f, "{{{min},{max}}}")?,
+                }
+                if !*greedy {
+                    write!(f, "?")?;
+                }
+                Ok(())
+            }
+            MatchPattern::Group(pattern) => write!(f, "({pattern})"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default, Drive, DriveMut)]
 pub struct WithOptions {
     pub options: BTreeMap<String, String>,
@@ -873,6 +1052,13 @@ pub enum TableReference {
         options: SelectStageOptions,
         alias: Option<TableAlias>,
     },
+    // `table MATCH_RECOGNIZE ( ... ) [ AS alias ]`
+    MatchRecognize {
+        span: Span,
+        table: Box<TableReference>,
+        match_recognize: Box<MatchRecognize>,
+        alias: Option<TableAlias>,
+    },
 }
 
 impl TableReference {
@@ -1039,8 +1225,17 @@ impl Display for TableReference {
                     JoinOperator::CrossJoin => {
                         write!(f, " CROSS JOIN")?;
                     }
+                    JoinOperator::Asof => {
+                        write!(f, " ASOF JOIN")?;
+                    }
+                    JoinOperator::LeftAsof => {
+                        write!(f, " ASOF LEFT JOIN")?;
+                    }
                 }
                 write!(f, " {}", join.right)?;
+                if let Some(match_condition) = &join.match_condition {
+                    write!(f, " MATCH_CONDITION ({match_condition})")?;
+                }
                 match &join.condition {
                     JoinCondition::On(expr) => {
                         write!(f, " ON {expr}")?;
@@ -1067,6 +1262,17 @@ impl Display for TableReference {
                     write!(f, " AS {alias}")?;
                 }
             }
+            TableReference::MatchRecognize {
+                span: _,
+                table,
+                match_recognize,
+                alias,
+            } => {
+                write!(f, "{table} {match_recognize}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+            }
         }
         Ok(())
     }
@@ -1094,6 +1300,8 @@ impl Display for TableAlias {
 pub struct Join {
     pub op: JoinOperator,
     pub condition: JoinCondition,
+    /// The inequality `MATCH_CONDITION` of an ASOF join
+    pub match_condition: Option<Box<Expr>>,
     pub left: Box<TableReference>,
     pub right: Box<TableReference>,
 }
@@ -1111,6 +1319,9 @@ pub enum JoinOperator {
     RightAnti,
     // CrossJoin can only work with `JoinCondition::None`
     CrossJoin,
+    // Asof joins must have a `MATCH_CONDITION`
+    Asof,
+    LeftAsof,
 }
 
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
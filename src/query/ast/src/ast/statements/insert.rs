@@ -34,9 +34,13 @@ pub struct InsertStmt {
     pub catalog: Option<Identifier>,
     pub database: Option<Identifier>,
     pub table: Identifier,
+    /// The `PARTITION` clause of a hive style insert.
+    pub partition: Vec<InsertPartitionValue>,
     pub columns: Vec<Identifier>,
     pub source: InsertSource,
     pub overwrite: bool,
+    /// The `ON CONFLICT` clause of an insert into a table with key constraints.
+    pub on_conflict: Option<InsertOnConflict>,
 }
 
 impl Display for InsertStmt {
@@ -60,15 +64,79 @@ impl Display for InsertStmt {
                 .chain(&self.database)
                 .chain(Some(&self.table)),
         )?;
+        if !self.partition.is_empty() {
+            write!(f, " PARTITION (")?;
+            write_comma_separated_list(f, &self.partition)?;
+            write!(f, ")")?;
+        }
         if !self.columns.is_empty() {
             write!(f, " (")?;
             write_comma_separated_list(f, &self.columns)?;
             write!(f, ")")?;
         }
+        if let Some(on_conflict) = &self.on_conflict {
+            write!(f, " {on_conflict}")?;
+        }
         write!(f, " {}", self.source)
     }
 }
 
+/// `ON CONFLICT [(columns)] DO NOTHING | DO UPDATE`, the conflict target is any key of the
+/// table if the columns are not specified.
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct InsertOnConflict {
+    pub columns: Vec<Identifier>,
+    pub action: OnConflictAction,
+}
+
+impl Display for InsertOnConflict {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "ON CONFLICT")?;
+        if !self.columns.is_empty() {
+            write!(f, " (")?;
+            write_comma_separated_list(f, &self.columns)?;
+            write!(f, ")")?;
+        }
+        write!(f, " {}", self.action)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Drive, DriveMut)]
+pub enum OnConflictAction {
+    /// Skip the rows conflicting with existing rows or earlier rows of the insert.
+    DoNothing,
+    /// Replace the existing rows by the conflicting rows, like `REPLACE INTO`,
+    /// so all the columns must be inserted.
+    DoUpdate,
+}
+
+impl Display for OnConflictAction {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            OnConflictAction::DoNothing => write!(f, "DO NOTHING"),
+            OnConflictAction::DoUpdate => write!(f, "DO UPDATE"),
+        }
+    }
+}
+
+/// A partition column in the `PARTITION` clause, the partition is static if the value is
+/// specified, otherwise the value is taken from the insert source.
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct InsertPartitionValue {
+    pub column: Identifier,
+    pub value: Option<Expr>,
+}
+
+impl Display for InsertPartitionValue {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.column)?;
+        if let Some(value) = &self.value {
+            write!(f, " = {value}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
 pub enum InsertSource {
     Values { rows: Vec<Vec<Expr>> },
@@ -0,0 +1,70 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use derive_visitor::Drive;
+use derive_visitor::DriveMut;
+
+use crate::ast::write_comma_separated_list;
+use crate::ast::Expr;
+use crate::ast::Identifier;
+use crate::ast::Statement;
+
+/// `PREPARE <name> AS <statement>`, the parameters of the statement are `?` or `$<n>`.
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct PrepareStmt {
+    pub name: Identifier,
+    pub statement: Box<Statement>,
+}
+
+impl Display for PrepareStmt {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "PREPARE {} AS {}", self.name, self.statement)
+    }
+}
+
+/// `EXECUTE <name> [USING <expr>, ...]`
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct ExecutePreparedStmt {
+    pub name: Identifier,
+    pub params: Vec<Expr>,
+}
+
+impl Display for ExecutePreparedStmt {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "EXECUTE {}", self.name)?;
+        if !self.params.is_empty() {
+            write!(f, " USING ")?;
+            write_comma_separated_list(f, &self.params)?;
+        }
+        Ok(())
+    }
+}
+
+/// `DEALLOCATE [PREPARE] { <name> | ALL }`, the name is `None` for `ALL`.
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct DeallocateStmt {
+    pub name: Option<Identifier>,
+}
+
+impl Display for DeallocateStmt {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "DEALLOCATE PREPARE {name}"),
+            None => write!(f, "DEALLOCATE ALL"),
+        }
+    }
+}
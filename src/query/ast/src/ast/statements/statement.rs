@@ -325,6 +325,7 @@ pub enum Statement {
     ShowTasks(ShowTasksStmt),
 
     CreateDynamicTable(CreateDynamicTableStmt),
+    RefreshDynamicTable(RefreshDynamicTableStmt),
 
     // pipes
     CreatePipe(CreatePipeStmt),
@@ -336,6 +337,9 @@ pub enum Statement {
     Begin,
     Commit,
     Abort,
+    Savepoint(Identifier),
+    RollbackToSavepoint(Identifier),
+    ReleaseSavepoint(Identifier),
 
     // Notifications
     CreateNotification(CreateNotificationStmt),
@@ -353,6 +357,11 @@ pub enum Statement {
     DescProcedure(DescProcedureStmt),
     CallProcedure(CallProcedureStmt),
 
+    // Prepared statements
+    Prepare(PrepareStmt),
+    ExecutePrepared(ExecutePreparedStmt),
+    Deallocate(DeallocateStmt),
+
     // Sequence
     CreateSequence(CreateSequenceStmt),
     DropSequence(DropSequenceStmt),
@@ -463,6 +472,7 @@ impl Statement {
             | Statement::RefreshIndex(..)
             | Statement::RefreshInvertedIndex(..)
             | Statement::RefreshVirtualColumn(..)
+            | Statement::RefreshDynamicTable(..)
             | Statement::ShowVirtualColumns(..)
             | Statement::ShowUsers { .. }
             | Statement::DescribeUser { .. }
@@ -489,8 +499,14 @@ impl Statement {
             | Statement::Begin
             | Statement::Commit
             | Statement::Abort
+            | Statement::Savepoint(..)
+            | Statement::RollbackToSavepoint(..)
+            | Statement::ReleaseSavepoint(..)
             | Statement::DescribeNotification(..)
             | Statement::ExecuteImmediate(..)
+            | Statement::Prepare(..)
+            | Statement::ExecutePrepared(..)
+            | Statement::Deallocate(..)
             | Statement::ShowProcedures { .. }
             | Statement::DescProcedure(..)
             | Statement::CallProcedure(..)
@@ -498,8 +514,23 @@ impl Statement {
             | Statement::ShowOnlineNodes(..)
             | Statement::InspectWarehouse(..) => true,
 
+            // Tables created, dropped or altered in a transaction are committed with it.
+            Statement::CreateTable(..) => true,
+            Statement::DropTable(stmt) => !stmt.all,
+            Statement::AlterTable(stmt) => match &stmt.action {
+                AlterTableAction::AddColumn { .. }
+                | AlterTableAction::RenameColumn { .. }
+                | AlterTableAction::ModifyTableComment { .. }
+                | AlterTableAction::DropColumn { .. } => true,
+                AlterTableAction::ModifyColumn { action } => !matches!(
+                    action,
+                    ModifyColumnAction::SetMaskingPolicy(..)
+                        | ModifyColumnAction::UnsetMaskingPolicy(..)
+                ),
+                _ => false,
+            },
+
             Statement::CreateDatabase(..)
-            | Statement::CreateTable(..)
             | Statement::CreateView(..)
             | Statement::CreateIndex(..)
             | Statement::CreateStage(..)
@@ -507,12 +538,10 @@ impl Statement {
             | Statement::CreateDictionary(..)
             | Statement::CreateConnection(..)
             | Statement::CreatePipe(..)
-            | Statement::AlterTable(..)
             | Statement::AlterView(..)
             | Statement::AlterUser(..)
             | Statement::AlterDatabase(..)
             | Statement::DropDatabase(..)
-            | Statement::DropTable(..)
             | Statement::DropView(..)
             | Statement::DropIndex(..)
             | Statement::DropSequence(..)
@@ -584,7 +613,12 @@ impl Statement {
     pub fn is_transaction_command(&self) -> bool {
         matches!(
             self,
-            Statement::Commit | Statement::Abort | Statement::Begin
+            Statement::Commit
+                | Statement::Abort
+                | Statement::Begin
+                | Statement::Savepoint(..)
+                | Statement::RollbackToSavepoint(..)
+                | Statement::ReleaseSavepoint(..)
         )
     }
 }
@@ -974,6 +1008,9 @@ impl Display for Statement {
             Statement::Begin => write!(f, "BEGIN")?,
             Statement::Commit => write!(f, "COMMIT")?,
             Statement::Abort => write!(f, "ABORT")?,
+            Statement::Savepoint(name) => write!(f, "SAVEPOINT {name}")?,
+            Statement::RollbackToSavepoint(name) => write!(f, "ROLLBACK TO SAVEPOINT {name}")?,
+            Statement::ReleaseSavepoint(name) => write!(f, "RELEASE SAVEPOINT {name}")?,
             Statement::CreateNotification(stmt) => write!(f, "{stmt}")?,
             Statement::AlterNotification(stmt) => write!(f, "{stmt}")?,
             Statement::DropNotification(stmt) => write!(f, "{stmt}")?,
@@ -988,9 +1025,13 @@ impl Display for Statement {
                     write!(f, " {show_options}")?;
                 }
             }
+            Statement::Prepare(stmt) => write!(f, "{stmt}")?,
+            Statement::ExecutePrepared(stmt) => write!(f, "{stmt}")?,
+            Statement::Deallocate(stmt) => write!(f, "{stmt}")?,
             Statement::CreateSequence(stmt) => write!(f, "{stmt}")?,
             Statement::DropSequence(stmt) => write!(f, "{stmt}")?,
             Statement::CreateDynamicTable(stmt) => write!(f, "{stmt}")?,
+            Statement::RefreshDynamicTable(stmt) => write!(f, "{stmt}")?,
             Statement::SetPriority {
                 priority,
                 object_id,
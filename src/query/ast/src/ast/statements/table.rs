@@ -284,7 +284,11 @@ impl Display for AttachTableStmt {
 
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
 pub enum CreateTableSource {
-    Columns(Vec<ColumnDefinition>, Option<Vec<InvertedIndexDefinition>>),
+    Columns(
+        Vec<ColumnDefinition>,
+        Option<Vec<InvertedIndexDefinition>>,
+        Option<Vec<ConstraintDefinition>>,
+    ),
     Like {
         catalog: Option<Identifier>,
         database: Option<Identifier>,
@@ -295,13 +299,17 @@ pub enum CreateTableSource {
 impl Display for CreateTableSource {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
-            CreateTableSource::Columns(columns, inverted_indexes) => {
+            CreateTableSource::Columns(columns, inverted_indexes, constraints) => {
                 write!(f, "(")?;
                 write_comma_separated_list(f, columns)?;
                 if let Some(inverted_indexes) = inverted_indexes {
                     write!(f, ", ")?;
                     write_comma_separated_list(f, inverted_indexes)?;
                 }
+                if let Some(constraints) = constraints {
+                    write!(f, ", ")?;
+                    write_comma_separated_list(f, constraints)?;
+                }
                 write!(f, ")")
             }
             CreateTableSource::Like {
@@ -443,6 +451,12 @@ pub enum AlterTableAction {
     UnsetOptions {
         targets: Vec<Identifier>,
     },
+    AddConstraint {
+        constraint: ConstraintDefinition,
+    },
+    DropConstraint {
+        name: Identifier,
+    },
 }
 
 impl Display for AlterTableAction {
@@ -512,6 +526,12 @@ impl Display for AlterTableAction {
                     write!(f, ")")?;
                 }
             }
+            AlterTableAction::AddConstraint { constraint } => {
+                write!(f, "ADD {constraint}")?;
+            }
+            AlterTableAction::DropConstraint { name } => {
+                write!(f, "DROP CONSTRAINT {name}")?;
+            }
         };
         Ok(())
     }
@@ -932,10 +952,83 @@ impl Display for InvertedIndexDefinition {
     }
 }
 
+/// A table constraint, `[CONSTRAINT name] PRIMARY KEY | UNIQUE | CHECK | FOREIGN KEY ...`.
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub struct ConstraintDefinition {
+    pub name: Option<Identifier>,
+    pub constraint: TableConstraint,
+}
+
+impl Display for ConstraintDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {name} ")?;
+        }
+        write!(f, "{}", self.constraint)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
+pub enum TableConstraint {
+    PrimaryKey {
+        columns: Vec<Identifier>,
+    },
+    Unique {
+        columns: Vec<Identifier>,
+    },
+    Check {
+        expr: Box<Expr>,
+    },
+    ForeignKey {
+        columns: Vec<Identifier>,
+        ref_database: Option<Identifier>,
+        ref_table: Identifier,
+        ref_columns: Vec<Identifier>,
+    },
+}
+
+impl Display for TableConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableConstraint::PrimaryKey { columns } => {
+                write!(f, "PRIMARY KEY (")?;
+                write_comma_separated_list(f, columns)?;
+                write!(f, ")")
+            }
+            TableConstraint::Unique { columns } => {
+                write!(f, "UNIQUE (")?;
+                write_comma_separated_list(f, columns)?;
+                write!(f, ")")
+            }
+            TableConstraint::Check { expr } => {
+                write!(f, "CHECK ({expr})")
+            }
+            TableConstraint::ForeignKey {
+                columns,
+                ref_database,
+                ref_table,
+                ref_columns,
+            } => {
+                write!(f, "FOREIGN KEY (")?;
+                write_comma_separated_list(f, columns)?;
+                write!(f, ") REFERENCES ")?;
+                write_dot_separated_list(f, ref_database.iter().chain(Some(ref_table)))?;
+                if !ref_columns.is_empty() {
+                    write!(f, " (")?;
+                    write_comma_separated_list(f, ref_columns)?;
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
 pub enum CreateDefinition {
     Column(ColumnDefinition),
     InvertedIndex(InvertedIndexDefinition),
+    Constraint(ConstraintDefinition),
 }
 
 impl Display for CreateDefinition {
@@ -947,6 +1040,9 @@ impl Display for CreateDefinition {
             CreateDefinition::InvertedIndex(inverted_index_def) => {
                 write!(f, "{}", inverted_index_def)?;
             }
+            CreateDefinition::Constraint(constraint_def) => {
+                write!(f, "{}", constraint_def)?;
+            }
         }
         Ok(())
     }
@@ -19,6 +19,7 @@ use derive_visitor::Drive;
 use derive_visitor::DriveMut;
 
 use crate::ast::write_comma_separated_list;
+use crate::ast::write_comma_separated_string_list;
 use crate::ast::CreateOption;
 use crate::ast::Expr;
 use crate::ast::Identifier;
@@ -44,6 +45,8 @@ pub enum UDFDefinition {
         handler: String,
         language: String,
         runtime_version: String,
+        imports: Vec<String>,
+        packages: Vec<String>,
     },
     UDAFServer {
         arg_types: Vec<TypeName>,
@@ -59,6 +62,25 @@ pub enum UDFDefinition {
         code: String,
         language: String,
         runtime_version: String,
+        imports: Vec<String>,
+        packages: Vec<String>,
+    },
+    UDTFServer {
+        arg_types: Vec<TypeName>,
+        return_columns: Vec<(Identifier, TypeName)>,
+        address: String,
+        handler: String,
+        language: String,
+    },
+    UDTFScript {
+        arg_types: Vec<TypeName>,
+        return_columns: Vec<(Identifier, TypeName)>,
+        code: String,
+        handler: String,
+        language: String,
+        runtime_version: String,
+        imports: Vec<String>,
+        packages: Vec<String>,
     },
 }
 
@@ -94,13 +116,14 @@ impl Display for UDFDefinition {
                 handler,
                 language,
                 runtime_version: _,
+                imports,
+                packages,
             } => {
                 write!(f, "( ")?;
                 write_comma_separated_list(f, arg_types)?;
-                write!(
-                    f,
-                    " ) RETURNS {return_type} LANGUAGE {language} HANDLER = '{handler}' AS $$\n{code}\n$$"
-                )?;
+                write!(f, " ) RETURNS {return_type} LANGUAGE {language}")?;
+                write_script_options(f, imports, packages)?;
+                write!(f, " HANDLER = '{handler}' AS $$\n{code}\n$$")?;
             }
             UDFDefinition::UDAFServer {
                 arg_types,
@@ -125,21 +148,85 @@ impl Display for UDFDefinition {
                 code,
                 language,
                 runtime_version: _,
+                imports,
+                packages,
             } => {
                 write!(f, "( ")?;
                 write_comma_separated_list(f, arg_types)?;
                 write!(f, " ) STATE {{ ")?;
                 write_comma_separated_list(f, state_types)?;
+                write!(f, " }} RETURNS {return_type} LANGUAGE {language}")?;
+                write_script_options(f, imports, packages)?;
+                write!(f, " AS $$\n{code}\n$$")?;
+            }
+            UDFDefinition::UDTFServer {
+                arg_types,
+                return_columns,
+                address,
+                handler,
+                language,
+            } => {
+                write!(f, "( ")?;
+                write_comma_separated_list(f, arg_types)?;
+                write!(f, " ) RETURNS ")?;
+                write_table_columns(f, return_columns)?;
                 write!(
                     f,
-                    " }} RETURNS {return_type} LANGUAGE {language} AS $$\n{code}\n$$"
+                    " LANGUAGE {language} HANDLER = '{handler}' ADDRESS = '{address}'"
                 )?;
             }
+            UDFDefinition::UDTFScript {
+                arg_types,
+                return_columns,
+                code,
+                handler,
+                language,
+                runtime_version: _,
+                imports,
+                packages,
+            } => {
+                write!(f, "( ")?;
+                write_comma_separated_list(f, arg_types)?;
+                write!(f, " ) RETURNS ")?;
+                write_table_columns(f, return_columns)?;
+                write!(f, " LANGUAGE {language}")?;
+                write_script_options(f, imports, packages)?;
+                write!(f, " HANDLER = '{handler}' AS $$\n{code}\n$$")?;
+            }
         }
         Ok(())
     }
 }
 
+fn write_table_columns(f: &mut Formatter, columns: &[(Identifier, TypeName)]) -> std::fmt::Result {
+    write!(f, "TABLE (")?;
+    for (i, (name, type_name)) in columns.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{name} {type_name}")?;
+    }
+    write!(f, ")")
+}
+
+fn write_script_options(
+    f: &mut Formatter,
+    imports: &[String],
+    packages: &[String],
+) -> std::fmt::Result {
+    if !imports.is_empty() {
+        write!(f, " IMPORTS = (")?;
+        write_comma_separated_string_list(f, imports)?;
+        write!(f, ")")?;
+    }
+    if !packages.is_empty() {
+        write!(f, " PACKAGES = (")?;
+        write_comma_separated_string_list(f, packages)?;
+        write!(f, ")")?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq, Drive, DriveMut)]
 pub struct UDAFStateField {
     pub name: Identifier,
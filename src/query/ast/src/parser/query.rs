@@ -566,6 +566,15 @@ pub fn at_snapshot_or_ts(i: Input) -> IResult<TimeTravelPoint> {
         rule! { "(" ~ SNAPSHOT ~ "=>" ~ #literal_string ~ ")" },
         |(_, _, _, s, _)| TimeTravelPoint::Snapshot(s),
     );
+    // Snapshot ids of iceberg tables are integers.
+    let at_snapshot_id = map(
+        rule! { "(" ~ SNAPSHOT ~ "=>" ~ #literal_u64 ~ ")" },
+        |(_, _, _, id, _)| TimeTravelPoint::Snapshot(id.to_string()),
+    );
+    let at_version = map(
+        rule! { "(" ~ VERSION ~ "=>" ~ #literal_u64 ~ ")" },
+        |(_, _, _, version, _)| TimeTravelPoint::Version(version),
+    );
     let at_timestamp = map(
         rule! { "(" ~ TIMESTAMP ~ "=>" ~ #expr ~ ")" },
         |(_, _, _, e, _)| TimeTravelPoint::Timestamp(Box::new(e)),
@@ -576,7 +585,7 @@ pub fn at_snapshot_or_ts(i: Input) -> IResult<TimeTravelPoint> {
     );
 
     rule!(
-        #at_snapshot | #at_timestamp | #at_offset
+        #at_snapshot | #at_snapshot_id | #at_version | #at_timestamp | #at_offset
     )(i)
 }
 
@@ -678,6 +687,8 @@ pub fn join_operator(i: Input) -> IResult<JoinOperator> {
         value(JoinOperator::RightOuter, rule! { RIGHT ~ OUTER? }),
         value(JoinOperator::FullOuter, rule! { FULL ~ OUTER? }),
         value(JoinOperator::CrossJoin, rule! { CROSS }),
+        value(JoinOperator::LeftAsof, rule! { ASOF ~ LEFT }),
+        value(JoinOperator::Asof, rule! { ASOF }),
     ))(i)
 }
 
@@ -764,6 +775,13 @@ pub enum TableReferenceElement {
     },
     // ON expr | USING (ident, ...)
     JoinCondition(JoinCondition),
+    // MATCH_CONDITION (expr)
+    MatchCondition(Expr),
+    // MATCH_RECOGNIZE ( ... ) [ AS alias ]
+    MatchRecognize {
+        match_recognize: Box<MatchRecognize>,
+        alias: Option<TableAlias>,
+    },
     Group(TableReference),
     Stage {
         location: FileLocation,
@@ -823,6 +841,21 @@ pub fn table_reference_element(i: Input) -> IResult<WithSpan<TableReferenceEleme
         },
         |(_, _, idents, _)| TableReferenceElement::JoinCondition(JoinCondition::Using(idents)),
     );
+    let match_condition = map(
+        rule! {
+            MATCH_CONDITION ~ ^"(" ~ ^#expr ~ ^")"
+        },
+        |(_, _, expr, _)| TableReferenceElement::MatchCondition(expr),
+    );
+    let match_recognize = map(
+        rule! {
+            MATCH_RECOGNIZE ~ ^"(" ~ ^#match_recognize ~ ^")" ~ #table_alias?
+        },
+        |(_, _, match_recognize, _, alias)| TableReferenceElement::MatchRecognize {
+            match_recognize: Box::new(match_recognize),
+            alias,
+        },
+    );
     let table_function = map(
         rule! {
             LATERAL? ~ #function_name ~ "(" ~ #comma_separated_list0(table_function_param) ~ ")" ~ #table_alias? ~ SAMPLE? ~ (BLOCK ~ "(" ~ #expr ~ ")")? ~ (ROW ~ "(" ~ #expr ~ ROWS? ~ ")")?
@@ -882,10 +915,137 @@ pub fn table_reference_element(i: Input) -> IResult<WithSpan<TableReferenceEleme
         | #join
         | #join_condition_on
         | #join_condition_using
+        | #match_condition
+        | #match_recognize
     })(i)?;
     Ok((rest, WithSpan { span, elem }))
 }
 
+// MATCH_RECOGNIZE(
+//     [PARTITION BY expr, ...] [ORDER BY expr, ...] [MEASURES expr AS ident, ...]
+//     [ONE ROW PER MATCH | ALL ROWS PER MATCH] [AFTER MATCH SKIP ...]
+//     PATTERN (pattern) DEFINE ident AS expr, ...
+// )
+pub fn match_recognize(i: Input) -> IResult<MatchRecognize> {
+    let measure = map(rule! { #expr ~ ^AS ~ ^#ident }, |(expr, _, alias)| {
+        MatchMeasure { expr, alias }
+    });
+    let definition = map(rule! { #ident ~ ^AS ~ ^#expr }, |(name, _, condition)| {
+        MatchDefinition { name, condition }
+    });
+    let rows_per_match = alt((
+        value(RowsPerMatch::OneRow, rule! { ONE ~ ^ROW ~ ^PER ~ ^MATCH }),
+        value(RowsPerMatch::AllRows, rule! { ALL ~ ^ROWS ~ ^PER ~ ^MATCH }),
+    ));
+    let after_match_skip = alt((
+        value(AfterMatchSkip::PastLastRow, rule! { PAST ~ ^LAST ~ ^ROW }),
+        value(AfterMatchSkip::ToNextRow, rule! { TO ~ NEXT ~ ROW }),
+        map(rule! { TO ~ FIRST ~ ^#ident }, |(_, _, name)| {
+            AfterMatchSkip::ToFirst(name)
+        }),
+        map(rule! { TO ~ LAST? ~ ^#ident }, |(_, _, name)| {
+            AfterMatchSkip::ToLast(name)
+        }),
+    ));
+    map(
+        rule! {
+            ( PARTITION ~ ^BY ~ ^#comma_separated_list1(expr) )?
+            ~ ( ORDER ~ ^BY ~ ^#comma_separated_list1(order_by_expr) )?
+            ~ ( MEASURES ~ ^#comma_separated_list1(measure) )?
+            ~ #rows_per_match?
+            ~ ( AFTER ~ ^MATCH ~ ^SKIP ~ ^#after_match_skip )?
+            ~ PATTERN ~ ^"(" ~ ^#match_pattern ~ ^")"
+            ~ DEFINE ~ ^#comma_separated_list1(definition)
+        },
+        |(
+            opt_partition,
+            opt_order,
+            opt_measures,
+            rows_per_match,
+            opt_after_match_skip,
+            _,
+            _,
+            pattern,
+            _,
+            _,
+            define,
+        )| MatchRecognize {
+            partition_by: opt_partition.map(|(_, _, exprs)| exprs).unwrap_or_default(),
+            order_by: opt_order.map(|(_, _, exprs)| exprs).unwrap_or_default(),
+            measures: opt_measures
+                .map(|(_, measures)| measures)
+                .unwrap_or_default(),
+            rows_per_match: rows_per_match.unwrap_or(RowsPerMatch::OneRow),
+            after_match_skip: opt_after_match_skip
+                .map(|(_, _, _, skip)| skip)
+                .unwrap_or(AfterMatchSkip::PastLastRow),
+            pattern,
+            define,
+        },
+    )(i)
+}
+
+// pattern | pattern ...
+fn match_pattern(i: Input) -> IResult<MatchPattern> {
+    map(
+        rule! {
+            #match_pattern_concat ~ ( "|" ~ ^#match_pattern_concat )*
+        },
+        |(first, rest)| {
+            if rest.is_empty() {
+                first
+            } else {
+                let mut patterns = vec![first];
+                patterns.extend(rest.into_iter().map(|(_, pattern)| pattern));
+                MatchPattern::Alternation(patterns)
+            }
+        },
+    )(i)
+}
+
+fn match_pattern_concat(i: Input) -> IResult<MatchPattern> {
+    map(rule! { #match_pattern_quantified+ }, |mut patterns| {
+        if patterns.len() == 1 {
+            patterns.remove(0)
+        } else {
+            MatchPattern::Concat(patterns)
+        }
+    })(i)
+}
+
+fn match_pattern_quantified(i: Input) -> IResult<MatchPattern> {
+    let symbol = map(ident, MatchPattern::Symbol);
+    let start = value(MatchPattern::Start, rule! { "^" });
+    let end = value(MatchPattern::End, rule! { "$" });
+    let group = map(rule! { "(" ~ ^#match_pattern ~ ^")" }, |(_, pattern, _)| {
+        MatchPattern::Group(Box::new(pattern))
+    });
+    let quantifier = alt((
+        value((0, None), rule! { "*" }),
+        value((1, None), rule! { "+" }),
+        value((0, Some(1)), rule! { "?" }),
+        map(rule! { "{" ~ #literal_u64 ~ "}" }, |(_, n, _)| (n, Some(n))),
+        map(
+            rule! { "{" ~ #literal_u64? ~ "," ~ #literal_u64? ~ ^"}" },
+            |(_, min, _, max, _)| (min.unwrap_or(0), max),
+        ),
+    ));
+    map(
+        rule! {
+            ( #symbol | #start | #end | #group ) ~ ( #quantifier ~ "?"? )?
+        },
+        |(pattern, opt_quantifier)| match opt_quantifier {
+            Some(((min, max), reluctant)) => MatchPattern::Repetition {
+                pattern: Box::new(pattern),
+                min,
+                max,
+                greedy: reluctant.is_none(),
+            },
+            None => pattern,
+        },
+    )(i)
+}
+
 // PIVOT(expr FOR col IN (ident, ... | subquery))
 fn pivot(i: Input) -> IResult<Pivot> {
     map(
@@ -977,6 +1137,8 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
         let affix = match &input.elem {
             TableReferenceElement::Join { .. } => Affix::Infix(Precedence(10), Associativity::Left),
             TableReferenceElement::JoinCondition(..) => Affix::Postfix(Precedence(5)),
+            TableReferenceElement::MatchCondition(..) => Affix::Postfix(Precedence(5)),
+            TableReferenceElement::MatchRecognize { .. } => Affix::Postfix(Precedence(20)),
             _ => Affix::Nilfix,
         };
         Ok(affix)
@@ -1088,6 +1250,7 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
                     join: Join {
                         op,
                         condition,
+                        match_condition: None,
                         left: Box::new(lhs),
                         right: Box::new(rhs),
                     },
@@ -1126,6 +1289,33 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
                 },
                 _ => Err("join condition must apply to a join"),
             },
+            TableReferenceElement::MatchCondition(expr) => match &mut lhs {
+                TableReference::Join {
+                    join:
+                        Join {
+                            op: JoinOperator::Asof | JoinOperator::LeftAsof,
+                            match_condition,
+                            ..
+                        },
+                    ..
+                } => match match_condition {
+                    None => {
+                        *match_condition = Some(Box::new(expr));
+                        Ok(lhs)
+                    }
+                    Some(_) => Err("MATCH_CONDITION already set"),
+                },
+                _ => Err("MATCH_CONDITION must apply to an ASOF join"),
+            },
+            TableReferenceElement::MatchRecognize {
+                match_recognize,
+                alias,
+            } => Ok(TableReference::MatchRecognize {
+                span: transform_span(op.span.tokens),
+                table: Box::new(lhs),
+                match_recognize,
+                alias,
+            }),
             _ => unreachable!(),
         }
     }
@@ -1210,13 +1400,22 @@ pub fn window_frame_between(i: Input) -> IResult<(WindowFrameBound, WindowFrameB
     ))(i)
 }
 
+pub fn window_frame_exclusion(i: Input) -> IResult<WindowFrameExclusion> {
+    alt((
+        value(WindowFrameExclusion::CurrentRow, rule! { CURRENT ~ ^ROW }),
+        value(WindowFrameExclusion::Group, rule! { GROUP }),
+        value(WindowFrameExclusion::Ties, rule! { TIES }),
+        value(WindowFrameExclusion::NoOthers, rule! { NO ~ ^OTHERS }),
+    ))(i)
+}
+
 pub fn window_spec(i: Input) -> IResult<WindowSpec> {
     map(
         rule! {
             #ident?
             ~ ( PARTITION ~ ^BY ~ ^#comma_separated_list1(subexpr(0)) )?
             ~ ( ORDER ~ ^BY ~ ^#comma_separated_list1(order_by_expr) )?
-            ~ ( (ROWS | RANGE) ~ ^#window_frame_between )?
+            ~ ( (ROWS | RANGE | GROUPS) ~ ^#window_frame_between ~ ( EXCLUDE ~ ^#window_frame_exclusion )? )?
         },
         |(existing_window_name, opt_partition, opt_order, between)| WindowSpec {
             existing_window_name,
@@ -1226,6 +1425,7 @@ pub fn window_spec(i: Input) -> IResult<WindowSpec> {
                 let unit = match x.0.kind {
                     ROWS => WindowFrameUnits::Rows,
                     RANGE => WindowFrameUnits::Range,
+                    GROUPS => WindowFrameUnits::Groups,
                     _ => unreachable!(),
                 };
                 let bw = x.1;
@@ -1233,6 +1433,10 @@ pub fn window_spec(i: Input) -> IResult<WindowSpec> {
                     units: unit,
                     start_bound: bw.0,
                     end_bound: bw.1,
+                    exclusion: x
+                        .2
+                        .map(|(_, exclusion)| exclusion)
+                        .unwrap_or(WindowFrameExclusion::NoOthers),
                 }
             }),
         },
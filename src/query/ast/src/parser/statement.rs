@@ -18,6 +18,7 @@ use std::time::Duration;
 use educe::Educe;
 use nom::branch::alt;
 use nom::combinator::consumed;
+use nom::combinator::fail;
 use nom::combinator::map;
 use nom::combinator::not;
 use nom::combinator::value;
@@ -2234,6 +2235,16 @@ pub fn statement_body(i: Input) -> IResult<Statement> {
     let begin = value(Statement::Begin, rule! { BEGIN ~ TRANSACTION? });
     let commit = value(Statement::Commit, rule! { COMMIT });
     let abort = value(Statement::Abort, rule! { ABORT | ROLLBACK });
+    let savepoint = map(rule! { SAVEPOINT ~ #ident }, |(_, name)| {
+        Statement::Savepoint(name)
+    });
+    let rollback_to_savepoint = map(
+        rule! { ROLLBACK ~ TO ~ SAVEPOINT? ~ #ident },
+        |(_, _, _, name)| Statement::RollbackToSavepoint(name),
+    );
+    let release_savepoint = map(rule! { RELEASE ~ SAVEPOINT? ~ #ident }, |(_, _, name)| {
+        Statement::ReleaseSavepoint(name)
+    });
 
     let execute_immediate = map(
         rule! {
@@ -2242,6 +2253,44 @@ pub fn statement_body(i: Input) -> IResult<Statement> {
         |(_, _, script)| Statement::ExecuteImmediate(ExecuteImmediateStmt { script }),
     );
 
+    let prepare = map(
+        rule! {
+            PREPARE ~ #ident ~ AS ~ #statement_body
+        },
+        |(_, name, _, statement)| {
+            Statement::Prepare(PrepareStmt {
+                name,
+                statement: Box::new(statement),
+            })
+        },
+    );
+
+    let execute_prepared = map(
+        rule! {
+            EXECUTE ~ #ident ~ ( USING ~ ^#comma_separated_list1(expr) )?
+        },
+        |(_, name, opt_params)| {
+            Statement::ExecutePrepared(ExecutePreparedStmt {
+                name,
+                params: opt_params.map(|(_, params)| params).unwrap_or_default(),
+            })
+        },
+    );
+
+    let deallocate_all = map(
+        rule! {
+            DEALLOCATE ~ PREPARE? ~ ALL
+        },
+        |_| Statement::Deallocate(DeallocateStmt { name: None }),
+    );
+
+    let deallocate = map(
+        rule! {
+            DEALLOCATE ~ PREPARE? ~ #ident
+        },
+        |(_, _, name)| Statement::Deallocate(DeallocateStmt { name: Some(name) }),
+    );
+
     let system_action = map(
         rule! {
             SYSTEM ~ #action
@@ -2493,14 +2542,17 @@ pub fn statement_body(i: Input) -> IResult<Statement> {
         rule!(
             #conditional_multi_table_insert() : "`INSERT [OVERWRITE] {FIRST|ALL} { WHEN <condition> THEN intoClause [ ... ] } [ ... ] [ ELSE intoClause ] <subquery>`"
             | #unconditional_multi_table_insert() : "`INSERT [OVERWRITE] ALL intoClause [ ... ] <subquery>`"
-            | #insert_stmt(false) : "`INSERT INTO [TABLE] <table> [(<column>, ...)] (FORMAT <format> | VALUES <values> | <query>)`"
+            | #insert_stmt(false) : "`INSERT INTO [TABLE] <table> [PARTITION (<column> [= <value>], ...)] [(<column>, ...)] [ON CONFLICT [(<column>, ...)] DO NOTHING | DO UPDATE] (FORMAT <format> | VALUES <values> | <query>)`"
             | #replace_stmt(false) : "`REPLACE INTO [TABLE] <table> [(<column>, ...)] (FORMAT <format> | VALUES <values> | <query>)`"
             | #merge : "`MERGE INTO <target_table> USING <source> ON <join_expr> { matchedClause | notMatchedClause } [ ... ]`"
             | #delete : "`DELETE FROM <table> [WHERE ...]`"
             | #update : "`UPDATE <table> SET <column> = <expr> [, <column> = <expr> , ... ] [WHERE ...]`"
             | #begin
             | #commit
+            | #rollback_to_savepoint : "`ROLLBACK TO [SAVEPOINT] <name>`"
             | #abort
+            | #savepoint : "`SAVEPOINT <name>`"
+            | #release_savepoint : "`RELEASE [SAVEPOINT] <name>`"
         ),
         rule!(
             #show_users : "`SHOW USERS`"
@@ -2654,6 +2706,10 @@ AS
             | #desc_connection: "`DESC | DESCRIBE CONNECTION  <connection_name>`"
             | #show_connections: "`SHOW CONNECTIONS`"
             | #execute_immediate : "`EXECUTE IMMEDIATE $$ <script> $$`"
+            | #prepare : "`PREPARE <name> AS <statement>`"
+            | #execute_prepared : "`EXECUTE <name> [USING <expr>, ...]`"
+            | #deallocate_all : "`DEALLOCATE [PREPARE] ALL`"
+            | #deallocate : "`DEALLOCATE [PREPARE] <name>`"
             | #create_procedure : "`CREATE [ OR REPLACE ] PROCEDURE <procedure_name>() RETURNS { <result_data_type> [ NOT NULL ] | TABLE(<var_name> <data_type>, ...)} LANGUAGE SQL [ COMMENT = '<string_literal>' ] AS <procedure_definition>`"
             | #drop_procedure : "`DROP PROCEDURE <procedure_name>()`"
             | #show_procedures : "`SHOW PROCEDURES [<show_options>]()`"
@@ -2700,7 +2756,9 @@ pub fn insert_stmt(allow_raw: bool) -> impl FnMut(Input) -> IResult<Statement> {
             rule! {
                 #with? ~ INSERT ~ #hint? ~ ( INTO | OVERWRITE ) ~ TABLE?
                 ~ #dot_separated_idents_1_to_3
+                ~ ( PARTITION ~ "(" ~ #comma_separated_list1(insert_partition_value) ~ ")" )?
                 ~ ( "(" ~ #comma_separated_list1(ident) ~ ")" )?
+                ~ #insert_on_conflict?
                 ~ #insert_source_parser
             },
             |(
@@ -2710,7 +2768,9 @@ pub fn insert_stmt(allow_raw: bool) -> impl FnMut(Input) -> IResult<Statement> {
                 overwrite,
                 _,
                 (catalog, database, table),
+                opt_partition,
                 opt_columns,
+                on_conflict,
                 source,
             )| {
                 Statement::Insert(InsertStmt {
@@ -2719,17 +2779,51 @@ pub fn insert_stmt(allow_raw: bool) -> impl FnMut(Input) -> IResult<Statement> {
                     catalog,
                     database,
                     table,
+                    partition: opt_partition
+                        .map(|(_, _, partition, _)| partition)
+                        .unwrap_or_default(),
                     columns: opt_columns
                         .map(|(_, columns, _)| columns)
                         .unwrap_or_default(),
                     source,
                     overwrite: overwrite.kind == OVERWRITE,
+                    on_conflict,
                 })
             },
         )(i)
     }
 }
 
+pub fn insert_on_conflict(i: Input) -> IResult<InsertOnConflict> {
+    let action = alt((
+        value(OnConflictAction::DoNothing, rule! { DO ~ NOTHING }),
+        value(OnConflictAction::DoUpdate, rule! { DO ~ UPDATE }),
+    ));
+    map(
+        rule! {
+            ON ~ CONFLICT ~ ( "(" ~ ^#comma_separated_list1(ident) ~ ^")" )? ~ ^#action
+        },
+        |(_, _, opt_columns, action)| InsertOnConflict {
+            columns: opt_columns
+                .map(|(_, columns, _)| columns)
+                .unwrap_or_default(),
+            action,
+        },
+    )(i)
+}
+
+pub fn insert_partition_value(i: Input) -> IResult<InsertPartitionValue> {
+    map(
+        rule! {
+            #ident ~ ( "=" ~ #expr )?
+        },
+        |(column, opt_value)| InsertPartitionValue {
+            column,
+            value: opt_value.map(|(_, value)| value),
+        },
+    )(i)
+}
+
 pub fn conditional_multi_table_insert() -> impl FnMut(Input) -> IResult<Statement> {
     move |i| {
         map(
@@ -3067,12 +3161,28 @@ pub fn rest_str(i: Input) -> IResult<(String, usize)> {
 }
 
 pub fn column_def(i: Input) -> IResult<ColumnDefinition> {
+    map(|i| column_def_inner(i, false), |(def, _)| def)(i)
+}
+
+/// A column definition of `CREATE TABLE`, which may declare the constraints of the column.
+pub fn column_def_with_constraints(i: Input) -> IResult<(ColumnDefinition, Vec<TableConstraint>)> {
+    column_def_inner(i, true)
+}
+
+fn column_def_inner(
+    i: Input,
+    allow_constraint: bool,
+) -> IResult<(ColumnDefinition, Vec<TableConstraint>)> {
     #[derive(Clone)]
     enum ColumnConstraint {
         Nullable(bool),
         DefaultExpr(Box<Expr>),
         VirtualExpr(Box<Expr>),
         StoredExpr(Box<Expr>),
+        PrimaryKey,
+        Unique,
+        Check(Box<Expr>),
+        References(Option<Identifier>, Identifier, Vec<Identifier>),
     }
 
     let nullable = alt((
@@ -3100,6 +3210,21 @@ pub fn column_def(i: Input) -> IResult<ColumnDefinition> {
         ),
     ));
 
+    let constraint = |i: Input| {
+        if allow_constraint {
+            alt((
+                value(ColumnConstraint::PrimaryKey, rule! { PRIMARY ~ ^KEY }),
+                value(ColumnConstraint::Unique, rule! { UNIQUE }),
+                map(check_constraint, ColumnConstraint::Check),
+                map(references, |(database, table, columns)| {
+                    ColumnConstraint::References(database, table, columns)
+                }),
+            ))(i)
+        } else {
+            fail(i)
+        }
+    };
+
     let comment = map(
         rule! {
             COMMENT ~ #literal_string
@@ -3111,7 +3236,7 @@ pub fn column_def(i: Input) -> IResult<ColumnDefinition> {
         rule! {
             #ident
             ~ #type_name
-            ~ ( #nullable | #expr )*
+            ~ ( #nullable | #expr | #constraint )*
             ~ ( #comment )?
             : "`<column name> <type> [DEFAULT <expr>] [AS (<expr>) VIRTUAL] [AS (<expr>) STORED] [COMMENT '<comment>']`"
         },
@@ -3126,6 +3251,7 @@ pub fn column_def(i: Input) -> IResult<ColumnDefinition> {
         },
     )(i)?;
 
+    let mut column_constraints = vec![];
     for constraint in constraints {
         match constraint {
             ColumnConstraint::Nullable(nullable) => {
@@ -3152,10 +3278,27 @@ pub fn column_def(i: Input) -> IResult<ColumnDefinition> {
             ColumnConstraint::StoredExpr(stored_expr) => {
                 def.expr = Some(ColumnExpr::Stored(stored_expr))
             }
+            ColumnConstraint::PrimaryKey => column_constraints.push(TableConstraint::PrimaryKey {
+                columns: vec![def.name.clone()],
+            }),
+            ColumnConstraint::Unique => column_constraints.push(TableConstraint::Unique {
+                columns: vec![def.name.clone()],
+            }),
+            ColumnConstraint::Check(expr) => {
+                column_constraints.push(TableConstraint::Check { expr })
+            }
+            ColumnConstraint::References(ref_database, ref_table, ref_columns) => {
+                column_constraints.push(TableConstraint::ForeignKey {
+                    columns: vec![def.name.clone()],
+                    ref_database,
+                    ref_table,
+                    ref_columns,
+                })
+            }
         }
     }
 
-    Ok((i, def))
+    Ok((i, (def, column_constraints)))
 }
 
 pub fn inverted_index_def(i: Input) -> IResult<InvertedIndexDefinition> {
@@ -3178,6 +3321,78 @@ pub fn inverted_index_def(i: Input) -> IResult<InvertedIndexDefinition> {
     )(i)
 }
 
+pub fn table_constraint(i: Input) -> IResult<TableConstraint> {
+    alt((
+        map(
+            rule! {
+                PRIMARY ~ KEY ~ ^"(" ~ ^#comma_separated_list1(ident) ~ ^")"
+            },
+            |(_, _, _, columns, _)| TableConstraint::PrimaryKey { columns },
+        ),
+        map(
+            rule! {
+                UNIQUE ~ "(" ~ ^#comma_separated_list1(ident) ~ ^")"
+            },
+            |(_, _, columns, _)| TableConstraint::Unique { columns },
+        ),
+        map(check_constraint, |expr| TableConstraint::Check { expr }),
+        map(
+            rule! {
+                FOREIGN ~ KEY ~ ^"(" ~ ^#comma_separated_list1(ident) ~ ^")" ~ ^#references
+            },
+            |(_, _, _, columns, _, (ref_database, ref_table, ref_columns))| {
+                TableConstraint::ForeignKey {
+                    columns,
+                    ref_database,
+                    ref_table,
+                    ref_columns,
+                }
+            },
+        ),
+    ))(i)
+}
+
+fn check_constraint(i: Input) -> IResult<Box<Expr>> {
+    map(
+        rule! {
+            CHECK ~ "(" ~ ^#expr ~ ^")"
+        },
+        |(_, _, expr, _)| Box::new(expr),
+    )(i)
+}
+
+/// The referenced table and columns of a foreign key, `REFERENCES [<database>.]<table> [(<column>, ...)]`.
+fn references(i: Input) -> IResult<(Option<Identifier>, Identifier, Vec<Identifier>)> {
+    map(
+        rule! {
+            REFERENCES ~ ^#dot_separated_idents_1_to_2
+            ~ ( "(" ~ ^#comma_separated_list1(ident) ~ ^")" )?
+        },
+        |(_, (ref_database, ref_table), opt_ref_columns)| {
+            (
+                ref_database,
+                ref_table,
+                opt_ref_columns
+                    .map(|(_, columns, _)| columns)
+                    .unwrap_or_default(),
+            )
+        },
+    )(i)
+}
+
+pub fn constraint_def(i: Input) -> IResult<ConstraintDefinition> {
+    map(
+        rule! {
+            ( CONSTRAINT ~ #ident )? ~ #table_constraint
+            : "`[CONSTRAINT <name>] PRIMARY KEY (<column>, ...) | UNIQUE (<column>, ...) | CHECK (<expr>) | FOREIGN KEY (<column>, ...) REFERENCES <table> [(<column>, ...)]`"
+        },
+        |(opt_name, constraint)| ConstraintDefinition {
+            name: opt_name.map(|(_, name)| name),
+            constraint,
+        },
+    )(i)
+}
+
 pub fn create_def(i: Input) -> IResult<CreateDefinition> {
     alt((
         map(rule! { #column_def }, CreateDefinition::Column),
@@ -3185,6 +3400,7 @@ pub fn create_def(i: Input) -> IResult<CreateDefinition> {
             rule! { #inverted_index_def },
             CreateDefinition::InvertedIndex,
         ),
+        map(rule! { #constraint_def }, CreateDefinition::Constraint),
     ))(i)
 }
 
@@ -3562,14 +3778,29 @@ pub fn grant_option(i: Input) -> IResult<PrincipalIdentity> {
 }
 
 pub fn create_table_source(i: Input) -> IResult<CreateTableSource> {
+    // The constraints declared in column definitions are turned into table constraints.
+    let create_def_with_constraints = alt((
+        map(
+            rule! { #column_def_with_constraints },
+            |(column, constraints)| (CreateDefinition::Column(column), constraints),
+        ),
+        map(rule! { #inverted_index_def }, |inverted_index| {
+            (CreateDefinition::InvertedIndex(inverted_index), vec![])
+        }),
+        map(rule! { #constraint_def }, |constraint| {
+            (CreateDefinition::Constraint(constraint), vec![])
+        }),
+    ));
     let columns = map(
         rule! {
-            "(" ~ ^#comma_separated_list1(create_def) ~ ^")"
+            "(" ~ ^#comma_separated_list1(create_def_with_constraints) ~ ^")"
         },
         |(_, create_defs, _)| {
             let mut columns = Vec::with_capacity(create_defs.len());
             let mut inverted_indexes = Vec::new();
-            for create_def in create_defs {
+            let mut column_constraints = Vec::new();
+            let mut constraints = Vec::new();
+            for (create_def, column_constraint) in create_defs {
                 match create_def {
                     CreateDefinition::Column(column) => {
                         columns.push(column);
@@ -3577,14 +3808,29 @@ pub fn create_table_source(i: Input) -> IResult<CreateTableSource> {
                     CreateDefinition::InvertedIndex(inverted_index) => {
                         inverted_indexes.push(inverted_index);
                     }
+                    CreateDefinition::Constraint(constraint) => {
+                        constraints.push(constraint);
+                    }
                 }
+                column_constraints.extend(column_constraint.into_iter().map(|constraint| {
+                    ConstraintDefinition {
+                        name: None,
+                        constraint,
+                    }
+                }));
             }
             let opt_inverted_indexes = if !inverted_indexes.is_empty() {
                 Some(inverted_indexes)
             } else {
                 None
             };
-            CreateTableSource::Columns(columns, opt_inverted_indexes)
+            column_constraints.extend(constraints);
+            let opt_constraints = if !column_constraints.is_empty() {
+                Some(column_constraints)
+            } else {
+                None
+            };
+            CreateTableSource::Columns(columns, opt_inverted_indexes, opt_constraints)
         },
     );
     let like = map(
@@ -3774,6 +4020,18 @@ pub fn alter_table_action(i: Input) -> IResult<AlterTableAction> {
         },
         |(_, _, column)| AlterTableAction::DropColumn { column },
     );
+    let add_constraint = map(
+        rule! {
+            ADD ~ #constraint_def
+        },
+        |(_, constraint)| AlterTableAction::AddConstraint { constraint },
+    );
+    let drop_constraint = map(
+        rule! {
+            DROP ~ CONSTRAINT ~ ^#ident
+        },
+        |(_, _, name)| AlterTableAction::DropConstraint { name },
+    );
     let alter_table_cluster_key = map(
         rule! {
             CLUSTER ~ ^BY ~ ( #cluster_type )? ~ ^"(" ~ ^#comma_separated_list1(expr) ~ ^")"
@@ -3831,6 +4089,8 @@ pub fn alter_table_action(i: Input) -> IResult<AlterTableAction> {
         | #rename_table
         | #rename_column
         | #modify_table_comment
+        | #add_constraint
+        | #drop_constraint
         | #add_column
         | #drop_column
         | #modify_column
@@ -4578,6 +4838,15 @@ pub fn udaf_state_field(i: Input) -> IResult<UDAFStateField> {
     )(i)
 }
 
+pub fn udtf_return_column(i: Input) -> IResult<(Identifier, TypeName)> {
+    rule! {
+        #ident
+        ~ #type_name
+        : "`<column name> <type>`"
+    }
+    (i)
+}
+
 pub fn udf_script_or_address(i: Input) -> IResult<(String, bool)> {
     let script = map(
         rule! {
@@ -4599,6 +4868,25 @@ pub fn udf_script_or_address(i: Input) -> IResult<(String, bool)> {
     )(i)
 }
 
+pub fn udf_script_options(i: Input) -> IResult<(Vec<String>, Vec<String>)> {
+    map(
+        rule! {
+            ( IMPORTS ~ ^"=" ~ ^"(" ~ ^#comma_separated_list0(literal_string) ~ ^")" )?
+            ~ ( PACKAGES ~ ^"=" ~ ^"(" ~ ^#comma_separated_list0(literal_string) ~ ^")" )?
+        },
+        |(imports, packages)| {
+            (
+                imports
+                    .map(|(_, _, _, imports, _)| imports)
+                    .unwrap_or_default(),
+                packages
+                    .map(|(_, _, _, packages, _)| packages)
+                    .unwrap_or_default(),
+            )
+        },
+    )(i)
+}
+
 pub fn udf_definition(i: Input) -> IResult<UDFDefinition> {
     let lambda_udf = map(
         rule! {
@@ -4611,17 +4899,31 @@ pub fn udf_definition(i: Input) -> IResult<UDFDefinition> {
         },
     );
 
-    let udf = map(
+    let udf = map_res(
         rule! {
             "(" ~ #comma_separated_list0(type_name) ~ ")"
             ~ RETURNS ~ #type_name
             ~ LANGUAGE ~ #ident
+            ~ #udf_script_options
             ~ HANDLER ~ ^"=" ~ ^#literal_string
             ~ #udf_script_or_address
         },
-        |(_, arg_types, _, _, return_type, _, language, _, _, handler, address_or_code)| {
+        |(
+            _,
+            arg_types,
+            _,
+            _,
+            return_type,
+            _,
+            language,
+            (imports, packages),
+            _,
+            _,
+            handler,
+            address_or_code,
+        )| {
             if address_or_code.1 {
-                UDFDefinition::UDFScript {
+                Ok(UDFDefinition::UDFScript {
                     arg_types,
                     return_type,
                     code: address_or_code.0,
@@ -4630,30 +4932,51 @@ pub fn udf_definition(i: Input) -> IResult<UDFDefinition> {
                     // TODO inject runtime_version by user
                     // Now we use fixed runtime version
                     runtime_version: "".to_string(),
-                }
-            } else {
-                UDFDefinition::UDFServer {
+                    imports,
+                    packages,
+                })
+            } else if imports.is_empty() && packages.is_empty() {
+                Ok(UDFDefinition::UDFServer {
                     arg_types,
                     return_type,
                     address: address_or_code.0,
                     handler,
                     language: language.to_string(),
-                }
+                })
+            } else {
+                Err(nom::Err::Failure(ErrorKind::Other(
+                    "IMPORTS and PACKAGES are only supported by script UDFs",
+                )))
             }
         },
     );
 
-    let udaf = map(
+    let udaf = map_res(
         rule! {
             "(" ~ #comma_separated_list0(type_name) ~ ")"
             ~ STATE ~ "{" ~ #comma_separated_list0(udaf_state_field) ~ "}"
             ~ RETURNS ~ #type_name
             ~ LANGUAGE ~ #ident
+            ~ #udf_script_options
             ~ #udf_script_or_address
         },
-        |(_, arg_types, _, _, _, state_types, _, _, return_type, _, language, address_or_code)| {
+        |(
+            _,
+            arg_types,
+            _,
+            _,
+            _,
+            state_types,
+            _,
+            _,
+            return_type,
+            _,
+            language,
+            (imports, packages),
+            address_or_code,
+        )| {
             if address_or_code.1 {
-                UDFDefinition::UDAFScript {
+                Ok(UDFDefinition::UDAFScript {
                     arg_types,
                     state_fields: state_types,
                     return_type,
@@ -4662,23 +4985,85 @@ pub fn udf_definition(i: Input) -> IResult<UDFDefinition> {
                     // TODO inject runtime_version by user
                     // Now we use fixed runtime version
                     runtime_version: "".to_string(),
-                }
-            } else {
-                UDFDefinition::UDAFServer {
+                    imports,
+                    packages,
+                })
+            } else if imports.is_empty() && packages.is_empty() {
+                Ok(UDFDefinition::UDAFServer {
                     arg_types,
                     state_fields: state_types,
                     return_type,
                     address: address_or_code.0,
                     language: language.to_string(),
-                }
+                })
+            } else {
+                Err(nom::Err::Failure(ErrorKind::Other(
+                    "IMPORTS and PACKAGES are only supported by script UDFs",
+                )))
+            }
+        },
+    );
+
+    let udtf = map_res(
+        rule! {
+            "(" ~ #comma_separated_list0(type_name) ~ ")"
+            ~ RETURNS ~ TABLE ~ ^"(" ~ ^#comma_separated_list1(udtf_return_column) ~ ^")"
+            ~ LANGUAGE ~ #ident
+            ~ #udf_script_options
+            ~ HANDLER ~ ^"=" ~ ^#literal_string
+            ~ #udf_script_or_address
+        },
+        |(
+            _,
+            arg_types,
+            _,
+            _,
+            _,
+            _,
+            return_columns,
+            _,
+            _,
+            language,
+            (imports, packages),
+            _,
+            _,
+            handler,
+            address_or_code,
+        )| {
+            if address_or_code.1 {
+                Ok(UDFDefinition::UDTFScript {
+                    arg_types,
+                    return_columns,
+                    code: address_or_code.0,
+                    handler,
+                    language: language.to_string(),
+                    // TODO inject runtime_version by user
+                    // Now we use fixed runtime version
+                    runtime_version: "".to_string(),
+                    imports,
+                    packages,
+                })
+            } else if imports.is_empty() && packages.is_empty() {
+                Ok(UDFDefinition::UDTFServer {
+                    arg_types,
+                    return_columns,
+                    address: address_or_code.0,
+                    handler,
+                    language: language.to_string(),
+                })
+            } else {
+                Err(nom::Err::Failure(ErrorKind::Other(
+                    "IMPORTS and PACKAGES are only supported by script UDFs",
+                )))
             }
         },
     );
 
     rule!(
         #lambda_udf: "AS (<parameter>, ...) -> <definition expr>"
-        | #udaf: "(<arg_type>, ...) STATE {<state_field>, ...} RETURNS <return_type> LANGUAGE <language> { ADDRESS=<udf_server_address> | AS <language_codes> } "
-        | #udf: "(<arg_type>, ...) RETURNS <return_type> LANGUAGE <language> HANDLER=<handler> { ADDRESS=<udf_server_address> | AS <language_codes> } "
+        | #udaf: "(<arg_type>, ...) STATE {<state_field>, ...} RETURNS <return_type> LANGUAGE <language> [IMPORTS = (<stage_file>, ...)] [PACKAGES = (<package>, ...)] { ADDRESS=<udf_server_address> | AS <language_codes> } "
+        | #udtf: "(<arg_type>, ...) RETURNS TABLE (<column> <type>, ...) LANGUAGE <language> [IMPORTS = (<stage_file>, ...)] [PACKAGES = (<package>, ...)] HANDLER=<handler> { ADDRESS=<udf_server_address> | AS <language_codes> } "
+        | #udf: "(<arg_type>, ...) RETURNS <return_type> LANGUAGE <language> [IMPORTS = (<stage_file>, ...)] [PACKAGES = (<package>, ...)] HANDLER=<handler> { ADDRESS=<udf_server_address> | AS <language_codes> } "
 
     )(i)
 }
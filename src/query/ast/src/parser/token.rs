@@ -375,6 +375,8 @@ pub enum TokenKind {
     AT,
     #[token("ASC", ignore(ascii_case))]
     ASC,
+    #[token("ASOF", ignore(ascii_case))]
+    ASOF,
     #[token("ANTI", ignore(ascii_case))]
     ANTI,
     #[token("ASYNC", ignore(ascii_case))]
@@ -465,8 +467,12 @@ pub enum TokenKind {
     COLUMNS,
     #[token("CHARACTER", ignore(ascii_case))]
     CHARACTER,
+    #[token("CHECK", ignore(ascii_case))]
+    CHECK,
     #[token("CONFLICT", ignore(ascii_case))]
     CONFLICT,
+    #[token("CONSTRAINT", ignore(ascii_case))]
+    CONSTRAINT,
     #[token("COMPRESSION", ignore(ascii_case))]
     COMPRESSION,
     #[token("COPY_OPTIONS", ignore(ascii_case))]
@@ -509,6 +515,8 @@ pub enum TokenKind {
     DATETIME,
     #[token("DAY", ignore(ascii_case))]
     DAY,
+    #[token("DEALLOCATE", ignore(ascii_case))]
+    DEALLOCATE,
     #[token("DECADE", ignore(ascii_case))]
     DECADE,
     #[token("DECIMAL", ignore(ascii_case))]
@@ -517,6 +525,8 @@ pub enum TokenKind {
     DECLARE,
     #[token("DEFAULT", ignore(ascii_case))]
     DEFAULT,
+    #[token("DEFINE", ignore(ascii_case))]
+    DEFINE,
     #[token("DEFLATE", ignore(ascii_case))]
     DEFLATE,
     #[token("DELETE", ignore(ascii_case))]
@@ -633,6 +643,8 @@ pub enum TokenKind {
     FLOAT64,
     #[token("FOR", ignore(ascii_case))]
     FOR,
+    #[token("FOREIGN", ignore(ascii_case))]
+    FOREIGN,
     #[token("FORCE", ignore(ascii_case))]
     FORCE,
     #[token("FORMAT", ignore(ascii_case))]
@@ -675,6 +687,8 @@ pub enum TokenKind {
     GRAPH,
     #[token("GROUP", ignore(ascii_case))]
     GROUP,
+    #[token("GROUPS", ignore(ascii_case))]
+    GROUPS,
     #[token("GZIP", ignore(ascii_case))]
     GZIP,
     #[token("HAVING", ignore(ascii_case))]
@@ -812,6 +826,8 @@ pub enum TokenKind {
     MEMO,
     #[token("MEMORY", ignore(ascii_case))]
     MEMORY,
+    #[token("MEASURES", ignore(ascii_case))]
+    MEASURES,
     #[token("METRICS", ignore(ascii_case))]
     METRICS,
     #[token("MICROSECONDS", ignore(ascii_case))]
@@ -830,6 +846,8 @@ pub enum TokenKind {
     MATERIALIZED,
     #[token("MUST_CHANGE_PASSWORD", ignore(ascii_case))]
     MUST_CHANGE_PASSWORD,
+    #[token("NEXT", ignore(ascii_case))]
+    NEXT,
     #[token("NEXT_DAY", ignore(ascii_case))]
     NEXT_DAY,
     #[token("NON_DISPLAY", ignore(ascii_case))]
@@ -842,6 +860,8 @@ pub enum TokenKind {
     DISABLED,
     #[token("NDJSON", ignore(ascii_case))]
     NDJSON,
+    #[token("NO", ignore(ascii_case))]
+    NO,
     #[token("NO_PASSWORD", ignore(ascii_case))]
     NO_PASSWORD,
     #[token("NONE", ignore(ascii_case))]
@@ -850,6 +870,8 @@ pub enum TokenKind {
     NOT,
     #[token("NOTENANTSETTING", ignore(ascii_case))]
     NOTENANTSETTING,
+    #[token("NOTHING", ignore(ascii_case))]
+    NOTHING,
     #[token("DEFAULT_ROLE", ignore(ascii_case))]
     DEFAULT_ROLE,
     #[token("NULL", ignore(ascii_case))]
@@ -864,6 +886,8 @@ pub enum TokenKind {
     OFFSET,
     #[token("ON", ignore(ascii_case))]
     ON,
+    #[token("ONE", ignore(ascii_case))]
+    ONE,
     #[token("ON_CREATE", ignore(ascii_case))]
     ON_CREATE,
     #[token("ON_SCHEDULE", ignore(ascii_case))]
@@ -878,6 +902,8 @@ pub enum TokenKind {
     ORC,
     #[token("ORDER", ignore(ascii_case))]
     ORDER,
+    #[token("OTHERS", ignore(ascii_case))]
+    OTHERS,
     #[token("OUTPUT_HEADER", ignore(ascii_case))]
     OUTPUT_HEADER,
     #[token("OUTER", ignore(ascii_case))]
@@ -892,6 +918,10 @@ pub enum TokenKind {
     PARTITION,
     #[token("PARQUET", ignore(ascii_case))]
     PARQUET,
+    #[token("PAST", ignore(ascii_case))]
+    PAST,
+    #[token("PER", ignore(ascii_case))]
+    PER,
     #[token("PASSWORD", ignore(ascii_case))]
     PASSWORD,
     #[token("PASSWORD_MIN_LENGTH", ignore(ascii_case))]
@@ -956,6 +986,8 @@ pub enum TokenKind {
     RECLUSTER,
     #[token("RECORD_DELIMITER", ignore(ascii_case))]
     RECORD_DELIMITER,
+    #[token("REFERENCES", ignore(ascii_case))]
+    REFERENCES,
     #[token("REFERENCE_USAGE", ignore(ascii_case))]
     REFERENCE_USAGE,
     #[token("REFRESH", ignore(ascii_case))]
@@ -974,8 +1006,14 @@ pub enum TokenKind {
     SAMPLE,
     #[token("MERGE", ignore(ascii_case))]
     MERGE,
+    #[token("MATCH", ignore(ascii_case))]
+    MATCH,
     #[token("MATCHED", ignore(ascii_case))]
     MATCHED,
+    #[token("MATCH_CONDITION", ignore(ascii_case))]
+    MATCH_CONDITION,
+    #[token("MATCH_RECOGNIZE", ignore(ascii_case))]
+    MATCH_RECOGNIZE,
     #[token("MISSING_FIELD_AS", ignore(ascii_case))]
     MISSING_FIELD_AS,
     #[token("NULL_FIELD_AS", ignore(ascii_case))]
@@ -998,6 +1036,8 @@ pub enum TokenKind {
     PRECEDING,
     #[token("PRECISION", ignore(ascii_case))]
     PRECISION,
+    #[token("PREPARE", ignore(ascii_case))]
+    PREPARE,
     #[token("PRESIGN", ignore(ascii_case))]
     PRESIGN,
     #[token("PRIVILEGES", ignore(ascii_case))]
@@ -1084,6 +1124,8 @@ pub enum TokenKind {
     MAX_FILES,
     #[token("MONDAY", ignore(ascii_case))]
     MONDAY,
+    #[token("SKIP", ignore(ascii_case))]
+    SKIP,
     #[token("SKIP_HEADER", ignore(ascii_case))]
     SKIP_HEADER,
     #[token("SMALLINT", ignore(ascii_case))]
@@ -1150,6 +1192,8 @@ pub enum TokenKind {
     LONGTEXT,
     #[token("MEDIUMTEXT", ignore(ascii_case))]
     MEDIUMTEXT,
+    #[token("TIES", ignore(ascii_case))]
+    TIES,
     #[token("TINYTEXT", ignore(ascii_case))]
     TINYTEXT,
     #[token("TENANTSETTING", ignore(ascii_case))]
@@ -1200,6 +1244,8 @@ pub enum TokenKind {
     UNBOUNDED,
     #[token("UNION", ignore(ascii_case))]
     UNION,
+    #[token("UNIQUE", ignore(ascii_case))]
+    UNIQUE,
     #[token("UINT16", ignore(ascii_case))]
     UINT16,
     #[token("UINT32", ignore(ascii_case))]
@@ -1240,6 +1286,8 @@ pub enum TokenKind {
     VARIABLE,
     #[token("VERBOSE", ignore(ascii_case))]
     VERBOSE,
+    #[token("VERSION", ignore(ascii_case))]
+    VERSION,
     #[token("GRAPHICAL", ignore(ascii_case))]
     GRAPHICAL,
     #[token("VIEW", ignore(ascii_case))]
@@ -1312,6 +1360,10 @@ pub enum TokenKind {
     LANGUAGE,
     #[token("STATE", ignore(ascii_case))]
     STATE,
+    #[token("IMPORTS", ignore(ascii_case))]
+    IMPORTS,
+    #[token("PACKAGES", ignore(ascii_case))]
+    PACKAGES,
     #[token("TASK", ignore(ascii_case))]
     TASK,
     #[token("TASKS", ignore(ascii_case))]
@@ -1366,6 +1418,10 @@ pub enum TokenKind {
     ABORT,
     #[token("ROLLBACK", ignore(ascii_case))]
     ROLLBACK,
+    #[token("SAVEPOINT", ignore(ascii_case))]
+    SAVEPOINT,
+    #[token("RELEASE", ignore(ascii_case))]
+    RELEASE,
     #[token("TEMPORARY", ignore(ascii_case))]
     TEMPORARY,
     #[token("TEMP", ignore(ascii_case))]
@@ -1654,6 +1710,7 @@ impl TokenKind {
             | TokenKind::FUNCTION
             | TokenKind::PROCEDURE
             | TokenKind::ASC
+            | TokenKind::ASOF
             | TokenKind::ANTI
             // | TokenKind::ASYMMETRIC
             // | TokenKind::AUTHORIZATION
@@ -1746,6 +1803,8 @@ impl TokenKind {
             // | TokenKind::ISNULL
             | TokenKind::LIMIT
             | TokenKind::FORMAT
+            | TokenKind::MATCH_CONDITION
+            | TokenKind::MATCH_RECOGNIZE
             // | TokenKind::NOTNULL
             | TokenKind::OFFSET
             | TokenKind::ON
@@ -670,6 +670,18 @@ fn test_statement() {
             AS
                 SELECT avg(a), d FROM db.t GROUP BY d
         "#,
+        r#"ALTER DYNAMIC TABLE db.MyDynamic REFRESH"#,
+        // transactions
+        r#"SAVEPOINT load_1"#,
+        r#"ROLLBACK TO SAVEPOINT load_1"#,
+        r#"RELEASE SAVEPOINT load_1"#,
+        // constraints
+        r#"CREATE TABLE t (id INT PRIMARY KEY, a INT UNIQUE, b INT, CONSTRAINT uk_ab UNIQUE (a, b))"#,
+        r#"ALTER TABLE t ADD CONSTRAINT t_pkey PRIMARY KEY (id)"#,
+        r#"ALTER TABLE t DROP CONSTRAINT t_pkey"#,
+        r#"ALTER TABLE t ADD CONSTRAINT ck CHECK (a > 0)"#,
+        r#"ALTER TABLE t ADD FOREIGN KEY (a) REFERENCES db.p (id)"#,
+        r#"INSERT INTO t (id, a) ON CONFLICT (id) DO NOTHING VALUES (1, 2)"#,
         // tasks
         r#"CREATE TASK IF NOT EXISTS MyTask1 WAREHOUSE = 'MyWarehouse' SCHEDULE = 15 MINUTE SUSPEND_TASK_AFTER_NUM_FAILURES = 3 ERROR_INTEGRATION = 'notification_name' COMMENT = 'This is test task 1' DATABASE = 'target', TIMEZONE = 'America/Los Angeles' AS SELECT * FROM MyTable1"#,
         r#"CREATE TASK IF NOT EXISTS MyTask1 WAREHOUSE = 'MyWarehouse' SCHEDULE = 15 SECOND SUSPEND_TASK_AFTER_NUM_FAILURES = 3 COMMENT = 'This is test task 1' AS SELECT * FROM MyTable1"#,
@@ -807,11 +819,22 @@ fn test_statement() {
             handler = 'addone_py'
             as '@data/abc/a.py';
         "#,
+        r#"
+            create or replace function addone(int)
+            returns int
+            language python
+            imports = ('@data/abc/helper.py')
+            packages = ('numpy', 'pandas')
+            handler = 'addone_py'
+            as '@data/abc/a.py';
+        "#,
         r#"DROP FUNCTION binary_reverse;"#,
         r#"DROP FUNCTION isnotempty;"#,
         r#"CREATE FUNCTION IF NOT EXISTS my_agg (INT) STATE { s STRING } RETURNS BOOLEAN LANGUAGE javascript ADDRESS = 'http://0.0.0.0:8815';"#,
         r#"CREATE FUNCTION IF NOT EXISTS my_agg (INT) STATE { s STRING, i INT NOT NULL } RETURNS BOOLEAN LANGUAGE javascript AS 'some code';"#,
         r#"ALTER FUNCTION my_agg (INT) STATE { s STRING } RETURNS BOOLEAN LANGUAGE javascript AS 'some code';"#,
+        r#"CREATE FUNCTION split_words (STRING) RETURNS TABLE (word STRING, pos INT) LANGUAGE python HANDLER = 'split_words' ADDRESS = 'http://0.0.0.0:8815';"#,
+        r#"CREATE FUNCTION split_words (STRING) RETURNS TABLE (word STRING, pos INT) LANGUAGE javascript HANDLER = 'splitWords' AS 'some code';"#,
         r#"
             EXECUTE IMMEDIATE
             $$
@@ -822,6 +845,9 @@ fn test_statement() {
             END;
             $$
         "#,
+        r#"EXECUTE p1 USING 1, 'a'"#,
+        r#"DEALLOCATE PREPARE p1"#,
+        r#"DEALLOCATE ALL"#,
         r#"
             with
             abc as (
@@ -1091,6 +1117,8 @@ fn test_query() {
         r#"select * from customer inner join orders on a = b limit 2 offset 3"#,
         r#"select * from customer natural full join orders"#,
         r#"select * from customer natural join orders left outer join detail using (id)"#,
+        r#"select * from trades asof left join quotes match_condition (trades.ts >= quotes.ts) on trades.sym = quotes.sym"#,
+        r#"select * from t match_recognize(partition by k order by ts measures last(v) as lv all rows per match after match skip to last b pattern (^ a b+? $) define b as v > prev(v)) as m"#,
         r#"with t2(tt) as (select a from t) select t2.tt from t2  where t2.tt > 1"#,
         r#"with t2(tt) as materialized (select a from t) select t2.tt from t2  where t2.tt > 1"#,
         r#"with t2 as (select a from t) select t2.a from t2  where t2.a > 1"#,
@@ -1268,6 +1296,7 @@ fn test_expr() {
         r#"COUNT() OVER (ORDER BY hire_date ROWS UNBOUNDED PRECEDING)"#,
         r#"COUNT() OVER (ORDER BY hire_date ROWS CURRENT ROW)"#,
         r#"COUNT() OVER (ORDER BY hire_date ROWS 3 PRECEDING)"#,
+        r#"COUNT() OVER (ORDER BY hire_date GROUPS BETWEEN 1 PRECEDING AND 1 FOLLOWING EXCLUDE TIES)"#,
         r#"QUANTILE_CONT(0.5)(salary) OVER (PARTITION BY department ORDER BY hire_date)"#,
         r#"ARRAY_APPLY([1,2,3], x -> x + 1)"#,
         r#"ARRAY_FILTER(col, y -> y % 2 = 0)"#,
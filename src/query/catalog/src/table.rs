@@ -123,6 +123,12 @@ pub trait Table: Sync + Send {
         false
     }
 
+    /// whether the files loaded by COPY are recorded in the commit of the insertion,
+    /// COPY without FORCE can not skip the loaded files otherwise.
+    fn support_copied_files(&self) -> bool {
+        true
+    }
+
     /// whether table has the exact number of total rows
     fn has_exact_total_row_count(&self) -> bool {
         false
@@ -453,10 +459,37 @@ pub trait Table: Sync + Send {
         )))
     }
 
+    /// Rebase the changes made to the table in a transaction onto the latest version of the table,
+    /// returns the meta to commit instead. `self` is the table changed by the transaction and
+    /// `base` is the version of the table the transaction started from.
+    ///
+    /// Fails with `UnresolvableConflict` if the changes conflict with the changes committed since `base`.
+    #[async_backtrace::framed]
+    async fn rebase_txn_mutation(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        base: &TableInfo,
+        latest: &TableInfo,
+    ) -> Result<TableMeta> {
+        let (_, _, _) = (ctx, base, latest);
+
+        Err(ErrorCode::UnresolvableConflict(format!(
+            "The table '{}' is changed by another transaction, and its changes can not be rebased. Table engine: '{}'.",
+            self.name(),
+            self.get_table_info().engine(),
+        )))
+    }
+
     fn is_stage_table(&self) -> bool {
         false
     }
 
+    /// The columns of a hive style partitioned table, which can be specified in the
+    /// `PARTITION` clause of `INSERT`.
+    fn partition_columns(&self) -> Vec<String> {
+        vec![]
+    }
+
     fn result_can_be_cached(&self) -> bool {
         false
     }
@@ -22,6 +22,7 @@ use std::time::Duration;
 use std::time::SystemTime;
 
 use dashmap::DashMap;
+use databend_common_ast::ast::Statement;
 use databend_common_base::base::Progress;
 use databend_common_base::base::ProgressValues;
 use databend_common_exception::ErrorCode;
@@ -364,10 +365,16 @@ pub trait TableContext: Send + Sync {
     fn get_variable(&self, key: &str) -> Option<Scalar>;
     fn get_all_variables(&self) -> HashMap<String, Scalar>;
 
+    fn set_prepared_statement(&self, name: String, statement: Statement);
+    fn unset_prepared_statement(&self, name: &str) -> bool;
+    fn get_prepared_statement(&self, name: &str) -> Option<Statement>;
+    fn clear_prepared_statements(&self);
+
     async fn load_datalake_schema(
         &self,
         _kind: &str,
         _sp: &StorageParams,
+        _options: &BTreeMap<String, String>,
     ) -> Result<(TableSchema, String)> {
         unimplemented!()
     }
@@ -1422,6 +1422,21 @@ pub struct QueryConfig {
     #[clap(long, value_name = "VALUE", default_value_t)]
     pub mysql_tls_server_key: String,
 
+    #[clap(long, value_name = "VALUE", default_value = "127.0.0.1")]
+    pub postgres_handler_host: String,
+
+    #[clap(long, value_name = "VALUE", default_value = "5433")]
+    pub postgres_handler_port: u16,
+
+    #[clap(long, value_name = "VALUE", default_value = "120")]
+    pub postgres_handler_tcp_keepalive_timeout_secs: u64,
+
+    #[clap(long, value_name = "VALUE", default_value_t)]
+    pub postgres_tls_server_cert: String,
+
+    #[clap(long, value_name = "VALUE", default_value_t)]
+    pub postgres_tls_server_key: String,
+
     #[clap(long, value_name = "VALUE", default_value = "256")]
     pub max_active_sessions: u64,
 
@@ -1723,6 +1738,10 @@ pub struct QueryConfig {
     #[clap(long, value_name = "VALUE", default_value = "50")]
     pub max_cached_queries_profiles: usize,
 
+    /// How often the stages of the auto-ingest pipes are polled for new files, in seconds.
+    #[clap(long, value_name = "VALUE", default_value = "60")]
+    pub pipe_poll_interval_secs: u64,
+
     /// A list of network that not to be checked by network policy.
     #[clap(long, value_name = "VALUE")]
     pub network_policy_whitelist: Vec<String>,
@@ -1756,6 +1775,12 @@ impl TryInto<InnerQueryConfig> for QueryConfig {
             mysql_handler_tcp_keepalive_timeout_secs: self.mysql_handler_tcp_keepalive_timeout_secs,
             mysql_tls_server_cert: self.mysql_tls_server_cert,
             mysql_tls_server_key: self.mysql_tls_server_key,
+            postgres_handler_host: self.postgres_handler_host,
+            postgres_handler_port: self.postgres_handler_port,
+            postgres_handler_tcp_keepalive_timeout_secs: self
+                .postgres_handler_tcp_keepalive_timeout_secs,
+            postgres_tls_server_cert: self.postgres_tls_server_cert,
+            postgres_tls_server_key: self.postgres_tls_server_key,
             max_active_sessions: self.max_active_sessions,
             max_running_queries: self.max_running_queries,
             max_server_memory_usage: self.max_server_memory_usage,
@@ -1824,6 +1849,7 @@ impl TryInto<InnerQueryConfig> for QueryConfig {
             cloud_control_grpc_server_address: self.cloud_control_grpc_server_address,
             cloud_control_grpc_timeout: self.cloud_control_grpc_timeout,
             max_cached_queries_profiles: self.max_cached_queries_profiles,
+            pipe_poll_interval_secs: self.pipe_poll_interval_secs,
             network_policy_whitelist: self.network_policy_whitelist,
             settings: self
                 .settings
@@ -1848,6 +1874,12 @@ impl From<InnerQueryConfig> for QueryConfig {
                 .mysql_handler_tcp_keepalive_timeout_secs,
             mysql_tls_server_cert: inner.mysql_tls_server_cert,
             mysql_tls_server_key: inner.mysql_tls_server_key,
+            postgres_handler_host: inner.postgres_handler_host,
+            postgres_handler_port: inner.postgres_handler_port,
+            postgres_handler_tcp_keepalive_timeout_secs: inner
+                .postgres_handler_tcp_keepalive_timeout_secs,
+            postgres_tls_server_cert: inner.postgres_tls_server_cert,
+            postgres_tls_server_key: inner.postgres_tls_server_key,
             max_active_sessions: inner.max_active_sessions,
             max_running_queries: inner.max_running_queries,
             max_server_memory_usage: inner.max_server_memory_usage,
@@ -1934,6 +1966,7 @@ impl From<InnerQueryConfig> for QueryConfig {
             cloud_control_grpc_server_address: inner.cloud_control_grpc_server_address,
             cloud_control_grpc_timeout: inner.cloud_control_grpc_timeout,
             max_cached_queries_profiles: inner.max_cached_queries_profiles,
+            pipe_poll_interval_secs: inner.pipe_poll_interval_secs,
             network_policy_whitelist: inner.network_policy_whitelist,
             settings: HashMap::new(),
             resources_management: None,
@@ -172,6 +172,11 @@ pub struct QueryConfig {
     pub mysql_handler_tcp_keepalive_timeout_secs: u64,
     pub mysql_tls_server_cert: String,
     pub mysql_tls_server_key: String,
+    pub postgres_handler_host: String,
+    pub postgres_handler_port: u16,
+    pub postgres_handler_tcp_keepalive_timeout_secs: u64,
+    pub postgres_tls_server_cert: String,
+    pub postgres_tls_server_key: String,
     pub max_active_sessions: u64,
     pub max_running_queries: u64,
     pub max_server_memory_usage: u64,
@@ -254,6 +259,7 @@ pub struct QueryConfig {
     pub cloud_control_grpc_server_address: Option<String>,
     pub cloud_control_grpc_timeout: u64,
     pub max_cached_queries_profiles: usize,
+    pub pipe_poll_interval_secs: u64,
 
     pub network_policy_whitelist: Vec<String>,
 
@@ -274,6 +280,11 @@ impl Default for QueryConfig {
             mysql_handler_tcp_keepalive_timeout_secs: 120,
             mysql_tls_server_cert: "".to_string(),
             mysql_tls_server_key: "".to_string(),
+            postgres_handler_host: "127.0.0.1".to_string(),
+            postgres_handler_port: 5433,
+            postgres_handler_tcp_keepalive_timeout_secs: 120,
+            postgres_tls_server_cert: "".to_string(),
+            postgres_tls_server_key: "".to_string(),
             max_active_sessions: 256,
             max_running_queries: 8,
             max_server_memory_usage: 0,
@@ -341,6 +352,7 @@ impl Default for QueryConfig {
             cloud_control_grpc_timeout: 0,
             data_retention_time_in_days_max: 90,
             max_cached_queries_profiles: 50,
+            pipe_poll_interval_secs: 60,
             network_policy_whitelist: Vec::new(),
             settings: HashMap::new(),
             resources_management: None,
@@ -243,6 +243,36 @@ impl UDFFlightClient {
     ) -> Result<RecordBatch> {
         let descriptor = FlightDescriptor::new_path(vec![func_name.to_string()]);
         let batch_rows = self.batch_rows;
+        self.exchange(descriptor, func_name, batch_rows, input_batch)
+            .await
+    }
+
+    /// Call one phase (`accumulate`, `merge` or `finish`) of an aggregate UDF,
+    /// the flight descriptor path is `[func_name, phase]`.
+    ///
+    /// The input batch is sent as a whole, because the rows of a phase
+    /// are reduced by group and can not be split across requests.
+    #[async_backtrace::framed]
+    pub async fn do_exchange_aggregate(
+        &mut self,
+        func_name: &str,
+        phase: &str,
+        input_batch: RecordBatch,
+    ) -> Result<RecordBatch> {
+        let descriptor = FlightDescriptor::new_path(vec![func_name.to_string(), phase.to_string()]);
+        let batch_rows = input_batch.num_rows().max(1);
+        self.exchange(descriptor, func_name, batch_rows, input_batch)
+            .await
+    }
+
+    #[async_backtrace::framed]
+    async fn exchange(
+        &mut self,
+        descriptor: FlightDescriptor,
+        func_name: &str,
+        batch_rows: usize,
+        input_batch: RecordBatch,
+    ) -> Result<RecordBatch> {
         let batches = (0..input_batch.num_rows())
             .step_by(batch_rows)
             .map(move |start| {
@@ -124,6 +124,28 @@ impl FieldEncoderValues {
         }
     }
 
+    // Booleans are `t` and `f` in the text format of postgres, which some clients require.
+    pub fn create_for_postgres_handler(
+        jiff_timezone: TimeZone,
+        timezone: Tz,
+        geometry_format: GeometryDataType,
+    ) -> Self {
+        FieldEncoderValues {
+            common_settings: OutputCommonSettings {
+                true_bytes: b"t".to_vec(),
+                false_bytes: b"f".to_vec(),
+                null_bytes: NULL_BYTES_UPPER.as_bytes().to_vec(),
+                nan_bytes: NAN_BYTES_SNAKE.as_bytes().to_vec(),
+                inf_bytes: INF_BYTES_LONG.as_bytes().to_vec(),
+                timezone,
+                jiff_timezone,
+                binary_format: Default::default(),
+                geometry_format,
+            },
+            quote_char: b'\'',
+        }
+    }
+
     pub fn write_field(
         &self,
         column: &Column,
@@ -18,11 +18,13 @@ mod connection;
 mod file_format;
 mod network_policy;
 mod password_policy;
+mod pipe;
 mod quota;
 mod role;
 mod serde;
 mod setting;
 mod stage;
+mod task;
 pub mod udf;
 mod user;
 mod warehouse;
@@ -36,6 +38,7 @@ pub use connection::ConnectionMgr;
 pub use file_format::FileFormatMgr;
 pub use network_policy::NetworkPolicyMgr;
 pub use password_policy::PasswordPolicyMgr;
+pub use pipe::PipeMgr;
 pub use procedure::ProcedureMgr;
 pub use quota::QuotaApi;
 pub use quota::QuotaMgr;
@@ -47,6 +50,8 @@ pub use serde::serialize_struct;
 pub use setting::SettingMgr;
 pub use stage::StageApi;
 pub use stage::StageMgr;
+pub use task::TaskMgr;
+pub use task::TaskRunMgr;
 pub use user::UserApi;
 pub use user::UserMgr;
 pub use warehouse::SelectedNode;
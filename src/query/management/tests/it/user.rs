@@ -76,6 +76,7 @@ fn default_test_auth_info() -> AuthInfo {
         hash_value: Vec::from("test_password"),
         hash_method: PasswordHashMethod::DoubleSha1,
         need_change: false,
+        scram_verifier: None,
     }
 }
 
@@ -469,6 +470,7 @@ mod update {
                 PasswordHashMethod::DoubleSha1
             },
             need_change: false,
+            scram_verifier: None,
         }
     }
 
@@ -802,6 +802,13 @@ impl Compiler {
                             "ABORT in script is not supported yet".to_string(),
                         ));
                     }
+                    Statement::Savepoint(_)
+                    | Statement::RollbackToSavepoint(_)
+                    | Statement::ReleaseSavepoint(_) => {
+                        self.error = Some(ErrorCode::Unimplemented(
+                            "SAVEPOINT in script is not supported yet".to_string(),
+                        ));
+                    }
                     Statement::Call { .. } => {
                         self.error = Some(ErrorCode::Unimplemented(
                             "CALL in script is not supported yet".to_string(),
@@ -56,6 +56,13 @@ pub enum Credential {
         password: Option<Vec<u8>>,
         client_ip: Option<String>,
     },
+    /// The proof of the password in the SCRAM-SHA-256 exchange.
+    Scram {
+        name: String,
+        auth_message: Vec<u8>,
+        client_proof: Vec<u8>,
+        client_ip: Option<String>,
+    },
     NoNeed,
 }
 
@@ -64,7 +71,7 @@ impl Credential {
         match self {
             Credential::DatabendToken { .. } => CredentialType::DatabendToken,
             Credential::Jwt { .. } => CredentialType::Jwt,
-            Credential::Password { .. } => CredentialType::Password,
+            Credential::Password { .. } | Credential::Scram { .. } => CredentialType::Password,
             Credential::NoNeed => CredentialType::NoNeed,
         }
     }
@@ -194,58 +201,105 @@ impl AuthMgr {
                 password: p,
                 client_ip,
             } => {
-                let tenant = session.get_current_tenant();
-                let identity = UserIdentity::new(name, "%");
-                let mut user = user_api
-                    .get_user_with_client_ip(&tenant, identity.clone(), client_ip.as_deref())
-                    .await?;
-
-                // check global network policy if user is not account admin
-                if !user.is_account_admin() && !global_network_policy.is_empty() {
-                    user_api
-                        .enforce_network_policy(
-                            &tenant,
-                            &global_network_policy,
-                            client_ip.as_deref(),
-                        )
-                        .await?;
-                }
-
-                // Check password policy for login
-                let need_change = UserApiProvider::instance()
-                    .check_login_password(&tenant, identity.clone(), &user)
-                    .await?;
-                if need_change {
-                    user.update_auth_need_change_password();
-                }
-
-                let authed = match &user.auth_info {
-                    AuthInfo::None => Ok(()),
-                    AuthInfo::Password {
-                        hash_value: h,
-                        hash_method: t,
-                        ..
-                    } => match p {
-                        None => Err(ErrorCode::AuthenticateFailure("password required")),
-                        Some(p) => {
-                            if *h == t.hash(p) {
+                self.auth_password(
+                    session,
+                    name,
+                    client_ip.as_deref(),
+                    &global_network_policy,
+                    |auth_info| match auth_info {
+                        AuthInfo::None => Ok(()),
+                        AuthInfo::Password {
+                            hash_value: h,
+                            hash_method: t,
+                            ..
+                        } => match p {
+                            None => Err(ErrorCode::AuthenticateFailure("password required")),
+                            Some(p) => {
+                                if *h == t.hash(p) {
+                                    Ok(())
+                                } else {
+                                    Err(ErrorCode::AuthenticateFailure("wrong password"))
+                                }
+                            }
+                        },
+                        _ => Err(ErrorCode::AuthenticateFailure("wrong auth type")),
+                    },
+                )
+                .await
+            }
+            Credential::Scram {
+                name,
+                auth_message,
+                client_proof,
+                client_ip,
+            } => {
+                self.auth_password(
+                    session,
+                    name,
+                    client_ip.as_deref(),
+                    &global_network_policy,
+                    |auth_info| match auth_info.get_scram_verifier() {
+                        Some(verifier) => {
+                            if verifier.verify_client_proof(auth_message, client_proof) {
                                 Ok(())
                             } else {
                                 Err(ErrorCode::AuthenticateFailure("wrong password"))
                             }
                         }
+                        None => Err(ErrorCode::AuthenticateFailure(
+                            "password has no SCRAM-SHA-256 verifier",
+                        )),
                     },
-                    _ => Err(ErrorCode::AuthenticateFailure("wrong auth type")),
-                };
-                UserApiProvider::instance()
-                    .update_user_login_result(tenant, identity, authed.is_ok(), &user)
-                    .await?;
+                )
+                .await
+            }
+        }
+    }
 
-                authed?;
+    /// Authenticate the user with the password, `check` verifies the credential against
+    /// the auth info of the user, and the result is recorded for the password policy.
+    #[async_backtrace::framed]
+    async fn auth_password<F>(
+        &self,
+        session: &mut Session,
+        name: &str,
+        client_ip: Option<&str>,
+        global_network_policy: &str,
+        check: F,
+    ) -> Result<(String, Option<String>)>
+    where
+        F: FnOnce(&AuthInfo) -> Result<()>,
+    {
+        let user_api = UserApiProvider::instance();
+        let tenant = session.get_current_tenant();
+        let identity = UserIdentity::new(name, "%");
+        let mut user = user_api
+            .get_user_with_client_ip(&tenant, identity.clone(), client_ip)
+            .await?;
 
-                session.set_authed_user(user, None).await?;
-                Ok((name.to_string(), None))
-            }
+        // check global network policy if user is not account admin
+        if !user.is_account_admin() && !global_network_policy.is_empty() {
+            user_api
+                .enforce_network_policy(&tenant, global_network_policy, client_ip)
+                .await?;
         }
+
+        // Check password policy for login
+        let need_change = user_api
+            .check_login_password(&tenant, identity.clone(), &user)
+            .await?;
+        if need_change {
+            user.update_auth_need_change_password();
+        }
+
+        let authed = check(&user.auth_info);
+        user_api
+            .update_user_login_result(tenant, identity, authed.is_ok(), &user)
+            .await?;
+
+        authed?;
+
+        session.set_authed_user(user, None).await?;
+        Ok((name.to_string(), None))
     }
 }
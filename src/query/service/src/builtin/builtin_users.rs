@@ -63,6 +63,7 @@ impl BuiltinUsers {
                             hash_value: p,
                             hash_method: password_type,
                             need_change: false,
+                            scram_verifier: None,
                         })
                     }
                 }
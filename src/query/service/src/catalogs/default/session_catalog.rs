@@ -323,7 +323,9 @@ impl Catalog for SessionCatalog {
         if let Some(t) = {
             let guard = self.txn_mgr.lock();
             if guard.is_active() {
-                guard.get_table_from_buffer_by_id(table_id)
+                guard
+                    .get_table_from_buffer_by_id(table_id)
+                    .or_else(|| guard.get_read_table(table_id))
             } else {
                 None
             }
@@ -403,12 +405,24 @@ impl Catalog for SessionCatalog {
         if let Some(table) = self.temp_tbl_mgr.lock().get_table(db_name, table_name)? {
             return self.get_table_by_info(&table);
         }
+        if is_active && self.txn_mgr.lock().is_table_dropped(db_name, table_name) {
+            return Err(ErrorCode::UnknownTable(format!(
+                "Unknown table '{}'.'{}', it is dropped in the current transaction",
+                db_name, table_name
+            )));
+        }
         let table = self.inner.get_table(tenant, db_name, table_name).await?;
         if table.is_stream() && is_active {
             self.txn_mgr
                 .lock()
                 .upsert_table_desc_to_id(table.get_table_info().clone());
         }
+        // Fuse tables are read at the same version in the transaction, until the transaction changes them.
+        if table.engine() == "FUSE" && !table.is_temp() && is_active {
+            self.txn_mgr
+                .lock()
+                .pin_read_table(table.get_table_info().clone());
+        }
         Ok(table)
     }
 
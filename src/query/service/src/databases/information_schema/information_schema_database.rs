@@ -25,6 +25,7 @@ use databend_common_storages_information_schema::KeyColumnUsageTable;
 use databend_common_storages_information_schema::KeywordsTable;
 use databend_common_storages_information_schema::SchemataTable;
 use databend_common_storages_information_schema::StatisticsTable;
+use databend_common_storages_information_schema::TableConstraintsTable;
 use databend_common_storages_information_schema::TablesTable;
 use databend_common_storages_information_schema::ViewsTable;
 
@@ -47,6 +48,7 @@ impl InformationSchemaDatabase {
             SchemataTable::create(sys_db_meta.next_table_id()),
             StatisticsTable::create(sys_db_meta.next_table_id()),
             KeyColumnUsageTable::create(sys_db_meta.next_table_id()),
+            TableConstraintsTable::create(sys_db_meta.next_table_id()),
         ];
 
         let db = "information_schema";
@@ -32,11 +32,13 @@ use databend_common_storages_system::ClusteringHistoryTable;
 use databend_common_storages_system::ClustersTable;
 use databend_common_storages_system::ColumnsTable;
 use databend_common_storages_system::ConfigsTable;
+use databend_common_storages_system::ConstraintsTable;
 use databend_common_storages_system::ContributorsTable;
 use databend_common_storages_system::CreditsTable;
 use databend_common_storages_system::DatabasesTableWithHistory;
 use databend_common_storages_system::DatabasesTableWithoutHistory;
 use databend_common_storages_system::DictionariesTable;
+use databend_common_storages_system::DynamicTablesTable;
 use databend_common_storages_system::EnginesTable;
 use databend_common_storages_system::FullStreamsTable;
 use databend_common_storages_system::FunctionsTable;
@@ -51,6 +53,7 @@ use databend_common_storages_system::NotificationHistoryTable;
 use databend_common_storages_system::NotificationsTable;
 use databend_common_storages_system::OneTable;
 use databend_common_storages_system::PasswordPoliciesTable;
+use databend_common_storages_system::PipesTable;
 use databend_common_storages_system::ProceduresTable;
 use databend_common_storages_system::ProcessesTable;
 use databend_common_storages_system::QueriesProfilingTable;
@@ -152,6 +155,9 @@ impl SystemDatabase {
             TemporaryTablesTable::create(sys_db_meta.next_table_id()),
             ProceduresTable::create(sys_db_meta.next_table_id()),
             DictionariesTable::create(sys_db_meta.next_table_id()),
+            PipesTable::create(sys_db_meta.next_table_id()),
+            DynamicTablesTable::create(sys_db_meta.next_table_id()),
+            ConstraintsTable::create(sys_db_meta.next_table_id()),
         ];
 
         let disable_tables = Self::disable_system_tables();
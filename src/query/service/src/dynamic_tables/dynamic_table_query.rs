@@ -0,0 +1,623 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use databend_common_ast::ast::ColumnID;
+use databend_common_ast::ast::Expr;
+use databend_common_ast::ast::GroupBy;
+use databend_common_ast::ast::Identifier;
+use databend_common_ast::ast::JoinOperator;
+use databend_common_ast::ast::Literal;
+use databend_common_ast::ast::Query;
+use databend_common_ast::ast::SelectTarget;
+use databend_common_ast::ast::SetExpr;
+use databend_common_ast::ast::Statement;
+use databend_common_ast::ast::TableAlias;
+use databend_common_ast::ast::TableReference;
+use databend_common_ast::ast::TemporalClause;
+use databend_common_ast::ast::TimeTravelPoint;
+use databend_common_ast::ast::CTE;
+use databend_common_ast::parser::parse_sql;
+use databend_common_ast::parser::tokenize_sql;
+use databend_common_ast::parser::Dialect;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_functions::aggregates::AggregateFunctionFactory;
+use databend_common_sql::normalize_identifier;
+use databend_common_sql::NameResolutionContext;
+use derive_visitor::Drive;
+use derive_visitor::DriveMut;
+use derive_visitor::Visitor;
+use derive_visitor::VisitorMut;
+
+/// The name of a table read by the query of a dynamic table, as it is written in the query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceName {
+    pub catalog: Option<String>,
+    pub database: String,
+    pub table: String,
+}
+
+/// How the result of the query of a dynamic table can be maintained incrementally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncrementalShape {
+    /// Filters and projections over inner joins, the new rows of the sources
+    /// only add rows to the result.
+    Project,
+    /// An aggregation over a `Project`, the new rows of the sources are aggregated
+    /// and merged into the groups of the result.
+    Aggregate(Vec<AggregateColumn>),
+}
+
+/// A column of the result of an aggregate query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateColumn {
+    GroupKey,
+    Count,
+    Sum,
+    Min,
+    Max,
+}
+
+/// A source table read at fixed snapshots, built by [`DynamicTableQuery::pin_source`].
+#[derive(Debug, Clone)]
+pub struct SourcePin {
+    /// The snapshot the dynamic table is refreshed to last time.
+    old: Option<String>,
+    /// The snapshot the dynamic table is being refreshed to.
+    new: Option<String>,
+    /// The rows inserted between the two snapshots.
+    changes: Query,
+    /// No rows, used to read a table at a snapshot before it has any.
+    empty: Query,
+}
+
+/// The query of a dynamic table, along with the rewrites used to refresh it.
+pub struct DynamicTableQuery {
+    query: Query,
+    dialect: Dialect,
+    name_resolution_ctx: NameResolutionContext,
+    cte_names: HashSet<String>,
+    shape: Option<IncrementalShape>,
+}
+
+impl DynamicTableQuery {
+    pub fn try_create(
+        sql: &str,
+        dialect: Dialect,
+        name_resolution_ctx: NameResolutionContext,
+    ) -> Result<Self> {
+        let query = parse_query(sql, dialect)?;
+
+        let mut collector = CteCollector::default();
+        query.drive(&mut collector);
+        let cte_names = collector
+            .names
+            .iter()
+            .map(|name| normalize_identifier(name, &name_resolution_ctx).name)
+            .collect();
+
+        let shape = incremental_shape(&query);
+        Ok(DynamicTableQuery {
+            query,
+            dialect,
+            name_resolution_ctx,
+            cte_names,
+            shape,
+        })
+    }
+
+    /// Returns None if the query can only be refreshed by recomputing it.
+    pub fn shape(&self) -> Option<&IncrementalShape> {
+        self.shape.as_ref()
+    }
+
+    /// The distinct tables read by the query, excluding the common table expressions.
+    pub fn sources(&self) -> Vec<SourceName> {
+        let mut collector = SourceCollector {
+            name_resolution_ctx: self.name_resolution_ctx.clone(),
+            cte_names: self.cte_names.clone(),
+            sources: vec![],
+        };
+        self.query.drive(&mut collector);
+        collector.sources
+    }
+
+    /// Pin the source `name` with the columns `columns` at the snapshots `old` and `new`,
+    /// None means the table has no snapshot at that point.
+    pub fn pin_source(
+        &self,
+        name: &SourceName,
+        columns: &[String],
+        old: Option<String>,
+        new: Option<String>,
+    ) -> Result<SourcePin> {
+        let quote = self.dialect.default_ident_quote();
+        let table = self.table_name(name);
+        let columns = columns
+            .iter()
+            .map(|column| format!("{quote}{column}{quote}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let empty = format!("SELECT {columns} FROM {table} LIMIT 0");
+        let changes = match (&old, &new) {
+            (Some(old), Some(new)) => format!(
+                "SELECT {columns} FROM {table} CHANGES (INFORMATION => APPEND_ONLY) AT (SNAPSHOT => '{old}') END (SNAPSHOT => '{new}')"
+            ),
+            (None, Some(new)) => format!("SELECT {columns} FROM {table} AT (SNAPSHOT => '{new}')"),
+            (_, None) => empty.clone(),
+        };
+        Ok(SourcePin {
+            old,
+            new,
+            changes: parse_query(&changes, self.dialect)?,
+            empty: parse_query(&empty, self.dialect)?,
+        })
+    }
+
+    /// The query counting the rows deleted or updated in the source between
+    /// the snapshots of `pin`, None if there are no changes to read.
+    pub fn deletes_query(&self, name: &SourceName, pin: &SourcePin) -> Option<String> {
+        match (&pin.old, &pin.new) {
+            (Some(old), Some(new)) if old != new => Some(format!(
+                "SELECT COUNT(*) FROM {} CHANGES (INFORMATION => DEFAULT) AT (SNAPSHOT => '{old}') END (SNAPSHOT => '{new}') WHERE change$action = 'DELETE'",
+                self.table_name(name)
+            )),
+            _ => None,
+        }
+    }
+
+    /// The query reading every pinned source at its new snapshot.
+    pub fn full_query(&self, pins: &HashMap<SourceName, SourcePin>) -> String {
+        let mut query = self.query.clone();
+        query.drive_mut(&mut self.rewriter(pins, None));
+        query.to_string()
+    }
+
+    /// The query computing the rows added to the result of the query
+    /// between the old and new snapshots of the pinned sources.
+    ///
+    /// For a join of `n` occurrences of the sources, the delta is the union of `n` terms,
+    /// term `i` joins the changes of occurrence `i` with the occurrences before it
+    /// at the old snapshots and the occurrences after it at the new snapshots.
+    pub fn delta_query(&self, pins: &HashMap<SourceName, SourcePin>) -> String {
+        let mut terms = vec![];
+        let mut num_occurrences = 1;
+        let mut delta = 0;
+        while delta < num_occurrences {
+            let mut query = self.query.clone();
+            let mut rewriter = self.rewriter(pins, Some(delta));
+            query.drive_mut(&mut rewriter);
+            terms.push(query.to_string());
+
+            num_occurrences = rewriter.occurrence;
+            delta += 1;
+        }
+        terms.join(" UNION ALL ")
+    }
+
+    /// The `MERGE` statement merging the groups computed by `delta_query`
+    /// into the dynamic table `table`, an aggregate query of `shape`.
+    ///
+    /// `columns` are the columns of the dynamic table along with whether they are nullable.
+    pub fn merge_statement(
+        &self,
+        table: &str,
+        columns: &[(String, bool)],
+        shape: &[AggregateColumn],
+        delta: &str,
+    ) -> String {
+        let quote = self.dialect.default_ident_quote();
+        let names = columns
+            .iter()
+            .map(|(name, _)| format!("{quote}{name}{quote}"))
+            .collect::<Vec<_>>();
+
+        let mut keys = vec![];
+        let mut select_list = vec![];
+        let mut conditions = vec![];
+        let mut assignments = vec![];
+        for ((name, (_, nullable)), column) in names.iter().zip(columns).zip(shape) {
+            let target = format!("__dt.{name}");
+            let source = format!("__delta.{name}");
+            let (item, combined) = match column {
+                AggregateColumn::GroupKey => {
+                    keys.push(name.clone());
+                    let op = if *nullable { "IS NOT DISTINCT FROM" } else { "=" };
+                    conditions.push(format!("{target} {op} {source}"));
+                    select_list.push(name.clone());
+                    continue;
+                }
+                AggregateColumn::Count => (
+                    format!("SUM({name})"),
+                    format!("{target} + {source}"),
+                ),
+                AggregateColumn::Sum => (
+                    format!("SUM({name})"),
+                    format!(
+                        "CASE WHEN {target} IS NULL THEN {source} WHEN {source} IS NULL THEN {target} ELSE {target} + {source} END"
+                    ),
+                ),
+                AggregateColumn::Min => (
+                    format!("MIN({name})"),
+                    format!(
+                        "CASE WHEN {target} IS NULL THEN {source} WHEN {source} IS NULL THEN {target} WHEN {source} < {target} THEN {source} ELSE {target} END"
+                    ),
+                ),
+                AggregateColumn::Max => (
+                    format!("MAX({name})"),
+                    format!(
+                        "CASE WHEN {target} IS NULL THEN {source} WHEN {source} IS NULL THEN {target} WHEN {source} > {target} THEN {source} ELSE {target} END"
+                    ),
+                ),
+            };
+            select_list.push(format!("{item} AS {name}"));
+            assignments.push(format!("{name} = {combined}"));
+        }
+
+        let names = names.join(", ");
+        let mut groups = format!(
+            "SELECT {} FROM ({delta}) AS __terms({names})",
+            select_list.join(", ")
+        );
+        if !keys.is_empty() {
+            groups.push_str(&format!(" GROUP BY {}", keys.join(", ")));
+        }
+        let condition = if conditions.is_empty() {
+            "TRUE".to_string()
+        } else {
+            conditions.join(" AND ")
+        };
+
+        let mut sql =
+            format!("MERGE INTO {table} AS __dt USING ({groups}) AS __delta ON {condition}");
+        if !assignments.is_empty() {
+            sql.push_str(&format!(
+                " WHEN MATCHED THEN UPDATE SET {}",
+                assignments.join(", ")
+            ));
+        }
+        let values = columns
+            .iter()
+            .map(|(name, _)| format!("__delta.{quote}{name}{quote}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sql.push_str(&format!(
+            " WHEN NOT MATCHED THEN INSERT ({names}) VALUES ({values})"
+        ));
+        sql
+    }
+
+    fn table_name(&self, name: &SourceName) -> String {
+        let quote = self.dialect.default_ident_quote();
+        match &name.catalog {
+            Some(catalog) => format!(
+                "{quote}{catalog}{quote}.{quote}{}{quote}.{quote}{}{quote}",
+                name.database, name.table
+            ),
+            None => format!(
+                "{quote}{}{quote}.{quote}{}{quote}",
+                name.database, name.table
+            ),
+        }
+    }
+
+    fn rewriter(
+        &self,
+        pins: &HashMap<SourceName, SourcePin>,
+        delta: Option<usize>,
+    ) -> SourceRewriter {
+        SourceRewriter {
+            name_resolution_ctx: self.name_resolution_ctx.clone(),
+            cte_names: self.cte_names.clone(),
+            pins: pins.clone(),
+            delta,
+            occurrence: 0,
+        }
+    }
+}
+
+fn parse_query(sql: &str, dialect: Dialect) -> Result<Query> {
+    let tokens = tokenize_sql(sql)?;
+    let (stmt, _) = parse_sql(&tokens, dialect)?;
+    match stmt {
+        Statement::Query(query) => Ok(*query),
+        _ => Err(ErrorCode::IllegalDynamicTable(format!(
+            "The query of dynamic table is not a query: {sql}"
+        ))),
+    }
+}
+
+fn source_name(
+    name_resolution_ctx: &NameResolutionContext,
+    cte_names: &HashSet<String>,
+    table_ref: &TableReference,
+) -> Option<SourceName> {
+    let TableReference::Table {
+        catalog,
+        database,
+        table,
+        ..
+    } = table_ref
+    else {
+        return None;
+    };
+    let normalize = |ident: &Identifier| normalize_identifier(ident, name_resolution_ctx).name;
+
+    let table = normalize(table);
+    if cte_names.contains(&table) {
+        return None;
+    }
+    Some(SourceName {
+        catalog: catalog.as_ref().map(normalize),
+        database: normalize(database.as_ref()?),
+        table,
+    })
+}
+
+#[derive(Visitor, Default)]
+#[visitor(CTE(enter))]
+struct CteCollector {
+    names: Vec<Identifier>,
+}
+
+impl CteCollector {
+    fn enter_cte(&mut self, cte: &CTE) {
+        self.names.push(cte.alias.name.clone());
+    }
+}
+
+#[derive(Visitor)]
+#[visitor(TableReference(enter))]
+struct SourceCollector {
+    name_resolution_ctx: NameResolutionContext,
+    cte_names: HashSet<String>,
+    sources: Vec<SourceName>,
+}
+
+impl SourceCollector {
+    fn enter_table_reference(&mut self, table_ref: &TableReference) {
+        if let Some(name) = source_name(&self.name_resolution_ctx, &self.cte_names, table_ref) {
+            if !self.sources.contains(&name) {
+                self.sources.push(name);
+            }
+        }
+    }
+}
+
+/// Rewrite the references of the pinned sources to read them at their snapshots.
+///
+/// Tables are rewritten on exit, so the subqueries replacing them are not visited.
+#[derive(VisitorMut)]
+#[visitor(TableReference(exit))]
+struct SourceRewriter {
+    name_resolution_ctx: NameResolutionContext,
+    cte_names: HashSet<String>,
+    pins: HashMap<SourceName, SourcePin>,
+    /// The occurrence of the sources replaced by its changes.
+    delta: Option<usize>,
+    occurrence: usize,
+}
+
+impl SourceRewriter {
+    fn exit_table_reference(&mut self, table_ref: &mut TableReference) {
+        let Some(name) = source_name(&self.name_resolution_ctx, &self.cte_names, table_ref) else {
+            return;
+        };
+        let Some(pin) = self.pins.get(&name) else {
+            return;
+        };
+        let TableReference::Table {
+            span,
+            table,
+            alias,
+            temporal,
+            ..
+        } = table_ref
+        else {
+            return;
+        };
+
+        let occurrence = self.occurrence;
+        self.occurrence += 1;
+        let replacement = match self.delta {
+            Some(delta) if occurrence == delta => &pin.changes,
+            delta => {
+                let snapshot = match delta {
+                    Some(delta) if occurrence < delta => &pin.old,
+                    _ => &pin.new,
+                };
+                if let Some(snapshot) = snapshot {
+                    *temporal = Some(TemporalClause::TimeTravel(TimeTravelPoint::Snapshot(
+                        snapshot.clone(),
+                    )));
+                    return;
+                }
+                &pin.empty
+            }
+        };
+
+        let alias = alias.clone().unwrap_or_else(|| TableAlias {
+            name: table.clone(),
+            columns: vec![],
+        });
+        *table_ref = TableReference::Subquery {
+            span: *span,
+            lateral: false,
+            subquery: Box::new(replacement.clone()),
+            alias: Some(alias),
+            pivot: None,
+            unpivot: None,
+        };
+    }
+}
+
+/// Find out which unsupported expressions are used by the query.
+#[derive(Visitor, Default)]
+#[visitor(Expr(enter))]
+struct ExprChecker {
+    has_subquery: bool,
+    has_window: bool,
+    has_aggregate: bool,
+}
+
+impl ExprChecker {
+    fn enter_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Subquery { .. } | Expr::Exists { .. } | Expr::InSubquery { .. } => {
+                self.has_subquery = true;
+            }
+            Expr::CountAll { window, .. } => {
+                self.has_aggregate = true;
+                self.has_window |= window.is_some();
+            }
+            Expr::FunctionCall { func, .. } => {
+                self.has_window |= func.window.is_some();
+                self.has_aggregate |=
+                    AggregateFunctionFactory::instance().contains(func.name.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn incremental_shape(query: &Query) -> Option<IncrementalShape> {
+    if query.with.is_some()
+        || !query.order_by.is_empty()
+        || !query.limit.is_empty()
+        || query.offset.is_some()
+    {
+        return None;
+    }
+    let SetExpr::Select(select) = &query.body else {
+        return None;
+    };
+    if select.distinct
+        || select.top_n.is_some()
+        || select.having.is_some()
+        || select.window_list.is_some()
+        || select.qualify.is_some()
+        || select.from.is_empty()
+        || !select.from.iter().all(is_inner_join_of_tables)
+    {
+        return None;
+    }
+
+    let mut checker = ExprChecker::default();
+    select.drive(&mut checker);
+    if checker.has_subquery || checker.has_window {
+        return None;
+    }
+
+    match &select.group_by {
+        None if !checker.has_aggregate => Some(IncrementalShape::Project),
+        Some(GroupBy::Normal(group_by)) => {
+            aggregate_shape(&select.select_list, group_by).map(IncrementalShape::Aggregate)
+        }
+        _ => None,
+    }
+}
+
+fn is_inner_join_of_tables(table_ref: &TableReference) -> bool {
+    match table_ref {
+        TableReference::Table {
+            temporal,
+            with_options,
+            pivot,
+            unpivot,
+            sample,
+            ..
+        } => {
+            temporal.is_none()
+                && with_options.is_none()
+                && pivot.is_none()
+                && unpivot.is_none()
+                && sample.is_none()
+        }
+        TableReference::Join { join, .. } => {
+            matches!(join.op, JoinOperator::Inner | JoinOperator::CrossJoin)
+                && join.match_condition.is_none()
+                && is_inner_join_of_tables(&join.left)
+                && is_inner_join_of_tables(&join.right)
+        }
+        _ => false,
+    }
+}
+
+fn aggregate_shape(
+    select_list: &[SelectTarget],
+    group_by: &[Expr],
+) -> Option<Vec<AggregateColumn>> {
+    let mut items = Vec::with_capacity(select_list.len());
+    for target in select_list {
+        match target {
+            SelectTarget::AliasedExpr { expr, alias } => items.push((expr.as_ref(), alias)),
+            SelectTarget::StarColumns { .. } => return None,
+        }
+    }
+
+    // Every group key must be in the result to merge the groups.
+    let mut columns = vec![None; items.len()];
+    for key in group_by {
+        let position = match key {
+            Expr::Literal {
+                value: Literal::UInt64(position),
+                ..
+            } => (*position as usize).checked_sub(1),
+            Expr::ColumnRef { column, .. } if column.table.is_none() => {
+                items.iter().position(|(expr, alias)| match &column.column {
+                    ColumnID::Name(name) => {
+                        alias.as_ref().is_some_and(|alias| alias.name == name.name)
+                            || expr.to_string() == key.to_string()
+                    }
+                    ColumnID::Position(_) => false,
+                })
+            }
+            _ => items
+                .iter()
+                .position(|(expr, _)| expr.to_string() == key.to_string()),
+        };
+        *columns.get_mut(position?)? = Some(AggregateColumn::GroupKey);
+    }
+
+    for ((expr, _), column) in items.iter().zip(columns.iter_mut()) {
+        if column.is_some() {
+            continue;
+        }
+        *column = match expr {
+            Expr::CountAll { window: None, .. } => Some(AggregateColumn::Count),
+            Expr::FunctionCall { func, .. }
+                if !func.distinct
+                    && func.params.is_empty()
+                    && func.order_by.is_empty()
+                    && func.window.is_none()
+                    && func.lambda.is_none()
+                    && func.args.len() == 1 =>
+            {
+                match func.name.name.to_lowercase().as_str() {
+                    "count" => Some(AggregateColumn::Count),
+                    "sum" => Some(AggregateColumn::Sum),
+                    "min" => Some(AggregateColumn::Min),
+                    "max" => Some(AggregateColumn::Max),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+    }
+    columns.into_iter().collect()
+}
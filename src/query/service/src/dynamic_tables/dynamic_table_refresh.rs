@@ -0,0 +1,465 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use databend_common_catalog::catalog::Catalog;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::DataBlock;
+use databend_common_expression::ScalarRef;
+use databend_common_meta_app::principal::UserInfo;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::UpsertTableOptionReq;
+use databend_common_meta_app::tenant::Tenant;
+use databend_common_meta_types::MatchSeq;
+use databend_common_sql::NameResolutionContext;
+use databend_common_storages_fuse::FuseTable;
+use databend_common_users::UserApiProvider;
+use databend_storages_common_table_meta::meta::TableSnapshot;
+use databend_storages_common_table_meta::table::DynamicTableRefreshState;
+use databend_storages_common_table_meta::table::DynamicTableSource;
+use databend_storages_common_table_meta::table::OPT_KEY_AS_QUERY;
+use databend_storages_common_table_meta::table::OPT_KEY_CHANGE_TRACKING_BEGIN_VER;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_MODE;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_STATE;
+use databend_storages_common_table_meta::table::REFRESH_ACTION_FULL;
+use databend_storages_common_table_meta::table::REFRESH_ACTION_INCREMENTAL;
+use databend_storages_common_table_meta::table::REFRESH_ACTION_NO_DATA;
+use futures::future::Either;
+use log::info;
+use log::warn;
+
+use crate::dynamic_tables::DynamicTableQuery;
+use crate::dynamic_tables::IncrementalShape;
+use crate::dynamic_tables::SourceName;
+use crate::dynamic_tables::SourcePin;
+use crate::interpreters::execute_statement;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionType;
+
+/// How long the refresh lease of a dynamic table lasts if it is not renewed.
+const DYNAMIC_TABLE_REFRESH_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often the refresh lease of a dynamic table is renewed during the refresh.
+const DYNAMIC_TABLE_REFRESH_LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Read the refresh state of a dynamic table, the state is empty if it is never refreshed.
+pub fn dynamic_table_refresh_state(table_info: &TableInfo) -> Result<DynamicTableRefreshState> {
+    match table_info.options().get(OPT_KEY_REFRESH_STATE) {
+        Some(state) => Ok(serde_json::from_str(state)?),
+        None => Ok(DynamicTableRefreshState::default()),
+    }
+}
+
+/// Create a session to refresh dynamic tables as `user` with `role`.
+pub async fn create_refresh_session(user: UserInfo, role: Option<String>) -> Result<Arc<Session>> {
+    let session_manager = SessionManager::instance();
+    let session = session_manager.create_session(SessionType::Dummy).await?;
+    let session = session_manager.register_session(session)?;
+    session.set_authed_user(user, role).await?;
+    Ok(session)
+}
+
+/// Refresh the dynamic table once in `session`, returns the action of the refresh,
+/// one of `FULL`, `INCREMENTAL` and `NO_DATA`.
+///
+/// The error of a failed refresh is kept in the refresh state of the table. The refresh
+/// holds the refresh lease of the table until it finishes, it fails if another query holds it.
+#[async_backtrace::framed]
+pub async fn refresh_dynamic_table(
+    session: Arc<Session>,
+    catalog: &str,
+    database: &str,
+    table: &str,
+) -> Result<&'static str> {
+    let ctx = session.create_query_context().await?;
+    let refresher = DynamicTableRefresher {
+        session,
+        tenant: ctx.get_tenant(),
+        catalog: ctx.get_catalog(catalog).await?,
+        database: database.to_string(),
+        table: table.to_string(),
+    };
+
+    // The refreshes of a table are serialized, two refreshes from the same state
+    // would apply the same changes twice.
+    let table_id = refresher.get_table().await?.get_id();
+    let query_id = ctx.get_id();
+    let user_api = UserApiProvider::instance();
+    if !user_api
+        .acquire_dynamic_table_refresh_lease(
+            &refresher.tenant,
+            table_id,
+            &query_id,
+            DYNAMIC_TABLE_REFRESH_LEASE_TTL,
+        )
+        .await?
+    {
+        return Err(ErrorCode::TableAlreadyLocked(format!(
+            "Dynamic table '{}'.'{}' is being refreshed by another query",
+            database, table
+        )));
+    }
+
+    let mut refresh = Box::pin(refresher.refresh());
+    let result = loop {
+        let renew = Box::pin(tokio::time::sleep(
+            DYNAMIC_TABLE_REFRESH_LEASE_RENEW_INTERVAL,
+        ));
+        match futures::future::select(renew, refresh).await {
+            Either::Left((_, right)) => {
+                // Stop refreshing if the lease is lost, another refresh may have started.
+                let renewed = user_api
+                    .acquire_dynamic_table_refresh_lease(
+                        &refresher.tenant,
+                        table_id,
+                        &query_id,
+                        DYNAMIC_TABLE_REFRESH_LEASE_TTL,
+                    )
+                    .await;
+                if !renewed.unwrap_or(false) {
+                    break None;
+                }
+                refresh = right;
+            }
+            Either::Right((result, _)) => break Some(result),
+        }
+    };
+    if let Err(cause) = user_api
+        .release_dynamic_table_refresh_lease(&refresher.tenant, table_id, &query_id)
+        .await
+    {
+        warn!(
+            "Failed to release the refresh lease of dynamic table {}.{}: {:?}",
+            database, table, cause
+        );
+    }
+    let Some(result) = result else {
+        return Err(ErrorCode::TableLockExpired(format!(
+            "Dynamic table '{}'.'{}' lost its refresh lease during the refresh",
+            database, table
+        )));
+    };
+
+    match &result {
+        Ok(action) => info!("Dynamic table {}.{} refreshed: {}", database, table, action),
+        Err(cause) => {
+            warn!(
+                "Failed to refresh dynamic table {}.{}: {:?}",
+                database, table, cause
+            );
+            if let Err(cause) = refresher.record_error(cause).await {
+                warn!(
+                    "Failed to record the refresh error of dynamic table {}.{}: {:?}",
+                    database, table, cause
+                );
+            }
+        }
+    }
+    result
+}
+
+struct DynamicTableRefresher {
+    session: Arc<Session>,
+    tenant: Tenant,
+    catalog: Arc<dyn Catalog>,
+    database: String,
+    table: String,
+}
+
+impl DynamicTableRefresher {
+    #[async_backtrace::framed]
+    async fn refresh(&self) -> Result<&'static str> {
+        let table = self.get_table().await?;
+        let table_info = table.get_table_info();
+        let Some(as_query) = table_info.options().get(OPT_KEY_AS_QUERY) else {
+            return Err(ErrorCode::IllegalDynamicTable(format!(
+                "Table '{}'.'{}' is not a dynamic table",
+                self.database, self.table
+            )));
+        };
+        let full_only = table_info
+            .options()
+            .get(OPT_KEY_REFRESH_MODE)
+            .is_some_and(|mode| mode == "FULL");
+        let state = dynamic_table_refresh_state(table_info)?;
+
+        // The offsets of the sources are only valid for the snapshot written by the last refresh.
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        let snapshot = fuse_table.read_table_snapshot().await?;
+        let state_valid = state.snapshot_location.is_some()
+            && state.snapshot_location == fuse_table.snapshot_loc();
+
+        let ctx = self.session.create_query_context().await?;
+        let settings = ctx.get_settings();
+        let query = DynamicTableQuery::try_create(
+            as_query,
+            settings.get_sql_dialect()?,
+            NameResolutionContext::try_from(settings.as_ref())?,
+        )?;
+
+        let data_timestamp = Utc::now();
+        let mut incremental = !full_only && query.shape().is_some() && state_valid;
+        let mut changed = !state_valid;
+        let mut sources = vec![];
+        let mut pins = HashMap::new();
+        for name in query.sources() {
+            let catalog_name = name
+                .catalog
+                .clone()
+                .unwrap_or_else(|| ctx.get_current_catalog());
+            let catalog = ctx.get_catalog(&catalog_name).await?;
+            let source = catalog
+                .get_table(&self.tenant, &name.database, &name.table)
+                .await?;
+            let Ok(fuse_source) = FuseTable::try_from_table(source.as_ref()) else {
+                // Views and tables of other engines can not be read at a snapshot,
+                // they are read as they are by full refreshes.
+                incremental = false;
+                changed = true;
+                continue;
+            };
+
+            let source_snapshot = fuse_source.read_table_snapshot().await?;
+            let snapshot_id = source_snapshot
+                .as_ref()
+                .map(|snapshot| snapshot.snapshot_id.simple().to_string());
+            let previous = state.sources.iter().find(|previous| {
+                previous.catalog == catalog_name
+                    && previous.database == name.database
+                    && previous.table == name.table
+                    && previous.table_id == source.get_id()
+            });
+            let offset = match previous {
+                Some(previous) => {
+                    changed |= previous.snapshot_id != snapshot_id;
+                    // The rows of an empty source are all new, its changes are not needed.
+                    incremental &= previous.trackable || previous.snapshot_id.is_none();
+                    previous.snapshot_id.clone()
+                }
+                None => {
+                    incremental = false;
+                    changed = true;
+                    None
+                }
+            };
+
+            let columns = source
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect::<Vec<_>>();
+            let pin = query.pin_source(&name, &columns, offset, snapshot_id.clone())?;
+            if incremental && !self.is_insert_only(&query, &name, &pin).await {
+                incremental = false;
+            }
+            pins.insert(name.clone(), pin);
+
+            sources.push(DynamicTableSource {
+                catalog: catalog_name,
+                database: name.database,
+                table: name.table,
+                table_id: source.get_id(),
+                trackable: source_snapshot
+                    .as_ref()
+                    .is_some_and(|snapshot| is_trackable(fuse_source, snapshot)),
+                snapshot_id,
+            });
+        }
+        // Nothing to refresh, but the data is up to date as of now.
+        if !changed {
+            let state = DynamicTableRefreshState {
+                sources,
+                data_timestamp: Some(data_timestamp),
+                refreshed_at: Some(Utc::now()),
+                refresh_action: Some(REFRESH_ACTION_NO_DATA.to_string()),
+                last_error: None,
+                ..state
+            };
+            self.save_state(table_info, &state).await?;
+            return Ok(REFRESH_ACTION_NO_DATA);
+        }
+
+        let quote = settings.get_sql_dialect()?.default_ident_quote();
+        let target = format!(
+            "{quote}{}{quote}.{quote}{}{quote}.{quote}{}{quote}",
+            self.catalog.name(),
+            self.database,
+            self.table
+        );
+        let full_refresh = format!("INSERT OVERWRITE {target} {}", query.full_query(&pins));
+        let mut action = REFRESH_ACTION_FULL;
+        let mut result = None;
+        if incremental {
+            let delta = query.delta_query(&pins);
+            let sql = match query.shape() {
+                Some(IncrementalShape::Aggregate(shape)) => {
+                    let columns = table
+                        .schema()
+                        .fields()
+                        .iter()
+                        .map(|field| (field.name().clone(), field.is_nullable()))
+                        .collect::<Vec<_>>();
+                    query.merge_statement(&target, &columns, shape, &delta)
+                }
+                _ => format!("INSERT INTO {target} {delta}"),
+            };
+            match self.execute(&sql).await {
+                Ok(_) => {
+                    action = REFRESH_ACTION_INCREMENTAL;
+                    result = Some(());
+                }
+                Err(cause) => warn!(
+                    "Failed to refresh dynamic table {}.{} incrementally, fall back to full refresh: {:?}",
+                    self.database, self.table, cause
+                ),
+            }
+        }
+        if result.is_none() {
+            self.execute(&full_refresh).await?;
+        }
+
+        // If the table is written by others during the refresh,
+        // the offsets may not match its data, the next refresh will be a full refresh.
+        let table = self.get_table().await?;
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        let previous_id = snapshot.as_ref().map(|snapshot| snapshot.snapshot_id);
+        let snapshot_location = match fuse_table.read_table_snapshot().await? {
+            Some(current)
+                if Some(current.snapshot_id) == previous_id
+                    || current.prev_snapshot_id.map(|(id, _)| id) == previous_id =>
+            {
+                fuse_table.snapshot_loc()
+            }
+            _ => None,
+        };
+
+        let state = DynamicTableRefreshState {
+            sources,
+            snapshot_location,
+            data_timestamp: Some(data_timestamp),
+            refreshed_at: Some(Utc::now()),
+            refresh_action: Some(action.to_string()),
+            refresh_count: state.refresh_count + 1,
+            last_error: None,
+        };
+        self.save_state(table.get_table_info(), &state).await?;
+        Ok(action)
+    }
+
+    /// Check that no rows are deleted or updated in the source since its offset,
+    /// which can not be applied by an incremental refresh.
+    #[async_backtrace::framed]
+    async fn is_insert_only(
+        &self,
+        query: &DynamicTableQuery,
+        name: &SourceName,
+        pin: &SourcePin,
+    ) -> bool {
+        let Some(sql) = query.deletes_query(name, pin) else {
+            return true;
+        };
+        let result = async {
+            let blocks = self.execute(&sql).await?;
+            let block = DataBlock::concat(&blocks)?;
+            if block.num_rows() != 1 || block.num_columns() != 1 {
+                return Err(ErrorCode::Internal(format!(
+                    "Expect a count of deleted rows, but got {} rows and {} columns",
+                    block.num_rows(),
+                    block.num_columns()
+                )));
+            }
+            let value = block.get_by_offset(0).value.index(0).unwrap();
+            Ok(matches!(value, ScalarRef::Number(NumberScalar::UInt64(0))))
+        }
+        .await;
+        result.unwrap_or_else(|cause| {
+            warn!(
+                "Failed to read the changes of {}.{}: {:?}",
+                name.database, name.table, cause
+            );
+            false
+        })
+    }
+
+    #[async_backtrace::framed]
+    async fn record_error(&self, cause: &ErrorCode) -> Result<()> {
+        let table = self.get_table().await?;
+        let mut state = dynamic_table_refresh_state(table.get_table_info())?;
+        state.last_error = Some(cause.message());
+        self.save_state(table.get_table_info(), &state).await
+    }
+
+    #[async_backtrace::framed]
+    async fn save_state(
+        &self,
+        table_info: &TableInfo,
+        state: &DynamicTableRefreshState,
+    ) -> Result<()> {
+        let req = UpsertTableOptionReq {
+            table_id: table_info.ident.table_id,
+            seq: MatchSeq::Exact(table_info.ident.seq),
+            options: HashMap::from([(
+                OPT_KEY_REFRESH_STATE.to_string(),
+                Some(serde_json::to_string(state)?),
+            )]),
+        };
+        self.catalog
+            .upsert_table_option(&self.tenant, &self.database, req)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_table(&self) -> Result<Arc<dyn Table>> {
+        self.catalog
+            .get_table(&self.tenant, &self.database, &self.table)
+            .await
+    }
+
+    #[async_backtrace::framed]
+    async fn execute(&self, sql: &str) -> Result<Vec<DataBlock>> {
+        let ctx = self.session.create_query_context().await?;
+        execute_statement(ctx, sql).await
+    }
+}
+
+/// Whether the changes after the snapshot can be read, i.e., the snapshot
+/// is committed after change tracking is enabled.
+fn is_trackable(table: &FuseTable, snapshot: &TableSnapshot) -> bool {
+    if !table.change_tracking_enabled() {
+        return false;
+    }
+    let Some(prev_table_seq) = snapshot.prev_table_seq else {
+        return false;
+    };
+    match table
+        .get_table_info()
+        .options()
+        .get(OPT_KEY_CHANGE_TRACKING_BEGIN_VER)
+    {
+        Some(begin_version) => begin_version
+            .parse::<u64>()
+            .is_ok_and(|begin_version| begin_version <= prev_table_seq + 1),
+        None => true,
+    }
+}
@@ -0,0 +1,395 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use chrono::DateTime;
+use chrono::Utc;
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_catalog::catalog::CatalogManager;
+use databend_common_config::InnerConfig;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::OwnershipObject;
+use databend_common_meta_app::principal::UserInfo;
+use databend_common_meta_app::tenant::Tenant;
+use databend_common_users::UserApiProvider;
+use databend_common_users::BUILTIN_ROLE_PUBLIC;
+use databend_storages_common_table_meta::table::DynamicTableRefreshState;
+use databend_storages_common_table_meta::table::OPT_KEY_AS_QUERY;
+use databend_storages_common_table_meta::table::OPT_KEY_TARGET_LAG;
+use futures::future::AbortHandle;
+use futures::future::Abortable;
+use log::info;
+use log::warn;
+use parking_lot::Mutex;
+
+use crate::dynamic_tables::create_refresh_session;
+use crate::dynamic_tables::dynamic_table_refresh_state;
+use crate::dynamic_tables::refresh_dynamic_table;
+
+/// How often the scheduler checks for dynamic tables to refresh.
+const DYNAMIC_TABLE_SCHEDULER_TICK: Duration = Duration::from_secs(5);
+
+/// How often the scheduler lists the dynamic tables of the tenant, the tables created or altered
+/// in between are scheduled after the next listing.
+const DYNAMIC_TABLE_LIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long the scheduler lease lasts if it is not renewed.
+const DYNAMIC_TABLE_SCHEDULER_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often the lease is renewed by its holder, or tried to be acquired by the other nodes.
+const DYNAMIC_TABLE_SCHEDULER_LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait before refreshing a dynamic table again after its refresh failed.
+const DYNAMIC_TABLE_REFRESH_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The background loop refreshing the dynamic tables of the tenant to meet their target lags.
+///
+/// Like the task scheduler, only the node holding the scheduler lease refreshes the tables,
+/// the refreshes still running on the former holder are aborted as soon as it finds the lease
+/// lost. A table is refreshed when its data would be older than its target lag by the time
+/// the refresh finishes, after the tables it reads with `TARGET_LAG = DOWNSTREAM`.
+pub struct DynamicTableScheduler {
+    conf: InnerConfig,
+    is_leader: bool,
+    lease_checked_at: Option<Instant>,
+    /// The dynamic tables found by the last listing.
+    tables: Vec<DynamicTableEntry>,
+    listed_at: Option<Instant>,
+    /// The last refresh of the dynamic tables by table id.
+    attempts: Arc<Mutex<HashMap<u64, RefreshAttempt>>>,
+    /// The dynamic tables being refreshed, they are not scheduled again until it finishes.
+    /// The tables refreshed together share the abort handle of their refresh.
+    running: Arc<Mutex<HashMap<u64, AbortHandle>>>,
+}
+
+#[derive(Clone, Copy)]
+struct RefreshAttempt {
+    duration: Duration,
+    finished_at: Instant,
+    failed: bool,
+    /// When the last successful refresh started, the listed refresh state
+    /// may be older than it.
+    data_timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+struct DynamicTableEntry {
+    catalog: String,
+    database: String,
+    db_id: u64,
+    name: String,
+    table_id: u64,
+    /// None if the table is refreshed when the tables reading it are refreshed.
+    target_lag: Option<Duration>,
+    state: DynamicTableRefreshState,
+}
+
+impl DynamicTableScheduler {
+    pub fn start(conf: InnerConfig) {
+        let scheduler = DynamicTableScheduler {
+            conf,
+            is_leader: false,
+            lease_checked_at: None,
+            tables: vec![],
+            listed_at: None,
+            attempts: Arc::new(Mutex::new(HashMap::new())),
+            running: Arc::new(Mutex::new(HashMap::new())),
+        };
+        GlobalIORuntime::instance().spawn(async move { scheduler.run().await });
+    }
+
+    async fn run(mut self) {
+        loop {
+            tokio::time::sleep(DYNAMIC_TABLE_SCHEDULER_TICK).await;
+
+            if let Err(cause) = self.schedule_refreshes().await {
+                warn!("Failed to schedule dynamic table refreshes: {:?}", cause);
+            }
+        }
+    }
+
+    #[async_backtrace::framed]
+    async fn check_lease(&mut self) {
+        if self
+            .lease_checked_at
+            .is_some_and(|t| t.elapsed() < DYNAMIC_TABLE_SCHEDULER_LEASE_RENEW_INTERVAL)
+        {
+            return;
+        }
+
+        let is_leader = UserApiProvider::instance()
+            .acquire_dynamic_table_scheduler_lease(
+                &self.conf.query.tenant_id,
+                &self.conf.query.node_id,
+                DYNAMIC_TABLE_SCHEDULER_LEASE_TTL,
+            )
+            .await;
+        self.lease_checked_at = Some(Instant::now());
+
+        let is_leader = is_leader.unwrap_or_else(|cause| {
+            warn!(
+                "Failed to acquire the dynamic table scheduler lease: {:?}",
+                cause
+            );
+            false
+        });
+        if is_leader != self.is_leader {
+            info!("Dynamic table scheduler lease acquired: {}", is_leader);
+            self.listed_at = None;
+        }
+        if !is_leader {
+            for (table_id, handle) in self.running.lock().drain() {
+                warn!(
+                    "Refresh of dynamic table {} is aborted, the dynamic table scheduler lease is lost",
+                    table_id
+                );
+                handle.abort();
+            }
+        }
+        self.is_leader = is_leader;
+    }
+
+    #[async_backtrace::framed]
+    async fn schedule_refreshes(&mut self) -> Result<()> {
+        self.check_lease().await;
+        if !self.is_leader {
+            return Ok(());
+        }
+
+        let tenant = &self.conf.query.tenant_id;
+        if self
+            .listed_at
+            .is_none_or(|t| t.elapsed() >= DYNAMIC_TABLE_LIST_INTERVAL)
+        {
+            self.tables = list_dynamic_tables(tenant).await?;
+            self.listed_at = Some(Instant::now());
+        }
+        let tables = &self.tables;
+        let tables_by_id = tables
+            .iter()
+            .map(|table| (table.table_id, table))
+            .collect::<HashMap<_, _>>();
+
+        for table in tables {
+            let Some(target_lag) = table.target_lag else {
+                continue;
+            };
+            if !self.is_due(table, target_lag) {
+                continue;
+            }
+
+            let mut chain = vec![];
+            collect_upstreams(table, &tables_by_id, &mut HashSet::new(), &mut chain);
+            chain.push(table.clone());
+            let table_name = table.name.clone();
+            let (abort_handle, registration) = AbortHandle::new_pair();
+            {
+                let mut running = self.running.lock();
+                if chain
+                    .iter()
+                    .any(|table| running.contains_key(&table.table_id))
+                {
+                    continue;
+                }
+                running.extend(
+                    chain
+                        .iter()
+                        .map(|table| (table.table_id, abort_handle.clone())),
+                );
+            }
+
+            let tenant = tenant.clone();
+            let attempts = self.attempts.clone();
+            let running = self.running.clone();
+            GlobalIORuntime::instance().spawn(async move {
+                let refreshes =
+                    Abortable::new(refresh_chain(&tenant, &chain, &attempts), registration);
+                if refreshes.await.is_err() {
+                    info!("Refresh of dynamic table {} is aborted", table_name);
+                }
+                // The entries of an aborted refresh are removed already, and may be replaced.
+                if !abort_handle.is_aborted() {
+                    let mut running = running.lock();
+                    for table in &chain {
+                        running.remove(&table.table_id);
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// A table is due if its data would exceed the target lag by the time
+    /// a refresh started now finishes, assuming it takes as long as the last one.
+    fn is_due(&self, table: &DynamicTableEntry, target_lag: Duration) -> bool {
+        let attempt = self.attempts.lock().get(&table.table_id).copied();
+        if attempt.is_some_and(|attempt| {
+            attempt.failed && attempt.finished_at.elapsed() < DYNAMIC_TABLE_REFRESH_RETRY_INTERVAL
+        }) {
+            return false;
+        }
+
+        let data_timestamp = table
+            .state
+            .data_timestamp
+            .max(attempt.and_then(|attempt| attempt.data_timestamp));
+        let Some(data_timestamp) = data_timestamp else {
+            return true;
+        };
+        let lag = (Utc::now() - data_timestamp).to_std().unwrap_or_default();
+        let duration = attempt.map(|attempt| attempt.duration).unwrap_or_default();
+        lag + duration >= target_lag
+    }
+}
+
+/// Refresh the tables in order, stops at the first failure.
+#[async_backtrace::framed]
+async fn refresh_chain(
+    tenant: &Tenant,
+    chain: &[DynamicTableEntry],
+    attempts: &Mutex<HashMap<u64, RefreshAttempt>>,
+) {
+    for table in chain {
+        let started_at = Instant::now();
+        let data_timestamp = Utc::now();
+        let result = refresh_as_owner(tenant, table).await;
+
+        {
+            let mut attempts = attempts.lock();
+            let data_timestamp = match &result {
+                Ok(_) => Some(data_timestamp),
+                Err(_) => attempts
+                    .get(&table.table_id)
+                    .and_then(|attempt| attempt.data_timestamp),
+            };
+            let attempt = RefreshAttempt {
+                duration: started_at.elapsed(),
+                finished_at: Instant::now(),
+                failed: result.is_err(),
+                data_timestamp,
+            };
+            attempts.insert(table.table_id, attempt);
+        }
+        // The tables after it read its data, which is not fresh.
+        if result.is_err() {
+            break;
+        }
+    }
+}
+
+/// Collect the dynamic tables with `TARGET_LAG = DOWNSTREAM` read by `table` transitively,
+/// in the order they should be refreshed.
+fn collect_upstreams(
+    table: &DynamicTableEntry,
+    tables_by_id: &HashMap<u64, &DynamicTableEntry>,
+    visited: &mut HashSet<u64>,
+    chain: &mut Vec<DynamicTableEntry>,
+) {
+    for source in &table.state.sources {
+        let Some(upstream) = tables_by_id.get(&source.table_id) else {
+            continue;
+        };
+        if upstream.target_lag.is_some() || !visited.insert(upstream.table_id) {
+            continue;
+        }
+        collect_upstreams(upstream, tables_by_id, visited, chain);
+        chain.push((*upstream).clone());
+    }
+}
+
+#[async_backtrace::framed]
+async fn list_dynamic_tables(tenant: &Tenant) -> Result<Vec<DynamicTableEntry>> {
+    let catalog = CatalogManager::instance().get_default_catalog(Default::default())?;
+    let mut tables = vec![];
+    for database in catalog.list_databases(tenant).await? {
+        let db_id = database.get_db_info().database_id.db_id;
+        let db_tables = match catalog.list_tables(tenant, database.name()).await {
+            Ok(tables) => tables,
+            Err(cause) => {
+                warn!(
+                    "Failed to list tables in database {}: {:?}",
+                    database.name(),
+                    cause
+                );
+                continue;
+            }
+        };
+
+        for table in db_tables {
+            let table_info = table.get_table_info();
+            if !table_info.options().contains_key(OPT_KEY_AS_QUERY) {
+                continue;
+            }
+            let state = match dynamic_table_refresh_state(table_info) {
+                Ok(state) => state,
+                Err(cause) => {
+                    warn!(
+                        "Invalid refresh state of dynamic table {}.{}: {:?}",
+                        database.name(),
+                        table.name(),
+                        cause
+                    );
+                    continue;
+                }
+            };
+            tables.push(DynamicTableEntry {
+                catalog: catalog.name(),
+                database: database.name().to_string(),
+                db_id,
+                name: table.name().to_string(),
+                table_id: table.get_id(),
+                target_lag: table_info
+                    .options()
+                    .get(OPT_KEY_TARGET_LAG)
+                    .and_then(|lag| parse_target_lag(lag)),
+                state,
+            });
+        }
+    }
+    Ok(tables)
+}
+
+/// Parse a target lag like `60 SECOND`, returns None for `DOWNSTREAM`.
+fn parse_target_lag(value: &str) -> Option<Duration> {
+    let secs = value.strip_suffix(" SECOND")?.parse::<u64>().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+/// Refresh the table in a session that can only use the owner role of the table.
+#[async_backtrace::framed]
+async fn refresh_as_owner(tenant: &Tenant, table: &DynamicTableEntry) -> Result<()> {
+    let owner = UserApiProvider::instance()
+        .get_ownership(tenant, &OwnershipObject::Table {
+            catalog_name: table.catalog.clone(),
+            db_id: table.db_id,
+            table_id: table.table_id,
+        })
+        .await?;
+    let role = owner
+        .map(|owner| owner.role)
+        .unwrap_or_else(|| BUILTIN_ROLE_PUBLIC.to_string());
+
+    let user = UserInfo::new_no_auth(
+        &format!("{}-dynamic-table-svc", tenant.tenant_name()),
+        "0.0.0.0",
+    );
+    let session = create_refresh_session(user, Some(role)).await?;
+    refresh_dynamic_table(session, &table.catalog, &table.database, &table.name).await?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod dynamic_table_query;
+mod dynamic_table_refresh;
+mod dynamic_table_scheduler;
+
+pub use dynamic_table_query::AggregateColumn;
+pub use dynamic_table_query::DynamicTableQuery;
+pub use dynamic_table_query::IncrementalShape;
+pub use dynamic_table_query::SourceName;
+pub use dynamic_table_query::SourcePin;
+pub use dynamic_table_refresh::create_refresh_session;
+pub use dynamic_table_refresh::dynamic_table_refresh_state;
+pub use dynamic_table_refresh::refresh_dynamic_table;
+pub use dynamic_table_scheduler::DynamicTableScheduler;
@@ -73,6 +73,8 @@ impl AccessChecker for ManagementModeAccess {
 
                 // Set
                 | Plan::Set(_)
+                | Plan::Prepare(_)
+                | Plan::Deallocate(_)
 
                 // Database.
                 | Plan::CreateDatabase(_)
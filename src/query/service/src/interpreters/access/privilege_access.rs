@@ -1022,6 +1022,12 @@ impl AccessChecker for PrivilegeAccess {
             Plan::DropTableClusterKey(plan) => {
                 self.validate_table_access(&plan.catalog, &plan.database, &plan.table, UserPrivilegeType::Drop, false, false).await?
             }
+            Plan::AddTableConstraint(plan) => {
+                self.validate_table_access(&plan.catalog, &plan.database, &plan.table, UserPrivilegeType::Alter, false, false).await?
+            }
+            Plan::DropTableConstraint(plan) => {
+                self.validate_table_access(&plan.catalog, &plan.database, &plan.table, UserPrivilegeType::Alter, false, false).await?
+            }
             Plan::ReclusterTable(plan) => {
                 self.validate_table_access(&plan.catalog, &plan.database, &plan.table, UserPrivilegeType::Alter, false, false).await?
             }
@@ -1165,6 +1171,9 @@ impl AccessChecker for PrivilegeAccess {
             Plan::CreateDynamicTable(plan) => {
                 self.validate_db_access(&plan.catalog, &plan.database, UserPrivilegeType::Create, false).await?;
             }
+            Plan::RefreshDynamicTable(plan) => {
+                self.validate_table_access(&plan.catalog, &plan.database, &plan.table, UserPrivilegeType::Insert, false, false).await?
+            }
             Plan::CreateUser(_) => {
                 self.validate_access(
                     &GrantObject::Global,
@@ -1269,6 +1278,10 @@ impl AccessChecker for PrivilegeAccess {
             | Plan::ShowConnections(_)
             | Plan::DescConnection(_)
             | Plan::DropConnection(_)
+            | Plan::CreatePipe(_)
+            | Plan::AlterPipe(_)
+            | Plan::DropPipe(_)
+            | Plan::DescPipe(_)
             | Plan::CreateIndex(_)
             | Plan::CreateTableIndex(_)
             | Plan::CreateNotification(_)
@@ -1298,6 +1311,7 @@ impl AccessChecker for PrivilegeAccess {
             // Note: No need to check privileges
             // SET ROLE & SHOW ROLES is a session-local statement (have same semantic with the SET ROLE in postgres), no need to check privileges
             Plan::SetRole(_) => {}
+            Plan::Prepare(_) | Plan::Deallocate(_) => {}
             Plan::SetSecondaryRoles(_) => {}
             Plan::Presign(plan) => {
                 let privilege = match &plan.action {
@@ -1324,6 +1338,9 @@ impl AccessChecker for PrivilegeAccess {
             }
             Plan::Commit => {}
             Plan::Abort => {}
+            Plan::Savepoint { .. } => {}
+            Plan::RollbackToSavepoint { .. } => {}
+            Plan::ReleaseSavepoint { .. } => {}
             Plan::ShowWarehouses => {
                 // check privilege in interpreter
             }
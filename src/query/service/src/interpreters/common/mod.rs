@@ -31,7 +31,9 @@ pub use stream::query_build_update_stream_req;
 pub use table::check_referenced_computed_columns;
 pub use task::get_task_client_config;
 pub use task::make_schedule_options;
+pub use task::make_task_schedule;
 pub use task::make_warehouse_options;
+pub use task::split_task_sql;
 pub use util::check_deduplicate_label;
 
 pub use self::metrics::*;
@@ -31,6 +31,7 @@ use databend_common_storages_fuse::FUSE_OPT_KEY_ROW_AVG_DEPTH_THRESHOLD;
 use databend_common_storages_fuse::FUSE_OPT_KEY_ROW_PER_BLOCK;
 use databend_common_storages_fuse::FUSE_OPT_KEY_ROW_PER_PAGE;
 use databend_storages_common_index::BloomIndex;
+use databend_storages_common_table_meta::table::OPT_KEY_AS_QUERY;
 use databend_storages_common_table_meta::table::OPT_KEY_BLOOM_INDEX_COLUMNS;
 use databend_storages_common_table_meta::table::OPT_KEY_CHANGE_TRACKING;
 use databend_storages_common_table_meta::table::OPT_KEY_CLUSTER_TYPE;
@@ -39,14 +40,22 @@ use databend_storages_common_table_meta::table::OPT_KEY_CONNECTION_NAME;
 use databend_storages_common_table_meta::table::OPT_KEY_DATABASE_ID;
 use databend_storages_common_table_meta::table::OPT_KEY_ENABLE_COPY_DEDUP_FULL_PATH;
 use databend_storages_common_table_meta::table::OPT_KEY_ENGINE;
+use databend_storages_common_table_meta::table::OPT_KEY_INITIALIZE;
 use databend_storages_common_table_meta::table::OPT_KEY_LOCATION;
+use databend_storages_common_table_meta::table::OPT_KEY_PARTITION_BY;
 use databend_storages_common_table_meta::table::OPT_KEY_RANDOM_MAX_ARRAY_LEN;
 use databend_storages_common_table_meta::table::OPT_KEY_RANDOM_MAX_STRING_LEN;
 use databend_storages_common_table_meta::table::OPT_KEY_RANDOM_MIN_STRING_LEN;
 use databend_storages_common_table_meta::table::OPT_KEY_RANDOM_SEED;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_MODE;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_STATE;
 use databend_storages_common_table_meta::table::OPT_KEY_STORAGE_FORMAT;
 use databend_storages_common_table_meta::table::OPT_KEY_TABLE_COMPRESSION;
+use databend_storages_common_table_meta::table::OPT_KEY_TARGET_LAG;
 use databend_storages_common_table_meta::table::OPT_KEY_TEMP_PREFIX;
+use databend_storages_common_table_meta::table::OPT_KEY_TIMESTAMP_AS_OF;
+use databend_storages_common_table_meta::table::OPT_KEY_VERSION_AS_OF;
+use databend_storages_common_table_meta::table::OPT_KEY_WAREHOUSE;
 use log::error;
 
 /// Table option keys that can occur in 'create table statement'.
@@ -78,6 +87,18 @@ pub static CREATE_FUSE_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(
     r
 });
 
+/// Table option keys that can occur in 'create dynamic table statement'.
+pub static CREATE_DYNAMIC_TABLE_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    let mut r = CREATE_FUSE_OPTIONS.clone();
+    r.insert(OPT_KEY_AS_QUERY);
+    r.insert(OPT_KEY_TARGET_LAG);
+    r.insert(OPT_KEY_REFRESH_MODE);
+    r.insert(OPT_KEY_INITIALIZE);
+    r.insert(OPT_KEY_WAREHOUSE);
+    r.insert(OPT_KEY_REFRESH_STATE);
+    r
+});
+
 pub static CREATE_LAKE_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     let mut r = HashSet::new();
     r.insert(OPT_KEY_ENGINE);
@@ -86,6 +107,19 @@ pub static CREATE_LAKE_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(
     r
 });
 
+pub static CREATE_ICEBERG_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    let mut r = CREATE_LAKE_OPTIONS.clone();
+    r.insert(OPT_KEY_PARTITION_BY);
+    r
+});
+
+pub static CREATE_DELTA_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    let mut r = CREATE_LAKE_OPTIONS.clone();
+    r.insert(OPT_KEY_VERSION_AS_OF);
+    r.insert(OPT_KEY_TIMESTAMP_AS_OF);
+    r
+});
+
 pub static CREATE_RANDOM_OPTIONS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     let mut r = HashSet::new();
     r.insert(OPT_KEY_ENGINE);
@@ -123,7 +157,8 @@ pub fn is_valid_create_opt<S: AsRef<str>>(opt_key: S, engine: &Engine) -> bool {
     let opt_key = opt_key.as_str();
     match engine {
         Engine::Fuse => CREATE_FUSE_OPTIONS.contains(opt_key),
-        Engine::Iceberg | Engine::Delta => CREATE_LAKE_OPTIONS.contains(&opt_key),
+        Engine::Iceberg => CREATE_ICEBERG_OPTIONS.contains(&opt_key),
+        Engine::Delta => CREATE_DELTA_OPTIONS.contains(&opt_key),
         Engine::Random => CREATE_RANDOM_OPTIONS.contains(&opt_key),
         Engine::Memory => CREATE_MEMORY_OPTIONS.contains(&opt_key),
         Engine::Null | Engine::View => opt_key == OPT_KEY_ENGINE,
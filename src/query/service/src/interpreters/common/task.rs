@@ -16,11 +16,13 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use databend_common_ast::ast::ScheduleOptions;
+use databend_common_ast::ast::TaskSql;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_cloud_control::client_config::build_client_config;
 use databend_common_cloud_control::client_config::ClientConfig;
 use databend_common_cloud_control::pb::schedule_options::ScheduleType;
 use databend_common_exception::Result;
+use databend_common_meta_app::principal::TaskSchedule;
 
 use crate::sessions::QueryContext;
 
@@ -51,6 +53,22 @@ pub fn make_schedule_options(
     }
 }
 
+pub fn make_task_schedule(opt: ScheduleOptions) -> TaskSchedule {
+    match opt {
+        ScheduleOptions::IntervalSecs(secs, millis) => TaskSchedule::Interval { secs, millis },
+        ScheduleOptions::CronExpression(expr, time_zone) => TaskSchedule::Cron { expr, time_zone },
+    }
+}
+
+/// Returns the SQL text of the task and the statements of its script block,
+/// the statements are empty if the task is a single statement.
+pub fn split_task_sql(sql: &TaskSql) -> (String, Vec<String>) {
+    match sql {
+        TaskSql::SingleStatement(stmt) => (stmt.clone(), vec![]),
+        TaskSql::ScriptBlock(sqls) => (format!("{}", sql), sqls.clone()),
+    }
+}
+
 pub fn make_warehouse_options(
     opt: Option<String>,
 ) -> databend_common_cloud_control::pb::WarehouseOptions {
@@ -32,6 +32,7 @@ use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_exception::ResultExt;
+use databend_common_expression::DataBlock;
 use databend_common_expression::SendableDataBlockStream;
 use databend_common_pipeline_core::always_callback;
 use databend_common_pipeline_core::processors::PlanProfile;
@@ -44,6 +45,7 @@ use databend_common_storages_system::ProfilesLogElement;
 use databend_common_storages_system::ProfilesLogQueue;
 use derive_visitor::DriveMut;
 use derive_visitor::VisitorMut;
+use futures_util::TryStreamExt;
 use log::error;
 use log::info;
 use md5::Digest;
@@ -52,6 +54,7 @@ use md5::Md5;
 use super::hook::vacuum_hook::hook_clear_m_cte_temp_table;
 use super::hook::vacuum_hook::hook_disk_temp_dir;
 use super::hook::vacuum_hook::hook_vacuum_temp_files;
+use super::InterpreterFactory;
 use super::InterpreterMetrics;
 use super::InterpreterQueryLog;
 use crate::pipelines::executor::ExecutorSettings;
@@ -215,6 +218,42 @@ pub async fn interpreter_plan_sql(
     acquire_queue: bool,
 ) -> Result<(Plan, PlanExtras, AcquireQueueGuard)> {
     let result = plan_sql(ctx.clone(), sql, acquire_queue).await;
+    log_plan_result(&ctx, sql, result)
+}
+
+/// Like `interpreter_plan_sql`, but the statement is already parsed, e.g. a prepared
+/// statement whose parameters are bound.
+pub async fn interpreter_plan_stmt(
+    ctx: Arc<QueryContext>,
+    sql: &str,
+    statement: Statement,
+    acquire_queue: bool,
+) -> Result<(Plan, PlanExtras, AcquireQueueGuard)> {
+    let planner = create_planner(&ctx);
+    let extras = PlanExtras {
+        format: None,
+        statement,
+    };
+    let result = plan_extras(ctx.clone(), planner, extras, acquire_queue).await;
+    log_plan_result(&ctx, sql, result)
+}
+
+/// Plan and execute the SQL in `ctx`, returns the result blocks.
+///
+/// It runs the statements of the background jobs, like tasks and dynamic table refreshes.
+#[async_backtrace::framed]
+pub async fn execute_statement(ctx: Arc<QueryContext>, sql: &str) -> Result<Vec<DataBlock>> {
+    let (plan, _, _) = interpreter_plan_sql(ctx.clone(), sql, false).await?;
+    let interpreter = InterpreterFactory::get(ctx.clone(), &plan).await?;
+    let stream = interpreter.execute(ctx.clone()).await?;
+    stream.try_collect::<Vec<_>>().await
+}
+
+fn log_plan_result(
+    ctx: &Arc<QueryContext>,
+    sql: &str,
+    result: Result<(Plan, PlanExtras, AcquireQueueGuard)>,
+) -> Result<(Plan, PlanExtras, AcquireQueueGuard)> {
     let short_sql = short_sql(
         sql.to_string(),
         ctx.get_settings().get_short_sql_max_length()?,
@@ -224,30 +263,43 @@ pub async fn interpreter_plan_sql(
     } else {
         // Only log if there's an error
         ctx.attach_query_str(QueryKind::Unknown, short_sql.to_string());
-        log_query_start(&ctx);
-        log_query_finished(&ctx, result.as_ref().err().cloned(), false);
+        log_query_start(ctx);
+        log_query_finished(ctx, result.as_ref().err().cloned(), false);
         None
     };
 
-    attach_query_hash(&ctx, &mut stmt, &short_sql);
+    attach_query_hash(ctx, &mut stmt, &short_sql);
 
     result
 }
 
+fn create_planner(ctx: &Arc<QueryContext>) -> Planner {
+    Planner::new_with_query_executor(
+        ctx.clone(),
+        Arc::new(ServiceQueryExecutor::new(QueryContext::create_from(
+            ctx.as_ref(),
+        ))),
+    )
+}
+
 async fn plan_sql(
     ctx: Arc<QueryContext>,
     sql: &str,
     acquire_queue: bool,
 ) -> Result<(Plan, PlanExtras, AcquireQueueGuard)> {
-    let mut planner = Planner::new_with_query_executor(
-        ctx.clone(),
-        Arc::new(ServiceQueryExecutor::new(QueryContext::create_from(
-            ctx.as_ref(),
-        ))),
-    );
+    let planner = create_planner(&ctx);
 
     // Parse the SQL query, get extract additional information.
     let extras = planner.parse_sql(sql)?;
+    plan_extras(ctx, planner, extras, acquire_queue).await
+}
+
+async fn plan_extras(
+    ctx: Arc<QueryContext>,
+    mut planner: Planner,
+    extras: PlanExtras,
+    acquire_queue: bool,
+) -> Result<(Plan, PlanExtras, AcquireQueueGuard)> {
     if !acquire_queue {
         // If queue guard is not required, plan the statement directly.
         let plan = planner.plan_stmt(&extras.statement).await?;
@@ -109,15 +109,20 @@ impl CopyIntoTableInterpreter {
                 &plan.table_name,
             )
             .await?;
-        let snapshot = FuseTable::try_from_table(to_table.as_ref())?
-            .read_table_snapshot()
-            .await?;
-        let table_meta_timestamps = self
-            .ctx
-            .get_table_meta_timestamps(to_table.as_ref(), snapshot)?;
+        let table_meta_timestamps = if to_table.engine() == "FUSE" {
+            let snapshot = FuseTable::try_from_table(to_table.as_ref())?
+                .read_table_snapshot()
+                .await?;
+            self.ctx
+                .get_table_meta_timestamps(to_table.as_ref(), snapshot)?
+        } else {
+            Default::default()
+        };
+        // the keys of the rows copied on different nodes can not be checked against each other
+        let enable_distributed = plan.enable_distributed && to_table.support_distributed_insert();
         let mut update_stream_meta_reqs = vec![];
         let (source, project_columns) = if let Some(ref query) = plan.query {
-            let query = if plan.enable_distributed {
+            let query = if enable_distributed {
                 query.remove_exchange_for_select()
             } else {
                 *query.clone()
@@ -173,7 +178,7 @@ impl CopyIntoTableInterpreter {
             table_meta_timestamps,
         }));
 
-        if plan.enable_distributed {
+        if enable_distributed {
             root = PhysicalPlan::Exchange(Exchange {
                 plan_id: 0,
                 input: Box::new(root),
@@ -264,11 +269,15 @@ impl CopyIntoTableInterpreter {
                 path_prefix,
             )?;
 
-            let fuse_table = FuseTable::try_from_table(to_table.as_ref())?;
-            let table_meta_timestamps = ctx.get_table_meta_timestamps(
-                to_table.as_ref(),
-                fuse_table.read_table_snapshot().await?,
-            )?;
+            let table_meta_timestamps = if to_table.engine() == "FUSE" {
+                let fuse_table = FuseTable::try_from_table(to_table.as_ref())?;
+                ctx.get_table_meta_timestamps(
+                    to_table.as_ref(),
+                    fuse_table.read_table_snapshot().await?,
+                )?
+            } else {
+                Default::default()
+            };
             to_table.commit_insertion(
                 ctx.clone(),
                 main_pipeline,
@@ -0,0 +1,290 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use databend_common_ast::ast::Engine;
+use databend_common_ast::ast::InitializeMode;
+use databend_common_ast::ast::RefreshMode;
+use databend_common_catalog::catalog::Catalog;
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::TableSchemaRefExt;
+use databend_common_management::RoleApi;
+use databend_common_meta_app::principal::OwnershipObject;
+use databend_common_meta_app::schema::CreateTableReq;
+use databend_common_meta_app::schema::TableMeta;
+use databend_common_meta_app::schema::TableNameIdent;
+use databend_common_meta_app::schema::UpsertTableOptionReq;
+use databend_common_meta_types::MatchSeq;
+use databend_common_sql::field_default_value;
+use databend_common_sql::plans::CreateDynamicTablePlan;
+use databend_common_sql::NameResolutionContext;
+use databend_common_storages_fuse::FuseStorageFormat;
+use databend_common_storages_fuse::FuseTable;
+use databend_common_users::RoleCacheManager;
+use databend_common_users::UserApiProvider;
+use databend_storages_common_table_meta::table::DynamicTableRefreshState;
+use databend_storages_common_table_meta::table::DynamicTableSource;
+use databend_storages_common_table_meta::table::OPT_KEY_CHANGE_TRACKING;
+use databend_storages_common_table_meta::table::OPT_KEY_CHANGE_TRACKING_BEGIN_VER;
+use databend_storages_common_table_meta::table::OPT_KEY_COMMENT;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_STATE;
+use databend_storages_common_table_meta::table::OPT_KEY_STORAGE_FORMAT;
+
+use crate::dynamic_tables::create_refresh_session;
+use crate::dynamic_tables::refresh_dynamic_table;
+use crate::dynamic_tables::DynamicTableQuery;
+use crate::interpreters::common::table_option_validation::is_valid_block_per_segment;
+use crate::interpreters::common::table_option_validation::is_valid_bloom_index_columns;
+use crate::interpreters::common::table_option_validation::is_valid_change_tracking;
+use crate::interpreters::common::table_option_validation::is_valid_data_retention_period;
+use crate::interpreters::common::table_option_validation::is_valid_row_per_block;
+use crate::interpreters::common::table_option_validation::CREATE_DYNAMIC_TABLE_OPTIONS;
+use crate::interpreters::interpreter_table_create::is_valid_column;
+use crate::interpreters::Interpreter;
+use crate::pipelines::PipelineBuildResult;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+pub struct CreateDynamicTableInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: CreateDynamicTablePlan,
+}
+
+impl CreateDynamicTableInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: CreateDynamicTablePlan) -> Result<Self> {
+        Ok(CreateDynamicTableInterpreter { ctx, plan })
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for CreateDynamicTableInterpreter {
+    fn name(&self) -> &str {
+        "CreateDynamicTableInterpreter"
+    }
+
+    fn is_ddl(&self) -> bool {
+        true
+    }
+
+    #[async_backtrace::framed]
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        let tenant = &self.plan.tenant;
+        let settings = self.ctx.get_settings();
+        let query = DynamicTableQuery::try_create(
+            &self.plan.as_query,
+            settings.get_sql_dialect()?,
+            NameResolutionContext::try_from(settings.as_ref())?,
+        )?;
+        if self.plan.refresh_mode == RefreshMode::Incremental && query.shape().is_none() {
+            return Err(ErrorCode::IllegalDynamicTable(format!(
+                "The query of dynamic table '{}'.'{}' can not be refreshed incrementally, \
+                only filters, projections, inner joins and COUNT/SUM/MIN/MAX aggregations are supported",
+                self.plan.database, self.plan.table
+            )));
+        }
+        let incremental = self.plan.refresh_mode != RefreshMode::Full && query.shape().is_some();
+
+        // The changes of the sources are read by incremental refreshes,
+        // so change tracking is enabled on them like creating streams.
+        let mut sources = vec![];
+        for name in query.sources() {
+            let catalog_name = name
+                .catalog
+                .clone()
+                .unwrap_or_else(|| self.ctx.get_current_catalog());
+            let catalog = self.ctx.get_catalog(&catalog_name).await?;
+            let mut source = catalog
+                .get_table(tenant, &name.database, &name.table)
+                .await?;
+            if incremental {
+                source = self
+                    .enable_change_tracking(catalog.as_ref(), &name.database, source)
+                    .await?;
+            }
+            sources.push(DynamicTableSource {
+                catalog: catalog_name,
+                database: name.database,
+                table: name.table,
+                table_id: source.get_id(),
+                snapshot_id: None,
+                trackable: false,
+            });
+        }
+
+        let catalog = self.ctx.get_catalog(&self.plan.catalog).await?;
+        let state = DynamicTableRefreshState {
+            sources,
+            ..Default::default()
+        };
+        let reply = catalog.create_table(self.build_request(&state)?).await?;
+        if !reply.new_table {
+            return Ok(PipelineBuildResult::create());
+        }
+
+        // grant the ownership of the table to the current role.
+        let current_role = self.ctx.get_current_role();
+        if let Some(current_role) = &current_role {
+            let role_api = UserApiProvider::instance().role_api(tenant);
+            role_api
+                .grant_ownership(
+                    &OwnershipObject::Table {
+                        catalog_name: self.plan.catalog.clone(),
+                        db_id: reply.db_id,
+                        table_id: reply.table_id,
+                    },
+                    &current_role.name,
+                )
+                .await?;
+            RoleCacheManager::instance().invalidate_cache(tenant);
+        }
+
+        if self.plan.initialize == InitializeMode::OnCreate {
+            let session = create_refresh_session(
+                self.ctx.get_current_user()?,
+                current_role.map(|role| role.name),
+            )
+            .await?;
+            refresh_dynamic_table(
+                session,
+                &self.plan.catalog,
+                &self.plan.database,
+                &self.plan.table,
+            )
+            .await?;
+        }
+
+        Ok(PipelineBuildResult::create())
+    }
+}
+
+impl CreateDynamicTableInterpreter {
+    /// Enable change tracking on a source if it is a fuse table that can have streams.
+    #[async_backtrace::framed]
+    async fn enable_change_tracking(
+        &self,
+        catalog: &dyn Catalog,
+        database: &str,
+        source: Arc<dyn Table>,
+    ) -> Result<Arc<dyn Table>> {
+        let source_info = source.get_table_info();
+        let trackable = FuseTable::try_from_table(source.as_ref()).is_ok()
+            && !source_info.options().contains_key("TRANSIENT")
+            && !source.is_temp();
+        if !trackable {
+            if self.plan.refresh_mode == RefreshMode::Incremental {
+                return Err(ErrorCode::IllegalDynamicTable(format!(
+                    "The changes of table '{}'.'{}' can not be tracked, \
+                    dynamic table '{}'.'{}' can not be refreshed incrementally",
+                    database,
+                    source.name(),
+                    self.plan.database,
+                    self.plan.table
+                )));
+            }
+            return Ok(source);
+        }
+        if source.change_tracking_enabled() {
+            return Ok(source);
+        }
+
+        let table_seq = source_info.ident.seq;
+        let req = UpsertTableOptionReq {
+            table_id: source_info.ident.table_id,
+            seq: MatchSeq::Exact(table_seq),
+            options: HashMap::from([
+                (
+                    OPT_KEY_CHANGE_TRACKING.to_string(),
+                    Some("true".to_string()),
+                ),
+                (
+                    OPT_KEY_CHANGE_TRACKING_BEGIN_VER.to_string(),
+                    Some(table_seq.to_string()),
+                ),
+            ]),
+        };
+        catalog
+            .upsert_table_option(&self.plan.tenant, database, req)
+            .await?;
+        source.refresh(self.ctx.as_ref()).await
+    }
+
+    /// Build CreateTableReq from CreateDynamicTablePlan, like `CreateTableInterpreter`
+    /// but with the options of dynamic tables.
+    fn build_request(&self, state: &DynamicTableRefreshState) -> Result<CreateTableReq> {
+        let fields = self.plan.schema.fields().clone();
+        for field in fields.iter() {
+            if field.default_expr().is_some() {
+                let _ = field_default_value(self.ctx.clone(), field)?;
+            }
+            is_valid_column(field.name())?;
+        }
+        let field_comments = if self.plan.field_comments.is_empty() {
+            vec!["".to_string(); fields.len()]
+        } else {
+            self.plan.field_comments.clone()
+        };
+        let schema = TableSchemaRefExt::create(fields);
+
+        let mut options = self.plan.options.clone();
+        options.insert(
+            OPT_KEY_REFRESH_STATE.to_string(),
+            serde_json::to_string(state)?,
+        );
+        for key in options.keys() {
+            if !CREATE_DYNAMIC_TABLE_OPTIONS.contains(key.to_lowercase().as_str()) {
+                return Err(ErrorCode::TableOptionInvalid(format!(
+                    "table option {key} is invalid for create dynamic table statement"
+                )));
+            }
+        }
+        if let Some(storage_format) = options.get(OPT_KEY_STORAGE_FORMAT) {
+            FuseStorageFormat::from_str(storage_format)?;
+        }
+        is_valid_block_per_segment(&options)?;
+        is_valid_row_per_block(&options)?;
+        is_valid_bloom_index_columns(&options, schema.clone())?;
+        is_valid_change_tracking(&options)?;
+        is_valid_data_retention_period(&options)?;
+        let comment = options.remove(OPT_KEY_COMMENT);
+
+        let mut table_meta = TableMeta {
+            schema,
+            engine: Engine::Fuse.to_string(),
+            options,
+            field_comments,
+            comment: comment.unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Some(cluster_key) = &self.plan.cluster_key {
+            table_meta.cluster_key = Some(cluster_key.clone());
+            table_meta.cluster_key_seq += 1;
+        }
+
+        Ok(CreateTableReq {
+            create_option: self.plan.create_option,
+            name_ident: TableNameIdent {
+                tenant: self.plan.tenant.clone(),
+                db_name: self.plan.database.clone(),
+                table_name: self.plan.table.clone(),
+            },
+            table_meta,
+            as_dropped: false,
+        })
+    }
+}
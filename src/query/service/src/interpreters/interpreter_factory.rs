@@ -58,6 +58,10 @@ use crate::interpreters::interpreter_notification_alter::AlterNotificationInterp
 use crate::interpreters::interpreter_notification_create::CreateNotificationInterpreter;
 use crate::interpreters::interpreter_notification_desc::DescNotificationInterpreter;
 use crate::interpreters::interpreter_notification_drop::DropNotificationInterpreter;
+use crate::interpreters::interpreter_pipe_alter::AlterPipeInterpreter;
+use crate::interpreters::interpreter_pipe_create::CreatePipeInterpreter;
+use crate::interpreters::interpreter_pipe_desc::DescPipeInterpreter;
+use crate::interpreters::interpreter_pipe_drop::DropPipeInterpreter;
 use crate::interpreters::interpreter_presign::PresignInterpreter;
 use crate::interpreters::interpreter_procedure_call::CallProcedureInterpreter;
 use crate::interpreters::interpreter_procedure_create::CreateProcedureInterpreter;
@@ -82,6 +86,9 @@ use crate::interpreters::interpreter_tasks_show::ShowTasksInterpreter;
 use crate::interpreters::interpreter_txn_abort::AbortInterpreter;
 use crate::interpreters::interpreter_txn_begin::BeginInterpreter;
 use crate::interpreters::interpreter_txn_commit::CommitInterpreter;
+use crate::interpreters::interpreter_txn_release_savepoint::ReleaseSavepointInterpreter;
+use crate::interpreters::interpreter_txn_rollback_to_savepoint::RollbackToSavepointInterpreter;
+use crate::interpreters::interpreter_txn_savepoint::SavepointInterpreter;
 use crate::interpreters::interpreter_unassign_warehouse_nodes::UnassignWarehouseNodesInterpreter;
 use crate::interpreters::interpreter_use_warehouse::UseWarehouseInterpreter;
 use crate::interpreters::interpreter_view_describe::DescribeViewInterpreter;
@@ -338,6 +345,12 @@ impl InterpreterFactory {
             Plan::DropTableClusterKey(drop_table_cluster_key) => Ok(Arc::new(
                 DropTableClusterKeyInterpreter::try_create(ctx, *drop_table_cluster_key.clone())?,
             )),
+            Plan::AddTableConstraint(add_table_constraint) => Ok(Arc::new(
+                AddTableConstraintInterpreter::try_create(ctx, *add_table_constraint.clone())?,
+            )),
+            Plan::DropTableConstraint(drop_table_constraint) => Ok(Arc::new(
+                DropTableConstraintInterpreter::try_create(ctx, *drop_table_constraint.clone())?,
+            )),
             Plan::ReclusterTable(recluster) => Ok(Arc::new(ReclusterTableInterpreter::try_create(
                 ctx,
                 *recluster.clone(),
@@ -409,7 +422,12 @@ impl InterpreterFactory {
             )?)),
 
             // dynamic tables
-            Plan::CreateDynamicTable(_) => Err(ErrorCode::Unimplemented("todo")),
+            Plan::CreateDynamicTable(plan) => Ok(Arc::new(
+                CreateDynamicTableInterpreter::try_create(ctx, *plan.clone())?,
+            )),
+            Plan::RefreshDynamicTable(plan) => Ok(Arc::new(
+                RefreshDynamicTableInterpreter::try_create(ctx, *plan.clone())?,
+            )),
 
             // Indexes
             Plan::CreateIndex(index) => Ok(Arc::new(CreateIndexInterpreter::try_create(
@@ -561,6 +579,11 @@ impl InterpreterFactory {
                 ctx,
                 *unset_variable.clone(),
             )?)),
+            Plan::Prepare(p) => Ok(Arc::new(PrepareInterpreter::try_create(ctx, *p.clone())?)),
+            Plan::Deallocate(p) => Ok(Arc::new(DeallocateInterpreter::try_create(
+                ctx,
+                *p.clone(),
+            )?)),
             Plan::UseDatabase(p) => Ok(Arc::new(UseDatabaseInterpreter::try_create(
                 ctx,
                 *p.clone(),
@@ -646,9 +669,26 @@ impl InterpreterFactory {
                 *p.clone(),
             )?)),
             Plan::ShowConnections(_) => Ok(Arc::new(ShowConnectionsInterpreter::try_create(ctx)?)),
+            Plan::CreatePipe(p) => Ok(Arc::new(CreatePipeInterpreter::try_create(
+                ctx,
+                *p.clone(),
+            )?)),
+            Plan::AlterPipe(p) => Ok(Arc::new(AlterPipeInterpreter::try_create(ctx, *p.clone())?)),
+            Plan::DropPipe(p) => Ok(Arc::new(DropPipeInterpreter::try_create(ctx, *p.clone())?)),
+            Plan::DescPipe(p) => Ok(Arc::new(DescPipeInterpreter::try_create(*p.clone())?)),
             Plan::Begin => Ok(Arc::new(BeginInterpreter::try_create(ctx)?)),
             Plan::Commit => Ok(Arc::new(CommitInterpreter::try_create(ctx)?)),
             Plan::Abort => Ok(Arc::new(AbortInterpreter::try_create(ctx)?)),
+            Plan::Savepoint { name } => Ok(Arc::new(SavepointInterpreter::try_create(
+                ctx,
+                name.clone(),
+            )?)),
+            Plan::RollbackToSavepoint { name } => Ok(Arc::new(
+                RollbackToSavepointInterpreter::try_create(ctx, name.clone())?,
+            )),
+            Plan::ReleaseSavepoint { name } => Ok(Arc::new(
+                ReleaseSavepointInterpreter::try_create(ctx, name.clone())?,
+            )),
             Plan::CreateNotification(p) => Ok(Arc::new(CreateNotificationInterpreter::try_create(
                 ctx,
                 *p.clone(),
@@ -195,6 +195,8 @@ impl Interpreter for InsertInterpreter {
                                 select_column_bindings,
                                 insert_schema: self.plan.dest_schema(),
                                 cast_needed: self.check_schema_cast(plan)?,
+                                overwrite: self.plan.overwrite,
+                                ignore_conflicts: self.plan.ignore_conflicts.clone(),
                                 table_meta_timestamps,
                             },
                         )));
@@ -212,6 +214,8 @@ impl Interpreter for InsertInterpreter {
                             select_column_bindings,
                             insert_schema: self.plan.dest_schema(),
                             cast_needed: self.check_schema_cast(plan)?,
+                            overwrite: self.plan.overwrite,
+                            ignore_conflicts: self.plan.ignore_conflicts.clone(),
                             table_meta_timestamps,
                         }))
                     }
@@ -257,6 +261,7 @@ impl Interpreter for InsertInterpreter {
             None,
             vec![],
             self.plan.overwrite,
+            &self.plan.ignore_conflicts,
             unsafe { self.ctx.get_settings().get_deduplicate_label()? },
             table_meta_timestamps,
         )?;
@@ -0,0 +1,81 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::PipeInfo;
+use databend_common_sql::plans::CreatePipePlan;
+use databend_common_users::UserApiProvider;
+use log::debug;
+
+use crate::interpreters::Interpreter;
+use crate::pipelines::PipelineBuildResult;
+use crate::sessions::QueryContext;
+
+#[derive(Debug)]
+pub struct CreatePipeInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: CreatePipePlan,
+}
+
+impl CreatePipeInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: CreatePipePlan) -> Result<Self> {
+        Ok(CreatePipeInterpreter { ctx, plan })
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for CreatePipeInterpreter {
+    fn name(&self) -> &str {
+        "CreatePipeInterpreter"
+    }
+
+    fn is_ddl(&self) -> bool {
+        true
+    }
+
+    #[fastrace::trace]
+    #[async_backtrace::framed]
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        debug!("ctx.id" = self.ctx.get_id().as_str(); "create_pipe_execute");
+
+        let plan = self.plan.clone();
+        let owner = self
+            .ctx
+            .get_current_role()
+            .unwrap_or_default()
+            .identity()
+            .to_string();
+        let pipe = PipeInfo {
+            name: plan.name.clone(),
+            copy_stmt: plan.copy_stmt.clone(),
+            auto_ingest: plan.auto_ingest,
+            execution_paused: false,
+            comment: plan.comment.clone(),
+            created_on: Utc::now(),
+            updated_on: None,
+            owner,
+        };
+
+        let user_mgr = UserApiProvider::instance();
+        user_mgr
+            .add_pipe(&plan.tenant, pipe, &plan.create_option)
+            .await?;
+
+        Ok(PipelineBuildResult::create())
+    }
+}
@@ -49,6 +49,7 @@ use databend_common_storage::StageFileInfo;
 use databend_common_storages_factory::Table;
 use databend_common_storages_fuse::FuseTable;
 use databend_storages_common_table_meta::readers::snapshot_reader::TableSnapshotAccessor;
+use databend_storages_common_table_meta::table::get_table_constraints;
 use databend_storages_common_table_meta::table::ClusterType;
 use parking_lot::RwLock;
 
@@ -358,6 +359,18 @@ impl ReplaceInterpreter {
             }));
         }
 
+        // the keys containing all the ON CONFLICT columns are kept unique by replacing, while
+        // the other keys of the table are checked before commit
+        let check_unique_keys = get_table_constraints(table.options())?
+            .iter()
+            .filter_map(|constraint| constraint.key_columns())
+            .any(|columns| {
+                !plan
+                    .on_conflict_fields
+                    .iter()
+                    .all(|field| columns.iter().any(|column| column == field.name()))
+            });
+
         root = Box::new(PhysicalPlan::CommitSink(Box::new(CommitSink {
             input: root,
             snapshot: base_snapshot,
@@ -371,6 +384,7 @@ impl ReplaceInterpreter {
             plan_id: u32::MAX,
             table_meta_timestamps,
             recluster_info: None,
+            check_unique_keys,
         })));
         root.adjust_plan_id(&mut 0);
         Ok((root, purge_info))
@@ -0,0 +1,181 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_catalog::table::TableExt;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_meta_app::schema::UpdateTableMetaReq;
+use databend_common_meta_types::MatchSeq;
+use databend_common_sql::plans::AddTableConstraintPlan;
+use databend_common_sql::Planner;
+use databend_common_storages_fuse::FuseTable;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::set_table_constraints;
+use futures_util::TryStreamExt;
+
+use super::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::pipelines::PipelineBuildResult;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+pub struct AddTableConstraintInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: AddTableConstraintPlan,
+}
+
+impl AddTableConstraintInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: AddTableConstraintPlan) -> Result<Self> {
+        Ok(AddTableConstraintInterpreter { ctx, plan })
+    }
+
+    // Find a key of the stored rows which violates the constraint, if any.
+    #[async_backtrace::framed]
+    async fn find_duplicated_key(&self, key_columns: &[String]) -> Result<Option<String>> {
+        let columns = key_columns
+            .iter()
+            .map(|c| format!("`{}`", c))
+            .collect::<Vec<_>>();
+        // rows with a NULL key column never conflict
+        let query = format!(
+            "SELECT {} FROM `{}`.`{}`.`{}` WHERE {} GROUP BY {} HAVING count(*) > 1 LIMIT 1",
+            columns.join(", "),
+            self.plan.catalog,
+            self.plan.database,
+            self.plan.table,
+            columns
+                .iter()
+                .map(|c| format!("{} IS NOT NULL", c))
+                .collect::<Vec<_>>()
+                .join(" AND "),
+            columns.join(", "),
+        );
+
+        let block = self.execute_query(&query).await?;
+        if block.num_rows() == 0 {
+            return Ok(None);
+        }
+
+        let values = block
+            .columns()
+            .iter()
+            .map(|entry| entry.value.index(0).unwrap().to_string())
+            .collect::<Vec<_>>();
+        Ok(Some(format!(
+            "({})=({})",
+            key_columns.join(", "),
+            values.join(", ")
+        )))
+    }
+
+    // Whether some of the stored rows violate the check expression, rows for which the
+    // expression is NULL satisfy it.
+    #[async_backtrace::framed]
+    async fn has_violated_row(&self, check_expr: &str) -> Result<bool> {
+        let query = format!(
+            "SELECT 1 FROM `{}`.`{}`.`{}` WHERE NOT ({}) LIMIT 1",
+            self.plan.catalog, self.plan.database, self.plan.table, check_expr,
+        );
+        let block = self.execute_query(&query).await?;
+        Ok(block.num_rows() > 0)
+    }
+
+    #[async_backtrace::framed]
+    async fn execute_query(&self, query: &str) -> Result<DataBlock> {
+        let ctx = self
+            .ctx
+            .get_current_session()
+            .create_query_context()
+            .await?;
+        let mut planner = Planner::new(ctx.clone());
+        let (plan, _) = planner.plan_sql(query).await?;
+        let interpreter = InterpreterFactory::get(ctx.clone(), &plan).await?;
+        let stream = interpreter.execute(ctx).await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        DataBlock::concat(&blocks)
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for AddTableConstraintInterpreter {
+    fn name(&self) -> &str {
+        "AddTableConstraintInterpreter"
+    }
+
+    fn is_ddl(&self) -> bool {
+        true
+    }
+
+    #[async_backtrace::framed]
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        let plan = &self.plan;
+        let tenant = self.ctx.get_tenant();
+        let catalog = self.ctx.get_catalog(&plan.catalog).await?;
+
+        let table = catalog
+            .get_table(&tenant, &plan.database, &plan.table)
+            .await?;
+        // check mutability
+        table.check_mutable()?;
+
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        let table_info = fuse_table.get_table_info();
+        let mut constraints = get_table_constraints(table_info.options())?;
+        if constraints.iter().any(|c| c.name == plan.constraint.name) {
+            return Err(ErrorCode::ConstraintAlreadyExists(format!(
+                "Constraint '{}' of table '{}' already exists",
+                plan.constraint.name, plan.table
+            )));
+        }
+        if plan.constraint.is_primary_key() && constraints.iter().any(|c| c.is_primary_key()) {
+            return Err(ErrorCode::ConstraintAlreadyExists(format!(
+                "Multiple primary keys for table '{}' are not allowed",
+                plan.table
+            )));
+        }
+
+        if let Some(key_columns) = plan.constraint.key_columns() {
+            if let Some(key) = self.find_duplicated_key(key_columns).await? {
+                return Err(ErrorCode::ConstraintViolation(format!(
+                    "Could not add constraint '{}', the key {} is duplicated",
+                    plan.constraint.name, key
+                )));
+            }
+        }
+        if let Some(check_expr) = plan.constraint.check_expr() {
+            if self.has_violated_row(check_expr).await? {
+                return Err(ErrorCode::ConstraintViolation(format!(
+                    "Could not add constraint '{}', existing rows violate it",
+                    plan.constraint.name
+                )));
+            }
+        }
+
+        constraints.push(plan.constraint.clone());
+        let mut new_table_meta = table_info.meta.clone();
+        set_table_constraints(&mut new_table_meta.options, &constraints)?;
+
+        let req = UpdateTableMetaReq {
+            table_id: table_info.ident.table_id,
+            seq: MatchSeq::Exact(table_info.ident.seq),
+            new_table_meta,
+        };
+        catalog.update_single_table_meta(req, table_info).await?;
+
+        Ok(PipelineBuildResult::create())
+    }
+}
@@ -18,6 +18,8 @@ use std::sync::Arc;
 use chrono::Utc;
 use databend_common_ast::ast::Engine;
 use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_catalog::catalog::Catalog;
+use databend_common_catalog::catalog_kind::CATALOG_DEFAULT;
 use databend_common_config::GlobalConfig;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
@@ -31,6 +33,7 @@ use databend_common_management::RoleApi;
 use databend_common_meta_app::principal::OwnershipObject;
 use databend_common_meta_app::schema::CommitTableMetaReq;
 use databend_common_meta_app::schema::CreateOption;
+use databend_common_meta_app::schema::CreateTableReply;
 use databend_common_meta_app::schema::CreateTableReq;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
@@ -182,7 +185,19 @@ impl CreateTableInterpreter {
         req.as_dropped = true;
         req.table_meta.drop_on = Some(Utc::now());
         let table_meta = req.table_meta.clone();
-        let reply = catalog.create_table(req.clone()).await?;
+        // In a transaction, the table is made visible when the transaction commits.
+        let in_txn = self.create_in_txn(&req);
+        let reply = if in_txn {
+            match self
+                .create_table_in_txn(catalog.as_ref(), req.clone())
+                .await?
+            {
+                Some(reply) => reply,
+                None => return Ok(PipelineBuildResult::create()),
+            }
+        } else {
+            catalog.create_table(req.clone()).await?
+        };
         if !reply.new_table && self.plan.create_option != CreateOption::CreateOrReplace {
             return Ok(PipelineBuildResult::create());
         }
@@ -237,6 +252,7 @@ impl CreateTableInterpreter {
             table: self.plan.table.clone(),
             schema: self.plan.schema.clone(),
             overwrite: false,
+            ignore_conflicts: vec![],
             source: InsertInputSource::SelectPlan(select_plan),
             table_info: Some(table_info),
         };
@@ -271,7 +287,7 @@ impl CreateTableInterpreter {
                 info!("{:?}", ctx.session_state().temp_tbl_mgr);
                 let qualified_table_name = format!("{}.{}", db_name, table_name);
 
-                if info.res.is_ok() {
+                if info.res.is_ok() && !in_txn {
                     info!(
                         "create_table_as_select {} success, commit table meta data by table id {}",
                         qualified_table_name, table_id
@@ -339,7 +355,17 @@ impl CreateTableInterpreter {
             self.build_request(stat)
         }?;
 
-        let reply = catalog.create_table(req.clone()).await?;
+        let reply = if self.create_in_txn(&req) {
+            match self
+                .create_table_in_txn(catalog.as_ref(), req.clone())
+                .await?
+            {
+                Some(reply) => reply,
+                None => return Ok(PipelineBuildResult::create()),
+            }
+        } else {
+            catalog.create_table(req.clone()).await?
+        };
 
         if !req.table_meta.options.contains_key(OPT_KEY_TEMP_PREFIX) {
             // grant the ownership of the table to the current role, the above req.table_meta.owner could be removed in future.
@@ -366,6 +392,78 @@ impl CreateTableInterpreter {
         Ok(PipelineBuildResult::create())
     }
 
+    /// Whether the table is created in the current transaction. Temporary tables and tables
+    /// of other catalogs are created immediately.
+    fn create_in_txn(&self, req: &CreateTableReq) -> bool {
+        self.ctx.txn_mgr().lock().is_active()
+            && self.plan.catalog == CATALOG_DEFAULT
+            && !req.table_meta.options.contains_key(OPT_KEY_TEMP_PREFIX)
+    }
+
+    /// Create the table as a dropped table in the current transaction, it is made visible when
+    /// the transaction commits. Returns None if the table exists and is not replaced.
+    #[async_backtrace::framed]
+    async fn create_table_in_txn(
+        &self,
+        catalog: &dyn Catalog,
+        mut req: CreateTableReq,
+    ) -> Result<Option<CreateTableReply>> {
+        let txn_mgr = self.ctx.txn_mgr();
+        let (created, dropped) = {
+            let guard = txn_mgr.lock();
+            (
+                guard.get_created_table(&self.plan.database, &self.plan.table),
+                guard.is_table_dropped(&self.plan.database, &self.plan.table),
+            )
+        };
+        if let Some(created) = created {
+            match req.create_option {
+                CreateOption::Create => {
+                    return Err(ErrorCode::TableAlreadyExists(format!(
+                        "Table '{}'.'{}' already exists",
+                        self.plan.database, self.plan.table
+                    )));
+                }
+                CreateOption::CreateIfNotExists => return Ok(None),
+                CreateOption::CreateOrReplace => {
+                    txn_mgr
+                        .lock()
+                        .remove_created_table(created.table_info.ident.table_id);
+                }
+            }
+        }
+        // The table dropped in the transaction still exists until the transaction commits.
+        if dropped {
+            req.create_option = CreateOption::CreateOrReplace;
+        }
+
+        req.as_dropped = true;
+        req.table_meta.drop_on = Some(Utc::now());
+        let reply = catalog.create_table(req.clone()).await?;
+        if !reply.new_table && req.create_option != CreateOption::CreateOrReplace {
+            return Ok(None);
+        }
+
+        let table_id_seq = reply
+            .table_id_seq
+            .expect("internal error: table_id_seq must have been set. create table in transaction");
+        let table_info = TableInfo::new(
+            &self.plan.database,
+            &self.plan.table,
+            TableIdent::new(reply.table_id, table_id_seq),
+            req.table_meta,
+        );
+        let commit_req = CommitTableMetaReq {
+            name_ident: req.name_ident,
+            db_id: reply.db_id,
+            table_id: reply.table_id,
+            prev_table_id: reply.prev_table_id,
+            orphan_table_name: reply.orphan_table_name.clone(),
+        };
+        txn_mgr.lock().add_created_table(table_info, commit_req);
+        Ok(Some(reply))
+    }
+
     /// Build CreateTableReq from CreateTablePlanV2.
     ///
     /// - Rebuild `DataSchema` with default exprs.
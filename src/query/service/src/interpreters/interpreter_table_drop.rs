@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use databend_common_catalog::catalog_kind::CATALOG_DEFAULT;
 use databend_common_catalog::table::TableExt;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
@@ -116,35 +117,47 @@ impl Interpreter for DropTableInterpreter {
 
         let tenant = self.ctx.get_tenant();
         let db = catalog.get_database(&tenant, &self.plan.database).await?;
+        let req = DropTableByIdReq {
+            if_exists: self.plan.if_exists,
+            tenant: tenant.clone(),
+            table_name: tbl_name.to_string(),
+            tb_id: tbl.get_table_info().ident.table_id,
+            db_id: db.get_db_info().database_id.db_id,
+            db_name: db.name().to_string(),
+            engine: tbl.engine().to_string(),
+            session_id: tbl
+                .options()
+                .get(OPT_KEY_TEMP_PREFIX)
+                .cloned()
+                .unwrap_or_default(),
+        };
+        let owner_object = OwnershipObject::Table {
+            catalog_name: self.plan.catalog.clone(),
+            db_id: db.get_db_info().database_id.db_id,
+            table_id,
+        };
+
+        // In a transaction, the table is dropped when the transaction commits.
+        let txn_mgr = self.ctx.txn_mgr();
+        let in_txn = txn_mgr.lock().is_active();
+        if in_txn && !is_temp && !self.plan.all && self.plan.catalog == CATALOG_DEFAULT {
+            let created_in_txn = txn_mgr.lock().add_dropped_table(req, owner_object.clone());
+            if created_in_txn {
+                let role_api = UserApiProvider::instance().role_api(&self.plan.tenant);
+                role_api.revoke_ownership(&owner_object).await?;
+                RoleCacheManager::instance().invalidate_cache(&tenant);
+            }
+            return Ok(PipelineBuildResult::create());
+        }
+
         // actually drop table
-        let _resp = catalog
-            .drop_table_by_id(DropTableByIdReq {
-                if_exists: self.plan.if_exists,
-                tenant: tenant.clone(),
-                table_name: tbl_name.to_string(),
-                tb_id: tbl.get_table_info().ident.table_id,
-                db_id: db.get_db_info().database_id.db_id,
-                db_name: db.name().to_string(),
-                engine: tbl.engine().to_string(),
-                session_id: tbl
-                    .options()
-                    .get(OPT_KEY_TEMP_PREFIX)
-                    .cloned()
-                    .unwrap_or_default(),
-            })
-            .await?;
+        let _resp = catalog.drop_table_by_id(req).await?;
 
         if !is_temp {
             // we should do `drop ownership` after actually drop table, otherwise when we drop the ownership,
             // but the table still exists, in the interval maybe some unexpected things will happen.
             // drop the ownership
             let role_api = UserApiProvider::instance().role_api(&self.plan.tenant);
-            let owner_object = OwnershipObject::Table {
-                catalog_name: self.plan.catalog.clone(),
-                db_id: db.get_db_info().database_id.db_id,
-                table_id,
-            };
-
             role_api.revoke_ownership(&owner_object).await?;
             RoleCacheManager::instance().invalidate_cache(&tenant);
         }
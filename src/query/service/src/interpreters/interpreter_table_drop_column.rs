@@ -23,6 +23,7 @@ use databend_common_sql::plans::DropTableColumnPlan;
 use databend_common_sql::BloomIndexColumns;
 use databend_common_storages_stream::stream_table::STREAM_ENGINE;
 use databend_common_storages_view::view_table::VIEW_ENGINE;
+use databend_storages_common_table_meta::table::get_table_constraints;
 use databend_storages_common_table_meta::table::OPT_KEY_BLOOM_INDEX_COLUMNS;
 
 use crate::interpreters::common::check_referenced_computed_columns;
@@ -107,6 +108,16 @@ impl Interpreter for DropTableColumnInterpreter {
             }
         }
 
+        // If the column is referenced by a constraint, the column can't be dropped.
+        for constraint in get_table_constraints(table_info.options())? {
+            if constraint.references_column(&field.name) {
+                return Err(ErrorCode::ColumnReferencedByConstraint(format!(
+                    "column `{}` is referenced by constraint `{}`, drop the constraint first",
+                    field.name, constraint.name,
+                )));
+            }
+        }
+
         let catalog = self.ctx.get_catalog(catalog_name).await?;
         let mut new_table_meta = table.get_table_info().meta.clone();
         new_table_meta.drop_column(&self.plan.column)?;
@@ -0,0 +1,88 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_catalog::table::TableExt;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::schema::UpdateTableMetaReq;
+use databend_common_meta_types::MatchSeq;
+use databend_common_sql::plans::DropTableConstraintPlan;
+use databend_common_storages_fuse::FuseTable;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::set_table_constraints;
+
+use super::Interpreter;
+use crate::pipelines::PipelineBuildResult;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+pub struct DropTableConstraintInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: DropTableConstraintPlan,
+}
+
+impl DropTableConstraintInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: DropTableConstraintPlan) -> Result<Self> {
+        Ok(DropTableConstraintInterpreter { ctx, plan })
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for DropTableConstraintInterpreter {
+    fn name(&self) -> &str {
+        "DropTableConstraintInterpreter"
+    }
+
+    fn is_ddl(&self) -> bool {
+        true
+    }
+
+    #[async_backtrace::framed]
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        let plan = &self.plan;
+        let tenant = self.ctx.get_tenant();
+        let catalog = self.ctx.get_catalog(&plan.catalog).await?;
+
+        let table = catalog
+            .get_table(&tenant, &plan.database, &plan.table)
+            .await?;
+        // check mutability
+        table.check_mutable()?;
+
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        let table_info = fuse_table.get_table_info();
+        let mut constraints = get_table_constraints(table_info.options())?;
+        let Some(pos) = constraints.iter().position(|c| c.name == plan.name) else {
+            return Err(ErrorCode::UnknownConstraint(format!(
+                "Constraint '{}' of table '{}' does not exist",
+                plan.name, plan.table
+            )));
+        };
+        constraints.remove(pos);
+
+        let mut new_table_meta = table_info.meta.clone();
+        set_table_constraints(&mut new_table_meta.options, &constraints)?;
+
+        let req = UpdateTableMetaReq {
+            table_id: table_info.ident.table_id,
+            seq: MatchSeq::Exact(table_info.ident.seq),
+            new_table_meta,
+        };
+        catalog.update_single_table_meta(req, table_info).await?;
+
+        Ok(PipelineBuildResult::create())
+    }
+}
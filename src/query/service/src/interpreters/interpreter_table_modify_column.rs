@@ -679,6 +679,8 @@ pub(crate) async fn build_select_insert_plan(
         select_column_bindings,
         insert_schema: Arc::new(new_schema.into()),
         cast_needed: true,
+        overwrite: true,
+        ignore_conflicts: vec![],
         table_meta_timestamps,
     }));
     let mut build_res = build_query_pipeline_without_render_result_set(&ctx, &insert_plan).await?;
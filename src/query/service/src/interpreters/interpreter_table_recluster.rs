@@ -738,6 +738,7 @@ impl ReclusterTableInterpreter {
             table_meta_timestamps,
             plan_id: u32::MAX,
             recluster_info,
+            check_unique_keys: false,
         }))
     }
 }
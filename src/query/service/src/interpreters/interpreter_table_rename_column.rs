@@ -23,6 +23,8 @@ use databend_common_sql::plans::RenameTableColumnPlan;
 use databend_common_sql::BloomIndexColumns;
 use databend_common_storages_stream::stream_table::STREAM_ENGINE;
 use databend_common_storages_view::view_table::VIEW_ENGINE;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::set_table_constraints;
 use databend_storages_common_table_meta::table::OPT_KEY_BLOOM_INDEX_COLUMNS;
 
 use crate::interpreters::common::check_referenced_computed_columns;
@@ -118,6 +120,23 @@ impl Interpreter for RenameTableColumnInterpreter {
                     }
                 }
             }
+            let mut constraints = get_table_constraints(opts)?;
+            if !constraints.is_empty() {
+                // the expression of a check constraint is not rewritten
+                for constraint in &constraints {
+                    if constraint.is_check() && constraint.references_column(&self.plan.old_column)
+                    {
+                        return Err(ErrorCode::ColumnReferencedByConstraint(format!(
+                            "column `{}` is referenced by check constraint `{}`, drop the constraint first",
+                            self.plan.old_column, constraint.name,
+                        )));
+                    }
+                }
+                for constraint in constraints.iter_mut() {
+                    constraint.rename_column(&self.plan.old_column, &self.plan.new_column);
+                }
+                set_table_constraints(opts, &constraints)?;
+            }
 
             commit_table_meta(
                 &self.ctx,
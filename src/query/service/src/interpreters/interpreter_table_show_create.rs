@@ -33,8 +33,10 @@ use databend_common_storages_stream::stream_table::StreamTable;
 use databend_common_storages_stream::stream_table::STREAM_ENGINE;
 use databend_common_storages_view::view_table::QUERY;
 use databend_common_storages_view::view_table::VIEW_ENGINE;
+use databend_storages_common_table_meta::table::get_table_constraints;
 use databend_storages_common_table_meta::table::is_internal_opt_key;
 use databend_storages_common_table_meta::table::StreamMode;
+use databend_storages_common_table_meta::table::TableConstraintKind;
 use databend_storages_common_table_meta::table::OPT_KEY_CLUSTER_TYPE;
 use databend_storages_common_table_meta::table::OPT_KEY_STORAGE_PREFIX;
 use databend_storages_common_table_meta::table::OPT_KEY_TABLE_ATTACHED_DATA_URI;
@@ -261,6 +263,64 @@ impl ShowCreateTableInterpreter {
                 create_defs.push(index_str);
             }
 
+            for constraint in get_table_constraints(table_info.options())? {
+                let display_names = |names: &[String]| {
+                    names
+                        .iter()
+                        .map(|name| {
+                            display_ident(
+                                name,
+                                force_quoted_ident,
+                                quoted_ident_case_sensitive,
+                                sql_dialect,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let constraint_str = match &constraint.kind {
+                    TableConstraintKind::PrimaryKey { columns }
+                    | TableConstraintKind::Unique { columns } => format!(
+                        "{} ({})",
+                        constraint.constraint_type(),
+                        display_names(columns)
+                    ),
+                    TableConstraintKind::Check { expr, .. } => format!("CHECK ({expr})"),
+                    TableConstraintKind::ForeignKey {
+                        columns,
+                        ref_database,
+                        ref_table,
+                        ref_columns,
+                    } => format!(
+                        "FOREIGN KEY ({}) REFERENCES {}.{} ({})",
+                        display_names(columns),
+                        display_ident(
+                            ref_database,
+                            force_quoted_ident,
+                            quoted_ident_case_sensitive,
+                            sql_dialect
+                        ),
+                        display_ident(
+                            ref_table,
+                            force_quoted_ident,
+                            quoted_ident_case_sensitive,
+                            sql_dialect
+                        ),
+                        display_names(ref_columns)
+                    ),
+                };
+                create_defs.push(format!(
+                    "  CONSTRAINT {} {}",
+                    display_ident(
+                        &constraint.name,
+                        force_quoted_ident,
+                        quoted_ident_case_sensitive,
+                        sql_dialect
+                    ),
+                    constraint_str
+                ));
+            }
+
             // Format is:
             //  (
             //      x,
@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use chrono::Utc;
 use databend_common_ast::ast::AlterTaskOptions;
 use databend_common_ast::ast::TaskSql;
 use databend_common_catalog::table_context::TableContext;
@@ -24,12 +25,16 @@ use databend_common_cloud_control::pb::alter_task_request::AlterTaskType;
 use databend_common_cloud_control::pb::AlterTaskRequest;
 use databend_common_cloud_control::pb::WarehouseOptions;
 use databend_common_config::GlobalConfig;
-use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_meta_app::principal::TaskInfo;
+use databend_common_meta_app::principal::TaskStatus;
 use databend_common_sql::plans::AlterTaskPlan;
+use databend_common_users::UserApiProvider;
 
 use crate::interpreters::common::get_task_client_config;
 use crate::interpreters::common::make_schedule_options;
+use crate::interpreters::common::make_task_schedule;
+use crate::interpreters::common::split_task_sql;
 use crate::interpreters::Interpreter;
 use crate::pipelines::PipelineBuildResult;
 use crate::sessions::QueryContext;
@@ -137,6 +142,87 @@ impl AlterTaskInterpreter {
         }
         req
     }
+
+    /// Alter the task in the meta-service for the built-in task scheduler.
+    async fn alter_local_task(&self) -> Result<()> {
+        let plan = self.plan.clone();
+        let user_api = UserApiProvider::instance();
+        if let AlterTaskOptions::AddAfter(tasks) = &plan.alter_options {
+            for name in tasks {
+                user_api.get_task(&plan.tenant, name).await?;
+            }
+        }
+
+        let alter_options = plan.alter_options;
+        let update = move |task: &mut TaskInfo| -> Result<()> {
+            match alter_options {
+                AlterTaskOptions::Resume => {
+                    task.status = TaskStatus::Started;
+                }
+                AlterTaskOptions::Suspend => {
+                    task.status = TaskStatus::Suspended;
+                    task.last_suspended_at = Some(Utc::now());
+                }
+                AlterTaskOptions::Set {
+                    schedule,
+                    comments,
+                    warehouse,
+                    suspend_task_after_num_failures,
+                    error_integration,
+                    session_parameters,
+                } => {
+                    if let Some(schedule) = schedule {
+                        task.schedule_options = Some(make_task_schedule(schedule));
+                    }
+                    if let Some(comments) = comments {
+                        task.comment = Some(comments);
+                    }
+                    if let Some(warehouse) = warehouse {
+                        task.warehouse = Some(warehouse);
+                    }
+                    if let Some(num) = suspend_task_after_num_failures {
+                        task.suspend_task_after_num_failures = Some(num);
+                    }
+                    if let Some(error_integration) = error_integration {
+                        task.error_integration = Some(error_integration);
+                    }
+                    if let Some(session_parameters) = session_parameters {
+                        task.session_params = session_parameters;
+                    }
+                }
+                AlterTaskOptions::Unset { warehouse } => {
+                    if warehouse {
+                        task.warehouse = None;
+                    }
+                }
+                AlterTaskOptions::ModifyAs(sql) => {
+                    let (query_text, script_sqls) = split_task_sql(&sql);
+                    task.query_text = query_text;
+                    task.script_sqls = script_sqls;
+                }
+                AlterTaskOptions::ModifyWhen(expr) => {
+                    task.when_condition = Some(expr.to_string());
+                }
+                AlterTaskOptions::AddAfter(tasks) => {
+                    for name in tasks {
+                        if !task.after.contains(&name) {
+                            task.after.push(name);
+                        }
+                    }
+                    // A task runs either on its schedule or after its predecessors.
+                    task.schedule_options = None;
+                }
+                AlterTaskOptions::RemoveAfter(tasks) => {
+                    task.after.retain(|name| !tasks.contains(name));
+                }
+            }
+            Ok(())
+        };
+        user_api
+            .update_task(&plan.tenant, &plan.task_name, plan.if_exists, update)
+            .await?;
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -154,9 +240,8 @@ impl Interpreter for AlterTaskInterpreter {
     async fn execute2(&self) -> Result<PipelineBuildResult> {
         let config = GlobalConfig::instance();
         if config.query.cloud_control_grpc_server_address.is_none() {
-            return Err(ErrorCode::CloudControlNotEnabled(
-                "cannot alter task without cloud control enabled, please set cloud_control_grpc_server_address in config",
-            ));
+            self.alter_local_task().await?;
+            return Ok(PipelineBuildResult::create());
         }
         let cloud_api = CloudControlApiProvider::instance();
         let task_client = cloud_api.get_task_client();
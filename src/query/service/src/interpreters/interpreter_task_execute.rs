@@ -18,14 +18,15 @@ use databend_common_cloud_control::client_config::make_request;
 use databend_common_cloud_control::cloud_api::CloudControlApiProvider;
 use databend_common_cloud_control::pb::ExecuteTaskRequest;
 use databend_common_config::GlobalConfig;
-use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_sql::plans::ExecuteTaskPlan;
+use databend_common_users::UserApiProvider;
 
 use crate::interpreters::common::get_task_client_config;
 use crate::interpreters::Interpreter;
 use crate::pipelines::PipelineBuildResult;
 use crate::sessions::QueryContext;
+use crate::tasks::execute_task_graph;
 
 #[derive(Debug)]
 pub struct ExecuteTaskInterpreter {
@@ -54,9 +55,13 @@ impl Interpreter for ExecuteTaskInterpreter {
     async fn execute2(&self) -> Result<PipelineBuildResult> {
         let config = GlobalConfig::instance();
         if config.query.cloud_control_grpc_server_address.is_none() {
-            return Err(ErrorCode::CloudControlNotEnabled(
-                "cannot execute task without cloud control enabled, please set cloud_control_grpc_server_address in config",
-            ));
+            // Run the task graph by the built-in task scheduler right now, the result of
+            // each task is recorded in the task history instead of returned.
+            let task = UserApiProvider::instance()
+                .get_task(&self.plan.tenant, &self.plan.task_name)
+                .await?;
+            execute_task_graph(&self.plan.tenant, task).await?;
+            return Ok(PipelineBuildResult::create());
         }
         let cloud_api = CloudControlApiProvider::instance();
         let task_client = cloud_api.get_task_client();
@@ -46,6 +46,8 @@ mod interpreter_dictionary_rename;
 mod interpreter_dictionary_show_create;
 mod interpreter_drop_warehouse_cluster;
 mod interpreter_drop_warehouses;
+mod interpreter_dynamic_table_create;
+mod interpreter_dynamic_table_refresh;
 mod interpreter_execute_immediate;
 mod interpreter_explain;
 mod interpreter_factory;
@@ -77,6 +79,11 @@ mod interpreter_password_policy_alter;
 mod interpreter_password_policy_create;
 mod interpreter_password_policy_desc;
 mod interpreter_password_policy_drop;
+mod interpreter_pipe_alter;
+mod interpreter_pipe_create;
+mod interpreter_pipe_desc;
+mod interpreter_pipe_drop;
+mod interpreter_prepare;
 mod interpreter_presign;
 mod interpreter_privilege_grant;
 mod interpreter_privilege_revoke;
@@ -106,11 +113,13 @@ mod interpreter_stream_drop;
 mod interpreter_suspend_warehouse;
 mod interpreter_system_action;
 mod interpreter_table_add_column;
+mod interpreter_table_add_constraint;
 mod interpreter_table_analyze;
 mod interpreter_table_create;
 mod interpreter_table_describe;
 mod interpreter_table_drop;
 mod interpreter_table_drop_column;
+mod interpreter_table_drop_constraint;
 mod interpreter_table_exists;
 mod interpreter_table_index_create;
 mod interpreter_table_index_drop;
@@ -136,6 +145,9 @@ mod interpreter_tasks_show;
 mod interpreter_txn_abort;
 mod interpreter_txn_begin;
 mod interpreter_txn_commit;
+mod interpreter_txn_release_savepoint;
+mod interpreter_txn_rollback_to_savepoint;
+mod interpreter_txn_savepoint;
 mod interpreter_unassign_warehouse_nodes;
 mod interpreter_unset;
 mod interpreter_use_database;
@@ -162,10 +174,13 @@ mod interpreter_virtual_column_drop;
 mod interpreter_virtual_column_refresh;
 mod util;
 
+pub use access::Accessor;
 pub use access::ManagementModeAccess;
 pub use common::InterpreterQueryLog;
 pub use hook::HookOperator;
+pub use interpreter::execute_statement;
 pub use interpreter::interpreter_plan_sql;
+pub use interpreter::interpreter_plan_stmt;
 pub use interpreter::Interpreter;
 pub use interpreter::InterpreterPtr;
 pub use interpreter_catalog_use::UseCatalogInterpreter;
@@ -181,6 +196,8 @@ pub use interpreter_database_rename::RenameDatabaseInterpreter;
 pub use interpreter_database_show_create::ShowCreateDatabaseInterpreter;
 pub use interpreter_database_undrop::UndropDatabaseInterpreter;
 pub use interpreter_dictionary_rename::RenameDictionaryInterpreter;
+pub use interpreter_dynamic_table_create::CreateDynamicTableInterpreter;
+pub use interpreter_dynamic_table_refresh::RefreshDynamicTableInterpreter;
 pub use interpreter_execute_immediate::ExecuteImmediateInterpreter;
 pub use interpreter_explain::ExplainInterpreter;
 pub use interpreter_factory::InterpreterFactory;
@@ -202,6 +219,8 @@ pub use interpreter_password_policy_alter::AlterPasswordPolicyInterpreter;
 pub use interpreter_password_policy_create::CreatePasswordPolicyInterpreter;
 pub use interpreter_password_policy_desc::DescPasswordPolicyInterpreter;
 pub use interpreter_password_policy_drop::DropPasswordPolicyInterpreter;
+pub use interpreter_prepare::DeallocateInterpreter;
+pub use interpreter_prepare::PrepareInterpreter;
 pub use interpreter_privilege_grant::GrantPrivilegeInterpreter;
 pub use interpreter_privilege_revoke::RevokePrivilegeInterpreter;
 pub use interpreter_procedure_desc::DescProcedureInterpreter;
@@ -221,11 +240,13 @@ pub use interpreter_stream_create::CreateStreamInterpreter;
 pub use interpreter_stream_drop::DropStreamInterpreter;
 pub use interpreter_system_action::SystemActionInterpreter;
 pub use interpreter_table_add_column::AddTableColumnInterpreter;
+pub use interpreter_table_add_constraint::AddTableConstraintInterpreter;
 pub use interpreter_table_analyze::AnalyzeTableInterpreter;
 pub use interpreter_table_create::CreateTableInterpreter;
 pub use interpreter_table_describe::DescribeTableInterpreter;
 pub use interpreter_table_drop::DropTableInterpreter;
 pub use interpreter_table_drop_column::DropTableColumnInterpreter;
+pub use interpreter_table_drop_constraint::DropTableConstraintInterpreter;
 pub use interpreter_table_exists::ExistsTableInterpreter;
 pub use interpreter_table_index_create::CreateTableIndexInterpreter;
 pub use interpreter_table_index_drop::DropTableIndexInterpreter;
@@ -47,16 +47,19 @@ pub mod auth;
 pub mod catalogs;
 pub mod clusters;
 pub mod databases;
+pub mod dynamic_tables;
 pub mod interpreters;
 pub mod local;
 pub mod locks;
 pub mod pipelines;
+pub mod pipes;
 pub mod schedulers;
 pub mod servers;
 pub mod sessions;
 pub mod spillers;
 pub mod stream;
 pub mod table_functions;
+pub mod tasks;
 pub mod test_kits;
 
 mod builtin;
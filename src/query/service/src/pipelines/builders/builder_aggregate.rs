@@ -39,6 +39,7 @@ use itertools::Itertools;
 
 use crate::pipelines::processors::transforms::aggregator::build_partition_bucket;
 use crate::pipelines::processors::transforms::aggregator::create_udaf_script_function;
+use crate::pipelines::processors::transforms::aggregator::create_udaf_server_function;
 use crate::pipelines::processors::transforms::aggregator::AggregateInjector;
 use crate::pipelines::processors::transforms::aggregator::AggregatorParams;
 use crate::pipelines::processors::transforms::aggregator::FinalSingleStateAggregator;
@@ -46,7 +47,9 @@ use crate::pipelines::processors::transforms::aggregator::PartialSingleStateAggr
 use crate::pipelines::processors::transforms::aggregator::TransformAggregateSpillWriter;
 use crate::pipelines::processors::transforms::aggregator::TransformExpandGroupingSets;
 use crate::pipelines::processors::transforms::aggregator::TransformPartialAggregate;
+use crate::pipelines::processors::transforms::ScriptLimits;
 use crate::pipelines::PipelineBuilder;
+use crate::sessions::QueryContext;
 
 impl PipelineBuilder {
     pub(crate) fn build_aggregate_expand(&mut self, expand: &AggregateExpand) -> Result<()> {
@@ -114,6 +117,7 @@ impl PipelineBuilder {
             self.is_exchange_neighbor,
             max_block_size as usize,
             max_spill_io_requests as usize,
+            &self.ctx,
         )?;
 
         if params.group_columns.is_empty() {
@@ -201,6 +205,7 @@ impl PipelineBuilder {
             self.is_exchange_neighbor,
             max_block_size as usize,
             max_spill_io_requests as usize,
+            &self.ctx,
         )?;
 
         if params.group_columns.is_empty() {
@@ -226,6 +231,7 @@ impl PipelineBuilder {
         build_partition_bucket(&mut self.main_pipeline, params.clone())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_aggregator_params(
         input_schema: DataSchemaRef,
         group_by: &[IndexType],
@@ -234,7 +240,9 @@ impl PipelineBuilder {
         cluster_aggregator: bool,
         max_block_size: usize,
         max_spill_io_requests: usize,
+        ctx: &Arc<QueryContext>,
     ) -> Result<Arc<AggregatorParams>> {
+        let script_limits = ScriptLimits::try_create(&ctx.get_settings())?;
         let mut agg_args = Vec::with_capacity(agg_funcs.len());
         let (group_by, group_data_types) = group_by
             .iter()
@@ -312,8 +320,28 @@ impl PipelineBuilder {
                             })
                             .collect(),
                         agg_func.sig.return_type.clone(),
+                        &script_limits,
+                    ),
+                    Some((UDFType::Server(address), state_fields)) => create_udaf_server_function(
+                        ctx,
+                        address,
+                        agg_func.sig.name.clone(),
+                        agg_func.display.clone(),
+                        state_fields
+                            .iter()
+                            .map(|f| DataField::new(&f.name, f.data_type.clone()))
+                            .collect(),
+                        agg_func
+                            .sig
+                            .args
+                            .iter()
+                            .enumerate()
+                            .map(|(i, data_type)| {
+                                DataField::new(&format!("arg_{}", i), data_type.clone())
+                            })
+                            .collect(),
+                        agg_func.sig.return_type.clone(),
                     ),
-                    Some((UDFType::Server(_), _state_fields)) => unimplemented!(),
                 }
             })
             .collect::<Result<_>>()?;
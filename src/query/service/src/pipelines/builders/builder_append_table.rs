@@ -20,6 +20,7 @@ use databend_common_expression::DataSchemaRef;
 use databend_common_meta_app::schema::UpdateStreamMetaReq;
 use databend_common_meta_app::schema::UpsertTableCopiedFileReq;
 use databend_common_pipeline_core::Pipeline;
+use databend_common_storages_fuse::FuseTable;
 use databend_storages_common_table_meta::meta::TableMetaTimestamps;
 
 use crate::pipelines::PipelineBuilder;
@@ -36,10 +37,18 @@ impl PipelineBuilder {
         copied_files: Option<UpsertTableCopiedFileReq>,
         update_stream_meta: Vec<UpdateStreamMetaReq>,
         overwrite: bool,
+        ignore_conflicts: &[String],
         deduplicated_label: Option<String>,
         table_meta_timestamps: TableMetaTimestamps,
     ) -> Result<()> {
         Self::fill_and_reorder_columns(ctx.clone(), main_pipeline, table.clone(), source_schema)?;
+        Self::build_unique_key_check(
+            ctx.clone(),
+            main_pipeline,
+            table.as_ref(),
+            ignore_conflicts,
+            overwrite,
+        )?;
 
         table.append_data(ctx.clone(), main_pipeline, table_meta_timestamps)?;
         table.commit_insertion(
@@ -61,12 +70,30 @@ impl PipelineBuilder {
         main_pipeline: &mut Pipeline,
         table: Arc<dyn Table>,
         source_schema: DataSchemaRef,
+        overwrite: bool,
         table_meta_timestamps: TableMetaTimestamps,
     ) -> Result<()> {
         Self::fill_and_reorder_columns(ctx.clone(), main_pipeline, table.clone(), source_schema)?;
+        Self::build_unique_key_check(ctx.clone(), main_pipeline, table.as_ref(), &[], overwrite)?;
 
         table.append_data(ctx, main_pipeline, table_meta_timestamps)?;
 
         Ok(())
     }
+
+    /// Check the PRIMARY KEY and UNIQUE constraints of the rows appended to a fuse table.
+    pub fn build_unique_key_check(
+        ctx: Arc<QueryContext>,
+        main_pipeline: &mut Pipeline,
+        table: &dyn Table,
+        ignore_conflicts: &[String],
+        overwrite: bool,
+    ) -> Result<()> {
+        match FuseTable::try_from_table(table) {
+            Ok(table) => {
+                table.add_unique_key_check(ctx, main_pipeline, ignore_conflicts, overwrite)
+            }
+            Err(_) => Ok(()),
+        }
+    }
 }
@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use databend_common_catalog::table::Table;
 use databend_common_exception::Result;
+use databend_common_expression::DataSchema;
 use databend_common_expression::RemoteExpr;
 use databend_common_functions::BUILTIN_FUNCTIONS;
 use databend_common_pipeline_transforms::processors::TransformPipelineHelper;
@@ -45,6 +47,16 @@ impl PipelineBuilder {
             .ctx
             .build_table_by_table_info(&column_mutation.table_info, None)?;
         let table = FuseTable::try_from_table(table.as_ref())?;
+        if matches!(column_mutation.mutation_kind, MutationKind::Update) {
+            // the updated blocks are in the order of the stored columns
+            let schema = table.schema_with_stream().remove_virtual_computed_fields();
+            Self::build_check_constraints(
+                self.ctx.clone(),
+                &mut self.main_pipeline,
+                table,
+                Arc::new(DataSchema::from(schema)),
+            )?;
+        }
 
         let block_thresholds = table.get_block_thresholds();
         let cluster_stats_gen = if matches!(column_mutation.mutation_kind, MutationKind::Delete) {
@@ -105,6 +105,13 @@ impl PipelineBuilder {
                             plan.table_meta_timestamps,
                         )
                     });
+                    if plan.check_unique_keys {
+                        table.add_mutation_unique_key_check(
+                            self.ctx.clone(),
+                            &mut self.main_pipeline,
+                            plan.snapshot.clone(),
+                        )?;
+                    }
                 }
 
                 let snapshot_gen = MutationGenerator::new(plan.snapshot.clone(), *kind);
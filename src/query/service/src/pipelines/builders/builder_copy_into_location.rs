@@ -41,6 +41,7 @@ impl PipelineBuilder {
             None,
             vec![],
             false,
+            &[],
             unsafe { self.ctx.get_settings().get_deduplicate_label()? },
             Default::default(),
         )
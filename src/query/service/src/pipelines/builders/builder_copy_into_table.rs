@@ -173,12 +173,13 @@ impl PipelineBuilder {
 
         // append data without commit.
         match plan_write_mode {
-            CopyIntoTableMode::Insert { overwrite: _ } => {
+            CopyIntoTableMode::Insert { overwrite } => {
                 Self::build_append2table_without_commit_pipeline(
                     ctx,
                     main_pipeline,
                     to_table.clone(),
                     plan_required_values_schema.clone(),
+                    *overwrite,
                     plan.table_meta_timestamps,
                 )?
             }
@@ -188,6 +189,7 @@ impl PipelineBuilder {
                 main_pipeline,
                 to_table.clone(),
                 plan_required_values_schema.clone(),
+                false,
                 plan.table_meta_timestamps,
             )?,
         }
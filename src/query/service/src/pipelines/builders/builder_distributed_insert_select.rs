@@ -59,6 +59,13 @@ impl PipelineBuilder {
             table.clone(),
             source_schema.clone(),
         )?;
+        Self::build_unique_key_check(
+            self.ctx.clone(),
+            &mut self.main_pipeline,
+            table.as_ref(),
+            &insert_select.ignore_conflicts,
+            insert_select.overwrite,
+        )?;
 
         table.append_data(
             self.ctx.clone(),
@@ -19,8 +19,10 @@ use databend_common_exception::Result;
 use databend_common_expression::DataSchemaRef;
 use databend_common_pipeline_core::Pipeline;
 use databend_common_pipeline_transforms::processors::TransformPipelineHelper;
+use databend_storages_common_table_meta::table::get_table_constraints;
 
 use crate::pipelines::processors::transforms::TransformAddComputedColumns;
+use crate::pipelines::processors::transforms::TransformCheckConstraints;
 use crate::pipelines::processors::TransformResortAddOn;
 use crate::pipelines::PipelineBuilder;
 use crate::sessions::QueryContext;
@@ -63,6 +65,29 @@ impl PipelineBuilder {
             })?;
         }
 
-        Ok(())
+        // Check the CHECK constraints of the rows.
+        Self::build_check_constraints(ctx, pipeline, table.as_ref(), computed_schema)
+    }
+
+    /// Check the CHECK constraints of the rows written to a table, the blocks are in the order
+    /// of the stored columns of `schema`.
+    pub fn build_check_constraints(
+        ctx: Arc<QueryContext>,
+        pipeline: &mut Pipeline,
+        table: &dyn Table,
+        schema: DataSchemaRef,
+    ) -> Result<()> {
+        let constraints = get_table_constraints(table.get_table_info().options())?;
+        if !constraints.iter().any(|c| c.is_check()) {
+            return Ok(());
+        }
+        pipeline.try_add_transformer(|| {
+            TransformCheckConstraints::try_new(
+                ctx.clone(),
+                schema.clone(),
+                table.name(),
+                &constraints,
+            )
+        })
     }
 }
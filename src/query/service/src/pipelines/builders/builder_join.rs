@@ -17,7 +17,7 @@ use std::sync::Arc;
 use databend_common_base::base::tokio::sync::Barrier;
 use databend_common_exception::Result;
 use databend_common_pipeline_core::processors::ProcessorPtr;
-use databend_common_pipeline_sinks::Sinker;
+use databend_common_pipeline_sinks::AsyncSinker;
 use databend_common_sql::executor::physical_plans::HashJoin;
 use databend_common_sql::executor::physical_plans::RangeJoin;
 use databend_common_sql::executor::PhysicalPlan;
@@ -36,7 +36,7 @@ use crate::sessions::QueryContext;
 
 impl PipelineBuilder {
     pub(crate) fn build_range_join(&mut self, range_join: &RangeJoin) -> Result<()> {
-        let state = Arc::new(RangeJoinState::new(self.ctx.clone(), range_join));
+        let state = Arc::new(RangeJoinState::try_create(self.ctx.clone(), range_join)?);
         self.expand_right_side_pipeline(range_join, state.clone())?;
         self.build_left_side(range_join, state)?;
         Ok(())
@@ -76,12 +76,10 @@ impl PipelineBuilder {
 
         let mut right_res = right_side_builder.finalize(&range_join.right)?;
         right_res.main_pipeline.add_sink(|input| {
-            Ok(ProcessorPtr::create(
-                Sinker::<TransformRangeJoinRight>::create(
-                    input,
-                    TransformRangeJoinRight::create(state.clone()),
-                ),
-            ))
+            Ok(ProcessorPtr::create(AsyncSinker::create(
+                input,
+                TransformRangeJoinRight::create(state.clone()),
+            )))
         })?;
         self.pipelines.push(right_res.main_pipeline.finalize());
         self.pipelines.extend(right_res.sources_pipelines);
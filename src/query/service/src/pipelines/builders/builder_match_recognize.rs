@@ -0,0 +1,42 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_exception::Result;
+use databend_common_pipeline_core::processors::ProcessorPtr;
+use databend_common_pipeline_transforms::processors::AccumulatingTransformer;
+use databend_common_sql::executor::physical_plans::MatchRecognize;
+
+use crate::pipelines::processors::transforms::TransformMatchRecognize;
+use crate::pipelines::PipelineBuilder;
+
+impl PipelineBuilder {
+    pub(crate) fn build_match_recognize(&mut self, plan: &MatchRecognize) -> Result<()> {
+        self.build_pipeline(&plan.input)?;
+
+        let input_schema = plan.input.output_schema()?;
+        let func_ctx = self.func_ctx.clone();
+        // `TransformMatchRecognize` is a pipeline breaker.
+        if plan.partition_by.is_empty() {
+            self.main_pipeline.try_resize(1)?;
+        }
+        self.main_pipeline.add_transform(|input, output| {
+            // The transform can only be created here, because it cannot be cloned.
+            let transform =
+                TransformMatchRecognize::try_create(func_ctx.clone(), plan, &input_schema)?;
+            Ok(ProcessorPtr::create(AccumulatingTransformer::create(
+                input, output, transform,
+            )))
+        })
+    }
+}
@@ -38,8 +38,10 @@ use databend_common_storages_fuse::operations::TransformSerializeBlock;
 use databend_common_storages_fuse::operations::TransformSerializeSegment;
 use databend_common_storages_fuse::operations::UnMatchedExprs;
 use databend_common_storages_fuse::FuseTable;
+use databend_storages_common_table_meta::table::get_table_constraints;
 
 use crate::pipelines::processors::transforms::TransformAddComputedColumns;
+use crate::pipelines::processors::transforms::TransformCheckConstraints;
 use crate::pipelines::processors::TransformResortAddOnWithoutSourceSchema;
 use crate::pipelines::PipelineBuilder;
 
@@ -231,6 +233,28 @@ impl PipelineBuilder {
             }
             self.main_pipeline.add_pipe(builder.finalize());
         }
+
+        // check the CHECK constraints of the updated and inserted rows
+        let constraints = get_table_constraints(table.get_table_info().options())?;
+        if constraints.iter().any(|c| c.is_check()) {
+            builder = self
+                .main_pipeline
+                .try_create_transform_pipeline_builder_with_len(
+                    || {
+                        TransformCheckConstraints::try_new(
+                            self.ctx.clone(),
+                            computed_schema.clone(),
+                            table.name(),
+                            &constraints,
+                        )
+                    },
+                    transform_len,
+                )?;
+            if need_match {
+                builder.add_items_prepend(vec![create_dummy_item()]);
+            }
+            self.main_pipeline.add_pipe(builder.finalize());
+        }
         Ok(())
     }
 
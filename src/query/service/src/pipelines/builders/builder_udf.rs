@@ -16,6 +16,7 @@ use databend_common_exception::Result;
 use databend_common_pipeline_transforms::processors::TransformPipelineHelper;
 use databend_common_sql::executor::physical_plans::Udf;
 
+use crate::pipelines::processors::transforms::ScriptLimits;
 use crate::pipelines::processors::transforms::TransformUdfScript;
 use crate::pipelines::processors::transforms::TransformUdfServer;
 use crate::pipelines::PipelineBuilder;
@@ -25,7 +26,8 @@ impl PipelineBuilder {
         self.build_pipeline(&udf.input)?;
 
         if udf.script_udf {
-            let runtimes = TransformUdfScript::init_runtime(&udf.udf_funcs)?;
+            let limits = ScriptLimits::try_create(&self.ctx.get_settings())?;
+            let runtimes = TransformUdfScript::init_runtime(&udf.udf_funcs, &limits)?;
             self.main_pipeline.try_add_transformer(|| {
                 Ok(TransformUdfScript::new(
                     self.func_ctx.clone(),
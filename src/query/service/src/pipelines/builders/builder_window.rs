@@ -75,6 +75,7 @@ impl PipelineBuilder {
             self.main_pipeline.try_resize(1)?;
         }
         let func = WindowFunctionInfo::try_create(&window.func, &input_schema)?;
+        let exclusion = window.window_frame.exclusion;
         // Window
         self.main_pipeline.add_transform(|input, output| {
             // The transform can only be created here, because it cannot be cloned.
@@ -89,6 +90,19 @@ impl PipelineBuilder {
                     partition_by.clone(),
                     order_by.clone(),
                     (start_bound, end_bound),
+                    exclusion,
+                )?) as Box<dyn Processor>
+            } else if window.window_frame.units.is_groups() {
+                let start_bound = FrameBound::try_from(&window.window_frame.start_bound)?;
+                let end_bound = FrameBound::try_from(&window.window_frame.end_bound)?;
+                Box::new(TransformWindow::<u64>::try_create_groups(
+                    input,
+                    output,
+                    func.clone(),
+                    partition_by.clone(),
+                    order_by.clone(),
+                    (start_bound, end_bound),
+                    exclusion,
                 )?) as Box<dyn Processor>
             } else {
                 if order_by.len() == 1 {
@@ -110,6 +124,7 @@ impl PipelineBuilder {
                                     partition_by.clone(),
                                     order_by.clone(),
                                     (start_bound, end_bound),
+                                    exclusion,
                                 )?,
                             )
                                 as Box<dyn Processor>));
@@ -129,6 +144,7 @@ impl PipelineBuilder {
                     partition_by.clone(),
                     order_by.clone(),
                     (start_bound, end_bound),
+                    exclusion,
                 )?) as Box<dyn Processor>
             };
             Ok(ProcessorPtr::create(transform))
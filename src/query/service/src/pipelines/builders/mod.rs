@@ -29,6 +29,7 @@ mod builder_hilbert_partition;
 mod builder_insert_multi_table;
 mod builder_join;
 mod builder_limit;
+mod builder_match_recognize;
 mod builder_mutation;
 mod builder_mutation_manipulate;
 mod builder_mutation_organize;
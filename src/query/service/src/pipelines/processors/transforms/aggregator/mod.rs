@@ -22,6 +22,7 @@ mod transform_aggregate_final;
 mod transform_aggregate_partial;
 mod transform_single_key;
 mod udaf_script;
+mod udaf_server;
 
 pub use aggregate_exchange_injector::AggregateInjector;
 pub use aggregate_meta::*;
@@ -33,6 +34,7 @@ pub use transform_aggregate_partial::TransformPartialAggregate;
 pub use transform_single_key::FinalSingleStateAggregator;
 pub use transform_single_key::PartialSingleStateAggregator;
 pub use udaf_script::*;
+pub use udaf_server::*;
 
 pub use self::serde::*;
 use super::runtime_pool;
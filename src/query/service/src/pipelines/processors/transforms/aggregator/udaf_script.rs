@@ -45,6 +45,7 @@ use databend_common_sql::plans::UDFScriptCode;
 
 use super::runtime_pool::Pool;
 use super::runtime_pool::RuntimeBuilder;
+use crate::pipelines::processors::transforms::ScriptLimits;
 
 pub struct AggregateUdfScript {
     display_name: String,
@@ -212,10 +213,10 @@ impl AggregateUdfScript {
 }
 
 #[derive(Debug)]
-pub struct UdfAggState(Arc<dyn Array>);
+pub struct UdfAggState(pub(super) Arc<dyn Array>);
 
 impl UdfAggState {
-    fn serialize(&self, writer: &mut Vec<u8>) -> std::result::Result<(), ArrowError> {
+    pub(super) fn serialize(&self, writer: &mut Vec<u8>) -> std::result::Result<(), ArrowError> {
         let schema = arrow_schema::Schema::new(vec![arrow_schema::Field::new(
             "state",
             self.0.data_type().clone(),
@@ -231,7 +232,7 @@ impl UdfAggState {
         writer.finish()
     }
 
-    fn deserialize(bytes: &mut &[u8]) -> std::result::Result<Self, ArrowError> {
+    pub(super) fn deserialize(bytes: &mut &[u8]) -> std::result::Result<Self, ArrowError> {
         let mut cursor = Cursor::new(&bytes);
         let mut reader = arrow_ipc::reader::FileReaderBuilder::new().build(&mut cursor)?;
         let array = reader
@@ -246,14 +247,15 @@ impl UdfAggState {
 }
 
 pub fn create_udaf_script_function(
-    code: &UDFScriptCode,
+    script: &UDFScriptCode,
     name: String,
     display_name: String,
     state_fields: Vec<DataField>,
     arguments: Vec<DataField>,
     output_type: DataType,
+    limits: &ScriptLimits,
 ) -> Result<Arc<dyn AggregateFunction>> {
-    let UDFScriptCode { language, code, .. } = code;
+    let UDFScriptCode { language, code, .. } = script;
     let runtime = match language {
         UDFLanguage::JavaScript => {
             let builder = JsRuntimeBuilder {
@@ -273,6 +275,7 @@ pub fn create_udaf_script_function(
         UDFLanguage::WebAssembly => unimplemented!(),
         #[cfg(not(feature = "python-udf"))]
         UDFLanguage::Python => {
+            let _ = limits;
             return Err(ErrorCode::EnterpriseFeatureNotEnable(
                 "Failed to create python script udf",
             ));
@@ -281,7 +284,13 @@ pub fn create_udaf_script_function(
         UDFLanguage::Python => {
             let builder = python_pool::PyRuntimeBuilder {
                 name,
-                code: String::from_utf8(code.to_vec())?,
+                code: super::super::python_script::build_python_script(script, limits, &[
+                    "create_state",
+                    "accumulate",
+                    "retract",
+                    "merge",
+                    "finish",
+                ])?,
                 state_type: ArrowType::Struct(
                     state_fields
                         .iter()
@@ -615,6 +624,9 @@ def finish(state):
             language: UDFLanguage::Python,
             code: code.into(),
             runtime_version: "3.12".to_string(),
+            imports: vec![],
+            packages: vec![],
+            table_columns: vec![],
         };
         let name = "test".to_string();
         let display_name = "test".to_string();
@@ -631,7 +643,59 @@ def finish(state):
             state_fields,
             arguments,
             output_type,
+            &ScriptLimits::default(),
         )?;
         Ok(())
     }
+
+    #[cfg(feature = "python-udf")]
+    #[test]
+    fn test_python_runtime_timeout() -> Result<()> {
+        use std::time::Duration;
+
+        use databend_common_expression::types::Int32Type;
+
+        let code = Vec::from(
+            r#"
+def create_state():
+    while True:
+        pass
+
+def accumulate(state, value):
+    return state
+
+def merge(state1, state2):
+    return state1
+
+def finish(state):
+    return state
+"#,
+        )
+        .into_boxed_slice();
+
+        let script = UDFScriptCode {
+            language: UDFLanguage::Python,
+            code: code.into(),
+            runtime_version: "3.12".to_string(),
+            imports: vec![],
+            packages: vec![],
+            table_columns: vec![],
+        };
+        let limits = ScriptLimits {
+            timeout: Some(Duration::from_secs(1)),
+            max_memory: None,
+        };
+        let result = create_udaf_script_function(
+            &script,
+            "test".to_string(),
+            "test".to_string(),
+            vec![DataField::new("sum", Int32Type::data_type())],
+            vec![DataField::new("value", Int32Type::data_type())],
+            Int32Type::data_type(),
+            &limits,
+        );
+        let err = result.err().unwrap();
+        assert!(err.message().contains("timeout"), "{}", err.message());
+        Ok(())
+    }
 }
@@ -0,0 +1,616 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aggregate UDFs served by an external UDF server.
+//!
+//! The state of a group is a one row struct array of the declared state
+//! fields, a null row is the empty state. Each phase is one Arrow Flight
+//! `do_exchange` call with the descriptor path `[name, phase]` and the usual
+//! tenant, function and query id headers. The input is sent as a stream of
+//! record batches, the server answers with record batches whose first column
+//! is the output, other columns are ignored:
+//!
+//! - `accumulate`: the input columns are `group: UInt32` followed by the
+//!   arguments `arg_0, arg_1, ..`. Every group of `0..N` has at least one row,
+//!   so `N` is the largest group plus one. The output has `N` rows, row `i`
+//!   is the state of group `i`.
+//! - `merge`: the input columns are `group: UInt32` and `state`, the states
+//!   are never null and every group of `0..N` has at least two of them. The
+//!   output has `N` rows, row `i` is the merged state of group `i`.
+//! - `finish`: the input column is `state`, the empty states are passed as
+//!   null. The output has one result of the return type per input row.
+//!
+//! The states returned by the server must be struct arrays with the declared
+//! state fields in order, the field names are not checked. A response with a
+//! different number of rows fails the query. `tests/udf/udf_server.py` has a
+//! reference implementation.
+//!
+//! Rows of a block are reduced into per group states in a single call, so the
+//! number of requests does not depend on the number of rows. The rows and
+//! states added to a group one at a time are buffered in the group and sent
+//! in one call once enough are pending, or the result is needed. States are
+//! serialized as arrow IPC, the same as script UDAFs, which lets the partial
+//! and final aggregation run on different nodes. A serialized state may hold
+//! several partial states that are merged by the receiver.
+
+use std::alloc::Layout;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow_array::new_null_array;
+use arrow_array::Array;
+use arrow_array::ArrayRef;
+use arrow_array::RecordBatch;
+use arrow_array::StructArray;
+use arrow_array::UInt32Array;
+use arrow_schema::DataType as ArrowType;
+use arrow_schema::Field;
+use arrow_schema::FieldRef;
+use arrow_schema::Fields;
+use arrow_schema::Schema;
+use databend_common_base::runtime::profile::Profile;
+use databend_common_base::runtime::profile::ProfileStatisticsName;
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::BinaryColumn;
+use databend_common_expression::types::Bitmap;
+use databend_common_expression::types::DataType;
+use databend_common_expression::udf_client::UDFFlightClient;
+use databend_common_expression::AggrState;
+use databend_common_expression::AggrStateLoc;
+use databend_common_expression::AggrStateRegistry;
+use databend_common_expression::AggrStateType;
+use databend_common_expression::Column;
+use databend_common_expression::ColumnBuilder;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchema;
+use databend_common_expression::InputColumns;
+use databend_common_expression::StateAddr;
+use databend_common_functions::aggregates::AggregateFunction;
+use parking_lot::Mutex;
+use tonic::transport::Endpoint;
+
+use super::udaf_script::UdfAggState;
+use crate::sessions::QueryContext;
+
+/// How many rows or partial states a group buffers before they are sent to the server.
+const MAX_PENDING_PER_GROUP: usize = 1024;
+
+/// The state of a group, the partial states that are not merged yet and the
+/// rows added by `accumulate_row` that are not accumulated yet.
+#[derive(Default)]
+struct UdfServerAggState {
+    states: Vec<ArrayRef>,
+    rows: Vec<DataBlock>,
+}
+
+impl UdfServerAggState {
+    fn num_pending(&self) -> usize {
+        self.states.len() + self.rows.len()
+    }
+}
+
+pub struct AggregateUdfServer {
+    name: String,
+    display_name: String,
+    endpoint: Arc<Endpoint>,
+    connect_timeout: u64,
+    /// The client is connected on the first call and shared by the later ones.
+    client: Mutex<Option<UDFFlightClient>>,
+    tenant: String,
+    query_id: String,
+    argument_schema: DataSchema,
+    state_fields: Fields,
+    return_type: DataType,
+}
+
+impl AggregateFunction for AggregateUdfServer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn return_type(&self) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn init_state(&self, place: AggrState) {
+        place.write(UdfServerAggState::default);
+    }
+
+    fn register_state(&self, registry: &mut AggrStateRegistry) {
+        registry.register(AggrStateType::Custom(Layout::new::<UdfServerAggState>()));
+    }
+
+    fn accumulate(
+        &self,
+        place: AggrState,
+        columns: InputColumns,
+        validity: Option<&Bitmap>,
+        _input_rows: usize,
+    ) -> Result<()> {
+        let block = self.create_input_block(columns, validity)?;
+        let num_rows = block.num_rows();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let states = self.accumulate_groups(block, vec![0; num_rows], 1)?;
+        self.merge_into(&[place], vec![states])
+    }
+
+    fn accumulate_keys(
+        &self,
+        addrs: &[StateAddr],
+        loc: &[AggrStateLoc],
+        columns: InputColumns,
+        _input_rows: usize,
+    ) -> Result<()> {
+        if addrs.is_empty() {
+            return Ok(());
+        }
+
+        let mut index = HashMap::new();
+        let mut places = Vec::new();
+        let groups = addrs
+            .iter()
+            .map(|addr| {
+                *index.entry(addr.addr()).or_insert_with(|| {
+                    places.push(AggrState::new(*addr, loc));
+                    (places.len() - 1) as u32
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let block = self.create_input_block(columns, None)?;
+        let states = self.accumulate_groups(block, groups, places.len())?;
+        let states = states.into_iter().map(|state| vec![state]).collect();
+        self.merge_into(&places, states)
+    }
+
+    fn accumulate_row(&self, place: AggrState, columns: InputColumns, row: usize) -> Result<()> {
+        let block = self.create_input_block(columns, None)?.slice(row..row + 1);
+        let state = place.get::<UdfServerAggState>();
+        state.rows.push(block);
+        self.compact_if_full(place)
+    }
+
+    fn serialize(&self, place: AggrState, writer: &mut Vec<u8>) -> Result<()> {
+        // Only the pending rows are sent, the partial states are serialized unmerged.
+        if !place.get::<UdfServerAggState>().rows.is_empty() {
+            self.compact(&[place])?;
+        }
+        let states = &place.get::<UdfServerAggState>().states;
+        let state = match states.len() {
+            0 => self.empty_state(),
+            1 => states[0].clone(),
+            _ => {
+                let states = states
+                    .iter()
+                    .map(|state| state.as_ref())
+                    .collect::<Vec<_>>();
+                arrow_select::concat::concat(&states)?
+            }
+        };
+        UdfAggState(state)
+            .serialize(writer)
+            .map_err(|e| ErrorCode::Internal(format!("state failed to serialize: {e}")))
+    }
+
+    fn merge(&self, place: AggrState, reader: &mut &[u8]) -> Result<()> {
+        let rhs = self.deserialize(reader)?;
+        place.get::<UdfServerAggState>().states.push(rhs);
+        self.compact_if_full(place)
+    }
+
+    fn batch_merge(
+        &self,
+        places: &[StateAddr],
+        loc: &[AggrStateLoc],
+        state: &BinaryColumn,
+    ) -> Result<()> {
+        let places = places
+            .iter()
+            .map(|addr| AggrState::new(*addr, loc))
+            .collect::<Vec<_>>();
+        let states = state
+            .iter()
+            .map(|mut data| Ok(vec![self.deserialize(&mut data)?]))
+            .collect::<Result<Vec<_>>>()?;
+        self.merge_into(&places, states)
+    }
+
+    fn batch_merge_single(&self, place: AggrState, state: &Column) -> Result<()> {
+        let column = state.as_binary().unwrap();
+        let states = column
+            .iter()
+            .map(|mut data| Ok(vec![self.deserialize(&mut data)?]))
+            .collect::<Result<Vec<_>>>()?;
+        let places = vec![place; states.len()];
+        self.merge_into(&places, states)
+    }
+
+    fn batch_merge_states(
+        &self,
+        places: &[StateAddr],
+        rhses: &[StateAddr],
+        loc: &[AggrStateLoc],
+    ) -> Result<()> {
+        let places = places
+            .iter()
+            .map(|addr| AggrState::new(*addr, loc))
+            .collect::<Vec<_>>();
+        let states = rhses
+            .iter()
+            .map(|addr| AggrState::new(*addr, loc))
+            .collect::<Vec<_>>();
+        // The pending rows of the right hand states are accumulated in the same call.
+        self.compact(&states)?;
+        let states = states
+            .iter()
+            .map(|state| state.get::<UdfServerAggState>().states.clone())
+            .collect();
+        self.merge_into(&places, states)
+    }
+
+    fn merge_states(&self, place: AggrState, rhs: AggrState) -> Result<()> {
+        let other = rhs.get::<UdfServerAggState>();
+        let state = place.get::<UdfServerAggState>();
+        state.states.extend(other.states.iter().cloned());
+        state.rows.extend(other.rows.iter().cloned());
+        self.compact_if_full(place)
+    }
+
+    fn batch_merge_result(
+        &self,
+        places: &[StateAddr],
+        loc: Box<[AggrStateLoc]>,
+        builder: &mut ColumnBuilder,
+    ) -> Result<()> {
+        let places = places
+            .iter()
+            .map(|addr| AggrState::new(*addr, &loc))
+            .collect::<Vec<_>>();
+        self.compact(&places)?;
+        let states = places
+            .iter()
+            .map(|place| self.merged_state(*place))
+            .collect();
+        self.finish(states, builder)
+    }
+
+    fn merge_result(&self, place: AggrState, builder: &mut ColumnBuilder) -> Result<()> {
+        self.compact(&[place])?;
+        let state = self.merged_state(place);
+        self.finish(vec![state], builder)
+    }
+
+    fn need_manual_drop_state(&self) -> bool {
+        true
+    }
+
+    unsafe fn drop_state(&self, place: AggrState) {
+        let state = place.get::<UdfServerAggState>();
+        std::ptr::drop_in_place(state);
+    }
+}
+
+impl fmt::Display for AggregateUdfServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
+
+impl AggregateUdfServer {
+    fn state_type(&self) -> ArrowType {
+        ArrowType::Struct(self.state_fields.clone())
+    }
+
+    fn empty_state(&self) -> ArrayRef {
+        new_null_array(&self.state_type(), 1)
+    }
+
+    fn create_input_block(
+        &self,
+        columns: InputColumns,
+        validity: Option<&Bitmap>,
+    ) -> Result<DataBlock> {
+        let columns = columns.iter().cloned().collect();
+        let block = DataBlock::new_from_columns(columns);
+        match validity {
+            Some(bitmap) => block.filter_with_bitmap(bitmap),
+            None => Ok(block),
+        }
+    }
+
+    fn deserialize(&self, reader: &mut &[u8]) -> Result<ArrayRef> {
+        let state =
+            UdfAggState::deserialize(reader).map_err(|e| ErrorCode::Internal(e.to_string()))?;
+        Ok(state.0)
+    }
+
+    /// Accumulates the rows of `block` into one state per group,
+    /// `groups[row]` is the group of the row.
+    fn accumulate_groups(
+        &self,
+        block: DataBlock,
+        groups: Vec<u32>,
+        num_groups: usize,
+    ) -> Result<Vec<ArrayRef>> {
+        let num_columns = block.num_columns();
+        let batch = block
+            .to_record_batch_with_dataschema(&self.argument_schema)
+            .map_err(|err| {
+                ErrorCode::UDFDataError(format!(
+                    "Failed to create input batch with {} columns: {}",
+                    num_columns, err
+                ))
+            })?;
+
+        let mut fields: Vec<FieldRef> =
+            vec![Arc::new(Field::new("group", ArrowType::UInt32, false))];
+        fields.extend(batch.schema().fields().iter().cloned());
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(UInt32Array::from(groups))];
+        columns.extend(batch.columns().iter().cloned());
+        let input = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+
+        let states = self.call("accumulate", input, num_groups)?;
+        let states = self.normalize_states(&states)?;
+        Ok((0..num_groups).map(|i| states.slice(i, 1)).collect())
+    }
+
+    /// Adds `states[i]` to `places[i]` and compacts the places,
+    /// a place may occur more than once.
+    fn merge_into(&self, places: &[AggrState], states: Vec<Vec<ArrayRef>>) -> Result<()> {
+        for (place, states) in places.iter().zip(states) {
+            place.get::<UdfServerAggState>().states.extend(states);
+        }
+        self.compact(places)
+    }
+
+    fn compact_if_full(&self, place: AggrState) -> Result<()> {
+        if place.get::<UdfServerAggState>().num_pending() < MAX_PENDING_PER_GROUP {
+            return Ok(());
+        }
+        self.compact(&[place])
+    }
+
+    /// Reduces every place to a single state. The pending rows of all the places
+    /// are accumulated in one `accumulate` call, then the partial states of all
+    /// the places are reduced in one `merge` call.
+    fn compact(&self, places: &[AggrState]) -> Result<()> {
+        let mut seen = HashSet::new();
+        let targets = places
+            .iter()
+            .filter(|place| seen.insert(place.addr.addr()))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut blocks = Vec::new();
+        let mut groups = Vec::new();
+        let mut owners = Vec::new();
+        for place in targets.iter() {
+            let state = place.get::<UdfServerAggState>();
+            if state.rows.is_empty() {
+                continue;
+            }
+            let group = owners.len() as u32;
+            owners.push(*place);
+            for block in state.rows.drain(..) {
+                groups.extend(std::iter::repeat(group).take(block.num_rows()));
+                blocks.push(block);
+            }
+        }
+        if !blocks.is_empty() {
+            let block = DataBlock::concat(&blocks)?;
+            let states = self.accumulate_groups(block, groups, owners.len())?;
+            for (place, state) in owners.iter().zip(states) {
+                place.get::<UdfServerAggState>().states.push(state);
+            }
+        }
+
+        let groups = targets
+            .iter()
+            .map(|place| std::mem::take(&mut place.get::<UdfServerAggState>().states))
+            .collect();
+        let merged = self.merge_groups(groups)?;
+        for (place, state) in targets.iter().zip(merged) {
+            place.get::<UdfServerAggState>().states = vec![state];
+        }
+        Ok(())
+    }
+
+    /// The state of a compacted place.
+    fn merged_state(&self, place: AggrState) -> ArrayRef {
+        match place.get::<UdfServerAggState>().states.first() {
+            Some(state) => state.clone(),
+            None => self.empty_state(),
+        }
+    }
+
+    /// Reduces the states of every group into one state. Only the groups
+    /// holding more than one non empty state are sent to the server, all of
+    /// them in a single `merge` call.
+    fn merge_groups(&self, groups: Vec<Vec<ArrayRef>>) -> Result<Vec<ArrayRef>> {
+        let mut merged = Vec::with_capacity(groups.len());
+        let mut pending = Vec::new();
+        let mut group_ids = Vec::new();
+        let mut inputs = Vec::new();
+        for states in groups {
+            // a deserialized state may hold several partial states
+            let mut states = states
+                .iter()
+                .flat_map(|state| {
+                    (0..state.len())
+                        .filter(move |i| state.is_valid(*i))
+                        .map(move |i| state.slice(i, 1))
+                })
+                .collect::<Vec<_>>();
+            match states.len() {
+                0 => merged.push(self.empty_state()),
+                1 => merged.push(states.pop().unwrap()),
+                n => {
+                    group_ids.extend(std::iter::repeat(pending.len() as u32).take(n));
+                    inputs.extend(states);
+                    pending.push(merged.len());
+                    merged.push(self.empty_state());
+                }
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(merged);
+        }
+
+        let inputs = inputs
+            .iter()
+            .map(|state| state.as_ref())
+            .collect::<Vec<_>>();
+        let schema = Schema::new(vec![
+            Field::new("group", ArrowType::UInt32, false),
+            Field::new("state", self.state_type(), true),
+        ]);
+        let input = RecordBatch::try_new(Arc::new(schema), vec![
+            Arc::new(UInt32Array::from(group_ids)),
+            arrow_select::concat::concat(&inputs)?,
+        ])?;
+
+        let states = self.call("merge", input, pending.len())?;
+        let states = self.normalize_states(&states)?;
+        for (i, pos) in pending.into_iter().enumerate() {
+            merged[pos] = states.slice(i, 1);
+        }
+        Ok(merged)
+    }
+
+    fn finish(&self, states: Vec<ArrayRef>, builder: &mut ColumnBuilder) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let num_rows = states.len();
+        let states = states
+            .iter()
+            .map(|state| state.as_ref())
+            .collect::<Vec<_>>();
+        let schema = Schema::new(vec![Field::new("state", self.state_type(), true)]);
+        let input = RecordBatch::try_new(Arc::new(schema), vec![arrow_select::concat::concat(
+            &states,
+        )?])?;
+
+        let array = self.call("finish", input, num_rows)?;
+        let result = Column::from_arrow_rs(array, &self.return_type)?;
+        builder.append_column(&result);
+        Ok(())
+    }
+
+    /// Rebuilds the states returned by the server with the declared state
+    /// fields, so they can be concatenated with the states of other sources.
+    fn normalize_states(&self, states: &ArrayRef) -> Result<ArrayRef> {
+        let states = states
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .ok_or_else(|| {
+                ErrorCode::UDFSchemaMismatch(format!(
+                    "UDF server return incorrect state type, expected: {}, but got: {}",
+                    self.state_type(),
+                    states.data_type()
+                ))
+            })?;
+        let states = StructArray::try_new(
+            self.state_fields.clone(),
+            states.columns().to_vec(),
+            states.nulls().cloned(),
+        )
+        .map_err(|err| {
+            ErrorCode::UDFSchemaMismatch(format!(
+                "UDF server return incorrect state type, expected: {}, but got: {}",
+                self.state_type(),
+                err
+            ))
+        })?;
+        Ok(Arc::new(states))
+    }
+
+    fn call(&self, phase: &str, input: RecordBatch, num_rows: usize) -> Result<ArrayRef> {
+        Profile::record_usize_profile(ProfileStatisticsName::ExternalServerRequestCount, 1);
+        let result = GlobalIORuntime::instance().block_on(async move {
+            let connected = self.client.lock().clone();
+            let mut client = match connected {
+                Some(client) => client,
+                None => {
+                    let client = UDFFlightClient::connect(
+                        self.endpoint.clone(),
+                        self.connect_timeout,
+                        65536,
+                    )
+                    .await?
+                    .with_tenant(&self.tenant)?
+                    .with_func_name(&self.name)?
+                    .with_handler_name(&self.name)?
+                    .with_query_id(&self.query_id)?;
+                    *self.client.lock() = Some(client.clone());
+                    client
+                }
+            };
+            client.do_exchange_aggregate(&self.name, phase, input).await
+        })?;
+
+        if result.num_columns() == 0 {
+            return Err(ErrorCode::EmptyDataFromServer(format!(
+                "Get empty data from UDF Server on {phase} of UDF function {}",
+                self.name
+            )));
+        }
+        if result.num_rows() != num_rows {
+            return Err(ErrorCode::UDFDataError(format!(
+                "UDF server should return {num_rows} rows on {phase} of UDF function {}, but got {}",
+                self.name,
+                result.num_rows()
+            )));
+        }
+        Ok(result.column(0).clone())
+    }
+}
+
+pub fn create_udaf_server_function(
+    ctx: &Arc<QueryContext>,
+    address: &str,
+    name: String,
+    display_name: String,
+    state_fields: Vec<DataField>,
+    arguments: Vec<DataField>,
+    output_type: DataType,
+) -> Result<Arc<dyn AggregateFunction>> {
+    let settings = ctx.get_settings();
+    let connect_timeout = settings.get_external_server_connect_timeout_secs()?;
+    let request_timeout = settings.get_external_server_request_timeout_secs()?;
+    let endpoint = UDFFlightClient::build_endpoint(address, connect_timeout, request_timeout)?;
+
+    Ok(Arc::new(AggregateUdfServer {
+        name,
+        display_name,
+        endpoint,
+        connect_timeout,
+        client: Mutex::new(None),
+        tenant: ctx.get_tenant().tenant_name().to_string(),
+        query_id: ctx.get_id(),
+        argument_schema: DataSchema::new(arguments),
+        state_fields: state_fields.iter().map(Field::from).collect(),
+        return_type: output_type,
+    }))
+}
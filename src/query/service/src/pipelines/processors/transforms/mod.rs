@@ -14,6 +14,8 @@
 
 pub mod aggregator;
 mod hash_join;
+#[cfg(feature = "python-udf")]
+mod python_script;
 pub(crate) mod range_join;
 mod runtime_pool;
 mod transform_add_computed_columns;
@@ -23,6 +25,7 @@ mod transform_add_stream_columns;
 mod transform_async_function;
 mod transform_cache_scan;
 mod transform_cast_schema;
+mod transform_check_constraints;
 mod transform_create_sets;
 mod transform_dictionary;
 mod transform_expression_scan;
@@ -50,6 +53,7 @@ pub use transform_cache_scan::CacheSourceState;
 pub use transform_cache_scan::HashJoinCacheState;
 pub use transform_cache_scan::TransformCacheScan;
 pub use transform_cast_schema::TransformCastSchema;
+pub use transform_check_constraints::TransformCheckConstraints;
 pub use transform_create_sets::TransformCreateSets;
 pub use transform_expression_scan::TransformExpressionScan;
 pub use transform_filter::TransformFilter;
@@ -62,6 +66,7 @@ pub use transform_resort_addon::TransformResortAddOn;
 pub use transform_resort_addon_without_source_schema::TransformResortAddOnWithoutSourceSchema;
 pub use transform_srf::TransformSRF;
 pub use transform_stream_sort_spill::*;
+pub use transform_udf_script::ScriptLimits;
 pub use transform_udf_script::TransformUdfScript;
 pub use transform_udf_server::TransformUdfServer;
 pub use window::*;
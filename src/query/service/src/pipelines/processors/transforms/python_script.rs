@@ -0,0 +1,248 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::PathBuf;
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_sql::plans::UDFScriptCode;
+use databend_common_sql::plans::UDFScriptImport;
+
+use super::ScriptLimits;
+
+/// Builds the source handed to the embedded python runtime.
+///
+/// The user code is prefixed with a prelude that makes the stage imports
+/// importable and checks the declared packages, then every function named in
+/// `limited` is rebound to a wrapper enforcing the script limits.
+pub fn build_python_script(
+    script: &UDFScriptCode,
+    limits: &ScriptLimits,
+    limited: &[&str],
+) -> Result<String> {
+    let mut source = String::new();
+
+    if !script.imports.is_empty() {
+        let dir = write_imports(&script.imports)?;
+        let dir = dir.to_str().ok_or_else(|| {
+            ErrorCode::UDFRuntimeError(format!("Invalid python UDF import directory {dir:?}"))
+        })?;
+        writeln!(source, "import sys as _databend_sys").unwrap();
+        writeln!(source, "_databend_sys.path.insert(0, {dir:?})").unwrap();
+    }
+
+    if !script.packages.is_empty() {
+        // strip version specifiers, `numpy==1.26` is checked as `numpy`
+        let packages = script
+            .packages
+            .iter()
+            .map(|package| {
+                package
+                    .split(['=', '<', '>', '!', '~', ' '])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            source,
+            "{}",
+            PACKAGES_CHECK.replace("$PACKAGES", &format!("{packages:?}"))
+        )
+        .unwrap();
+    }
+
+    source.push_str(&String::from_utf8(script.code.to_vec())?);
+    source.push('\n');
+
+    if limits.is_limited() {
+        let timeout = match limits.timeout {
+            Some(timeout) => timeout.as_secs_f64().to_string(),
+            None => "None".to_string(),
+        };
+        let max_memory = match limits.max_memory {
+            Some(max_memory) => max_memory.to_string(),
+            None => "None".to_string(),
+        };
+        source.push_str(
+            &LIMIT_WRAPPER
+                .replace("$TIMEOUT", &timeout)
+                .replace("$MAX_MEMORY", &max_memory),
+        );
+        for name in limited {
+            writeln!(
+                source,
+                "if {name:?} in globals():\n    {name} = _databend_limit({name})"
+            )
+            .unwrap();
+        }
+    }
+
+    Ok(source)
+}
+
+/// Writes the imported modules into a directory keyed by their content, so
+/// runtimes of the same function share the files and a changed module never
+/// shadows an older one still in use.
+fn write_imports(imports: &[UDFScriptImport]) -> Result<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    imports.hash(&mut hasher);
+    let dir = std::env::temp_dir()
+        .join("databend-python-udf")
+        .join(format!("{:016x}", hasher.finish()));
+    std::fs::create_dir_all(&dir)?;
+
+    for import in imports {
+        let path = dir.join(format!("{}.py", import.module));
+        if path.exists() {
+            continue;
+        }
+        // write then rename, concurrent runtimes must never see a partial module
+        let tmp = dir.join(format!("{}.py.{}", import.module, std::process::id()));
+        std::fs::write(&tmp, import.code.as_ref())?;
+        std::fs::rename(&tmp, &path)?;
+    }
+    Ok(dir)
+}
+
+const PACKAGES_CHECK: &str = r#"
+import importlib.metadata as _databend_metadata
+for _databend_package in $PACKAGES:
+    try:
+        _databend_metadata.version(_databend_package)
+    except _databend_metadata.PackageNotFoundError:
+        raise ImportError(f"package {_databend_package!r} is not installed in the python UDF runtime")
+"#;
+
+// A watchdog thread shared by the calls of a function interrupts a call that
+// runs out of time or memory by raising an asynchronous exception in the
+// calling thread, so nothing runs between the lines of the UDF. Native code
+// is only interrupted once it returns to python.
+//
+// tracemalloc is global to the interpreter, so the calls with a memory limit
+// run one at a time, each traced from its own start. The memory allocated
+// meanwhile by other python threads, e.g. functions without a memory limit,
+// is still counted to the running call.
+const LIMIT_WRAPPER: &str = r#"
+def _databend_limit(func):
+    import ctypes
+    import functools
+    import threading
+    import time
+    import tracemalloc
+
+    timeout = $TIMEOUT
+    max_memory = $MAX_MEMORY
+
+    class UDFTimeoutError(TimeoutError):
+        def __init__(self):
+            super().__init__(f"python UDF exceeded the timeout of {timeout} seconds")
+
+    class UDFMemoryError(MemoryError):
+        def __init__(self):
+            super().__init__(f"python UDF exceeded the memory limit of {max_memory} bytes")
+
+    # shared by all the limited functions of the interpreter, like tracemalloc
+    memory_lock = vars(tracemalloc).setdefault("_databend_memory_lock", threading.Lock())
+    lock = threading.Lock()
+    active = threading.Event()
+    # thread id -> deadline
+    calls = {}
+    # thread id -> the error raised in the thread by the watchdog
+    interrupted = {}
+    watchdog = None
+
+    def interrupt(thread_id, error):
+        ctypes.pythonapi.PyThreadState_SetAsyncExc(
+            ctypes.c_ulong(thread_id), None if error is None else ctypes.py_object(error)
+        )
+
+    def watch():
+        while True:
+            active.wait()
+            time.sleep(0.01)
+            with lock:
+                now = time.monotonic()
+                memory = tracemalloc.get_traced_memory()[0] if max_memory is not None else 0
+                for thread_id, deadline in calls.items():
+                    if thread_id in interrupted:
+                        continue
+                    if deadline is not None and now > deadline:
+                        interrupted[thread_id] = UDFTimeoutError
+                    elif max_memory is not None and memory > max_memory:
+                        interrupted[thread_id] = UDFMemoryError
+                    else:
+                        continue
+                    interrupt(thread_id, interrupted[thread_id])
+                if not calls:
+                    active.clear()
+
+    def start(thread_id):
+        nonlocal watchdog
+        if watchdog is None:
+            watchdog = threading.Thread(target=watch, name="databend-udf-limit", daemon=True)
+            watchdog.start()
+        if max_memory is not None:
+            tracemalloc.start()
+        calls[thread_id] = None if timeout is None else time.monotonic() + timeout
+        active.set()
+
+    def finish(thread_id):
+        calls.pop(thread_id)
+        error = interrupted.pop(thread_id, None)
+        if error is not None:
+            # discard the interruption if it is still pending
+            interrupt(thread_id, None)
+        if max_memory is not None:
+            if error is None and tracemalloc.get_traced_memory()[1] > max_memory:
+                error = UDFMemoryError
+            tracemalloc.stop()
+        return error
+
+    @functools.wraps(func)
+    def wrapper(*args, **kwargs):
+        thread_id = threading.get_ident()
+        # a recursive call is limited by the outermost call
+        if thread_id in calls:
+            return func(*args, **kwargs)
+
+        if max_memory is not None:
+            memory_lock.acquire()
+        try:
+            with lock:
+                start(thread_id)
+            error = None
+            try:
+                return_value = func(*args, **kwargs)
+            finally:
+                while thread_id in calls:
+                    try:
+                        with lock:
+                            error = finish(thread_id) or error
+                    except (UDFTimeoutError, UDFMemoryError) as e:
+                        # the interruption fired after the call returned
+                        error = type(e)
+        finally:
+            if max_memory is not None:
+                memory_lock.release()
+        if error is not None:
+            raise error()
+        return return_value
+
+    return wrapper
+"#;
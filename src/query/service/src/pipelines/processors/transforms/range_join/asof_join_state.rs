@@ -0,0 +1,413 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::BlockEntry;
+use databend_common_expression::Column;
+use databend_common_expression::DataBlock;
+use databend_common_expression::Evaluator;
+use databend_common_expression::Expr;
+use databend_common_expression::FunctionContext;
+use databend_common_expression::Scalar;
+use databend_common_expression::Value;
+use databend_common_functions::BUILTIN_FUNCTIONS;
+use databend_common_pipeline_transforms::MemorySettings;
+use databend_common_sql::executor::physical_plans::RangeJoin;
+use databend_common_sql::plans::JoinType;
+use databend_common_storage::DataOperator;
+use parking_lot::Mutex;
+
+use crate::pipelines::memory_settings::MemorySettingsExt;
+use crate::pipelines::processors::transforms::range_join::RangeJoinState;
+use crate::sessions::QueryContext;
+use crate::spillers::Location;
+use crate::spillers::Spiller;
+use crate::spillers::SpillerConfig;
+use crate::spillers::SpillerType;
+
+// The rows of both sides are hash partitioned by the equi keys, so that each partition
+// can be joined independently and the partitions can be spilled when memory is tight.
+#[derive(Default)]
+struct AsofPartitions {
+    left_blocks: Vec<Vec<DataBlock>>,
+    right_blocks: Vec<Vec<DataBlock>>,
+    left_spilled: Vec<Vec<Location>>,
+    right_spilled: Vec<Vec<Location>>,
+}
+
+pub struct AsofJoinState {
+    func_ctx: FunctionContext,
+    left_keys: Vec<Expr>,
+    right_keys: Vec<Expr>,
+    // The match condition is `left_match_key operator right_match_key`
+    left_match_key: Expr,
+    right_match_key: Expr,
+    operator: String,
+    is_left_join: bool,
+    // The output data types of the right side
+    right_data_types: Vec<DataType>,
+    max_block_size: usize,
+    num_partitions: usize,
+    partitions: Mutex<AsofPartitions>,
+    spiller: Spiller,
+    memory_settings: MemorySettings,
+}
+
+impl AsofJoinState {
+    pub fn try_create(ctx: &Arc<QueryContext>, range_join: &RangeJoin) -> Result<Self> {
+        let settings = ctx.get_settings();
+        let num_partitions = if range_join.equi_conditions.is_empty() {
+            1
+        } else {
+            1 << settings.get_join_spilling_partition_bits()?
+        };
+        let condition = &range_join.conditions[0];
+
+        let spill_config = SpillerConfig {
+            spiller_type: SpillerType::AsofJoin,
+            location_prefix: ctx.query_id_spill_prefix(),
+            disk_spill: None,
+            use_parquet: settings.get_spilling_file_format()?.is_parquet(),
+        };
+        let operator = DataOperator::instance().spill_operator();
+        let spiller = Spiller::create(ctx.clone(), operator, spill_config)?;
+
+        let partitions = AsofPartitions {
+            left_blocks: vec![vec![]; num_partitions],
+            right_blocks: vec![vec![]; num_partitions],
+            left_spilled: vec![vec![]; num_partitions],
+            right_spilled: vec![vec![]; num_partitions],
+        };
+
+        Ok(Self {
+            func_ctx: ctx.get_function_context()?,
+            left_keys: range_join
+                .equi_conditions
+                .iter()
+                .map(|condition| condition.left_expr.as_expr(&BUILTIN_FUNCTIONS))
+                .collect(),
+            right_keys: range_join
+                .equi_conditions
+                .iter()
+                .map(|condition| condition.right_expr.as_expr(&BUILTIN_FUNCTIONS))
+                .collect(),
+            left_match_key: condition.left_expr.as_expr(&BUILTIN_FUNCTIONS),
+            right_match_key: condition.right_expr.as_expr(&BUILTIN_FUNCTIONS),
+            operator: condition.operator.clone(),
+            is_left_join: range_join.join_type == JoinType::LeftAsof,
+            right_data_types: range_join
+                .output_schema()?
+                .fields()
+                .iter()
+                .skip(range_join.left.output_schema()?.num_fields())
+                .map(|field| field.data_type().clone())
+                .collect(),
+            max_block_size: settings.get_max_block_size()? as usize,
+            num_partitions,
+            partitions: Mutex::new(partitions),
+            spiller,
+            memory_settings: MemorySettings::from_join_settings(ctx)?,
+        })
+    }
+
+    pub(crate) fn num_partitions(&self) -> usize {
+        self.num_partitions
+    }
+
+    pub(crate) fn sink(&self, block: DataBlock, is_left: bool) -> Result<()> {
+        if block.is_empty() {
+            return Ok(());
+        }
+        let partition_blocks = if self.num_partitions == 1 {
+            vec![block]
+        } else {
+            let keys = if is_left {
+                &self.left_keys
+            } else {
+                &self.right_keys
+            };
+            let columns = self.evaluate(&block, keys)?;
+            let indices = (0..block.num_rows())
+                .map(|row| {
+                    let mut hasher = DefaultHasher::new();
+                    for column in columns.iter() {
+                        unsafe { column.index_unchecked(row) }.hash(&mut hasher);
+                    }
+                    (hasher.finish() % self.num_partitions as u64) as u32
+                })
+                .collect::<Vec<_>>();
+            DataBlock::scatter(&block, &indices, self.num_partitions)?
+        };
+
+        let mut partitions = self.partitions.lock();
+        let buffers = if is_left {
+            &mut partitions.left_blocks
+        } else {
+            &mut partitions.right_blocks
+        };
+        for (buffer, block) in buffers.iter_mut().zip(partition_blocks) {
+            if !block.is_empty() {
+                buffer.push(block);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn need_spill(&self) -> bool {
+        self.memory_settings.check_spill()
+    }
+
+    // Spill all the in-memory blocks of both sides.
+    pub(crate) async fn spill(&self) -> Result<()> {
+        let (left_blocks, right_blocks) = {
+            let mut partitions = self.partitions.lock();
+            let left_blocks = std::mem::replace(&mut partitions.left_blocks, vec![
+                    vec![];
+                    self.num_partitions
+                ]);
+            let right_blocks = std::mem::replace(&mut partitions.right_blocks, vec![
+                    vec![];
+                    self.num_partitions
+                ]);
+            (left_blocks, right_blocks)
+        };
+
+        for (is_left, blocks) in [(true, left_blocks), (false, right_blocks)] {
+            for (partition_id, blocks) in blocks.into_iter().enumerate() {
+                if blocks.is_empty() {
+                    continue;
+                }
+                let location = self.spiller.spill(blocks).await?;
+                let mut partitions = self.partitions.lock();
+                if is_left {
+                    partitions.left_spilled[partition_id].push(location);
+                } else {
+                    partitions.right_spilled[partition_id].push(location);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn join_partition(&self, partition_id: usize) -> Result<Vec<DataBlock>> {
+        let (mut left_blocks, mut right_blocks, left_spilled, right_spilled) = {
+            let mut partitions = self.partitions.lock();
+            (
+                std::mem::take(&mut partitions.left_blocks[partition_id]),
+                std::mem::take(&mut partitions.right_blocks[partition_id]),
+                std::mem::take(&mut partitions.left_spilled[partition_id]),
+                std::mem::take(&mut partitions.right_spilled[partition_id]),
+            )
+        };
+        if left_blocks.is_empty() && left_spilled.is_empty() {
+            return Ok(vec![]);
+        }
+        for location in left_spilled.iter() {
+            left_blocks.push(self.spiller.read_spilled_file(location).await?);
+        }
+        for location in right_spilled.iter() {
+            right_blocks.push(self.spiller.read_spilled_file(location).await?);
+        }
+
+        let left = DataBlock::concat(&left_blocks)?;
+        let right = if right_blocks.is_empty() {
+            None
+        } else {
+            Some(DataBlock::concat(&right_blocks)?)
+        };
+        self.asof_join(left, right)
+    }
+
+    fn asof_join(&self, left: DataBlock, right: Option<DataBlock>) -> Result<Vec<DataBlock>> {
+        let num_keys = self.left_keys.len() + 1;
+        let mut left_keys = self.evaluate(&left, &self.left_keys)?;
+        left_keys.extend(self.evaluate(&left, &[self.left_match_key.clone()])?);
+        let (left_rows, mut unmatched) = sorted_rows(&left_keys, left.num_rows());
+
+        let mut left_indices = Vec::with_capacity(left_rows.len());
+        let mut right_indices = Vec::with_capacity(left_rows.len());
+        if let Some(right) = &right {
+            let mut right_keys = self.evaluate(right, &self.right_keys)?;
+            right_keys.extend(self.evaluate(right, &[self.right_match_key.clone()])?);
+            let (right_rows, _) = sorted_rows(&right_keys, right.num_rows());
+
+            // Both sides are sorted by (equi keys, match key), so the position of the closest
+            // right row only moves forward while the left rows are scanned.
+            // `gte`/`gt` match the last right row before the left row, `lte`/`lt` match the first
+            // right row after it. Equal match keys precede the left row for `gte` and `lt`.
+            let backward = matches!(self.operator.as_str(), "gte" | "gt");
+            let inclusive = matches!(self.operator.as_str(), "gte" | "lt");
+            let mut j = 0;
+            for left_row in left_rows {
+                while j < right_rows.len() {
+                    let precedes = match compare_rows(
+                        &right_keys,
+                        right_rows[j],
+                        &left_keys,
+                        left_row,
+                        num_keys,
+                    ) {
+                        Ordering::Less => true,
+                        Ordering::Equal => inclusive,
+                        Ordering::Greater => false,
+                    };
+                    if !precedes {
+                        break;
+                    }
+                    j += 1;
+                }
+                let candidate = if backward {
+                    j.checked_sub(1)
+                } else {
+                    (j < right_rows.len()).then_some(j)
+                };
+                match candidate {
+                    Some(candidate)
+                        if compare_rows(
+                            &right_keys,
+                            right_rows[candidate],
+                            &left_keys,
+                            left_row,
+                            num_keys - 1,
+                        ) == Ordering::Equal =>
+                    {
+                        left_indices.push(left_row as u32);
+                        right_indices.push(right_rows[candidate] as u32);
+                    }
+                    _ => unmatched.push(left_row),
+                }
+            }
+        } else {
+            unmatched.extend(left_rows);
+        }
+
+        let mut result_blocks = vec![];
+        if let Some(right) = &right {
+            for (left_chunk, right_chunk) in left_indices
+                .chunks(self.max_block_size)
+                .zip(right_indices.chunks(self.max_block_size))
+            {
+                let mut block = left.take(left_chunk)?;
+                let right_block = right.take(right_chunk)?;
+                for (entry, data_type) in right_block
+                    .columns()
+                    .iter()
+                    .zip(self.right_data_types.iter())
+                {
+                    block.add_column(wrap_nullable_entry(entry, data_type, block.num_rows()));
+                }
+                result_blocks.push(block);
+            }
+        }
+        if self.is_left_join {
+            let unmatched = unmatched
+                .into_iter()
+                .map(|row| row as u32)
+                .collect::<Vec<_>>();
+            for chunk in unmatched.chunks(self.max_block_size) {
+                let mut block = left.take(chunk)?;
+                for data_type in self.right_data_types.iter() {
+                    block.add_column(BlockEntry::new(
+                        data_type.clone(),
+                        Value::Scalar(Scalar::Null),
+                    ));
+                }
+                result_blocks.push(block);
+            }
+        }
+        Ok(result_blocks)
+    }
+
+    fn evaluate(&self, block: &DataBlock, exprs: &[Expr]) -> Result<Vec<Column>> {
+        let evaluator = Evaluator::new(block, &self.func_ctx, &BUILTIN_FUNCTIONS);
+        exprs
+            .iter()
+            .map(|expr| {
+                Ok(evaluator
+                    .run(expr)?
+                    .convert_to_full_column(expr.data_type(), block.num_rows()))
+            })
+            .collect()
+    }
+}
+
+// Sort the rows by the key columns, the rows containing a NULL key never match and are returned separately.
+fn sorted_rows(keys: &[Column], num_rows: usize) -> (Vec<usize>, Vec<usize>) {
+    let (mut rows, null_rows): (Vec<usize>, Vec<usize>) = (0..num_rows).partition(|row| {
+        keys.iter()
+            .all(|column| !unsafe { column.index_unchecked(*row) }.is_null())
+    });
+    rows.sort_by(|a, b| compare_rows(keys, *a, keys, *b, keys.len()));
+    (rows, null_rows)
+}
+
+fn compare_rows(
+    left: &[Column],
+    left_row: usize,
+    right: &[Column],
+    right_row: usize,
+    num_keys: usize,
+) -> Ordering {
+    for (left, right) in left.iter().zip(right.iter()).take(num_keys) {
+        let left = unsafe { left.index_unchecked(left_row) };
+        let right = unsafe { right.index_unchecked(right_row) };
+        match left.cmp(&right) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+    Ordering::Equal
+}
+
+fn wrap_nullable_entry(entry: &BlockEntry, data_type: &DataType, num_rows: usize) -> BlockEntry {
+    if entry.data_type == *data_type {
+        return entry.clone();
+    }
+    let column = entry
+        .value
+        .convert_to_full_column(&entry.data_type, num_rows)
+        .wrap_nullable(None);
+    BlockEntry::new(data_type.clone(), Value::Column(column))
+}
+
+impl RangeJoinState {
+    pub(crate) fn need_spill(&self) -> bool {
+        self.asof_join_state
+            .as_ref()
+            .is_some_and(|asof_join_state| asof_join_state.need_spill())
+    }
+
+    pub(crate) async fn spill(&self) -> Result<()> {
+        if let Some(asof_join_state) = &self.asof_join_state {
+            asof_join_state.spill().await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn asof_join(&self, task_id: usize) -> Result<Vec<DataBlock>> {
+        let (partition_id, _) = self.tasks.read()[task_id];
+        match &self.asof_join_state {
+            Some(asof_join_state) => asof_join_state.join_partition(partition_id).await,
+            None => Ok(vec![]),
+        }
+    }
+}
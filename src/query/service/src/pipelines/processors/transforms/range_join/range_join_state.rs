@@ -35,6 +35,7 @@ use parking_lot::Mutex;
 use parking_lot::RwLock;
 
 use crate::pipelines::executor::WatchNotify;
+use crate::pipelines::processors::transforms::range_join::AsofJoinState;
 use crate::pipelines::processors::transforms::range_join::IEJoinState;
 use crate::sessions::QueryContext;
 
@@ -61,17 +62,24 @@ pub struct RangeJoinState {
     pub(crate) finished_tasks: AtomicU64,
     // IEJoin state
     pub(crate) ie_join_state: Option<IEJoinState>,
+    // AsofJoin state
+    pub(crate) asof_join_state: Option<AsofJoinState>,
 }
 
 impl RangeJoinState {
-    pub fn new(ctx: Arc<QueryContext>, range_join: &RangeJoin) -> Self {
+    pub fn try_create(ctx: Arc<QueryContext>, range_join: &RangeJoin) -> Result<Self> {
         let ie_join_state = if matches!(range_join.range_join_type, RangeJoinType::IEJoin) {
             Some(IEJoinState::new(range_join))
         } else {
             None
         };
+        let asof_join_state = if matches!(range_join.range_join_type, RangeJoinType::Asof) {
+            Some(AsofJoinState::try_create(&ctx, range_join)?)
+        } else {
+            None
+        };
 
-        Self {
+        Ok(Self {
             ctx,
             left_table: RwLock::new(vec![]),
             right_table: RwLock::new(vec![]),
@@ -88,10 +96,14 @@ impl RangeJoinState {
             row_offset: RwLock::new(vec![]),
             finished_tasks: AtomicU64::new(0),
             ie_join_state,
-        }
+            asof_join_state,
+        })
     }
 
     pub(crate) fn sink_right(&self, block: DataBlock) -> Result<()> {
+        if let Some(asof_join_state) = &self.asof_join_state {
+            return asof_join_state.sink(block, false);
+        }
         // Sink block to right table
         let mut right_table = self.right_table.write();
         right_table.push(block);
@@ -99,6 +111,9 @@ impl RangeJoinState {
     }
 
     pub(crate) fn sink_left(&self, block: DataBlock) -> Result<()> {
+        if let Some(asof_join_state) = &self.asof_join_state {
+            return asof_join_state.sink(block, true);
+        }
         // Sink block to left table
         let mut left_table = self.left_table.write();
         left_table.push(block);
@@ -172,6 +187,14 @@ impl RangeJoinState {
     }
 
     pub(crate) fn partition(&self) -> Result<()> {
+        if let Some(asof_join_state) = &self.asof_join_state {
+            // Each task of asof join joins one hash partition of both sides
+            let mut tasks = self.tasks.write();
+            for partition_id in 0..asof_join_state.num_partitions() {
+                tasks.push((partition_id, partition_id));
+            }
+            return Ok(());
+        }
         let max_threads = self.ctx.get_settings().get_max_threads()? as usize;
         let left_table = self.left_table.read();
         // Right table is bigger than left table
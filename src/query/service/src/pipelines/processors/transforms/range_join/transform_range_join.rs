@@ -22,12 +22,14 @@ use databend_common_pipeline_core::processors::Event;
 use databend_common_pipeline_core::processors::InputPort;
 use databend_common_pipeline_core::processors::OutputPort;
 use databend_common_pipeline_core::processors::Processor;
-use databend_common_pipeline_sinks::Sink;
+use databend_common_pipeline_sinks::AsyncSink;
 
 use crate::pipelines::processors::transforms::range_join::RangeJoinState;
 
 enum RangeJoinStep {
     Sink,
+    // Spill the sunk data of asof join when memory is tight
+    Spill,
     Merging,
     // Execute ie_join algo,
     Execute,
@@ -67,6 +69,8 @@ impl Processor for TransformRangeJoinLeft {
     fn name(&self) -> String {
         if self.state.ie_join_state.is_some() {
             "TransformIEJoinLeft".to_string()
+        } else if self.state.asof_join_state.is_some() {
+            "TransformAsofJoinLeft".to_string()
         } else {
             "TransformMergeJoinLeft".to_string()
         }
@@ -98,6 +102,7 @@ impl Processor for TransformRangeJoinLeft {
                     }
                 }
             }
+            RangeJoinStep::Spill => Ok(Event::Async),
             RangeJoinStep::Execute => {
                 if self.output_port.is_finished() {
                     return Ok(Event::Finished);
@@ -113,11 +118,14 @@ impl Processor for TransformRangeJoinLeft {
                     return Ok(Event::NeedConsume);
                 }
 
-                if !self.execute_finished {
-                    Ok(Event::Sync)
-                } else {
+                if self.execute_finished {
                     self.output_port.finish();
                     Ok(Event::Finished)
+                } else if self.state.asof_join_state.is_some() {
+                    // Asof join may need to read spilled data
+                    Ok(Event::Async)
+                } else {
+                    Ok(Event::Sync)
                 }
             }
             _ => unreachable!(),
@@ -129,6 +137,9 @@ impl Processor for TransformRangeJoinLeft {
             RangeJoinStep::Sink => {
                 if let Some(data_block) = self.input_data.take() {
                     self.state.sink_left(data_block)?;
+                    if self.state.need_spill() {
+                        self.step = RangeJoinStep::Spill;
+                    }
                 }
             }
             RangeJoinStep::Execute => {
@@ -154,9 +165,27 @@ impl Processor for TransformRangeJoinLeft {
 
     #[async_backtrace::framed]
     async fn async_process(&mut self) -> Result<()> {
-        if let RangeJoinStep::Merging = self.step {
-            self.state.wait_merge_finish().await?;
-            self.step = RangeJoinStep::Execute;
+        match self.step {
+            RangeJoinStep::Spill => {
+                self.state.spill().await?;
+                self.step = RangeJoinStep::Sink;
+            }
+            RangeJoinStep::Merging => {
+                self.state.wait_merge_finish().await?;
+                self.step = RangeJoinStep::Execute;
+            }
+            RangeJoinStep::Execute => {
+                if let Some(task_id) = self.state.task_id() {
+                    for block in self.state.asof_join(task_id).await? {
+                        if !block.is_empty() {
+                            self.output_data_blocks.push_back(block);
+                        }
+                    }
+                } else {
+                    self.execute_finished = true;
+                }
+            }
+            _ => unreachable!(),
         }
         Ok(())
     }
@@ -175,15 +204,22 @@ impl TransformRangeJoinRight {
     }
 }
 
-impl Sink for TransformRangeJoinRight {
+#[async_trait::async_trait]
+impl AsyncSink for TransformRangeJoinRight {
     const NAME: &'static str = "TransformRangeJoinRight";
 
-    fn on_finish(&mut self) -> Result<()> {
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self) -> Result<()> {
         self.state.right_detach()?;
         Ok(())
     }
 
-    fn consume(&mut self, data_block: DataBlock) -> Result<()> {
-        self.state.sink_right(data_block)
+    #[async_backtrace::framed]
+    async fn consume(&mut self, data_block: DataBlock) -> Result<bool> {
+        self.state.sink_right(data_block)?;
+        if self.state.need_spill() {
+            self.state.spill().await?;
+        }
+        Ok(false)
     }
 }
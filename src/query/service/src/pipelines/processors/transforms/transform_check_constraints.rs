@@ -0,0 +1,98 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::type_check::check_cast;
+use databend_common_expression::types::BooleanType;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NullableType;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::Evaluator;
+use databend_common_expression::Expr;
+use databend_common_expression::FunctionContext;
+use databend_common_expression::Value;
+use databend_common_functions::BUILTIN_FUNCTIONS;
+use databend_common_pipeline_transforms::processors::Transform;
+use databend_common_sql::parse_computed_expr;
+use databend_storages_common_table_meta::table::TableConstraint;
+
+use crate::sessions::QueryContext;
+
+/// Rejects the blocks containing a row for which the expression of a check constraint is
+/// false, the expression being NULL satisfies the constraint.
+pub struct TransformCheckConstraints {
+    func_ctx: FunctionContext,
+    table_name: String,
+    // (name, expr) of the check constraints
+    checks: Vec<(String, Expr)>,
+}
+
+impl TransformCheckConstraints
+where Self: Transform
+{
+    pub fn try_new(
+        ctx: Arc<QueryContext>,
+        schema: DataSchemaRef,
+        table_name: &str,
+        constraints: &[TableConstraint],
+    ) -> Result<Self> {
+        let mut checks = Vec::new();
+        for constraint in constraints {
+            if let Some(check_expr) = constraint.check_expr() {
+                let expr = parse_computed_expr(ctx.clone(), schema.clone(), check_expr)?;
+                let expr = check_cast(
+                    None,
+                    false,
+                    expr,
+                    &DataType::Nullable(Box::new(DataType::Boolean)),
+                    &BUILTIN_FUNCTIONS,
+                )?;
+                checks.push((constraint.name.clone(), expr));
+            }
+        }
+
+        Ok(Self {
+            func_ctx: ctx.get_function_context()?,
+            table_name: table_name.to_string(),
+            checks,
+        })
+    }
+}
+
+impl Transform for TransformCheckConstraints {
+    const NAME: &'static str = "CheckConstraintsTransform";
+
+    fn transform(&mut self, block: DataBlock) -> Result<DataBlock> {
+        let evaluator = Evaluator::new(&block, &self.func_ctx, &BUILTIN_FUNCTIONS);
+        for (name, expr) in &self.checks {
+            let value = evaluator.run(expr)?;
+            let violated = match value.try_downcast::<NullableType<BooleanType>>().unwrap() {
+                Value::Scalar(value) => value == Some(false),
+                Value::Column(column) => column.iter().any(|value| value == Some(false)),
+            };
+            if violated {
+                return Err(ErrorCode::ConstraintViolation(format!(
+                    "New row violates check constraint '{}' of table '{}'",
+                    name, self.table_name
+                )));
+            }
+        }
+        Ok(block)
+    }
+}
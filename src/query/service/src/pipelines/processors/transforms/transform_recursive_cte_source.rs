@@ -246,6 +246,9 @@ async fn create_memory_table_for_cte_scan(
         PhysicalPlan::WindowPartition(plan) => {
             create_memory_table_for_cte_scan(ctx, plan.input.as_ref()).await?;
         }
+        PhysicalPlan::MatchRecognize(plan) => {
+            create_memory_table_for_cte_scan(ctx, plan.input.as_ref()).await?;
+        }
         PhysicalPlan::Sort(plan) => {
             create_memory_table_for_cte_scan(ctx, plan.input.as_ref()).await?;
         }
@@ -16,6 +16,7 @@ use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 use arrow_array::RecordBatch;
 use arrow_udf_js::FunctionOptions;
@@ -24,6 +25,7 @@ use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::converts::arrow::ARROW_EXT_TYPE_VARIANT;
 use databend_common_expression::converts::arrow::EXTENSION_KEY;
+use databend_common_expression::infer_schema_type;
 use databend_common_expression::types::DataType;
 use databend_common_expression::variant_transform::contains_variant;
 use databend_common_expression::variant_transform::transform_variant;
@@ -32,7 +34,10 @@ use databend_common_expression::DataBlock;
 use databend_common_expression::DataField;
 use databend_common_expression::DataSchema;
 use databend_common_expression::FunctionContext;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
 use databend_common_pipeline_transforms::processors::Transform;
+use databend_common_settings::Settings;
 use databend_common_sql::executor::physical_plans::UdfFunctionDesc;
 use databend_common_sql::plans::UDFLanguage;
 use databend_common_sql::plans::UDFScriptCode;
@@ -41,6 +46,29 @@ use databend_common_sql::plans::UDFType;
 use super::runtime_pool::Pool;
 use super::runtime_pool::RuntimeBuilder;
 
+/// Resource limits of a single call into a script UDF runtime,
+/// only enforced by the python runtime for now.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptLimits {
+    pub timeout: Option<Duration>,
+    pub max_memory: Option<usize>,
+}
+
+impl ScriptLimits {
+    pub fn try_create(settings: &Settings) -> Result<Self> {
+        let timeout = settings.get_udf_script_timeout_secs()?;
+        let max_memory = settings.get_udf_script_max_memory()?;
+        Ok(Self {
+            timeout: (timeout > 0).then(|| Duration::from_secs(timeout)),
+            max_memory: (max_memory > 0).then_some(max_memory as usize),
+        })
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.timeout.is_some() || self.max_memory.is_some()
+    }
+}
+
 pub enum ScriptRuntime {
     JavaScript(JsRuntimePool),
     WebAssembly(arrow_udf_wasm::Runtime),
@@ -49,10 +77,11 @@ pub enum ScriptRuntime {
 }
 
 impl ScriptRuntime {
-    pub fn try_create(func: &UdfFunctionDesc) -> Result<Self> {
-        let UDFType::Script(UDFScriptCode { language, code, .. }) = &func.udf_type else {
+    pub fn try_create(func: &UdfFunctionDesc, limits: &ScriptLimits) -> Result<Self> {
+        let UDFType::Script(script) = &func.udf_type else {
             unreachable!()
         };
+        let UDFScriptCode { language, code, .. } = script;
         match language {
             UDFLanguage::JavaScript => {
                 let builder = JsRuntimeBuilder {
@@ -60,6 +89,7 @@ impl ScriptRuntime {
                     handler: func.func_name.clone(),
                     code: String::from_utf8(code.to_vec())?,
                     output_type: func.data_type.as_ref().clone(),
+                    table_columns: script.table_columns.clone(),
                     counter: Default::default(),
                 };
                 Ok(Self::JavaScript(JsRuntimePool::new(builder)))
@@ -83,16 +113,22 @@ impl ScriptRuntime {
                 let builder = PyRuntimeBuilder {
                     name: func.name.clone(),
                     handler: func.func_name.clone(),
-                    code: String::from_utf8(code.to_vec())?,
+                    code: super::python_script::build_python_script(script, limits, &[
+                        &func.func_name
+                    ])?,
                     output_type: func.data_type.as_ref().clone(),
+                    table_columns: script.table_columns.clone(),
                     counter: Default::default(),
                 };
                 Ok(Self::Python(python_pool::PyRuntimePool::new(builder)))
             }
             #[cfg(not(feature = "python-udf"))]
-            UDFLanguage::Python => Err(ErrorCode::EnterpriseFeatureNotEnable(
-                "Failed to create python script udf",
-            )),
+            UDFLanguage::Python => {
+                let _ = limits;
+                Err(ErrorCode::EnterpriseFeatureNotEnable(
+                    "Failed to create python script udf",
+                ))
+            }
         }
     }
 
@@ -158,6 +194,7 @@ pub struct JsRuntimeBuilder {
     handler: String,
     code: String,
     output_type: DataType,
+    table_columns: Vec<String>,
 
     counter: AtomicUsize,
 }
@@ -173,6 +210,7 @@ impl RuntimeBuilder<arrow_udf_js::Runtime> for JsRuntimeBuilder {
                 .map_err(|e| ErrorCode::UDFDataError(format!("Cannot create js runtime: {e}")))
         })?;
 
+        let output_field = script_output_field(&self.name, &self.output_type, &self.table_columns)?;
         let converter = runtime.converter_mut();
         converter.set_arrow_extension_key(EXTENSION_KEY);
         converter.set_json_extension_name(ARROW_EXT_TYPE_VARIANT);
@@ -184,7 +222,7 @@ impl RuntimeBuilder<arrow_udf_js::Runtime> for JsRuntimeBuilder {
                     // we pass the field instead of the data type because arrow-udf-js
                     // now takes the field as an argument here so that it can get any
                     // metadata associated with the field
-                    arrow_field_from_data_type(&self.name, self.output_type.clone()),
+                    output_field,
                     &self.code,
                     FunctionOptions::default()
                         .return_null_on_null_input()
@@ -211,6 +249,33 @@ fn arrow_field_from_data_type(name: &str, dt: DataType) -> arrow_schema::Field {
     (&field).into()
 }
 
+/// A table-valued UDF returns an array of tuples, the tuple fields are named
+/// after the table columns so the script can build rows keyed by column name.
+fn script_output_field(
+    name: &str,
+    output_type: &DataType,
+    table_columns: &[String],
+) -> Result<arrow_schema::Field> {
+    if table_columns.is_empty() {
+        return Ok(arrow_field_from_data_type(name, output_type.clone()));
+    }
+    match infer_schema_type(output_type)? {
+        TableDataType::Array(box TableDataType::Tuple { fields_type, .. })
+            if fields_type.len() == table_columns.len() =>
+        {
+            let data_type = TableDataType::Array(Box::new(TableDataType::Tuple {
+                fields_name: table_columns.to_vec(),
+                fields_type,
+            }));
+            Ok((&TableField::new(name, data_type)).into())
+        }
+        _ => Err(ErrorCode::UDFDataError(format!(
+            "Table function {name:?} must return an array of {} tuple fields, but got {output_type}",
+            table_columns.len()
+        ))),
+    }
+}
+
 type JsRuntimePool = Pool<arrow_udf_js::Runtime, JsRuntimeBuilder>;
 
 #[cfg(feature = "python-udf")]
@@ -219,6 +284,7 @@ pub struct PyRuntimeBuilder {
     handler: String,
     code: String,
     output_type: DataType,
+    table_columns: Vec<String>,
 
     counter: AtomicUsize,
 }
@@ -237,7 +303,7 @@ mod python_pool {
                 .build()?;
             runtime.add_function_with_handler(
                 &self.name,
-                arrow_field_from_data_type(&self.name, self.output_type.clone()),
+                script_output_field(&self.name, &self.output_type, &self.table_columns)?,
                 arrow_udf_python::CallMode::CalledOnNullInput,
                 &self.code,
                 &self.handler,
@@ -299,7 +365,10 @@ impl Transform for TransformUdfScript {
 }
 
 impl TransformUdfScript {
-    pub fn init_runtime(funcs: &[UdfFunctionDesc]) -> Result<BTreeMap<String, Arc<ScriptRuntime>>> {
+    pub fn init_runtime(
+        funcs: &[UdfFunctionDesc],
+        limits: &ScriptLimits,
+    ) -> Result<BTreeMap<String, Arc<ScriptRuntime>>> {
         let mut script_runtimes: BTreeMap<String, Arc<ScriptRuntime>> = BTreeMap::new();
 
         for func in funcs {
@@ -309,7 +378,7 @@ impl TransformUdfScript {
             };
 
             if let Entry::Vacant(entry) = script_runtimes.entry(func.name.clone()) {
-                let runtime = ScriptRuntime::try_create(func).map_err(|err| {
+                let runtime = ScriptRuntime::try_create(func, limits).map_err(|err| {
                     ErrorCode::UDFDataError(format!(
                         "Failed to create UDF runtime for language {:?} with error: {err}",
                         code.language
@@ -14,10 +14,12 @@
 
 mod frame_bound;
 mod partition;
+mod transform_match_recognize;
 mod transform_window;
 mod window_function;
 
 pub use frame_bound::FrameBound;
 pub use partition::*;
+pub use transform_match_recognize::TransformMatchRecognize;
 pub use transform_window::*;
 pub use window_function::WindowFunctionInfo;
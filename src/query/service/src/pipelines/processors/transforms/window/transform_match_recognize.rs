@@ -0,0 +1,479 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::BooleanType;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::BlockEntry;
+use databend_common_expression::ColumnBuilder;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchema;
+use databend_common_expression::Evaluator;
+use databend_common_expression::Expr;
+use databend_common_expression::FunctionContext;
+use databend_common_expression::ScalarRef;
+use databend_common_expression::Value;
+use databend_common_functions::BUILTIN_FUNCTIONS;
+use databend_common_pipeline_transforms::processors::AccumulatingTransform;
+use databend_common_sql::executor::physical_plans::MatchFunctionDesc;
+use databend_common_sql::executor::physical_plans::MatchRecognize;
+use databend_common_sql::executor::physical_plans::WindowFunction;
+use databend_common_sql::plans::MatchInstruction;
+use databend_common_sql::plans::MatchRowsPerMatch;
+use databend_common_sql::plans::MatchSkip;
+
+use super::window_function::WindowFuncAggImpl;
+use super::window_function::WindowFunctionImpl;
+use super::WindowFunctionInfo;
+
+struct MatchNavigationImpl {
+    arg: usize,
+    offset: i64,
+    data_type: DataType,
+}
+
+enum MatchFunctionImpl {
+    First(usize),
+    Last(usize),
+    Aggregate(WindowFuncAggImpl),
+    MatchNumber,
+    Classifier,
+}
+
+struct MatchMeasureImpl {
+    func: MatchFunctionImpl,
+    variable: Option<usize>,
+    data_type: DataType,
+}
+
+/// A match found in a partition, `variables[i]` is the pattern variable
+/// the `start + i` row is mapped to.
+struct RowMatch {
+    start: usize,
+    variables: Vec<usize>,
+    number: u64,
+}
+
+/// `TransformMatchRecognize` finds the row pattern matches in each partition.
+///
+/// The input is sorted by the partition keys and the order keys, and the rows of a
+/// partition are in the same stream, which is guaranteed by the window partition
+/// exchange and `TransformWindowPartitionCollect`.
+pub struct TransformMatchRecognize {
+    func_ctx: FunctionContext,
+    partition_by: Vec<usize>,
+    navigations: Vec<MatchNavigationImpl>,
+    variable_names: Vec<String>,
+    conditions: Vec<Option<Expr>>,
+    pattern: Vec<MatchInstruction>,
+    measures: Vec<MatchMeasureImpl>,
+    rows_per_match: MatchRowsPerMatch,
+    after_match_skip: MatchSkip,
+
+    /// The blocks of the partition that is not ended yet.
+    blocks: Vec<DataBlock>,
+}
+
+impl TransformMatchRecognize {
+    pub fn try_create(
+        func_ctx: FunctionContext,
+        plan: &MatchRecognize,
+        input_schema: &DataSchema,
+    ) -> Result<Self> {
+        let partition_by = plan
+            .partition_by
+            .iter()
+            .map(|index| input_schema.index_of(&index.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        let navigations = plan
+            .navigations
+            .iter()
+            .map(|navigation| {
+                Ok(MatchNavigationImpl {
+                    arg: input_schema.index_of(&navigation.arg.to_string())?,
+                    offset: navigation.offset,
+                    data_type: navigation.data_type.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let measures = plan
+            .measures
+            .iter()
+            .map(|measure| {
+                let func = match &measure.func {
+                    MatchFunctionDesc::First(arg) => {
+                        MatchFunctionImpl::First(input_schema.index_of(&arg.to_string())?)
+                    }
+                    MatchFunctionDesc::Last(arg) => {
+                        MatchFunctionImpl::Last(input_schema.index_of(&arg.to_string())?)
+                    }
+                    MatchFunctionDesc::Aggregate(agg) => {
+                        let info = WindowFunctionInfo::try_create(
+                            &WindowFunction::Aggregate(agg.clone()),
+                            input_schema,
+                        )?;
+                        match WindowFunctionImpl::try_create(info)? {
+                            WindowFunctionImpl::Aggregate(agg) => MatchFunctionImpl::Aggregate(agg),
+                            _ => unreachable!(),
+                        }
+                    }
+                    MatchFunctionDesc::MatchNumber => MatchFunctionImpl::MatchNumber,
+                    MatchFunctionDesc::Classifier => MatchFunctionImpl::Classifier,
+                };
+                Ok(MatchMeasureImpl {
+                    func,
+                    variable: measure.variable,
+                    data_type: measure.data_type.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            func_ctx,
+            partition_by,
+            navigations,
+            variable_names: plan.variables.iter().map(|v| v.name.clone()).collect(),
+            conditions: plan
+                .variables
+                .iter()
+                .map(|v| v.condition.as_ref().map(|c| c.as_expr(&BUILTIN_FUNCTIONS)))
+                .collect(),
+            pattern: plan.pattern.clone(),
+            measures,
+            rows_per_match: plan.rows_per_match,
+            after_match_skip: plan.after_match_skip,
+            blocks: vec![],
+        })
+    }
+
+    fn is_same_partition(
+        &self,
+        lhs: &DataBlock,
+        lhs_row: usize,
+        rhs: &DataBlock,
+        rhs_row: usize,
+    ) -> bool {
+        self.partition_by.iter().all(|offset| {
+            lhs.get_by_offset(*offset).value.index(lhs_row)
+                == rhs.get_by_offset(*offset).value.index(rhs_row)
+        })
+    }
+
+    /// Process the buffered blocks as a whole partition.
+    fn flush_partition(&mut self) -> Result<Option<DataBlock>> {
+        if self.blocks.is_empty() {
+            return Ok(None);
+        }
+        let partition = DataBlock::concat(&std::mem::take(&mut self.blocks))?;
+        self.process_partition(partition)
+    }
+
+    fn process_partition(&mut self, partition: DataBlock) -> Result<Option<DataBlock>> {
+        let num_rows = partition.num_rows();
+        if num_rows == 0 {
+            return Ok(None);
+        }
+        let partition = partition.consume_convert_to_full();
+
+        // 1. Evaluate the `DEFINE` conditions of all the rows in the partition.
+        let mut condition_block = partition.clone();
+        for navigation in self.navigations.iter() {
+            let arg = &partition.get_by_offset(navigation.arg).value;
+            let mut builder = ColumnBuilder::with_capacity(&navigation.data_type, num_rows);
+            for row in 0..num_rows {
+                let target = row as i64 + navigation.offset;
+                if target >= 0 && (target as usize) < num_rows {
+                    builder.push(arg.index(target as usize).unwrap());
+                } else {
+                    builder.push(ScalarRef::Null);
+                }
+            }
+            condition_block.add_column(BlockEntry::new(
+                navigation.data_type.clone(),
+                Value::Column(builder.build()),
+            ));
+        }
+        let evaluator = Evaluator::new(&condition_block, &self.func_ctx, &BUILTIN_FUNCTIONS);
+        let conditions = self
+            .conditions
+            .iter()
+            .map(|condition| {
+                condition
+                    .as_ref()
+                    .map(|expr| {
+                        let value = evaluator.run(expr)?;
+                        value.try_downcast::<BooleanType>().ok_or_else(|| {
+                            ErrorCode::Internal("DEFINE condition must be a boolean expression")
+                        })
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // 2. Find the matches.
+        let mut matches = vec![];
+        let mut start = 0;
+        while start < num_rows {
+            let variables = match self.find_match(start, num_rows, &conditions) {
+                // Empty matches are not shown.
+                Some(variables) if !variables.is_empty() => variables,
+                _ => {
+                    start += 1;
+                    continue;
+                }
+            };
+            let next = match self.after_match_skip {
+                MatchSkip::PastLastRow => Some(start + variables.len()),
+                MatchSkip::ToNextRow => Some(start + 1),
+                MatchSkip::ToFirst(variable) => variables
+                    .iter()
+                    .position(|v| *v == variable)
+                    .map(|i| start + i),
+                MatchSkip::ToLast(variable) => variables
+                    .iter()
+                    .rposition(|v| *v == variable)
+                    .map(|i| start + i),
+            };
+            let next = match next {
+                Some(next) if next > start => next,
+                _ => {
+                    return Err(ErrorCode::BadArguments(format!(
+                        "AFTER MATCH SKIP failed at row {}, the row to skip to is not in the match or is the first row of the match",
+                        start + 1
+                    )));
+                }
+            };
+            matches.push(RowMatch {
+                start,
+                variables,
+                number: matches.len() as u64 + 1,
+            });
+            start = next;
+        }
+        if matches.is_empty() {
+            return Ok(None);
+        }
+
+        // 3. Compute the measures.
+        let mut indices: Vec<u32> = vec![];
+        let mut builders = self
+            .measures
+            .iter()
+            .map(|measure| ColumnBuilder::with_capacity(&measure.data_type, matches.len()))
+            .collect::<Vec<_>>();
+        for row_match in matches.iter() {
+            match self.rows_per_match {
+                MatchRowsPerMatch::OneRow => {
+                    indices.push(row_match.start as u32);
+                    let end = row_match.variables.len() - 1;
+                    for (measure, builder) in self.measures.iter().zip(builders.iter_mut()) {
+                        self.compute_measure(measure, &partition, row_match, end, true, builder)?;
+                    }
+                }
+                MatchRowsPerMatch::AllRows => {
+                    for current in 0..row_match.variables.len() {
+                        indices.push((row_match.start + current) as u32);
+                        for (measure, builder) in self.measures.iter().zip(builders.iter_mut()) {
+                            self.compute_measure(
+                                measure,
+                                &partition,
+                                row_match,
+                                current,
+                                current == 0,
+                                builder,
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+
+        // 4. Build the output block.
+        let rows = partition.take(&indices)?;
+        let mut output = match self.rows_per_match {
+            MatchRowsPerMatch::OneRow => DataBlock::new(
+                self.partition_by
+                    .iter()
+                    .map(|offset| rows.get_by_offset(*offset).clone())
+                    .collect(),
+                indices.len(),
+            ),
+            MatchRowsPerMatch::AllRows => rows,
+        };
+        for (measure, builder) in self.measures.iter().zip(builders) {
+            output.add_column(BlockEntry::new(
+                measure.data_type.clone(),
+                Value::Column(builder.build()),
+            ));
+        }
+        Ok(Some(output))
+    }
+
+    /// Find the preferred match starting at `start` by backtracking, returns the
+    /// variables the matched rows are mapped to.
+    fn find_match(
+        &self,
+        start: usize,
+        num_rows: usize,
+        conditions: &[Option<Value<BooleanType>>],
+    ) -> Option<Vec<usize>> {
+        let is_true = |variable: usize, row: usize| match &conditions[variable] {
+            None => true,
+            Some(Value::Scalar(value)) => *value,
+            Some(Value::Column(bitmap)) => bitmap.get_bit(row),
+        };
+
+        // A state that has been visited can't lead to a match, because the rest
+        // of the matching only depends on the instruction and the row.
+        let mut visited = HashSet::new();
+        let mut path = vec![];
+        let mut stack = vec![(0, start, 0)];
+        while let Some((mut pc, mut row, path_len)) = stack.pop() {
+            path.truncate(path_len);
+            while visited.insert((pc, row)) {
+                match self.pattern[pc] {
+                    MatchInstruction::Variable(variable) => {
+                        if row >= num_rows || !is_true(variable, row) {
+                            break;
+                        }
+                        path.push(variable);
+                        pc += 1;
+                        row += 1;
+                    }
+                    MatchInstruction::Split(first, second) => {
+                        stack.push((second, row, path.len()));
+                        pc = first;
+                    }
+                    MatchInstruction::Jump(target) => pc = target,
+                    MatchInstruction::PartitionStart => {
+                        if row != 0 {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    MatchInstruction::PartitionEnd => {
+                        if row != num_rows {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    MatchInstruction::Match => return Some(path),
+                }
+            }
+        }
+        None
+    }
+
+    /// Compute the measure for the `current` row of the match. The measures of the
+    /// rows of a match are computed in order, `reset` is true for the first one.
+    fn compute_measure(
+        &self,
+        measure: &MatchMeasureImpl,
+        partition: &DataBlock,
+        row_match: &RowMatch,
+        current: usize,
+        reset: bool,
+        builder: &mut ColumnBuilder,
+    ) -> Result<()> {
+        let is_selected = |i: usize| match measure.variable {
+            Some(variable) => row_match.variables[i] == variable,
+            None => true,
+        };
+        match &measure.func {
+            MatchFunctionImpl::First(arg) | MatchFunctionImpl::Last(arg) => {
+                let mut rows = (0..=current).filter(|i| is_selected(*i));
+                let row = if matches!(measure.func, MatchFunctionImpl::First(_)) {
+                    rows.next()
+                } else {
+                    rows.last()
+                };
+                match row {
+                    Some(i) => {
+                        let value = &partition.get_by_offset(*arg).value;
+                        builder.push(value.index(row_match.start + i).unwrap());
+                    }
+                    None => builder.push(ScalarRef::Null),
+                }
+            }
+            MatchFunctionImpl::Aggregate(agg) => {
+                // The rows before `current` have been accumulated unless it's the first one.
+                let from = if reset {
+                    agg.reset();
+                    0
+                } else {
+                    current
+                };
+                let args = agg.arg_columns(partition);
+                for i in from..=current {
+                    if is_selected(i) {
+                        agg.accumulate_row(args, row_match.start + i)?;
+                    }
+                }
+                agg.merge_result(builder)?;
+            }
+            MatchFunctionImpl::MatchNumber => {
+                builder.push(ScalarRef::Number(NumberScalar::UInt64(row_match.number)));
+            }
+            MatchFunctionImpl::Classifier => {
+                let variable = row_match.variables[current];
+                builder.push(ScalarRef::String(&self.variable_names[variable]));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AccumulatingTransform for TransformMatchRecognize {
+    const NAME: &'static str = "TransformMatchRecognize";
+
+    fn transform(&mut self, data: DataBlock) -> Result<Vec<DataBlock>> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        if self.partition_by.is_empty() {
+            self.blocks.push(data);
+            return Ok(vec![]);
+        }
+
+        let data = data.consume_convert_to_full();
+        let mut output = vec![];
+        let mut partition_start = 0;
+        if let Some(last) = self.blocks.last() {
+            if !self.is_same_partition(last, last.num_rows() - 1, &data, 0) {
+                output.extend(self.flush_partition()?);
+            }
+        }
+        for row in 1..data.num_rows() {
+            if !self.is_same_partition(&data, row - 1, &data, row) {
+                self.blocks.push(data.slice(partition_start..row));
+                output.extend(self.flush_partition()?);
+                partition_start = row;
+            }
+        }
+        self.blocks
+            .push(data.slice(partition_start..data.num_rows()));
+        Ok(output)
+    }
+
+    fn on_finish(&mut self, output: bool) -> Result<Vec<DataBlock>> {
+        if !output {
+            return Ok(vec![]);
+        }
+        Ok(self.flush_partition()?.into_iter().collect())
+    }
+}
@@ -38,6 +38,7 @@ use databend_common_pipeline_core::processors::InputPort;
 use databend_common_pipeline_core::processors::OutputPort;
 use databend_common_pipeline_core::processors::Processor;
 use databend_common_sql::executor::physical_plans::LagLeadDefault;
+use databend_common_sql::plans::WindowFuncFrameExclusion;
 use databend_common_sql::plans::WindowFuncFrameUnits;
 
 use super::frame_bound::FrameBound;
@@ -119,10 +120,13 @@ pub struct TransformWindow<T: Number> {
     start_bound: FrameBound<T>,
     end_bound: FrameBound<T>,
 
-    // Only used for ROWS frame, default value: 0. (when not used)
+    // Only used for ROWS and GROUPS frame, default value: 0. (when not used)
     rows_start_bound: usize,
     rows_end_bound: usize,
 
+    // The rows excluded from the frame of the current row.
+    exclusion: WindowFuncFrameExclusion,
+
     // NULL frame is a special RANGE frame, we need to check if the frame is a null frame.
     need_check_null_frame: bool,
     // If current frame is a null frame. This is only used when `need_check_null_frame` is true.
@@ -133,6 +137,11 @@ pub struct TransformWindow<T: Number> {
     frame_started: bool,
     frame_ended: bool,
 
+    // Only used for GROUPS frame, the peer group number (counting from 1)
+    // of the rows at `frame_start` and `frame_end` in the partition.
+    frame_start_group: usize,
+    frame_end_group: usize,
+
     // Can be used to optimize window frame sliding.
     prev_frame_start: RowPtr,
     prev_frame_end: RowPtr,
@@ -318,7 +327,77 @@ impl<T: Number> TransformWindow<T> {
         .min(self.partition_end);
     }
 
-    /// This function is used for both `ROWS` and `RANGE`.
+    /// Move `row` of peer group `group` forward to the first row of peer group `target`,
+    /// or to the end of the partition if there are not enough peer groups.
+    ///
+    /// Returns `None` if more data is needed to know where peer group `target` starts.
+    fn advance_to_group(
+        &self,
+        mut row: RowPtr,
+        mut group: usize,
+        target: usize,
+    ) -> Option<(RowPtr, usize)> {
+        while group < target && row < self.partition_end {
+            let next = self.advance_row(row);
+            if next == self.partition_end {
+                if !self.partition_ended {
+                    return None;
+                }
+            } else if !self.are_peers(&row, &next, false) {
+                group += 1;
+            }
+            row = next;
+        }
+        Some((row, group))
+    }
+
+    fn advance_frame_start_groups_preceding(&mut self, n: usize) {
+        let target = self.current_dense_rank.saturating_sub(n).max(1);
+        // The peer groups before the current row are always available.
+        let (frame_start, group) = self
+            .advance_to_group(self.frame_start, self.frame_start_group, target)
+            .unwrap();
+        self.frame_start = frame_start;
+        self.frame_start_group = group;
+        self.frame_started = true;
+    }
+
+    fn advance_frame_start_groups_following(&mut self, n: usize) {
+        let target = self.current_dense_rank + n;
+        if let Some((frame_start, group)) =
+            self.advance_to_group(self.frame_start, self.frame_start_group, target)
+        {
+            self.frame_start = frame_start;
+            self.frame_start_group = group;
+            self.frame_started = true;
+        }
+    }
+
+    fn advance_frame_end_groups_preceding(&mut self, n: usize) {
+        // `self.frame_end` is excluded, so it is the first row of the next peer group.
+        // With `0 PRECEDING` that is the end of the current peer group, which may need more data.
+        let target = self.current_dense_rank.saturating_sub(n) + 1;
+        if let Some((frame_end, group)) =
+            self.advance_to_group(self.frame_end, self.frame_end_group, target)
+        {
+            self.frame_end = frame_end;
+            self.frame_end_group = group;
+            self.frame_ended = true;
+        }
+    }
+
+    fn advance_frame_end_groups_following(&mut self, n: usize) {
+        let target = self.current_dense_rank + n + 1;
+        if let Some((frame_end, group)) =
+            self.advance_to_group(self.frame_end, self.frame_end_group, target)
+        {
+            self.frame_end = frame_end;
+            self.frame_end_group = group;
+            self.frame_ended = true;
+        }
+    }
+
+    /// This function is used for `ROWS`, `RANGE` and `GROUPS`.
     fn advance_frame_end_current_row(&mut self) {
         // Every frame must be processed to the end of the input block if the its partition is started.
         debug_assert!(
@@ -466,12 +545,16 @@ impl<T: Number> TransformWindow<T> {
         }
 
         // Release memory that is no longer needed.
-        let first_used_block = if self.is_ranking {
+        let mut first_used_block = if self.is_ranking {
             self.next_output_block.min(self.peer_group_start.block)
         } else {
             self.next_output_block.min(self.prev_frame_start.block)
         }
         .min(self.current_row.block);
+        if self.frame_unit.is_groups() || self.exclusion != WindowFuncFrameExclusion::NoOthers {
+            // The peer group of the current row is used to count the peer groups and exclude rows.
+            first_used_block = first_used_block.min(self.peer_group_start.block);
+        }
 
         if self.first_block < first_used_block {
             self.blocks.drain(..first_used_block - self.first_block);
@@ -526,6 +609,69 @@ impl<T: Number> TransformWindow<T> {
         Ok(())
     }
 
+    /// If the row is removed from the frame of the current row by the `EXCLUDE` clause.
+    fn is_excluded(&self, row: &RowPtr) -> bool {
+        match self.exclusion {
+            WindowFuncFrameExclusion::NoOthers => false,
+            WindowFuncFrameExclusion::CurrentRow => *row == self.current_row,
+            WindowFuncFrameExclusion::Group => {
+                self.peer_group_start <= *row && *row < self.peer_group_end
+            }
+            WindowFuncFrameExclusion::Ties => {
+                *row != self.current_row
+                    && self.peer_group_start <= *row
+                    && *row < self.peer_group_end
+            }
+        }
+    }
+
+    /// The excluded rows are removed from the middle of the frame, so the aggregation
+    /// cannot slide over the frames and is computed from scratch for every row.
+    fn apply_aggregate_with_exclusion(&self, agg: &WindowFuncAggImpl) -> Result<()> {
+        agg.reset();
+
+        let mut row = self.frame_start;
+        while row < self.frame_end {
+            if !self.is_excluded(&row) {
+                let data = self.block_at(&row);
+                agg.accumulate_row(agg.arg_columns(data), row.row)?;
+            }
+            row = self.advance_row(row);
+        }
+        Ok(())
+    }
+
+    /// Get the nth (or the last if `n` is `None`) value of the frame without the excluded rows.
+    fn get_nth_value_with_exclusion(
+        &self,
+        n: Option<u64>,
+        arg_index: usize,
+        ignore_null: bool,
+    ) -> Scalar {
+        let mut value = Scalar::Null;
+        let mut count = 0;
+        let mut row = self.frame_start;
+        while row < self.frame_end {
+            if !self.is_excluded(&row) {
+                let current = unsafe { self.column_at(&row, arg_index).index_unchecked(row.row) };
+                if !(ignore_null && current.is_null()) {
+                    count += 1;
+                    match n {
+                        Some(n) if count == n => return current.to_owned(),
+                        Some(_) => {}
+                        None => value = current.to_owned(),
+                    }
+                }
+            }
+            row = self.advance_row(row);
+        }
+        if n.is_some() {
+            Scalar::Null
+        } else {
+            value
+        }
+    }
+
     #[inline]
     fn merge_result_of_current_row(&mut self) -> Result<()> {
         match &self.func {
@@ -587,6 +733,8 @@ impl<T: Number> TransformWindow<T> {
             WindowFunctionImpl::NthValue(func) => {
                 let value = if self.frame_start == self.frame_end {
                     Scalar::Null
+                } else if self.exclusion != WindowFuncFrameExclusion::NoOthers {
+                    self.get_nth_value_with_exclusion(func.n, func.arg, func.ignore_null)
                 } else if let Some(mut n) = func.n {
                     let mut cur = self.frame_start;
                     // n is counting from 1
@@ -750,6 +898,7 @@ impl TransformWindow<u64> {
         partition_indices: Vec<usize>,
         order_by: Vec<WindowSortDesc>,
         bounds: (FrameBound<u64>, FrameBound<u64>),
+        exclusion: WindowFuncFrameExclusion,
     ) -> Result<Self> {
         let func = WindowFunctionImpl::try_create(func)?;
         let (start_bound, end_bound) = bounds;
@@ -765,6 +914,12 @@ impl TransformWindow<u64> {
         let rows_start_bound = start_bound.get_inner().unwrap_or_default() as usize;
         let rows_end_bound = end_bound.get_inner().unwrap_or_default() as usize;
 
+        // The peer group of the current row is excluded from the frame.
+        let need_peer = matches!(
+            exclusion,
+            WindowFuncFrameExclusion::Group | WindowFuncFrameExclusion::Ties
+        );
+
         Ok(Self {
             input,
             output,
@@ -785,18 +940,21 @@ impl TransformWindow<u64> {
             end_bound,
             rows_start_bound,
             rows_end_bound,
+            exclusion,
             need_check_null_frame: false,
             is_null_frame: false,
             frame_start: RowPtr::default(),
             frame_end: RowPtr::default(),
             frame_started: false,
             frame_ended: false,
+            frame_start_group: 1,
+            frame_end_group: 1,
             prev_frame_start: RowPtr::default(),
             prev_frame_end: RowPtr::default(),
             peer_group_start: RowPtr::default(),
             peer_group_end: RowPtr::default(),
             peer_group_ended: false,
-            need_peer: false,
+            need_peer,
             current_row: RowPtr::default(),
             current_row_in_partition: 1,
             current_rank: 1,
@@ -807,6 +965,30 @@ impl TransformWindow<u64> {
             is_ranking,
         })
     }
+
+    /// The offsets of GROUPS frame are counted in peer groups,
+    /// so it shares the [`FrameBound<u64>`] bounds with ROWS frame.
+    pub fn try_create_groups(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        func: WindowFunctionInfo,
+        partition_indices: Vec<usize>,
+        order_by: Vec<WindowSortDesc>,
+        bounds: (FrameBound<u64>, FrameBound<u64>),
+        exclusion: WindowFuncFrameExclusion,
+    ) -> Result<Self> {
+        let mut transform = Self::try_create_rows(
+            input,
+            output,
+            func,
+            partition_indices,
+            order_by,
+            bounds,
+            exclusion,
+        )?;
+        transform.frame_unit = WindowFuncFrameUnits::Groups;
+        Ok(transform)
+    }
 }
 
 // For RANGE frame
@@ -821,6 +1003,7 @@ where T: Number + ResultTypeOfUnary
         partition_indices: Vec<usize>,
         order_by: Vec<WindowSortDesc>,
         bounds: (FrameBound<T>, FrameBound<T>),
+        exclusion: WindowFuncFrameExclusion,
     ) -> Result<Self> {
         let func = WindowFunctionImpl::try_create(func)?;
         let (start_bound, end_bound) = bounds;
@@ -840,7 +1023,11 @@ where T: Number + ResultTypeOfUnary
             false
         };
 
-        let need_peer = matches!(func, WindowFunctionImpl::CumeDist);
+        let need_peer = matches!(func, WindowFunctionImpl::CumeDist)
+            || matches!(
+                exclusion,
+                WindowFuncFrameExclusion::Group | WindowFuncFrameExclusion::Ties
+            );
 
         Ok(Self {
             input,
@@ -862,12 +1049,15 @@ where T: Number + ResultTypeOfUnary
             end_bound,
             rows_start_bound: 0,
             rows_end_bound: 0,
+            exclusion,
             need_check_null_frame,
             is_null_frame: false,
             frame_start: RowPtr::default(),
             frame_end: RowPtr::default(),
             frame_started: false,
             frame_ended: false,
+            frame_start_group: 1,
+            frame_end_group: 1,
             prev_frame_start: RowPtr::default(),
             prev_frame_end: RowPtr::default(),
             peer_group_start: RowPtr::default(),
@@ -917,20 +1107,23 @@ where T: Number + ResultTypeOfUnary
                 debug_assert!(self.peer_group_start <= self.current_row);
 
                 self.frame_started = true;
-                if self.frame_unit.is_range() {
-                    self.frame_start = self.peer_group_start;
-                } else {
+                if self.frame_unit.is_rows() {
                     self.frame_start = self.current_row;
+                } else {
+                    self.frame_start = self.peer_group_start;
+                    self.frame_start_group = self.current_dense_rank;
                 }
             }
             FrameBound::Preceding(Some(n)) => {
-                debug_assert!(self.frame_unit.is_rows() || self.order_by.len() == 1);
+                debug_assert!(!self.frame_unit.is_range() || self.order_by.len() == 1);
 
                 if self.is_null_frame {
                     self.frame_started = true;
                     self.frame_start = self.peer_group_start;
                 } else if self.frame_unit.is_rows() {
                     self.advance_frame_start_rows_preceding(self.rows_start_bound);
+                } else if self.frame_unit.is_groups() {
+                    self.advance_frame_start_groups_preceding(self.rows_start_bound);
                 } else if self.order_by[0].is_nullable {
                     self.advance_frame_start_nullable_range(*n, true);
                 } else {
@@ -941,13 +1134,15 @@ where T: Number + ResultTypeOfUnary
                 self.frame_started = true;
             }
             FrameBound::Following(Some(n)) => {
-                debug_assert!(self.frame_unit.is_rows() || self.order_by.len() == 1);
+                debug_assert!(!self.frame_unit.is_range() || self.order_by.len() == 1);
 
                 if self.is_null_frame {
                     self.frame_started = true;
                     self.frame_start = self.peer_group_start;
                 } else if self.frame_unit.is_rows() {
                     self.advance_frame_start_rows_following(self.rows_start_bound);
+                } else if self.frame_unit.is_groups() {
+                    self.advance_frame_start_groups_following(self.rows_start_bound);
                 } else if self.order_by[0].is_nullable {
                     self.advance_frame_start_nullable_range(*n, false);
                 } else {
@@ -968,12 +1163,14 @@ where T: Number + ResultTypeOfUnary
                 self.advance_frame_end_current_row();
             }
             FrameBound::Preceding(Some(n)) => {
-                debug_assert!(self.frame_unit.is_rows() || self.order_by.len() == 1);
+                debug_assert!(!self.frame_unit.is_range() || self.order_by.len() == 1);
 
                 if self.is_null_frame {
                     self.advance_frame_end_current_row();
                 } else if self.frame_unit.is_rows() {
                     self.advance_frame_end_rows_preceding(self.rows_end_bound);
+                } else if self.frame_unit.is_groups() {
+                    self.advance_frame_end_groups_preceding(self.rows_end_bound);
                 } else if self.order_by[0].is_nullable {
                     self.advance_frame_end_nullable_range(*n, true);
                 } else {
@@ -984,12 +1181,14 @@ where T: Number + ResultTypeOfUnary
                 unreachable!()
             }
             FrameBound::Following(Some(n)) => {
-                debug_assert!(self.frame_unit.is_rows() || self.order_by.len() == 1);
+                debug_assert!(!self.frame_unit.is_range() || self.order_by.len() == 1);
 
                 if self.is_null_frame {
                     self.advance_frame_end_current_row();
                 } else if self.frame_unit.is_rows() {
                     self.advance_frame_end_rows_following(self.rows_end_bound);
+                } else if self.frame_unit.is_groups() {
+                    self.advance_frame_end_groups_following(self.rows_end_bound);
                 } else if self.order_by[0].is_nullable {
                     self.advance_frame_end_nullable_range(*n, false);
                 } else {
@@ -1005,6 +1204,11 @@ where T: Number + ResultTypeOfUnary
 
     fn compute_on_frame(&mut self) -> Result<()> {
         match &self.func {
+            WindowFunctionImpl::Aggregate(agg)
+                if self.exclusion != WindowFuncFrameExclusion::NoOthers =>
+            {
+                self.apply_aggregate_with_exclusion(agg)
+            }
             WindowFunctionImpl::Aggregate(agg) => self.apply_aggregate(agg),
             _ => Ok(()),
         }
@@ -1075,6 +1279,11 @@ where T: Number + ResultTypeOfUnary
                     self.advance_peer_group_end(self.current_row);
                 }
 
+                // The peer group may be extended by the new block.
+                if self.need_peer && !self.peer_group_ended {
+                    self.advance_peer_group_end(self.peer_group_start);
+                }
+
                 if self.need_peer && self.partition_ended {
                     self.peer_group_ended = true;
                 }
@@ -1103,6 +1312,7 @@ where T: Number + ResultTypeOfUnary
 
                     if self.frame_end < self.frame_start {
                         self.frame_end = self.frame_start;
+                        self.frame_end_group = self.frame_start_group;
                     }
 
                     self.advance_frame_end();
@@ -1153,6 +1363,8 @@ where T: Number + ResultTypeOfUnary
                 self.is_null_frame = false;
                 self.frame_start = self.partition_start;
                 self.frame_end = self.partition_start;
+                self.frame_start_group = 1;
+                self.frame_end_group = 1;
                 self.prev_frame_start = self.frame_start;
                 self.prev_frame_end = self.frame_end;
 
@@ -1374,6 +1586,7 @@ mod tests {
     use databend_common_pipeline_core::processors::InputPort;
     use databend_common_pipeline_core::processors::OutputPort;
     use databend_common_pipeline_core::processors::Processor;
+    use databend_common_sql::plans::WindowFuncFrameExclusion;
     use databend_common_sql::plans::WindowFuncFrameUnits;
 
     use super::TransformWindow;
@@ -1399,6 +1612,7 @@ mod tests {
                 is_nullable: false,
             }],
             bounds,
+            WindowFuncFrameExclusion::NoOthers,
         )
     }
 
@@ -1422,6 +1636,7 @@ mod tests {
                 is_nullable: false,
             }],
             bounds,
+            WindowFuncFrameExclusion::NoOthers,
         )
     }
 
@@ -1440,6 +1655,7 @@ mod tests {
             vec![0],
             vec![],
             bounds,
+            WindowFuncFrameExclusion::NoOthers,
         )
     }
 
@@ -2292,6 +2508,7 @@ mod tests {
             vec![0],
             vec![],
             bounds,
+            WindowFuncFrameExclusion::NoOthers,
         )?;
 
         Ok((Box::new(transform), input, output))
@@ -0,0 +1,72 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_ast::ast::CopyIntoTableSource;
+use databend_common_ast::ast::FileLocation;
+use databend_common_ast::ast::Statement;
+use databend_common_ast::parser::parse_sql;
+use databend_common_ast::parser::tokenize_sql;
+use databend_common_ast::parser::Dialect;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::PipeInfo;
+
+use crate::interpreters::execute_statement;
+use crate::sessions::QueryContext;
+
+/// Run the `COPY INTO <table>` statement of the pipe once.
+///
+/// Files loaded by the previous runs are skipped by COPY itself, so only new files are loaded.
+/// If `prefix` is given, only the files under `<stage location>/<prefix>` are considered.
+#[async_backtrace::framed]
+pub async fn execute_pipe(
+    ctx: Arc<QueryContext>,
+    pipe: &PipeInfo,
+    prefix: Option<&str>,
+) -> Result<()> {
+    let sql = match prefix {
+        None => pipe.copy_stmt.clone(),
+        Some(prefix) => {
+            let tokens = tokenize_sql(&pipe.copy_stmt)?;
+            let (stmt, _) = parse_sql(&tokens, Dialect::PostgreSQL)?;
+            let Statement::CopyIntoTable(mut copy_stmt) = stmt else {
+                return Err(ErrorCode::IllegalPipe(format!(
+                    "pipe '{}' is not defined by COPY INTO <table>",
+                    pipe.name
+                )));
+            };
+            match &mut copy_stmt.src {
+                CopyIntoTableSource::Location(FileLocation::Stage(location)) => {
+                    *location = format!(
+                        "{}/{}",
+                        location.trim_end_matches('/'),
+                        prefix.trim_start_matches('/')
+                    );
+                }
+                _ => {
+                    return Err(ErrorCode::IllegalPipe(format!(
+                        "REFRESH with PREFIX is only supported by pipe loading from a stage location, but got pipe '{}'",
+                        pipe.name
+                    )));
+                }
+            }
+            copy_stmt.to_string()
+        }
+    };
+
+    execute_statement(ctx, &sql).await?;
+    Ok(())
+}
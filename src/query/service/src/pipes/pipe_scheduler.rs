@@ -0,0 +1,158 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_config::InnerConfig;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::PipeInfo;
+use databend_common_meta_app::principal::UserInfo;
+use databend_common_users::UserApiProvider;
+use databend_common_users::BUILTIN_ROLE_PUBLIC;
+use futures::future::Either;
+use log::info;
+use log::warn;
+
+use crate::pipes::execute_pipe;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionType;
+
+/// How long the ingest lease of a pipe lasts if it is not renewed.
+const PIPE_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often the ingest lease of a pipe is renewed while the pipe is being ingested.
+const PIPE_LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Background loop that keeps the `AUTO_INGEST = TRUE` pipes of the tenant loading.
+///
+/// Every node of every cluster of the tenant runs the loop, a pipe is ingested only by the
+/// node holding its lease in the meta-service, so the same file is never loaded twice
+/// concurrently. The lease is renewed during the ingestion and expires if its holder stops.
+pub struct PipeScheduler {
+    conf: InnerConfig,
+}
+
+impl PipeScheduler {
+    pub fn start(conf: InnerConfig) {
+        let scheduler = PipeScheduler { conf };
+        GlobalIORuntime::instance().spawn(async move { scheduler.run().await });
+    }
+
+    async fn run(self) {
+        let interval = Duration::from_secs(self.conf.query.pipe_poll_interval_secs.max(1));
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if let Err(cause) = self.ingest_pipes().await {
+                warn!("Failed to run pipes: {:?}", cause);
+            }
+        }
+    }
+
+    #[async_backtrace::framed]
+    async fn ingest_pipes(&self) -> Result<()> {
+        let tenant = &self.conf.query.tenant_id;
+        let pipes = UserApiProvider::instance().get_pipes(tenant).await?;
+        for pipe in pipes {
+            if !pipe.auto_ingest || pipe.execution_paused {
+                continue;
+            }
+            // A failure on one pipe must not keep the following pipes from being ingested.
+            match self.acquire_lease(&pipe.name).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(cause) => {
+                    warn!(
+                        "Pipe {} failed to acquire its lease: {:?}",
+                        pipe.name, cause
+                    );
+                    continue;
+                }
+            }
+
+            let ctx = match self.create_session(&pipe).await {
+                Ok(session) => session.create_query_context().await,
+                Err(cause) => Err(cause),
+            };
+            let ctx = match ctx {
+                Ok(ctx) => ctx,
+                Err(cause) => {
+                    warn!(
+                        "Pipe {} failed to create its session: {:?}",
+                        pipe.name, cause
+                    );
+                    continue;
+                }
+            };
+            let mut ingest = Box::pin(execute_pipe(ctx, &pipe, None));
+            let res = loop {
+                let renew = Box::pin(tokio::time::sleep(PIPE_LEASE_RENEW_INTERVAL));
+                match futures::future::select(renew, ingest).await {
+                    Either::Left((_, right)) => {
+                        // Stop ingesting if the lease is lost, another node may have taken over.
+                        if !self.acquire_lease(&pipe.name).await.unwrap_or(false) {
+                            break None;
+                        }
+                        ingest = right;
+                    }
+                    Either::Right((res, _)) => break Some(res),
+                }
+            };
+            match res {
+                Some(Ok(_)) => info!("Pipe {} ingested new files", pipe.name),
+                Some(Err(cause)) => warn!("Pipe {} failed to ingest: {:?}", pipe.name, cause),
+                None => warn!("Pipe {} lost its lease during the ingestion", pipe.name),
+            }
+        }
+        Ok(())
+    }
+
+    #[async_backtrace::framed]
+    async fn acquire_lease(&self, pipe_name: &str) -> Result<bool> {
+        UserApiProvider::instance()
+            .acquire_pipe_lease(
+                &self.conf.query.tenant_id,
+                pipe_name,
+                &self.conf.query.node_id,
+                PIPE_LEASE_TTL,
+            )
+            .await
+    }
+
+    /// Create a session to ingest the files, the session can only use the owner role of the pipe.
+    async fn create_session(&self, pipe: &PipeInfo) -> Result<Arc<Session>> {
+        let session_manager = SessionManager::instance();
+        let session = session_manager.create_session(SessionType::Dummy).await?;
+        let session = session_manager.register_session(session)?;
+
+        let user = UserInfo::new_no_auth(
+            &format!(
+                "{}-{}-pipe-svc",
+                self.conf.query.tenant_id.tenant_name(),
+                self.conf.query.cluster_id
+            ),
+            "0.0.0.0",
+        );
+        let role = if pipe.owner.is_empty() {
+            BUILTIN_ROLE_PUBLIC.to_string()
+        } else {
+            pipe.owner.clone()
+        };
+        session.set_authed_user(user, Some(role)).await?;
+        Ok(session)
+    }
+}
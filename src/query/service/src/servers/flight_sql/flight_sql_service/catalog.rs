@@ -14,23 +14,56 @@
 
 use std::sync::Arc;
 
+use arrow_array::builder::Int32Builder;
 use arrow_array::builder::StringBuilder;
+use arrow_array::builder::UInt8Builder;
 use arrow_array::ArrayRef;
 use arrow_array::RecordBatch;
+use arrow_flight::sql::CommandGetCatalogs;
+use arrow_flight::sql::CommandGetCrossReference;
+use arrow_flight::sql::CommandGetDbSchemas;
+use arrow_flight::sql::CommandGetExportedKeys;
+use arrow_flight::sql::CommandGetImportedKeys;
+use arrow_flight::sql::CommandGetPrimaryKeys;
+use arrow_flight::sql::CommandGetTables;
 use arrow_flight::utils::batches_to_flight_data;
 use arrow_schema::DataType;
 use arrow_schema::Field;
 use arrow_schema::Schema;
 use databend_common_catalog::catalog::Catalog;
 use databend_common_catalog::catalog::CatalogManager;
+use databend_common_catalog::table::Table;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraintKind;
 use futures_util::stream;
 use log::warn;
 use tonic::Status;
 
 use crate::servers::flight_sql::flight_sql_service::DoGetStream;
 
+const TABLE_TYPE_TABLE: &str = "BASE TABLE";
+const TABLE_TYPE_VIEW: &str = "VIEW";
+
+/// The update and delete rule of the foreign keys, which are not enforced.
+const FOREIGN_KEY_RULE_NO_ACTION: u8 = 3;
+
+/// A table given by a Flight SQL command, the catalog and the database match any if not given.
+struct TableFilter {
+    catalog: Option<String>,
+    db_schema: Option<String>,
+    table: String,
+}
+
+impl TableFilter {
+    fn matches(&self, catalog_name: &str, db_name: &str, table_name: &str) -> bool {
+        self.catalog.as_deref().is_none_or(|c| c == catalog_name)
+            && self.db_schema.as_deref().is_none_or(|d| d == db_name)
+            && self.table == table_name
+    }
+}
+
 pub(super) struct CatalogInfoProvider {}
 
 impl CatalogInfoProvider {
@@ -45,15 +78,13 @@ impl CatalogInfoProvider {
         Ok(Box::pin(stream))
     }
 
-    async fn get_tables_internal(
-        ctx: Arc<dyn TableContext>,
+    async fn list_catalogs(
+        ctx: &Arc<dyn TableContext>,
         catalog_name: Option<String>,
-        database_name: Option<String>,
-    ) -> databend_common_exception::Result<(Vec<String>, Vec<String>, Vec<String>, Vec<String>)>
-    {
+    ) -> databend_common_exception::Result<Vec<(String, Arc<dyn Catalog>)>> {
         let tenant = ctx.get_tenant();
         let catalog_mgr = CatalogManager::instance();
-        let catalogs: Vec<(String, Arc<dyn Catalog>)> = if let Some(catalog_name) = catalog_name {
+        let catalogs = if let Some(catalog_name) = catalog_name {
             vec![(
                 catalog_name.clone(),
                 catalog_mgr
@@ -68,20 +99,67 @@ impl CatalogInfoProvider {
                 .map(|r| (r.name(), r.clone()))
                 .collect()
         };
+        Ok(catalogs)
+    }
 
-        let mut catalog_names = vec![];
-        let mut database_names = vec![];
-        let mut table_names = vec![];
-        let mut table_types = vec![];
-        let table_type = "table".to_string();
-        for (catalog_name, catalog) in catalogs.into_iter() {
-            let dbs = if let Some(database_name) = &database_name {
-                vec![catalog.get_database(&tenant, database_name).await?]
-            } else {
-                catalog.list_databases(&tenant).await?
-            };
-            for db in dbs {
+    async fn get_catalogs_internal(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetCatalogs,
+    ) -> databend_common_exception::Result<RecordBatch> {
+        let catalogs = Self::list_catalogs(&ctx, None).await?;
+
+        let mut builder = query.into_builder();
+        for (catalog_name, _) in catalogs {
+            builder.append(catalog_name);
+        }
+        Ok(builder.build()?)
+    }
+
+    async fn get_schemas_internal(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetDbSchemas,
+    ) -> databend_common_exception::Result<RecordBatch> {
+        let tenant = ctx.get_tenant();
+        let catalogs = Self::list_catalogs(&ctx, query.catalog.clone()).await?;
+        let visibility_checker = ctx.get_visibility_checker(false).await?;
+
+        // The builder filters the databases with `db_schema_filter_pattern`.
+        let mut builder = query.into_builder();
+        for (catalog_name, catalog) in catalogs {
+            for db in catalog.list_databases(&tenant).await? {
+                if visibility_checker.check_database_visibility(
+                    &catalog_name,
+                    db.name(),
+                    db.get_db_info().database_id.db_id,
+                ) {
+                    builder.append(&catalog_name, db.name());
+                }
+            }
+        }
+        Ok(builder.build()?)
+    }
+
+    async fn get_tables_internal(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetTables,
+    ) -> databend_common_exception::Result<RecordBatch> {
+        let tenant = ctx.get_tenant();
+        let catalogs = Self::list_catalogs(&ctx, query.catalog.clone()).await?;
+        let visibility_checker = ctx.get_visibility_checker(false).await?;
+        let include_schema = query.include_schema;
+        let empty_schema = Schema::empty();
+
+        // The builder filters the tables with `db_schema_filter_pattern`,
+        // `table_name_filter_pattern` and `table_types`.
+        let mut builder = query.into_builder();
+        for (catalog_name, catalog) in catalogs {
+            for db in catalog.list_databases(&tenant).await? {
                 let db_name = db.name();
+                let db_id = db.get_db_info().database_id.db_id;
+                if !visibility_checker.check_database_visibility(&catalog_name, db_name, db_id) {
+                    continue;
+                }
+
                 let tables = match catalog.list_tables(&tenant, db_name).await {
                     Ok(tables) => tables,
                     Err(err) if err.code() == ErrorCode::EMPTY_SHARE_ENDPOINT_CONFIG => {
@@ -91,41 +169,395 @@ impl CatalogInfoProvider {
                     Err(err) => return Err(err),
                 };
                 for table in tables {
-                    catalog_names.push(catalog_name.clone());
-                    database_names.push(db_name.to_string());
-                    table_names.push(table.name().to_string());
-                    table_types.push(table_type.clone());
+                    if table.is_stream()
+                        || !visibility_checker.check_table_visibility(
+                            &catalog_name,
+                            db_name,
+                            table.name(),
+                            db_id,
+                            table.get_id(),
+                        )
+                    {
+                        continue;
+                    }
+
+                    let table_type = if table.engine().to_uppercase() == "VIEW" {
+                        TABLE_TYPE_VIEW
+                    } else {
+                        TABLE_TYPE_TABLE
+                    };
+                    let table_schema = if include_schema {
+                        Schema::from(table.schema().as_ref())
+                    } else {
+                        empty_schema.clone()
+                    };
+                    builder.append(
+                        &catalog_name,
+                        db_name,
+                        table.name(),
+                        table_type,
+                        &table_schema,
+                    )?;
                 }
             }
         }
-        Ok((catalog_names, database_names, table_names, table_types))
+        Ok(builder.build()?)
+    }
+
+    pub(crate) async fn get_catalogs(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetCatalogs,
+    ) -> Result<DoGetStream, Status> {
+        let batch = Self::get_catalogs_internal(ctx, query)
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
+    }
+
+    pub(crate) async fn get_schemas(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetDbSchemas,
+    ) -> Result<DoGetStream, Status> {
+        let batch = Self::get_schemas_internal(ctx, query)
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
     }
 
     pub(crate) async fn get_tables(
         ctx: Arc<dyn TableContext>,
-        catalog_name: Option<String>,
-        database_name: Option<String>,
+        query: CommandGetTables,
     ) -> Result<DoGetStream, Status> {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("catalog_name", DataType::Utf8, false),
-            Field::new("db_schema_name", DataType::Utf8, false),
-            Field::new("table_name", DataType::Utf8, false),
-            Field::new("table_type", DataType::Utf8, false),
-        ]));
-        let (catalog_name, db_schema_name, table_name, table_type) =
-            Self::get_tables_internal(ctx.clone(), catalog_name, database_name)
-                .await
-                .map_err(|e| Status::internal(format!("{e:?}")))?;
-        let batch = RecordBatch::try_new(schema, vec![
-            Self::string_array(catalog_name),
-            Self::string_array(db_schema_name),
-            Self::string_array(table_name),
-            Self::string_array(table_type),
-        ])
+        let batch = Self::get_tables_internal(ctx, query)
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
+    }
+
+    pub(crate) fn get_table_types() -> Result<DoGetStream, Status> {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "table_type",
+            DataType::Utf8,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Self::string_array(vec![
+            TABLE_TYPE_TABLE.to_string(),
+            TABLE_TYPE_VIEW.to_string(),
+        ])])
         .map_err(|e| Status::internal(format!("RecordBatch::try_new fail {:?}", e)))?;
         Self::batch_to_get_stream(batch)
     }
 
+    async fn get_primary_keys_internal(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetPrimaryKeys,
+    ) -> databend_common_exception::Result<RecordBatch> {
+        let tables = Self::list_visible_tables(
+            &ctx,
+            query.catalog.clone(),
+            query.db_schema.as_deref(),
+            Some(&query.table),
+        )
+        .await?;
+
+        let mut catalog_names = StringBuilder::new();
+        let mut db_names = StringBuilder::new();
+        let mut table_names = StringBuilder::new();
+        let mut column_names = StringBuilder::new();
+        let mut key_names = StringBuilder::new();
+        let mut key_sequences = Int32Builder::new();
+        for (catalog_name, db_name, table) in tables {
+            let constraints = get_table_constraints(table.options())?;
+            let Some(primary_key) = constraints.iter().find(|c| c.is_primary_key()) else {
+                continue;
+            };
+            for (idx, column) in primary_key
+                .key_columns()
+                .unwrap_or_default()
+                .iter()
+                .enumerate()
+            {
+                catalog_names.append_value(&catalog_name);
+                db_names.append_value(&db_name);
+                table_names.append_value(table.name());
+                column_names.append_value(column);
+                key_names.append_value(&primary_key.name);
+                key_sequences.append_value(idx as i32 + 1);
+            }
+        }
+
+        let batch = RecordBatch::try_new(Self::primary_keys_schema(), vec![
+            Arc::new(catalog_names.finish()),
+            Arc::new(db_names.finish()),
+            Arc::new(table_names.finish()),
+            Arc::new(column_names.finish()),
+            Arc::new(key_names.finish()),
+            Arc::new(key_sequences.finish()),
+        ])?;
+        Ok(batch)
+    }
+
+    fn primary_keys_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("catalog_name", DataType::Utf8, true),
+            Field::new("db_schema_name", DataType::Utf8, true),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("column_name", DataType::Utf8, false),
+            Field::new("key_name", DataType::Utf8, true),
+            Field::new("key_sequence", DataType::Int32, false),
+        ]))
+    }
+
+    /// The columns of the primary key of the table, ordered by the key sequence.
+    pub(crate) async fn get_primary_keys(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetPrimaryKeys,
+    ) -> Result<DoGetStream, Status> {
+        let batch = Self::get_primary_keys_internal(ctx, query)
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
+    }
+
+    /// The visible tables of the catalogs, the catalog, the database and the table match any
+    /// if not given.
+    async fn list_visible_tables(
+        ctx: &Arc<dyn TableContext>,
+        catalog_name: Option<String>,
+        db_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> databend_common_exception::Result<Vec<(String, String, Arc<dyn Table>)>> {
+        let tenant = ctx.get_tenant();
+        let catalogs = Self::list_catalogs(ctx, catalog_name).await?;
+        let visibility_checker = ctx.get_visibility_checker(false).await?;
+
+        let mut visible_tables = vec![];
+        for (catalog_name, catalog) in catalogs {
+            let databases = match db_name {
+                Some(db_name) => match catalog.get_database(&tenant, db_name).await {
+                    Ok(db) => vec![db],
+                    Err(err) if err.code() == ErrorCode::UNKNOWN_DATABASE => continue,
+                    Err(err) => return Err(err),
+                },
+                None => catalog.list_databases(&tenant).await?,
+            };
+            for db in databases {
+                let db_name = db.name();
+                let db_id = db.get_db_info().database_id.db_id;
+                if !visibility_checker.check_database_visibility(&catalog_name, db_name, db_id) {
+                    continue;
+                }
+                let tables = match table_name {
+                    Some(table_name) => match catalog.get_table(&tenant, db_name, table_name).await
+                    {
+                        Ok(table) => vec![table],
+                        Err(_) => continue,
+                    },
+                    None => match catalog.list_tables(&tenant, db_name).await {
+                        Ok(tables) => tables,
+                        Err(err) if err.code() == ErrorCode::EMPTY_SHARE_ENDPOINT_CONFIG => {
+                            warn!("list tables failed on db {}: {}", db_name, err);
+                            continue;
+                        }
+                        Err(err) => return Err(err),
+                    },
+                };
+                for table in tables {
+                    if !table.is_stream()
+                        && visibility_checker.check_table_visibility(
+                            &catalog_name,
+                            db_name,
+                            table.name(),
+                            db_id,
+                            table.get_id(),
+                        )
+                    {
+                        visible_tables.push((catalog_name.clone(), db_name.to_string(), table));
+                    }
+                }
+            }
+        }
+        Ok(visible_tables)
+    }
+
+    /// The foreign keys of the tables matching `fk_table`, which reference the tables matching
+    /// `pk_table`, a foreign key references a table of the catalog of its table.
+    async fn get_foreign_keys_internal(
+        ctx: Arc<dyn TableContext>,
+        pk_table: Option<TableFilter>,
+        fk_table: Option<TableFilter>,
+    ) -> databend_common_exception::Result<RecordBatch> {
+        let fk_tables = match &fk_table {
+            Some(filter) => {
+                Self::list_visible_tables(
+                    &ctx,
+                    filter.catalog.clone(),
+                    filter.db_schema.as_deref(),
+                    Some(&filter.table),
+                )
+                .await?
+            }
+            None => {
+                let catalog_name = pk_table.as_ref().and_then(|filter| filter.catalog.clone());
+                Self::list_visible_tables(&ctx, catalog_name, None, None).await?
+            }
+        };
+
+        let mut pk_catalog_names = StringBuilder::new();
+        let mut pk_db_names = StringBuilder::new();
+        let mut pk_table_names = StringBuilder::new();
+        let mut pk_column_names = StringBuilder::new();
+        let mut fk_catalog_names = StringBuilder::new();
+        let mut fk_db_names = StringBuilder::new();
+        let mut fk_table_names = StringBuilder::new();
+        let mut fk_column_names = StringBuilder::new();
+        let mut key_sequences = Int32Builder::new();
+        let mut fk_key_names = StringBuilder::new();
+        let mut pk_key_names = StringBuilder::new();
+        let mut update_rules = UInt8Builder::new();
+        let mut delete_rules = UInt8Builder::new();
+        for (catalog_name, db_name, table) in fk_tables {
+            for constraint in get_table_constraints(table.options())? {
+                let TableConstraintKind::ForeignKey {
+                    columns,
+                    ref_database,
+                    ref_table,
+                    ref_columns,
+                } = &constraint.kind
+                else {
+                    continue;
+                };
+                if pk_table
+                    .as_ref()
+                    .is_some_and(|filter| !filter.matches(&catalog_name, ref_database, ref_table))
+                {
+                    continue;
+                }
+
+                // The referenced table must be visible too, its key is the primary key or
+                // unique constraint on the referenced columns.
+                let referenced = Self::list_visible_tables(
+                    &ctx,
+                    Some(catalog_name.clone()),
+                    Some(ref_database),
+                    Some(ref_table),
+                )
+                .await?;
+                let Some((_, _, referenced)) = referenced.first() else {
+                    continue;
+                };
+                let pk_key_name = get_table_constraints(referenced.options())?
+                    .into_iter()
+                    .find(|c| c.key_columns() == Some(ref_columns.as_slice()))
+                    .map(|c| c.name);
+
+                for (idx, (column, ref_column)) in columns.iter().zip(ref_columns).enumerate() {
+                    pk_catalog_names.append_value(&catalog_name);
+                    pk_db_names.append_value(ref_database);
+                    pk_table_names.append_value(ref_table);
+                    pk_column_names.append_value(ref_column);
+                    fk_catalog_names.append_value(&catalog_name);
+                    fk_db_names.append_value(&db_name);
+                    fk_table_names.append_value(table.name());
+                    fk_column_names.append_value(column);
+                    key_sequences.append_value(idx as i32 + 1);
+                    fk_key_names.append_value(&constraint.name);
+                    pk_key_names.append_option(pk_key_name.as_ref());
+                    update_rules.append_value(FOREIGN_KEY_RULE_NO_ACTION);
+                    delete_rules.append_value(FOREIGN_KEY_RULE_NO_ACTION);
+                }
+            }
+        }
+
+        let batch = RecordBatch::try_new(Self::foreign_keys_schema(), vec![
+            Arc::new(pk_catalog_names.finish()),
+            Arc::new(pk_db_names.finish()),
+            Arc::new(pk_table_names.finish()),
+            Arc::new(pk_column_names.finish()),
+            Arc::new(fk_catalog_names.finish()),
+            Arc::new(fk_db_names.finish()),
+            Arc::new(fk_table_names.finish()),
+            Arc::new(fk_column_names.finish()),
+            Arc::new(key_sequences.finish()),
+            Arc::new(fk_key_names.finish()),
+            Arc::new(pk_key_names.finish()),
+            Arc::new(update_rules.finish()),
+            Arc::new(delete_rules.finish()),
+        ])?;
+        Ok(batch)
+    }
+
+    fn foreign_keys_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("pk_catalog_name", DataType::Utf8, true),
+            Field::new("pk_db_schema_name", DataType::Utf8, true),
+            Field::new("pk_table_name", DataType::Utf8, false),
+            Field::new("pk_column_name", DataType::Utf8, false),
+            Field::new("fk_catalog_name", DataType::Utf8, true),
+            Field::new("fk_db_schema_name", DataType::Utf8, true),
+            Field::new("fk_table_name", DataType::Utf8, false),
+            Field::new("fk_column_name", DataType::Utf8, false),
+            Field::new("key_sequence", DataType::Int32, false),
+            Field::new("fk_key_name", DataType::Utf8, true),
+            Field::new("pk_key_name", DataType::Utf8, true),
+            Field::new("update_rule", DataType::UInt8, false),
+            Field::new("delete_rule", DataType::UInt8, false),
+        ]))
+    }
+
+    /// The foreign keys referencing the table.
+    pub(crate) async fn get_exported_keys(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetExportedKeys,
+    ) -> Result<DoGetStream, Status> {
+        let pk_table = TableFilter {
+            catalog: query.catalog,
+            db_schema: query.db_schema,
+            table: query.table,
+        };
+        let batch = Self::get_foreign_keys_internal(ctx, Some(pk_table), None)
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
+    }
+
+    /// The foreign keys of the table.
+    pub(crate) async fn get_imported_keys(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetImportedKeys,
+    ) -> Result<DoGetStream, Status> {
+        let fk_table = TableFilter {
+            catalog: query.catalog,
+            db_schema: query.db_schema,
+            table: query.table,
+        };
+        let batch = Self::get_foreign_keys_internal(ctx, None, Some(fk_table))
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
+    }
+
+    /// The foreign keys of the foreign key table referencing the primary key table.
+    pub(crate) async fn get_cross_reference(
+        ctx: Arc<dyn TableContext>,
+        query: CommandGetCrossReference,
+    ) -> Result<DoGetStream, Status> {
+        let pk_table = TableFilter {
+            catalog: query.pk_catalog,
+            db_schema: query.pk_db_schema,
+            table: query.pk_table,
+        };
+        let fk_table = TableFilter {
+            catalog: query.fk_catalog,
+            db_schema: query.fk_db_schema,
+            table: query.fk_table,
+        };
+        let batch = Self::get_foreign_keys_internal(ctx, Some(pk_table), Some(fk_table))
+            .await
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        Self::batch_to_get_stream(batch)
+    }
+
     fn string_array(values: Vec<String>) -> ArrayRef {
         let mut builder = StringBuilder::new();
         for v in &values {
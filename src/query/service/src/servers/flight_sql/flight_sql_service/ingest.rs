@@ -0,0 +1,393 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
+use arrow_flight::sql::command_statement_ingest::table_definition_options::TableExistsOption;
+use arrow_flight::sql::command_statement_ingest::table_definition_options::TableNotExistOption;
+use arrow_flight::sql::server::PeekableFlightDataStream;
+use arrow_flight::sql::CommandStatementIngest;
+use arrow_schema::Schema as ArrowSchema;
+use databend_common_ast::ast::quote::display_ident;
+use databend_common_catalog::query_kind::QueryKind;
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchema;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::TableSchema;
+use databend_common_pipeline_sources::AsyncSource;
+use databend_common_pipeline_sources::AsyncSourcer;
+use databend_common_sql::execute_commit_statement;
+use databend_common_sql::plans::Insert;
+use databend_common_sql::plans::InsertInputSource;
+use databend_common_sql::plans::InsertValue;
+use databend_common_sql::plans::Plan;
+use databend_common_storages_fuse::FuseTable;
+use databend_common_storages_fuse::TableContext;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use log::info;
+use parking_lot::Mutex;
+
+use super::FlightSqlServiceImpl;
+use crate::interpreters::Accessor;
+use crate::interpreters::Interpreter;
+use crate::pipelines::processors::TransformCastSchema;
+use crate::pipelines::PipelineBuildResult;
+use crate::pipelines::PipelineBuilder;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+
+impl FlightSqlServiceImpl {
+    /// Appends the record batches of a `CommandStatementIngest` into the target table,
+    /// creating or replacing the table first if the table definition options ask for it.
+    ///
+    /// Returns the number of ingested rows.
+    #[async_backtrace::framed]
+    pub(super) async fn execute_ingest(
+        &self,
+        session: Arc<Session>,
+        cmd: CommandStatementIngest,
+        flight_data: PeekableFlightDataStream,
+    ) -> Result<i64> {
+        if cmd.transaction_id.is_some() {
+            return Err(ErrorCode::Unimplemented(
+                "Flight SQL ingest within a transaction is not supported",
+            ));
+        }
+
+        let ctx = session.create_query_context().await?;
+        let catalog = match &cmd.catalog {
+            Some(catalog) => catalog.clone(),
+            None => ctx.get_current_catalog(),
+        };
+        let database = match &cmd.schema {
+            Some(schema) => schema.clone(),
+            None => ctx.get_current_database(),
+        };
+
+        // Decode the first batch, so that the schema of the stream is known
+        // before the table is created.
+        let mut batches =
+            FlightRecordBatchStream::new_from_flight_data(flight_data.map_err(FlightError::from));
+        let first_batch = batches
+            .try_next()
+            .await
+            .map_err(|e| ErrorCode::BadBytes(format!("Fail to decode ingest data: {e}")))?;
+        let arrow_schema = batches.schema().cloned().ok_or_else(|| {
+            ErrorCode::BadArguments("Flight SQL ingest stream does not contain a schema")
+        })?;
+        let source_schema = DataSchema::try_from(arrow_schema.as_ref())?;
+
+        let (if_not_exist, if_exists) = match &cmd.table_definition_options {
+            Some(options) => (options.if_not_exist(), options.if_exists()),
+            None => (
+                TableNotExistOption::Unspecified,
+                TableExistsOption::Unspecified,
+            ),
+        };
+        let table_exists = match ctx.get_table(&catalog, &database, &cmd.table).await {
+            Ok(_) => true,
+            Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE => false,
+            Err(e) => return Err(e),
+        };
+
+        // The created or replaced table and the ingested data are committed in one transaction,
+        // so that the table is not left created or replaced and empty if the ingestion fails.
+        let create_table = match table_exists {
+            true => if_exists == TableExistsOption::Replace,
+            false => if_not_exist == TableNotExistOption::Create,
+        };
+        let txn_mgr = session.txn_mgr();
+        let own_txn = create_table && !txn_mgr.lock().is_active();
+        if own_txn {
+            txn_mgr.lock().begin();
+        }
+
+        let res = async {
+            match (table_exists, if_exists, if_not_exist) {
+                (true, TableExistsOption::Append, _) => {}
+                (true, TableExistsOption::Replace, _) => {
+                    self.create_ingest_table(&session, &ctx, &cmd, &database, &arrow_schema, true)
+                        .await?;
+                }
+                (true, _, _) => {
+                    return Err(ErrorCode::TableAlreadyExists(format!(
+                        "Table '{}'.'{}' already exists",
+                        database, cmd.table
+                    )));
+                }
+                (false, _, TableNotExistOption::Create) => {
+                    self.create_ingest_table(&session, &ctx, &cmd, &database, &arrow_schema, false)
+                        .await?;
+                }
+                (false, _, _) => {
+                    return Err(ErrorCode::UnknownTable(format!(
+                        "Table '{}'.'{}' does not exist",
+                        database, cmd.table
+                    )));
+                }
+            }
+
+            let source = IngestSource {
+                schema: source_schema,
+                first_batch,
+                batches,
+            };
+            self.ingest_into_table(&session, &catalog, &database, &cmd.table, source)
+                .await
+        }
+        .await;
+
+        if !own_txn {
+            return res;
+        }
+        match res {
+            Ok(ingested_rows) => {
+                let ctx = session.create_query_context().await?;
+                execute_commit_statement(ctx).await?;
+                Ok(ingested_rows)
+            }
+            Err(cause) => {
+                txn_mgr.lock().clear();
+                Err(cause)
+            }
+        }
+    }
+
+    /// Runs the append pipeline of the table with the ingested batches as source,
+    /// returns the number of ingested rows.
+    async fn ingest_into_table(
+        &self,
+        session: &Arc<Session>,
+        catalog: &str,
+        database: &str,
+        table_name: &str,
+        source: IngestSource,
+    ) -> Result<i64> {
+        // A new context, the table created above must not be served from the table cache.
+        let ctx = session.create_query_context().await?;
+        ctx.attach_query_str(
+            QueryKind::Insert,
+            format!("INGEST INTO {}.{}", database, table_name),
+        );
+        let table = ctx.get_table(catalog, database, table_name).await?;
+
+        // The append pipeline is built directly, check the privileges of the
+        // equivalent INSERT, as the interpreter factory would do.
+        let insert_plan = Plan::Insert(Box::new(Insert {
+            catalog: catalog.to_string(),
+            database: database.to_string(),
+            table: table_name.to_string(),
+            schema: table.schema(),
+            overwrite: false,
+            ignore_conflicts: vec![],
+            source: InsertInputSource::Values(InsertValue::Values { rows: vec![] }),
+            table_info: None,
+        }));
+        Accessor::create(ctx.clone()).check(&insert_plan).await?;
+
+        let interpreter = IngestInterpreter {
+            ctx: ctx.clone(),
+            table,
+            source_schema: Arc::new(source.schema.clone()),
+            source: Mutex::new(Some(source)),
+        };
+
+        let mut blocks = interpreter.execute(ctx.clone()).await?;
+        while let Some(block) = blocks.next().await {
+            block?;
+        }
+
+        let ingested_rows = ctx.get_write_progress_value().rows;
+        info!(
+            "Flight SQL ingest into {}.{} finished, {} rows",
+            database, table_name, ingested_rows
+        );
+        Ok(ingested_rows as i64)
+    }
+
+    /// Creates the ingest target table with the schema of the record batches,
+    /// the table definition is planned as SQL so that it is bound like a user `CREATE TABLE`.
+    async fn create_ingest_table(
+        &self,
+        session: &Arc<Session>,
+        ctx: &Arc<QueryContext>,
+        cmd: &CommandStatementIngest,
+        database: &str,
+        arrow_schema: &ArrowSchema,
+        replace: bool,
+    ) -> Result<()> {
+        let dialect = ctx.get_settings().get_sql_dialect()?;
+        let table_schema = TableSchema::try_from(arrow_schema)?;
+        let columns = table_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                format!(
+                    "{} {}",
+                    display_ident(field.name(), true, true, dialect),
+                    field.data_type().sql_name_explicit_null()
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut table_name = vec![];
+        if let Some(catalog) = &cmd.catalog {
+            table_name.push(display_ident(catalog, true, true, dialect));
+        }
+        table_name.push(display_ident(database, true, true, dialect));
+        table_name.push(display_ident(&cmd.table, true, true, dialect));
+
+        let sql = format!(
+            "CREATE {}{}TABLE {} ({})",
+            if replace { "OR REPLACE " } else { "" },
+            if cmd.temporary { "TEMPORARY " } else { "" },
+            table_name.join("."),
+            columns.join(", ")
+        );
+        info!("Flight SQL ingest creates table: {sql}");
+
+        let (plan, plan_extras) = self.plan_sql(session, &sql).await?;
+        self.execute_update(session.clone(), &plan, Some(&plan_extras))
+            .await?;
+        Ok(())
+    }
+}
+
+struct IngestSource {
+    schema: DataSchema,
+    first_batch: Option<RecordBatch>,
+    batches: FlightRecordBatchStream,
+}
+
+#[async_trait::async_trait]
+impl AsyncSource for IngestSource {
+    const NAME: &'static str = "FlightSqlIngestSource";
+    const SKIP_EMPTY_DATA_BLOCK: bool = true;
+
+    #[async_backtrace::framed]
+    async fn generate(&mut self) -> Result<Option<DataBlock>> {
+        let batch = match self.first_batch.take() {
+            Some(batch) => Some(batch),
+            None => self
+                .batches
+                .try_next()
+                .await
+                .map_err(|e| ErrorCode::BadBytes(format!("Fail to decode ingest data: {e}")))?,
+        };
+        match batch {
+            Some(batch) => {
+                let (block, _) = DataBlock::from_record_batch(&self.schema, &batch)?;
+                Ok(Some(block))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Runs the normal append and commit pipeline of the table with the ingested batches as source.
+struct IngestInterpreter {
+    ctx: Arc<QueryContext>,
+    table: Arc<dyn Table>,
+    source_schema: DataSchemaRef,
+    source: Mutex<Option<IngestSource>>,
+}
+
+#[async_trait::async_trait]
+impl Interpreter for IngestInterpreter {
+    fn name(&self) -> &str {
+        "FlightSqlIngestInterpreter"
+    }
+
+    fn is_ddl(&self) -> bool {
+        false
+    }
+
+    #[async_backtrace::framed]
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        self.table.check_mutable()?;
+
+        // The columns are matched by name, the ingested columns are cast to the table types.
+        let table_schema = self.table.schema();
+        let insert_fields = self
+            .source_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let table_field = table_schema.field_with_name(field.name()).map_err(|_| {
+                    ErrorCode::BadArguments(format!(
+                        "Ingested column '{}' does not exist in table '{}'",
+                        field.name(),
+                        self.table.name()
+                    ))
+                })?;
+                Ok(DataField::from(table_field))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let insert_schema = Arc::new(DataSchema::new(insert_fields));
+
+        let table_meta_timestamps = if self.table.engine() == "FUSE" {
+            let fuse_table = FuseTable::try_from_table(self.table.as_ref())?;
+            let snapshot = fuse_table.read_table_snapshot().await?;
+            self.ctx
+                .get_table_meta_timestamps(self.table.as_ref(), snapshot)?
+        } else {
+            Default::default()
+        };
+
+        let mut build_res = PipelineBuildResult::create();
+        build_res.main_pipeline.add_source(
+            |output| {
+                let source = self.source.lock().take().ok_or_else(|| {
+                    ErrorCode::Internal("Flight SQL ingest source is already consumed")
+                })?;
+                AsyncSourcer::create(self.ctx.clone(), output, source)
+            },
+            1,
+        )?;
+
+        if self.source_schema != insert_schema {
+            let func_ctx = self.ctx.get_function_context()?;
+            build_res.main_pipeline.try_add_transformer(|| {
+                TransformCastSchema::try_new(
+                    self.source_schema.clone(),
+                    insert_schema.clone(),
+                    func_ctx.clone(),
+                )
+            })?;
+        }
+
+        PipelineBuilder::build_append2table_with_commit_pipeline(
+            self.ctx.clone(),
+            &mut build_res.main_pipeline,
+            self.table.clone(),
+            insert_schema,
+            None,
+            vec![],
+            false,
+            &[],
+            unsafe { self.ctx.get_settings().get_deduplicate_label()? },
+            table_meta_timestamps,
+        )?;
+
+        Ok(build_res)
+    }
+}
@@ -15,10 +15,12 @@
 // The servers module used for external communication with user, such as MySQL wired protocol, etc.
 
 mod catalog;
+mod ingest;
 mod query;
 mod service;
 mod session;
 mod sql_info;
+mod type_info;
 
 use std::pin::Pin;
 use std::sync::Arc;
@@ -31,6 +33,7 @@ use databend_common_sql::PlanExtras;
 use futures::Stream;
 use parking_lot::Mutex;
 use sql_info::SqlInfoProvider;
+use type_info::TypeInfoProvider;
 use tonic::Status;
 use uuid::Uuid;
 
@@ -51,7 +54,8 @@ type DoGetStream = Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send
 
 pub struct FlightSqlServiceImpl {
     pub sessions: Mutex<ExpiringMap<String, Arc<Session>>>,
-    statements: Arc<DashMap<Uuid, (Plan, PlanExtras)>>,
+    // Prepared plans, the extras are absent for plans built from substrait.
+    statements: Arc<DashMap<Uuid, (Plan, Option<PlanExtras>)>>,
 }
 
 /// in current official JDBC driver, Statement is based on PreparedStatement too, so we impl it first.
@@ -28,6 +28,7 @@ use arrow_ipc::MetadataVersion;
 use arrow_schema::Schema as ArrowSchema;
 use bytes::Bytes;
 use databend_common_base::base::tokio;
+use databend_common_catalog::query_kind::QueryKind;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::DataBlock;
@@ -124,22 +125,41 @@ impl FlightSqlServiceImpl {
         planner.plan_sql(query).await
     }
 
+    #[async_backtrace::framed]
+    pub async fn plan_substrait(&self, session: &Arc<Session>, plan: &[u8]) -> Result<Plan> {
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+
+        let mut planner = Planner::new(context.clone());
+        planner.plan_substrait(plan).await
+    }
+
+    // Plans built from substrait have no SQL statement, they are always queries.
+    fn attach_query_str(context: &QueryContext, plan_extras: Option<&PlanExtras>) {
+        match plan_extras {
+            Some(plan_extras) => context.attach_query_str(
+                get_query_kind(&plan_extras.statement),
+                plan_extras.statement.to_mask_sql(),
+            ),
+            None => context.attach_query_str(QueryKind::Query, "SUBSTRAIT PLAN".to_string()),
+        }
+    }
+
     #[async_backtrace::framed]
     pub(super) async fn execute_update(
         &self,
         session: Arc<Session>,
         plan: &Plan,
-        plan_extras: &PlanExtras,
+        plan_extras: Option<&PlanExtras>,
     ) -> Result<i64> {
         let context = session
             .create_query_context()
             .await
             .map_err(|e| status!("Could not create_query_context", e))?;
 
-        context.attach_query_str(
-            get_query_kind(&plan_extras.statement),
-            plan_extras.statement.to_mask_sql(),
-        );
+        Self::attach_query_str(&context, plan_extras);
         let interpreter = InterpreterFactory::get(context.clone(), plan).await?;
 
         let mut blocks = interpreter.execute(context.clone()).await?;
@@ -155,7 +175,7 @@ impl FlightSqlServiceImpl {
         &self,
         session: Arc<Session>,
         plan: &Plan,
-        plan_extras: &PlanExtras,
+        plan_extras: Option<&PlanExtras>,
     ) -> Result<DoGetStream> {
         let is_native_client = session.get_status().read().is_native_client;
 
@@ -164,10 +184,7 @@ impl FlightSqlServiceImpl {
             .await
             .map_err(|e| status!("Could not create_query_context", e))?;
 
-        context.attach_query_str(
-            get_query_kind(&plan_extras.statement),
-            plan_extras.statement.to_mask_sql(),
-        );
+        Self::attach_query_str(&context, plan_extras);
         let interpreter = InterpreterFactory::get(context.clone(), plan).await?;
 
         let data_schema = plan.schema();
@@ -45,6 +45,7 @@ use arrow_flight::sql::CommandGetTables;
 use arrow_flight::sql::CommandGetXdbcTypeInfo;
 use arrow_flight::sql::CommandPreparedStatementQuery;
 use arrow_flight::sql::CommandPreparedStatementUpdate;
+use arrow_flight::sql::CommandStatementIngest;
 use arrow_flight::sql::CommandStatementQuery;
 use arrow_flight::sql::CommandStatementSubstraitPlan;
 use arrow_flight::sql::CommandStatementUpdate;
@@ -52,6 +53,7 @@ use arrow_flight::sql::DoPutPreparedStatementResult;
 use arrow_flight::sql::DoPutUpdateResult;
 use arrow_flight::sql::ProstMessageExt;
 use arrow_flight::sql::SqlInfo;
+use arrow_flight::sql::SubstraitPlan;
 use arrow_flight::sql::TicketStatementQuery;
 use arrow_flight::Action;
 use arrow_flight::FlightDescriptor;
@@ -69,6 +71,7 @@ use databend_common_base::base::uuid::Uuid;
 use databend_common_expression::DataSchema;
 use futures::Stream;
 use log::info;
+use prost::bytes::Bytes;
 use prost::Message;
 use tonic::metadata::MetadataValue;
 use tonic::server::NamedService;
@@ -93,6 +96,37 @@ fn try_unpack_any<T: ProstMessageExt>(message: Any) -> std::result::Result<T, St
         })
 }
 
+fn substrait_plan<'a>(
+    plan: &'a Option<SubstraitPlan>,
+    transaction_id: &Option<Bytes>,
+) -> std::result::Result<&'a [u8], Status> {
+    if transaction_id.is_some() {
+        return Err(Status::unimplemented(
+            "substrait plan with transaction is not supported",
+        ));
+    }
+    let plan = plan
+        .as_ref()
+        .ok_or_else(|| Status::invalid_argument("substrait plan is required"))?;
+    Ok(plan.plan.as_ref())
+}
+
+fn prepared_statement_result(
+    handle: Uuid,
+    data_schema: &DataSchema,
+) -> std::result::Result<ActionCreatePreparedStatementResult, Status> {
+    let schema = data_schema.into();
+    let message = SchemaAsIpc::new(&schema, &IpcWriteOptions::default())
+        .try_into()
+        .map_err(|e| status!("Unable to serialize schema", e))?;
+    let IpcMessage(schema_bytes) = message;
+    Ok(ActionCreatePreparedStatementResult {
+        prepared_statement_handle: handle.as_bytes().to_vec().into(),
+        dataset_schema: schema_bytes,
+        parameter_schema: Default::default(), // TODO: parameters
+    })
+}
+
 fn simple_flight_info<T: ProstMessageExt>(message: T) -> Response<FlightInfo> {
     let loc = Location {
         uri: "location_not_used".to_string(),
@@ -186,6 +220,21 @@ impl FlightSqlService for FlightSqlServiceImpl {
         message: Any,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         let session = self.get_session(&request)?;
+        if message.is::<CommandStatementSubstraitPlan>() {
+            let query: CommandStatementSubstraitPlan = try_unpack_any(message)?;
+            info!("do_get_fallback with substrait plan");
+            let plan = substrait_plan(&query.plan, &query.transaction_id)?;
+            let plan = self
+                .plan_substrait(&session, plan)
+                .await
+                .map_err(|e| status!("Error planning substrait plan", e))?;
+            let stream = self
+                .execute_query(session, &plan, None)
+                .await
+                .map_err(|e| status!("fail to execute", e))?;
+            return Ok(Response::new(stream));
+        }
+
         let fetch_results: FetchResults = try_unpack_any(message)?;
 
         let handle = Uuid::try_parse(&fetch_results.handle).map_err(|e| {
@@ -199,7 +248,11 @@ impl FlightSqlService for FlightSqlServiceImpl {
 
         let handle_plan = self.statements.get(&handle).unwrap();
         let stream = self
-            .execute_query(session, &handle_plan.value().0, &handle_plan.value().1)
+            .execute_query(
+                session,
+                &handle_plan.value().0,
+                handle_plan.value().1.as_ref(),
+            )
             .await
             .map_err(|e| status!("fail to execute", e))?;
         let resp = Response::new(stream);
@@ -329,48 +382,44 @@ impl FlightSqlService for FlightSqlServiceImpl {
     async fn get_flight_info_primary_keys(
         &self,
         query: CommandGetPrimaryKeys,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> std::result::Result<Response<FlightInfo>, Status> {
-        info!("get_flight_info_primary_keys({query:?})",);
-        Err(Status::unimplemented(
-            "get_flight_info_primary_keys not implemented",
-        ))
+        info!("get_flight_info_primary_keys({query:?})");
+        let _session = self.get_session(&request)?;
+        Ok(simple_flight_info(query))
     }
 
     #[async_backtrace::framed]
     async fn get_flight_info_exported_keys(
         &self,
         query: CommandGetExportedKeys,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> std::result::Result<Response<FlightInfo>, Status> {
         info!("get_flight_info_exported_keys({query:?})");
-        Err(Status::unimplemented(
-            "get_flight_info_exported_keys not implemented",
-        ))
+        let _session = self.get_session(&request)?;
+        Ok(simple_flight_info(query))
     }
 
     #[async_backtrace::framed]
     async fn get_flight_info_imported_keys(
         &self,
         query: CommandGetImportedKeys,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> std::result::Result<Response<FlightInfo>, Status> {
         info!("get_flight_info_imported_keys({query:?})");
-        Err(Status::unimplemented(
-            "get_flight_info_imported_keys not implemented",
-        ))
+        let _session = self.get_session(&request)?;
+        Ok(simple_flight_info(query))
     }
 
     #[async_backtrace::framed]
     async fn get_flight_info_cross_reference(
         &self,
         query: CommandGetCrossReference,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> std::result::Result<Response<FlightInfo>, Status> {
         info!("get_flight_info_cross_reference({query:?})");
-        Err(Status::unimplemented(
-            "get_flight_info_imported_keys not implemented",
-        ))
+        let _session = self.get_session(&request)?;
+        Ok(simple_flight_info(query))
     }
 
     // do_get
@@ -399,21 +448,35 @@ impl FlightSqlService for FlightSqlServiceImpl {
     #[async_backtrace::framed]
     async fn do_get_catalogs(
         &self,
-        _query: CommandGetCatalogs,
-        _request: Request<Ticket>,
+        query: CommandGetCatalogs,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         info!("do_get_catalogs()");
-        Err(Status::unimplemented("do_get_catalogs not implemented"))
+        let session = self.get_session(&request)?;
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+        Ok(Response::new(
+            super::CatalogInfoProvider::get_catalogs(context.clone(), query).await?,
+        ))
     }
 
     #[async_backtrace::framed]
     async fn do_get_schemas(
         &self,
         query: CommandGetDbSchemas,
-        _request: Request<Ticket>,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        info!("do_get_schemas({query:?}");
-        Err(Status::unimplemented("do_get_schemas not implemented"))
+        info!("do_get_schemas({query:?})");
+        let session = self.get_session(&request)?;
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+        Ok(Response::new(
+            super::CatalogInfoProvider::get_schemas(context.clone(), query).await?,
+        ))
     }
 
     #[async_backtrace::framed]
@@ -429,8 +492,7 @@ impl FlightSqlService for FlightSqlServiceImpl {
             .await
             .map_err(|e| status!("Could not create_query_context", e))?;
         Ok(Response::new(
-            super::CatalogInfoProvider::get_tables(context.clone(), query.catalog.clone(), None)
-                .await?,
+            super::CatalogInfoProvider::get_tables(context.clone(), query).await?,
         ))
     }
 
@@ -438,10 +500,11 @@ impl FlightSqlService for FlightSqlServiceImpl {
     async fn do_get_table_types(
         &self,
         _query: CommandGetTableTypes,
-        _request: Request<Ticket>,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         info!("do_get_table_types()");
-        Err(Status::unimplemented("do_get_table_types not implemented"))
+        let _session = self.get_session(&request)?;
+        Ok(Response::new(super::CatalogInfoProvider::get_table_types()?))
     }
 
     #[async_backtrace::framed]
@@ -458,21 +521,33 @@ impl FlightSqlService for FlightSqlServiceImpl {
     async fn do_get_primary_keys(
         &self,
         query: CommandGetPrimaryKeys,
-        _request: Request<Ticket>,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         info!("do_get_primary_keys({query:?})");
-        Err(Status::unimplemented("do_get_primary_keys not implemented"))
+        let session = self.get_session(&request)?;
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+        Ok(Response::new(
+            super::CatalogInfoProvider::get_primary_keys(context.clone(), query).await?,
+        ))
     }
 
     #[async_backtrace::framed]
     async fn do_get_exported_keys(
         &self,
         query: CommandGetExportedKeys,
-        _request: Request<Ticket>,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         info!("do_get_exported_keys({query:?})");
-        Err(Status::unimplemented(
-            "do_get_exported_keys not implemented",
+        let session = self.get_session(&request)?;
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+        Ok(Response::new(
+            super::CatalogInfoProvider::get_exported_keys(context.clone(), query).await?,
         ))
     }
 
@@ -480,11 +555,16 @@ impl FlightSqlService for FlightSqlServiceImpl {
     async fn do_get_imported_keys(
         &self,
         query: CommandGetImportedKeys,
-        _request: Request<Ticket>,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         info!("do_get_imported_keys({query:?})");
-        Err(Status::unimplemented(
-            "do_get_imported_keys not implemented",
+        let session = self.get_session(&request)?;
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+        Ok(Response::new(
+            super::CatalogInfoProvider::get_imported_keys(context.clone(), query).await?,
         ))
     }
 
@@ -492,11 +572,16 @@ impl FlightSqlService for FlightSqlServiceImpl {
     async fn do_get_cross_reference(
         &self,
         query: CommandGetCrossReference,
-        _request: Request<Ticket>,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         info!("do_get_cross_reference({query:?})");
-        Err(Status::unimplemented(
-            "do_get_cross_reference not implemented",
+        let session = self.get_session(&request)?;
+        let context = session
+            .create_query_context()
+            .await
+            .map_err(|e| status!("Could not create_query_context", e))?;
+        Ok(Response::new(
+            super::CatalogInfoProvider::get_cross_reference(context.clone(), query).await?,
         ))
     }
 
@@ -516,12 +601,29 @@ impl FlightSqlService for FlightSqlServiceImpl {
             .await
             .map_err(|e| status!("Error getting result schema", e))?;
         let res = self
-            .execute_update(session, &plan, &plan_extras)
+            .execute_update(session, &plan, Some(&plan_extras))
             .await
             .map_err(|e| status!("fail to execute", e))?;
         Ok(res)
     }
 
+    // called by ADBC bulk ingestion.
+    #[async_backtrace::framed]
+    async fn do_put_statement_ingest(
+        &self,
+        ticket: CommandStatementIngest,
+        request: Request<PeekableFlightDataStream>,
+    ) -> std::result::Result<i64, Status> {
+        let session = self.get_session(&request)?;
+        info!("do_put_statement_ingest({ticket:?})");
+
+        let res = self
+            .execute_ingest(session, ticket, request.into_inner())
+            .await
+            .map_err(|e| status!("fail to ingest", e))?;
+        Ok(res)
+    }
+
     #[async_backtrace::framed]
     async fn do_put_prepared_statement_query(
         &self,
@@ -536,7 +638,11 @@ impl FlightSqlService for FlightSqlServiceImpl {
 
         let handle_plan = self.statements.get(&handle).unwrap();
         let record_count = self
-            .execute_update(session, &handle_plan.value().0, &handle_plan.value().1)
+            .execute_update(
+                session,
+                &handle_plan.value().0,
+                handle_plan.value().1.as_ref(),
+            )
             .await
             .map_err(|e| status!("fail to execute", e))?;
         let result = DoPutUpdateResult { record_count };
@@ -564,7 +670,11 @@ impl FlightSqlService for FlightSqlServiceImpl {
 
         let handle_plan = self.statements.get(&handle).unwrap();
         let res = self
-            .execute_update(session, &handle_plan.value().0, &handle_plan.value().1)
+            .execute_update(
+                session,
+                &handle_plan.value().0,
+                handle_plan.value().1.as_ref(),
+            )
             .await
             .map_err(|e| status!("fail to execute", e))?;
 
@@ -581,7 +691,7 @@ impl FlightSqlService for FlightSqlServiceImpl {
         let session = self.get_session(&request)?;
         let sql = query.query.clone();
         let handle = Uuid::new_v4();
-        let plan = self
+        let (plan, plan_extras) = self
             .plan_sql(&session, &sql)
             .await
             .map_err(|e| status!("Error getting result schema", e))?;
@@ -590,8 +700,8 @@ impl FlightSqlService for FlightSqlServiceImpl {
             query.query
         );
         // JDBC client use call put when schema.fields == 0
-        let data_schema = if plan.0.has_result_set() {
-            plan.0.schema()
+        let data_schema = if plan.has_result_set() {
+            plan.schema()
         } else {
             Arc::new(DataSchema::empty())
         };
@@ -599,18 +709,8 @@ impl FlightSqlService for FlightSqlServiceImpl {
             "do_action_create_prepared_statement with handler={handle}, query={:?}, return schema={data_schema:?}",
             query.query
         );
-        let schema = (&*data_schema).into();
-        self.statements.insert(handle, plan);
-        let message = SchemaAsIpc::new(&schema, &IpcWriteOptions::default())
-            .try_into()
-            .map_err(|e| status!("Unable to serialize schema", e))?;
-        let IpcMessage(schema_bytes) = message;
-        let res = ActionCreatePreparedStatementResult {
-            prepared_statement_handle: handle.as_bytes().to_vec().into(),
-            dataset_schema: schema_bytes,
-            parameter_schema: Default::default(), // TODO: parameters
-        };
-        Ok(res)
+        self.statements.insert(handle, (plan, Some(plan_extras)));
+        prepared_statement_result(handle, &data_schema)
     }
 
     #[async_backtrace::framed]
@@ -646,46 +746,89 @@ impl FlightSqlService for FlightSqlServiceImpl {
         info!("register_sql_info({id}, {result:?})");
     }
 
-    /// Get a FlightInfo to extract information about the supported XDBC types.
+    #[async_backtrace::framed]
     async fn get_flight_info_xdbc_type_info(
         &self,
-        _query: CommandGetXdbcTypeInfo,
-        _request: Request<FlightDescriptor>,
+        query: CommandGetXdbcTypeInfo,
+        request: Request<FlightDescriptor>,
     ) -> std::result::Result<Response<FlightInfo>, Status> {
-        unimplemented!()
+        info!("get_flight_info_xdbc_type_info({query:?})");
+        let _session = self.get_session(&request)?;
+        Ok(simple_flight_info(query))
     }
 
-    /// Get a FlightDataStream containing the data related to the supported XDBC types.
+    #[async_backtrace::framed]
     async fn do_get_xdbc_type_info(
         &self,
-        _query: CommandGetXdbcTypeInfo,
-        _request: Request<Ticket>,
+        query: CommandGetXdbcTypeInfo,
+        request: Request<Ticket>,
     ) -> std::result::Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        unimplemented!()
+        info!("do_get_xdbc_type_info({query:?})");
+        let _session = self.get_session(&request)?;
+        Ok(Response::new(super::TypeInfoProvider::get_type_info(
+            query,
+        )?))
     }
 
+    #[async_backtrace::framed]
     async fn get_flight_info_substrait_plan(
         &self,
-        _query: CommandStatementSubstraitPlan,
-        _request: Request<FlightDescriptor>,
+        query: CommandStatementSubstraitPlan,
+        request: Request<FlightDescriptor>,
     ) -> std::result::Result<Response<FlightInfo>, Status> {
-        unimplemented!()
+        info!("get_flight_info_substrait_plan()");
+        let session = self.get_session(&request)?;
+        let plan = substrait_plan(&query.plan, &query.transaction_id)?;
+        let plan = self
+            .plan_substrait(&session, plan)
+            .await
+            .map_err(|e| status!("Error planning substrait plan", e))?;
+
+        // The plan is carried by the ticket and planned again in do_get,
+        // so nothing is kept on the server for it.
+        let schema = plan.schema().as_ref().into();
+        let message = SchemaAsIpc::new(&schema, &IpcWriteOptions::default())
+            .try_into()
+            .map_err(|e| status!("Unable to serialize schema", e))?;
+        let IpcMessage(schema_bytes) = message;
+        let mut resp = simple_flight_info(query);
+        resp.get_mut().schema = schema_bytes;
+        Ok(resp)
     }
 
+    #[async_backtrace::framed]
     async fn do_put_substrait_plan(
         &self,
         _query: CommandStatementSubstraitPlan,
-        _request: Request<PeekableFlightDataStream>,
+        request: Request<PeekableFlightDataStream>,
     ) -> std::result::Result<i64, Status> {
-        unimplemented!()
+        info!("do_put_substrait_plan()");
+        self.get_session(&request)?;
+        // Only query relations of substrait plans are supported, they never update.
+        Err(Status::invalid_argument(
+            "do_put_substrait_plan only accepts update plans, use get_flight_info_substrait_plan to execute queries",
+        ))
     }
 
+    #[async_backtrace::framed]
     async fn do_action_create_prepared_substrait_plan(
         &self,
-        _query: ActionCreatePreparedSubstraitPlanRequest,
-        _request: Request<Action>,
+        query: ActionCreatePreparedSubstraitPlanRequest,
+        request: Request<Action>,
     ) -> std::result::Result<ActionCreatePreparedStatementResult, Status> {
-        unimplemented!()
+        let session = self.get_session(&request)?;
+        let handle = Uuid::new_v4();
+        let plan = substrait_plan(&query.plan, &query.transaction_id)?;
+        let plan = self
+            .plan_substrait(&session, plan)
+            .await
+            .map_err(|e| status!("Error planning substrait plan", e))?;
+        let data_schema = plan.schema();
+        info!(
+            "do_action_create_prepared_substrait_plan with handler={handle}, return schema={data_schema:?}"
+        );
+        self.statements.insert(handle, (plan, None));
+        prepared_statement_result(handle, &data_schema)
     }
 
     async fn do_action_begin_transaction(
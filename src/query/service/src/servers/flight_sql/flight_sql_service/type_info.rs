@@ -0,0 +1,193 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::LazyLock;
+
+use arrow_flight::sql::metadata::XdbcTypeInfo;
+use arrow_flight::sql::metadata::XdbcTypeInfoData;
+use arrow_flight::sql::metadata::XdbcTypeInfoDataBuilder;
+use arrow_flight::sql::CommandGetXdbcTypeInfo;
+use arrow_flight::sql::Nullable;
+use arrow_flight::sql::Searchable;
+use arrow_flight::sql::XdbcDataType;
+use arrow_flight::sql::XdbcDatetimeSubcode;
+use arrow_flight::utils::batches_to_flight_data;
+use futures_util::stream;
+use tonic::Status;
+
+use crate::servers::flight_sql::flight_sql_service::DoGetStream;
+
+/// The SQL types of Databend known by the XDBC clients, the nested types are not listed.
+static TYPE_INFO: LazyLock<XdbcTypeInfoData> = LazyLock::new(|| {
+    let mut builder = XdbcTypeInfoDataBuilder::new();
+    builder.append(TypeInfoProvider::basic("BOOLEAN", XdbcDataType::XdbcBit, 1));
+    for (name, data_type, column_size) in [
+        ("TINYINT", XdbcDataType::XdbcTinyint, 3),
+        ("SMALLINT", XdbcDataType::XdbcSmallint, 5),
+        ("INT", XdbcDataType::XdbcInteger, 10),
+        ("BIGINT", XdbcDataType::XdbcBigint, 19),
+    ] {
+        builder.append(TypeInfoProvider::integer(
+            name,
+            data_type,
+            column_size,
+            false,
+        ));
+    }
+    for (name, data_type, column_size) in [
+        ("TINYINT UNSIGNED", XdbcDataType::XdbcTinyint, 3),
+        ("SMALLINT UNSIGNED", XdbcDataType::XdbcSmallint, 5),
+        ("INT UNSIGNED", XdbcDataType::XdbcInteger, 10),
+        ("BIGINT UNSIGNED", XdbcDataType::XdbcBigint, 20),
+    ] {
+        builder.append(TypeInfoProvider::integer(
+            name,
+            data_type,
+            column_size,
+            true,
+        ));
+    }
+    builder.append(TypeInfoProvider::float("FLOAT", XdbcDataType::XdbcReal, 24));
+    builder.append(TypeInfoProvider::float(
+        "DOUBLE",
+        XdbcDataType::XdbcDouble,
+        53,
+    ));
+    builder.append(XdbcTypeInfo {
+        create_params: Some(vec!["precision".to_string(), "scale".to_string()]),
+        unsigned_attribute: Some(false),
+        fixed_prec_scale: true,
+        minimum_scale: Some(0),
+        maximum_scale: Some(76),
+        num_prec_radix: Some(10),
+        ..TypeInfoProvider::basic("DECIMAL", XdbcDataType::XdbcDecimal, 76)
+    });
+    builder.append(TypeInfoProvider::string(
+        "VARCHAR",
+        XdbcDataType::XdbcVarchar,
+    ));
+    builder.append(TypeInfoProvider::string(
+        "VARIANT",
+        XdbcDataType::XdbcLongvarchar,
+    ));
+    builder.append(XdbcTypeInfo {
+        column_size: None,
+        ..TypeInfoProvider::basic("BINARY", XdbcDataType::XdbcVarbinary, 0)
+    });
+    builder.append(TypeInfoProvider::datetime(
+        "DATE",
+        XdbcDataType::XdbcDate,
+        XdbcDatetimeSubcode::XdbcSubcodeDate,
+        10,
+    ));
+    builder.append(TypeInfoProvider::datetime(
+        "TIMESTAMP",
+        XdbcDataType::XdbcTimestamp,
+        XdbcDatetimeSubcode::XdbcSubcodeTimestamp,
+        26,
+    ));
+    builder.build().expect("the xdbc type info must be valid")
+});
+
+pub(super) struct TypeInfoProvider {}
+
+impl TypeInfoProvider {
+    fn basic(type_name: &str, data_type: XdbcDataType, column_size: i32) -> XdbcTypeInfo {
+        XdbcTypeInfo {
+            type_name: type_name.to_string(),
+            data_type,
+            column_size: Some(column_size),
+            literal_prefix: None,
+            literal_suffix: None,
+            create_params: None,
+            nullable: Nullable::NullabilityNullable,
+            case_sensitive: false,
+            searchable: Searchable::Basic,
+            unsigned_attribute: None,
+            fixed_prec_scale: false,
+            auto_increment: Some(false),
+            local_type_name: Some(type_name.to_string()),
+            minimum_scale: None,
+            maximum_scale: None,
+            sql_data_type: data_type,
+            datetime_subcode: None,
+            num_prec_radix: None,
+            interval_precision: None,
+        }
+    }
+
+    fn integer(
+        type_name: &str,
+        data_type: XdbcDataType,
+        column_size: i32,
+        unsigned: bool,
+    ) -> XdbcTypeInfo {
+        XdbcTypeInfo {
+            unsigned_attribute: Some(unsigned),
+            fixed_prec_scale: true,
+            minimum_scale: Some(0),
+            maximum_scale: Some(0),
+            num_prec_radix: Some(10),
+            ..Self::basic(type_name, data_type, column_size)
+        }
+    }
+
+    fn float(type_name: &str, data_type: XdbcDataType, column_size: i32) -> XdbcTypeInfo {
+        XdbcTypeInfo {
+            unsigned_attribute: Some(false),
+            num_prec_radix: Some(2),
+            ..Self::basic(type_name, data_type, column_size)
+        }
+    }
+
+    fn string(type_name: &str, data_type: XdbcDataType) -> XdbcTypeInfo {
+        XdbcTypeInfo {
+            column_size: None,
+            literal_prefix: Some("'".to_string()),
+            literal_suffix: Some("'".to_string()),
+            case_sensitive: true,
+            searchable: Searchable::Full,
+            ..Self::basic(type_name, data_type, 0)
+        }
+    }
+
+    fn datetime(
+        type_name: &str,
+        data_type: XdbcDataType,
+        subcode: XdbcDatetimeSubcode,
+        column_size: i32,
+    ) -> XdbcTypeInfo {
+        XdbcTypeInfo {
+            literal_prefix: Some("'".to_string()),
+            literal_suffix: Some("'".to_string()),
+            sql_data_type: XdbcDataType::XdbcDatetime,
+            datetime_subcode: Some(subcode),
+            ..Self::basic(type_name, data_type, column_size)
+        }
+    }
+
+    /// The types matching the `data_type` of the command, or all of them if it is not given.
+    pub(crate) fn get_type_info(query: CommandGetXdbcTypeInfo) -> Result<DoGetStream, Status> {
+        let builder = query.into_builder(&TYPE_INFO);
+        let schema = builder.schema();
+        let batch = builder
+            .build()
+            .map_err(|e| Status::internal(format!("{e:?}")))?;
+        let flight_data = batches_to_flight_data(&schema, vec![batch])
+            .map_err(|e| Status::internal(format!("{e:?}")))?
+            .into_iter()
+            .map(Ok);
+        Ok(Box::pin(stream::iter(flight_data)))
+    }
+}
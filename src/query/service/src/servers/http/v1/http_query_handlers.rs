@@ -23,10 +23,14 @@ use databend_common_base::runtime::execute_futures_in_parallel;
 use databend_common_base::version::DATABEND_SEMVER;
 use databend_common_config::GlobalConfig;
 use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchema;
 use databend_common_expression::DataSchemaRef;
 use databend_common_metrics::http::metrics_incr_http_response_errors_count;
 use fastrace::func_path;
 use fastrace::prelude::*;
+use futures::TryStreamExt;
 use highway::HighwayHash;
 use http::HeaderMap;
 use http::HeaderValue;
@@ -42,16 +46,21 @@ use poem::post;
 use poem::put;
 use poem::web::Json;
 use poem::web::Path;
+use poem::Body;
 use poem::EndpointExt;
 use poem::IntoResponse;
 use poem::Request;
+use poem::Response;
 use poem::Route;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::query::result_stream;
+use super::query::BodyFormat;
 use super::query::ExecuteStateKind;
 use super::query::HttpQueryRequest;
 use super::query::HttpQueryResponseInternal;
+use super::query::PageData;
 use super::query::RemoveReason;
 use crate::clusters::ClusterDiscovery;
 use crate::servers::http::error::HttpErrorCode;
@@ -71,7 +80,6 @@ use crate::servers::http::v1::discovery_nodes;
 use crate::servers::http::v1::list_suggestions;
 use crate::servers::http::v1::login_handler;
 use crate::servers::http::v1::logout_handler;
-use crate::servers::http::v1::query::string_block::StringBlock;
 use crate::servers::http::v1::query::Progresses;
 use crate::servers::http::v1::refresh_handler;
 use crate::servers::http::v1::roles::list_roles_handler;
@@ -161,31 +169,69 @@ impl QueryResponse {
         id: String,
         r: HttpQueryResponseInternal,
         is_final: bool,
-    ) -> impl IntoResponse {
+    ) -> Response {
+        let state = r.state.state;
+        let (mut response, data) = Self::split_internal(id.clone(), r, is_final);
+        let rows = data.as_ref().map(|d| d.num_rows()).unwrap_or(0);
+
+        let body = match data {
+            Some(PageData::Strings(block)) => {
+                response.data = block.into();
+                Json(response).into_response()
+            }
+            // a parquet file without columns is useless, fallback to JSON.
+            Some(PageData::Blocks {
+                format,
+                schema,
+                blocks,
+            }) if !(format == BodyFormat::Parquet && schema.fields().is_empty()) => {
+                match serialize_page(&response, format, &schema, blocks) {
+                    Ok(body) => Response::builder()
+                        .content_type(format.content_type())
+                        .body(body),
+                    Err(err) => PoemError::from(HttpErrorCode::server_error(err)).into_response(),
+                }
+            }
+            _ => Json(response).into_response(),
+        };
+
+        body.with_header(HEADER_QUERY_ID, id.clone())
+            .with_header(HEADER_QUERY_STATE, state.to_string())
+            .with_header(HEADER_QUERY_PAGE_ROWS, rows)
+            .into_response()
+    }
+
+    /// Build the response without `data`, the data of the page is returned separately to be
+    /// encoded in the negotiated format.
+    pub(crate) fn split_internal(
+        id: String,
+        r: HttpQueryResponseInternal,
+        is_final: bool,
+    ) -> (QueryResponse, Option<PageData>) {
         let state = r.state.clone();
         let (data, next_uri) = if is_final {
-            (StringBlock::empty(), None)
+            (None, None)
         } else {
             match state.state {
                 ExecuteStateKind::Running | ExecuteStateKind::Starting => match r.data {
-                    None => (StringBlock::empty(), Some(make_state_uri(&id))),
+                    None => (None, Some(make_state_uri(&id))),
                     Some(d) => {
                         let uri = match d.next_page_no {
                             Some(n) => Some(make_page_uri(&id, n)),
                             None => Some(make_state_uri(&id)),
                         };
-                        (d.page.data, uri)
+                        (Some(d.page.data), uri)
                     }
                 },
-                ExecuteStateKind::Failed => (StringBlock::empty(), Some(make_final_uri(&id))),
+                ExecuteStateKind::Failed => (None, Some(make_final_uri(&id))),
                 ExecuteStateKind::Succeeded => match r.data {
-                    None => (StringBlock::empty(), Some(make_final_uri(&id))),
+                    None => (None, Some(make_final_uri(&id))),
                     Some(d) => {
                         let uri = match d.next_page_no {
                             Some(n) => Some(make_page_uri(&id, n)),
                             None => Some(make_final_uri(&id)),
                         };
-                        (d.page.data, uri)
+                        (Some(d.page.data), uri)
                     }
                 },
             }
@@ -200,10 +246,8 @@ impl QueryResponse {
             progresses: state.progresses.clone(),
             running_time_ms: state.running_time_ms,
         };
-        let rows = data.data.len();
-
-        Json(QueryResponse {
-            data: data.into(),
+        let response = QueryResponse {
+            data: vec![],
             state: state.state,
             schema: state.schema.clone(),
             session_id: Some(session_id),
@@ -220,13 +264,22 @@ impl QueryResponse {
             error: r.state.error.map(QueryError::from_error_code),
             has_result_set: r.state.has_result_set,
             result_timeout_secs: Some(r.result_timeout_secs),
-        })
-        .with_header(HEADER_QUERY_ID, id.clone())
-        .with_header(HEADER_QUERY_STATE, state.state.to_string())
-        .with_header(HEADER_QUERY_PAGE_ROWS, rows)
+        };
+        (response, data)
     }
 }
 
+/// Encode the blocks of the page, the response without the data is kept in the metadata.
+fn serialize_page(
+    response: &QueryResponse,
+    format: BodyFormat,
+    schema: &DataSchema,
+    blocks: Vec<DataBlock>,
+) -> Result<Vec<u8>> {
+    let response_header = serde_json::to_string(response)?;
+    format.serialize(schema, blocks, response_header)
+}
+
 /// final is not ACKed by client, so client should not depend on the final response,
 ///
 /// for server:
@@ -386,6 +439,7 @@ async fn query_page_handler(
 #[async_backtrace::framed]
 pub(crate) async fn query_handler(
     ctx: &HttpQueryContext,
+    request: &Request,
     Json(req): Json<HttpQueryRequest>,
 ) -> PoemResult<impl IntoResponse> {
     let root = get_http_tracing_span(func_path!(), ctx, &ctx.query_id);
@@ -397,9 +451,10 @@ pub(crate) async fn query_handler(
         info!("http query new request{}{}: {}", agent_info, client_session_id_info, mask_connection_info(&format!("{:?}", req)));
         let http_query_manager = HttpQueryManager::instance();
         let sql = req.sql.clone();
+        let body_format = BodyFormat::from_headers(request.headers());
 
         let query = http_query_manager
-            .try_create_query(ctx, req.clone())
+            .try_create_query(ctx, req.clone(), body_format)
             .await
             .map_err(|err| err.display_with_sql(&sql));
         match query {
@@ -422,7 +477,7 @@ pub(crate) async fn query_handler(
                         &query.id, &resp.state, rows, next_page, mask_connection_info(&sql)
                     );
                 query.update_expire_time(false).await;
-                Ok(QueryResponse::from_internal(query.id.to_string(), resp, false).into_response())
+                Ok(QueryResponse::from_internal(query.id.to_string(), resp, false))
             }
             Err(e) => {
                 error!("http query fail to start sql, error: {:?}", e);
@@ -435,6 +490,93 @@ pub(crate) async fn query_handler(
         .await
 }
 
+/// Start a query and stream all of its results in one chunked response, see `result_stream`
+/// for the format of the body. A query failing to start is responded the same as `/v1/query`.
+#[poem::handler]
+#[async_backtrace::framed]
+pub(crate) async fn query_stream_handler(
+    ctx: &HttpQueryContext,
+    request: &Request,
+    Json(mut req): Json<HttpQueryRequest>,
+) -> PoemResult<Response> {
+    let root = get_http_tracing_span(func_path!(), ctx, &ctx.query_id);
+    let _t = SlowRequestLogTracker::new(ctx);
+
+    async {
+        info!(
+            "http query new streaming request: {}",
+            mask_connection_info(&format!("{:?}", req))
+        );
+        let format = match BodyFormat::from_headers(request.headers()) {
+            BodyFormat::Parquet => {
+                return Err(PoemError::from_string(
+                    "parquet is not supported by the streaming query, use /v1/query instead",
+                    StatusCode::NOT_ACCEPTABLE,
+                ));
+            }
+            format => format,
+        };
+        // the pages are pulled by the stream, wait for the blocks instead of spinning.
+        req.pagination.wait_time_secs = req.pagination.wait_time_secs.max(1);
+        let sql = req.sql.clone();
+
+        let http_query_manager = HttpQueryManager::instance();
+        let query = match http_query_manager
+            .try_create_query(ctx, req.clone(), format)
+            .await
+            .map_err(|err| err.display_with_sql(&sql))
+        {
+            Ok(query) => query,
+            Err(e) => {
+                error!("http query fail to start sql, error: {:?}", e);
+                ctx.set_fail();
+                return Ok(req.fail_to_start_sql(e).into_response());
+            }
+        };
+
+        // wait for the first rows, so that the schema of the results is known.
+        let mut resp;
+        loop {
+            query.update_expire_time(true).await;
+            resp = query
+                .get_response_page(0)
+                .await
+                .map_err(|err| err.display_with_sql(&sql))
+                .map_err(HttpErrorCode::server_error)?;
+            let waiting = resp
+                .data
+                .as_ref()
+                .is_some_and(|d| d.page.data.num_rows() == 0 && d.next_page_no.is_some());
+            if resp.state.state.is_stopped() || !waiting {
+                break;
+            }
+        }
+
+        if matches!(resp.state.state, ExecuteStateKind::Failed) {
+            ctx.set_fail();
+            http_query_manager
+                .remove_query(
+                    &query.id,
+                    &ctx.client_session_id,
+                    RemoveReason::Finished,
+                    ErrorCode::ClosedQuery("closed by client"),
+                )
+                .await?;
+            return Ok(QueryResponse::from_internal(query.id.clone(), resp, true));
+        }
+
+        let query_id = query.id.clone();
+        let stream = result_stream(query, ctx.client_session_id.clone(), format, resp)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+        Ok(Response::builder()
+            .content_type(format.stream_content_type())
+            .header(HEADER_QUERY_ID, query_id)
+            .body(Body::from_bytes_stream(stream)))
+    }
+    .in_span(root)
+    .await
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 struct HeartBeatRequest {
     node_to_queries: HashMap<String, Vec<String>>,
@@ -548,9 +690,14 @@ pub async fn heartbeat_handler(
 }
 
 pub fn query_route() -> Route {
-    // Note: endpoints except /v1/query may change without notice, use uris in response instead
+    // Note: endpoints except /v1/query and /v1/query/stream may change without notice, use uris in response instead
     let rules = [
         ("/query", post(query_handler), EndpointKind::StartQuery),
+        (
+            "/query/stream",
+            post(query_stream_handler),
+            EndpointKind::StartQuery,
+        ),
         (
             "/query/:id",
             get(query_state_handler),
@@ -31,12 +31,14 @@ pub use http_query_handlers::query_route;
 pub use http_query_handlers::QueryResponse;
 pub use http_query_handlers::QueryStats;
 pub use query::string_block::StringBlock;
+pub use query::BodyFormat;
 pub use query::ExecuteStateKind;
 pub use query::ExpiringMap;
 pub use query::ExpiringState;
 pub use query::HttpQueryContext;
 pub use query::HttpQueryManager;
 pub use query::HttpSessionConf;
+pub use query::RESPONSE_HEADER_METADATA_KEY;
 pub use roles::list_roles_handler;
 pub use session::login_handler::login_handler;
 pub use session::login_handler::LoginResponse;
@@ -0,0 +1,137 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::Schema;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::infer_table_schema;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchema;
+use http::header::ACCEPT;
+use http::HeaderMap;
+use parquet::arrow::ArrowWriter;
+use parquet::format::KeyValue;
+
+pub const CONTENT_TYPE_JSON: &str = "application/json";
+pub const CONTENT_TYPE_ARROW_STREAM: &str = "application/vnd.apache.arrow.stream";
+pub const CONTENT_TYPE_PARQUET: &str = "application/vnd.apache.parquet";
+pub const CONTENT_TYPE_NDJSON: &str = "application/x-ndjson";
+
+/// The key of the schema metadata which carries the JSON response without `data`,
+/// so that `next_uri`, `state` and `session` are available to the clients.
+pub const RESPONSE_HEADER_METADATA_KEY: &str = "response_header";
+
+/// The encoding of the result pages of a http query, negotiated by the `Accept` header of
+/// the request which starts the query, the following pages are in the same format.
+///
+/// The responses without data, like the state, final and failed responses, are always JSON,
+/// the clients should check the `Content-Type` of the response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyFormat {
+    #[default]
+    Json,
+    /// A page is an Arrow IPC stream with the native types.
+    Arrow,
+    /// A page is a Parquet file with the native types, for downloading large results.
+    Parquet,
+}
+
+impl BodyFormat {
+    /// Pick the first supported media type of the `Accept` header, the quality values are
+    /// ignored. Fallback to JSON if nothing is supported.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(ACCEPT).and_then(|v| v.to_str().ok()) else {
+            return BodyFormat::Json;
+        };
+        for media_range in accept.split(',') {
+            let media_type = media_range.split(';').next().unwrap_or("").trim();
+            if media_type.eq_ignore_ascii_case(CONTENT_TYPE_ARROW_STREAM) {
+                return BodyFormat::Arrow;
+            }
+            if media_type.eq_ignore_ascii_case(CONTENT_TYPE_PARQUET) {
+                return BodyFormat::Parquet;
+            }
+            if media_type.eq_ignore_ascii_case(CONTENT_TYPE_JSON) {
+                return BodyFormat::Json;
+            }
+        }
+        BodyFormat::Json
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BodyFormat::Json => CONTENT_TYPE_JSON,
+            BodyFormat::Arrow => CONTENT_TYPE_ARROW_STREAM,
+            BodyFormat::Parquet => CONTENT_TYPE_PARQUET,
+        }
+    }
+
+    /// The content type of the streaming response, the JSON rows are streamed as NDJSON.
+    pub fn stream_content_type(&self) -> &'static str {
+        match self {
+            BodyFormat::Json => CONTENT_TYPE_NDJSON,
+            format => format.content_type(),
+        }
+    }
+
+    /// Encode the blocks of a page, with the JSON response in the metadata.
+    pub fn serialize(
+        &self,
+        schema: &DataSchema,
+        blocks: Vec<DataBlock>,
+        response_header: String,
+    ) -> Result<Vec<u8>> {
+        let table_schema = infer_table_schema(schema)?;
+        let metadata = HashMap::from([(
+            RESPONSE_HEADER_METADATA_KEY.to_string(),
+            response_header.clone(),
+        )]);
+        let arrow_schema = Arc::new(Schema::from(table_schema.as_ref()).with_metadata(metadata));
+
+        let mut batches = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let batch = block.to_record_batch(&table_schema)?;
+            batches.push(batch.with_schema(arrow_schema.clone())?);
+        }
+
+        match self {
+            BodyFormat::Json => Err(ErrorCode::Internal(
+                "the JSON pages are not encoded from blocks",
+            )),
+            BodyFormat::Arrow => {
+                let mut writer = StreamWriter::try_new(Vec::new(), &arrow_schema)?;
+                for batch in &batches {
+                    writer.write(batch)?;
+                }
+                writer.finish()?;
+                Ok(writer.into_inner()?)
+            }
+            BodyFormat::Parquet => {
+                let mut writer = ArrowWriter::try_new(Vec::new(), arrow_schema.clone(), None)?;
+                for batch in &batches {
+                    writer.write(batch)?;
+                }
+                writer.append_key_value_metadata(KeyValue::new(
+                    RESPONSE_HEADER_METADATA_KEY.to_string(),
+                    response_header,
+                ));
+                Ok(writer.into_inner()?)
+            }
+        }
+    }
+}
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::SystemTime;
 
+use databend_common_ast::ast::Statement;
 use databend_common_base::base::tokio::sync::RwLock;
 use databend_common_base::base::ProgressValues;
 use databend_common_base::base::SpillProgress;
@@ -157,6 +158,7 @@ pub struct ExecutorSessionState {
     pub txn_manager: TxnManagerRef,
     pub temp_tbl_mgr: TempTblMgrRef,
     pub variables: HashMap<String, Scalar>,
+    pub prepared_statements: HashMap<String, Statement>,
 }
 
 impl ExecutorSessionState {
@@ -170,6 +172,7 @@ impl ExecutorSessionState {
             txn_manager: session.txn_mgr(),
             temp_tbl_mgr: session.temp_tbl_mgr(),
             variables: session.get_all_variables(),
+            prepared_statements: session.get_all_prepared_statements(),
         }
     }
 }
@@ -343,6 +346,7 @@ impl ExecuteState {
         ctx: Arc<QueryContext>,
         block_sender: SizedChannelSender<DataBlock>,
         format_settings: Arc<parking_lot::RwLock<Option<FormatSettings>>>,
+        result_schema: Arc<parking_lot::RwLock<Option<DataSchemaRef>>>,
     ) -> Result<(), ExecutionError> {
         let make_error = || format!("failed to start query: {sql}");
 
@@ -358,6 +362,7 @@ impl ExecuteState {
             let mut guard = format_settings.write();
             *guard = Some(ctx.get_format_settings().with_context(make_error)?);
         }
+        *result_schema.write() = Some(plan.schema());
 
         let interpreter = InterpreterFactory::get(ctx.clone(), &plan)
             .await
@@ -21,7 +21,12 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
+use databend_common_ast::ast::Statement;
+use databend_common_ast::parser::parse_sql;
+use databend_common_ast::parser::tokenize_sql;
+use databend_common_ast::parser::Dialect;
 use databend_common_base::base::short_sql;
+use databend_common_base::base::tokio;
 use databend_common_base::base::tokio::sync::Mutex as TokioMutex;
 use databend_common_base::base::tokio::sync::RwLock;
 use databend_common_base::runtime::CatchUnwindFuture;
@@ -31,6 +36,7 @@ use databend_common_catalog::table_context::StageAttachment;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_exception::ResultExt;
+use databend_common_expression::DataSchemaRef;
 use databend_common_expression::Scalar;
 use databend_common_io::prelude::FormatSettings;
 use databend_common_meta_app::tenant::Tenant;
@@ -49,6 +55,7 @@ use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
 
+use super::body_format::BodyFormat;
 use super::execute_state::ExecutionError;
 use super::HttpQueryContext;
 use super::RemoveReason;
@@ -196,10 +203,16 @@ pub struct ServerInfo {
 pub struct HttpSessionStateInternal {
     /// value is JSON of Scalar
     variables: Vec<(String, String)>,
+    /// value is the SQL text of the prepared statement
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    prepared_statements: Vec<(String, String)>,
 }
 
 impl HttpSessionStateInternal {
-    fn new(variables: &HashMap<String, Scalar>) -> Self {
+    fn new(
+        variables: &HashMap<String, Scalar>,
+        prepared_statements: &HashMap<String, Statement>,
+    ) -> Self {
         let variables = variables
             .iter()
             .map(|(k, v)| {
@@ -209,7 +222,14 @@ impl HttpSessionStateInternal {
                 )
             })
             .collect();
-        Self { variables }
+        let prepared_statements = prepared_statements
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_string()))
+            .collect();
+        Self {
+            variables,
+            prepared_statements,
+        }
     }
 
     pub fn get_variables(&self) -> Result<HashMap<String, Scalar>> {
@@ -228,6 +248,20 @@ impl HttpSessionStateInternal {
         }
         Ok(vars)
     }
+
+    pub fn get_prepared_statements(&self, dialect: Dialect) -> Result<HashMap<String, Statement>> {
+        let mut statements = HashMap::with_capacity(self.prepared_statements.len());
+        for (k, v) in self.prepared_statements.iter() {
+            let tokens = tokenize_sql(v)?;
+            let (stmt, _) = parse_sql(&tokens, dialect).map_err(|e| {
+                ErrorCode::BadBytes(format!(
+                    "fail decode prepared statement '{k}' from string '{v}', error: {e}"
+                ))
+            })?;
+            statements.insert(k.to_string(), stmt);
+        }
+        Ok(statements)
+    }
 }
 
 fn serialize_as_json_string<S>(
@@ -456,6 +490,10 @@ impl HttpQuery {
                 if !state.variables.is_empty() {
                     session.set_all_variables(state.get_variables()?)
                 }
+                if !state.prepared_statements.is_empty() {
+                    let dialect = session.get_settings().get_sql_dialect()?;
+                    session.set_all_prepared_statements(state.get_prepared_statements(dialect)?)
+                }
             }
             try_set_txn(&ctx.query_id, &session, session_conf, &http_query_manager)?;
             if session_conf.need_sticky
@@ -531,6 +569,8 @@ impl HttpQuery {
         };
         let format_settings: Arc<parking_lot::RwLock<Option<FormatSettings>>> = Default::default();
         let format_settings_clone = format_settings.clone();
+        let result_schema: Arc<parking_lot::RwLock<Option<DataSchemaRef>>> = Default::default();
+        let result_schema_clone = result_schema.clone();
         let tenant = session.get_current_tenant();
         let user_name = session.get_current_user()?.name;
 
@@ -548,6 +588,7 @@ impl HttpQuery {
                     ctx_clone.clone(),
                     block_sender,
                     format_settings_clone,
+                    result_schema_clone,
                 ))
                 .await
                 .with_context(|| "failed to start query")
@@ -577,6 +618,8 @@ impl HttpQuery {
             request.pagination.max_rows_per_page,
             block_receiver,
             format_settings,
+            body_format,
+            result_schema,
         )));
 
         let query = HttpQuery {
@@ -634,6 +677,26 @@ impl HttpQuery {
         })
     }
 
+    /// Wait for the query to stop, then get the final state with the session state.
+    #[async_backtrace::framed]
+    pub async fn get_response_final(&self) -> Result<HttpQueryResponseInternal> {
+        // the block channel may be closed just before the state is changed to stopped.
+        while !matches!(self.state.read().await.state, ExecuteState::Stopped(_)) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let state = self.get_state().await;
+        let session = self.get_response_session().await?;
+
+        Ok(HttpQueryResponseInternal {
+            data: None,
+            state,
+            session: Some(session),
+            node_id: self.node_id.clone(),
+            session_id: self.session_id.clone(),
+            result_timeout_secs: self.result_timeout_secs,
+        })
+    }
+
     #[async_backtrace::framed]
     async fn get_state(&self) -> ResponseState {
         let state = self.state.read().await;
@@ -662,8 +725,13 @@ impl HttpQuery {
         let role = session_state.current_role.clone();
         let secondary_roles = session_state.secondary_roles.clone();
         let txn_state = session_state.txn_manager.lock().state();
-        let internal = if !session_state.variables.is_empty() {
-            Some(HttpSessionStateInternal::new(&session_state.variables))
+        let internal = if !session_state.variables.is_empty()
+            || !session_state.prepared_statements.is_empty()
+        {
+            Some(HttpSessionStateInternal::new(
+                &session_state.variables,
+                &session_state.prepared_statements,
+            ))
         } else {
             None
         };
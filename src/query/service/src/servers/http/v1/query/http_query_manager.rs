@@ -33,6 +33,7 @@ use databend_common_exception::Result;
 use databend_storages_common_session::TxnManagerRef;
 use parking_lot::Mutex;
 
+use super::BodyFormat;
 use super::HttpQueryContext;
 use crate::servers::http::v1::query::http_query::ExpireResult;
 use crate::servers::http::v1::query::http_query::HttpQuery;
@@ -111,8 +112,9 @@ impl HttpQueryManager {
         self: &Arc<Self>,
         ctx: &HttpQueryContext,
         request: HttpQueryRequest,
+        body_format: BodyFormat,
     ) -> Result<Arc<HttpQuery>> {
-        let query = HttpQuery::try_create(ctx, request).await?;
+        let query = HttpQuery::try_create(ctx, request, body_format).await?;
         self.add_query(&query.id, query.clone()).await;
         Ok(query)
     }
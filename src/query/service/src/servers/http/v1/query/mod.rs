@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod body_format;
 pub mod execute_state;
 pub mod expirable;
 pub mod expiring_map;
@@ -19,9 +20,12 @@ mod http_query;
 mod http_query_context;
 mod http_query_manager;
 mod page_manager;
+mod result_stream;
 pub mod sized_spsc;
 pub mod string_block;
 
+pub use body_format::BodyFormat;
+pub use body_format::RESPONSE_HEADER_METADATA_KEY;
 pub(crate) use execute_state::ExecuteState;
 pub use execute_state::ExecuteStateKind;
 pub(crate) use execute_state::Executor;
@@ -34,6 +38,9 @@ pub use http_query::HttpSessionConf;
 pub use http_query_context::HttpQueryContext;
 pub use http_query_manager::HttpQueryManager;
 pub(crate) use http_query_manager::RemoveReason;
+pub use page_manager::Page;
+pub use page_manager::PageData;
 pub use page_manager::PageManager;
 pub use page_manager::ResponseData;
 pub use page_manager::Wait;
+pub(crate) use result_stream::result_stream;
@@ -20,11 +20,14 @@ use databend_common_base::base::tokio;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchema;
+use databend_common_expression::DataSchemaRef;
 use databend_common_io::prelude::FormatSettings;
 use log::debug;
 use log::info;
 use parking_lot::RwLock;
 
+use super::body_format::BodyFormat;
 use super::string_block::block_to_strings;
 use super::string_block::StringBlock;
 use crate::servers::http::v1::query::sized_spsc::SizedChannelReceiver;
@@ -35,9 +38,31 @@ pub enum Wait {
     Deadline(Instant),
 }
 
+const MAX_BYTES_PER_PAGE: usize = 10 * 1024 * 1024;
+
+#[derive(Clone)]
+pub enum PageData {
+    Strings(StringBlock),
+    /// The blocks of the page in the native types, encoded in `format` when responding.
+    Blocks {
+        format: BodyFormat,
+        schema: DataSchemaRef,
+        blocks: Vec<DataBlock>,
+    },
+}
+
+impl PageData {
+    pub fn num_rows(&self) -> usize {
+        match self {
+            PageData::Strings(block) => block.num_rows(),
+            PageData::Blocks { blocks, .. } => blocks.iter().map(|b| b.num_rows()).sum(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Page {
-    pub data: StringBlock,
+    pub data: PageData,
 }
 
 pub struct ResponseData {
@@ -53,8 +78,11 @@ pub struct PageManager {
     block_end: bool,
     last_page: Option<Page>,
     row_buffer: VecDeque<Vec<Option<String>>>,
+    block_buffer: Option<DataBlock>,
     block_receiver: SizedChannelReceiver<DataBlock>,
     format_settings: Arc<RwLock<Option<FormatSettings>>>,
+    body_format: BodyFormat,
+    result_schema: Arc<RwLock<Option<DataSchemaRef>>>,
 }
 
 impl PageManager {
@@ -62,6 +90,8 @@ impl PageManager {
         max_rows_per_page: usize,
         block_receiver: SizedChannelReceiver<DataBlock>,
         format_settings: Arc<RwLock<Option<FormatSettings>>>,
+        body_format: BodyFormat,
+        result_schema: Arc<RwLock<Option<DataSchemaRef>>>,
     ) -> PageManager {
         PageManager {
             total_rows: 0,
@@ -70,9 +100,12 @@ impl PageManager {
             end: false,
             block_end: false,
             row_buffer: Default::default(),
+            block_buffer: None,
             block_receiver,
             max_rows_per_page,
             format_settings,
+            body_format,
+            result_schema,
         }
     }
 
@@ -89,10 +122,10 @@ impl PageManager {
         let next_no = self.total_pages;
         if page_no == next_no {
             if !self.end {
-                let (block, end) = self.collect_new_page(tp).await?;
-                let num_row = block.num_rows();
+                let (data, end) = self.collect_new_page(tp).await?;
+                let num_row = data.num_rows();
                 self.total_rows += num_row;
-                let page = Page { data: block };
+                let page = Page { data };
                 if num_row > 0 {
                     self.total_pages += 1;
                     self.last_page = Some(page.clone());
@@ -104,7 +137,7 @@ impl PageManager {
                 // but the response may be lost and client will retry,
                 // we simply return an empty page.
                 let page = Page {
-                    data: StringBlock::default(),
+                    data: self.page_data(vec![]),
                 };
                 Ok(page)
             }
@@ -153,10 +186,76 @@ impl PageManager {
         Ok(())
     }
 
+    fn page_data(&self, blocks: Vec<DataBlock>) -> PageData {
+        match self.body_format {
+            BodyFormat::Json => PageData::Strings(StringBlock::default()),
+            format => {
+                let schema = self
+                    .result_schema
+                    .read()
+                    .clone()
+                    .unwrap_or_else(|| Arc::new(DataSchema::empty()));
+                PageData::Blocks {
+                    format,
+                    schema,
+                    blocks,
+                }
+            }
+        }
+    }
+
+    // Receive the next block, returns None if there is no block before the wait deadline.
+    #[async_backtrace::framed]
+    async fn recv_block(&mut self, tp: &Wait) -> Option<DataBlock> {
+        match tp {
+            Wait::Async => self.block_receiver.try_recv(),
+            Wait::Deadline(t) => {
+                let now = Instant::now();
+                let d = *t - now;
+                if d.is_zero() {
+                    // timeout() will return Ok if the future completes immediately
+                    return None;
+                }
+                match tokio::time::timeout(d, self.block_receiver.recv()).await {
+                    Ok(Some(block)) => {
+                        debug!("http query got new block with {} rows", block.num_rows());
+                        Some(block)
+                    }
+                    Ok(None) => {
+                        info!("http query reach end of blocks");
+                        None
+                    }
+                    Err(_) => {
+                        debug!("http query long pulling timeout");
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    #[async_backtrace::framed]
+    async fn collect_new_page(&mut self, tp: &Wait) -> Result<(PageData, bool)> {
+        let data = match self.body_format {
+            BodyFormat::Json => PageData::Strings(self.collect_string_block(tp).await?),
+            _ => {
+                let blocks = self.collect_blocks(tp).await;
+                self.page_data(blocks)
+            }
+        };
+
+        // try to report 'no more data' earlier to client to avoid unnecessary http call
+        if !self.block_end {
+            self.block_end = self.block_receiver.is_empty();
+        }
+        let end = self.block_end && self.row_buffer.is_empty() && self.block_buffer.is_none();
+        Ok((data, end))
+    }
+
     #[async_backtrace::framed]
-    async fn collect_new_page(&mut self, tp: &Wait) -> Result<(StringBlock, bool)> {
+    async fn collect_string_block(&mut self, tp: &Wait) -> Result<StringBlock> {
         let mut res: Vec<Vec<Option<String>>> = Vec::with_capacity(self.max_rows_per_page);
-        let mut remain_size = 10 * 1024 * 1024;
+        let mut remain_size = MAX_BYTES_PER_PAGE;
         let mut remain_rows = self.max_rows_per_page;
         while remain_rows > 0 && remain_size > 0 {
             if let Some(row) = self.row_buffer.pop_front() {
@@ -179,53 +278,65 @@ impl PageManager {
         }
 
         while remain_rows > 0 && remain_size > 0 {
-            match tp {
-                Wait::Async => match self.block_receiver.try_recv() {
-                    Some(block) => {
-                        self.append_block(&mut res, block, &mut remain_rows, &mut remain_size)?
-                    }
-                    None => break,
-                },
-                Wait::Deadline(t) => {
-                    let now = Instant::now();
-                    let d = *t - now;
-                    if d.is_zero() {
-                        // timeout() will return Ok if the future completes immediately
-                        break;
-                    }
-                    match tokio::time::timeout(d, self.block_receiver.recv()).await {
-                        Ok(Some(block)) => {
-                            debug!("http query got new block with {} rows", block.num_rows());
-                            self.append_block(&mut res, block, &mut remain_rows, &mut remain_size)?;
-                        }
-                        Ok(None) => {
-                            info!("http query reach end of blocks");
-                            break;
-                        }
-                        Err(_) => {
-                            debug!("http query long pulling timeout");
-                            break;
-                        }
-                    }
+            match self.recv_block(tp).await {
+                Some(block) => {
+                    self.append_block(&mut res, block, &mut remain_rows, &mut remain_size)?
                 }
+                None => break,
             }
         }
 
-        let block = StringBlock { data: res };
+        Ok(StringBlock { data: res })
+    }
 
-        // try to report 'no more data' earlier to client to avoid unnecessary http call
-        if !self.block_end {
-            self.block_end = self.block_receiver.is_empty();
+    // Collect the blocks of a page, the size of the page is limited by the memory size of the
+    // blocks, the block is sliced if it is over the limit.
+    #[async_backtrace::framed]
+    async fn collect_blocks(&mut self, tp: &Wait) -> Vec<DataBlock> {
+        let mut res = vec![];
+        let mut remain_size = MAX_BYTES_PER_PAGE;
+        let mut remain_rows = self.max_rows_per_page;
+        while remain_rows > 0 && remain_size > 0 {
+            let block = match self.block_buffer.take() {
+                Some(block) => block,
+                None => match self.recv_block(tp).await {
+                    Some(block) => block,
+                    None => break,
+                },
+            };
+            let num_rows = block.num_rows();
+            if num_rows == 0 {
+                continue;
+            }
+
+            let row_size = block.memory_size().div_ceil(num_rows).max(1);
+            let mut rows = num_rows.min(remain_rows).min(remain_size / row_size);
+            if rows == 0 && res.is_empty() {
+                // a page has at least one row
+                rows = 1;
+            }
+            if rows == 0 {
+                self.block_buffer = Some(block);
+                break;
+            }
+            if rows < num_rows {
+                self.block_buffer = Some(block.slice(rows..num_rows));
+                res.push(block.slice(0..rows));
+            } else {
+                res.push(block);
+            }
+            remain_rows -= rows;
+            remain_size = remain_size.saturating_sub(rows * row_size);
         }
-        let end = self.block_end && self.row_buffer.is_empty();
-        Ok((block, end))
+        res
     }
 
     #[async_backtrace::framed]
     pub async fn detach(&mut self) {
         self.block_receiver.close();
         self.last_page = None;
-        self.row_buffer.clear()
+        self.row_buffer.clear();
+        self.block_buffer = None;
     }
 }
 
@@ -0,0 +1,275 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::Schema;
+use arrow_schema::SchemaRef;
+use async_stream::stream;
+use databend_common_base::runtime::drop_guard;
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::infer_table_schema;
+use databend_common_expression::DataSchema;
+use databend_common_expression::TableSchemaRef;
+use futures::Stream;
+use log::info;
+use log::warn;
+
+use super::body_format::BodyFormat;
+use super::body_format::RESPONSE_HEADER_METADATA_KEY;
+use super::http_query::HttpQuery;
+use super::HttpQueryResponseInternal;
+use super::PageData;
+use super::RemoveReason;
+use crate::servers::http::v1::HttpQueryManager;
+use crate::servers::http::v1::QueryResponse;
+
+/// Stream the results of a http query in one chunked response, instead of the pages polled
+/// by `next_uri`.
+///
+/// A page is pulled only after the previous chunk is taken by the connection, and the
+/// pipeline is blocked when the block buffer of the query is full, so a slow client applies
+/// backpressure to the query. The query is still registered in the `HttpQueryManager`, it
+/// can be killed by the `kill_uri`, and it is killed if the client closes the connection.
+///
+/// - NDJSON: the first line is the response without data, then one JSON array for each row,
+///   and the last line is the final response with the state, stats and error.
+/// - Arrow: an Arrow IPC stream, the response without data is in the schema metadata. If
+///   the query fails, the stream is aborted without the end-of-stream marker, and the error
+///   can be got by the `final_uri`.
+pub(crate) fn result_stream(
+    query: Arc<HttpQuery>,
+    client_session_id: Option<String>,
+    format: BodyFormat,
+    first: HttpQueryResponseInternal,
+) -> impl Stream<Item = Result<Vec<u8>>> {
+    stream! {
+        let mut guard = StreamGuard {
+            query: query.clone(),
+            client_session_id,
+            finished: false,
+        };
+        let mut encoder = ResultEncoder::new(format);
+
+        let mut next_page_no = first.data.as_ref().and_then(|d| d.next_page_no);
+        let (mut header, data) = QueryResponse::split_internal(query.id.clone(), first, false);
+        header.next_uri = None;
+        let schema = match &data {
+            Some(PageData::Blocks { schema, .. }) => schema.clone(),
+            _ => Arc::new(DataSchema::empty()),
+        };
+        yield encoder.header(&header, &schema);
+        if let Some(data) = data {
+            match encoder.page(data) {
+                Ok(bytes) if bytes.is_empty() => {}
+                res => yield res,
+            }
+        }
+
+        while let Some(page_no) = next_page_no {
+            if query.check_removed().is_some() {
+                break;
+            }
+            query.update_expire_time(true).await;
+            let resp = match query.get_response_page(page_no).await {
+                Ok(resp) => resp,
+                Err(err) => {
+                    yield Err(err);
+                    return;
+                }
+            };
+            next_page_no = resp.data.as_ref().and_then(|d| d.next_page_no);
+            if let Some(d) = resp.data {
+                match encoder.page(d.page.data) {
+                    Ok(bytes) if bytes.is_empty() => {}
+                    res => yield res,
+                }
+            }
+        }
+
+        let last = match query.get_response_final().await {
+            Ok(last) => last,
+            Err(err) => {
+                yield Err(err);
+                return;
+            }
+        };
+        let (footer, _) = QueryResponse::split_internal(query.id.clone(), last, true);
+        info!(
+            "{}: http query finished streaming results, state={:?}",
+            query.id, footer.state
+        );
+        match encoder.footer(&footer) {
+            Some(bytes) => {
+                guard.finish(true).await;
+                yield bytes;
+            }
+            None => {
+                // keep the query, so that the error can be got by the `final_uri`.
+                guard.finish(false).await;
+                let message = footer.error.map(|e| e.message).unwrap_or_default();
+                yield Err(ErrorCode::AbortedQuery(format!(
+                    "http query {} failed: {message}",
+                    query.id
+                )));
+            }
+        }
+    }
+}
+
+struct ResultEncoder {
+    format: BodyFormat,
+    arrow: Option<ArrowEncoder>,
+}
+
+struct ArrowEncoder {
+    table_schema: TableSchemaRef,
+    schema: SchemaRef,
+    writer: StreamWriter<Vec<u8>>,
+}
+
+impl ResultEncoder {
+    fn new(format: BodyFormat) -> Self {
+        ResultEncoder {
+            format,
+            arrow: None,
+        }
+    }
+
+    fn header(&mut self, header: &QueryResponse, schema: &DataSchema) -> Result<Vec<u8>> {
+        let header = serde_json::to_string(header)?;
+        match self.format {
+            BodyFormat::Arrow => {
+                let table_schema = infer_table_schema(schema)?;
+                let metadata = HashMap::from([(RESPONSE_HEADER_METADATA_KEY.to_string(), header)]);
+                let schema = Arc::new(Schema::from(table_schema.as_ref()).with_metadata(metadata));
+                let mut writer = StreamWriter::try_new(Vec::new(), &schema)?;
+                let bytes = std::mem::take(writer.get_mut());
+                self.arrow = Some(ArrowEncoder {
+                    table_schema,
+                    schema,
+                    writer,
+                });
+                Ok(bytes)
+            }
+            _ => Ok(json_line(header)),
+        }
+    }
+
+    fn page(&mut self, data: PageData) -> Result<Vec<u8>> {
+        match data {
+            PageData::Strings(block) => {
+                let mut bytes = vec![];
+                for row in block.data {
+                    serde_json::to_writer(&mut bytes, &row)?;
+                    bytes.push(b'\n');
+                }
+                Ok(bytes)
+            }
+            PageData::Blocks { blocks, .. } => {
+                let arrow = self
+                    .arrow
+                    .as_mut()
+                    .ok_or_else(|| ErrorCode::Internal("the arrow stream is not started"))?;
+                for block in blocks {
+                    let batch = block
+                        .to_record_batch(&arrow.table_schema)?
+                        .with_schema(arrow.schema.clone())?;
+                    arrow.writer.write(&batch)?;
+                }
+                Ok(std::mem::take(arrow.writer.get_mut()))
+            }
+        }
+    }
+
+    /// Returns None if the query failed and the stream should be aborted.
+    fn footer(&mut self, footer: &QueryResponse) -> Option<Result<Vec<u8>>> {
+        match self.format {
+            BodyFormat::Arrow => {
+                if footer.error.is_some() {
+                    return None;
+                }
+                let arrow = self.arrow.as_mut()?;
+                let res = arrow
+                    .writer
+                    .finish()
+                    .map(|_| std::mem::take(arrow.writer.get_mut()))
+                    .map_err(ErrorCode::from);
+                Some(res)
+            }
+            _ => Some(
+                serde_json::to_string(footer)
+                    .map(json_line)
+                    .map_err(ErrorCode::from),
+            ),
+        }
+    }
+}
+
+fn json_line(json: String) -> Vec<u8> {
+    let mut line = json.into_bytes();
+    line.push(b'\n');
+    line
+}
+
+/// Remove the query when the stream is finished, or kill it if the client goes away.
+struct StreamGuard {
+    query: Arc<HttpQuery>,
+    client_session_id: Option<String>,
+    finished: bool,
+}
+
+impl StreamGuard {
+    async fn finish(&mut self, remove: bool) {
+        self.finished = true;
+        if remove {
+            let _ = HttpQueryManager::instance()
+                .remove_query(
+                    &self.query.id,
+                    &self.client_session_id,
+                    RemoveReason::Finished,
+                    ErrorCode::ClosedQuery("closed by client"),
+                )
+                .await;
+        } else {
+            self.query.update_expire_time(false).await;
+        }
+    }
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        drop_guard(move || {
+            if self.finished {
+                return;
+            }
+            let query_id = self.query.id.clone();
+            let client_session_id = self.client_session_id.clone();
+            warn!("{query_id}: http query result stream is closed by the client, kill the query");
+            GlobalIORuntime::instance().spawn(async move {
+                let _ = HttpQueryManager::instance()
+                    .remove_query(
+                        &query_id,
+                        &client_session_id,
+                        RemoveReason::Canceled,
+                        ErrorCode::AbortedQuery("result stream closed by client"),
+                    )
+                    .await;
+            });
+        })
+    }
+}
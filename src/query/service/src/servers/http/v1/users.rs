@@ -97,8 +97,11 @@ async fn create_user(ctx: &HttpQueryContext, req: CreateUserRequest) -> Result<(
         ));
     }
     let user_api = UserApiProvider::instance();
-    let auth_info = AuthInfo::create(&req.auth_type, &req.auth_string)
+    let mut auth_info = AuthInfo::create(&req.auth_type, &req.auth_string)
         .map_err(|e| ErrorCode::InvalidArgument(format!("invalid auth info: {}", e)))?;
+    if let Some(password) = &req.auth_string {
+        auth_info = auth_info.with_scram_verifier(password);
+    }
     let mut user_info = UserInfo::new(
         &req.name,
         &req.hostname.unwrap_or("%".to_string()),
@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
+use databend_common_ast::ast::Expr;
+use databend_common_ast::ast::Statement;
 use databend_common_base::base::convert_byte_size;
 use databend_common_base::base::convert_number_size;
 use databend_common_base::base::tokio::io::AsyncWrite;
@@ -49,8 +52,11 @@ use rand::RngCore;
 use uuid::Uuid;
 
 use crate::interpreters::interpreter_plan_sql;
+use crate::interpreters::interpreter_plan_stmt;
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterFactory;
+use crate::servers::mysql::mysql_prepared_statement::PreparedStatement;
+use crate::servers::mysql::writers::convert_schema;
 use crate::servers::mysql::writers::DFInitResultWriter;
 use crate::servers::mysql::writers::DFQueryResultWriter;
 use crate::servers::mysql::writers::ProgressReporter;
@@ -60,10 +66,13 @@ use crate::servers::mysql::MYSQL_VERSION;
 use crate::sessions::QueryContext;
 use crate::sessions::Session;
 use crate::sessions::TableContext;
+use crate::sql::Planner;
 use crate::stream::DataBlockStream;
 
 struct InteractiveWorkerBase {
     session: Arc<Session>,
+    statements: HashMap<u32, PreparedStatement>,
+    next_statement_id: u32,
 }
 
 pub struct InteractiveWorker {
@@ -175,7 +184,32 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
             ));
         }
 
-        self.base.do_execute(id, param, writer).await
+        let (query, prepared) = match self.base.statements.get(&id) {
+            None => {
+                let message = format!(
+                    "Unknown prepared statement handler ({id}) given to mysqld_stmt_execute"
+                );
+                writer
+                    .error(ErrorKind::ER_UNKNOWN_STMT_HANDLER, message.as_bytes())
+                    .await?;
+                return Ok(());
+            }
+            Some(statement) => match statement.bind(param) {
+                Ok(params) => (
+                    statement.query().to_string(),
+                    statement.statement().map(|stmt| (stmt.clone(), params)),
+                ),
+                Err(error) => {
+                    writer
+                        .error(ErrorKind::ER_WRONG_ARGUMENTS, error.message().as_bytes())
+                        .await?;
+                    return Ok(());
+                }
+            },
+        };
+
+        // The rows of the prepared statements are sent in the binary protocol.
+        self.run_query(&query, prepared, writer, true).await
     }
 
     /// https://dev.mysql.com/doc/internals/en/com-stmt-close.html
@@ -191,56 +225,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
         query: &'a str,
         writer: QueryResultWriter<'a, W>,
     ) -> Result<()> {
-        let query_id = Uuid::new_v4().to_string();
-        let root = Span::root(func_path!(), SpanContext::random())
-            .with_properties(|| self.base.session.to_fastrace_properties());
-
-        let mut tracking_payload = ThreadTracker::new_tracking_payload();
-        tracking_payload.query_id = Some(query_id.clone());
-        tracking_payload.mem_stat = Some(MemStat::create(format!("Query-{}", query_id)));
-        let _guard = ThreadTracker::tracking(tracking_payload);
-
-        ThreadTracker::tracking_future(async {
-            if self.base.session.is_aborting() {
-                writer
-                    .error(
-                        ErrorKind::ER_ABORTING_CONNECTION,
-                        "Aborting this connection. because we are try aborting server.".as_bytes(),
-                    )
-                    .await?;
-
-                return Err(ErrorCode::AbortedSession(
-                    "Aborting this connection. because we are try aborting server.",
-                ));
-            }
-
-            let mut writer = DFQueryResultWriter::create(writer, self.base.session.clone());
-            if !self.keep_alive_task_started {
-                self.start_keep_alive().await
-            }
-
-            let instant = Instant::now();
-            let query_result = self
-                .base
-                .do_query(query_id, query)
-                .await
-                .map_err(|err| err.display_with_sql(query));
-
-            let format = self.base.session.get_format_settings();
-
-            let mut write_result = writer.write(query_result, &format).await;
-
-            if let Err(cause) = write_result {
-                self.base.session.txn_mgr().lock().set_fail();
-                let suffix = format!("(while in query {})", query);
-                write_result = Err(cause.add_message_back(suffix));
-            }
-            observe_mysql_process_request_duration(instant.elapsed());
-
-            write_result
-        })
-        .in_span(root)
-        .await
+        self.run_query(query, None, writer, false).await
     }
 
     #[async_backtrace::framed]
@@ -311,36 +296,58 @@ impl InteractiveWorkerBase {
     #[async_backtrace::framed]
     async fn do_prepare<W: AsyncWrite + Unpin>(
         &mut self,
-        _: &str,
+        query: &str,
         writer: StatementMetaWriter<'_, W>,
     ) -> Result<()> {
-        writer
-            .error(
-                ErrorKind::ER_UNKNOWN_ERROR,
-                "Prepare is not support in Databend.".as_bytes(),
-            )
-            .await?;
+        let mut statement = match self.prepare_statement(query).await {
+            Ok(statement) => statement,
+            Err(error) => {
+                let error = error.display_with_sql(query);
+                writer
+                    .error(ErrorKind::ER_UNKNOWN_ERROR, error.message().as_bytes())
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        let id = self.next_statement_id;
+        self.next_statement_id = self.next_statement_id.wrapping_add(1).max(1);
+        let params = statement.param_columns();
+        let columns = std::mem::take(&mut statement.columns);
+        self.statements.insert(id, statement);
+        writer.reply(id, &params, &columns).await?;
         Ok(())
     }
 
+    // The statement is parsed once here and bound to the parameters in the executions, the
+    // one without parameters is also planned to reply the columns of the result set.
     #[async_backtrace::framed]
-    async fn do_execute<W: AsyncWrite + Unpin>(
-        &mut self,
-        _: u32,
-        _: ParamParser<'_>,
-        writer: QueryResultWriter<'_, W>,
-    ) -> Result<()> {
-        writer
-            .error(
-                ErrorKind::ER_UNKNOWN_ERROR,
-                "Execute is not support in Databend.".as_bytes(),
-            )
-            .await?;
-        Ok(())
+    async fn prepare_statement(&self, query: &str) -> Result<PreparedStatement> {
+        if let Some((schema, data_block)) = self.federated_server_command_check(query) {
+            let mut statement = PreparedStatement::create(query, None);
+            if data_block.num_rows() > 0 {
+                statement.columns = convert_schema(&schema, true)?;
+            }
+            return Ok(statement);
+        }
+
+        let context = self.session.create_query_context().await?;
+        let mut planner = Planner::new(context);
+        let stmt = planner.parse_prepared_sql(query)?;
+        let mut statement = PreparedStatement::create(query, Some(stmt.clone()));
+        if statement.num_params() == 0 {
+            let plan = planner.plan_stmt(&stmt).await?;
+            if plan.has_result_set() {
+                statement.columns = convert_schema(&plan.schema(), true)?;
+            }
+        }
+        Ok(statement)
     }
 
     #[async_backtrace::framed]
-    async fn do_close(&mut self, _: u32) {}
+    async fn do_close(&mut self, id: u32) {
+        self.statements.remove(&id);
+    }
 
     // Check the query is a federated or driver setup command.
     // Here we fake some values for the command which Databend not supported.
@@ -364,8 +371,13 @@ impl InteractiveWorkerBase {
         &mut self,
         query_id: String,
         query: &str,
+        prepared: Option<(Statement, Vec<Expr>)>,
     ) -> Result<(QueryResult, Option<FormatSettings>)> {
-        match self.federated_server_command_check(query) {
+        let federated = match prepared {
+            Some(_) => None,
+            None => self.federated_server_command_check(query),
+        };
+        match federated {
             Some((schema, data_block)) => {
                 info!("Federated query: {}", query);
                 if data_block.num_rows() > 0 {
@@ -389,7 +401,14 @@ impl InteractiveWorkerBase {
                 context.update_init_query_id(query_id);
 
                 // Use interpreter_plan_sql, we can write the query log if an error occurs.
-                let (plan, _, _guard) = interpreter_plan_sql(context.clone(), query, true).await?;
+                let (plan, _, _guard) = match prepared {
+                    Some((statement, params)) => {
+                        let statement =
+                            Planner::new(context.clone()).bind_placeholders(&statement, &params)?;
+                        interpreter_plan_stmt(context.clone(), query, statement, true).await?
+                    }
+                    None => interpreter_plan_sql(context.clone(), query, true).await?,
+                };
 
                 let interpreter = InterpreterFactory::get(context.clone(), &plan).await?;
                 let has_result_set = plan.has_result_set();
@@ -468,7 +487,8 @@ impl InteractiveWorkerBase {
         tracking_payload.mem_stat = Some(MemStat::create(format!("Query-{}", query_id)));
         let _guard = ThreadTracker::tracking(tracking_payload);
 
-        let do_query = ThreadTracker::tracking_future(self.do_query(query_id, &init_query)).await;
+        let do_query =
+            ThreadTracker::tracking_future(self.do_query(query_id, &init_query, None)).await;
         match do_query {
             Ok((_, _)) => Ok(()),
             Err(error_code) => Err(error_code),
@@ -491,7 +511,11 @@ impl InteractiveWorker {
         }
 
         InteractiveWorker {
-            base: InteractiveWorkerBase { session },
+            base: InteractiveWorkerBase {
+                session,
+                statements: HashMap::new(),
+                next_statement_id: 1,
+            },
             salt: scramble,
             version: format!("{}-{}", MYSQL_VERSION, *DATABEND_COMMIT_VERSION),
             client_addr,
@@ -499,6 +523,66 @@ impl InteractiveWorker {
         }
     }
 
+    #[async_backtrace::framed]
+    async fn run_query<W: AsyncWrite + Send + Unpin>(
+        &mut self,
+        query: &str,
+        prepared: Option<(Statement, Vec<Expr>)>,
+        writer: QueryResultWriter<'_, W>,
+        binary: bool,
+    ) -> Result<()> {
+        let query_id = Uuid::new_v4().to_string();
+        let root = Span::root(func_path!(), SpanContext::random())
+            .with_properties(|| self.base.session.to_fastrace_properties());
+
+        let mut tracking_payload = ThreadTracker::new_tracking_payload();
+        tracking_payload.query_id = Some(query_id.clone());
+        tracking_payload.mem_stat = Some(MemStat::create(format!("Query-{}", query_id)));
+        let _guard = ThreadTracker::tracking(tracking_payload);
+
+        ThreadTracker::tracking_future(async {
+            if self.base.session.is_aborting() {
+                writer
+                    .error(
+                        ErrorKind::ER_ABORTING_CONNECTION,
+                        "Aborting this connection. because we are try aborting server.".as_bytes(),
+                    )
+                    .await?;
+
+                return Err(ErrorCode::AbortedSession(
+                    "Aborting this connection. because we are try aborting server.",
+                ));
+            }
+
+            let mut writer = DFQueryResultWriter::create(writer, self.base.session.clone(), binary);
+            if !self.keep_alive_task_started {
+                self.start_keep_alive().await
+            }
+
+            let instant = Instant::now();
+            let query_result = self
+                .base
+                .do_query(query_id, query, prepared)
+                .await
+                .map_err(|err| err.display_with_sql(query));
+
+            let format = self.base.session.get_format_settings();
+
+            let mut write_result = writer.write(query_result, &format).await;
+
+            if let Err(cause) = write_result {
+                self.base.session.txn_mgr().lock().set_fail();
+                let suffix = format!("(while in query {})", query);
+                write_result = Err(cause.add_message_back(suffix));
+            }
+            observe_mysql_process_request_duration(instant.elapsed());
+
+            write_result
+        })
+        .in_span(root)
+        .await
+    }
+
     async fn start_keep_alive(&mut self) {
         let session = &self.base.session;
         let tenant = session.get_current_tenant();
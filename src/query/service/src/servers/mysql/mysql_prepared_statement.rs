@@ -0,0 +1,276 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_ast::ast::Expr;
+use databend_common_ast::ast::FunctionCall;
+use databend_common_ast::ast::Identifier;
+use databend_common_ast::ast::Literal;
+use databend_common_ast::ast::Statement;
+use databend_common_ast::ast::TypeName;
+use databend_common_ast::ast::UnaryOperator;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_sql::PlaceholderCollector;
+use opensrv_mysql::Column;
+use opensrv_mysql::ColumnFlags;
+use opensrv_mysql::ColumnType;
+use opensrv_mysql::ParamParser;
+use opensrv_mysql::ValueInner;
+
+/// A statement prepared by `COM_STMT_PREPARE`, which is parsed once, and its `?` placeholders
+/// are replaced by the typed parameters on `COM_STMT_EXECUTE`.
+pub struct PreparedStatement {
+    query: String,
+    /// The parsed statement, which is none for the federated commands answered without
+    /// planning.
+    statement: Option<Statement>,
+    num_params: usize,
+    /// The columns of the result set, which are empty if the statement has no result set,
+    /// or it has parameters so the result set is unknown before executing.
+    pub columns: Vec<Column>,
+}
+
+impl PreparedStatement {
+    pub fn create(query: &str, statement: Option<Statement>) -> PreparedStatement {
+        let num_params = statement
+            .as_ref()
+            .map_or(0, |stmt| PlaceholderCollector::collect(stmt).num_params());
+        PreparedStatement {
+            query: query.to_string(),
+            statement,
+            num_params,
+            columns: vec![],
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn statement(&self) -> Option<&Statement> {
+        self.statement.as_ref()
+    }
+
+    pub fn num_params(&self) -> usize {
+        self.num_params
+    }
+
+    /// The columns of the parameters, the clients send the types of the parameters when
+    /// executing, so the types here are only hints.
+    pub fn param_columns(&self) -> Vec<Column> {
+        (0..self.num_params())
+            .map(|_| Column {
+                table: "".to_string(),
+                column: "?".to_string(),
+                coltype: ColumnType::MYSQL_TYPE_VAR_STRING,
+                colflags: ColumnFlags::empty(),
+            })
+            .collect()
+    }
+
+    /// Convert the parameters to the expressions bound to the placeholders.
+    pub fn bind(&self, params: ParamParser<'_>) -> Result<Vec<Expr>> {
+        let params = params
+            .into_iter()
+            .map(|param| value_to_expr(param.value.into_inner()))
+            .collect::<Result<Vec<_>>>()?;
+        if params.len() != self.num_params() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Incorrect arguments to mysqld_stmt_execute, expect {} parameters, got {}",
+                self.num_params(),
+                params.len()
+            )));
+        }
+        Ok(params)
+    }
+}
+
+/// Convert the parameter value in the binary protocol to a constant expression of its type.
+pub fn value_to_expr(value: ValueInner<'_>) -> Result<Expr> {
+    Ok(match value {
+        ValueInner::NULL => literal(Literal::Null),
+        ValueInner::Int(v) if v < 0 => Expr::UnaryOp {
+            span: None,
+            op: UnaryOperator::Minus,
+            expr: Box::new(literal(Literal::UInt64(v.unsigned_abs()))),
+        },
+        ValueInner::Int(v) => literal(Literal::UInt64(v as u64)),
+        ValueInner::UInt(v) => literal(Literal::UInt64(v)),
+        ValueInner::Double(v) => literal(Literal::Float64(v)),
+        ValueInner::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(s) => literal(Literal::String(s.to_string())),
+            Err(_) => Expr::FunctionCall {
+                span: None,
+                func: FunctionCall {
+                    distinct: false,
+                    name: Identifier::from_name(None, "from_hex"),
+                    args: vec![literal(Literal::String(hex::encode(bytes)))],
+                    params: vec![],
+                    order_by: vec![],
+                    window: None,
+                    lambda: None,
+                },
+            },
+        },
+        ValueInner::Date(bytes) => {
+            let (date, _) = decode_datetime(bytes)?;
+            cast(date, TypeName::Date)
+        }
+        ValueInner::Datetime(bytes) => {
+            let (date, time) = decode_datetime(bytes)?;
+            cast(format!("{date} {time}"), TypeName::Timestamp)
+        }
+        ValueInner::Time(bytes) => literal(Literal::String(decode_time(bytes)?)),
+    })
+}
+
+fn literal(value: Literal) -> Expr {
+    Expr::Literal { span: None, value }
+}
+
+fn cast(value: String, target_type: TypeName) -> Expr {
+    Expr::Cast {
+        span: None,
+        expr: Box::new(literal(Literal::String(value))),
+        target_type,
+        pg_style: false,
+    }
+}
+
+// The binary date and datetime: year(2) month(1) day(1) [hour(1) minute(1) second(1)
+// [microsecond(4)]], the length is 0, 4, 7 or 11.
+fn decode_datetime(bytes: &[u8]) -> Result<(String, String)> {
+    if !matches!(bytes.len(), 0 | 4 | 7 | 11) {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid length {} of the binary datetime parameter",
+            bytes.len()
+        )));
+    }
+    let mut padded = [0u8; 11];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    let year = u16::from_le_bytes([padded[0], padded[1]]);
+    let micros = u32::from_le_bytes([padded[7], padded[8], padded[9], padded[10]]);
+    Ok((
+        format!("{:04}-{:02}-{:02}", year, padded[2], padded[3]),
+        format!(
+            "{:02}:{:02}:{:02}.{:06}",
+            padded[4], padded[5], padded[6], micros
+        ),
+    ))
+}
+
+// The binary time: is_negative(1) days(4) hour(1) minute(1) second(1) [microsecond(4)],
+// the length is 0, 8 or 12.
+fn decode_time(bytes: &[u8]) -> Result<String> {
+    if !matches!(bytes.len(), 0 | 8 | 12) {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid length {} of the binary time parameter",
+            bytes.len()
+        )));
+    }
+    let mut padded = [0u8; 12];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    let sign = if padded[0] == 1 { "-" } else { "" };
+    let days = u32::from_le_bytes([padded[1], padded[2], padded[3], padded[4]]);
+    let hours = days * 24 + padded[5] as u32;
+    let micros = u32::from_le_bytes([padded[8], padded[9], padded[10], padded[11]]);
+    Ok(format!(
+        "{sign}{:02}:{:02}:{:02}.{:06}",
+        hours, padded[6], padded[7], micros
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_ast::parser::parse_sql;
+    use databend_common_ast::parser::tokenize_sql;
+    use databend_common_ast::parser::Dialect;
+    use databend_common_sql::PlaceholderRewriter;
+
+    use super::*;
+
+    fn prepare(query: &str) -> Result<PreparedStatement> {
+        let (stmt, _) = parse_sql(&tokenize_sql(query)?, Dialect::MySQL)?;
+        Ok(PreparedStatement::create(query, Some(stmt)))
+    }
+
+    fn bind(stmt: &PreparedStatement, values: Vec<ValueInner<'_>>) -> Result<String> {
+        let params = values
+            .into_iter()
+            .map(value_to_expr)
+            .collect::<Result<Vec<_>>>()?;
+        let mut stmt = stmt.statement().unwrap().clone();
+        PlaceholderRewriter::rewrite(&mut stmt, &params)?;
+        Ok(stmt.to_string())
+    }
+
+    #[test]
+    fn test_placeholders() -> Result<()> {
+        let stmt = prepare("SELECT ?, '?', \"?\" FROM t WHERE a = ? -- ?")?;
+        assert_eq!(stmt.num_params(), 2);
+        assert_eq!(stmt.param_columns().len(), 2);
+        assert_eq!(
+            PreparedStatement::create("SELECT @@version", None).num_params(),
+            0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_bind() -> Result<()> {
+        // The parameters are bound as expressions, a negative number is never a comment.
+        let stmt = prepare("SELECT 1-?")?;
+        assert_eq!(bind(&stmt, vec![ValueInner::Int(-5)])?, "SELECT 1 - - 5");
+
+        let stmt = prepare("SELECT * FROM t WHERE a = ? AND b = ?")?;
+        assert_eq!(
+            bind(&stmt, vec![
+                ValueInner::Bytes(b"x' OR '1' = '1"),
+                ValueInner::NULL
+            ])?,
+            "SELECT * FROM t WHERE a = 'x\\' OR \\'1\\' = \\'1' AND b = NULL"
+        );
+        assert!(bind(&stmt, vec![ValueInner::NULL]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_to_expr() -> Result<()> {
+        let display = |value| value_to_expr(value).map(|expr| expr.to_string());
+        assert_eq!(display(ValueInner::NULL)?, "NULL");
+        assert_eq!(display(ValueInner::Int(-1))?, "- 1");
+        assert_eq!(display(ValueInner::UInt(u64::MAX))?, "18446744073709551615");
+        assert_eq!(display(ValueInner::Double(1.5))?, "1.5");
+        assert_eq!(display(ValueInner::Double(f64::NAN))?, "'NaN'::FLOAT64");
+        assert_eq!(display(ValueInner::Bytes(b"it's"))?, "'it\\'s'");
+        assert_eq!(
+            display(ValueInner::Bytes(&[0xff, 0x00]))?,
+            "from_hex('ff00')"
+        );
+        assert_eq!(
+            display(ValueInner::Date(&[0xe8, 0x07, 1, 2]))?,
+            "CAST('2024-01-02' AS DATE)"
+        );
+        assert_eq!(
+            display(ValueInner::Datetime(&[0xe8, 0x07, 1, 2, 3, 4, 5]))?,
+            "CAST('2024-01-02 03:04:05.000000' AS TIMESTAMP)"
+        );
+        assert_eq!(
+            display(ValueInner::Time(&[1, 1, 0, 0, 0, 2, 3, 4]))?,
+            "'-26:03:04.000000'"
+        );
+        assert!(display(ValueInner::Date(&[1, 2, 3])).is_err());
+        Ok(())
+    }
+}
@@ -14,12 +14,15 @@
 
 use std::sync::Arc;
 
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
 use databend_common_base::base::tokio::io::AsyncWrite;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::types::number::NumberScalar;
 use databend_common_expression::types::DataType;
 use databend_common_expression::types::NumberDataType;
+use databend_common_expression::utils::serialize::EPOCH_DAYS_FROM_CE;
 use databend_common_expression::Column as ExprColumn;
 use databend_common_expression::DataField;
 use databend_common_expression::DataSchemaRef;
@@ -28,6 +31,8 @@ use databend_common_expression::SendableDataBlockStream;
 use databend_common_formats::field_encoder::FieldEncoderValues;
 use databend_common_io::prelude::FormatSettings;
 use futures_util::StreamExt;
+use jiff::tz::TimeZone;
+use jiff::Timestamp;
 use log::error;
 use opensrv_mysql::*;
 
@@ -71,6 +76,8 @@ impl QueryResult {
 pub struct DFQueryResultWriter<'a, W: AsyncWrite + Send + Unpin> {
     inner: Option<QueryResultWriter<'a, W>>,
     session: Arc<Session>,
+    /// Write the rows in the binary protocol, which is used by the prepared statements.
+    binary: bool,
 }
 
 fn write_field<W: AsyncWrite + Unpin>(
@@ -86,14 +93,98 @@ fn write_field<W: AsyncWrite + Unpin>(
     Ok(())
 }
 
+fn convert_field_type(field: &DataField) -> Result<ColumnType> {
+    match field.data_type().remove_nullable() {
+        DataType::Null => Ok(ColumnType::MYSQL_TYPE_NULL),
+        DataType::EmptyArray => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::EmptyMap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Boolean => Ok(ColumnType::MYSQL_TYPE_SHORT),
+        DataType::Binary => Ok(ColumnType::MYSQL_TYPE_BLOB),
+        DataType::String => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Number(num_ty) => match num_ty {
+            NumberDataType::Int8 => Ok(ColumnType::MYSQL_TYPE_TINY),
+            NumberDataType::Int16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
+            NumberDataType::Int32 => Ok(ColumnType::MYSQL_TYPE_LONG),
+            NumberDataType::Int64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
+            NumberDataType::UInt8 => Ok(ColumnType::MYSQL_TYPE_TINY),
+            NumberDataType::UInt16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
+            NumberDataType::UInt32 => Ok(ColumnType::MYSQL_TYPE_LONG),
+            NumberDataType::UInt64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
+            NumberDataType::Float32 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+            NumberDataType::Float64 => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
+        },
+        DataType::Date => Ok(ColumnType::MYSQL_TYPE_DATE),
+        DataType::Timestamp => Ok(ColumnType::MYSQL_TYPE_DATETIME),
+        DataType::Array(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Map(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Bitmap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Tuple(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Variant => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Geometry => Ok(ColumnType::MYSQL_TYPE_GEOMETRY),
+        DataType::Geography => Ok(ColumnType::MYSQL_TYPE_GEOMETRY),
+        DataType::Decimal(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
+        _ => Err(ErrorCode::Unimplemented(format!(
+            "Unsupported column type:{:?}",
+            field.data_type()
+        ))),
+    }
+}
+
+fn make_column_from_field(field: &DataField, binary: bool) -> Result<Column> {
+    // The unsigned values are encoded by the unsigned flag in the binary protocol.
+    let colflags = match field.data_type().remove_nullable() {
+        DataType::Number(num_ty) if binary && !num_ty.is_signed() => ColumnFlags::UNSIGNED_FLAG,
+        _ => ColumnFlags::empty(),
+    };
+    convert_field_type(field).map(|column_type| Column {
+        table: "".to_string(),
+        column: field.name().to_string(),
+        coltype: column_type,
+        colflags,
+    })
+}
+
+pub fn convert_schema(schema: &DataSchemaRef, binary: bool) -> Result<Vec<Column>> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| make_column_from_field(field, binary))
+        .collect()
+}
+
+// The date and timestamp in the binary protocol are encoded from the chrono types.
+fn date_to_naive(days: i32) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(days.checked_add(EPOCH_DAYS_FROM_CE)?)
+}
+
+fn timestamp_to_naive(micros: i64, tz: &TimeZone) -> Option<NaiveDateTime> {
+    let datetime = Timestamp::from_microsecond(micros)
+        .ok()?
+        .to_zoned(tz.clone())
+        .datetime();
+    NaiveDate::from_ymd_opt(
+        datetime.year() as i32,
+        datetime.month() as u32,
+        datetime.day() as u32,
+    )?
+    .and_hms_nano_opt(
+        datetime.hour() as u32,
+        datetime.minute() as u32,
+        datetime.second() as u32,
+        datetime.subsec_nanosecond() as u32,
+    )
+}
+
 impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
     pub fn create(
         inner: QueryResultWriter<'a, W>,
         session: Arc<Session>,
+        binary: bool,
     ) -> DFQueryResultWriter<'a, W> {
         DFQueryResultWriter::<'a, W> {
             inner: Some(inner),
             session,
+            binary,
         }
     }
 
@@ -161,58 +252,8 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
             return Ok(());
         }
 
-        fn convert_field_type(field: &DataField) -> Result<ColumnType> {
-            match field.data_type().remove_nullable() {
-                DataType::Null => Ok(ColumnType::MYSQL_TYPE_NULL),
-                DataType::EmptyArray => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::EmptyMap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Boolean => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                DataType::Binary => Ok(ColumnType::MYSQL_TYPE_BLOB),
-                DataType::String => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Number(num_ty) => match num_ty {
-                    NumberDataType::Int8 => Ok(ColumnType::MYSQL_TYPE_TINY),
-                    NumberDataType::Int16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                    NumberDataType::Int32 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                    NumberDataType::Int64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
-                    NumberDataType::UInt8 => Ok(ColumnType::MYSQL_TYPE_TINY),
-                    NumberDataType::UInt16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                    NumberDataType::UInt32 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                    NumberDataType::UInt64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
-                    NumberDataType::Float32 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
-                    NumberDataType::Float64 => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
-                },
-                DataType::Date => Ok(ColumnType::MYSQL_TYPE_DATE),
-                DataType::Timestamp => Ok(ColumnType::MYSQL_TYPE_DATETIME),
-                DataType::Array(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Map(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Bitmap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Tuple(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Variant => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Geometry => Ok(ColumnType::MYSQL_TYPE_GEOMETRY),
-                DataType::Geography => Ok(ColumnType::MYSQL_TYPE_GEOMETRY),
-                DataType::Decimal(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
-                _ => Err(ErrorCode::Unimplemented(format!(
-                    "Unsupported column type:{:?}",
-                    field.data_type()
-                ))),
-            }
-        }
-
-        fn make_column_from_field(field: &DataField) -> Result<Column> {
-            convert_field_type(field).map(|column_type| Column {
-                table: "".to_string(),
-                column: field.name().to_string(),
-                coltype: column_type,
-                colflags: ColumnFlags::empty(),
-            })
-        }
-
-        fn convert_schema(schema: &DataSchemaRef) -> Result<Vec<Column>> {
-            schema.fields().iter().map(make_column_from_field).collect()
-        }
-
         let _tz = format.timezone;
-        match convert_schema(&query_result.schema) {
+        match convert_schema(&query_result.schema, self.binary) {
             Err(error) => self.err(&error, dataset_writer).await,
             Ok(columns) => {
                 let mut row_writer = dataset_writer.start(&columns).await?;
@@ -259,6 +300,34 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                                 ScalarRef::Boolean(v) => {
                                     row_writer.write_col(v as u8)?;
                                 }
+                                ScalarRef::Number(NumberScalar::Float32(v)) if self.binary => {
+                                    row_writer.write_col(v.0)?;
+                                }
+                                ScalarRef::Number(NumberScalar::Float64(v)) if self.binary => {
+                                    row_writer.write_col(v.0)?;
+                                }
+                                ScalarRef::Date(v) if self.binary => match date_to_naive(v) {
+                                    Some(date) => row_writer.write_col(date)?,
+                                    None => write_field(
+                                        &mut row_writer,
+                                        column,
+                                        &encoder,
+                                        &mut buf,
+                                        row_index,
+                                    )?,
+                                },
+                                ScalarRef::Timestamp(v) if self.binary => {
+                                    match timestamp_to_naive(v, &format.jiff_timezone) {
+                                        Some(datetime) => row_writer.write_col(datetime)?,
+                                        None => write_field(
+                                            &mut row_writer,
+                                            column,
+                                            &encoder,
+                                            &mut buf,
+                                            row_index,
+                                        )?,
+                                    }
+                                }
                                 ScalarRef::Number(number) => match number {
                                     NumberScalar::UInt8(v) => {
                                         row_writer.write_col(v)?;
@@ -0,0 +1,224 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use databend_common_config::DATABEND_COMMIT_VERSION;
+use databend_common_expression::types::StringType;
+use databend_common_expression::utils::FromData;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchema;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchemaRef;
+use databend_common_expression::TableSchemaRefExt;
+use regex::Regex;
+
+use crate::servers::federated_helper::FederatedHelper;
+use crate::servers::federated_helper::LazyBlockFunc;
+use crate::servers::postgres::PG_SERVER_VERSION;
+
+pub struct PostgresFederated {}
+
+impl PostgresFederated {
+    pub fn create() -> Self {
+        PostgresFederated {}
+    }
+
+    // Build block for select function or show variable.
+    // Format:
+    // |name|
+    // |value|
+    fn single_value_block(name: &str, value: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        let schema = TableSchemaRefExt::create(vec![TableField::new(name, TableDataType::String)]);
+        let block =
+            DataBlock::new_from_columns(vec![StringType::from_data(vec![value.to_string()])]);
+        Some((schema, block))
+    }
+
+    // Build an empty block with the string columns, for the catalog queries of the drivers.
+    fn empty_block(names: &[&str]) -> Option<(TableSchemaRef, DataBlock)> {
+        let schema = TableSchemaRefExt::create(
+            names
+                .iter()
+                .map(|name| TableField::new(name, TableDataType::String))
+                .collect(),
+        );
+        let block = DataBlock::new_from_columns(
+            names
+                .iter()
+                .map(|_| StringType::from_data(Vec::<String>::new()))
+                .collect(),
+        );
+        Some((schema, block))
+    }
+
+    // SELECT version(), the clients like sqlalchemy parse the version of postgres from it.
+    fn select_version_block(_query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        Self::single_value_block(
+            "version",
+            &format!(
+                "PostgreSQL {} (Databend {})",
+                PG_SERVER_VERSION, *DATABEND_COMMIT_VERSION
+            ),
+        )
+    }
+
+    // Check SELECT version() and SELECT pg_catalog.version().
+    fn federated_select_version_check(&self, query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        static SELECT_VERSION_LAZY_RULES: LazyLock<Vec<(Regex, LazyBlockFunc)>> =
+            LazyLock::new(|| {
+                vec![(
+                    Regex::new("(?i)^(SELECT (pg_catalog\\.)?version\\(\\)\\s*;?\\s*)$").unwrap(),
+                    PostgresFederated::select_version_block,
+                )]
+            });
+
+        FederatedHelper::lazy_block_match_rule(query, &SELECT_VERSION_LAZY_RULES)
+    }
+
+    // Check for SET, SHOW and the catalog queries which Databend not supported.
+    fn federated_mixed_check(&self, query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        #![allow(clippy::type_complexity)]
+        static MIXED_RULES: LazyLock<Vec<(Regex, Option<(TableSchemaRef, DataBlock)>)>> =
+            LazyLock::new(|| {
+                vec![
+                    // JDBC, psycopg and sqlalchemy.
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?extra_float_digits(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?application_name(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?client_encoding(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?datestyle(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?intervalstyle(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?search_path(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?statement_timeout(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET (SESSION )?client_min_messages(.*))").unwrap(),
+                        None,
+                    ),
+                    (
+                        Regex::new("(?i)^(SET SESSION CHARACTERISTICS(.*))").unwrap(),
+                        None,
+                    ),
+                    (Regex::new("(?i)^(DISCARD ALL)").unwrap(), None),
+                    (
+                        Regex::new("(?i)^(SHOW TRANSACTION ISOLATION LEVEL)").unwrap(),
+                        PostgresFederated::single_value_block(
+                            "transaction_isolation",
+                            "read committed",
+                        ),
+                    ),
+                    (
+                        Regex::new("(?i)^(SHOW standard_conforming_strings)").unwrap(),
+                        PostgresFederated::single_value_block("standard_conforming_strings", "on"),
+                    ),
+                    (
+                        Regex::new("(?i)^(SELECT (pg_catalog\\.)?current_schema\\(\\)\\s*;?\\s*)$")
+                            .unwrap(),
+                        PostgresFederated::single_value_block("current_schema", "public"),
+                    ),
+                    // JDBC type lookup.
+                    (
+                        Regex::new("(?i)^(SELECT(.*)FROM pg_catalog\\.pg_type(.*))").unwrap(),
+                        PostgresFederated::empty_block(&["oid", "typname"]),
+                    ),
+                ]
+            });
+
+        FederatedHelper::block_match_rule(query, &MIXED_RULES)
+    }
+
+    // Check the query is a federated or driver setup command.
+    // Here we fake some values for the command which Databend not supported.
+    pub fn check(&self, query: &str) -> Option<(DataSchemaRef, DataBlock)> {
+        let select_version = self
+            .federated_select_version_check(query)
+            .map(|(schema, chunk)| (Arc::new(DataSchema::from(schema)), chunk));
+        if select_version.is_some() {
+            return select_version;
+        }
+
+        self.federated_mixed_check(query)
+            .map(|(schema, chunk)| (Arc::new(DataSchema::from(schema)), chunk))
+    }
+
+    // Rewrite the introspection queries on pg_catalog of psql and Grafana to the queries on
+    // the system tables.
+    pub fn rewrite(&self, query: &str) -> Option<String> {
+        static REWRITE_RULES: LazyLock<Vec<(Regex, &str)>> = LazyLock::new(|| {
+            vec![
+                (
+                    // psql \l
+                    Regex::new("(?is)^SELECT\\s+d\\.datname as \"Name\"(.*)FROM pg_catalog\\.pg_database d").unwrap(),
+                    "SELECT name AS \"Name\", 'databend' AS \"Owner\", 'UTF8' AS \"Encoding\" FROM system.databases ORDER BY 1",
+                ),
+                (
+                    // psql \dn
+                    Regex::new("(?is)^SELECT\\s+n\\.nspname AS \"Name\"(.*)FROM pg_catalog\\.pg_namespace n").unwrap(),
+                    "SELECT name AS \"Name\", 'databend' AS \"Owner\" FROM system.databases ORDER BY 1",
+                ),
+                (
+                    // psql \d and \dt
+                    Regex::new("(?is)^SELECT\\s+n\\.nspname as \"Schema\",\\s*c\\.relname as \"Name\"(.*)FROM pg_catalog\\.pg_class c").unwrap(),
+                    "SELECT database AS \"Schema\", name AS \"Name\", CASE table_type WHEN 'VIEW' THEN 'view' ELSE 'table' END AS \"Type\", owner AS \"Owner\" FROM system.tables WHERE database = database() ORDER BY 1, 2",
+                ),
+                (
+                    // Grafana table list.
+                    Regex::new("(?is)^select\\s+quote_ident\\(table_name\\) as \"table\"\\s+from information_schema\\.tables").unwrap(),
+                    "SELECT table_name AS \"table\" FROM information_schema.tables WHERE table_schema = database() ORDER BY 1",
+                ),
+            ]
+        });
+
+        // Grafana column list of a table.
+        static COLUMNS_RULE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new("(?is)^select\\s+quote_ident\\(column_name\\) as \"column\",\\s*data_type as \"type\"\\s+from information_schema\\.columns\\s+where\\s+quote_ident\\(table_name\\)\\s*=\\s*'([^'\\\\]*)'").unwrap()
+        });
+
+        for (regex, sql) in REWRITE_RULES.iter() {
+            if regex.is_match(query) {
+                return Some(sql.to_string());
+            }
+        }
+
+        COLUMNS_RULE.captures(query).map(|captures| {
+            format!(
+                "SELECT column_name AS \"column\", data_type AS \"type\" FROM information_schema.columns WHERE table_schema = database() AND table_name = '{}' ORDER BY 1",
+                &captures[1]
+            )
+        })
+    }
+}
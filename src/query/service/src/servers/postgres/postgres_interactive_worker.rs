@@ -0,0 +1,825 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use databend_common_ast::parser::token::TokenKind;
+use databend_common_ast::parser::token::Tokenizer;
+use databend_common_base::base::tokio::io::AsyncRead;
+use databend_common_base::base::tokio::io::AsyncWrite;
+use databend_common_base::runtime::MemStat;
+use databend_common_base::runtime::ThreadTracker;
+use databend_common_base::runtime::TrySpawn;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_exception::ToErrorCode;
+use databend_common_expression::Column;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::SendableDataBlockStream;
+use databend_common_io::prelude::FormatSettings;
+use databend_common_sql::plans::Plan;
+use databend_common_users::UserApiProvider;
+use futures_util::StreamExt;
+use log::error;
+use log::info;
+use uuid::Uuid;
+
+use crate::interpreters::interpreter_plan_sql;
+use crate::interpreters::InterpreterFactory;
+use crate::servers::postgres::postgres_federated::PostgresFederated;
+use crate::servers::postgres::postgres_protocol::read_message;
+use crate::servers::postgres::postgres_protocol::FrontendMessage;
+use crate::servers::postgres::postgres_protocol::PostgresWriter;
+use crate::servers::postgres::postgres_protocol::FORMAT_TEXT;
+use crate::servers::postgres::postgres_protocol::TRANSACTION_ACTIVE;
+use crate::servers::postgres::postgres_protocol::TRANSACTION_FAILED;
+use crate::servers::postgres::postgres_protocol::TRANSACTION_IDLE;
+use crate::servers::postgres::postgres_types::field_descriptions;
+use crate::servers::postgres::postgres_types::param_to_literal;
+use crate::servers::postgres::postgres_types::result_formats;
+use crate::servers::postgres::postgres_types::ValueEncoder;
+use crate::servers::postgres::postgres_types::TEXT_OID;
+use crate::servers::postgres::postgres_types::UNSPECIFIED_OID;
+use crate::sessions::AcquireQueueGuard;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::TableContext;
+use crate::stream::DataBlockStream;
+
+/// A statement created by `Parse`.
+struct PreparedStatement {
+    query: String,
+    /// The types of the parameters, `0` if the type is not specified by the client.
+    param_types: Vec<u32>,
+}
+
+/// A query planned but not executed, the planning is done on `Bind` so that the result
+/// columns of the portal can be described before `Execute`.
+enum PlannedQuery {
+    Federated {
+        schema: DataSchemaRef,
+        block: DataBlock,
+    },
+    Plan {
+        context: Arc<QueryContext>,
+        plan: Box<Plan>,
+        guard: AcquireQueueGuard,
+    },
+}
+
+impl PlannedQuery {
+    fn schema(&self) -> DataSchemaRef {
+        match self {
+            PlannedQuery::Federated { schema, .. } => schema.clone(),
+            PlannedQuery::Plan { plan, .. } => plan.schema(),
+        }
+    }
+
+    fn has_result_set(&self) -> bool {
+        match self {
+            PlannedQuery::Federated { schema, .. } => !schema.fields().is_empty(),
+            PlannedQuery::Plan { plan, .. } => plan.has_result_set(),
+        }
+    }
+}
+
+/// A query being executed, whose result set may be sent by several `Execute`.
+struct RunningQuery {
+    context: Option<Arc<QueryContext>>,
+    _guard: Option<AcquireQueueGuard>,
+    has_result_set: bool,
+    blocks: SendableDataBlockStream,
+    encoder: ValueEncoder,
+    /// The block being sent, and the index of the next row to send.
+    columns: Vec<Column>,
+    num_rows: usize,
+    row_index: usize,
+    rows_sent: usize,
+}
+
+/// A portal created by `Bind`.
+struct Portal {
+    query_id: String,
+    query: String,
+    schema: DataSchemaRef,
+    has_result_set: bool,
+    formats: Vec<i16>,
+    planned: Option<PlannedQuery>,
+    running: Option<RunningQuery>,
+    completed: bool,
+}
+
+pub struct PostgresInteractiveWorker<R, W>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+{
+    session: Arc<Session>,
+    reader: R,
+    writer: PostgresWriter<W>,
+    statements: HashMap<String, PreparedStatement>,
+    portals: HashMap<String, Portal>,
+    /// Once an error occurs in the extended query protocol, the messages are discarded
+    /// until `Sync`.
+    ignore_till_sync: bool,
+}
+
+impl<R, W> PostgresInteractiveWorker<R, W>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+{
+    pub fn create(session: Arc<Session>, reader: R, writer: PostgresWriter<W>) -> Self {
+        PostgresInteractiveWorker {
+            session,
+            reader,
+            writer,
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+            ignore_till_sync: false,
+        }
+    }
+
+    /// Run the `USE` statement for the `database` parameter of the startup message.
+    #[async_backtrace::framed]
+    pub async fn init(&mut self, database: &str) -> Result<()> {
+        let query = format!("USE \"{}\"", database.replace('"', "\"\""));
+        let query_id = Uuid::new_v4().to_string();
+        let planned = tracking(&query_id, self.plan_query(&query_id, &query, true)).await?;
+        let mut running = tracking(&query_id, self.start_query(planned)).await?;
+        while let Some(block) = running.blocks.next().await {
+            block?;
+        }
+        Ok(())
+    }
+
+    pub fn transaction_status(&self) -> u8 {
+        let txn_mgr = self.session.txn_mgr();
+        let txn_mgr = txn_mgr.lock();
+        if txn_mgr.is_active() {
+            TRANSACTION_ACTIVE
+        } else if txn_mgr.is_fail() {
+            TRANSACTION_FAILED
+        } else {
+            TRANSACTION_IDLE
+        }
+    }
+
+    pub fn writer(&mut self) -> &mut PostgresWriter<W> {
+        &mut self.writer
+    }
+
+    #[async_backtrace::framed]
+    pub async fn run(&mut self) -> Result<()> {
+        self.start_keep_alive();
+
+        while let Some(message) = read_message(&mut self.reader).await? {
+            if self.session.is_aborting() {
+                self.writer.error_response(
+                    "FATAL",
+                    "57P01",
+                    "Aborting this connection. because we are try aborting server.",
+                );
+                self.writer.flush().await?;
+                return Err(ErrorCode::AbortedSession(
+                    "Aborting this connection. because we are try aborting server.",
+                ));
+            }
+
+            if self.ignore_till_sync && message.is_extended_query() {
+                continue;
+            }
+
+            let result = match message {
+                FrontendMessage::Query(query) => {
+                    self.on_query(&query).await?;
+                    continue;
+                }
+                FrontendMessage::Parse {
+                    name,
+                    query,
+                    param_types,
+                } => self.on_parse(name, query, param_types),
+                FrontendMessage::Bind {
+                    portal,
+                    statement,
+                    param_formats,
+                    params,
+                    result_formats,
+                } => {
+                    self.on_bind(portal, statement, param_formats, params, result_formats)
+                        .await
+                }
+                FrontendMessage::Describe { kind, name } => self.on_describe(kind, &name).await,
+                FrontendMessage::Execute { portal, max_rows } => {
+                    self.on_execute(&portal, max_rows).await
+                }
+                FrontendMessage::Close { kind, name } => {
+                    match kind {
+                        b'S' => self.statements.remove(&name).map(|_| ()),
+                        _ => self.portals.remove(&name).map(|_| ()),
+                    };
+                    self.writer.close_complete();
+                    Ok(())
+                }
+                FrontendMessage::Sync => {
+                    self.ignore_till_sync = false;
+                    // Portals are closed at the end of the transaction.
+                    if self.transaction_status() == TRANSACTION_IDLE {
+                        self.portals.clear();
+                    }
+                    self.writer.ready_for_query(self.transaction_status());
+                    self.writer.flush().await?;
+                    continue;
+                }
+                FrontendMessage::Flush => {
+                    self.writer.flush().await?;
+                    continue;
+                }
+                FrontendMessage::Terminate => break,
+                // The connection is closed on unexpected messages, as the postgres server does.
+                FrontendMessage::Password(_) => return self.on_unexpected_message(b'p').await,
+                FrontendMessage::Unsupported(tag) => return self.on_unexpected_message(tag).await,
+            };
+
+            if let Err(error) = result {
+                self.write_error(&error, None);
+                self.ignore_till_sync = true;
+                self.writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    #[async_backtrace::framed]
+    async fn on_unexpected_message(&mut self, tag: u8) -> Result<()> {
+        let error = ErrorCode::Unimplemented(format!(
+            "Postgres message type '{}' is not supported",
+            tag as char
+        ));
+        self.writer
+            .error_response("FATAL", sqlstate(&error), &error.message());
+        self.writer.flush().await?;
+        Err(error)
+    }
+
+    /// The simple query protocol, the query may contain multiple statements.
+    #[async_backtrace::framed]
+    async fn on_query(&mut self, query: &str) -> Result<()> {
+        // A simple query closes the unnamed portal and statement.
+        self.portals.remove("");
+        self.statements.remove("");
+        self.ignore_till_sync = false;
+
+        let statements = split_statements(query);
+        if statements.is_empty() {
+            self.writer.empty_query_response();
+        }
+
+        for statement in statements {
+            let query_id = Uuid::new_v4().to_string();
+            let result = tracking(&query_id, self.run_simple_query(&query_id, statement)).await;
+            if let Err(error) = result {
+                self.write_error(&error, Some(statement));
+                break;
+            }
+        }
+
+        self.writer.ready_for_query(self.transaction_status());
+        self.writer.flush().await
+    }
+
+    #[async_backtrace::framed]
+    async fn run_simple_query(&mut self, query_id: &str, query: &str) -> Result<()> {
+        let planned = self.plan_query(query_id, query, true).await?;
+        let schema = planned.schema();
+        let mut running = self.start_query(planned).await?;
+
+        let formats = vec![FORMAT_TEXT; schema.num_fields()];
+        if running.has_result_set {
+            self.writer
+                .row_description(&field_descriptions(&schema, &formats));
+        }
+        self.send_rows(&mut running, &formats, 0).await?;
+        self.writer.command_complete(&command_tag(query, &running));
+        Ok(())
+    }
+
+    fn on_parse(&mut self, name: String, query: String, mut param_types: Vec<u32>) -> Result<()> {
+        if !name.is_empty() && self.statements.contains_key(&name) {
+            return Err(ErrorCode::BadArguments(format!(
+                "Prepared statement \"{name}\" already exists"
+            )));
+        }
+
+        let num_params = count_params(&query);
+        if param_types.len() < num_params {
+            param_types.resize(num_params, UNSPECIFIED_OID);
+        }
+
+        self.statements
+            .insert(name, PreparedStatement { query, param_types });
+        self.writer.parse_complete();
+        Ok(())
+    }
+
+    #[async_backtrace::framed]
+    async fn on_bind(
+        &mut self,
+        portal: String,
+        statement: String,
+        param_formats: Vec<i16>,
+        params: Vec<Option<Vec<u8>>>,
+        formats: Vec<i16>,
+    ) -> Result<()> {
+        let prepared = self.statements.get(&statement).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Prepared statement \"{statement}\" does not exist"))
+        })?;
+
+        if params.len() != prepared.param_types.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Bind message supplies {} parameters, but prepared statement \"{statement}\" requires {}",
+                params.len(),
+                prepared.param_types.len()
+            )));
+        }
+
+        let param_formats = match param_formats.len() {
+            0 => vec![FORMAT_TEXT; params.len()],
+            1 => vec![param_formats[0]; params.len()],
+            _ => param_formats,
+        };
+        let literals = params
+            .iter()
+            .zip(param_formats.iter())
+            .zip(prepared.param_types.iter())
+            .map(|((value, format), oid)| param_to_literal(*oid, *format, value.as_deref()))
+            .collect::<Result<Vec<_>>>()?;
+        let query = bind_params(&prepared.query, &literals)?;
+
+        let query_id = Uuid::new_v4().to_string();
+        let planned = tracking(&query_id, self.plan_query(&query_id, &query, true)).await?;
+        let schema = planned.schema();
+        let formats = result_formats(&formats, schema.num_fields())?;
+
+        self.portals.insert(portal, Portal {
+            query_id,
+            query,
+            has_result_set: planned.has_result_set(),
+            schema,
+            formats,
+            planned: Some(planned),
+            running: None,
+            completed: false,
+        });
+        self.writer.bind_complete();
+        Ok(())
+    }
+
+    #[async_backtrace::framed]
+    async fn on_describe(&mut self, kind: u8, name: &str) -> Result<()> {
+        if kind == b'P' {
+            let portal = self.portals.get(name).ok_or_else(|| {
+                ErrorCode::BadArguments(format!("Portal \"{name}\" does not exist"))
+            })?;
+            if portal.has_result_set {
+                self.writer
+                    .row_description(&field_descriptions(&portal.schema, &portal.formats));
+            } else {
+                self.writer.no_data();
+            }
+            return Ok(());
+        }
+
+        let prepared = self.statements.get(name).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Prepared statement \"{name}\" does not exist"))
+        })?;
+        let param_types = prepared
+            .param_types
+            .iter()
+            .map(|oid| match *oid {
+                UNSPECIFIED_OID => TEXT_OID,
+                oid => oid,
+            })
+            .collect::<Vec<_>>();
+
+        // Plan the query with null parameters to get the result columns.
+        let query = bind_params(&prepared.query, &vec![
+            "NULL".to_string();
+            param_types.len()
+        ])?;
+        let query_id = Uuid::new_v4().to_string();
+        let planned = tracking(&query_id, self.plan_query(&query_id, &query, false)).await;
+
+        self.writer.parameter_description(&param_types);
+        match planned {
+            Ok(planned) if planned.has_result_set() => {
+                let schema = planned.schema();
+                let formats = vec![FORMAT_TEXT; schema.num_fields()];
+                self.writer
+                    .row_description(&field_descriptions(&schema, &formats));
+            }
+            _ => self.writer.no_data(),
+        }
+        Ok(())
+    }
+
+    #[async_backtrace::framed]
+    async fn on_execute(&mut self, name: &str, max_rows: i32) -> Result<()> {
+        let mut portal = self
+            .portals
+            .remove(name)
+            .ok_or_else(|| ErrorCode::BadArguments(format!("Portal \"{name}\" does not exist")))?;
+
+        let query_id = portal.query_id.clone();
+        let result = tracking(&query_id, self.execute_portal(&mut portal, max_rows)).await;
+        let result = result.map_err(|error| error.display_with_sql(&portal.query));
+        if result.is_ok() {
+            self.portals.insert(name.to_string(), portal);
+        }
+        result
+    }
+
+    #[async_backtrace::framed]
+    async fn execute_portal(&mut self, portal: &mut Portal, max_rows: i32) -> Result<()> {
+        if portal.completed {
+            self.writer.command_complete(&command_tag_with_rows(
+                &portal.query,
+                portal.has_result_set,
+                0,
+            ));
+            return Ok(());
+        }
+
+        if let Some(planned) = portal.planned.take() {
+            portal.running = Some(self.start_query(planned).await?);
+        }
+
+        let running = portal.running.as_mut().unwrap();
+        let max_rows = max_rows.max(0) as usize;
+        if self.send_rows(running, &portal.formats, max_rows).await? {
+            let tag = command_tag(&portal.query, running);
+            portal.running = None;
+            portal.completed = true;
+            self.writer.command_complete(&tag);
+        } else {
+            self.writer.portal_suspended();
+        }
+        Ok(())
+    }
+
+    /// Check the query is a federated or driver setup command, otherwise plan the query.
+    #[async_backtrace::framed]
+    async fn plan_query(
+        &self,
+        query_id: &str,
+        query: &str,
+        acquire_queue: bool,
+    ) -> Result<PlannedQuery> {
+        let federated = PostgresFederated::create();
+        if let Some((schema, block)) = federated.check(query) {
+            info!("Federated query: {}", query);
+            return Ok(PlannedQuery::Federated { schema, block });
+        }
+
+        let query = match federated.rewrite(query) {
+            Some(rewritten) => {
+                info!("Rewritten query: {} to {}", query, rewritten);
+                rewritten
+            }
+            None => {
+                info!("Normal query: {}", query);
+                query.to_string()
+            }
+        };
+
+        let context = self.session.create_query_context().await?;
+        context.update_init_query_id(query_id.to_string());
+
+        // Use interpreter_plan_sql, we can write the query log if an error occurs.
+        let (plan, _, guard) = interpreter_plan_sql(context.clone(), &query, acquire_queue).await?;
+        Ok(PlannedQuery::Plan {
+            context,
+            plan: Box::new(plan),
+            guard,
+        })
+    }
+
+    #[async_backtrace::framed]
+    async fn start_query(&self, planned: PlannedQuery) -> Result<RunningQuery> {
+        let (context, guard, has_result_set, blocks, format) = match planned {
+            PlannedQuery::Federated { schema, block } => (
+                None,
+                None,
+                !schema.fields().is_empty(),
+                DataBlockStream::create(None, vec![block]).boxed(),
+                self.session.get_format_settings(),
+            ),
+            PlannedQuery::Plan {
+                context,
+                plan,
+                guard,
+            } => {
+                let interpreter = InterpreterFactory::get(context.clone(), &plan).await?;
+                let ctx = context.clone();
+                let blocks = context
+                    .try_spawn(async move { interpreter.execute(ctx).await }, None)?
+                    .await
+                    .map_err_to_code(ErrorCode::TokioError, || {
+                        "Cannot join handle from context's runtime"
+                    })??;
+                let format = context.get_format_settings()?;
+                (
+                    Some(context),
+                    Some(guard),
+                    plan.has_result_set(),
+                    blocks,
+                    format,
+                )
+            }
+        };
+
+        Ok(RunningQuery {
+            context,
+            _guard: guard,
+            has_result_set,
+            blocks,
+            encoder: ValueEncoder::create(&format),
+            columns: vec![],
+            num_rows: 0,
+            row_index: 0,
+            rows_sent: 0,
+        })
+    }
+
+    /// Send at most `max_rows` rows of the result set, or all if it is 0. Returns true if all
+    /// the rows are sent.
+    #[async_backtrace::framed]
+    async fn send_rows(
+        &mut self,
+        query: &mut RunningQuery,
+        formats: &[i16],
+        max_rows: usize,
+    ) -> Result<bool> {
+        let mut sent = 0;
+        loop {
+            while query.row_index < query.num_rows {
+                if max_rows > 0 && sent == max_rows {
+                    return Ok(false);
+                }
+
+                let offset = self.writer.start_data_row(query.columns.len());
+                for (column, format) in query.columns.iter().zip(formats) {
+                    self.writer.data_value(|buf| {
+                        query
+                            .encoder
+                            .write_value(column, query.row_index, *format, buf)
+                    })?;
+                }
+                self.writer.end_data_row(offset).await?;
+
+                query.row_index += 1;
+                query.rows_sent += 1;
+                sent += 1;
+            }
+
+            match query.blocks.next().await {
+                None => return Ok(true),
+                Some(block) => {
+                    let block = block?;
+                    if !query.has_result_set {
+                        continue;
+                    }
+                    query.num_rows = block.num_rows();
+                    query.row_index = 0;
+                    query.columns = block
+                        .consume_convert_to_full()
+                        .columns()
+                        .iter()
+                        .map(|entry| entry.value.clone().into_column().unwrap())
+                        .collect();
+                }
+            }
+        }
+    }
+
+    fn write_error(&mut self, error: &ErrorCode, query: Option<&str>) {
+        self.session.txn_mgr().lock().set_fail();
+
+        let error = match query {
+            Some(query) => error.clone().display_with_sql(query),
+            None => error.clone(),
+        };
+        if error.code() != ErrorCode::ABORTED_QUERY && error.code() != ErrorCode::ABORTED_SESSION {
+            error!("OnQuery Error: {:?}", error);
+        }
+        self.writer
+            .error_response("ERROR", sqlstate(&error), &error.message());
+    }
+
+    fn start_keep_alive(&self) {
+        let session = &self.session;
+        let tenant = session.get_current_tenant();
+        let session_id = session.get_id();
+        let user_name = match session.get_current_user() {
+            Ok(user) => user.name,
+            Err(_) => return,
+        };
+
+        databend_common_base::runtime::spawn(async move {
+            loop {
+                UserApiProvider::instance()
+                    .client_session_api(&tenant)
+                    .upsert_client_session_id(
+                        &session_id,
+                        &user_name,
+                        Duration::from_secs(3600 + 600),
+                    )
+                    .await
+                    .ok();
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+        });
+    }
+}
+
+async fn tracking<F: Future>(query_id: &str, future: F) -> F::Output {
+    let mut tracking_payload = ThreadTracker::new_tracking_payload();
+    tracking_payload.query_id = Some(query_id.to_string());
+    tracking_payload.mem_stat = Some(MemStat::create(format!("Query-{}", query_id)));
+    let _guard = ThreadTracker::tracking(tracking_payload);
+    ThreadTracker::tracking_future(future).await
+}
+
+/// The SQLSTATE of the error, clients like JDBC handle the errors by the class of it.
+pub fn sqlstate(error: &ErrorCode) -> &'static str {
+    match error.code() {
+        ErrorCode::AUTHENTICATE_FAILURE => "28P01",
+        ErrorCode::UNKNOWN_DATABASE => "3D000",
+        ErrorCode::UNKNOWN_TABLE => "42P01",
+        ErrorCode::UNKNOWN_COLUMN => "42703",
+        ErrorCode::SYNTAX_EXCEPTION => "42601",
+        ErrorCode::SEMANTIC_ERROR => "42000",
+        ErrorCode::PERMISSION_DENIED => "42501",
+        ErrorCode::ABORTED_QUERY => "57014",
+        ErrorCode::UNIMPLEMENTED => "0A000",
+        _ => "XX000",
+    }
+}
+
+/// Split the statements of a simple query by the semicolons.
+fn split_statements(query: &str) -> Vec<&str> {
+    let mut statements = vec![];
+    let mut start = 0;
+    for token in Tokenizer::new(query) {
+        match token {
+            Ok(token) if token.kind == TokenKind::SemiColon => {
+                statements.push(&query[start..token.span.start()]);
+                start = token.span.end();
+            }
+            Ok(_) => {}
+            // Leave the rest to the parser to report the error.
+            Err(_) => break,
+        }
+    }
+    statements.push(&query[start..]);
+    // Skip the empty statements, which may contain comments only.
+    statements
+        .into_iter()
+        .map(|statement| statement.trim())
+        .filter(|statement| {
+            Tokenizer::new(statement).any(|token| match token {
+                Ok(token) => token.kind != TokenKind::EOI,
+                Err(_) => true,
+            })
+        })
+        .collect()
+}
+
+/// The number of parameters is the largest placeholder like `$1` in the query.
+fn count_params(query: &str) -> usize {
+    Tokenizer::new(query)
+        .map_while(|token| token.ok())
+        .filter(|token| token.kind == TokenKind::ColumnPosition)
+        .filter_map(|token| token.text()[1..].parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Replace the placeholders like `$1` with the literals of the parameters. The placeholders
+/// are kept if there are no parameters, which are the column positions of Databend.
+fn bind_params(query: &str, literals: &[String]) -> Result<String> {
+    if literals.is_empty() {
+        return Ok(query.to_string());
+    }
+
+    let mut bound = String::with_capacity(query.len());
+    let mut start = 0;
+    for token in Tokenizer::new(query).map_while(|token| token.ok()) {
+        if token.kind != TokenKind::ColumnPosition {
+            continue;
+        }
+        let index = token.text()[1..].parse::<usize>().unwrap_or(0);
+        if index == 0 || index > literals.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "There is no parameter {}",
+                token.text()
+            )));
+        }
+        bound.push_str(&query[start..token.span.start()]);
+        bound.push_str(&literals[index - 1]);
+        start = token.span.end();
+    }
+    bound.push_str(&query[start..]);
+    Ok(bound)
+}
+
+fn command_tag(query: &str, running: &RunningQuery) -> String {
+    let rows = match (&running.context, running.has_result_set) {
+        (_, true) => running.rows_sent,
+        (Some(context), false) => context.get_write_progress_value().rows,
+        (None, false) => 0,
+    };
+    command_tag_with_rows(query, running.has_result_set, rows)
+}
+
+/// The tag of `CommandComplete`, like `SELECT 5`, `INSERT 0 5` or `CREATE TABLE`.
+fn command_tag_with_rows(query: &str, has_result_set: bool, rows: usize) -> String {
+    if has_result_set {
+        return format!("SELECT {rows}");
+    }
+
+    let keywords = Tokenizer::new(query)
+        .map_while(|token| token.ok())
+        .take(2)
+        .map(|token| token.text().to_ascii_uppercase())
+        .collect::<Vec<_>>();
+    match keywords.first().map(|s| s.as_str()) {
+        Some("INSERT") => format!("INSERT 0 {rows}"),
+        Some(keyword @ ("UPDATE" | "DELETE" | "COPY" | "MERGE" | "REPLACE")) => {
+            format!("{keyword} {rows}")
+        }
+        Some(keyword @ ("CREATE" | "DROP" | "ALTER")) => match keywords.get(1) {
+            Some(object) => format!("{keyword} {object}"),
+            None => keyword.to_string(),
+        },
+        Some(keyword) => keyword.to_string(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(
+            split_statements("select 1; select ';' ;; -- comment\n"),
+            vec!["select 1", "select ';'"]
+        );
+        assert!(split_statements(" ; ").is_empty());
+    }
+
+    #[test]
+    fn test_bind_params() -> Result<()> {
+        let literals = vec!["1".to_string(), "'a$1'".to_string()];
+        assert_eq!(count_params("select $2, '$3' from t where a = $1"), 2);
+        assert_eq!(
+            bind_params("select $2, '$3' from t where a = $1", &literals)?,
+            "select 'a$1', '$3' from t where a = 1"
+        );
+        assert_eq!(bind_params("select $1 from @s", &[])?, "select $1 from @s");
+        assert!(bind_params("select $3", &literals).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_tag() {
+        assert_eq!(command_tag_with_rows("select 1", true, 1), "SELECT 1");
+        assert_eq!(
+            command_tag_with_rows("insert into t values (1)", false, 1),
+            "INSERT 0 1"
+        );
+        assert_eq!(
+            command_tag_with_rows("create table t(a int)", false, 0),
+            "CREATE TABLE"
+        );
+        assert_eq!(command_tag_with_rows("begin", false, 0), "BEGIN");
+    }
+}
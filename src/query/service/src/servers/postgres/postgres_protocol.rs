@@ -0,0 +1,585 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Codec of the PostgreSQL frontend/backend protocol version 3.0.
+//!
+//! See https://www.postgresql.org/docs/current/protocol-message-formats.html
+
+use std::collections::HashMap;
+
+use databend_common_base::base::tokio::io::AsyncRead;
+use databend_common_base::base::tokio::io::AsyncReadExt;
+use databend_common_base::base::tokio::io::AsyncWrite;
+use databend_common_base::base::tokio::io::AsyncWriteExt;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+
+pub const PROTOCOL_VERSION_3: i32 = 196608;
+pub const SSL_REQUEST_CODE: i32 = 80877103;
+pub const GSSENC_REQUEST_CODE: i32 = 80877104;
+pub const CANCEL_REQUEST_CODE: i32 = 80877102;
+
+/// Messages larger than this are rejected, the same limit as the postgres server.
+const MAX_MESSAGE_LENGTH: usize = 1024 * 1024 * 1024;
+/// The buffered backend messages are flushed once their size reaches this threshold.
+const WRITE_BUFFER_SIZE: usize = 100 * 1024;
+
+pub const FORMAT_TEXT: i16 = 0;
+pub const FORMAT_BINARY: i16 = 1;
+
+/// Transaction status in `ReadyForQuery`.
+pub const TRANSACTION_IDLE: u8 = b'I';
+pub const TRANSACTION_ACTIVE: u8 = b'T';
+pub const TRANSACTION_FAILED: u8 = b'E';
+
+/// The first message sent by the client, which has no type byte.
+#[derive(Debug, PartialEq)]
+pub enum StartupMessage {
+    SslRequest,
+    GssEncRequest,
+    CancelRequest {
+        process_id: u32,
+        secret_key: u32,
+    },
+    Startup {
+        protocol_version: i32,
+        parameters: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FrontendMessage {
+    Query(String),
+    Parse {
+        name: String,
+        query: String,
+        param_types: Vec<u32>,
+    },
+    Bind {
+        portal: String,
+        statement: String,
+        param_formats: Vec<i16>,
+        params: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+    },
+    /// `kind` is `S` for a prepared statement, `P` for a portal.
+    Describe {
+        kind: u8,
+        name: String,
+    },
+    Execute {
+        portal: String,
+        max_rows: i32,
+    },
+    /// `kind` is `S` for a prepared statement, `P` for a portal.
+    Close {
+        kind: u8,
+        name: String,
+    },
+    Sync,
+    Flush,
+    Terminate,
+    /// The response to an authentication request.
+    Password(Vec<u8>),
+    Unsupported(u8),
+}
+
+impl FrontendMessage {
+    /// Whether the message is a part of the extended query protocol.
+    pub fn is_extended_query(&self) -> bool {
+        matches!(
+            self,
+            FrontendMessage::Parse { .. }
+                | FrontendMessage::Bind { .. }
+                | FrontendMessage::Describe { .. }
+                | FrontendMessage::Execute { .. }
+                | FrontendMessage::Close { .. }
+                | FrontendMessage::Flush
+        )
+    }
+}
+
+struct MessageReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> MessageReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        MessageReader { buf }
+    }
+
+    fn get_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(ErrorCode::BadBytes(
+                "Invalid postgres message, unexpected end of message",
+            ));
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn get_u8(&mut self) -> Result<u8> {
+        Ok(self.get_bytes(1)?[0])
+    }
+
+    fn get_i16(&mut self) -> Result<i16> {
+        Ok(i16::from_be_bytes(self.get_bytes(2)?.try_into().unwrap()))
+    }
+
+    fn get_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.get_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn get_cstr(&mut self) -> Result<String> {
+        let end = self.buf.iter().position(|b| *b == 0).ok_or_else(|| {
+            ErrorCode::BadBytes("Invalid postgres message, string is not terminated")
+        })?;
+        let s = std::str::from_utf8(&self.buf[..end])
+            .map_err(|_| ErrorCode::BadBytes("Invalid postgres message, string is not utf8"))?
+            .to_string();
+        self.buf = &self.buf[end + 1..];
+        Ok(s)
+    }
+
+    fn get_i16_list(&mut self) -> Result<Vec<i16>> {
+        let len = self.get_i16()?;
+        (0..len).map(|_| self.get_i16()).collect()
+    }
+}
+
+async fn read_body<R: AsyncRead + Unpin>(reader: &mut R, len: i32) -> Result<Vec<u8>> {
+    if len < 4 || len as usize > MAX_MESSAGE_LENGTH {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid postgres message length {len}"
+        )));
+    }
+    let mut body = vec![0; len as usize - 4];
+    reader.read_exact(&mut body).await?;
+    Ok(body)
+}
+
+pub async fn read_startup_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<StartupMessage> {
+    let len = reader.read_i32().await?;
+    let body = read_body(reader, len).await?;
+    let mut body = MessageReader::new(&body);
+    let code = body.get_i32()?;
+    match code {
+        SSL_REQUEST_CODE => Ok(StartupMessage::SslRequest),
+        GSSENC_REQUEST_CODE => Ok(StartupMessage::GssEncRequest),
+        CANCEL_REQUEST_CODE => Ok(StartupMessage::CancelRequest {
+            process_id: body.get_i32()? as u32,
+            secret_key: body.get_i32()? as u32,
+        }),
+        protocol_version => {
+            let mut parameters = HashMap::new();
+            loop {
+                let name = body.get_cstr()?;
+                if name.is_empty() {
+                    break;
+                }
+                let value = body.get_cstr()?;
+                parameters.insert(name, value);
+            }
+            Ok(StartupMessage::Startup {
+                protocol_version,
+                parameters,
+            })
+        }
+    }
+}
+
+/// Read a message after the startup phase, returns `None` if the connection is closed.
+/// Decode the body of `SASLInitialResponse`, which is received as `FrontendMessage::Password`.
+/// Returns the selected mechanism and the initial response of it.
+pub fn decode_sasl_initial_response(body: &[u8]) -> Result<(String, Vec<u8>)> {
+    let mut body = MessageReader::new(body);
+    let mechanism = body.get_cstr()?;
+    let len = body.get_i32()?;
+    let data = if len < 0 {
+        vec![]
+    } else {
+        body.get_bytes(len as usize)?.to_vec()
+    };
+    Ok((mechanism, data))
+}
+
+pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<FrontendMessage>> {
+    let tag = match reader.read_u8().await {
+        Ok(tag) => tag,
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let len = reader.read_i32().await?;
+    let body = read_body(reader, len).await?;
+    decode_message(tag, &body).map(Some)
+}
+
+fn decode_message(tag: u8, body: &[u8]) -> Result<FrontendMessage> {
+    let mut body = MessageReader::new(body);
+    let message = match tag {
+        b'Q' => FrontendMessage::Query(body.get_cstr()?),
+        b'P' => {
+            let name = body.get_cstr()?;
+            let query = body.get_cstr()?;
+            let len = body.get_i16()?;
+            let param_types = (0..len)
+                .map(|_| body.get_i32().map(|oid| oid as u32))
+                .collect::<Result<_>>()?;
+            FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            }
+        }
+        b'B' => {
+            let portal = body.get_cstr()?;
+            let statement = body.get_cstr()?;
+            let param_formats = body.get_i16_list()?;
+            let len = body.get_i16()?;
+            let mut params = Vec::with_capacity(len.max(0) as usize);
+            for _ in 0..len {
+                let value_len = body.get_i32()?;
+                if value_len < 0 {
+                    params.push(None);
+                } else {
+                    params.push(Some(body.get_bytes(value_len as usize)?.to_vec()));
+                }
+            }
+            let result_formats = body.get_i16_list()?;
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            }
+        }
+        b'D' => FrontendMessage::Describe {
+            kind: body.get_u8()?,
+            name: body.get_cstr()?,
+        },
+        b'E' => FrontendMessage::Execute {
+            portal: body.get_cstr()?,
+            max_rows: body.get_i32()?,
+        },
+        b'C' => FrontendMessage::Close {
+            kind: body.get_u8()?,
+            name: body.get_cstr()?,
+        },
+        b'S' => FrontendMessage::Sync,
+        b'H' => FrontendMessage::Flush,
+        b'X' => FrontendMessage::Terminate,
+        b'p' => FrontendMessage::Password(body.buf.to_vec()),
+        tag => FrontendMessage::Unsupported(tag),
+    };
+    Ok(message)
+}
+
+/// The description of a column in `RowDescription`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDescription {
+    pub name: String,
+    pub type_oid: u32,
+    pub type_len: i16,
+    pub format: i16,
+}
+
+/// Buffers the backend messages and writes them to the client on flush.
+pub struct PostgresWriter<W: AsyncWrite + Unpin> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> PostgresWriter<W> {
+    pub fn create(writer: W) -> Self {
+        PostgresWriter {
+            writer,
+            buf: Vec::with_capacity(WRITE_BUFFER_SIZE),
+        }
+    }
+
+    /// Write the type byte and a placeholder of the length, returns the offset of the length.
+    fn start_message(&mut self, tag: u8) -> usize {
+        self.buf.push(tag);
+        let offset = self.buf.len();
+        self.buf.extend_from_slice(&[0; 4]);
+        offset
+    }
+
+    fn end_message(&mut self, offset: usize) {
+        let len = (self.buf.len() - offset) as i32;
+        self.buf[offset..offset + 4].copy_from_slice(&len.to_be_bytes());
+    }
+
+    fn put_i16(&mut self, v: i16) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn put_i32(&mut self, v: i32) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn put_cstr(&mut self, s: &str) {
+        self.buf.extend_from_slice(s.as_bytes());
+        self.buf.push(0);
+    }
+
+    /// The response to `SSLRequest` and `GSSENCRequest`, which has no type byte.
+    pub async fn reject_encryption(&mut self) -> Result<()> {
+        self.buf.push(b'N');
+        self.flush().await
+    }
+
+    pub fn authentication_ok(&mut self) {
+        let offset = self.start_message(b'R');
+        self.put_i32(0);
+        self.end_message(offset);
+    }
+
+    pub fn authentication_cleartext_password(&mut self) {
+        let offset = self.start_message(b'R');
+        self.put_i32(3);
+        self.end_message(offset);
+    }
+
+    /// Ask for the SASL authentication with one of the mechanisms.
+    pub fn authentication_sasl(&mut self, mechanisms: &[&str]) {
+        let offset = self.start_message(b'R');
+        self.put_i32(10);
+        for mechanism in mechanisms {
+            self.put_cstr(mechanism);
+        }
+        self.buf.push(0);
+        self.end_message(offset);
+    }
+
+    pub fn authentication_sasl_continue(&mut self, data: &[u8]) {
+        let offset = self.start_message(b'R');
+        self.put_i32(11);
+        self.buf.extend_from_slice(data);
+        self.end_message(offset);
+    }
+
+    pub fn authentication_sasl_final(&mut self, data: &[u8]) {
+        let offset = self.start_message(b'R');
+        self.put_i32(12);
+        self.buf.extend_from_slice(data);
+        self.end_message(offset);
+    }
+
+    pub fn parameter_status(&mut self, name: &str, value: &str) {
+        let offset = self.start_message(b'S');
+        self.put_cstr(name);
+        self.put_cstr(value);
+        self.end_message(offset);
+    }
+
+    pub fn backend_key_data(&mut self, process_id: u32, secret_key: u32) {
+        let offset = self.start_message(b'K');
+        self.put_i32(process_id as i32);
+        self.put_i32(secret_key as i32);
+        self.end_message(offset);
+    }
+
+    pub fn ready_for_query(&mut self, transaction_status: u8) {
+        let offset = self.start_message(b'Z');
+        self.buf.push(transaction_status);
+        self.end_message(offset);
+    }
+
+    pub fn parse_complete(&mut self) {
+        let offset = self.start_message(b'1');
+        self.end_message(offset);
+    }
+
+    pub fn bind_complete(&mut self) {
+        let offset = self.start_message(b'2');
+        self.end_message(offset);
+    }
+
+    pub fn close_complete(&mut self) {
+        let offset = self.start_message(b'3');
+        self.end_message(offset);
+    }
+
+    pub fn no_data(&mut self) {
+        let offset = self.start_message(b'n');
+        self.end_message(offset);
+    }
+
+    pub fn portal_suspended(&mut self) {
+        let offset = self.start_message(b's');
+        self.end_message(offset);
+    }
+
+    pub fn empty_query_response(&mut self) {
+        let offset = self.start_message(b'I');
+        self.end_message(offset);
+    }
+
+    pub fn command_complete(&mut self, tag: &str) {
+        let offset = self.start_message(b'C');
+        self.put_cstr(tag);
+        self.end_message(offset);
+    }
+
+    pub fn parameter_description(&mut self, type_oids: &[u32]) {
+        let offset = self.start_message(b't');
+        self.put_i16(type_oids.len() as i16);
+        for oid in type_oids {
+            self.put_i32(*oid as i32);
+        }
+        self.end_message(offset);
+    }
+
+    pub fn row_description(&mut self, fields: &[FieldDescription]) {
+        let offset = self.start_message(b'T');
+        self.put_i16(fields.len() as i16);
+        for field in fields {
+            self.put_cstr(&field.name);
+            // The table oid and the column attribute number.
+            self.put_i32(0);
+            self.put_i16(0);
+            self.put_i32(field.type_oid as i32);
+            self.put_i16(field.type_len);
+            // The type modifier.
+            self.put_i32(-1);
+            self.put_i16(field.format);
+        }
+        self.end_message(offset);
+    }
+
+    /// Start a `DataRow`, the values are written by [`Self::data_value`], and the message is
+    /// finished by [`Self::end_data_row`] with the returned offset.
+    pub fn start_data_row(&mut self, num_columns: usize) -> usize {
+        let offset = self.start_message(b'D');
+        self.put_i16(num_columns as i16);
+        offset
+    }
+
+    /// Write a value of `DataRow`, the encoded value is appended by `f`, `None` for null.
+    pub fn data_value<F>(&mut self, f: F) -> Result<()>
+    where F: FnOnce(&mut Vec<u8>) -> Result<bool> {
+        let offset = self.buf.len();
+        self.put_i32(0);
+        if f(&mut self.buf)? {
+            let len = (self.buf.len() - offset - 4) as i32;
+            self.buf[offset..offset + 4].copy_from_slice(&len.to_be_bytes());
+        } else {
+            self.buf.truncate(offset);
+            self.put_i32(-1);
+        }
+        Ok(())
+    }
+
+    pub async fn end_data_row(&mut self, offset: usize) -> Result<()> {
+        self.end_message(offset);
+        if self.buf.len() >= WRITE_BUFFER_SIZE {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Write an `ErrorResponse`, or a `NoticeResponse` if the severity is not an error.
+    pub fn error_response(&mut self, severity: &str, code: &str, message: &str) {
+        let tag = match severity {
+            "ERROR" | "FATAL" | "PANIC" => b'E',
+            _ => b'N',
+        };
+        let offset = self.start_message(tag);
+        for (field, value) in [(b'S', severity), (b'V', severity), (b'C', code)] {
+            self.buf.push(field);
+            self.put_cstr(value);
+        }
+        self.buf.push(b'M');
+        // Strings in the protocol can not contain null bytes.
+        self.put_cstr(&message.replace('\0', ""));
+        self.buf.push(0);
+        self.end_message(offset);
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        if !self.buf.is_empty() {
+            self.writer.write_all(&self.buf).await?;
+            self.buf.clear();
+        }
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_bind() -> Result<()> {
+        let mut body = vec![];
+        body.extend_from_slice(b"p1\0s1\0");
+        body.extend_from_slice(&1i16.to_be_bytes());
+        body.extend_from_slice(&FORMAT_BINARY.to_be_bytes());
+        body.extend_from_slice(&2i16.to_be_bytes());
+        body.extend_from_slice(&4i32.to_be_bytes());
+        body.extend_from_slice(&7i32.to_be_bytes());
+        body.extend_from_slice(&(-1i32).to_be_bytes());
+        body.extend_from_slice(&0i16.to_be_bytes());
+
+        assert_eq!(decode_message(b'B', &body)?, FrontendMessage::Bind {
+            portal: "p1".to_string(),
+            statement: "s1".to_string(),
+            param_formats: vec![FORMAT_BINARY],
+            params: vec![Some(7i32.to_be_bytes().to_vec()), None],
+            result_formats: vec![],
+        });
+
+        assert!(decode_message(b'B', &body[..body.len() - 3]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_sasl_initial_response() -> Result<()> {
+        let mut body = b"SCRAM-SHA-256\0".to_vec();
+        body.extend_from_slice(&8i32.to_be_bytes());
+        body.extend_from_slice(b"n,,n=,r=");
+        assert_eq!(
+            decode_sasl_initial_response(&body)?,
+            ("SCRAM-SHA-256".to_string(), b"n,,n=,r=".to_vec())
+        );
+
+        assert!(decode_sasl_initial_response(&body[..body.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_data_row() -> Result<()> {
+        let mut writer = PostgresWriter::create(Vec::new());
+        let offset = writer.start_data_row(2);
+        writer.data_value(|buf| {
+            buf.extend_from_slice(b"ab");
+            Ok(true)
+        })?;
+        writer.data_value(|_| Ok(false))?;
+        writer.end_message(offset);
+
+        let mut expected = vec![b'D'];
+        expected.extend_from_slice(&16i32.to_be_bytes());
+        expected.extend_from_slice(&2i16.to_be_bytes());
+        expected.extend_from_slice(&2i32.to_be_bytes());
+        expected.extend_from_slice(b"ab");
+        expected.extend_from_slice(&(-1i32).to_be_bytes());
+        assert_eq!(writer.buf, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::ScramVerifier;
+use rand::RngCore;
+
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+const SERVER_NONCE_LEN: usize = 18;
+
+/// The server side of the SCRAM-SHA-256 exchange in the SASL authentication.
+///
+/// Channel binding is not supported, so the `SCRAM-SHA-256-PLUS` mechanism is not offered.
+pub struct ScramExchange {
+    gs2_header: String,
+    client_first_bare: String,
+    server_first: String,
+    nonce: String,
+}
+
+impl ScramExchange {
+    /// Start the exchange with the `client-first-message`.
+    pub fn start(client_first: &[u8], verifier: &ScramVerifier) -> Result<ScramExchange> {
+        let mut server_nonce = [0u8; SERVER_NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut server_nonce);
+        Self::start_with_nonce(
+            client_first,
+            verifier,
+            &BASE64_STANDARD.encode(server_nonce),
+        )
+    }
+
+    fn start_with_nonce(
+        client_first: &[u8],
+        verifier: &ScramVerifier,
+        server_nonce: &str,
+    ) -> Result<ScramExchange> {
+        let client_first = std::str::from_utf8(client_first)
+            .map_err(|_| bad_message("client-first-message is not utf8"))?;
+
+        // gs2-header = gs2-cbind-flag "," [ authzid ] ","
+        let mut parts = client_first.splitn(3, ',');
+        let (Some(cbind_flag), Some(authzid), Some(client_first_bare)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(bad_message("client-first-message has no gs2-header"));
+        };
+        match cbind_flag {
+            "n" | "y" => {}
+            _ if cbind_flag.starts_with("p=") => {
+                return Err(bad_message("channel binding is not supported"));
+            }
+            _ => return Err(bad_message("invalid gs2-cbind-flag")),
+        }
+        if !authzid.is_empty() {
+            return Err(bad_message("authorization identity is not supported"));
+        }
+
+        // The user name is ignored, it is the one of the startup message.
+        let mut attributes = client_first_bare.split(',');
+        if !attributes.next().is_some_and(|a| a.starts_with("n=")) {
+            return Err(bad_message("client-first-message has no username"));
+        }
+        let client_nonce = attributes
+            .next()
+            .and_then(|a| a.strip_prefix("r="))
+            .filter(|nonce| !nonce.is_empty())
+            .ok_or_else(|| bad_message("client-first-message has no nonce"))?;
+
+        let nonce = format!("{client_nonce}{server_nonce}");
+        let server_first = format!(
+            "r={},s={},i={}",
+            nonce,
+            BASE64_STANDARD.encode(&verifier.salt),
+            verifier.iterations
+        );
+        Ok(ScramExchange {
+            gs2_header: client_first[..client_first.len() - client_first_bare.len()].to_string(),
+            client_first_bare: client_first_bare.to_string(),
+            server_first,
+            nonce,
+        })
+    }
+
+    /// The `server-first-message` sent to the client.
+    pub fn server_first(&self) -> &str {
+        &self.server_first
+    }
+
+    /// Check the `client-final-message`, returns the `AuthMessage` of the exchange and
+    /// the `ClientProof` to verify.
+    pub fn finish(&self, client_final: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let client_final = std::str::from_utf8(client_final)
+            .map_err(|_| bad_message("client-final-message is not utf8"))?;
+        let (without_proof, proof) = client_final
+            .rsplit_once(",p=")
+            .ok_or_else(|| bad_message("client-final-message has no proof"))?;
+
+        let mut attributes = without_proof.split(',');
+        let channel_binding = attributes
+            .next()
+            .and_then(|a| a.strip_prefix("c="))
+            .ok_or_else(|| bad_message("client-final-message has no channel binding"))?;
+        if channel_binding != BASE64_STANDARD.encode(&self.gs2_header) {
+            return Err(bad_message("unexpected channel binding"));
+        }
+        if attributes.next().and_then(|a| a.strip_prefix("r=")) != Some(self.nonce.as_str()) {
+            return Err(bad_message("unexpected nonce"));
+        }
+        let proof = BASE64_STANDARD
+            .decode(proof)
+            .map_err(|_| bad_message("invalid proof"))?;
+
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, self.server_first, without_proof
+        );
+        Ok((auth_message.into_bytes(), proof))
+    }
+
+    /// The `server-final-message` sent to the client after the proof is verified.
+    pub fn server_final(verifier: &ScramVerifier, auth_message: &[u8]) -> String {
+        format!(
+            "v={}",
+            BASE64_STANDARD.encode(verifier.server_signature(auth_message))
+        )
+    }
+}
+
+fn bad_message(message: &str) -> ErrorCode {
+    ErrorCode::AuthenticateFailure(format!("Invalid SCRAM message: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example of RFC 7677.
+    #[test]
+    fn test_scram_exchange() -> Result<()> {
+        let salt = BASE64_STANDARD.decode("W22ZaJ0SNY7soEsUEjb6gQ==").unwrap();
+        let verifier = ScramVerifier::create_with_salt("pencil", salt, 4096);
+
+        let exchange = ScramExchange::start_with_nonce(
+            b"n,,n=user,r=rOprNGfwEbeRWgbNEkqO",
+            &verifier,
+            "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0",
+        )?;
+        assert_eq!(
+            exchange.server_first(),
+            "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096"
+        );
+
+        let (auth_message, proof) = exchange.finish(
+            b"c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=",
+        )?;
+        assert!(verifier.verify_client_proof(&auth_message, &proof));
+        assert_eq!(
+            ScramExchange::server_final(&verifier, &auth_message),
+            "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4="
+        );
+
+        // The same password salted differently does not match.
+        let other = ScramVerifier::create("pencil");
+        assert!(!other.verify_client_proof(&auth_message, &proof));
+
+        // The nonce of the server is not echoed.
+        assert!(exchange
+            .finish(b"c=biws,r=rOprNGfwEbeRWgbNEkqO,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=")
+            .is_err());
+        // Channel binding is not supported.
+        assert!(ScramExchange::start(b"p=tls-server-end-point,,n=,r=abc", &verifier).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,540 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::net::Shutdown;
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use databend_common_base::base::tokio::io::split;
+use databend_common_base::base::tokio::io::AsyncRead;
+use databend_common_base::base::tokio::io::AsyncWrite;
+use databend_common_base::base::tokio::io::AsyncWriteExt;
+use databend_common_base::base::tokio::io::BufReader;
+use databend_common_base::base::tokio::net::TcpStream;
+use databend_common_base::runtime::Runtime;
+use databend_common_base::runtime::Thread;
+use databend_common_base::runtime::TrySpawn;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_exception::ToErrorCode;
+use databend_common_meta_app::principal::AuthInfo;
+use databend_common_meta_app::principal::ScramVerifier;
+use databend_common_meta_app::principal::UserIdentity;
+use databend_common_users::UserApiProvider;
+use databend_storages_common_session::drop_all_temp_tables;
+use log::error;
+use log::info;
+use log::warn;
+use rand::RngCore;
+use rustls::ServerConfig;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio_rustls::TlsAcceptor;
+
+use crate::auth::AuthMgr;
+use crate::auth::Credential;
+use crate::servers::postgres::postgres_interactive_worker::sqlstate;
+use crate::servers::postgres::postgres_interactive_worker::PostgresInteractiveWorker;
+use crate::servers::postgres::postgres_protocol::decode_sasl_initial_response;
+use crate::servers::postgres::postgres_protocol::read_message;
+use crate::servers::postgres::postgres_protocol::read_startup_message;
+use crate::servers::postgres::postgres_protocol::FrontendMessage;
+use crate::servers::postgres::postgres_protocol::PostgresWriter;
+use crate::servers::postgres::postgres_protocol::StartupMessage;
+use crate::servers::postgres::postgres_scram::ScramExchange;
+use crate::servers::postgres::postgres_scram::SCRAM_SHA_256;
+use crate::servers::postgres::PG_SERVER_VERSION;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+
+type PostgresStreamReader = Box<dyn AsyncRead + Unpin + Send>;
+type PostgresStreamWriter = Box<dyn AsyncWrite + Unpin + Send>;
+
+pub struct PostgresConnection;
+
+impl PostgresConnection {
+    /// Run the connection on the stream, the session is registered to the session manager
+    /// after the client is authenticated.
+    pub fn run_on_stream(
+        session: Session,
+        stream: TcpStream,
+        tls: Option<Arc<ServerConfig>>,
+    ) -> Result<()> {
+        let blocking_stream = Self::convert_stream(stream)?;
+        let blocking_stream_ref = blocking_stream.try_clone()?;
+        let non_blocking_stream = TcpStream::from_std(blocking_stream)?;
+        let query_executor =
+            Runtime::with_worker_threads(1, Some("postgres-query-executor".to_string()))?;
+        Thread::spawn(move || {
+            let join_handle = query_executor.spawn(async move {
+                let client_addr = match non_blocking_stream.peer_addr() {
+                    Ok(addr) => addr,
+                    Err(e) => {
+                        warn!(
+                            "Failed to get postgres conn peer address for {:?}: {}",
+                            non_blocking_stream, e
+                        );
+                        return Ok(());
+                    }
+                };
+
+                let (r, w, first_message, secure) =
+                    Self::negotiate_encryption(non_blocking_stream, tls).await?;
+                let mut reader = BufReader::new(r);
+                let mut writer = PostgresWriter::create(w);
+
+                let client_ip = client_addr.ip().to_string();
+                let Some((session, parameters)) = Self::startup(
+                    session,
+                    first_message,
+                    &mut reader,
+                    &mut writer,
+                    client_ip,
+                    secure,
+                )
+                .await?
+                else {
+                    return Ok(());
+                };
+                Self::attach_session(&session, client_addr, blocking_stream_ref);
+
+                let mut worker = PostgresInteractiveWorker::create(session.clone(), reader, writer);
+                if Self::init(&mut worker, &session, &parameters).await? {
+                    if let Err(error) = worker.run().await {
+                        info!("Postgres connection closed: {}", error);
+                    }
+                }
+
+                let tenant = session.get_current_tenant();
+                let session_id = session.get_id();
+                let user = session.get_current_user()?.name;
+                UserApiProvider::instance()
+                    .client_session_api(&tenant)
+                    .drop_client_session_id(&session_id, &user)
+                    .await
+                    .ok();
+                drop_all_temp_tables(&session_id, session.temp_tbl_mgr()).await
+            });
+            let _ = futures::executor::block_on(join_handle);
+        });
+        Ok(())
+    }
+
+    /// Handle the encryption requests sent before the startup message. The connection is
+    /// upgraded to TLS if the client asks for it and the server has a certificate, the
+    /// other requests are refused and the client continues in plain text or gives up.
+    /// Returns the first message which is not an encryption request if it is read already,
+    /// and whether the connection is encrypted.
+    #[async_backtrace::framed]
+    async fn negotiate_encryption(
+        mut stream: TcpStream,
+        tls: Option<Arc<ServerConfig>>,
+    ) -> Result<(
+        PostgresStreamReader,
+        PostgresStreamWriter,
+        Option<StartupMessage>,
+        bool,
+    )> {
+        loop {
+            match (read_startup_message(&mut stream).await?, &tls) {
+                (StartupMessage::SslRequest, Some(tls)) => {
+                    stream.write_all(b"S").await?;
+                    let acceptor = TlsAcceptor::from(tls.clone());
+                    let (r, w) = split(acceptor.accept(stream).await?);
+                    return Ok((Box::new(r), Box::new(w), None, true));
+                }
+                (StartupMessage::SslRequest | StartupMessage::GssEncRequest, _) => {
+                    stream.write_all(b"N").await?;
+                }
+                (message, _) => {
+                    let (r, w) = stream.into_split();
+                    return Ok((Box::new(r), Box::new(w), Some(message), false));
+                }
+            }
+        }
+    }
+
+    /// Handle the startup messages and authenticate the client. Returns `None` if the
+    /// connection should be closed, which happens on the `CancelRequest` too.
+    #[async_backtrace::framed]
+    async fn startup<R, W>(
+        mut session: Session,
+        mut first_message: Option<StartupMessage>,
+        reader: &mut R,
+        writer: &mut PostgresWriter<W>,
+        client_ip: String,
+        secure: bool,
+    ) -> Result<Option<(Arc<Session>, HashMap<String, String>)>>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let parameters = loop {
+            let message = match first_message.take() {
+                Some(message) => message,
+                None => read_startup_message(reader).await?,
+            };
+            match message {
+                // The encryption is negotiated already.
+                StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                    writer.reject_encryption().await?
+                }
+                StartupMessage::CancelRequest {
+                    process_id,
+                    secret_key,
+                } => {
+                    Self::cancel_request(process_id, secret_key);
+                    return Ok(None);
+                }
+                StartupMessage::Startup {
+                    protocol_version,
+                    parameters,
+                } => {
+                    if protocol_version >> 16 != 3 {
+                        writer.error_response(
+                            "FATAL",
+                            "0A000",
+                            &format!(
+                                "Unsupported frontend protocol {}.{}",
+                                protocol_version >> 16,
+                                protocol_version & 0xFFFF
+                            ),
+                        );
+                        writer.flush().await?;
+                        return Ok(None);
+                    }
+                    break parameters;
+                }
+            }
+        };
+
+        let Some(user) = parameters.get("user").cloned() else {
+            writer.error_response("FATAL", "28000", "No user name specified in startup packet");
+            writer.flush().await?;
+            return Ok(None);
+        };
+
+        // The password is asked only if the user has one. The unknown users are asked too,
+        // with a mock SCRAM verifier, so that the existence of the users is not revealed.
+        let tenant = session.get_current_tenant();
+        let method = match UserApiProvider::instance()
+            .get_user_with_client_ip(&tenant, UserIdentity::new(&user, "%"), Some(&client_ip))
+            .await
+        {
+            Ok(user_info) => match user_info.auth_info {
+                AuthInfo::None => AuthMethod::Trust,
+                AuthInfo::Password {
+                    scram_verifier: Some(verifier),
+                    ..
+                } => AuthMethod::Scram(verifier),
+                _ => AuthMethod::Cleartext,
+            },
+            Err(_) => AuthMethod::Scram(mock_scram_verifier(&user)),
+        };
+
+        // The `server-final-message` of SCRAM is sent after the client is authenticated.
+        let (credential, server_final) = match method {
+            AuthMethod::Trust => {
+                let credential = Credential::Password {
+                    name: user.clone(),
+                    password: None,
+                    client_ip: Some(client_ip.clone()),
+                };
+                (credential, None)
+            }
+            AuthMethod::Scram(verifier) => {
+                match Self::scram_exchange(reader, writer, &user, &client_ip, &verifier).await? {
+                    Some((credential, server_final)) => (credential, Some(server_final)),
+                    None => return Ok(None),
+                }
+            }
+            // The password set before the SCRAM verifier is introduced is only stored as
+            // sha256 or double sha1 hash, which has to be sent in cleartext, so it is asked
+            // only on the encrypted connections.
+            AuthMethod::Cleartext if !secure => {
+                writer.error_response(
+                    "FATAL",
+                    "28000",
+                    &format!(
+                        "Password authentication of user \"{user}\" requires SSL, \
+                        or set the password again to enable SCRAM-SHA-256 authentication"
+                    ),
+                );
+                writer.flush().await?;
+                return Ok(None);
+            }
+            AuthMethod::Cleartext => {
+                writer.authentication_cleartext_password();
+                writer.flush().await?;
+                match read_message(reader).await? {
+                    Some(FrontendMessage::Password(mut password)) => {
+                        if password.last() == Some(&0) {
+                            password.pop();
+                        }
+                        let credential = Credential::Password {
+                            name: user.clone(),
+                            password: Some(password),
+                            client_ip: Some(client_ip.clone()),
+                        };
+                        (credential, None)
+                    }
+                    // The client closes the connection to ask the user for the password.
+                    _ => return Ok(None),
+                }
+            }
+        };
+
+        if let Err(error) = AuthMgr::instance()
+            .auth(&mut session, &credential, true)
+            .await
+        {
+            error!(
+                "Postgres handler authenticate failed, \
+                    user_name: {}, \
+                    client_address: {}, \
+                    failure_cause: {}",
+                user, client_ip, error
+            );
+            writer.error_response(
+                "FATAL",
+                "28P01",
+                &format!("Password authentication failed for user \"{user}\""),
+            );
+            writer.flush().await?;
+            return Ok(None);
+        }
+        if let Some(server_final) = server_final {
+            writer.authentication_sasl_final(server_final.as_bytes());
+        }
+
+        let session = match SessionManager::instance().register_session(session) {
+            Ok(session) => session,
+            Err(error) => {
+                warn!("fail to register session, {:?}", error);
+                writer.error_response("FATAL", "53300", &error.message());
+                writer.flush().await?;
+                return Ok(None);
+            }
+        };
+
+        info!("Postgres connection authenticated: {}@{}", user, client_ip);
+        writer.authentication_ok();
+        Ok(Some((session, parameters)))
+    }
+
+    /// Run the SCRAM-SHA-256 exchange until the proof of the client is received. Returns
+    /// the credential to verify the proof and the `server-final-message`, or `None` if the
+    /// connection should be closed.
+    #[async_backtrace::framed]
+    async fn scram_exchange<R, W>(
+        reader: &mut R,
+        writer: &mut PostgresWriter<W>,
+        user: &str,
+        client_ip: &str,
+        verifier: &ScramVerifier,
+    ) -> Result<Option<(Credential, String)>>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        writer.authentication_sasl(&[SCRAM_SHA_256]);
+        writer.flush().await?;
+        let exchange = match read_message(reader).await? {
+            Some(FrontendMessage::Password(body)) => {
+                let (mechanism, client_first) = decode_sasl_initial_response(&body)?;
+                if mechanism != SCRAM_SHA_256 {
+                    writer.error_response(
+                        "FATAL",
+                        "28000",
+                        &format!("Unsupported SASL mechanism {mechanism}"),
+                    );
+                    writer.flush().await?;
+                    return Ok(None);
+                }
+                ScramExchange::start(&client_first, verifier)
+            }
+            // The client closes the connection to ask the user for the password.
+            _ => return Ok(None),
+        };
+        let exchange = match exchange {
+            Ok(exchange) => exchange,
+            Err(error) => {
+                writer.error_response("FATAL", "08P01", &error.message());
+                writer.flush().await?;
+                return Ok(None);
+            }
+        };
+
+        writer.authentication_sasl_continue(exchange.server_first().as_bytes());
+        writer.flush().await?;
+        let Some(FrontendMessage::Password(client_final)) = read_message(reader).await? else {
+            return Ok(None);
+        };
+        let (auth_message, client_proof) = match exchange.finish(&client_final) {
+            Ok(result) => result,
+            Err(error) => {
+                writer.error_response("FATAL", "08P01", &error.message());
+                writer.flush().await?;
+                return Ok(None);
+            }
+        };
+
+        let server_final = ScramExchange::server_final(verifier, &auth_message);
+        let credential = Credential::Scram {
+            name: user.to_string(),
+            auth_message,
+            client_proof,
+            client_ip: Some(client_ip.to_string()),
+        };
+        Ok(Some((credential, server_final)))
+    }
+
+    /// Apply the parameters of the startup message, and report the parameters of the
+    /// server. Returns false if the connection should be closed.
+    #[async_backtrace::framed]
+    async fn init<R, W>(
+        worker: &mut PostgresInteractiveWorker<R, W>,
+        session: &Arc<Session>,
+        parameters: &HashMap<String, String>,
+    ) -> Result<bool>
+    where
+        R: AsyncRead + Unpin + Send,
+        W: AsyncWrite + Unpin + Send,
+    {
+        if let Some(database) = parameters.get("database").filter(|db| !db.is_empty()) {
+            if let Err(error) = worker.init(database).await {
+                worker
+                    .writer()
+                    .error_response("FATAL", sqlstate(&error), &error.message());
+                worker.writer().flush().await?;
+                return Ok(false);
+            }
+        }
+
+        let settings = session.get_settings();
+        if let Some(timezone) = parameters.get("TimeZone") {
+            if let Err(error) = settings.set_setting("timezone".to_string(), timezone.clone()) {
+                warn!(
+                    "Ignore the postgres TimeZone parameter {}: {}",
+                    timezone, error
+                );
+            }
+        }
+        let timezone = settings.get_timezone()?;
+
+        let process_id = session.get_mysql_conn_id().unwrap_or_default();
+        let transaction_status = worker.transaction_status();
+        let writer = worker.writer();
+        for (name, value) in [
+            ("server_version", PG_SERVER_VERSION),
+            ("server_encoding", "UTF8"),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO, YMD"),
+            ("IntervalStyle", "postgres"),
+            ("integer_datetimes", "on"),
+            ("standard_conforming_strings", "on"),
+            ("TimeZone", timezone.as_str()),
+        ] {
+            writer.parameter_status(name, value);
+        }
+        writer.backend_key_data(process_id, secret_key(&session.get_id()));
+        writer.ready_for_query(transaction_status);
+        writer.flush().await?;
+        Ok(true)
+    }
+
+    /// Kill the running query of the session, the process id is the connection id of it.
+    fn cancel_request(process_id: u32, key: u32) {
+        let session_manager = SessionManager::instance();
+        let session = session_manager
+            .get_id_by_mysql_conn_id(&Some(process_id))
+            .and_then(|id| session_manager.get_session_by_id(&id));
+        match session {
+            Some(session) if secret_key(&session.get_id()) == key => {
+                info!("Postgres cancel request of connection {}", process_id);
+                session.force_kill_query(ErrorCode::AbortedQuery(
+                    "canceling statement due to user request",
+                ));
+            }
+            _ => warn!(
+                "Invalid postgres cancel request of connection {}",
+                process_id
+            ),
+        }
+    }
+
+    fn attach_session(
+        session: &Arc<Session>,
+        client_addr: std::net::SocketAddr,
+        blocking_stream: std::net::TcpStream,
+    ) {
+        session.attach(Some(client_addr), move || {
+            if let Err(error) = blocking_stream.shutdown(Shutdown::Both) {
+                error!("Cannot shutdown Postgres session io {}", error);
+            }
+        });
+    }
+
+    // TODO: move to ToBlockingStream trait
+    fn convert_stream(stream: TcpStream) -> Result<std::net::TcpStream> {
+        let stream = stream
+            .into_std()
+            .map_err_to_code(ErrorCode::TokioError, || {
+                "Cannot to convert Tokio TcpStream to Std TcpStream"
+            })?;
+        stream
+            .set_nonblocking(false)
+            .map_err_to_code(ErrorCode::TokioError, || {
+                "Cannot to convert Tokio TcpStream to Std TcpStream"
+            })?;
+
+        Ok(stream)
+    }
+}
+
+/// How the client is authenticated, decided by the stored auth info of the user.
+enum AuthMethod {
+    Trust,
+    Scram(ScramVerifier),
+    Cleartext,
+}
+
+/// The SCRAM verifier of the unknown users. The salt is derived from the user name and a
+/// random key of the process, so that it is stable for the same user like a real one.
+fn mock_scram_verifier(user: &str) -> ScramVerifier {
+    static MOCK_KEY: LazyLock<[u8; 32]> = LazyLock::new(|| {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        key
+    });
+    let mut hasher = Sha256::new();
+    hasher.update(*MOCK_KEY);
+    hasher.update(user.as_bytes());
+    ScramVerifier {
+        iterations: ScramVerifier::ITERATIONS,
+        salt: hasher.finalize()[..ScramVerifier::SALT_LEN].to_vec(),
+        stored_key: vec![],
+        server_key: vec![],
+    }
+}
+
+/// The secret key of `BackendKeyData` for the cancel request, which is derived from the
+/// random session id so that it is not known by the other clients.
+fn secret_key(session_id: &str) -> u32 {
+    let bytes = session_id.as_bytes();
+    bytes.iter().fold(0x811c9dc5u32, |hash, b| {
+        (hash ^ *b as u32).wrapping_mul(0x01000193)
+    })
+}
@@ -0,0 +1,442 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Mapping between the Databend data types and the PostgreSQL types.
+
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::number::NumberScalar;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::Column;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::ScalarRef;
+use databend_common_formats::field_encoder::FieldEncoderValues;
+use databend_common_io::prelude::FormatSettings;
+use jiff::tz::TimeZone;
+use jiff::Timestamp;
+
+use crate::servers::postgres::postgres_protocol::FieldDescription;
+use crate::servers::postgres::postgres_protocol::FORMAT_BINARY;
+use crate::servers::postgres::postgres_protocol::FORMAT_TEXT;
+
+pub const UNSPECIFIED_OID: u32 = 0;
+pub const BOOL_OID: u32 = 16;
+pub const BYTEA_OID: u32 = 17;
+pub const INT8_OID: u32 = 20;
+pub const INT2_OID: u32 = 21;
+pub const INT4_OID: u32 = 23;
+pub const TEXT_OID: u32 = 25;
+pub const OID_OID: u32 = 26;
+pub const JSON_OID: u32 = 114;
+pub const FLOAT4_OID: u32 = 700;
+pub const FLOAT8_OID: u32 = 701;
+pub const VARCHAR_OID: u32 = 1043;
+pub const DATE_OID: u32 = 1082;
+pub const TIMESTAMP_OID: u32 = 1114;
+pub const TIMESTAMPTZ_OID: u32 = 1184;
+pub const NUMERIC_OID: u32 = 1700;
+
+/// Days from 1970-01-01 to 2000-01-01, the epoch of the postgres date and timestamp.
+const PG_EPOCH_DAYS: i64 = 10957;
+const PG_EPOCH_MICROS: i64 = PG_EPOCH_DAYS * 24 * 3600 * 1_000_000;
+
+const NUMERIC_POSITIVE: u16 = 0x0000;
+const NUMERIC_NEGATIVE: u16 = 0x4000;
+
+/// Unsigned integers are mapped to the signed type wider than them, as postgres has no
+/// unsigned types. Types without a postgres counterpart are sent as text.
+pub fn type_oid(data_type: &DataType) -> u32 {
+    match data_type.remove_nullable() {
+        DataType::Boolean => BOOL_OID,
+        DataType::Number(num_ty) => match num_ty {
+            NumberDataType::Int8 | NumberDataType::Int16 | NumberDataType::UInt8 => INT2_OID,
+            NumberDataType::Int32 | NumberDataType::UInt16 => INT4_OID,
+            NumberDataType::Int64 | NumberDataType::UInt32 => INT8_OID,
+            NumberDataType::UInt64 => NUMERIC_OID,
+            NumberDataType::Float32 => FLOAT4_OID,
+            NumberDataType::Float64 => FLOAT8_OID,
+        },
+        DataType::Decimal(_) => NUMERIC_OID,
+        DataType::Binary => BYTEA_OID,
+        DataType::Date => DATE_OID,
+        DataType::Timestamp => TIMESTAMP_OID,
+        DataType::Variant => JSON_OID,
+        _ => TEXT_OID,
+    }
+}
+
+/// The size of the type, negative for the types with variable length.
+pub fn type_len(oid: u32) -> i16 {
+    match oid {
+        BOOL_OID => 1,
+        INT2_OID => 2,
+        INT4_OID | FLOAT4_OID | DATE_OID => 4,
+        INT8_OID | FLOAT8_OID | TIMESTAMP_OID => 8,
+        _ => -1,
+    }
+}
+
+/// Resolve the format of each result column from the format codes in `Bind`: no codes for
+/// all text, one code for all the columns, or one code for each column.
+pub fn result_formats(formats: &[i16], num_columns: usize) -> Result<Vec<i16>> {
+    match formats.len() {
+        0 => Ok(vec![FORMAT_TEXT; num_columns]),
+        1 => Ok(vec![formats[0]; num_columns]),
+        n if n == num_columns => Ok(formats.to_vec()),
+        n => Err(ErrorCode::BadArguments(format!(
+            "Bind message has {n} result formats but query has {num_columns} columns"
+        ))),
+    }
+}
+
+pub fn field_descriptions(schema: &DataSchemaRef, formats: &[i16]) -> Vec<FieldDescription> {
+    schema
+        .fields()
+        .iter()
+        .zip(formats)
+        .map(|(field, format)| {
+            let type_oid = type_oid(field.data_type());
+            FieldDescription {
+                name: field.name().to_string(),
+                type_oid,
+                type_len: type_len(type_oid),
+                format: *format,
+            }
+        })
+        .collect()
+}
+
+/// Encodes the values of the result set in the text or binary format of postgres.
+pub struct ValueEncoder {
+    encoder: FieldEncoderValues,
+    jiff_timezone: TimeZone,
+}
+
+impl ValueEncoder {
+    pub fn create(format: &FormatSettings) -> Self {
+        ValueEncoder {
+            encoder: FieldEncoderValues::create_for_postgres_handler(
+                format.jiff_timezone.clone(),
+                format.timezone,
+                format.geometry_format,
+            ),
+            jiff_timezone: format.jiff_timezone.clone(),
+        }
+    }
+
+    /// Append the encoded value to `buf`, returns false if the value is null.
+    pub fn write_value(
+        &self,
+        column: &Column,
+        row_index: usize,
+        format: i16,
+        buf: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let value = unsafe { column.index_unchecked(row_index) };
+        match value {
+            ScalarRef::Null => return Ok(false),
+            ScalarRef::String(s) => buf.extend_from_slice(s.as_bytes()),
+            ScalarRef::Binary(v) if format == FORMAT_BINARY => buf.extend_from_slice(v),
+            ScalarRef::Binary(v) => {
+                buf.extend_from_slice(b"\\x");
+                buf.extend_from_slice(hex::encode(v).as_bytes());
+            }
+            value if format == FORMAT_BINARY => self.write_binary(column, row_index, value, buf)?,
+            _ => self.encoder.write_field(column, row_index, buf, false),
+        }
+        Ok(true)
+    }
+
+    fn write_binary(
+        &self,
+        column: &Column,
+        row_index: usize,
+        value: ScalarRef,
+        buf: &mut Vec<u8>,
+    ) -> Result<()> {
+        match value {
+            ScalarRef::Boolean(v) => buf.push(v as u8),
+            ScalarRef::Number(num) => match num {
+                NumberScalar::Int8(v) => buf.extend_from_slice(&(v as i16).to_be_bytes()),
+                NumberScalar::UInt8(v) => buf.extend_from_slice(&(v as i16).to_be_bytes()),
+                NumberScalar::Int16(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                NumberScalar::UInt16(v) => buf.extend_from_slice(&(v as i32).to_be_bytes()),
+                NumberScalar::Int32(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                NumberScalar::UInt32(v) => buf.extend_from_slice(&(v as i64).to_be_bytes()),
+                NumberScalar::Int64(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                NumberScalar::UInt64(v) => write_numeric(&v.to_string(), buf)?,
+                NumberScalar::Float32(v) => buf.extend_from_slice(&v.0.to_be_bytes()),
+                NumberScalar::Float64(v) => buf.extend_from_slice(&v.0.to_be_bytes()),
+            },
+            ScalarRef::Decimal(v) => write_numeric(&v.to_string(), buf)?,
+            ScalarRef::Date(v) => {
+                buf.extend_from_slice(&((v as i64 - PG_EPOCH_DAYS) as i32).to_be_bytes())
+            }
+            ScalarRef::Timestamp(v) => {
+                // The timestamp without time zone of postgres is the wall clock time, same
+                // as the text format in the session time zone.
+                let offset = Timestamp::from_microsecond(v)
+                    .map(|ts| self.jiff_timezone.to_offset(ts).seconds() as i64)
+                    .unwrap_or_default();
+                let micros = v + offset * 1_000_000 - PG_EPOCH_MICROS;
+                buf.extend_from_slice(&micros.to_be_bytes())
+            }
+            // The binary format of json is the same as the text format.
+            ScalarRef::Variant(_) => self.encoder.write_field(column, row_index, buf, false),
+            _ => {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Binary format of {} is not supported by postgres handler",
+                    column.data_type().remove_nullable()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write a decimal string like `-123.450` in the binary format of postgres numeric, which is
+/// the digits in base 10000 with the weight of the first digit.
+fn write_numeric(s: &str, buf: &mut Vec<u8>) -> Result<()> {
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => (NUMERIC_NEGATIVE, s),
+        None => (NUMERIC_POSITIVE, s),
+    };
+    let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+    if !int_part
+        .bytes()
+        .chain(frac_part.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return Err(ErrorCode::BadBytes(format!("Invalid numeric value {s}")));
+    }
+
+    let int_part = int_part.trim_start_matches('0');
+    // Pad the integer part on the left and the fraction part on the right to groups of 4.
+    let int_padded = format!("{}{}", "0".repeat((4 - int_part.len() % 4) % 4), int_part);
+    let frac_padded = format!("{}{}", frac_part, "0".repeat((4 - frac_part.len() % 4) % 4));
+    let group = |s: &str| -> Vec<i16> {
+        s.as_bytes()
+            .chunks(4)
+            .map(|c| std::str::from_utf8(c).unwrap().parse::<i16>().unwrap())
+            .collect()
+    };
+
+    let int_groups = group(&int_padded);
+    let mut weight = int_groups.len() as i16 - 1;
+    let mut digits = int_groups;
+    digits.extend(group(&frac_padded));
+
+    // Strip the leading and trailing zero digits.
+    let leading = digits.iter().take_while(|d| **d == 0).count();
+    digits.drain(..leading);
+    weight -= leading as i16;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    let sign = if digits.is_empty() {
+        weight = 0;
+        NUMERIC_POSITIVE
+    } else {
+        sign
+    };
+
+    buf.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    buf.extend_from_slice(&weight.to_be_bytes());
+    buf.extend_from_slice(&sign.to_be_bytes());
+    buf.extend_from_slice(&(frac_part.len() as i16).to_be_bytes());
+    for digit in digits {
+        buf.extend_from_slice(&digit.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Convert the value of a parameter in `Bind` to a SQL literal, which replaces the
+/// placeholder in the query.
+pub fn param_to_literal(type_oid: u32, format: i16, value: Option<&[u8]>) -> Result<String> {
+    let Some(value) = value else {
+        return Ok("NULL".to_string());
+    };
+
+    if format == FORMAT_BINARY {
+        return binary_param_to_literal(type_oid, value);
+    }
+
+    let text = std::str::from_utf8(value)
+        .map_err(|_| ErrorCode::BadBytes("Invalid postgres parameter, value is not utf8"))?;
+    match type_oid {
+        INT2_OID | INT4_OID | INT8_OID | OID_OID => {
+            text.trim().parse::<i64>().map_err(|_| {
+                ErrorCode::BadArguments(format!("Invalid input syntax for type integer: {text}"))
+            })?;
+            Ok(text.trim().to_string())
+        }
+        FLOAT4_OID | FLOAT8_OID | NUMERIC_OID => {
+            let v = text.trim().parse::<f64>().map_err(|_| {
+                ErrorCode::BadArguments(format!("Invalid input syntax for type numeric: {text}"))
+            })?;
+            if v.is_finite() {
+                Ok(text.trim().to_string())
+            } else {
+                Ok(format!("{}::DOUBLE", quote_string(text.trim())))
+            }
+        }
+        BOOL_OID => match text.trim().to_ascii_lowercase().as_str() {
+            "t" | "true" | "y" | "yes" | "on" | "1" => Ok("TRUE".to_string()),
+            "f" | "false" | "n" | "no" | "off" | "0" => Ok("FALSE".to_string()),
+            _ => Err(ErrorCode::BadArguments(format!(
+                "Invalid input syntax for type boolean: {text}"
+            ))),
+        },
+        BYTEA_OID => {
+            let hex = text.strip_prefix("\\x").ok_or_else(|| {
+                ErrorCode::BadArguments("Only the hex format of bytea parameter is supported")
+            })?;
+            Ok(format!("FROM_HEX({})", quote_string(hex)))
+        }
+        DATE_OID => Ok(format!("{}::DATE", quote_string(text))),
+        TIMESTAMP_OID | TIMESTAMPTZ_OID => Ok(format!("{}::TIMESTAMP", quote_string(text))),
+        // The parameters without a type are strings, which are casted by the planner.
+        _ => Ok(quote_string(text)),
+    }
+}
+
+fn binary_param_to_literal(type_oid: u32, value: &[u8]) -> Result<String> {
+    fn bytes<const N: usize>(value: &[u8]) -> Result<[u8; N]> {
+        value.try_into().map_err(|_| {
+            ErrorCode::BadBytes(format!(
+                "Invalid postgres parameter, expect {N} bytes but got {}",
+                value.len()
+            ))
+        })
+    }
+
+    match type_oid {
+        BOOL_OID => Ok(if bytes::<1>(value)?[0] != 0 {
+            "TRUE".to_string()
+        } else {
+            "FALSE".to_string()
+        }),
+        INT2_OID => Ok(i16::from_be_bytes(bytes(value)?).to_string()),
+        INT4_OID | OID_OID => Ok(i32::from_be_bytes(bytes(value)?).to_string()),
+        INT8_OID => Ok(i64::from_be_bytes(bytes(value)?).to_string()),
+        FLOAT4_OID => float_literal(f32::from_be_bytes(bytes(value)?) as f64),
+        FLOAT8_OID => float_literal(f64::from_be_bytes(bytes(value)?)),
+        BYTEA_OID => Ok(format!("FROM_HEX('{}')", hex::encode(value))),
+        DATE_OID => {
+            let days = i32::from_be_bytes(bytes(value)?) as i64;
+            let date = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap() + Duration::days(days);
+            Ok(format!("'{}'::DATE", date.format("%Y-%m-%d")))
+        }
+        TIMESTAMP_OID | TIMESTAMPTZ_OID => {
+            let micros = i64::from_be_bytes(bytes(value)?);
+            let epoch = NaiveDate::from_ymd_opt(2000, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap();
+            let ts: NaiveDateTime = epoch + Duration::microseconds(micros);
+            let suffix = if type_oid == TIMESTAMPTZ_OID {
+                "+00:00"
+            } else {
+                ""
+            };
+            Ok(format!(
+                "'{}{suffix}'::TIMESTAMP",
+                ts.format("%Y-%m-%d %H:%M:%S%.6f")
+            ))
+        }
+        TEXT_OID | VARCHAR_OID | JSON_OID | UNSPECIFIED_OID => {
+            let text = std::str::from_utf8(value).map_err(|_| {
+                ErrorCode::BadBytes("Invalid postgres parameter, value is not utf8")
+            })?;
+            Ok(quote_string(text))
+        }
+        oid => Err(ErrorCode::Unimplemented(format!(
+            "Binary format of parameter type {oid} is not supported by postgres handler"
+        ))),
+    }
+}
+
+fn float_literal(v: f64) -> Result<String> {
+    if v.is_finite() {
+        Ok(v.to_string())
+    } else if v.is_nan() {
+        Ok("'NaN'::DOUBLE".to_string())
+    } else if v > 0.0 {
+        Ok("'Infinity'::DOUBLE".to_string())
+    } else {
+        Ok("'-Infinity'::DOUBLE".to_string())
+    }
+}
+
+fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric(s: &str) -> Vec<i16> {
+        let mut buf = vec![];
+        write_numeric(s, &mut buf).unwrap();
+        buf.chunks(2)
+            .map(|c| i16::from_be_bytes([c[0], c[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_write_numeric() {
+        // ndigits, weight, sign, dscale, digits...
+        assert_eq!(numeric("0"), vec![0, 0, 0, 0]);
+        assert_eq!(numeric("0.00"), vec![0, 0, 0, 2]);
+        assert_eq!(numeric("12345.678"), vec![3, 1, 0, 3, 1, 2345, 6780]);
+        assert_eq!(numeric("-0.0001"), vec![1, -1, 0x4000, 4, 1]);
+        assert_eq!(numeric("10000"), vec![1, 1, 0, 0, 1]);
+        assert!(write_numeric("1e10", &mut vec![]).is_err());
+    }
+
+    #[test]
+    fn test_param_to_literal() -> Result<()> {
+        assert_eq!(param_to_literal(INT4_OID, FORMAT_TEXT, None)?, "NULL");
+        assert_eq!(param_to_literal(INT4_OID, FORMAT_TEXT, Some(b" 42"))?, "42");
+        assert!(param_to_literal(INT4_OID, FORMAT_TEXT, Some(b"1; DROP TABLE t")).is_err());
+        assert_eq!(
+            param_to_literal(UNSPECIFIED_OID, FORMAT_TEXT, Some(b"it's \\"))?,
+            "'it''s \\\\'"
+        );
+        assert_eq!(
+            param_to_literal(FLOAT8_OID, FORMAT_TEXT, Some(b"NaN"))?,
+            "'NaN'::DOUBLE"
+        );
+        assert_eq!(param_to_literal(BOOL_OID, FORMAT_TEXT, Some(b"t"))?, "TRUE");
+        assert_eq!(
+            param_to_literal(INT8_OID, FORMAT_BINARY, Some(&(-7i64).to_be_bytes()))?,
+            "-7"
+        );
+        assert_eq!(
+            param_to_literal(DATE_OID, FORMAT_BINARY, Some(&366i32.to_be_bytes()))?,
+            "'2001-01-01'::DATE"
+        );
+        assert_eq!(
+            param_to_literal(BYTEA_OID, FORMAT_BINARY, Some(&[0xab, 0x01]))?,
+            "FROM_HEX('ab01')"
+        );
+        Ok(())
+    }
+}
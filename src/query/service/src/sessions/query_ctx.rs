@@ -15,6 +15,7 @@
 use std::any::Any;
 use std::cmp::min;
 use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
@@ -31,6 +32,7 @@ use std::time::UNIX_EPOCH;
 use chrono_tz::Tz;
 use dashmap::mapref::multiple::RefMulti;
 use dashmap::DashMap;
+use databend_common_ast::ast::Statement;
 use databend_common_base::base::Progress;
 use databend_common_base::base::ProgressValues;
 use databend_common_base::base::SpillProgress;
@@ -102,6 +104,7 @@ use databend_common_storage::StageFileInfo;
 use databend_common_storage::StageFilesInfo;
 use databend_common_storage::StorageMetrics;
 use databend_common_storages_delta::DeltaTable;
+use databend_common_storages_delta::DeltaVersion;
 use databend_common_storages_fuse::FuseTable;
 use databend_common_storages_fuse::TableContext;
 use databend_common_storages_iceberg::IcebergTable;
@@ -1564,15 +1567,41 @@ impl TableContext for QueryContext {
         self.shared.session.session_ctx.get_all_variables()
     }
 
+    fn set_prepared_statement(&self, name: String, statement: Statement) {
+        self.shared
+            .session
+            .session_ctx
+            .set_prepared_statement(name, statement)
+    }
+
+    fn unset_prepared_statement(&self, name: &str) -> bool {
+        self.shared
+            .session
+            .session_ctx
+            .unset_prepared_statement(name)
+    }
+
+    fn get_prepared_statement(&self, name: &str) -> Option<Statement> {
+        self.shared.session.session_ctx.get_prepared_statement(name)
+    }
+
+    fn clear_prepared_statements(&self) {
+        self.shared
+            .session
+            .session_ctx
+            .set_all_prepared_statements(HashMap::new())
+    }
+
     #[async_backtrace::framed]
     async fn load_datalake_schema(
         &self,
         kind: &str,
         sp: &StorageParams,
+        options: &BTreeMap<String, String>,
     ) -> Result<(TableSchema, String)> {
         match kind {
             "delta" => {
-                let table = DeltaTable::load(sp).await?;
+                let table = DeltaTable::load(sp, DeltaVersion::from_options(options)?).await?;
                 DeltaTable::get_meta(&table).await
             }
             // TODO: iceberg doesn't support load from storage directly.
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use databend_common_ast::ast::Statement;
 use databend_common_base::runtime::drop_guard;
 use databend_common_base::runtime::MemStat;
 use databend_common_base::runtime::ThreadTracker;
@@ -409,6 +410,14 @@ impl Session {
         self.session_ctx.set_all_variables(variables)
     }
 
+    pub fn get_all_prepared_statements(&self) -> HashMap<String, Statement> {
+        self.session_ctx.get_all_prepared_statements()
+    }
+
+    pub fn set_all_prepared_statements(&self, statements: HashMap<String, Statement>) {
+        self.session_ctx.set_all_prepared_statements(statements)
+    }
+
     pub fn get_client_session_id(&self) -> Option<String> {
         self.session_ctx.get_client_session_id()
     }
@@ -419,7 +428,7 @@ impl Session {
     pub fn get_temp_table_prefix(&self) -> Result<String> {
         let typ = self.typ.read().clone();
         let session_id = match typ {
-            SessionType::MySQL => self.id.clone(),
+            SessionType::MySQL | SessionType::Postgres => self.id.clone(),
             SessionType::HTTPQuery => {
                 if let Some(id) = self.get_client_session_id() {
                     id
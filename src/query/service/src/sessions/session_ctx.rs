@@ -19,6 +19,7 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Weak;
 
+use databend_common_ast::ast::Statement;
 use databend_common_config::GlobalConfig;
 use databend_common_exception::Result;
 use databend_common_expression::Scalar;
@@ -74,6 +75,8 @@ pub struct SessionContext {
     query_ids_results: RwLock<Vec<(String, Option<String>)>>,
     // Used in set variables inside session
     variables: Arc<RwLock<HashMap<String, Scalar>>>,
+    // Used in PREPARE and EXECUTE inside session
+    prepared_statements: Arc<RwLock<HashMap<String, Statement>>>,
     typ: SessionType,
     txn_mgr: Mutex<TxnManagerRef>,
     temp_tbl_mgr: Mutex<TempTblMgrRef>,
@@ -103,6 +106,7 @@ impl SessionContext {
             query_context_shared: Default::default(),
             query_ids_results: Default::default(),
             variables: Default::default(),
+            prepared_statements: Default::default(),
             typ,
             txn_mgr: Mutex::new(TxnManager::init()),
             client_session_id: Default::default(),
@@ -355,6 +359,24 @@ impl SessionContext {
         *self.variables.write() = variables
     }
 
+    pub fn set_prepared_statement(&self, name: String, statement: Statement) {
+        self.prepared_statements.write().insert(name, statement);
+    }
+
+    pub fn unset_prepared_statement(&self, name: &str) -> bool {
+        self.prepared_statements.write().remove(name).is_some()
+    }
+
+    pub fn get_prepared_statement(&self, name: &str) -> Option<Statement> {
+        self.prepared_statements.read().get(name).cloned()
+    }
+    pub fn get_all_prepared_statements(&self) -> HashMap<String, Statement> {
+        self.prepared_statements.read().clone()
+    }
+    pub fn set_all_prepared_statements(&self, statements: HashMap<String, Statement>) {
+        *self.prepared_statements.write() = statements
+    }
+
     pub fn session_state(&self) -> SessionState {
         SessionState {
             txn_mgr: self.txn_mgr(),
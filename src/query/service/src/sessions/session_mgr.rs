@@ -92,7 +92,7 @@ impl SessionManager {
             self.validate_max_active_sessions(sessions.len(), "active sessions")?;
         }
 
-        if matches!(typ, SessionType::MySQL) {
+        if matches!(typ, SessionType::MySQL | SessionType::Postgres) {
             let mysql_conn_map = self.mysql_conn_map.read();
             self.validate_max_active_sessions(mysql_conn_map.len(), "mysql conns")?;
         }
@@ -133,7 +133,10 @@ impl SessionManager {
     ) -> Result<Session> {
         let id = uuid::Uuid::new_v4().to_string();
         let mysql_conn_id = match typ {
-            SessionType::MySQL => Some(self.mysql_basic_conn_id.fetch_add(1, Ordering::Relaxed)),
+            // The connection id of postgres sessions is used as the backend process id.
+            SessionType::MySQL | SessionType::Postgres => {
+                Some(self.mysql_basic_conn_id.fetch_add(1, Ordering::Relaxed))
+            }
             _ => None,
         };
 
@@ -159,7 +162,7 @@ impl SessionManager {
         let session = Arc::new(session);
         self.try_add_session(session.clone(), typ.clone())?;
 
-        if let SessionType::MySQL = typ {
+        if let SessionType::MySQL | SessionType::Postgres = typ {
             let mut mysql_conn_map = self.mysql_conn_map.write();
             self.validate_max_active_sessions(mysql_conn_map.len(), "mysql conns")?;
 
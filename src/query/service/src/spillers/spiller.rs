@@ -48,6 +48,7 @@ pub enum SpillerType {
     Window,
     OrderBy,
     Aggregation,
+    AsofJoin,
 }
 
 impl Display for SpillerType {
@@ -58,6 +59,7 @@ impl Display for SpillerType {
             SpillerType::Window => write!(f, "Window"),
             SpillerType::OrderBy => write!(f, "OrderBy"),
             SpillerType::Aggregation => write!(f, "Aggregation"),
+            SpillerType::AsofJoin => write!(f, "AsofJoin"),
         }
     }
 }
@@ -16,6 +16,7 @@ use std::any::Any;
 use std::sync::Arc;
 
 use chrono::DateTime;
+use chrono::Utc;
 use databend_common_catalog::plan::DataSourcePlan;
 use databend_common_catalog::plan::PartStatistics;
 use databend_common_catalog::plan::Partitions;
@@ -43,8 +44,11 @@ use databend_common_pipeline_sources::AsyncSource;
 use databend_common_pipeline_sources::AsyncSourcer;
 use databend_common_sql::plans::task_run_schema;
 use databend_common_storages_factory::Table;
+use databend_common_storages_system::list_local_task_runs;
 use databend_common_storages_system::parse_task_runs_to_datablock;
+use databend_common_storages_system::LocalTaskRunFilter;
 use jiff::tz::TimeZone;
+use jiff::Zoned;
 
 pub struct TaskHistoryTable {
     table_info: TableInfo,
@@ -188,9 +192,10 @@ impl AsyncSource for TaskHistorySource {
         self.is_finished = true;
         let config = GlobalConfig::instance();
         if config.query.cloud_control_grpc_server_address.is_none() {
-            return Err(ErrorCode::CloudControlNotEnabled(
-                "cannot view system.task_history table without cloud control enabled, please set cloud_control_grpc_server_address in config",
-            ));
+            // Task runs are recorded by the built-in task scheduler.
+            let filter = self.args_parsed.local_filter()?;
+            let trs = list_local_task_runs(&self.ctx, &filter).await?;
+            return parse_task_runs_to_datablock(trs).map(Some);
         }
         let cloud_api = CloudControlApiProvider::instance();
         let tenant = self.ctx.get_tenant();
@@ -312,4 +317,37 @@ impl TableHistoryArgsParsed {
             root_task_id,
         })
     }
+
+    fn local_filter(&self) -> Result<LocalTaskRunFilter> {
+        let parse_time = |s: &Option<String>| -> Result<Option<DateTime<Utc>>> {
+            let Some(s) = s else {
+                return Ok(None);
+            };
+            let zoned = s.parse::<Zoned>().map_err(|e| {
+                ErrorCode::BadArguments(format!("invalid scheduled time {}: {}", s, e))
+            })?;
+            Ok(DateTime::from_timestamp_micros(
+                zoned.timestamp().as_microsecond(),
+            ))
+        };
+        let root_task_id = match &self.root_task_id {
+            Some(id) => Some(
+                id.parse::<u64>()
+                    .map_err(|_| ErrorCode::BadArguments(format!("invalid root_task_id {}", id)))?,
+            ),
+            None => None,
+        };
+        Ok(LocalTaskRunFilter {
+            task_name: self.task_name.clone(),
+            scheduled_time_start: parse_time(&self.scheduled_time_range_start)?,
+            scheduled_time_end: parse_time(&self.scheduled_time_range_end)?,
+            error_only: self.error_only.unwrap_or(false),
+            root_task_id,
+            // 0 means default, the same as the cloud control plane
+            result_limit: self
+                .result_limit
+                .filter(|limit| *limit > 0)
+                .map(|limit| limit as usize),
+        })
+    }
 }
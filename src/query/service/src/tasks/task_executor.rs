@@ -0,0 +1,264 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use chrono::Utc;
+use databend_common_base::runtime::drop_guard;
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_expression::ScalarRef;
+use databend_common_meta_app::principal::TaskInfo;
+use databend_common_meta_app::principal::TaskRun;
+use databend_common_meta_app::principal::TaskRunState;
+use databend_common_meta_app::principal::TaskStatus;
+use databend_common_meta_app::principal::UserInfo;
+use databend_common_meta_app::tenant::Tenant;
+use databend_common_users::UserApiProvider;
+use databend_common_users::BUILTIN_ROLE_PUBLIC;
+use log::info;
+use log::warn;
+use uuid::Uuid;
+
+use crate::interpreters::execute_statement;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionType;
+
+/// Run the task graph rooted at `root` once.
+///
+/// `root` runs first, then every started task runs after all the tasks in its `AFTER` list
+/// have succeeded in this run of the graph. A task whose `WHEN` condition is not true
+/// is skipped, together with the tasks after it.
+#[async_backtrace::framed]
+pub async fn execute_task_graph(tenant: &Tenant, root: TaskInfo) -> Result<()> {
+    let tasks = UserApiProvider::instance().get_tasks(tenant).await?;
+    let root_task_id = root.task_id;
+
+    let mut succeeded = HashSet::new();
+    let mut pending = VecDeque::from([root]);
+    while let Some(task) = pending.pop_front() {
+        if !execute_task(tenant, &task, root_task_id).await? {
+            continue;
+        }
+        succeeded.insert(task.task_name.clone());
+
+        for next in &tasks {
+            if next.status == TaskStatus::Started
+                && next.after.contains(&task.task_name)
+                && next.after.iter().all(|name| succeeded.contains(name))
+            {
+                pending.push_back(next.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run a task once and record the run, returns true if the task succeeded.
+#[async_backtrace::framed]
+async fn execute_task(tenant: &Tenant, task: &TaskInfo, root_task_id: u64) -> Result<bool> {
+    let user_api = UserApiProvider::instance();
+    let scheduled_at = Utc::now();
+    let session = create_task_session(tenant, task).await?;
+
+    if let Some(condition) = &task.when_condition {
+        let ctx = session.create_query_context().await?;
+        if !evaluate_condition(ctx, condition).await? {
+            info!(
+                "Task {} skipped, condition {} is not true",
+                task.task_name, condition
+            );
+            return Ok(false);
+        }
+    }
+
+    let run = TaskRun {
+        run_id: Uuid::new_v4().simple().to_string(),
+        task_id: task.task_id,
+        task_name: task.task_name.clone(),
+        query_text: task.query_text.clone(),
+        condition_text: task.when_condition.clone().unwrap_or_default(),
+        owner: task.owner.clone(),
+        comment: task.comment.clone(),
+        schedule_options: task.schedule_options.clone(),
+        warehouse: task.warehouse.clone(),
+        attempt_number: 1,
+        state: TaskRunState::Executing,
+        error_code: 0,
+        error_message: None,
+        query_id: String::new(),
+        root_task_id,
+        session_params: task.session_params.clone(),
+        scheduled_at,
+        completed_at: None,
+    };
+    user_api.upsert_task_run(tenant, run.clone()).await?;
+    let mut guard = TaskRunGuard {
+        tenant: tenant.clone(),
+        run: Some(run),
+    };
+
+    let mut result = Ok(());
+    for sql in task.statements() {
+        let ctx = session.create_query_context().await?;
+        guard.run_mut().query_id = ctx.get_id();
+        result = execute_statement(ctx, &sql).await.map(|_| ());
+        if result.is_err() {
+            break;
+        }
+    }
+
+    let mut run = guard.complete();
+    run.completed_at = Some(Utc::now());
+    match &result {
+        Ok(_) => {
+            info!("Task {} succeeded", task.task_name);
+            run.state = TaskRunState::Succeeded;
+        }
+        Err(cause) => {
+            warn!("Task {} failed: {:?}", task.task_name, cause);
+            run.state = TaskRunState::Failed;
+            run.error_code = cause.code() as i64;
+            run.error_message = Some(cause.message());
+        }
+    }
+    user_api.upsert_task_run(tenant, run).await?;
+
+    if result.is_err() {
+        suspend_task_if_keeps_failing(tenant, task).await?;
+    }
+    Ok(result.is_ok())
+}
+
+/// Records the run as cancelled if it is dropped before it completes,
+/// e.g. the task graph is aborted when the task scheduler lease is lost.
+struct TaskRunGuard {
+    tenant: Tenant,
+    run: Option<TaskRun>,
+}
+
+impl TaskRunGuard {
+    fn run_mut(&mut self) -> &mut TaskRun {
+        self.run.as_mut().unwrap()
+    }
+
+    fn complete(mut self) -> TaskRun {
+        self.run.take().unwrap()
+    }
+}
+
+impl Drop for TaskRunGuard {
+    fn drop(&mut self) {
+        drop_guard(move || {
+            let Some(mut run) = self.run.take() else {
+                return;
+            };
+            warn!("Task {} is cancelled", run.task_name);
+            run.state = TaskRunState::Cancelled;
+            run.completed_at = Some(Utc::now());
+            let tenant = self.tenant.clone();
+            GlobalIORuntime::instance().spawn(async move {
+                if let Err(cause) = UserApiProvider::instance()
+                    .upsert_task_run(&tenant, run)
+                    .await
+                {
+                    warn!("Failed to record the cancelled task run: {:?}", cause);
+                }
+            });
+        })
+    }
+}
+
+/// Suspend the task if its last `SUSPEND_TASK_AFTER_NUM_FAILURES` runs all failed.
+#[async_backtrace::framed]
+async fn suspend_task_if_keeps_failing(tenant: &Tenant, task: &TaskInfo) -> Result<()> {
+    let Some(num_failures) = task.suspend_task_after_num_failures.filter(|n| *n > 0) else {
+        return Ok(());
+    };
+
+    let user_api = UserApiProvider::instance();
+    let mut runs = user_api
+        .get_task_runs(tenant)
+        .await?
+        .into_iter()
+        .filter(|run| run.task_id == task.task_id && run.state != TaskRunState::Executing)
+        .collect::<Vec<_>>();
+    runs.sort_by(|a, b| b.scheduled_at.cmp(&a.scheduled_at));
+
+    let num_failures = num_failures as usize;
+    if runs.len() < num_failures
+        || runs[..num_failures]
+            .iter()
+            .any(|run| run.state != TaskRunState::Failed)
+    {
+        return Ok(());
+    }
+
+    warn!(
+        "Task {} is suspended after {} consecutive failures",
+        task.task_name, num_failures
+    );
+    user_api
+        .update_task(tenant, &task.task_name, true, |task| {
+            task.status = TaskStatus::Suspended;
+            task.last_suspended_at = Some(Utc::now());
+            Ok(())
+        })
+        .await?;
+    Ok(())
+}
+
+#[async_backtrace::framed]
+async fn evaluate_condition(ctx: Arc<QueryContext>, condition: &str) -> Result<bool> {
+    let blocks = execute_statement(ctx, &format!("SELECT {}", condition)).await?;
+    let block = DataBlock::concat(&blocks)?;
+    if block.num_rows() != 1 || block.num_columns() != 1 {
+        return Err(ErrorCode::BadArguments(format!(
+            "Expect a scalar result of the task condition {}, but got {} rows and {} columns",
+            condition,
+            block.num_rows(),
+            block.num_columns()
+        )));
+    }
+    let value = block.get_by_offset(0).value.index(0).unwrap();
+    Ok(matches!(value, ScalarRef::Boolean(true)))
+}
+
+/// Create a session to run the task, the session can only use the owner role of the task.
+async fn create_task_session(tenant: &Tenant, task: &TaskInfo) -> Result<Arc<Session>> {
+    let session_manager = SessionManager::instance();
+    let session = session_manager.create_session(SessionType::Dummy).await?;
+    let session = session_manager.register_session(session)?;
+
+    let user = UserInfo::new_no_auth(&format!("{}-task-svc", tenant.tenant_name()), "0.0.0.0");
+    let role = if task.owner.is_empty() {
+        BUILTIN_ROLE_PUBLIC.to_string()
+    } else {
+        task.owner.clone()
+    };
+    session.set_authed_user(user, Some(role)).await?;
+
+    let settings = session.get_settings();
+    for (name, value) in &task.session_params {
+        settings.set_setting(name.clone(), value.clone())?;
+    }
+    Ok(session)
+}
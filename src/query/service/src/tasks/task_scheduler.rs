@@ -0,0 +1,195 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use chrono::DateTime;
+use chrono::Utc;
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_config::InnerConfig;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::TaskStatus;
+use databend_common_users::UserApiProvider;
+use futures::future::AbortHandle;
+use futures::future::Abortable;
+use log::info;
+use log::warn;
+use parking_lot::Mutex;
+
+use crate::tasks::execute_task_graph;
+
+/// How often the scheduler checks for due tasks.
+const TASK_SCHEDULER_TICK: Duration = Duration::from_secs(1);
+
+/// How long the scheduler lease lasts if it is not renewed.
+const TASK_SCHEDULER_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// How often the lease is renewed by its holder, or tried to be acquired by the other nodes.
+const TASK_SCHEDULER_LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The built-in scheduler of the tasks created by `CREATE TASK`,
+/// used when the cloud control plane is not configured.
+///
+/// Every node of the cluster runs the loop, but only the node holding the scheduler lease
+/// in the meta-service runs the tasks. The lease expires if its holder stops renewing it,
+/// then another node takes over, the task graphs still running on the former holder are
+/// aborted as soon as it finds the lease lost, so they do not run on two nodes at once.
+/// The runs of an aborted graph are recorded as cancelled.
+pub struct TaskScheduler {
+    conf: InnerConfig,
+    is_leader: bool,
+    lease_checked_at: Option<Instant>,
+    /// The next scheduled time of the root tasks by task id,
+    /// along with the `updated_at` of the task it is computed from.
+    next_runs: HashMap<u64, (DateTime<Utc>, DateTime<Utc>)>,
+    /// The root tasks whose graph is running, they are not scheduled again until it finishes.
+    running: Arc<Mutex<HashMap<u64, AbortHandle>>>,
+}
+
+impl TaskScheduler {
+    pub fn start(conf: InnerConfig) {
+        let scheduler = TaskScheduler {
+            conf,
+            is_leader: false,
+            lease_checked_at: None,
+            next_runs: HashMap::new(),
+            running: Arc::new(Mutex::new(HashMap::new())),
+        };
+        GlobalIORuntime::instance().spawn(async move { scheduler.run().await });
+    }
+
+    async fn run(mut self) {
+        loop {
+            tokio::time::sleep(TASK_SCHEDULER_TICK).await;
+
+            if let Err(cause) = self.schedule_tasks().await {
+                warn!("Failed to schedule tasks: {:?}", cause);
+            }
+        }
+    }
+
+    #[async_backtrace::framed]
+    async fn check_lease(&mut self) {
+        if self
+            .lease_checked_at
+            .is_some_and(|t| t.elapsed() < TASK_SCHEDULER_LEASE_RENEW_INTERVAL)
+        {
+            return;
+        }
+
+        let is_leader = UserApiProvider::instance()
+            .acquire_task_scheduler_lease(
+                &self.conf.query.tenant_id,
+                &self.conf.query.node_id,
+                TASK_SCHEDULER_LEASE_TTL,
+            )
+            .await;
+        self.lease_checked_at = Some(Instant::now());
+
+        // Step down if the lease can not be renewed, it may be taken over by another node.
+        let is_leader = is_leader.unwrap_or_else(|cause| {
+            warn!("Failed to acquire the task scheduler lease: {:?}", cause);
+            false
+        });
+        if is_leader != self.is_leader {
+            info!("Task scheduler lease acquired: {}", is_leader);
+            self.next_runs.clear();
+        }
+        if !is_leader {
+            for (task_id, handle) in self.running.lock().drain() {
+                warn!(
+                    "Task {} is aborted, the task scheduler lease is lost",
+                    task_id
+                );
+                handle.abort();
+            }
+        }
+        self.is_leader = is_leader;
+    }
+
+    #[async_backtrace::framed]
+    async fn schedule_tasks(&mut self) -> Result<()> {
+        self.check_lease().await;
+        if !self.is_leader {
+            return Ok(());
+        }
+
+        let tenant = &self.conf.query.tenant_id;
+        let tasks = UserApiProvider::instance().get_tasks(tenant).await?;
+        let now = Utc::now();
+
+        let mut next_runs = HashMap::with_capacity(tasks.len());
+        for task in tasks {
+            // Tasks with predecessors are run by the graph of their root task.
+            if task.status != TaskStatus::Started || !task.after.is_empty() {
+                continue;
+            }
+            let Some(schedule) = &task.schedule_options else {
+                continue;
+            };
+
+            // The schedule restarts from now if the task is created or altered.
+            let next_run = match self.next_runs.get(&task.task_id) {
+                Some((updated_at, next_run)) if *updated_at == task.updated_at => Some(*next_run),
+                _ => schedule.next_time_after(now),
+            };
+            let Some(next_run) = next_run else {
+                warn!("Task {} has an invalid schedule", task.task_name);
+                continue;
+            };
+            if next_run > now {
+                next_runs.insert(task.task_id, (task.updated_at, next_run));
+                continue;
+            }
+
+            if let Some(following) = schedule.next_time_after(now) {
+                next_runs.insert(task.task_id, (task.updated_at, following));
+            }
+            let (abort_handle, registration) = AbortHandle::new_pair();
+            {
+                let mut running = self.running.lock();
+                if running.contains_key(&task.task_id) {
+                    info!(
+                        "Task {} is skipped, the previous run is still running",
+                        task.task_name
+                    );
+                    continue;
+                }
+                running.insert(task.task_id, abort_handle.clone());
+            }
+
+            let tenant = tenant.clone();
+            let running = self.running.clone();
+            GlobalIORuntime::instance().spawn(async move {
+                let task_id = task.task_id;
+                let task_name = task.task_name.clone();
+                let graph = Abortable::new(execute_task_graph(&tenant, task), registration);
+                match graph.await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(cause)) => warn!("Failed to run task {}: {:?}", task_name, cause),
+                    Err(_) => info!("Task {} is aborted", task_name),
+                }
+                // The entry of an aborted run is removed already, and may be replaced by a later run.
+                if !abort_handle.is_aborted() {
+                    running.lock().remove(&task_id);
+                }
+            });
+        }
+        self.next_runs = next_runs;
+        Ok(())
+    }
+}
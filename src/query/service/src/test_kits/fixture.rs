@@ -184,6 +184,7 @@ impl TestFixture {
             hash_method: PasswordHashMethod::Sha256,
             hash_value: Vec::from("pass"),
             need_change: false,
+            scram_verifier: None,
         });
 
         user_info.grants.grant_privileges(
@@ -201,6 +202,7 @@ impl TestFixture {
             hash_method: PasswordHashMethod::Sha256,
             hash_value: Vec::from("pass"),
             need_change: false,
+            scram_verifier: None,
         });
 
         user_info.grants.grant_privileges(
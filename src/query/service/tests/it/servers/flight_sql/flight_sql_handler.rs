@@ -16,12 +16,35 @@
 
 use std::fs;
 use std::io::Write;
+use std::sync::Arc;
 
+use arrow_array::cast::AsArray;
+use arrow_array::Int32Array;
 use arrow_array::RecordBatch;
+use arrow_array::StringArray;
 use arrow_cast::pretty::pretty_format_batches;
+use arrow_flight::error::FlightError;
 use arrow_flight::flight_service_server::FlightServiceServer;
 use arrow_flight::sql::client::FlightSqlServiceClient;
+use arrow_flight::sql::command_statement_ingest::table_definition_options::TableExistsOption;
+use arrow_flight::sql::command_statement_ingest::table_definition_options::TableNotExistOption;
+use arrow_flight::sql::command_statement_ingest::TableDefinitionOptions;
+use arrow_flight::sql::CommandGetCrossReference;
+use arrow_flight::sql::CommandGetDbSchemas;
+use arrow_flight::sql::CommandGetExportedKeys;
+use arrow_flight::sql::CommandGetImportedKeys;
+use arrow_flight::sql::CommandGetPrimaryKeys;
+use arrow_flight::sql::CommandGetTables;
+use arrow_flight::sql::CommandGetXdbcTypeInfo;
+use arrow_flight::sql::CommandStatementIngest;
+use arrow_flight::sql::CommandStatementSubstraitPlan;
+use arrow_flight::sql::SubstraitPlan;
+use arrow_flight::sql::XdbcDataType;
+use arrow_flight::FlightInfo;
 use arrow_schema::ArrowError;
+use arrow_schema::DataType;
+use arrow_schema::Field;
+use arrow_schema::Schema;
 use databend_common_base::base::tokio;
 use databend_common_base::runtime::Runtime;
 use databend_common_config::InnerConfig;
@@ -36,6 +59,49 @@ use futures::TryStreamExt;
 use goldenfile::Mint;
 use hyper_util::rt::TokioIo;
 use log::debug;
+use prost::Message;
+use substrait::proto::expression::field_reference::ReferenceType;
+use substrait::proto::expression::field_reference::RootReference;
+use substrait::proto::expression::field_reference::RootType;
+use substrait::proto::expression::literal::LiteralType;
+use substrait::proto::expression::reference_segment;
+use substrait::proto::expression::FieldReference;
+use substrait::proto::expression::Literal;
+use substrait::proto::expression::ReferenceSegment;
+use substrait::proto::expression::RexType;
+use substrait::proto::expression::ScalarFunction;
+use substrait::proto::extensions::simple_extension_declaration::ExtensionFunction;
+use substrait::proto::extensions::simple_extension_declaration::MappingType;
+use substrait::proto::extensions::SimpleExtensionDeclaration;
+use substrait::proto::fetch_rel::CountMode;
+use substrait::proto::function_argument::ArgType;
+use substrait::proto::plan_rel;
+use substrait::proto::r#type;
+use substrait::proto::r#type::Kind;
+use substrait::proto::r#type::Nullability;
+use substrait::proto::read_rel::NamedTable;
+use substrait::proto::read_rel::ReadType;
+use substrait::proto::rel::RelType;
+use substrait::proto::rel_common;
+use substrait::proto::rel_common::EmitKind;
+use substrait::proto::sort_field::SortDirection;
+use substrait::proto::sort_field::SortKind;
+use substrait::proto::Expression;
+use substrait::proto::FetchRel;
+use substrait::proto::FilterRel;
+use substrait::proto::FunctionArgument;
+use substrait::proto::NamedStruct;
+use substrait::proto::Plan as SubstraitProtoPlan;
+use substrait::proto::PlanRel;
+use substrait::proto::ProjectRel;
+use substrait::proto::ReadRel;
+use substrait::proto::Rel;
+use substrait::proto::RelCommon;
+use substrait::proto::RelRoot;
+use substrait::proto::SetRel;
+use substrait::proto::SortField;
+use substrait::proto::SortRel;
+use substrait::proto::Type;
 use tempfile::NamedTempFile;
 use tokio::net::UnixListener;
 use tokio::net::UnixStream;
@@ -82,6 +148,182 @@ async fn run_query(
     Ok(res)
 }
 
+async fn fetch_column(
+    client: &mut FlightSqlServiceClient<Channel>,
+    flight_info: FlightInfo,
+    column: &str,
+) -> Vec<String> {
+    let ticket = flight_info.endpoint[0].ticket.as_ref().unwrap().clone();
+    let flight_data = client.do_get(ticket).await.unwrap();
+    let batches: Vec<RecordBatch> = flight_data.try_collect().await.unwrap();
+    let mut values = vec![];
+    for batch in batches {
+        let array = batch.column_by_name(column).unwrap().as_string::<i32>();
+        values.extend(array.iter().map(|v| v.unwrap().to_string()));
+    }
+    values
+}
+
+fn ingest_command(
+    table: &str,
+    if_not_exist: TableNotExistOption,
+    if_exists: TableExistsOption,
+) -> CommandStatementIngest {
+    CommandStatementIngest {
+        table_definition_options: Some(TableDefinitionOptions {
+            if_not_exist: if_not_exist.into(),
+            if_exists: if_exists.into(),
+        }),
+        table: table.to_string(),
+        schema: None,
+        catalog: None,
+        temporary: false,
+        transaction_id: None,
+        options: Default::default(),
+    }
+}
+
+fn ingest_batch(start: i32, rows: i32) -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let a = Int32Array::from_iter_values(start..start + rows);
+    let b = StringArray::from_iter_values((start..start + rows).map(|v| format!("v{v}")));
+    RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(b)]).unwrap()
+}
+
+fn substrait_field(field: i32) -> Expression {
+    let segment = ReferenceSegment {
+        reference_type: Some(reference_segment::ReferenceType::StructField(Box::new(
+            reference_segment::StructField { field, child: None },
+        ))),
+    };
+    Expression {
+        rex_type: Some(RexType::Selection(Box::new(FieldReference {
+            reference_type: Some(ReferenceType::DirectReference(segment)),
+            root_type: Some(RootType::RootReference(RootReference {})),
+        }))),
+    }
+}
+
+fn substrait_command(input: Rel, names: &[&str]) -> CommandStatementSubstraitPlan {
+    let gt = ExtensionFunction {
+        function_anchor: 1,
+        name: "gt:i32_i32".to_string(),
+        ..Default::default()
+    };
+    let plan = SubstraitProtoPlan {
+        extensions: vec![SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(gt)),
+        }],
+        relations: vec![PlanRel {
+            rel_type: Some(plan_rel::RelType::Root(RelRoot {
+                input: Some(input),
+                names: names.iter().map(|name| name.to_string()).collect(),
+            })),
+        }],
+        ..Default::default()
+    };
+    CommandStatementSubstraitPlan {
+        plan: Some(SubstraitPlan {
+            plan: plan.encode_to_vec().into(),
+            version: "0.50".to_string(),
+        }),
+        transaction_id: None,
+    }
+}
+
+// select b from substrait_t1 where a > 1 order by a desc limit 2
+fn substrait_query() -> Rel {
+    let nullable = Nullability::Nullable as i32;
+    let read = ReadRel {
+        base_schema: Some(NamedStruct {
+            names: vec!["a".to_string(), "b".to_string()],
+            r#struct: Some(r#type::Struct {
+                types: vec![
+                    Type {
+                        kind: Some(Kind::I32(r#type::I32 {
+                            nullability: nullable,
+                            ..Default::default()
+                        })),
+                    },
+                    Type {
+                        kind: Some(Kind::String(r#type::String {
+                            nullability: nullable,
+                            ..Default::default()
+                        })),
+                    },
+                ],
+                ..Default::default()
+            }),
+        }),
+        read_type: Some(ReadType::NamedTable(NamedTable {
+            names: vec!["default".to_string(), "substrait_t1".to_string()],
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let condition = Expression {
+        rex_type: Some(RexType::ScalarFunction(ScalarFunction {
+            function_reference: 1,
+            arguments: vec![
+                FunctionArgument {
+                    arg_type: Some(ArgType::Value(substrait_field(0))),
+                },
+                FunctionArgument {
+                    arg_type: Some(ArgType::Value(Expression {
+                        rex_type: Some(RexType::Literal(Literal {
+                            literal_type: Some(LiteralType::I32(1)),
+                            ..Default::default()
+                        })),
+                    })),
+                },
+            ],
+            ..Default::default()
+        })),
+    };
+    let filter = FilterRel {
+        input: Some(Box::new(Rel {
+            rel_type: Some(RelType::Read(Box::new(read))),
+        })),
+        condition: Some(Box::new(condition)),
+        ..Default::default()
+    };
+    let sort = SortRel {
+        input: Some(Box::new(Rel {
+            rel_type: Some(RelType::Filter(Box::new(filter))),
+        })),
+        sorts: vec![SortField {
+            expr: Some(substrait_field(0)),
+            sort_kind: Some(SortKind::Direction(SortDirection::DescNullsLast as i32)),
+        }],
+        ..Default::default()
+    };
+    let fetch = FetchRel {
+        input: Some(Box::new(Rel {
+            rel_type: Some(RelType::Sort(Box::new(sort))),
+        })),
+        count_mode: Some(CountMode::Count(2)),
+        ..Default::default()
+    };
+    let project = ProjectRel {
+        common: Some(RelCommon {
+            emit_kind: Some(EmitKind::Emit(rel_common::Emit {
+                output_mapping: vec![1],
+            })),
+            ..Default::default()
+        }),
+        input: Some(Box::new(Rel {
+            rel_type: Some(RelType::Fetch(Box::new(fetch))),
+        })),
+        ..Default::default()
+    };
+    Rel {
+        rel_type: Some(RelType::Project(Box::new(project))),
+    }
+}
+
 fn prepare_config() -> InnerConfig {
     let hash_method = PasswordHashMethod::DoubleSha1;
     let hash_value = hash_method.hash(TEST_PASSWORD.as_bytes());
@@ -163,3 +405,403 @@ async fn test_query() -> Result<()> {
         Ok(())
     })
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_catalog_metadata() -> Result<()> {
+    let _fixture = TestFixture::setup_with_config(&prepare_config()).await?;
+
+    let runtime = Runtime::with_default_worker_threads()?;
+    runtime.block_on(async {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.into_temp_path().to_str().unwrap().to_string();
+        let _ = fs::remove_file(path.clone());
+
+        let uds = UnixListener::bind(path.clone()).unwrap();
+        let stream = UnixListenerStream::new(uds);
+
+        let service = FlightSqlServiceImpl::create();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let serve_future = Server::builder()
+            .add_service(FlightServiceServer::new(service))
+            .serve_with_incoming_shutdown(stream, async { shutdown_rx.await.unwrap() });
+
+        let request_future = async {
+            let mut client = client_with_uds(path).await;
+            client.handshake(TEST_USER, TEST_PASSWORD).await.unwrap();
+            run_query(&mut client, "create table metadata_t1(a int, b string)")
+                .await
+                .unwrap();
+            run_query(
+                &mut client,
+                "create view metadata_v1 as select a from metadata_t1",
+            )
+            .await
+            .unwrap();
+
+            let flight_info = client.get_catalogs().await.unwrap();
+            let catalogs = fetch_column(&mut client, flight_info, "catalog_name").await;
+            assert!(catalogs.contains(&"default".to_string()));
+
+            let flight_info = client
+                .get_db_schemas(CommandGetDbSchemas {
+                    catalog: Some("default".to_string()),
+                    db_schema_filter_pattern: Some("def%".to_string()),
+                })
+                .await
+                .unwrap();
+            let schemas = fetch_column(&mut client, flight_info, "db_schema_name").await;
+            assert_eq!(schemas, vec!["default".to_string()]);
+
+            let flight_info = client
+                .get_tables(CommandGetTables {
+                    catalog: Some("default".to_string()),
+                    db_schema_filter_pattern: Some("default".to_string()),
+                    table_name_filter_pattern: Some("metadata%".to_string()),
+                    table_types: vec![],
+                    include_schema: true,
+                })
+                .await
+                .unwrap();
+            let table_types = fetch_column(&mut client, flight_info, "table_type").await;
+            assert_eq!(table_types, vec![
+                "BASE TABLE".to_string(),
+                "VIEW".to_string()
+            ]);
+
+            let flight_info = client
+                .get_tables(CommandGetTables {
+                    catalog: Some("default".to_string()),
+                    db_schema_filter_pattern: Some("default".to_string()),
+                    table_name_filter_pattern: Some("metadata%".to_string()),
+                    table_types: vec!["VIEW".to_string()],
+                    include_schema: false,
+                })
+                .await
+                .unwrap();
+            let tables = fetch_column(&mut client, flight_info, "table_name").await;
+            assert_eq!(tables, vec!["metadata_v1".to_string()]);
+
+            let flight_info = client.get_table_types().await.unwrap();
+            let table_types = fetch_column(&mut client, flight_info, "table_type").await;
+            assert_eq!(table_types, vec![
+                "BASE TABLE".to_string(),
+                "VIEW".to_string()
+            ]);
+
+            let flight_info = client
+                .get_xdbc_type_info(CommandGetXdbcTypeInfo { data_type: None })
+                .await
+                .unwrap();
+            let type_names = fetch_column(&mut client, flight_info, "type_name").await;
+            assert!(type_names.contains(&"VARCHAR".to_string()));
+
+            let flight_info = client
+                .get_xdbc_type_info(CommandGetXdbcTypeInfo {
+                    data_type: Some(XdbcDataType::XdbcInteger as i32),
+                })
+                .await
+                .unwrap();
+            let type_names = fetch_column(&mut client, flight_info, "type_name").await;
+            assert_eq!(type_names, vec![
+                "INT".to_string(),
+                "INT UNSIGNED".to_string()
+            ]);
+
+            let flight_info = client
+                .get_primary_keys(CommandGetPrimaryKeys {
+                    catalog: Some("default".to_string()),
+                    db_schema: Some("default".to_string()),
+                    table: "metadata_t1".to_string(),
+                })
+                .await
+                .unwrap();
+            let columns = fetch_column(&mut client, flight_info, "column_name").await;
+            assert!(columns.is_empty());
+
+            run_query(
+                &mut client,
+                "create table metadata_t2(a int, b string, c int, primary key (b, a), unique (c))",
+            )
+            .await
+            .unwrap();
+            let flight_info = client
+                .get_primary_keys(CommandGetPrimaryKeys {
+                    catalog: Some("default".to_string()),
+                    db_schema: Some("default".to_string()),
+                    table: "metadata_t2".to_string(),
+                })
+                .await
+                .unwrap();
+            let columns = fetch_column(&mut client, flight_info, "column_name").await;
+            assert_eq!(columns, vec!["b".to_string(), "a".to_string()]);
+
+            run_query(
+                &mut client,
+                "create table metadata_t3(id int, b string, a int, foreign key (b, a) references metadata_t2 (b, a))",
+            )
+            .await
+            .unwrap();
+            let flight_info = client
+                .get_imported_keys(CommandGetImportedKeys {
+                    catalog: Some("default".to_string()),
+                    db_schema: Some("default".to_string()),
+                    table: "metadata_t3".to_string(),
+                })
+                .await
+                .unwrap();
+            let columns = fetch_column(&mut client, flight_info, "pk_column_name").await;
+            assert_eq!(columns, vec!["b".to_string(), "a".to_string()]);
+
+            let flight_info = client
+                .get_exported_keys(CommandGetExportedKeys {
+                    catalog: Some("default".to_string()),
+                    db_schema: Some("default".to_string()),
+                    table: "metadata_t2".to_string(),
+                })
+                .await
+                .unwrap();
+            let tables = fetch_column(&mut client, flight_info, "fk_table_name").await;
+            assert_eq!(tables, vec!["metadata_t3".to_string(), "metadata_t3".to_string()]);
+
+            let flight_info = client
+                .get_cross_reference(CommandGetCrossReference {
+                    pk_catalog: Some("default".to_string()),
+                    pk_db_schema: Some("default".to_string()),
+                    pk_table: "metadata_t2".to_string(),
+                    fk_catalog: Some("default".to_string()),
+                    fk_db_schema: Some("default".to_string()),
+                    fk_table: "metadata_t3".to_string(),
+                })
+                .await
+                .unwrap();
+            let columns = fetch_column(&mut client, flight_info, "fk_column_name").await;
+            assert_eq!(columns, vec!["b".to_string(), "a".to_string()]);
+
+            let flight_info = client
+                .get_cross_reference(CommandGetCrossReference {
+                    pk_catalog: Some("default".to_string()),
+                    pk_db_schema: Some("default".to_string()),
+                    pk_table: "metadata_t1".to_string(),
+                    fk_catalog: Some("default".to_string()),
+                    fk_db_schema: Some("default".to_string()),
+                    fk_table: "metadata_t3".to_string(),
+                })
+                .await
+                .unwrap();
+            let columns = fetch_column(&mut client, flight_info, "fk_column_name").await;
+            assert!(columns.is_empty());
+        };
+        tokio::pin!(serve_future);
+
+        tokio::select! {
+            _ = &mut serve_future => panic!("server returned first"),
+            _ = request_future => {
+                debug!("Client finished!");
+            }
+        }
+        shutdown_tx.send(()).unwrap();
+        serve_future.await.unwrap();
+
+        Ok(())
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_ingest() -> Result<()> {
+    let _fixture = TestFixture::setup_with_config(&prepare_config()).await?;
+
+    let runtime = Runtime::with_default_worker_threads()?;
+    runtime.block_on(async {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.into_temp_path().to_str().unwrap().to_string();
+        let _ = fs::remove_file(path.clone());
+
+        let uds = UnixListener::bind(path.clone()).unwrap();
+        let stream = UnixListenerStream::new(uds);
+
+        let service = FlightSqlServiceImpl::create();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let serve_future = Server::builder()
+            .add_service(FlightServiceServer::new(service))
+            .serve_with_incoming_shutdown(stream, async { shutdown_rx.await.unwrap() });
+
+        let request_future = async {
+            let mut client = client_with_uds(path.clone()).await;
+            client.handshake(TEST_USER, TEST_PASSWORD).await.unwrap();
+
+            let batches = || {
+                futures::stream::iter(vec![
+                    Ok::<_, FlightError>(ingest_batch(0, 3)),
+                    Ok(ingest_batch(3, 2)),
+                ])
+            };
+
+            // The table does not exist, and is not allowed to be created.
+            let cmd = ingest_command(
+                "ingest_t1",
+                TableNotExistOption::Fail,
+                TableExistsOption::Append,
+            );
+            assert!(client.execute_ingest(cmd, batches()).await.is_err());
+
+            // The created table is rolled back if the ingestion fails, here the schema changes.
+            let other_schema = Arc::new(Schema::new(vec![Field::new("c", DataType::Int32, false)]));
+            let other_batch =
+                RecordBatch::try_new(other_schema, vec![Arc::new(Int32Array::from(vec![1]))])
+                    .unwrap();
+            let failing_batches = futures::stream::iter(vec![
+                Ok::<_, FlightError>(ingest_batch(0, 3)),
+                Ok(other_batch),
+            ]);
+            let cmd = ingest_command(
+                "ingest_t1",
+                TableNotExistOption::Create,
+                TableExistsOption::Fail,
+            );
+            assert!(client.execute_ingest(cmd, failing_batches).await.is_err());
+            assert!(run_query(&mut client, "select count(*) from ingest_t1")
+                .await
+                .is_err());
+
+            // Create the table.
+            let cmd = ingest_command(
+                "ingest_t1",
+                TableNotExistOption::Create,
+                TableExistsOption::Fail,
+            );
+            let rows = client.execute_ingest(cmd.clone(), batches()).await.unwrap();
+            assert_eq!(rows, 5);
+
+            // The table exists now.
+            assert!(client.execute_ingest(cmd, batches()).await.is_err());
+
+            // Append to the table.
+            let cmd = ingest_command(
+                "ingest_t1",
+                TableNotExistOption::Fail,
+                TableExistsOption::Append,
+            );
+            let rows = client.execute_ingest(cmd, batches()).await.unwrap();
+            assert_eq!(rows, 5);
+            let res = run_query(&mut client, "select count(*), sum(a) from ingest_t1")
+                .await
+                .unwrap();
+            assert!(res.contains("| 10       | 20     |"), "{res}");
+
+            // Replace the table.
+            let cmd = ingest_command(
+                "ingest_t1",
+                TableNotExistOption::Fail,
+                TableExistsOption::Replace,
+            );
+            let rows = client.execute_ingest(cmd, batches()).await.unwrap();
+            assert_eq!(rows, 5);
+            let res = run_query(&mut client, "select count(*), sum(a) from ingest_t1")
+                .await
+                .unwrap();
+            assert!(res.contains("| 5        | 10     |"), "{res}");
+
+            // A user without the INSERT privilege can not ingest into the table.
+            run_query(&mut client, "create user ingest_u1 identified by 'p1'")
+                .await
+                .unwrap();
+            let mut user_client = client_with_uds(path.clone()).await;
+            user_client.handshake("ingest_u1", "p1").await.unwrap();
+            let cmd = ingest_command(
+                "ingest_t1",
+                TableNotExistOption::Fail,
+                TableExistsOption::Append,
+            );
+            let err = user_client
+                .execute_ingest(cmd.clone(), batches())
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("Permission denied"), "{err}");
+
+            run_query(
+                &mut client,
+                "grant insert on default.ingest_t1 to ingest_u1",
+            )
+            .await
+            .unwrap();
+            let rows = user_client.execute_ingest(cmd, batches()).await.unwrap();
+            assert_eq!(rows, 5);
+        };
+        tokio::pin!(serve_future);
+
+        tokio::select! {
+            _ = &mut serve_future => panic!("server returned first"),
+            _ = request_future => {
+                debug!("Client finished!");
+            }
+        }
+        shutdown_tx.send(()).unwrap();
+        serve_future.await.unwrap();
+
+        Ok(())
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_substrait() -> Result<()> {
+    let _fixture = TestFixture::setup_with_config(&prepare_config()).await?;
+
+    let runtime = Runtime::with_default_worker_threads()?;
+    runtime.block_on(async {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.into_temp_path().to_str().unwrap().to_string();
+        let _ = fs::remove_file(path.clone());
+
+        let uds = UnixListener::bind(path.clone()).unwrap();
+        let stream = UnixListenerStream::new(uds);
+
+        let service = FlightSqlServiceImpl::create();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let serve_future = Server::builder()
+            .add_service(FlightServiceServer::new(service))
+            .serve_with_incoming_shutdown(stream, async { shutdown_rx.await.unwrap() });
+
+        let request_future = async {
+            let mut client = client_with_uds(path).await;
+            client.handshake(TEST_USER, TEST_PASSWORD).await.unwrap();
+
+            for sql in [
+                "create table substrait_t1(a int, b string)",
+                "insert into substrait_t1 values(1, 'v1'), (2, 'v2'), (3, 'v3'), (4, 'v4')",
+            ] {
+                run_query(&mut client, sql).await.unwrap();
+            }
+
+            let cmd = substrait_command(substrait_query(), &["b"]);
+            let flight_info = client.get_flight_info_for_command(cmd).await.unwrap();
+            let values = fetch_column(&mut client, flight_info, "b").await;
+            assert_eq!(values, vec!["v4", "v3"]);
+
+            let set = Rel {
+                rel_type: Some(RelType::Set(SetRel {
+                    inputs: vec![substrait_query(), substrait_query()],
+                    ..Default::default()
+                })),
+            };
+            let cmd = substrait_command(set, &["b"]);
+            let err = client.get_flight_info_for_command(cmd).await.unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("Substrait Set relation is not supported"),
+                "{err}"
+            );
+        };
+        tokio::pin!(serve_future);
+
+        tokio::select! {
+            _ = &mut serve_future => panic!("server returned first"),
+            _ = request_future => {
+                debug!("Client finished!");
+            }
+        }
+        shutdown_tx.send(()).unwrap();
+        serve_future.await.unwrap();
+
+        Ok(())
+    })
+}
@@ -14,9 +14,14 @@
 
 use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::Cursor;
 use std::io::Read;
 use std::time::Duration;
 
+use arrow_array::Array;
+use arrow_array::RecordBatch;
+use arrow_array::UInt64Array;
+use arrow_ipc::reader::StreamReader;
 use base64::engine::general_purpose;
 use base64::prelude::*;
 use databend_common_base::base::get_free_tcp_port;
@@ -34,6 +39,7 @@ use databend_query::servers::http::error::QueryError;
 use databend_query::servers::http::middleware::json_response;
 use databend_query::servers::http::v1::catalog;
 use databend_query::servers::http::v1::make_page_uri;
+use databend_query::servers::http::v1::make_state_uri;
 use databend_query::servers::http::v1::query_route;
 use databend_query::servers::http::v1::roles::ListRolesResponse;
 use databend_query::servers::http::v1::users::CreateUserRequest;
@@ -41,6 +47,7 @@ use databend_query::servers::http::v1::users::ListUsersResponse;
 use databend_query::servers::http::v1::ExecuteStateKind;
 use databend_query::servers::http::v1::HttpSessionConf;
 use databend_query::servers::http::v1::QueryResponse;
+use databend_query::servers::http::v1::RESPONSE_HEADER_METADATA_KEY;
 use databend_query::servers::HttpHandler;
 use databend_query::servers::HttpHandlerKind;
 use databend_query::sessions::QueryAffect;
@@ -625,6 +632,144 @@ async fn test_pagination() -> Result<()> {
     Ok(())
 }
 
+async fn read_arrow_page(response: Response) -> Result<(QueryResponse, Vec<RecordBatch>)> {
+    let body = response.into_body().into_bytes().await.unwrap();
+    let reader = StreamReader::try_new(Cursor::new(body), None)?;
+    let header = reader
+        .schema()
+        .metadata()
+        .get(RESPONSE_HEADER_METADATA_KEY)
+        .cloned()
+        .unwrap();
+    let batches = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok((serde_json::from_str(&header)?, batches))
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_arrow_body_format() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let ep = create_endpoint()?;
+    let sql = "select number, number::string as s from numbers(10)";
+    let json = serde_json::json!({"sql": sql.to_string(), "pagination": {"wait_time_secs": 6, "max_rows_per_page": 4}});
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::ACCEPT,
+        HeaderValue::from_static("application/vnd.apache.arrow.stream"),
+    );
+
+    let mut response = post_uri(&ep, "/v1/query", &json, headers).await?;
+    let mut numbers = vec![];
+    let mut pages = 0;
+    loop {
+        assert_eq!(response.status(), StatusCode::OK);
+        let content_type = response.content_type().unwrap_or_default().to_string();
+        let result = if content_type == "application/vnd.apache.arrow.stream" {
+            let (result, batches) = read_arrow_page(response).await?;
+            for batch in batches {
+                assert_eq!(batch.num_columns(), 2);
+                let column = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<UInt64Array>()
+                    .unwrap();
+                numbers.extend(column.values().iter().copied());
+            }
+            pages += 1;
+            result
+        } else {
+            // the response without data is JSON
+            check_response(response).await?.1
+        };
+        assert!(result.error.is_none(), "{:?}", result);
+        assert!(result.data.is_empty(), "{:?}", result);
+        match result.next_uri {
+            Some(uri) if !uri.ends_with("/final") => response = get_uri(&ep, &uri).await,
+            _ => break,
+        }
+    }
+    assert_eq!(numbers, (0..10).collect::<Vec<u64>>());
+    assert!(pages >= 3, "{pages}");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_ndjson() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let ep = create_endpoint()?;
+    let sql = "select number, number::string as s from numbers(10)";
+    let json = serde_json::json!({"sql": sql.to_string(), "pagination": {"max_rows_per_page": 4}});
+    let response = post_uri(&ep, "/v1/query/stream", &json, HeaderMap::new()).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.content_type().unwrap_or_default(),
+        "application/x-ndjson"
+    );
+
+    let body = response.into_body().into_string().await.unwrap();
+    let lines = body.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 12, "{body}");
+
+    let header: QueryResponse = serde_json::from_str(lines[0])?;
+    assert_eq!(header.schema.len(), 2, "{:?}", header);
+    assert!(header.next_uri.is_none(), "{:?}", header);
+    for (i, line) in lines[1..11].iter().enumerate() {
+        let row: Vec<Option<String>> = serde_json::from_str(line)?;
+        assert_eq!(row, vec![Some(i.to_string()), Some(i.to_string())]);
+    }
+    let footer: QueryResponse = serde_json::from_str(lines[11])?;
+    assert_eq!(footer.state, ExecuteStateKind::Succeeded, "{:?}", footer);
+    assert!(footer.error.is_none(), "{:?}", footer);
+    assert_eq!(footer.stats.progresses.result_progress.rows, 10);
+
+    // the query is removed after the stream is finished
+    let response = get_uri(&ep, &make_state_uri(&header.id)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // the failure before the results is responded as JSON
+    let json = serde_json::json!({"sql": "select * from not_exists"});
+    let response = post_uri(&ep, "/v1/query/stream", &json, HeaderMap::new()).await?;
+    let (status, result) = check_response(response).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(result.error.is_some(), "{:?}", result);
+    assert!(result.next_uri.is_none(), "{:?}", result);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_arrow() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let ep = create_endpoint()?;
+    let sql = "select number from numbers(10)";
+    let json = serde_json::json!({"sql": sql.to_string(), "pagination": {"max_rows_per_page": 4}});
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::ACCEPT,
+        HeaderValue::from_static("application/vnd.apache.arrow.stream"),
+    );
+    let response = post_uri(&ep, "/v1/query/stream", &json, headers).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (header, batches) = read_arrow_page(response).await?;
+    assert!(header.error.is_none(), "{:?}", header);
+    let mut numbers = vec![];
+    for batch in batches {
+        let column = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        numbers.extend(column.values().iter().copied());
+    }
+    assert_eq!(numbers, (0..10).collect::<Vec<u64>>());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 #[ignore]
 async fn test_result_timeout() -> Result<()> {
@@ -53,6 +53,35 @@ async fn test_generic_code_with_on_query() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs, MySQLTlsConfig::default())?;
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port(), false).await?;
+
+    let rows: Vec<(i64, String, Option<i64>)> = connection
+        .exec("SELECT ? + 1, ?, ?", (1, "it's", None::<i64>))
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+    assert_eq!(rows, vec![(2, "it's".to_string(), None)]);
+
+    let rows: Vec<(f64, String)> = connection
+        .exec("SELECT 1.5::DOUBLE, 'a'", ())
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+    assert_eq!(rows, vec![(1.5, "a".to_string())]);
+
+    let result: std::result::Result<Vec<i64>, _> = connection.exec("SELECT ? FROM", (1,)).await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn test_connect_with_tls() -> Result<()> {
     let _fixture = TestFixture::setup().await?;
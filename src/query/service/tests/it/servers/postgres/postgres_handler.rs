@@ -0,0 +1,269 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use databend_common_base::base::tokio;
+use databend_common_base::base::tokio::io::AsyncRead;
+use databend_common_base::base::tokio::io::AsyncReadExt;
+use databend_common_base::base::tokio::io::AsyncWrite;
+use databend_common_base::base::tokio::io::AsyncWriteExt;
+use databend_common_base::base::tokio::net::TcpStream;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::AuthInfo;
+use databend_common_meta_app::principal::UserInfo;
+use databend_common_meta_app::schema::CreateOption;
+use databend_common_users::UserApiProvider;
+use databend_query::servers::MySQLTlsConfig;
+use databend_query::servers::PostgresHandler;
+use databend_query::test_kits::TestFixture;
+use hmac::Hmac;
+use hmac::Mac;
+use rustls::ClientConfig;
+use rustls::RootCertStore;
+use rustls_pki_types::ServerName;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio_rustls::TlsConnector;
+
+use crate::tests::tls_constants::*;
+
+const SSL_REQUEST_CODE: i32 = 80877103;
+const PROTOCOL_VERSION: i32 = 196608;
+
+async fn start_handler(tls_config: MySQLTlsConfig) -> Result<u16> {
+    let mut handler = PostgresHandler::create(120, tls_config)?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    Ok(handler.start(listening).await?.port())
+}
+
+async fn send_ssl_request(stream: &mut TcpStream) -> Result<u8> {
+    stream.write_all(&8i32.to_be_bytes()).await?;
+    stream.write_all(&SSL_REQUEST_CODE.to_be_bytes()).await?;
+    Ok(stream.read_u8().await?)
+}
+
+async fn send_startup<S: AsyncWrite + Unpin>(stream: &mut S, user: &str) -> Result<()> {
+    let mut body = PROTOCOL_VERSION.to_be_bytes().to_vec();
+    for value in ["user", user, "database", "default", ""] {
+        body.extend_from_slice(value.as_bytes());
+        body.push(0);
+    }
+    stream
+        .write_all(&(body.len() as i32 + 4).to_be_bytes())
+        .await?;
+    stream.write_all(&body).await?;
+    Ok(())
+}
+
+/// Read a backend message, returns its type and body.
+async fn read_message<S: AsyncRead + Unpin>(stream: &mut S) -> Result<(u8, Vec<u8>)> {
+    let tag = stream.read_u8().await?;
+    let len = stream.read_i32().await?;
+    let mut body = vec![0; len as usize - 4];
+    stream.read_exact(&mut body).await?;
+    Ok((tag, body))
+}
+
+async fn send_password_message<S: AsyncWrite + Unpin>(stream: &mut S, body: &[u8]) -> Result<()> {
+    stream.write_u8(b'p').await?;
+    stream
+        .write_all(&(body.len() as i32 + 4).to_be_bytes())
+        .await?;
+    stream.write_all(body).await?;
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut m = <Hmac<Sha256> as Mac>::new_from_slice(key).unwrap();
+    m.update(message);
+    m.finalize().into_bytes().to_vec()
+}
+
+/// Run the client side of the SCRAM-SHA-256 exchange, returns the types of the messages
+/// received after the proof is sent.
+async fn scram_auth<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    password: &str,
+) -> Result<Vec<u8>> {
+    let (tag, body) = read_message(stream).await?;
+    assert_eq!(tag, b'R');
+    assert_eq!(&body[..4], &10i32.to_be_bytes());
+    assert_eq!(&body[4..], b"SCRAM-SHA-256\0\0");
+
+    let client_first_bare = "n=,r=fyko+d2lbbFgONRv9qkxdawL";
+    let client_first = format!("n,,{client_first_bare}");
+    let mut initial_response = b"SCRAM-SHA-256\0".to_vec();
+    initial_response.extend_from_slice(&(client_first.len() as i32).to_be_bytes());
+    initial_response.extend_from_slice(client_first.as_bytes());
+    send_password_message(stream, &initial_response).await?;
+
+    let (tag, body) = read_message(stream).await?;
+    assert_eq!(tag, b'R');
+    assert_eq!(&body[..4], &11i32.to_be_bytes());
+    let server_first = String::from_utf8(body[4..].to_vec()).unwrap();
+    let attributes = server_first
+        .split(',')
+        .map(|a| a.split_at(2))
+        .collect::<Vec<_>>();
+    let (nonce, salt, iterations) = match attributes.as_slice() {
+        [("r=", nonce), ("s=", salt), ("i=", iterations)] => (
+            nonce.to_string(),
+            BASE64_STANDARD.decode(salt).unwrap(),
+            iterations.parse::<u32>().unwrap(),
+        ),
+        _ => panic!("unexpected server-first-message {server_first}"),
+    };
+    assert!(nonce.starts_with("fyko+d2lbbFgONRv9qkxdawL"));
+
+    let mut salted_password = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, iterations, &mut salted_password);
+    let client_key = hmac_sha256(&salted_password, b"Client Key");
+    let stored_key = Sha256::digest(&client_key);
+    let without_proof = format!("c=biws,r={nonce}");
+    let auth_message = format!("{client_first_bare},{server_first},{without_proof}");
+    let client_signature = hmac_sha256(&stored_key, auth_message.as_bytes());
+    let proof = client_key
+        .iter()
+        .zip(client_signature.iter())
+        .map(|(a, b)| a ^ b)
+        .collect::<Vec<_>>();
+    let client_final = format!("{without_proof},p={}", BASE64_STANDARD.encode(proof));
+    send_password_message(stream, client_final.as_bytes()).await?;
+
+    let (tag, body) = read_message(stream).await?;
+    if tag != b'R' {
+        return Ok(vec![tag]);
+    }
+    assert_eq!(&body[..4], &12i32.to_be_bytes());
+    let server_key = hmac_sha256(&salted_password, b"Server Key");
+    let server_signature = hmac_sha256(&server_key, auth_message.as_bytes());
+    let server_final = format!("v={}", BASE64_STANDARD.encode(server_signature));
+    assert_eq!(&body[4..], server_final.as_bytes());
+
+    let mut tags = vec![tag];
+    tags.extend(read_until_ready(stream).await?);
+    Ok(tags)
+}
+
+/// Read the messages until `ReadyForQuery`, returns the types of them.
+async fn read_until_ready<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>> {
+    let mut tags = vec![];
+    loop {
+        let (tag, _) = read_message(stream).await?;
+        tags.push(tag);
+        if tag == b'Z' || tag == b'E' {
+            return Ok(tags);
+        }
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_connect_with_tls() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let tls_config = MySQLTlsConfig::new(TEST_SERVER_CERT.to_string(), TEST_SERVER_KEY.to_string());
+    let port = start_handler(tls_config).await?;
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    assert_eq!(send_ssl_request(&mut stream).await?, b'S');
+
+    let mut roots = RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut BufReader::new(File::open(TEST_CA_CERT)?)) {
+        roots.add(cert?).unwrap();
+    }
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(TEST_CN_NAME).unwrap();
+    let mut stream = TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await?;
+
+    send_startup(&mut stream, "root").await?;
+    let tags = read_until_ready(&mut stream).await?;
+    assert_eq!(tags.first(), Some(&b'R'));
+    assert_eq!(tags.last(), Some(&b'Z'));
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_connect_without_tls() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let port = start_handler(MySQLTlsConfig::default()).await?;
+
+    // The client continues in plain text if the server has no certificate.
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    assert_eq!(send_ssl_request(&mut stream).await?, b'N');
+
+    send_startup(&mut stream, "root").await?;
+    let tags = read_until_ready(&mut stream).await?;
+    assert_eq!(tags.first(), Some(&b'R'));
+    assert_eq!(tags.last(), Some(&b'Z'));
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_scram_auth() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    fixture
+        .execute_command("CREATE USER 'pg_scram' IDENTIFIED BY 'scram_password'")
+        .await?;
+
+    let port = start_handler(MySQLTlsConfig::default()).await?;
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    send_startup(&mut stream, "pg_scram").await?;
+    let tags = scram_auth(&mut stream, "scram_password").await?;
+    assert_eq!(tags.first(), Some(&b'R'));
+    assert_eq!(tags.last(), Some(&b'Z'));
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    send_startup(&mut stream, "pg_scram").await?;
+    assert_eq!(scram_auth(&mut stream, "wrong_password").await?, vec![b'E']);
+
+    // The unknown users are asked for the password in the same way.
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    send_startup(&mut stream, "pg_unknown").await?;
+    assert_eq!(scram_auth(&mut stream, "scram_password").await?, vec![b'E']);
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cleartext_password_requires_tls() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    // The password set without the SCRAM verifier.
+    let auth_info = AuthInfo::create(&None, &Some("password".to_string()))?;
+    UserApiProvider::instance()
+        .add_user(
+            &fixture.default_session().get_current_tenant(),
+            UserInfo::new("pg_cleartext", "%", auth_info),
+            &CreateOption::Create,
+        )
+        .await?;
+
+    let port = start_handler(MySQLTlsConfig::default()).await?;
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    send_startup(&mut stream, "pg_cleartext").await?;
+    let (tag, _) = read_message(&mut stream).await?;
+    assert_eq!(tag, b'E');
+    Ok(())
+}
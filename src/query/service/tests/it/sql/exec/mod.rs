@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use databend_common_base::base::tokio;
 use databend_common_base::runtime::Runtime;
 use databend_common_base::runtime::TrySpawn;
 use databend_common_catalog::lock::LockTableOption;
+use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_sql::plans::Plan;
@@ -170,4 +173,48 @@ pub async fn test_snapshot_consistency() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+pub async fn test_prepared_statement_bound_once() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    fixture.create_default_database().await?;
+    fixture.create_default_table().await?;
+
+    // The plan of a table without snapshot is not cached.
+    let table = fixture.latest_default_table().await?;
+    let blocks = TestFixture::gen_sample_blocks_stream(1, 1)
+        .try_collect()
+        .await?;
+    fixture
+        .append_commit_blocks(table, blocks, false, true)
+        .await?;
+
+    let query = format!(
+        "select id from {}.{} where id > ?",
+        fixture.default_db_name(),
+        fixture.default_table_name()
+    );
+    let ctx = fixture.new_query_ctx().await?;
+    let stmt = Planner::new(ctx.clone()).parse_sql(&query)?.statement;
+    ctx.set_prepared_statement("p".to_string(), stmt);
+
+    // Every query context gets its own instance of the table, the plan bound by the first
+    // execution is reused by the second one if the table instances are the same.
+    let mut tables = Vec::with_capacity(2);
+    for value in [1, 2] {
+        let ctx = fixture.new_query_ctx().await?;
+        let mut planner = Planner::new(ctx);
+        let (plan, _) = planner
+            .plan_sql(&format!("execute p using {value}"))
+            .await?;
+        let Plan::Query { metadata, .. } = plan else {
+            return Err(ErrorCode::BadArguments("query bad plan"));
+        };
+        let table = metadata.read().tables()[0].table();
+        tables.push(table);
+    }
+    assert!(Arc::ptr_eq(&tables[0], &tables[1]));
+
+    Ok(())
+}
+
 mod get_table_bind_test;
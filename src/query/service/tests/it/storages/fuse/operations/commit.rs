@@ -18,6 +18,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use dashmap::DashMap;
+use databend_common_ast::ast::Statement;
 use databend_common_base::base::tokio;
 use databend_common_base::base::Progress;
 use databend_common_base::base::ProgressValues;
@@ -729,6 +730,18 @@ impl TableContext for CtxDelegation {
         HashMap::new()
     }
 
+    fn set_prepared_statement(&self, _name: String, _statement: Statement) {}
+
+    fn unset_prepared_statement(&self, _name: &str) -> bool {
+        false
+    }
+
+    fn get_prepared_statement(&self, _name: &str) -> Option<Statement> {
+        None
+    }
+
+    fn clear_prepared_statements(&self) {}
+
     fn add_written_segment_location(&self, _segment_loc: Location) -> Result<()> {
         todo!()
     }
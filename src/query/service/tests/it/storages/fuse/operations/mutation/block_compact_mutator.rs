@@ -145,6 +145,7 @@ async fn do_compact(ctx: Arc<QueryContext>, table: Arc<dyn Table>) -> Result<boo
             plan_id: u32::MAX,
             recluster_info: None,
             table_meta_timestamps,
+            check_unique_keys: false,
         }));
 
         let build_res =
@@ -0,0 +1,129 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_base::base::tokio;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_sql::Planner;
+use databend_query::interpreters::Interpreter;
+use databend_query::interpreters::InterpreterFactory;
+use databend_query::interpreters::InterpreterPtr;
+use databend_query::sessions::QueryContext;
+use databend_query::sessions::Session;
+use databend_query::sessions::SessionType;
+use databend_query::test_kits::*;
+use futures_util::TryStreamExt;
+
+// The interpreter is planned before the concurrent insertion is committed, so it checks the keys
+// against the snapshot without the concurrent rows, and its commit is retried.
+async fn plan_insert(
+    fixture: &TestFixture,
+    query: &str,
+) -> Result<(Arc<QueryContext>, InterpreterPtr)> {
+    let ctx = fixture.new_query_ctx().await?;
+    let (plan, _) = Planner::new(ctx.clone()).plan_sql(query).await?;
+    let interpreter = InterpreterFactory::get(ctx.clone(), &plan).await?;
+    Ok((ctx, interpreter))
+}
+
+async fn execute((ctx, interpreter): (Arc<QueryContext>, InterpreterPtr)) -> Result<()> {
+    let stream = interpreter.execute(ctx).await?;
+    stream.try_collect::<Vec<DataBlock>>().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unique_key_concurrent_insert() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let db = fixture.default_db_name();
+    fixture.create_default_database().await?;
+    fixture
+        .execute_command(&format!("create table {db}.t(a int primary key, b int)"))
+        .await?;
+
+    // conflicting keys
+    let insert = plan_insert(&fixture, &format!("insert into {db}.t values(1, 1)")).await?;
+    fixture
+        .execute_command(&format!("insert into {db}.t values(1, 2)"))
+        .await?;
+    let err = execute(insert).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CONSTRAINT_VIOLATION);
+
+    // distinct keys
+    let insert = plan_insert(&fixture, &format!("insert into {db}.t values(2, 1)")).await?;
+    fixture
+        .execute_command(&format!("insert into {db}.t values(3, 1)"))
+        .await?;
+    execute(insert).await?;
+
+    let blocks = fixture
+        .execute_query(&format!("select a from {db}.t order by a"))
+        .await?
+        .try_collect::<Vec<DataBlock>>()
+        .await?;
+    let rows = blocks.iter().map(|block| block.num_rows()).sum::<usize>();
+    assert_eq!(rows, 3);
+    Ok(())
+}
+
+async fn execute_in_session(session: &Arc<Session>, query: &str) -> Result<()> {
+    let ctx = session.create_query_context().await?;
+    let (plan, _) = Planner::new(ctx.clone()).plan_sql(query).await?;
+    let interpreter = InterpreterFactory::get(ctx.clone(), &plan).await?;
+    execute((ctx, interpreter)).await
+}
+
+// The changes of an explicit transaction are rebased on the latest snapshot when it commits,
+// the keys it appended must be checked against the rows committed by others meanwhile.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unique_key_concurrent_txn() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let db = fixture.default_db_name();
+    fixture.create_default_database().await?;
+    fixture
+        .execute_command(&format!("create table {db}.t(a int primary key, b int)"))
+        .await?;
+
+    let s1 = fixture.new_session_with_type(SessionType::Dummy).await?;
+    let s2 = fixture.new_session_with_type(SessionType::Dummy).await?;
+
+    // conflicting keys
+    execute_in_session(&s1, "begin").await?;
+    execute_in_session(&s1, &format!("insert into {db}.t values(1, 1)")).await?;
+    execute_in_session(&s2, "begin").await?;
+    execute_in_session(&s2, &format!("insert into {db}.t values(1, 2)")).await?;
+    execute_in_session(&s2, "commit").await?;
+    let err = execute_in_session(&s1, "commit").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::CONSTRAINT_VIOLATION);
+
+    // distinct keys
+    execute_in_session(&s1, "begin").await?;
+    execute_in_session(&s1, &format!("insert into {db}.t values(2, 1)")).await?;
+    execute_in_session(&s2, "begin").await?;
+    execute_in_session(&s2, &format!("insert into {db}.t values(3, 1)")).await?;
+    execute_in_session(&s2, "commit").await?;
+    execute_in_session(&s1, "commit").await?;
+
+    let blocks = fixture
+        .execute_query(&format!("select a from {db}.t order by a"))
+        .await?
+        .try_collect::<Vec<DataBlock>>()
+        .await?;
+    let rows = blocks.iter().map(|block| block.num_rows()).sum::<usize>();
+    assert_eq!(rows, 3);
+    Ok(())
+}
@@ -698,6 +698,14 @@ impl Settings {
         self.try_get_u64("external_server_request_retry_times")
     }
 
+    pub fn get_udf_script_timeout_secs(&self) -> Result<u64> {
+        self.try_get_u64("udf_script_timeout_secs")
+    }
+
+    pub fn get_udf_script_max_memory(&self) -> Result<u64> {
+        self.try_get_u64("udf_script_max_memory")
+    }
+
     pub fn get_create_query_flight_client_with_current_rt(&self) -> Result<bool> {
         Ok(self.try_get_u64("create_query_flight_client_with_current_rt")? != 0)
     }
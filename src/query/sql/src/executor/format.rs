@@ -49,6 +49,7 @@ use crate::executor::physical_plans::Filter;
 use crate::executor::physical_plans::FragmentKind;
 use crate::executor::physical_plans::HashJoin;
 use crate::executor::physical_plans::Limit;
+use crate::executor::physical_plans::MatchRecognize;
 use crate::executor::physical_plans::Mutation;
 use crate::executor::physical_plans::MutationManipulate;
 use crate::executor::physical_plans::MutationOrganize;
@@ -70,6 +71,8 @@ use crate::planner::Metadata;
 use crate::planner::MetadataRef;
 use crate::planner::DUMMY_TABLE_INDEX;
 use crate::plans::CacheSource;
+use crate::plans::MatchRowsPerMatch;
+use crate::plans::MatchSkip;
 
 impl PhysicalPlan {
     pub fn format(
@@ -355,6 +358,7 @@ fn to_format_tree(
         PhysicalPlan::WindowPartition(plan) => {
             window_partition_to_format_tree(plan, metadata, profs)
         }
+        PhysicalPlan::MatchRecognize(plan) => match_recognize_to_format_tree(plan, metadata, profs),
         PhysicalPlan::Sort(plan) => sort_to_format_tree(plan, metadata, profs),
         PhysicalPlan::Limit(plan) => limit_to_format_tree(plan, metadata, profs),
         PhysicalPlan::RowFetch(plan) => row_fetch_to_format_tree(plan, metadata, profs),
@@ -1245,6 +1249,67 @@ fn window_to_format_tree(
     ))
 }
 
+fn match_recognize_to_format_tree(
+    plan: &MatchRecognize,
+    metadata: &Metadata,
+    profs: &HashMap<u32, PlanProfile>,
+) -> Result<FormatTreeNode<String>> {
+    let partition_by = plan
+        .partition_by
+        .iter()
+        .map(|&index| metadata.column(index).name())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let measures = plan
+        .measures
+        .iter()
+        .map(|measure| match measure.variable {
+            Some(variable) => format!("{} of {}", measure.display, plan.variables[variable].name),
+            None => measure.display.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let rows_per_match = match plan.rows_per_match {
+        MatchRowsPerMatch::OneRow => "one row",
+        MatchRowsPerMatch::AllRows => "all rows",
+    };
+
+    let after_match_skip = match plan.after_match_skip {
+        MatchSkip::PastLastRow => "past last row".to_string(),
+        MatchSkip::ToNextRow => "to next row".to_string(),
+        MatchSkip::ToFirst(variable) => format!("to first {}", plan.variables[variable].name),
+        MatchSkip::ToLast(variable) => format!("to last {}", plan.variables[variable].name),
+    };
+
+    let mut children = vec![
+        FormatTreeNode::new(format!(
+            "output columns: [{}]",
+            format_output_columns(plan.output_schema()?, metadata, true)
+        )),
+        FormatTreeNode::new(format!("partition by: [{partition_by}]")),
+        FormatTreeNode::new(format!("pattern: [{}]", plan.pattern_display)),
+        FormatTreeNode::new(format!("measures: [{measures}]")),
+        FormatTreeNode::new(format!("rows per match: [{rows_per_match}]")),
+        FormatTreeNode::new(format!("after match skip: [{after_match_skip}]")),
+    ];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    append_profile_info(&mut children, profs, plan.plan_id);
+
+    children.push(to_format_tree(&plan.input, metadata, profs)?);
+
+    Ok(FormatTreeNode::with_children(
+        "MatchRecognize".to_string(),
+        children,
+    ))
+}
+
 fn sort_to_format_tree(
     plan: &Sort,
     metadata: &Metadata,
@@ -1416,6 +1481,22 @@ fn range_join_to_format_tree(
         })
         .collect::<Vec<_>>()
         .join(", ");
+    let equi_conditions = plan
+        .equi_conditions
+        .iter()
+        .map(|condition| {
+            let left = condition
+                .left_expr
+                .as_expr(&BUILTIN_FUNCTIONS)
+                .sql_display();
+            let right = condition
+                .right_expr
+                .as_expr(&BUILTIN_FUNCTIONS)
+                .sql_display();
+            format!("{left} = {right}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
     let other_conditions = plan
         .other_conditions
         .iter()
@@ -1436,8 +1517,15 @@ fn range_join_to_format_tree(
         )),
         FormatTreeNode::new(format!("join type: {}", plan.join_type)),
         FormatTreeNode::new(format!("range join conditions: [{range_join_conditions}]")),
-        FormatTreeNode::new(format!("other conditions: [{other_conditions}]")),
     ];
+    if !equi_conditions.is_empty() {
+        children.push(FormatTreeNode::new(format!(
+            "equi conditions: [{equi_conditions}]"
+        )));
+    }
+    children.push(FormatTreeNode::new(format!(
+        "other conditions: [{other_conditions}]"
+    )));
 
     if let Some(info) = &plan.stat_info {
         let items = plan_stats_info_to_format_tree(info);
@@ -1453,6 +1541,7 @@ fn range_join_to_format_tree(
         match plan.range_join_type {
             RangeJoinType::IEJoin => "IEJoin".to_string(),
             RangeJoinType::Merge => "MergeJoin".to_string(),
+            RangeJoinType::Asof => "AsofJoin".to_string(),
         },
         children,
     ))
@@ -59,6 +59,7 @@ use crate::executor::physical_plans::ExpressionScan;
 use crate::executor::physical_plans::Filter;
 use crate::executor::physical_plans::HashJoin;
 use crate::executor::physical_plans::Limit;
+use crate::executor::physical_plans::MatchRecognize;
 use crate::executor::physical_plans::Mutation;
 use crate::executor::physical_plans::ProjectSet;
 use crate::executor::physical_plans::RangeJoin;
@@ -93,6 +94,7 @@ pub enum PhysicalPlan {
     Window(Window),
     Sort(Sort),
     WindowPartition(WindowPartition),
+    MatchRecognize(MatchRecognize),
     Limit(Limit),
     RowFetch(RowFetch),
     HashJoin(HashJoin),
@@ -210,6 +212,11 @@ impl PhysicalPlan {
                 *next_id += 1;
                 plan.input.adjust_plan_id(next_id);
             }
+            PhysicalPlan::MatchRecognize(plan) => {
+                plan.plan_id = *next_id;
+                *next_id += 1;
+                plan.input.adjust_plan_id(next_id);
+            }
             PhysicalPlan::Sort(plan) => {
                 plan.plan_id = *next_id;
                 *next_id += 1;
@@ -421,6 +428,7 @@ impl PhysicalPlan {
             PhysicalPlan::AggregateFinal(v) => v.plan_id,
             PhysicalPlan::Window(v) => v.plan_id,
             PhysicalPlan::WindowPartition(v) => v.plan_id,
+            PhysicalPlan::MatchRecognize(v) => v.plan_id,
             PhysicalPlan::Sort(v) => v.plan_id,
             PhysicalPlan::Limit(v) => v.plan_id,
             PhysicalPlan::RowFetch(v) => v.plan_id,
@@ -475,6 +483,7 @@ impl PhysicalPlan {
             PhysicalPlan::AggregateFinal(plan) => plan.output_schema(),
             PhysicalPlan::Window(plan) => plan.output_schema(),
             PhysicalPlan::WindowPartition(plan) => plan.output_schema(),
+            PhysicalPlan::MatchRecognize(plan) => plan.output_schema(),
             PhysicalPlan::Sort(plan) => plan.output_schema(),
             PhysicalPlan::Limit(plan) => plan.output_schema(),
             PhysicalPlan::RowFetch(plan) => plan.output_schema(),
@@ -536,6 +545,7 @@ impl PhysicalPlan {
             PhysicalPlan::AggregateFinal(_) => "AggregateFinal".to_string(),
             PhysicalPlan::Window(_) => "Window".to_string(),
             PhysicalPlan::WindowPartition(_) => "WindowPartition".to_string(),
+            PhysicalPlan::MatchRecognize(_) => "MatchRecognize".to_string(),
             PhysicalPlan::Sort(_) => "Sort".to_string(),
             PhysicalPlan::Limit(_) => "Limit".to_string(),
             PhysicalPlan::RowFetch(_) => "RowFetch".to_string(),
@@ -598,6 +608,7 @@ impl PhysicalPlan {
             PhysicalPlan::AggregateFinal(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::Window(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::WindowPartition(plan) => Box::new(std::iter::once(plan.input.as_ref())),
+            PhysicalPlan::MatchRecognize(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::Sort(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::Limit(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::RowFetch(plan) => Box::new(std::iter::once(plan.input.as_ref())),
@@ -660,6 +671,7 @@ impl PhysicalPlan {
             PhysicalPlan::EvalScalar(plan) => plan.input.try_find_single_data_source(),
             PhysicalPlan::Window(plan) => plan.input.try_find_single_data_source(),
             PhysicalPlan::WindowPartition(plan) => plan.input.try_find_single_data_source(),
+            PhysicalPlan::MatchRecognize(plan) => plan.input.try_find_single_data_source(),
             PhysicalPlan::Sort(plan) => plan.input.try_find_single_data_source(),
             PhysicalPlan::Limit(plan) => plan.input.try_find_single_data_source(),
             PhysicalPlan::Exchange(plan) => plan.input.try_find_single_data_source(),
@@ -826,6 +838,7 @@ impl PhysicalPlan {
 
                 format!("partition by {}, order by {}", partition_by, order_by)
             }
+            PhysicalPlan::MatchRecognize(v) => format!("pattern ({})", v.pattern_display),
             PhysicalPlan::RowFetch(v) => {
                 let table_schema = v.source.source_info.schema();
                 let projected_schema = v.cols_to_fetch.project_schema(&table_schema);
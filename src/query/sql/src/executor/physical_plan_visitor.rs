@@ -50,6 +50,7 @@ use crate::executor::physical_plans::ExchangeSource;
 use crate::executor::physical_plans::Filter;
 use crate::executor::physical_plans::HashJoin;
 use crate::executor::physical_plans::Limit;
+use crate::executor::physical_plans::MatchRecognize;
 use crate::executor::physical_plans::Mutation;
 use crate::executor::physical_plans::MutationSource;
 use crate::executor::physical_plans::ProjectSet;
@@ -79,6 +80,7 @@ pub trait PhysicalPlanReplacer {
             PhysicalPlan::AggregateFinal(plan) => self.replace_aggregate_final(plan),
             PhysicalPlan::Window(plan) => self.replace_window(plan),
             PhysicalPlan::WindowPartition(plan) => self.replace_window_partition(plan),
+            PhysicalPlan::MatchRecognize(plan) => self.replace_match_recognize(plan),
             PhysicalPlan::Sort(plan) => self.replace_sort(plan),
             PhysicalPlan::Limit(plan) => self.replace_limit(plan),
             PhysicalPlan::RowFetch(plan) => self.replace_row_fetch(plan),
@@ -249,6 +251,15 @@ pub trait PhysicalPlanReplacer {
         }))
     }
 
+    fn replace_match_recognize(&mut self, plan: &MatchRecognize) -> Result<PhysicalPlan> {
+        let input = self.replace(&plan.input)?;
+
+        Ok(PhysicalPlan::MatchRecognize(MatchRecognize {
+            input: Box::new(input),
+            ..plan.clone()
+        }))
+    }
+
     fn replace_hash_join(&mut self, plan: &HashJoin) -> Result<PhysicalPlan> {
         let build = self.replace(&plan.build)?;
         let probe = self.replace(&plan.probe)?;
@@ -288,6 +299,7 @@ pub trait PhysicalPlanReplacer {
             left: Box::new(left),
             right: Box::new(right),
             conditions: plan.conditions.clone(),
+            equi_conditions: plan.equi_conditions.clone(),
             other_conditions: plan.other_conditions.clone(),
             join_type: plan.join_type.clone(),
             range_join_type: plan.range_join_type.clone(),
@@ -669,6 +681,9 @@ impl PhysicalPlan {
                 PhysicalPlan::WindowPartition(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
+                PhysicalPlan::MatchRecognize(plan) => {
+                    Self::traverse(&plan.input, pre_visit, visit, post_visit);
+                }
                 PhysicalPlan::Sort(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
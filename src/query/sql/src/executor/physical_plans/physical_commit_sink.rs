@@ -36,6 +36,8 @@ pub struct CommitSink {
     pub update_stream_meta: Vec<UpdateStreamMetaReq>,
     pub deduplicated_label: Option<String>,
     pub table_meta_timestamps: TableMetaTimestamps,
+    // Check the PRIMARY KEY and UNIQUE constraints of the blocks written by the mutation.
+    pub check_unique_keys: bool,
 
     // Used for recluster.
     pub recluster_info: Option<ReclusterInfoSideCar>,
@@ -104,6 +104,7 @@ impl PhysicalPlanBuilder {
             update_stream_meta: vec![],
             deduplicated_label: None,
             plan_id: u32::MAX,
+            check_unique_keys: false,
             recluster_info: None,
             table_meta_timestamps,
         }));
@@ -30,5 +30,8 @@ pub struct DistributedInsertSelect {
     pub select_schema: DataSchemaRef,
     pub select_column_bindings: Vec<ColumnBinding>,
     pub cast_needed: bool,
+    pub overwrite: bool,
+    // the constraints whose conflicting rows are skipped
+    pub ignore_conflicts: Vec<String>,
     pub table_meta_timestamps: TableMetaTimestamps,
 }
@@ -0,0 +1,292 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::ConstantFolder;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::DataSchemaRefExt;
+use databend_common_expression::RemoteExpr;
+use databend_common_functions::BUILTIN_FUNCTIONS;
+
+use crate::executor::cast_expr_to_non_null_boolean;
+use crate::executor::explain::PlanStatsInfo;
+use crate::executor::physical_plans::common::AggregateFunctionDesc;
+use crate::executor::physical_plans::common::AggregateFunctionSignature;
+use crate::executor::PhysicalPlan;
+use crate::executor::PhysicalPlanBuilder;
+use crate::optimizer::SExpr;
+use crate::plans::MatchFunction;
+use crate::plans::MatchInstruction;
+use crate::plans::MatchRowsPerMatch;
+use crate::plans::MatchSkip;
+use crate::ColumnSet;
+use crate::IndexType;
+use crate::ScalarExpr;
+use crate::TypeCheck;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MatchRecognize {
+    // A unique id of operator in a `PhysicalPlan` tree, only used for display.
+    pub plan_id: u32,
+    pub input: Box<PhysicalPlan>,
+    pub partition_by: Vec<IndexType>,
+    pub navigations: Vec<MatchNavigationDesc>,
+    pub variables: Vec<MatchVariableDesc>,
+    pub pattern: Vec<MatchInstruction>,
+    pub pattern_display: String,
+    pub measures: Vec<MatchMeasureDesc>,
+    pub rows_per_match: MatchRowsPerMatch,
+    pub after_match_skip: MatchSkip,
+
+    // Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl MatchRecognize {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        let input_schema = self.input.output_schema()?;
+        let mut fields = match self.rows_per_match {
+            MatchRowsPerMatch::OneRow => self
+                .partition_by
+                .iter()
+                .map(|index| Ok(input_schema.field_with_name(&index.to_string())?.clone()))
+                .collect::<Result<Vec<_>>>()?,
+            MatchRowsPerMatch::AllRows => input_schema.fields().clone(),
+        };
+        for measure in self.measures.iter() {
+            fields.push(DataField::new(
+                &measure.index.to_string(),
+                measure.data_type.clone(),
+            ));
+        }
+        Ok(DataSchemaRefExt::create(fields))
+    }
+
+    /// The schema the `DEFINE` conditions are evaluated on, the input columns
+    /// followed by the navigation columns.
+    pub fn condition_schema(&self) -> Result<DataSchemaRef> {
+        let input_schema = self.input.output_schema()?;
+        let mut fields = input_schema.fields().clone();
+        for navigation in self.navigations.iter() {
+            fields.push(DataField::new(
+                &navigation.index.to_string(),
+                navigation.data_type.clone(),
+            ));
+        }
+        Ok(DataSchemaRefExt::create(fields))
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MatchNavigationDesc {
+    pub index: IndexType,
+    pub arg: IndexType,
+    /// Negative for `PREV`, positive for `NEXT`.
+    pub offset: i64,
+    pub data_type: DataType,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MatchVariableDesc {
+    pub name: String,
+    /// Evaluated on the schema returned by `MatchRecognize::condition_schema`.
+    pub condition: Option<RemoteExpr>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MatchMeasureDesc {
+    pub index: IndexType,
+    pub func: MatchFunctionDesc,
+    pub variable: Option<usize>,
+    pub data_type: DataType,
+    pub display: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum MatchFunctionDesc {
+    First(IndexType),
+    Last(IndexType),
+    Aggregate(AggregateFunctionDesc),
+    MatchNumber,
+    Classifier,
+}
+
+impl PhysicalPlanBuilder {
+    pub(crate) async fn build_match_recognize(
+        &mut self,
+        s_expr: &SExpr,
+        match_recognize: &crate::plans::MatchRecognize,
+        required: ColumnSet,
+        stat_info: PlanStatsInfo,
+    ) -> Result<PhysicalPlan> {
+        // 1. Prune unused Columns.
+        // The navigation and measure columns are computed by `MatchRecognize` itself.
+        let mut child_required = match match_recognize.rows_per_match {
+            MatchRowsPerMatch::OneRow => ColumnSet::new(),
+            MatchRowsPerMatch::AllRows => required,
+        };
+        child_required.extend(match_recognize.used_columns());
+        for navigation in match_recognize.navigations.iter() {
+            child_required.remove(&navigation.index);
+        }
+        for measure in match_recognize.measures.iter() {
+            child_required.remove(&measure.index);
+        }
+
+        // 2. Build physical plan.
+        let input = self.build(s_expr.child(0)?, child_required).await?;
+        let mut plan = MatchRecognize {
+            plan_id: 0,
+            input: Box::new(input),
+            partition_by: match_recognize
+                .partition_by
+                .iter()
+                .map(|item| item.index)
+                .collect(),
+            navigations: match_recognize
+                .navigations
+                .iter()
+                .map(|navigation| MatchNavigationDesc {
+                    index: navigation.index,
+                    arg: navigation.arg,
+                    offset: navigation.offset,
+                    data_type: *navigation.data_type.clone(),
+                })
+                .collect(),
+            variables: vec![],
+            pattern: match_recognize.pattern.clone(),
+            pattern_display: match_recognize.pattern_display.clone(),
+            measures: vec![],
+            rows_per_match: match_recognize.rows_per_match,
+            after_match_skip: match_recognize.after_match_skip,
+            stat_info: Some(stat_info),
+        };
+
+        let condition_schema = plan.condition_schema()?;
+        plan.variables = match_recognize
+            .variables
+            .iter()
+            .map(|variable| {
+                let condition = variable
+                    .condition
+                    .as_ref()
+                    .map(|condition| {
+                        let expr = condition
+                            .type_check(condition_schema.as_ref())?
+                            .project_column_ref(|index| {
+                                condition_schema.index_of(&index.to_string()).unwrap()
+                            });
+                        let expr = cast_expr_to_non_null_boolean(expr)?;
+                        let (expr, _) =
+                            ConstantFolder::fold(&expr, &self.func_ctx, &BUILTIN_FUNCTIONS);
+                        Ok::<_, ErrorCode>(expr.as_remote_expr())
+                    })
+                    .transpose()?;
+                Ok(MatchVariableDesc {
+                    name: variable.name.clone(),
+                    condition,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        plan.measures = match_recognize
+            .measures
+            .iter()
+            .map(|measure| {
+                let (func, display) = match &measure.func {
+                    MatchFunction::First(arg) => (
+                        MatchFunctionDesc::First(*arg),
+                        format!("first({})", self.metadata.read().column(*arg).name()),
+                    ),
+                    MatchFunction::Last(arg) => (
+                        MatchFunctionDesc::Last(*arg),
+                        format!("last({})", self.metadata.read().column(*arg).name()),
+                    ),
+                    MatchFunction::MatchNumber => {
+                        (MatchFunctionDesc::MatchNumber, "match_number()".to_string())
+                    }
+                    MatchFunction::Classifier => {
+                        (MatchFunctionDesc::Classifier, "classifier()".to_string())
+                    }
+                    MatchFunction::Aggregate(agg) => {
+                        let display = ScalarExpr::AggregateFunction(agg.clone())
+                            .as_expr()?
+                            .sql_display();
+                        let desc = AggregateFunctionDesc {
+                            sig: AggregateFunctionSignature {
+                                name: agg.func_name.clone(),
+                                udaf: None,
+                                return_type: *agg.return_type.clone(),
+                                args: agg
+                                    .args
+                                    .iter()
+                                    .map(|s| s.data_type())
+                                    .collect::<Result<_>>()?,
+                                params: agg.params.clone(),
+                                sort_descs: agg
+                                    .sort_descs
+                                    .iter()
+                                    .map(|d| d.try_into())
+                                    .collect::<Result<_>>()?,
+                            },
+                            output_column: measure.index,
+                            arg_indices: agg
+                                .args
+                                .iter()
+                                .map(|arg| {
+                                    if let ScalarExpr::BoundColumnRef(col) = arg {
+                                        Ok(col.column.index)
+                                    } else {
+                                        Err(ErrorCode::Internal(
+                                            "Aggregate function argument must be a BoundColumnRef"
+                                                .to_string(),
+                                        ))
+                                    }
+                                })
+                                .collect::<Result<_>>()?,
+                            sort_desc_indices: agg
+                                .sort_descs
+                                .iter()
+                                .map(|desc| {
+                                    if let ScalarExpr::BoundColumnRef(col) = &desc.expr {
+                                        Ok(col.column.index)
+                                    } else {
+                                        Err(ErrorCode::Internal(
+                                            "Aggregate function sort description must be a BoundColumnRef"
+                                                .to_string(),
+                                        ))
+                                    }
+                                })
+                                .collect::<Result<_>>()?,
+                            display: display.clone(),
+                        };
+                        (MatchFunctionDesc::Aggregate(desc), display)
+                    }
+                };
+                Ok(MatchMeasureDesc {
+                    index: measure.index,
+                    func,
+                    variable: measure.variable,
+                    data_type: *measure.data_type.clone(),
+                    display,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(PhysicalPlan::MatchRecognize(plan))
+    }
+}
@@ -42,6 +42,7 @@ use databend_storages_common_table_meta::meta::Location;
 use databend_storages_common_table_meta::meta::TableMetaTimestamps;
 use databend_storages_common_table_meta::meta::NUM_BLOCK_ID_BITS;
 use databend_storages_common_table_meta::readers::snapshot_reader::TableSnapshotAccessor;
+use databend_storages_common_table_meta::table::get_table_constraints;
 use itertools::Itertools;
 
 use super::ColumnMutation;
@@ -138,6 +139,24 @@ impl PhysicalPlanBuilder {
         let table_info = table.get_table_info();
         let table_name = table_name.clone();
 
+        // the keys of the updated and inserted rows are checked before commit
+        let check_unique_keys = {
+            let schema = table.schema_with_stream();
+            let key_indexes = get_table_constraints(table.options())?
+                .iter()
+                .filter_map(|constraint| constraint.key_columns())
+                .flatten()
+                .filter_map(|name| schema.index_of(name).ok())
+                .collect::<HashSet<_>>();
+            !key_indexes.is_empty()
+                && (!unmatched_evaluators.is_empty()
+                    || matched_evaluators.iter().any(|evaluator| {
+                        evaluator.update.as_ref().is_some_and(|update_list| {
+                            update_list.keys().any(|idx| key_indexes.contains(idx))
+                        })
+                    }))
+        };
+
         let mutation_build_info = self.mutation_build_info.clone().unwrap();
         let mutation_input_schema = plan.output_schema()?;
 
@@ -156,6 +175,7 @@ impl PhysicalPlanBuilder {
                 plan_id: u32::MAX,
                 recluster_info: None,
                 table_meta_timestamps: mutation_build_info.table_meta_timestamps,
+                check_unique_keys: false,
             }));
             plan.adjust_plan_id(&mut 0);
             return Ok(plan);
@@ -248,6 +268,7 @@ impl PhysicalPlanBuilder {
                 plan_id: u32::MAX,
                 recluster_info: None,
                 table_meta_timestamps: mutation_build_info.table_meta_timestamps,
+                check_unique_keys,
             }));
 
             plan.adjust_plan_id(&mut 0);
@@ -484,6 +505,7 @@ impl PhysicalPlanBuilder {
             plan_id: u32::MAX,
             recluster_info: None,
             table_meta_timestamps: mutation_build_info.table_meta_timestamps,
+            check_unique_keys,
         }));
         physical_plan.adjust_plan_id(&mut 0);
         Ok(physical_plan)
@@ -15,6 +15,7 @@
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::type_check::common_super_type;
+use databend_common_expression::DataField;
 use databend_common_expression::DataSchemaRef;
 use databend_common_expression::DataSchemaRefExt;
 use databend_common_expression::RemoteExpr;
@@ -29,6 +30,8 @@ use crate::optimizer::ColumnSet;
 use crate::optimizer::RelExpr;
 use crate::optimizer::RelationalProperty;
 use crate::optimizer::SExpr;
+use crate::plans::Join;
+use crate::plans::JoinEquiCondition;
 use crate::plans::JoinType;
 use crate::ScalarExpr;
 use crate::TypeCheck;
@@ -42,9 +45,12 @@ pub struct RangeJoin {
     // The first two conditions: (>, >=, <, <=)
     // Condition's left/right side only contains one table's column
     pub conditions: Vec<RangeJoinCondition>,
+    // The equi conditions of asof join, the match condition is only evaluated
+    // between the rows with equal keys.
+    pub equi_conditions: Vec<RangeJoinCondition>,
     // The other conditions
     pub other_conditions: Vec<RemoteExpr>,
-    // Now only support inner join, will support left/right join later
+    // Inner join, or asof/left asof join for `RangeJoinType::Asof`
     pub join_type: JoinType,
     pub range_join_type: RangeJoinType,
 
@@ -55,7 +61,16 @@ pub struct RangeJoin {
 impl RangeJoin {
     pub fn output_schema(&self) -> Result<DataSchemaRef> {
         let mut fields = self.left.output_schema()?.fields().clone();
-        fields.extend(self.right.output_schema()?.fields().clone());
+        let right_fields = self.right.output_schema()?.fields().clone();
+        if self.join_type == JoinType::LeftAsof {
+            fields.extend(
+                right_fields
+                    .into_iter()
+                    .map(|field| DataField::new(field.name(), field.data_type().wrap_nullable())),
+            );
+        } else {
+            fields.extend(right_fields);
+        }
         Ok(DataSchemaRefExt::create(fields))
     }
 }
@@ -64,13 +79,14 @@ impl RangeJoin {
 pub enum RangeJoinType {
     IEJoin,
     Merge,
+    Asof,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct RangeJoinCondition {
     pub left_expr: RemoteExpr,
     pub right_expr: RemoteExpr,
-    // "gt" | "lt" | "gte" | "lte"
+    // "gt" | "lt" | "gte" | "lte", or "eq" for the equi conditions
     pub operator: String,
 }
 
@@ -131,6 +147,7 @@ impl PhysicalPlanBuilder {
                     )
                 })
                 .collect::<Result<_>>()?,
+            equi_conditions: vec![],
             other_conditions: other_conditions
                 .iter()
                 .map(|scalar| resolve_scalar(scalar, &merged_schema))
@@ -142,6 +159,92 @@ impl PhysicalPlanBuilder {
     }
 }
 
+impl PhysicalPlanBuilder {
+    pub async fn build_asof_join(
+        &mut self,
+        s_expr: &SExpr,
+        join: &Join,
+        left_required: ColumnSet,
+        right_required: ColumnSet,
+    ) -> Result<PhysicalPlan> {
+        // Unlike the other range joins, the children are not swapped: the left side
+        // is the one whose rows look for the closest right row.
+        let left_prop = RelExpr::with_s_expr(s_expr.child(0)?).derive_relational_prop()?;
+        let right_prop = RelExpr::with_s_expr(s_expr.child(1)?).derive_relational_prop()?;
+
+        let left_side = self.build(s_expr.child(0)?, left_required).await?;
+        let right_side = self.build(s_expr.child(1)?, right_required).await?;
+
+        let left_schema = left_side.output_schema()?;
+        let right_schema = right_side.output_schema()?;
+
+        let conditions = join
+            .non_equi_conditions
+            .iter()
+            .map(|scalar| {
+                resolve_range_condition(
+                    scalar,
+                    &left_schema,
+                    &right_schema,
+                    &left_prop,
+                    &right_prop,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if conditions.len() != 1 {
+            return Err(ErrorCode::Internal(
+                "Asof join must have exactly one match condition",
+            ));
+        }
+
+        Ok(PhysicalPlan::RangeJoin(RangeJoin {
+            plan_id: 0,
+            left: Box::new(left_side),
+            right: Box::new(right_side),
+            conditions,
+            equi_conditions: join
+                .equi_conditions
+                .iter()
+                .map(|condition| resolve_equi_condition(condition, &left_schema, &right_schema))
+                .collect::<Result<_>>()?,
+            other_conditions: vec![],
+            join_type: join.join_type.clone(),
+            range_join_type: RangeJoinType::Asof,
+            stat_info: Some(self.build_plan_stat_info(s_expr)?),
+        }))
+    }
+}
+
+fn resolve_equi_condition(
+    condition: &JoinEquiCondition,
+    left_schema: &DataSchemaRef,
+    right_schema: &DataSchemaRef,
+) -> Result<RangeJoinCondition> {
+    let mut left = condition.left.clone();
+    let mut right = condition.right.clone();
+    let left_data_type = left.data_type()?;
+    let right_data_type = right.data_type()?;
+    if left_data_type.ne(&right_data_type) {
+        let common_type = common_super_type(
+            left_data_type.clone(),
+            right_data_type.clone(),
+            &BUILTIN_FUNCTIONS.default_cast_rules,
+        )
+        .ok_or_else(|| {
+            ErrorCode::IllegalDataType(format!(
+                "Cannot find common type for {left_data_type} and {right_data_type}"
+            ))
+        })?;
+        left = wrap_cast(&left, &common_type);
+        right = wrap_cast(&right, &common_type);
+    }
+    Ok(RangeJoinCondition {
+        left_expr: resolve_scalar(&left, left_schema)?,
+        right_expr: resolve_scalar(&right, right_schema)?,
+        operator: "eq".to_string(),
+    })
+}
+
 fn resolve_range_condition(
     expr: &ScalarExpr,
     left_schema: &DataSchemaRef,
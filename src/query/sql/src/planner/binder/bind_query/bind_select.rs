@@ -99,6 +99,7 @@ impl Binder {
                     join: Join {
                         op: JoinOperator::CrossJoin,
                         condition: JoinCondition::None,
+                        match_condition: None,
                         left: Box::new(left),
                         right: Box::new(right),
                     },
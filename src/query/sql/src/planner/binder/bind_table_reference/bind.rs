@@ -80,6 +80,12 @@ impl Binder {
                 alias,
             } => self.bind_location(bind_context, location, options, alias),
             TableReference::Join { join, .. } => self.bind_join(bind_context, join),
+            TableReference::MatchRecognize {
+                span,
+                table,
+                match_recognize,
+                alias,
+            } => self.bind_match_recognize(bind_context, span, table, match_recognize, alias),
         }
     }
 }
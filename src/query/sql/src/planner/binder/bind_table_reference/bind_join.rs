@@ -77,6 +77,14 @@ impl Binder {
             .expression_scan_context
             .add_hash_join_build_cache(cache_column_bindings, cache_column_indexes);
 
+        if matches!(join.op, JoinOperator::Asof | JoinOperator::LeftAsof)
+            && (join.right.is_lateral_table_function() || join.right.is_lateral_subquery())
+        {
+            return Err(ErrorCode::SemanticError(
+                "ASOF JOIN does not support lateral table references".to_string(),
+            ));
+        }
+
         if join.right.is_lateral_table_function() {
             let (result_expr, bind_context) = self.bind_lateral_table_function(
                 &mut left_context,
@@ -104,6 +112,7 @@ impl Binder {
             &right_column_bindings,
             &join.op,
             &join.condition,
+            join.match_condition.as_deref(),
         )?;
 
         let mut left_derived_scalars = Vec::new();
@@ -120,6 +129,7 @@ impl Binder {
             &mut bind_context,
             &join.op,
             &join.condition,
+            join.match_condition.as_deref(),
             &left_column_bindings,
             &right_column_bindings,
         )?;
@@ -187,6 +197,7 @@ impl Binder {
             &right_context.columns,
             &join_op,
             &join_condition,
+            None,
         )?;
 
         let mut left_column_bindings = left_context.columns.clone();
@@ -203,6 +214,7 @@ impl Binder {
             &mut bind_context,
             &join_op,
             &join_condition,
+            None,
             &left_column_bindings,
             &right_column_bindings,
         )?;
@@ -235,7 +247,7 @@ impl Binder {
         right_column_bindings: &mut Vec<ColumnBinding>,
     ) {
         match join_op {
-            JoinOperator::LeftOuter => {
+            JoinOperator::LeftOuter | JoinOperator::LeftAsof => {
                 self.replace_column_binding(right_derived_scalars, right_column_bindings);
             }
             JoinOperator::RightOuter => {
@@ -288,7 +300,7 @@ impl Binder {
         right_column_bindings: &mut Vec<ColumnBinding>,
     ) {
         match join_op {
-            JoinOperator::LeftOuter | JoinOperator::FullOuter => {
+            JoinOperator::LeftOuter | JoinOperator::FullOuter | JoinOperator::LeftAsof => {
                 for column in right_column_bindings {
                     if !column.data_type.is_nullable_or_null() {
                         column.data_type = Box::new(column.data_type.wrap_nullable());
@@ -314,6 +326,7 @@ impl Binder {
         bind_context: &mut BindContext,
         join_op: &JoinOperator,
         join_condition: &JoinCondition,
+        match_condition: Option<&Expr>,
         left_column_bindings: &[ColumnBinding],
         right_column_bindings: &[ColumnBinding],
     ) -> Result<JoinConditions> {
@@ -340,6 +353,17 @@ impl Binder {
             join_op,
         )?;
 
+        if let Some(match_condition) = match_condition {
+            // The match condition must be the only non-equi condition of an asof join.
+            if !non_equi_conditions.is_empty() || !other_conditions.is_empty() {
+                return Err(ErrorCode::SemanticError(
+                    "ASOF JOIN only supports equality conditions in the join condition".to_string(),
+                ));
+            }
+            non_equi_conditions
+                .push(join_condition_resolver.resolve_match_condition(match_condition)?);
+        }
+
         Ok(JoinConditions {
             left_conditions: left_join_conditions,
             right_conditions: right_join_conditions,
@@ -492,7 +516,9 @@ impl Binder {
                         need_push_down = true;
                         left_push_down.push(predicate.clone());
                     }
-                    JoinType::Full => non_equi_conditions.push(predicate.clone()),
+                    JoinType::Full | JoinType::Asof | JoinType::LeftAsof => {
+                        non_equi_conditions.push(predicate.clone())
+                    }
                 },
                 JoinPredicate::Left(_) => {
                     need_push_down = true;
@@ -545,10 +571,23 @@ impl Binder {
         right_column_bindings: &[ColumnBinding],
         join_op: &JoinOperator,
         join_condition: &JoinCondition,
+        match_condition: Option<&Expr>,
     ) -> Result<()> {
         check_duplicate_join_tables(left_column_bindings, right_column_bindings)?;
 
         match join_op {
+            JoinOperator::Asof | JoinOperator::LeftAsof if match_condition.is_none() => {
+                return Err(ErrorCode::SemanticError(
+                    "asof join should contain MATCH_CONDITION".to_string(),
+                ));
+            }
+            JoinOperator::Asof | JoinOperator::LeftAsof
+                if join_condition == &JoinCondition::Natural =>
+            {
+                return Err(ErrorCode::SemanticError(
+                    "asof join should not be a natural join".to_string(),
+                ));
+            }
             JoinOperator::LeftOuter | JoinOperator::RightOuter | JoinOperator::FullOuter
                 if join_condition == &JoinCondition::None =>
             {
@@ -963,6 +1002,51 @@ impl<'a> JoinConditionResolver<'a> {
         Ok(false)
     }
 
+    // The match condition of an asof join is a comparison between an expression of
+    // the left table and an expression of the right table, e.g. `t1.ts >= t2.ts`.
+    fn resolve_match_condition(&mut self, match_condition: &Expr) -> Result<ScalarExpr> {
+        let mut join_context = (*self.join_context).clone();
+        bind_join_columns(
+            self.left_column_bindings,
+            self.right_column_bindings,
+            &mut join_context,
+        );
+        let mut scalar_binder = ScalarBinder::new(
+            &mut join_context,
+            self.ctx.clone(),
+            self.name_resolution_ctx,
+            self.metadata.clone(),
+            &[],
+        );
+        let (scalar, _) = scalar_binder.bind(match_condition)?;
+        self.check_join_allowed_scalar_expr(&vec![scalar.clone()])?;
+
+        let (left_columns, right_columns) = self.left_right_columns()?;
+        let is_valid = match &scalar {
+            ScalarExpr::FunctionCall(func)
+                if func.arguments.len() == 2
+                    && matches!(func.func_name.as_str(), "gt" | "gte" | "lt" | "lte") =>
+            {
+                let arg1_columns = func.arguments[0].used_columns();
+                let arg2_columns = func.arguments[1].used_columns();
+                !arg1_columns.is_empty()
+                    && !arg2_columns.is_empty()
+                    && ((arg1_columns.is_subset(&left_columns)
+                        && arg2_columns.is_subset(&right_columns))
+                        || (arg1_columns.is_subset(&right_columns)
+                            && arg2_columns.is_subset(&left_columns)))
+            }
+            _ => false,
+        };
+        if !is_valid {
+            return Err(ErrorCode::SemanticError(
+                "MATCH_CONDITION must be a comparison (>, >=, <, <=) between a column of the left table and a column of the right table".to_string(),
+            )
+            .set_span(match_condition.span()));
+        }
+        Ok(scalar)
+    }
+
     fn left_right_columns(&self) -> Result<(ColumnSet, ColumnSet)> {
         let left_columns: ColumnSet =
             self.left_column_bindings
@@ -1004,6 +1088,8 @@ fn join_type(join_type: &JoinOperator) -> JoinType {
         JoinOperator::RightSemi => JoinType::RightSemi,
         JoinOperator::LeftAnti => JoinType::LeftAnti,
         JoinOperator::RightAnti => JoinType::RightAnti,
+        JoinOperator::Asof => JoinType::Asof,
+        JoinOperator::LeftAsof => JoinType::LeftAsof,
     }
 }
 
@@ -0,0 +1,953 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_ast::ast::AfterMatchSkip;
+use databend_common_ast::ast::ColumnID;
+use databend_common_ast::ast::ColumnRef;
+use databend_common_ast::ast::Expr;
+use databend_common_ast::ast::FunctionCall;
+use databend_common_ast::ast::Identifier;
+use databend_common_ast::ast::Literal;
+use databend_common_ast::ast::MatchPattern;
+use databend_common_ast::ast::MatchRecognize as ASTMatchRecognize;
+use databend_common_ast::ast::RowsPerMatch;
+use databend_common_ast::ast::TableAlias;
+use databend_common_ast::ast::TableReference;
+use databend_common_ast::Span;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_functions::aggregates::AggregateFunctionFactory;
+use derive_visitor::Drive;
+use derive_visitor::DriveMut;
+use derive_visitor::Visitor;
+use derive_visitor::VisitorMut;
+
+use crate::binder::ColumnBinding;
+use crate::binder::ColumnBindingBuilder;
+use crate::normalize_identifier;
+use crate::optimizer::SExpr;
+use crate::plans::AggregateFunctionScalarSortDesc;
+use crate::plans::BoundColumnRef;
+use crate::plans::EvalScalar;
+use crate::plans::MatchFunction;
+use crate::plans::MatchInstruction;
+use crate::plans::MatchMeasure;
+use crate::plans::MatchNavigation;
+use crate::plans::MatchRecognize;
+use crate::plans::MatchRowsPerMatch;
+use crate::plans::MatchSkip;
+use crate::plans::MatchVariable;
+use crate::plans::ScalarExpr;
+use crate::plans::ScalarItem;
+use crate::plans::Sort;
+use crate::plans::SortItem;
+use crate::plans::WindowFuncType;
+use crate::plans::WindowPartition;
+use crate::BindContext;
+use crate::Binder;
+use crate::NameResolutionContext;
+use crate::ScalarBinder;
+use crate::Visibility;
+
+/// The maximum number of instructions a row pattern can be compiled to,
+/// bounded repetitions like `a{1,1000}` are unrolled.
+const MAX_PATTERN_INSTRUCTIONS: usize = 10000;
+
+impl Binder {
+    /// Bind `table MATCH_RECOGNIZE ( ... ) [ AS alias ]`.
+    pub(crate) fn bind_match_recognize(
+        &mut self,
+        bind_context: &mut BindContext,
+        span: &Span,
+        table: &TableReference,
+        match_recognize: &ASTMatchRecognize,
+        alias: &Option<TableAlias>,
+    ) -> Result<(SExpr, BindContext)> {
+        let (child, mut input_context) = self.bind_table_reference(bind_context, table)?;
+
+        // Items evaluated below the sort, they are the arguments of
+        // partition keys, order keys, navigations and measures.
+        let mut scalar_items: Vec<ScalarItem> = vec![];
+
+        // 1. Collect pattern variables and compile the pattern.
+        let mut variable_names = vec![];
+        self.collect_pattern_variables(&match_recognize.pattern, &mut variable_names);
+        let mut pattern = vec![];
+        compile_pattern(
+            &match_recognize.pattern,
+            &self.name_resolution_ctx,
+            &variable_names,
+            &mut pattern,
+        )
+        .map_err(|e| e.set_span(*span))?;
+        pattern.push(MatchInstruction::Match);
+
+        // 2. Bind `PARTITION BY` and `ORDER BY`.
+        let mut partition_by = Vec::with_capacity(match_recognize.partition_by.len());
+        let mut partition_bindings = Vec::with_capacity(match_recognize.partition_by.len());
+        for expr in match_recognize.partition_by.iter() {
+            check_match_expr(expr, "PARTITION BY")?;
+            let (scalar, _) = self.bind_match_expr(&mut input_context, expr)?;
+            let column = self.match_argument(&expr.to_string(), scalar, &mut scalar_items)?;
+            partition_by.push(ScalarItem {
+                index: column.index,
+                scalar: ScalarExpr::BoundColumnRef(BoundColumnRef {
+                    span: expr.span(),
+                    column: column.clone(),
+                }),
+            });
+            partition_bindings.push(column);
+        }
+
+        let default_nulls_first = self.ctx.get_settings().get_nulls_first();
+        let mut sort_items: Vec<SortItem> = partition_by
+            .iter()
+            .map(|item| SortItem {
+                index: item.index,
+                asc: true,
+                nulls_first: default_nulls_first(true),
+            })
+            .collect();
+        for order in match_recognize.order_by.iter() {
+            check_match_expr(&order.expr, "ORDER BY")?;
+            let (scalar, _) = self.bind_match_expr(&mut input_context, &order.expr)?;
+            let column = self.match_argument(&order.expr.to_string(), scalar, &mut scalar_items)?;
+            let asc = order.asc.unwrap_or(true);
+            sort_items.push(SortItem {
+                index: column.index,
+                asc,
+                nulls_first: order
+                    .nulls_first
+                    .unwrap_or_else(|| default_nulls_first(asc)),
+            });
+        }
+
+        // 3. Bind `DEFINE` conditions, `PREV` and `NEXT` are replaced with navigation columns.
+        let mut navigations: Vec<MatchNavigation> = vec![];
+        let mut conditions: Vec<Option<ScalarExpr>> = vec![None; variable_names.len()];
+        for definition in match_recognize.define.iter() {
+            let name = normalize_identifier(&definition.name, &self.name_resolution_ctx).name;
+            let Some(variable) = variable_names.iter().position(|v| v == &name) else {
+                return Err(ErrorCode::SemanticError(format!(
+                    "pattern variable {name} is defined but not used in PATTERN"
+                ))
+                .set_span(definition.name.span));
+            };
+            if conditions[variable].is_some() {
+                return Err(ErrorCode::SemanticError(format!(
+                    "pattern variable {name} is defined more than once"
+                ))
+                .set_span(definition.name.span));
+            }
+
+            let mut condition = definition.condition.clone();
+            let mut rewriter =
+                MatchNavigationRewriter::new(&self.name_resolution_ctx, &variable_names, &name);
+            condition.drive_mut(&mut rewriter);
+            if let Some(err) = rewriter.error {
+                return Err(err);
+            }
+            check_match_expr(&condition, "DEFINE")?;
+
+            let mut condition_context = input_context.clone();
+            for (placeholder, arg, offset) in rewriter.navigations {
+                check_match_expr(&arg, "DEFINE")?;
+                let (scalar, data_type) = self.bind_match_expr(&mut input_context, &arg)?;
+                let arg_column =
+                    self.match_argument(&arg.to_string(), scalar, &mut scalar_items)?;
+                let data_type = data_type.wrap_nullable();
+                let display_name = if offset < 0 {
+                    format!("prev({arg}, {})", -offset)
+                } else {
+                    format!("next({arg}, {offset})")
+                };
+                let index =
+                    self.metadata
+                        .write()
+                        .add_derived_column(display_name, data_type.clone(), None);
+                navigations.push(MatchNavigation {
+                    index,
+                    arg: arg_column.index,
+                    offset,
+                    data_type: Box::new(data_type.clone()),
+                });
+                condition_context.add_column_binding(
+                    ColumnBindingBuilder::new(
+                        placeholder,
+                        index,
+                        Box::new(data_type),
+                        Visibility::Visible,
+                    )
+                    .build(),
+                );
+            }
+            let (scalar, _) = self.bind_match_expr(&mut condition_context, &condition)?;
+            conditions[variable] = Some(scalar);
+        }
+        let variables = variable_names
+            .iter()
+            .zip(conditions)
+            .map(|(name, condition)| MatchVariable {
+                name: name.clone(),
+                condition,
+            })
+            .collect::<Vec<_>>();
+
+        // 4. Bind `MEASURES`, the pattern functions are computed by `MatchRecognize`
+        // and the rest of each measure is evaluated on top of it.
+        let mut measures: Vec<MatchMeasure> = vec![];
+        let mut measure_items: Vec<ScalarItem> = vec![];
+        let mut measure_bindings: Vec<ColumnBinding> = vec![];
+        for measure in match_recognize.measures.iter() {
+            let mut expr = measure.expr.clone();
+            let mut rewriter =
+                MatchMeasureRewriter::new(&self.name_resolution_ctx, &variable_names);
+            expr.drive_mut(&mut rewriter);
+            if let Some(err) = rewriter.error {
+                return Err(err);
+            }
+            check_match_expr(&expr, "MEASURES")?;
+
+            // Measures see the partition columns in `ONE ROW PER MATCH` mode,
+            // and all the input columns in `ALL ROWS PER MATCH` mode.
+            let mut measure_context = input_context.clone();
+            if match_recognize.rows_per_match == RowsPerMatch::OneRow {
+                measure_context.columns = partition_bindings.clone();
+            }
+            for call in rewriter.calls {
+                let (func, data_type) = self.bind_match_function(
+                    &mut input_context,
+                    &call.expr,
+                    &call.kind,
+                    &mut scalar_items,
+                )?;
+                let index = self.metadata.write().add_derived_column(
+                    call.expr.to_string(),
+                    data_type.clone(),
+                    None,
+                );
+                measures.push(MatchMeasure {
+                    index,
+                    func,
+                    variable: call.variable,
+                    data_type: Box::new(data_type.clone()),
+                });
+                measure_context.add_column_binding(
+                    ColumnBindingBuilder::new(
+                        call.placeholder,
+                        index,
+                        Box::new(data_type),
+                        Visibility::Visible,
+                    )
+                    .build(),
+                );
+            }
+
+            let (scalar, data_type) = self.bind_match_expr(&mut measure_context, &expr)?;
+            let name = normalize_identifier(&measure.alias, &self.name_resolution_ctx).name;
+            let column = if let ScalarExpr::BoundColumnRef(column_ref) = &scalar {
+                let mut column = column_ref.column.clone();
+                column.column_name = name;
+                column
+            } else {
+                let column =
+                    self.create_derived_column_binding(name, data_type, Some(scalar.clone()));
+                measure_items.push(ScalarItem {
+                    index: column.index,
+                    scalar,
+                });
+                column
+            };
+            measure_bindings.push(column);
+        }
+
+        // 5. Bind `AFTER MATCH SKIP`.
+        let after_match_skip = match &match_recognize.after_match_skip {
+            AfterMatchSkip::PastLastRow => MatchSkip::PastLastRow,
+            AfterMatchSkip::ToNextRow => MatchSkip::ToNextRow,
+            AfterMatchSkip::ToFirst(ident) => {
+                MatchSkip::ToFirst(self.pattern_variable(ident, &variable_names)?)
+            }
+            AfterMatchSkip::ToLast(ident) => {
+                MatchSkip::ToLast(self.pattern_variable(ident, &variable_names)?)
+            }
+        };
+        let rows_per_match = match match_recognize.rows_per_match {
+            RowsPerMatch::OneRow => MatchRowsPerMatch::OneRow,
+            RowsPerMatch::AllRows => MatchRowsPerMatch::AllRows,
+        };
+
+        // 6. Build the plan:
+        // EvalScalar(measures) -> MatchRecognize -> Sort -> EvalScalar(arguments) -> child
+        let mut s_expr = child;
+        if !scalar_items.is_empty() {
+            s_expr = SExpr::create_unary(
+                Arc::new(
+                    EvalScalar {
+                        items: scalar_items,
+                    }
+                    .into(),
+                ),
+                Arc::new(s_expr),
+            );
+        }
+        if !sort_items.is_empty() {
+            let sort = Sort {
+                items: sort_items,
+                limit: None,
+                after_exchange: None,
+                pre_projection: None,
+                window_partition: if partition_by.is_empty() {
+                    None
+                } else {
+                    Some(WindowPartition {
+                        partition_by: partition_by.clone(),
+                        top: None,
+                        func: WindowFuncType::RowNumber,
+                    })
+                },
+            };
+            s_expr = SExpr::create_unary(Arc::new(sort.into()), Arc::new(s_expr));
+        }
+        let match_recognize_plan = MatchRecognize {
+            partition_by,
+            navigations,
+            variables,
+            pattern,
+            pattern_display: match_recognize.pattern.to_string(),
+            measures,
+            rows_per_match,
+            after_match_skip,
+        };
+        s_expr = SExpr::create_unary(Arc::new(match_recognize_plan.into()), Arc::new(s_expr));
+        if !measure_items.is_empty() {
+            s_expr = SExpr::create_unary(
+                Arc::new(
+                    EvalScalar {
+                        items: measure_items,
+                    }
+                    .into(),
+                ),
+                Arc::new(s_expr),
+            );
+        }
+
+        // 7. Build the output context.
+        let mut output_context = BindContext::with_parent(bind_context.clone())?;
+        let columns = match rows_per_match {
+            MatchRowsPerMatch::OneRow => partition_bindings,
+            MatchRowsPerMatch::AllRows => input_context.columns.clone(),
+        };
+        for column in columns.into_iter().chain(measure_bindings) {
+            output_context.add_column_binding(column);
+        }
+        if let Some(alias) = alias {
+            output_context.apply_table_alias(alias, &self.name_resolution_ctx)?;
+        }
+
+        Ok((s_expr, output_context))
+    }
+
+    fn bind_match_expr(
+        &mut self,
+        bind_context: &mut BindContext,
+        expr: &Expr,
+    ) -> Result<(ScalarExpr, DataType)> {
+        let mut scalar_binder = ScalarBinder::new(
+            bind_context,
+            self.ctx.clone(),
+            &self.name_resolution_ctx,
+            self.metadata.clone(),
+            &[],
+        );
+        scalar_binder.forbid_udf();
+        scalar_binder.bind(expr)
+    }
+
+    /// Make `scalar` an input column of `MatchRecognize`, a derived column is
+    /// evaluated before the sort if it's not a column reference.
+    fn match_argument(
+        &mut self,
+        name: &str,
+        scalar: ScalarExpr,
+        scalar_items: &mut Vec<ScalarItem>,
+    ) -> Result<ColumnBinding> {
+        let column = if let ScalarExpr::BoundColumnRef(column_ref) = &scalar {
+            column_ref.column.clone()
+        } else {
+            self.create_derived_column_binding(
+                name.to_string(),
+                scalar.data_type()?,
+                Some(scalar.clone()),
+            )
+        };
+        if !scalar_items.iter().any(|item| item.index == column.index) {
+            scalar_items.push(ScalarItem {
+                index: column.index,
+                scalar,
+            });
+        }
+        Ok(column)
+    }
+
+    fn bind_match_function(
+        &mut self,
+        input_context: &mut BindContext,
+        expr: &Expr,
+        kind: &MatchFunctionKind,
+        scalar_items: &mut Vec<ScalarItem>,
+    ) -> Result<(MatchFunction, DataType)> {
+        let (name, args) = match expr {
+            Expr::FunctionCall { func, .. } => {
+                (func.name.name.to_lowercase(), func.args.as_slice())
+            }
+            _ => (String::new(), [].as_slice()),
+        };
+        match kind {
+            MatchFunctionKind::First | MatchFunctionKind::Last => {
+                if args.len() != 1 {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "{name} in MEASURES expects 1 argument, but got {}",
+                        args.len()
+                    ))
+                    .set_span(expr.span()));
+                }
+                check_match_expr(&args[0], "MEASURES")?;
+                let (scalar, data_type) = self.bind_match_expr(input_context, &args[0])?;
+                let column = self.match_argument(&args[0].to_string(), scalar, scalar_items)?;
+                let func = match kind {
+                    MatchFunctionKind::First => MatchFunction::First(column.index),
+                    _ => MatchFunction::Last(column.index),
+                };
+                Ok((func, data_type.wrap_nullable()))
+            }
+            MatchFunctionKind::MatchNumber | MatchFunctionKind::Classifier => {
+                if !args.is_empty() {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "{name} expects 0 arguments, but got {}",
+                        args.len()
+                    ))
+                    .set_span(expr.span()));
+                }
+                if matches!(kind, MatchFunctionKind::MatchNumber) {
+                    Ok((
+                        MatchFunction::MatchNumber,
+                        DataType::Number(NumberDataType::UInt64),
+                    ))
+                } else {
+                    Ok((MatchFunction::Classifier, DataType::String))
+                }
+            }
+            MatchFunctionKind::Aggregate => {
+                for arg in args {
+                    check_match_expr(arg, "MEASURES")?;
+                }
+                let (scalar, data_type) = self.bind_match_expr(input_context, expr)?;
+                let ScalarExpr::AggregateFunction(mut agg) = scalar else {
+                    return Err(ErrorCode::Internal(format!(
+                        "{expr} is expected to be an aggregate function"
+                    )));
+                };
+                let mut args = Vec::with_capacity(agg.args.len());
+                for (i, arg) in agg.args.iter().enumerate() {
+                    let name = format!("{}_arg_{i}", agg.func_name);
+                    let column = self.match_argument(&name, arg.clone(), scalar_items)?;
+                    args.push(ScalarExpr::BoundColumnRef(BoundColumnRef {
+                        span: arg.span(),
+                        column,
+                    }));
+                }
+                let mut sort_descs = Vec::with_capacity(agg.sort_descs.len());
+                for (i, desc) in agg.sort_descs.iter().enumerate() {
+                    let name = format!("{}_sort_desc_{i}", agg.func_name);
+                    let column = self.match_argument(&name, desc.expr.clone(), scalar_items)?;
+                    let is_reuse_index = args.iter().any(|arg| match arg {
+                        ScalarExpr::BoundColumnRef(col) => col.column.index == column.index,
+                        _ => false,
+                    });
+                    sort_descs.push(AggregateFunctionScalarSortDesc {
+                        expr: ScalarExpr::BoundColumnRef(BoundColumnRef {
+                            span: desc.expr.span(),
+                            column,
+                        }),
+                        is_reuse_index,
+                        nulls_first: desc.nulls_first,
+                        asc: desc.asc,
+                    });
+                }
+                agg.args = args;
+                agg.sort_descs = sort_descs;
+                Ok((MatchFunction::Aggregate(agg), data_type))
+            }
+        }
+    }
+
+    fn collect_pattern_variables(&self, pattern: &MatchPattern, variables: &mut Vec<String>) {
+        match pattern {
+            MatchPattern::Symbol(ident) => {
+                let name = normalize_identifier(ident, &self.name_resolution_ctx).name;
+                if !variables.contains(&name) {
+                    variables.push(name);
+                }
+            }
+            MatchPattern::Start | MatchPattern::End => {}
+            MatchPattern::Concat(patterns) | MatchPattern::Alternation(patterns) => {
+                for pattern in patterns {
+                    self.collect_pattern_variables(pattern, variables);
+                }
+            }
+            MatchPattern::Repetition { pattern, .. } | MatchPattern::Group(pattern) => {
+                self.collect_pattern_variables(pattern, variables);
+            }
+        }
+    }
+
+    fn pattern_variable(&self, ident: &Identifier, variables: &[String]) -> Result<usize> {
+        let name = normalize_identifier(ident, &self.name_resolution_ctx).name;
+        variables.iter().position(|v| v == &name).ok_or_else(|| {
+            ErrorCode::SemanticError(format!("pattern variable {name} is not used in PATTERN"))
+                .set_span(ident.span)
+        })
+    }
+}
+
+/// Compile the row pattern into the instructions of a backtracking matcher.
+fn compile_pattern(
+    pattern: &MatchPattern,
+    name_resolution_ctx: &NameResolutionContext,
+    variables: &[String],
+    program: &mut Vec<MatchInstruction>,
+) -> Result<()> {
+    if program.len() > MAX_PATTERN_INSTRUCTIONS {
+        return Err(ErrorCode::SemanticError(
+            "the row pattern of MATCH_RECOGNIZE is too large".to_string(),
+        ));
+    }
+    match pattern {
+        MatchPattern::Symbol(ident) => {
+            let name = normalize_identifier(ident, name_resolution_ctx).name;
+            let position = variables
+                .iter()
+                .position(|v| v == &name)
+                .ok_or_else(|| ErrorCode::Internal(format!("unknown pattern variable {name}")))?;
+            program.push(MatchInstruction::Variable(position));
+        }
+        MatchPattern::Start => program.push(MatchInstruction::PartitionStart),
+        MatchPattern::End => program.push(MatchInstruction::PartitionEnd),
+        MatchPattern::Concat(patterns) => {
+            for pattern in patterns {
+                compile_pattern(pattern, name_resolution_ctx, variables, program)?;
+            }
+        }
+        MatchPattern::Alternation(patterns) => {
+            let mut jumps = vec![];
+            for (i, pattern) in patterns.iter().enumerate() {
+                if i + 1 == patterns.len() {
+                    compile_pattern(pattern, name_resolution_ctx, variables, program)?;
+                    break;
+                }
+                let split = program.len();
+                program.push(MatchInstruction::Split(split + 1, 0));
+                compile_pattern(pattern, name_resolution_ctx, variables, program)?;
+                jumps.push(program.len());
+                program.push(MatchInstruction::Jump(0));
+                program[split] = MatchInstruction::Split(split + 1, program.len());
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = MatchInstruction::Jump(end);
+            }
+        }
+        MatchPattern::Repetition {
+            pattern,
+            min,
+            max,
+            greedy,
+        } => {
+            if let Some(max) = max {
+                if max < min {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "invalid quantifier {{{min},{max}}} in the row pattern"
+                    )));
+                }
+            }
+            for _ in 0..*min {
+                compile_pattern(pattern, name_resolution_ctx, variables, program)?;
+            }
+            let split = |body: usize, next: usize| {
+                if *greedy {
+                    MatchInstruction::Split(body, next)
+                } else {
+                    MatchInstruction::Split(next, body)
+                }
+            };
+            match max {
+                None => {
+                    let start = program.len();
+                    program.push(MatchInstruction::Split(0, 0));
+                    compile_pattern(pattern, name_resolution_ctx, variables, program)?;
+                    program.push(MatchInstruction::Jump(start));
+                    program[start] = split(start + 1, program.len());
+                }
+                Some(max) => {
+                    let mut splits = vec![];
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(MatchInstruction::Split(0, 0));
+                        compile_pattern(pattern, name_resolution_ctx, variables, program)?;
+                    }
+                    let end = program.len();
+                    for start in splits {
+                        program[start] = split(start + 1, end);
+                    }
+                }
+            }
+        }
+        MatchPattern::Group(pattern) => {
+            compile_pattern(pattern, name_resolution_ctx, variables, program)?
+        }
+    }
+    Ok(())
+}
+
+/// Reject the expressions that can't be evaluated row by row in a `MATCH_RECOGNIZE` clause.
+fn check_match_expr(expr: &Expr, clause: &str) -> Result<()> {
+    #[derive(Visitor)]
+    #[visitor(Expr(enter))]
+    struct MatchExprChecker {
+        error: Option<(Span, String)>,
+    }
+
+    impl MatchExprChecker {
+        fn enter_expr(&mut self, expr: &Expr) {
+            if self.error.is_some() {
+                return;
+            }
+            let kind = match expr {
+                Expr::Subquery { .. } | Expr::Exists { .. } | Expr::InSubquery { .. } => {
+                    Some("subquery")
+                }
+                Expr::CountAll {
+                    window: Some(_), ..
+                }
+                | Expr::FunctionCall {
+                    func:
+                        FunctionCall {
+                            window: Some(_), ..
+                        },
+                    ..
+                } => Some("window function"),
+                Expr::CountAll { .. } => Some("aggregate function"),
+                Expr::FunctionCall { func, .. } => match match_function_kind(func) {
+                    Some(MatchFunctionKind::Aggregate) => Some("aggregate function"),
+                    Some(_) => Some("pattern function"),
+                    None if is_navigation(func) => Some("PREV or NEXT"),
+                    None => None,
+                },
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                self.error = Some((expr.span(), kind.to_string()));
+            }
+        }
+    }
+
+    let mut checker = MatchExprChecker { error: None };
+    expr.drive(&mut checker);
+    match checker.error {
+        Some((span, kind)) => Err(ErrorCode::SemanticError(format!(
+            "{kind} is not allowed in {clause} of MATCH_RECOGNIZE"
+        ))
+        .set_span(span)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MatchFunctionKind {
+    First,
+    Last,
+    Aggregate,
+    MatchNumber,
+    Classifier,
+}
+
+fn match_function_kind(func: &FunctionCall) -> Option<MatchFunctionKind> {
+    if func.window.is_some() {
+        return None;
+    }
+    match func.name.name.to_lowercase().as_str() {
+        "first" => Some(MatchFunctionKind::First),
+        "last" => Some(MatchFunctionKind::Last),
+        "match_number" => Some(MatchFunctionKind::MatchNumber),
+        "classifier" => Some(MatchFunctionKind::Classifier),
+        name if AggregateFunctionFactory::instance().contains(name) => {
+            Some(MatchFunctionKind::Aggregate)
+        }
+        _ => None,
+    }
+}
+
+fn is_navigation(func: &FunctionCall) -> bool {
+    func.window.is_none()
+        && (func.name.name.eq_ignore_ascii_case("prev")
+            || func.name.name.eq_ignore_ascii_case("next"))
+}
+
+fn placeholder_column(span: Span, name: &str) -> Expr {
+    Expr::ColumnRef {
+        span,
+        column: ColumnRef {
+            database: None,
+            table: None,
+            column: ColumnID::Name(Identifier::from_name(span, name)),
+        },
+    }
+}
+
+/// Returns the pattern variable qualifying a column reference, e.g. `a` of `a.price`.
+fn column_variable(
+    expr: &Expr,
+    name_resolution_ctx: &NameResolutionContext,
+    variables: &[String],
+) -> Option<String> {
+    if let Expr::ColumnRef {
+        column:
+            ColumnRef {
+                database: None,
+                table: Some(table),
+                ..
+            },
+        ..
+    } = expr
+    {
+        let name = normalize_identifier(table, name_resolution_ctx).name;
+        if variables.contains(&name) {
+            return Some(name);
+        }
+    }
+    None
+}
+
+fn strip_column_variable(expr: &mut Expr) {
+    if let Expr::ColumnRef { column, .. } = expr {
+        column.table = None;
+    }
+}
+
+/// Replace `PREV(expr [, offset])` and `NEXT(expr [, offset])` in a `DEFINE` condition with
+/// placeholder columns, and strip the qualifier of the variable being defined.
+#[derive(VisitorMut)]
+#[visitor(Expr(enter, exit))]
+struct MatchNavigationRewriter<'a> {
+    name_resolution_ctx: &'a NameResolutionContext,
+    variables: &'a [String],
+    variable: &'a str,
+    depth: usize,
+    navigations: Vec<(String, Expr, i64)>,
+    error: Option<ErrorCode>,
+}
+
+impl<'a> MatchNavigationRewriter<'a> {
+    fn new(
+        name_resolution_ctx: &'a NameResolutionContext,
+        variables: &'a [String],
+        variable: &'a str,
+    ) -> Self {
+        Self {
+            name_resolution_ctx,
+            variables,
+            variable,
+            depth: 0,
+            navigations: vec![],
+            error: None,
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(name) = column_variable(expr, self.name_resolution_ctx, self.variables) {
+            if name == self.variable {
+                strip_column_variable(expr);
+            } else {
+                self.error = Some(
+                    ErrorCode::SemanticError(format!(
+                        "pattern variable {name} can't be referenced in the definition of {}",
+                        self.variable
+                    ))
+                    .set_span(expr.span()),
+                );
+            }
+            return;
+        }
+        if let Expr::FunctionCall { func, .. } = expr {
+            if is_navigation(func) {
+                self.depth += 1;
+            }
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr) {
+        if self.error.is_some() {
+            return;
+        }
+        let Expr::FunctionCall { span, func } = expr else {
+            return;
+        };
+        if !is_navigation(func) {
+            return;
+        }
+        self.depth -= 1;
+        if self.depth > 0 {
+            self.error = Some(
+                ErrorCode::SemanticError("PREV and NEXT can't be nested".to_string())
+                    .set_span(*span),
+            );
+            return;
+        }
+        let offset = match func.args.as_slice() {
+            [_] => 1,
+            [_, Expr::Literal {
+                value: Literal::UInt64(offset),
+                ..
+            }] => *offset as i64,
+            _ => {
+                self.error = Some(
+                    ErrorCode::SemanticError(format!(
+                        "{} expects an expression and an optional constant offset",
+                        func.name
+                    ))
+                    .set_span(*span),
+                );
+                return;
+            }
+        };
+        let offset = if func.name.name.eq_ignore_ascii_case("prev") {
+            -offset
+        } else {
+            offset
+        };
+        let placeholder = format!("_match_navigation_{}", self.navigations.len());
+        self.navigations
+            .push((placeholder.clone(), func.args[0].clone(), offset));
+        *expr = placeholder_column(*span, &placeholder);
+    }
+}
+
+struct MatchFunctionCall {
+    kind: MatchFunctionKind,
+    /// The function call with the qualifiers of pattern variables stripped.
+    expr: Expr,
+    variable: Option<usize>,
+    placeholder: String,
+}
+
+/// Replace the pattern functions in a measure with placeholder columns.
+#[derive(VisitorMut)]
+#[visitor(Expr(enter, exit))]
+struct MatchMeasureRewriter<'a> {
+    name_resolution_ctx: &'a NameResolutionContext,
+    variables: &'a [String],
+    depth: usize,
+    variable: Option<usize>,
+    calls: Vec<MatchFunctionCall>,
+    error: Option<ErrorCode>,
+}
+
+impl<'a> MatchMeasureRewriter<'a> {
+    fn new(name_resolution_ctx: &'a NameResolutionContext, variables: &'a [String]) -> Self {
+        Self {
+            name_resolution_ctx,
+            variables,
+            depth: 0,
+            variable: None,
+            calls: vec![],
+            error: None,
+        }
+    }
+
+    fn function_kind(expr: &Expr) -> Option<MatchFunctionKind> {
+        match expr {
+            Expr::CountAll { window: None, .. } => Some(MatchFunctionKind::Aggregate),
+            Expr::FunctionCall { func, .. } => match_function_kind(func),
+            _ => None,
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(name) = column_variable(expr, self.name_resolution_ctx, self.variables) {
+            if self.depth == 0 {
+                self.error = Some(
+                    ErrorCode::SemanticError(format!(
+                        "column qualified by pattern variable {name} must be used in FIRST, LAST or an aggregate function"
+                    ))
+                    .set_span(expr.span()),
+                );
+                return;
+            }
+            let variable = self.variables.iter().position(|v| v == &name);
+            if self.variable.is_some() && self.variable != variable {
+                self.error = Some(
+                    ErrorCode::SemanticError(
+                        "a pattern function can't reference more than one pattern variable"
+                            .to_string(),
+                    )
+                    .set_span(expr.span()),
+                );
+                return;
+            }
+            self.variable = variable;
+            strip_column_variable(expr);
+            return;
+        }
+        if Self::function_kind(expr).is_some() {
+            self.depth += 1;
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr) {
+        if self.error.is_some() {
+            return;
+        }
+        let Some(kind) = Self::function_kind(expr) else {
+            return;
+        };
+        self.depth -= 1;
+        if self.depth > 0 {
+            self.error = Some(
+                ErrorCode::SemanticError("pattern functions can't be nested".to_string())
+                    .set_span(expr.span()),
+            );
+            return;
+        }
+        let placeholder = format!("_match_measure_{}", self.calls.len());
+        let span = expr.span();
+        let call = std::mem::replace(expr, placeholder_column(span, &placeholder));
+        self.calls.push(MatchFunctionCall {
+            kind,
+            expr: call,
+            variable: self.variable.take(),
+            placeholder,
+        });
+    }
+}
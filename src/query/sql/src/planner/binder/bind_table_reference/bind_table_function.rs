@@ -26,6 +26,8 @@ use databend_common_ast::ast::TableAlias;
 use databend_common_ast::ast::TableReference;
 use databend_common_ast::Span;
 use databend_common_catalog::catalog_kind::CATALOG_DEFAULT;
+use databend_common_catalog::table::NavigationPoint;
+use databend_common_catalog::table::TimeNavigation;
 use databend_common_catalog::table_args::TableArgs;
 use databend_common_catalog::table_function::TableFunction;
 use databend_common_exception::ErrorCode;
@@ -34,6 +36,7 @@ use databend_common_expression::types::NumberScalar;
 use databend_common_expression::FunctionKind;
 use databend_common_expression::Scalar;
 use databend_common_functions::BUILTIN_FUNCTIONS;
+use databend_common_meta_app::principal::UDFDefinition;
 use databend_common_storages_result_cache::ResultCacheMetaManager;
 use databend_common_storages_result_cache::ResultScan;
 use databend_common_users::UserApiProvider;
@@ -66,11 +69,8 @@ impl Binder {
     ) -> Result<(SExpr, BindContext)> {
         let func_name = normalize_identifier(name, &self.name_resolution_ctx);
 
-        if BUILTIN_FUNCTIONS
-            .get_property(&func_name.name)
-            .map(|p| p.kind == FunctionKind::SRF)
-            .unwrap_or(false)
-        {
+        let udtf_columns = self.udtf_return_columns(&func_name)?;
+        if is_srf(&func_name) || udtf_columns.is_some() {
             // If it is a set-returning function, we bind it as a subquery.
             let args = parse_table_function_args(span, &func_name, params, named_params)?;
 
@@ -107,10 +107,11 @@ impl Binder {
             let (srf_expr, mut bind_context) =
                 self.bind_select(bind_context, &select_stmt, &[], None)?;
 
+            let fields = udtf_columns.or_else(|| srf_table_function_fields(&func_name));
             return self.extract_srf_table_function_columns(
                 &mut bind_context,
                 span,
-                &func_name,
+                fields,
                 srf_expr,
                 alias,
             );
@@ -131,6 +132,8 @@ impl Binder {
 
         if func_name.name.eq_ignore_ascii_case("result_scan") {
             self.bind_result_scan(bind_context, span, alias, &table_args)
+        } else if func_name.name.eq_ignore_ascii_case("delta_changes") {
+            self.bind_delta_changes(bind_context, span, alias, &table_args)
         } else {
             // Other table functions always reside is default catalog
             let table_meta: Arc<dyn TableFunction> = self
@@ -225,30 +228,107 @@ impl Binder {
         })
     }
 
-    /// Extract the srf inner tuple fields as columns.
-    fn extract_srf_table_function_columns(
+    /// Bind `delta_changes(database, table, start_version[, end_version])`, which reads
+    /// the change data feed of a delta table.
+    fn bind_delta_changes(
         &mut self,
         bind_context: &mut BindContext,
         span: &Span,
-        func_name: &Identifier,
-        srf_expr: SExpr,
         alias: &Option<TableAlias>,
+        table_args: &TableArgs,
     ) -> Result<(SExpr, BindContext)> {
-        let fields = if func_name.name.eq_ignore_ascii_case("flatten") {
-            Some(vec![
-                "seq".to_string(),
-                "key".to_string(),
-                "path".to_string(),
-                "index".to_string(),
-                "value".to_string(),
-                "this".to_string(),
-            ])
-        } else if func_name.name.eq_ignore_ascii_case("json_each") {
-            Some(vec!["key".to_string(), "value".to_string()])
+        let (database, table_name, start, end) = parse_delta_changes_args(table_args)?;
+        let catalog = self.ctx.get_current_catalog();
+        let table = self.resolve_data_source(
+            &catalog,
+            &database,
+            &table_name,
+            None,
+            None,
+            self.ctx.clone().get_abort_checker(),
+        )?;
+        if table.engine() != "DELTA" {
+            return Err(ErrorCode::TableEngineNotSupported(format!(
+                "`DELTA_CHANGES` only supports delta tables, but {database}.{table_name} is {}",
+                table.engine()
+            ))
+            .set_span(*span));
+        }
+
+        let navigation = TimeNavigation::Changes {
+            append_only: false,
+            desc: format!("delta_changes('{database}', '{table_name}', {start:?}, {end:?})"),
+            at: NavigationPoint::SnapshotID(start.to_string()),
+            end: end.map(|v| NavigationPoint::SnapshotID(v.to_string())),
+        };
+        let abort_checker = self.ctx.clone().get_abort_checker();
+        let table =
+            databend_common_base::runtime::block_on(table.navigate_to(&navigation, abort_checker))?;
+
+        let table_alias_name = if let Some(table_alias) = alias {
+            Some(normalize_identifier(&table_alias.name, &self.name_resolution_ctx).name)
         } else {
             None
         };
+        let table_index = self.metadata.write().add_table(
+            catalog,
+            database.clone(),
+            table,
+            table_alias_name,
+            false,
+            false,
+            false,
+            None,
+        );
+
+        let (s_expr, mut bind_context) =
+            self.bind_base_table(bind_context, &database, table_index, None, &None)?;
+        if let Some(alias) = alias {
+            bind_context.apply_table_alias(alias, &self.name_resolution_ctx)?;
+        }
+        Ok((s_expr, bind_context))
+    }
+
+    /// Returns the column names if `func_name` is a table-valued UDF.
+    /// Builtin table functions take precedence over UDFs with the same name.
+    fn udtf_return_columns(&self, func_name: &Identifier) -> Result<Option<Vec<String>>> {
+        if is_srf(func_name)
+            || func_name.name.eq_ignore_ascii_case("obfuscate")
+            || func_name.name.eq_ignore_ascii_case("result_scan")
+            || func_name.name.eq_ignore_ascii_case("delta_changes")
+            || self
+                .catalogs
+                .get_default_catalog(self.ctx.session_state())?
+                .exists_table_function(&func_name.name)
+        {
+            return Ok(None);
+        }
+
+        let udf = databend_common_base::runtime::block_on(
+            UserApiProvider::instance().get_udf(&self.ctx.get_tenant(), &func_name.name),
+        )?;
+        let return_fields = match udf.map(|udf| udf.definition) {
+            Some(UDFDefinition::UDTFServer(udtf)) => udtf.return_fields,
+            Some(UDFDefinition::UDTFScript(udtf)) => udtf.return_fields,
+            _ => return Ok(None),
+        };
+        Ok(Some(
+            return_fields
+                .iter()
+                .map(|field| field.name().clone())
+                .collect(),
+        ))
+    }
 
+    /// Extract the srf inner tuple fields as columns.
+    fn extract_srf_table_function_columns(
+        &mut self,
+        bind_context: &mut BindContext,
+        span: &Span,
+        fields: Option<Vec<String>>,
+        srf_expr: SExpr,
+        alias: &Option<TableAlias>,
+    ) -> Result<(SExpr, BindContext)> {
         if let Some(fields) = fields {
             if let RelOperator::EvalScalar(plan) = (*srf_expr.plan).clone() {
                 if plan.items.len() != 1 {
@@ -332,11 +412,8 @@ impl Binder {
                 let mut bind_context = BindContext::with_parent(parent_context.clone())?;
                 let func_name = normalize_identifier(name, &self.name_resolution_ctx);
 
-                if BUILTIN_FUNCTIONS
-                    .get_property(&func_name.name)
-                    .map(|p| p.kind == FunctionKind::SRF)
-                    .unwrap_or(false)
-                {
+                let udtf_columns = self.udtf_return_columns(&func_name)?;
+                if is_srf(&func_name) || udtf_columns.is_some() {
                     let args = parse_table_function_args(span, &func_name, params, named_params)?;
 
                     // convert lateral join table function to srf function
@@ -364,6 +441,8 @@ impl Binder {
                     let srf_expr = self.bind_project_set(&mut bind_context, child, false)?;
                     // clear Set-returning functions, avoid duplicate bind.
                     bind_context.srf_info = Default::default();
+                    // evaluate the table-valued udf below the set-returning function.
+                    let srf_expr = self.rewrite_udf(&mut bind_context, srf_expr)?;
 
                     if let Some(item) = select_list.items.pop() {
                         let srf_result = item.scalar;
@@ -399,11 +478,12 @@ impl Binder {
                         let flatten_expr =
                             SExpr::create_unary(Arc::new(eval_scalar.into()), Arc::new(srf_expr));
 
+                        let fields = udtf_columns.or_else(|| srf_table_function_fields(&func_name));
                         let (new_expr, mut bind_context) = self
                             .extract_srf_table_function_columns(
                                 &mut bind_context,
                                 span,
-                                &func_name,
+                                fields,
                                 flatten_expr,
                                 alias,
                             )?;
@@ -420,7 +500,7 @@ impl Binder {
                     }
                 } else {
                     Err(ErrorCode::InvalidArgument(format!(
-                        "The function '{}' is not supported for lateral joins. Lateral joins currently support only Set Returning Functions (SRFs).",
+                        "The function '{}' is not supported for lateral joins. Lateral joins currently support only Set Returning Functions (SRFs) and table-valued UDFs.",
                         func_name
                     ))
                     .set_span(*span))
@@ -431,6 +511,31 @@ impl Binder {
     }
 }
 
+fn is_srf(func_name: &Identifier) -> bool {
+    BUILTIN_FUNCTIONS
+        .get_property(&func_name.name)
+        .map(|p| p.kind == FunctionKind::SRF)
+        .unwrap_or(false)
+}
+
+/// Column names of the srf table functions returning multiple columns.
+fn srf_table_function_fields(func_name: &Identifier) -> Option<Vec<String>> {
+    if func_name.name.eq_ignore_ascii_case("flatten") {
+        Some(vec![
+            "seq".to_string(),
+            "key".to_string(),
+            "path".to_string(),
+            "index".to_string(),
+            "value".to_string(),
+            "this".to_string(),
+        ])
+    } else if func_name.name.eq_ignore_ascii_case("json_each") {
+        Some(vec!["key".to_string(), "value".to_string()])
+    } else {
+        None
+    }
+}
+
 // parse flatten named params to arguments
 fn parse_table_function_args(
     span: &Span,
@@ -506,3 +611,28 @@ pub fn parse_result_scan_args(table_args: &TableArgs) -> Result<String> {
     let args = table_args.expect_all_positioned("RESULT_SCAN", Some(1))?;
     string_value(&args[0])
 }
+
+fn parse_delta_changes_args(table_args: &TableArgs) -> Result<(String, String, u64, Option<u64>)> {
+    let args = table_args.expect_all_positioned("DELTA_CHANGES", None)?;
+    if args.len() != 3 && args.len() != 4 {
+        return Err(ErrorCode::NumberArgumentsNotMatch(format!(
+            "`DELTA_CHANGES` expects 3 or 4 arguments (database, table, start_version[, end_version]), but got {}",
+            args.len()
+        )));
+    }
+    let version = |arg: &Scalar| {
+        arg.get_i64()
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or_else(|| {
+                ErrorCode::BadArguments(format!(
+                    "`DELTA_CHANGES` expects a non-negative integer version, but got {arg}"
+                ))
+            })
+    };
+    Ok((
+        string_value(&args[0])?,
+        string_value(&args[1])?,
+        version(&args[2])?,
+        args.get(3).map(version).transpose()?,
+    ))
+}
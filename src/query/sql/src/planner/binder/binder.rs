@@ -42,9 +42,17 @@ use databend_common_meta_app::principal::FileFormatOptionsReader;
 use databend_common_meta_app::principal::FileFormatParams;
 use databend_common_meta_app::principal::StageFileFormatType;
 use databend_common_meta_app::principal::StageInfo;
+use databend_common_meta_app::schema::TableIdent;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_common_meta_app::schema::UpdateMultiTableMetaReq;
+use databend_common_meta_app::schema::UpdateTableMetaReq;
+use databend_common_meta_types::MatchSeq;
 use databend_common_metrics::storage::metrics_inc_copy_purge_files_cost_milliseconds;
 use databend_common_metrics::storage::metrics_inc_copy_purge_files_counter;
 use databend_common_storage::init_stage_operator;
+use databend_common_users::RoleCacheManager;
+use databend_common_users::UserApiProvider;
 use databend_storages_common_io::Files;
 use databend_storages_common_session::TxnManagerRef;
 use databend_storages_common_table_meta::table::is_stream_name;
@@ -676,19 +684,12 @@ impl<'a> Binder {
 
             // Dynamic Table
             Statement::CreateDynamicTable(stmt) => self.bind_create_dynamic_table(stmt).await?,
+            Statement::RefreshDynamicTable(stmt) => self.bind_refresh_dynamic_table(stmt).await?,
 
-            Statement::CreatePipe(_) => {
-                todo!()
-            }
-            Statement::DescribePipe(_) => {
-                todo!()
-            }
-            Statement::AlterPipe(_) => {
-                todo!()
-            }
-            Statement::DropPipe(_) => {
-                todo!()
-            }
+            Statement::CreatePipe(stmt) => self.bind_create_pipe(stmt).await?,
+            Statement::DescribePipe(stmt) => self.bind_desc_pipe(stmt).await?,
+            Statement::AlterPipe(stmt) => self.bind_alter_pipe(stmt).await?,
+            Statement::DropPipe(stmt) => self.bind_drop_pipe(stmt).await?,
             Statement::CreateNotification(stmt) => self.bind_create_notification(stmt).await?,
             Statement::DropNotification(stmt) => self.bind_drop_notification(stmt).await?,
             Statement::AlterNotification(stmt) => self.bind_alter_notification(stmt).await?,
@@ -698,7 +699,21 @@ impl<'a> Binder {
             Statement::Begin => Plan::Begin,
             Statement::Commit => Plan::Commit,
             Statement::Abort => Plan::Abort,
+            Statement::Savepoint(name) => Plan::Savepoint {
+                name: self.normalize_identifier(name).name,
+            },
+            Statement::RollbackToSavepoint(name) => Plan::RollbackToSavepoint {
+                name: self.normalize_identifier(name).name,
+            },
+            Statement::ReleaseSavepoint(name) => Plan::ReleaseSavepoint {
+                name: self.normalize_identifier(name).name,
+            },
             Statement::ExecuteImmediate(stmt) => self.bind_execute_immediate(stmt).await?,
+            Statement::Prepare(stmt) => self.bind_prepare(stmt).await?,
+            Statement::ExecutePrepared(stmt) => {
+                self.bind_execute_prepared(bind_context, stmt).await?
+            }
+            Statement::Deallocate(stmt) => self.bind_deallocate(stmt).await?,
             Statement::SetPriority {
                 priority,
                 object_id,
@@ -1034,6 +1049,18 @@ impl<'a> Binder {
                 }
                 f.scalars().is_empty()
             }
+            RelOperator::MatchRecognize(match_recognize) => {
+                f.reset_finder();
+                for scalar_item in &match_recognize.partition_by {
+                    f.visit(&scalar_item.scalar)?;
+                }
+                for variable in &match_recognize.variables {
+                    if let Some(condition) = &variable.condition {
+                        f.visit(condition)?;
+                    }
+                }
+                f.scalars().is_empty()
+            }
             RelOperator::Udf(_) => false,
             _ => true,
         };
@@ -1118,6 +1145,9 @@ impl<'a> Binder {
     }
 }
 
+/// How many times the changes of a transaction are rebased on concurrent transactions before giving up.
+const MAX_TXN_REBASE_ATTEMPTS: usize = 3;
+
 struct ClearTxnManagerGuard(TxnManagerRef);
 
 impl Drop for ClearTxnManagerGuard {
@@ -1150,39 +1180,71 @@ pub async fn execute_commit_statement(ctx: Arc<dyn TableContext>) -> Result<()>
             (table_descriptions, stream_descriptions)
         };
 
-        let mismatched_tids = {
-            ctx.txn_mgr().lock().set_auto_commit();
-            let ret = catalog.retryable_update_multi_table_meta(req).await;
-            if let Err(ref e) = ret {
-                // other errors may occur, especially the version mismatch of streams,
-                // let's log it here for the convenience of diagnostics
-                error!(
-                    "Non-recoverable fault occurred during updating tables. {}",
-                    e
-                );
+        ctx.txn_mgr().lock().set_auto_commit();
+        let mut req = req;
+        let mut rebased = HashMap::new();
+        let mut attempt = 0;
+        loop {
+            let mismatched_tids = {
+                let ret = catalog.retryable_update_multi_table_meta(req.clone()).await;
+                if let Err(ref e) = ret {
+                    // other errors may occur, especially the version mismatch of streams,
+                    // let's log it here for the convenience of diagnostics
+                    error!(
+                        "Non-recoverable fault occurred during updating tables. {}",
+                        e
+                    );
+                }
+                ret?
+            };
+
+            match mismatched_tids {
+                Ok(_) => {
+                    info!(
+                        "COMMIT: Commit explicit transaction success, targets updated {:?}",
+                        update_summary
+                    );
+                    break;
+                }
+                Err(e) if attempt < MAX_TXN_REBASE_ATTEMPTS => {
+                    info!(
+                        "COMMIT: Tables changed by concurrent transactions, rebasing on them: {:?}",
+                        e.iter().map(|(tid, seq, _)| (tid, seq)).collect::<Vec<_>>()
+                    );
+                    rebase_txn_mutations(ctx.clone(), &mut req, &mut rebased, e).await?;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    let err_msg = format!(
+                        "Due to concurrent transactions, explicit transaction commit failed. Conflicting table IDs: {:?}",
+                        e.iter().map(|(tid, _, _)| tid).collect::<Vec<_>>()
+                    );
+                    info!(
+                        "Due to concurrent transactions, explicit transaction commit failed. Conflicting table IDs: {:?}",
+                        e
+                    );
+                    return Err(ErrorCode::TableVersionMismatched(err_msg));
+                }
             }
-            ret?
-        };
+        }
 
-        match &mismatched_tids {
-            Ok(_) => {
-                info!(
-                    "COMMIT: Commit explicit transaction success, targets updated {:?}",
-                    update_summary
-                );
-            }
-            Err(e) => {
-                let err_msg = format!(
-                    "Due to concurrent transactions, explicit transaction commit failed. Conflicting table IDs: {:?}",
-                    e.iter().map(|(tid, _, _)| tid).collect::<Vec<_>>()
-                );
-                info!(
-                    "Due to concurrent transactions, explicit transaction commit failed. Conflicting table IDs: {:?}",
-                    e
+        // The tables dropped and created in the transaction are committed with the changes
+        // above, only the ownerships of the dropped tables are revoked afterwards.
+        let dropped_tables = ctx.txn_mgr().lock().dropped_tables();
+        for dropped in dropped_tables {
+            let tenant = dropped.req.tenant.clone();
+            if let Err(e) = UserApiProvider::instance()
+                .revoke_ownership(&tenant, &dropped.owner_object)
+                .await
+            {
+                warn!(
+                    "COMMIT: Failed to revoke the ownership of dropped table {}: {}",
+                    dropped.req.table_name, e
                 );
-                return Err(ErrorCode::TableVersionMismatched(err_msg));
             }
+            RoleCacheManager::instance().invalidate_cache(&tenant);
         }
+
         let need_purge_files = ctx.txn_mgr().lock().need_purge_files();
         for (stage_info, files) in need_purge_files {
             try_purge_files(ctx.clone(), &stage_info, &files).await;
@@ -1191,6 +1253,61 @@ pub async fn execute_commit_statement(ctx: Arc<dyn TableContext>) -> Result<()>
     Ok(())
 }
 
+/// Rebase the changes of the tables changed by concurrent transactions onto their latest versions.
+///
+/// The tables are read at the version they are first read in the transaction,
+/// which is the base of the changes made by the transaction, until they are rebased.
+#[async_backtrace::framed]
+async fn rebase_txn_mutations(
+    ctx: Arc<dyn TableContext>,
+    req: &mut UpdateMultiTableMetaReq,
+    rebased: &mut HashMap<u64, TableInfo>,
+    mismatched: Vec<(u64, u64, TableMeta)>,
+) -> Result<()> {
+    let catalog = ctx.get_default_catalog()?;
+    // The temporary tables are updated by the session, even if the other tables are not.
+    req.update_temp_tables.clear();
+    for (table_id, seq, meta) in mismatched {
+        let base = match rebased.get(&table_id) {
+            Some(base) => Some(base.clone()),
+            None => ctx.txn_mgr().lock().get_read_table(table_id),
+        };
+        let update = req
+            .update_table_metas
+            .iter_mut()
+            .find(|(update, _)| update.table_id == table_id);
+        let (Some(base), Some((update, txn_info))) = (base, update) else {
+            return Err(ErrorCode::TableVersionMismatched(format!(
+                "Due to concurrent transactions, explicit transaction commit failed. Conflicting table ID: {}",
+                table_id
+            )));
+        };
+
+        let latest = TableInfo {
+            ident: TableIdent::new(table_id, seq),
+            meta,
+            ..txn_info.clone()
+        };
+        let txn_table = catalog.get_table_by_info(&TableInfo {
+            meta: update.new_table_meta.clone(),
+            ..txn_info.clone()
+        })?;
+        let new_table_meta = txn_table
+            .rebase_txn_mutation(ctx.clone(), &base, &latest)
+            .await?;
+
+        // The latest version is the base of the rebased changes, if it has to be rebased again.
+        rebased.insert(table_id, latest.clone());
+        *txn_info = latest;
+        *update = UpdateTableMetaReq {
+            table_id,
+            seq: MatchSeq::Exact(seq),
+            new_table_meta,
+        };
+    }
+    Ok(())
+}
+
 #[async_backtrace::framed]
 async fn try_purge_files(ctx: Arc<dyn TableContext>, stage_info: &StageInfo, files: &[String]) {
     let start = Instant::now();
@@ -152,6 +152,13 @@ impl Binder {
             .ctx
             .get_table(&catalog_name, &database_name, &table_name)
             .await?;
+        if !stmt.options.force && !table.support_copied_files() {
+            return Err(ErrorCode::Unimplemented(format!(
+                "COPY INTO {} table {} requires FORCE = TRUE, the loaded files are not tracked",
+                table.engine(),
+                table_name
+            )));
+        }
         let dedup_full_path = table
             .get_table_info()
             .meta
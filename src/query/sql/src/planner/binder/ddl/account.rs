@@ -289,14 +289,20 @@ impl Binder {
             .cloned()
             .unwrap_or_default();
 
+        let mut auth_info = AuthInfo::create2(
+            &auth_option.auth_type.clone().map(Into::into),
+            &auth_option.password,
+            need_change,
+        )?;
+        // Store the SCRAM verifier for the clients of postgres protocol.
+        if let Some(password) = &auth_option.password {
+            auth_info = auth_info.with_scram_verifier(password);
+        }
+
         let plan = CreateUserPlan {
             create_option: create_option.clone().into(),
             user: user.clone().into(),
-            auth_info: AuthInfo::create2(
-                &auth_option.auth_type.clone().map(Into::into),
-                &auth_option.password,
-                need_change,
-            )?,
+            auth_info,
             user_option,
             password_update_on: Some(Utc::now()),
         };
@@ -356,8 +362,14 @@ impl Binder {
                     Some(&auth_info),
                 )
                 .await?;
-            if user_info.auth_info == auth_info {
+            // The SCRAM verifier is salted randomly, compare the password without it,
+            // and add the verifier if the stored password does not have one yet.
+            if user_info.auth_info.get_scram_verifier().is_some()
+                && user_info.auth_info.without_scram_verifier() == auth_info
+            {
                 None
+            } else if let Some(password) = &auth_option.password {
+                Some(auth_info.with_scram_verifier(password))
             } else {
                 Some(auth_info)
             }
@@ -0,0 +1,325 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use databend_common_ast::ast::ConstraintDefinition;
+use databend_common_ast::ast::Identifier;
+use databend_common_ast::ast::TableConstraint as AstTableConstraint;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableSchema;
+use databend_common_expression::TableSchemaRef;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraint;
+use databend_storages_common_table_meta::table::TableConstraintKind;
+
+use crate::parse_check_expr_to_string;
+use crate::plans::AddTableConstraintPlan;
+use crate::plans::Plan;
+use crate::Binder;
+
+impl Binder {
+    #[async_backtrace::framed]
+    pub(in crate::planner::binder) async fn bind_add_table_constraint(
+        &mut self,
+        catalog: String,
+        database: String,
+        table: String,
+        constraint_def: &ConstraintDefinition,
+    ) -> Result<Plan> {
+        let table_info = self.ctx.get_table(&catalog, &database, &table).await?;
+        if table_info.engine() != "FUSE" {
+            return Err(ErrorCode::TableEngineNotSupported(format!(
+                "Table engine {} does not support constraints",
+                table_info.engine()
+            )));
+        }
+        let schema = table_info.schema();
+        let existing = get_table_constraints(table_info.options())?;
+        let (_, mut constraints) = self
+            .analyze_table_constraints(
+                &catalog,
+                &database,
+                &table,
+                schema.clone(),
+                &existing,
+                std::slice::from_ref(constraint_def),
+            )
+            .await?;
+        let constraint = constraints.remove(0);
+
+        // the schema is not changed by ALTER TABLE, the columns of the primary key must be NOT NULL
+        if constraint.is_primary_key() {
+            for column in constraint.key_columns().unwrap_or_default() {
+                if schema.field_with_name(column)?.is_nullable() {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "Column '{column}' of the primary key must be NOT NULL"
+                    )));
+                }
+            }
+        }
+
+        Ok(Plan::AddTableConstraint(Box::new(AddTableConstraintPlan {
+            catalog,
+            database,
+            table,
+            constraint,
+        })))
+    }
+
+    /// Analyze the constraint definitions of a table, which are added to the `existing`
+    /// constraints.
+    ///
+    /// Returns the table schema, in which the columns of the primary key are NOT NULL, and the
+    /// constraints being added.
+    #[async_backtrace::framed]
+    pub(in crate::planner::binder) async fn analyze_table_constraints(
+        &self,
+        catalog: &str,
+        database: &str,
+        table: &str,
+        schema: TableSchemaRef,
+        existing: &[TableConstraint],
+        constraint_defs: &[ConstraintDefinition],
+    ) -> Result<(TableSchemaRef, Vec<TableConstraint>)> {
+        let mut names = existing
+            .iter()
+            .map(|c| c.name.clone())
+            .collect::<HashSet<_>>();
+        let mut has_primary_key = existing.iter().any(|c| c.is_primary_key());
+        let mut new_schema = TableSchema::clone(&schema);
+        let mut constraints: Vec<TableConstraint> = Vec::with_capacity(constraint_defs.len());
+        // the foreign keys are analyzed at last, they may reference the keys of the table itself
+        let (foreign_keys, others): (Vec<_>, Vec<_>) = constraint_defs
+            .iter()
+            .partition(|def| matches!(def.constraint, AstTableConstraint::ForeignKey { .. }));
+        for constraint_def in others.into_iter().chain(foreign_keys) {
+            let constraint = match &constraint_def.constraint {
+                AstTableConstraint::PrimaryKey { columns } => {
+                    if has_primary_key {
+                        return Err(ErrorCode::ConstraintAlreadyExists(format!(
+                            "Multiple primary keys for table '{table}' are not allowed"
+                        )));
+                    }
+                    has_primary_key = true;
+                    let columns = self.analyze_key_columns(&schema, columns)?;
+                    for column in &columns {
+                        let index = new_schema.index_of(column)?;
+                        let field = &mut new_schema.fields[index];
+                        field.data_type = field.data_type.remove_nullable();
+                    }
+                    let name = match &constraint_def.name {
+                        Some(name) => self.normalize_object_identifier(name),
+                        None => format!("{table}_pkey"),
+                    };
+                    TableConstraint {
+                        name,
+                        kind: TableConstraintKind::PrimaryKey { columns },
+                    }
+                }
+                AstTableConstraint::Unique { columns } => {
+                    let columns = self.analyze_key_columns(&schema, columns)?;
+                    let name = match &constraint_def.name {
+                        Some(name) => self.normalize_object_identifier(name),
+                        None => format!("{table}_{}_key", columns.join("_")),
+                    };
+                    TableConstraint {
+                        name,
+                        kind: TableConstraintKind::Unique { columns },
+                    }
+                }
+                AstTableConstraint::Check { expr } => {
+                    // the check is evaluated on the stored columns
+                    let (expr, columns) = parse_check_expr_to_string(
+                        self.ctx.clone(),
+                        Arc::new(schema.remove_virtual_computed_fields()),
+                        expr,
+                    )?;
+                    let name = match &constraint_def.name {
+                        Some(name) => self.normalize_object_identifier(name),
+                        None => {
+                            let mut name = format!("{table}_check");
+                            let mut n = 0;
+                            while names.contains(&name) {
+                                n += 1;
+                                name = format!("{table}_check{n}");
+                            }
+                            name
+                        }
+                    };
+                    TableConstraint {
+                        name,
+                        kind: TableConstraintKind::Check { expr, columns },
+                    }
+                }
+                AstTableConstraint::ForeignKey {
+                    columns,
+                    ref_database,
+                    ref_table,
+                    ref_columns,
+                } => {
+                    let columns = self.analyze_key_columns(&schema, columns)?;
+                    let ref_database = match ref_database {
+                        Some(ref_database) => self.normalize_object_identifier(ref_database),
+                        None => database.to_string(),
+                    };
+                    let ref_table = self.normalize_object_identifier(ref_table);
+                    let ref_columns = ref_columns
+                        .iter()
+                        .map(|column| self.normalize_object_identifier(column))
+                        .collect::<Vec<_>>();
+                    let ref_columns = if ref_database == database && ref_table == table {
+                        Self::analyze_referenced_key(
+                            &columns,
+                            &schema,
+                            &ref_table,
+                            ref_columns,
+                            &new_schema,
+                            existing.iter().chain(constraints.iter()),
+                        )?
+                    } else {
+                        let ref_table_info = self
+                            .ctx
+                            .get_table(catalog, &ref_database, &ref_table)
+                            .await?;
+                        let ref_constraints = get_table_constraints(ref_table_info.options())?;
+                        Self::analyze_referenced_key(
+                            &columns,
+                            &schema,
+                            &ref_table,
+                            ref_columns,
+                            &ref_table_info.schema(),
+                            ref_constraints.iter(),
+                        )?
+                    };
+                    let name = match &constraint_def.name {
+                        Some(name) => self.normalize_object_identifier(name),
+                        None => format!("{table}_{}_fkey", columns.join("_")),
+                    };
+                    TableConstraint {
+                        name,
+                        kind: TableConstraintKind::ForeignKey {
+                            columns,
+                            ref_database,
+                            ref_table,
+                            ref_columns,
+                        },
+                    }
+                }
+            };
+            if !names.insert(constraint.name.clone()) {
+                return Err(ErrorCode::ConstraintAlreadyExists(format!(
+                    "Constraint '{}' of table '{table}' already exists",
+                    constraint.name
+                )));
+            }
+            constraints.push(constraint);
+        }
+        Ok((Arc::new(new_schema), constraints))
+    }
+
+    /// Check the columns referenced by a foreign key are a key of the referenced table, the
+    /// primary key is referenced if no column is specified.
+    fn analyze_referenced_key<'a>(
+        columns: &[String],
+        schema: &TableSchema,
+        ref_table: &str,
+        ref_columns: Vec<String>,
+        ref_schema: &TableSchema,
+        mut ref_keys: impl Iterator<Item = &'a TableConstraint>,
+    ) -> Result<Vec<String>> {
+        let ref_columns = if ref_columns.is_empty() {
+            match ref_keys.find(|c| c.is_primary_key()) {
+                Some(primary_key) => primary_key.columns().to_vec(),
+                None => {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "Table '{ref_table}' referenced by the foreign key has no primary key"
+                    )));
+                }
+            }
+        } else {
+            let ref_column_set = ref_columns.iter().collect::<HashSet<_>>();
+            if !ref_keys.any(|c| {
+                c.key_columns()
+                    .is_some_and(|key| key.iter().collect::<HashSet<_>>() == ref_column_set)
+            }) {
+                return Err(ErrorCode::SemanticError(format!(
+                    "There is no PRIMARY KEY or UNIQUE constraint on columns ({}) of table '{ref_table}'",
+                    ref_columns.join(", ")
+                )));
+            }
+            ref_columns
+        };
+
+        if columns.len() != ref_columns.len() {
+            return Err(ErrorCode::SemanticError(format!(
+                "The foreign key has {} columns, but {} columns of table '{ref_table}' are referenced",
+                columns.len(),
+                ref_columns.len()
+            )));
+        }
+        for (column, ref_column) in columns.iter().zip(ref_columns.iter()) {
+            let data_type = DataType::from(schema.field_with_name(column)?.data_type());
+            let ref_data_type = DataType::from(ref_schema.field_with_name(ref_column)?.data_type());
+            if data_type.remove_nullable() != ref_data_type.remove_nullable() {
+                return Err(ErrorCode::SemanticError(format!(
+                    "Column '{column}' of type {data_type} can't reference column '{ref_column}' of type {ref_data_type}"
+                )));
+            }
+        }
+        Ok(ref_columns)
+    }
+
+    fn analyze_key_columns(
+        &self,
+        schema: &TableSchema,
+        columns: &[Identifier],
+    ) -> Result<Vec<String>> {
+        let mut key_columns = Vec::with_capacity(columns.len());
+        for column in columns {
+            let name = self.normalize_object_identifier(column);
+            let field = schema.field_with_name(&name)?;
+            if field.computed_expr().is_some() {
+                return Err(ErrorCode::SemanticError(format!(
+                    "Computed column '{name}' can't be a key column"
+                )));
+            }
+            match field.data_type().remove_nullable() {
+                TableDataType::Boolean
+                | TableDataType::Binary
+                | TableDataType::String
+                | TableDataType::Number(_)
+                | TableDataType::Decimal(_)
+                | TableDataType::Timestamp
+                | TableDataType::Date => {}
+                data_type => {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "Column '{name}' of type {data_type} can't be a key column"
+                    )));
+                }
+            }
+            if key_columns.contains(&name) {
+                return Err(ErrorCode::SemanticError(format!(
+                    "Column '{name}' appears twice in the key"
+                )));
+            }
+            key_columns.push(name);
+        }
+        Ok(key_columns)
+    }
+}
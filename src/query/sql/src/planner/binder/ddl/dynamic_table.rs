@@ -16,6 +16,7 @@ use std::collections::BTreeMap;
 
 use databend_common_ast::ast::CreateDynamicTableStmt;
 use databend_common_ast::ast::CreateTableSource;
+use databend_common_ast::ast::RefreshDynamicTableStmt;
 use databend_common_ast::ast::TypeName;
 use databend_common_config::GlobalConfig;
 use databend_common_exception::ErrorCode;
@@ -27,14 +28,20 @@ use databend_common_meta_app::storage::StorageParams;
 use databend_storages_common_table_meta::table::OPT_KEY_AS_QUERY;
 use databend_storages_common_table_meta::table::OPT_KEY_CLUSTER_TYPE;
 use databend_storages_common_table_meta::table::OPT_KEY_DATABASE_ID;
+use databend_storages_common_table_meta::table::OPT_KEY_INITIALIZE;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_MODE;
 use databend_storages_common_table_meta::table::OPT_KEY_STORAGE_FORMAT;
 use databend_storages_common_table_meta::table::OPT_KEY_TABLE_COMPRESSION;
 use databend_storages_common_table_meta::table::OPT_KEY_TARGET_LAG;
+use databend_storages_common_table_meta::table::OPT_KEY_WAREHOUSE;
+use derive_visitor::DriveMut;
 
 use crate::plans::CreateDynamicTablePlan;
 use crate::plans::Plan;
+use crate::plans::RefreshDynamicTablePlan;
 use crate::BindContext;
 use crate::Binder;
+use crate::ViewRewriter;
 
 impl Binder {
     pub(in crate::planner::binder) async fn bind_create_dynamic_table(
@@ -60,6 +67,14 @@ impl Binder {
         let (catalog_name, database, table) =
             self.normalize_object_identifier_triple(catalog, database, table);
 
+        // The query is refreshed in other sessions, so the tables in it are qualified
+        // with the current database, like the query of a view.
+        let mut as_query = *as_query.clone();
+        let mut visitor = ViewRewriter {
+            current_database: self.ctx.get_current_database(),
+        };
+        as_query.drive_mut(&mut visitor);
+
         let mut options: BTreeMap<String, String> = BTreeMap::new();
         {
             // If table is TRANSIENT, set a flag in table option
@@ -69,6 +84,11 @@ impl Binder {
 
             options.insert(OPT_KEY_AS_QUERY.to_owned(), format!("{as_query}"));
             options.insert(OPT_KEY_TARGET_LAG.to_owned(), format!("{target_lag}"));
+            options.insert(OPT_KEY_REFRESH_MODE.to_owned(), format!("{refresh_mode}"));
+            options.insert(OPT_KEY_INITIALIZE.to_owned(), format!("{initialize}"));
+            if let Some(warehouse) = &warehouse_opts.warehouse {
+                options.insert(OPT_KEY_WAREHOUSE.to_owned(), warehouse.clone());
+            }
 
             let catalog = self.ctx.get_catalog(&catalog_name).await?;
             let db = catalog
@@ -123,7 +143,7 @@ impl Binder {
         }
 
         // todo(geometry): remove this when geometry stable.
-        if let Some(CreateTableSource::Columns(cols, indexes)) = &source {
+        if let Some(CreateTableSource::Columns(cols, indexes, constraints)) = &source {
             if cols
                 .iter()
                 .any(|col| matches!(col.data_type, TypeName::Geometry))
@@ -140,10 +160,15 @@ impl Binder {
                     "dynamic table don't support inverted indexes".to_string(),
                 ));
             }
+            if constraints.is_some() {
+                return Err(ErrorCode::SemanticError(
+                    "dynamic table don't support constraints".to_string(),
+                ));
+            }
         }
 
         let mut init_bind_context = BindContext::new();
-        let (_, bind_context) = self.bind_query(&mut init_bind_context, as_query)?;
+        let (_, bind_context) = self.bind_query(&mut init_bind_context, &as_query)?;
         let query_fields = bind_context
             .columns
             .iter()
@@ -204,4 +229,34 @@ impl Binder {
         };
         Ok(Plan::CreateDynamicTable(Box::new(plan)))
     }
+
+    pub(in crate::planner::binder) async fn bind_refresh_dynamic_table(
+        &mut self,
+        stmt: &RefreshDynamicTableStmt,
+    ) -> Result<Plan> {
+        let RefreshDynamicTableStmt {
+            catalog,
+            database,
+            table,
+        } = stmt;
+
+        let (catalog, database, table) =
+            self.normalize_object_identifier_triple(catalog, database, table);
+
+        let table_info = self.ctx.get_table(&catalog, &database, &table).await?;
+        if !table_info.options().contains_key(OPT_KEY_AS_QUERY) {
+            return Err(ErrorCode::IllegalDynamicTable(format!(
+                "Table '{}'.'{}' is not a dynamic table",
+                database, table
+            )));
+        }
+
+        let plan = RefreshDynamicTablePlan {
+            tenant: self.ctx.get_tenant(),
+            catalog,
+            database,
+            table,
+        };
+        Ok(Plan::RefreshDynamicTable(Box::new(plan)))
+    }
 }
@@ -16,6 +16,7 @@ mod account;
 mod catalog;
 mod column;
 mod connection;
+mod constraint;
 mod data_mask;
 mod database;
 mod dictionary;
@@ -24,6 +25,7 @@ mod index;
 mod network_policy;
 mod notification;
 mod password_policy;
+mod pipe;
 mod procedure;
 mod role;
 mod sequence;
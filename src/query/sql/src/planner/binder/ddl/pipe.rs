@@ -0,0 +1,162 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_ast::ast::AlterPipeStmt;
+use databend_common_ast::ast::CopyIntoTableSource;
+use databend_common_ast::ast::CreatePipeStmt;
+use databend_common_ast::ast::DescribePipeStmt;
+use databend_common_ast::ast::DropPipeStmt;
+use databend_common_ast::ast::FileLocation;
+use databend_common_ast::ast::Identifier;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::schema::CreateOption;
+
+use crate::binder::resolve_stage_location;
+use crate::binder::Binder;
+use crate::plans::AlterPipePlan;
+use crate::plans::CreatePipePlan;
+use crate::plans::DescPipePlan;
+use crate::plans::DropPipePlan;
+use crate::plans::Plan;
+
+impl Binder {
+    #[async_backtrace::framed]
+    pub(in crate::planner::binder) async fn bind_create_pipe(
+        &mut self,
+        stmt: &CreatePipeStmt,
+    ) -> Result<Plan> {
+        let CreatePipeStmt {
+            if_not_exists,
+            name,
+            auto_ingest,
+            comments,
+            copy_stmt,
+        } = stmt;
+
+        // A pipe only ever loads the files it has not loaded before,
+        // which relies on the copied files info kept by COPY.
+        if copy_stmt.options.force {
+            return Err(ErrorCode::IllegalPipe(format!(
+                "pipe '{}' cannot be created with FORCE = TRUE, which loads the same files repeatedly",
+                name
+            )));
+        }
+
+        if let CopyIntoTableSource::Location(location) = &copy_stmt.src {
+            match location {
+                FileLocation::Stage(location) if location.starts_with('~') => {
+                    return Err(ErrorCode::IllegalPipe(format!(
+                        "pipe '{}' cannot load from the user stage",
+                        name
+                    )));
+                }
+                FileLocation::Stage(location) => {
+                    resolve_stage_location(self.ctx.as_ref(), location).await?;
+                }
+                FileLocation::Uri(_) => {
+                    return Err(ErrorCode::IllegalPipe(format!(
+                        "pipe '{}' must load from a stage, create a stage for the uri location first",
+                        name
+                    )));
+                }
+            }
+        }
+
+        // The pipe is executed in a background session without current database,
+        // so the target table is qualified with the database it was created in.
+        let (catalog_name, database_name, table_name) = self.normalize_object_identifier_triple(
+            &copy_stmt.dst.catalog,
+            &copy_stmt.dst.database,
+            &copy_stmt.dst.table,
+        );
+        self.ctx
+            .get_table(&catalog_name, &database_name, &table_name)
+            .await?;
+
+        let mut copy_stmt = copy_stmt.clone();
+        if copy_stmt.dst.database.is_none() {
+            copy_stmt.dst.database = Some(Identifier::from_name_with_quoted(
+                None,
+                database_name,
+                Some('`'),
+            ));
+        }
+
+        let create_option = if *if_not_exists {
+            CreateOption::CreateIfNotExists
+        } else {
+            CreateOption::Create
+        };
+
+        let plan = CreatePipePlan {
+            create_option,
+            tenant: self.ctx.get_tenant(),
+            name: name.to_string(),
+            auto_ingest: *auto_ingest,
+            comment: comments.to_string(),
+            copy_stmt: copy_stmt.to_string(),
+        };
+        Ok(Plan::CreatePipe(Box::new(plan)))
+    }
+
+    #[async_backtrace::framed]
+    pub(in crate::planner::binder) async fn bind_alter_pipe(
+        &mut self,
+        stmt: &AlterPipeStmt,
+    ) -> Result<Plan> {
+        let AlterPipeStmt {
+            if_exists,
+            name,
+            options,
+        } = stmt;
+
+        let plan = AlterPipePlan {
+            if_exists: *if_exists,
+            tenant: self.ctx.get_tenant(),
+            name: name.to_string(),
+            options: options.clone(),
+        };
+        Ok(Plan::AlterPipe(Box::new(plan)))
+    }
+
+    #[async_backtrace::framed]
+    pub(in crate::planner::binder) async fn bind_drop_pipe(
+        &mut self,
+        stmt: &DropPipeStmt,
+    ) -> Result<Plan> {
+        let DropPipeStmt { if_exists, name } = stmt;
+
+        let plan = DropPipePlan {
+            if_exists: *if_exists,
+            tenant: self.ctx.get_tenant(),
+            name: name.to_string(),
+        };
+        Ok(Plan::DropPipe(Box::new(plan)))
+    }
+
+    #[async_backtrace::framed]
+    pub(in crate::planner::binder) async fn bind_desc_pipe(
+        &mut self,
+        stmt: &DescribePipeStmt,
+    ) -> Result<Plan> {
+        let DescribePipeStmt { name } = stmt;
+
+        let plan = DescPipePlan {
+            tenant: self.ctx.get_tenant(),
+            name: name.to_string(),
+        };
+        Ok(Plan::DescPipe(Box::new(plan)))
+    }
+}
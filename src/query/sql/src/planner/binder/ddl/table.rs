@@ -78,6 +78,7 @@ use databend_common_expression::TableSchemaRefExt;
 use databend_common_functions::BUILTIN_FUNCTIONS;
 use databend_common_license::license::Feature;
 use databend_common_license::license_manager::LicenseManagerSwitch;
+use databend_common_meta_app::schema::CatalogType;
 use databend_common_meta_app::schema::CreateOption;
 use databend_common_meta_app::schema::TableIndex;
 use databend_common_meta_app::storage::StorageParams;
@@ -86,6 +87,7 @@ use databend_common_storage::init_operator;
 use databend_common_storages_view::view_table::QUERY;
 use databend_common_storages_view::view_table::VIEW_ENGINE;
 use databend_storages_common_table_meta::table::is_reserved_opt_key;
+use databend_storages_common_table_meta::table::set_table_constraints;
 use databend_storages_common_table_meta::table::OPT_KEY_CLUSTER_TYPE;
 use databend_storages_common_table_meta::table::OPT_KEY_DATABASE_ID;
 use databend_storages_common_table_meta::table::OPT_KEY_ENGINE_META;
@@ -118,6 +120,7 @@ use crate::plans::CreateTablePlan;
 use crate::plans::DescribeTablePlan;
 use crate::plans::DropTableClusterKeyPlan;
 use crate::plans::DropTableColumnPlan;
+use crate::plans::DropTableConstraintPlan;
 use crate::plans::DropTablePlan;
 use crate::plans::ExistsTablePlan;
 use crate::plans::ModifyColumnAction as ModifyColumnActionInPlan;
@@ -449,8 +452,18 @@ impl Binder {
         let (catalog, database, table) =
             self.normalize_object_identifier_triple(catalog, database, table);
 
-        // Take FUSE engine AS default engine
-        let engine = engine.unwrap_or(Engine::Fuse);
+        // Take FUSE engine AS default engine, except that tables in an iceberg catalog are always iceberg tables.
+        let engine = match engine {
+            Some(engine) => *engine,
+            None => {
+                let catalog_type = self.ctx.get_catalog(&catalog).await?.info().catalog_type();
+                if catalog_type == CatalogType::Iceberg {
+                    Engine::Iceberg
+                } else {
+                    Engine::Fuse
+                }
+            }
+        };
         let mut options: BTreeMap<String, String> = BTreeMap::new();
         let mut engine_options: BTreeMap<String, String> = BTreeMap::new();
         for table_option in table_options.iter() {
@@ -512,7 +525,7 @@ impl Binder {
         };
 
         // todo(geometry): remove this when geometry stable.
-        if let Some(CreateTableSource::Columns(cols, _)) = &source {
+        if let Some(CreateTableSource::Columns(cols, _, _)) = &source {
             if cols
                 .iter()
                 .any(|col| matches!(col.data_type, TypeName::Geometry | TypeName::Geography))
@@ -594,7 +607,7 @@ impl Binder {
                         let sp =
                             get_storage_params_from_options(self.ctx.as_ref(), &options).await?;
                         let (table_schema, _) =
-                            self.ctx.load_datalake_schema("iceberg", &sp).await?;
+                            self.ctx.load_datalake_schema("iceberg", &sp, &options).await?;
                         // the first version of current iceberg table do not need to persist the storage_params,
                         // since we get it from table options location and connection when load table each time.
                         // we do this in case we change this idea.
@@ -605,7 +618,7 @@ impl Binder {
                         let sp =
                             get_storage_params_from_options(self.ctx.as_ref(), &options).await?;
                         let (table_schema, meta) =
-                            self.ctx.load_datalake_schema("delta", &sp).await?;
+                            self.ctx.load_datalake_schema("delta", &sp, &options).await?;
                         // the first version of current iceberg table do not need to persist the storage_params,
                         // since we get it from table options location and connection when load table each time.
                         // we do this in case we change this idea.
@@ -692,6 +705,30 @@ impl Binder {
             )));
         }
 
+        let schema = match &source {
+            Some(CreateTableSource::Columns(_, _, Some(constraint_defs))) => {
+                if engine != Engine::Fuse {
+                    return Err(ErrorCode::TableEngineNotSupported(format!(
+                        "Table engine {} does not support constraints",
+                        engine
+                    )));
+                }
+                let (schema, constraints) = self
+                    .analyze_table_constraints(
+                        &catalog,
+                        &database,
+                        &table,
+                        schema,
+                        &[],
+                        constraint_defs,
+                    )
+                    .await?;
+                set_table_constraints(&mut options, &constraints)?;
+                schema
+            }
+            _ => schema,
+        };
+
         let mut cluster_key = None;
         if let Some(cluster_opt) = cluster_by {
             let keys = self
@@ -1025,6 +1062,18 @@ impl Binder {
                     table,
                 },
             ))),
+            AlterTableAction::AddConstraint { constraint } => {
+                self.bind_add_table_constraint(catalog, database, table, constraint)
+                    .await
+            }
+            AlterTableAction::DropConstraint { name } => Ok(Plan::DropTableConstraint(Box::new(
+                DropTableConstraintPlan {
+                    catalog,
+                    database,
+                    table,
+                    name: self.normalize_object_identifier(name),
+                },
+            ))),
             AlterTableAction::ReclusterTable {
                 is_final,
                 selection,
@@ -1524,7 +1573,7 @@ impl Binder {
         Option<BTreeMap<String, TableIndex>>,
     )> {
         match source {
-            CreateTableSource::Columns(columns, inverted_index_defs) => {
+            CreateTableSource::Columns(columns, inverted_index_defs, _) => {
                 let (schema, comments) =
                     self.analyze_create_table_schema_by_columns(columns).await?;
                 let inverted_indexes = if let Some(inverted_index_defs) = inverted_index_defs {
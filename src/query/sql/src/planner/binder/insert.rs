@@ -12,16 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use databend_common_ast::ast::Identifier;
+use databend_common_ast::ast::InsertOnConflict;
+use databend_common_ast::ast::InsertPartitionValue;
 use databend_common_ast::ast::InsertSource;
 use databend_common_ast::ast::InsertStmt;
+use databend_common_ast::ast::OnConflictAction;
+use databend_common_ast::ast::ReplaceStmt;
 use databend_common_ast::ast::Statement;
+use databend_common_catalog::table::Table;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::TableSchema;
 use databend_common_expression::TableSchemaRefExt;
+use databend_common_meta_app::schema::TableInfo;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraint;
 
 use super::util::TableIdentifier;
 use crate::binder::Binder;
@@ -67,6 +77,114 @@ impl Binder {
         Ok(TableSchemaRefExt::create(fields))
     }
 
+    /// Bind the `PARTITION` clause of a hive style insert, returns the columns provided by
+    /// the insert source and the table info to insert into.
+    ///
+    /// The values of the static partitions are filled as the default values of the partition
+    /// columns, so they are excluded from the columns provided by the insert source.
+    fn bind_insert_partition(
+        &self,
+        table: &dyn Table,
+        partition: &[InsertPartitionValue],
+        columns: &[Identifier],
+    ) -> Result<(Arc<TableSchema>, TableInfo)> {
+        let partition_columns = table.partition_columns();
+        if partition_columns.is_empty() {
+            return Err(ErrorCode::SemanticError(format!(
+                "PARTITION clause is not allowed, table '{}' is not partitioned",
+                table.name()
+            )));
+        }
+
+        let mut static_values = BTreeMap::new();
+        for value in partition {
+            let name = normalize_identifier(&value.column, &self.name_resolution_ctx).name;
+            if !partition_columns.contains(&name) {
+                return Err(ErrorCode::SemanticError(format!(
+                    "'{name}' is not a partition column of table '{}'",
+                    table.name()
+                )));
+            }
+            if let Some(expr) = &value.value {
+                if static_values
+                    .insert(name.clone(), expr.to_string())
+                    .is_some()
+                {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "Duplicate partition column '{name}'"
+                    )));
+                }
+            }
+        }
+
+        let mut table_info = table.get_table_info().clone();
+        let mut schema = table_info.meta.schema.as_ref().clone();
+        for field in schema.fields.iter_mut() {
+            if let Some(value) = static_values.get(&field.name) {
+                field.default_expr = Some(value.clone());
+            }
+        }
+        let schema = Arc::new(schema);
+        table_info.meta.schema = schema.clone();
+
+        let projected = self.schema_project(&schema, columns)?;
+        let mut fields = Vec::with_capacity(projected.num_fields());
+        for field in projected.fields() {
+            if !static_values.contains_key(&field.name) {
+                fields.push(field.clone());
+            } else if !columns.is_empty() {
+                return Err(ErrorCode::SemanticError(format!(
+                    "Static partition column '{}' can not be specified in the column list",
+                    field.name
+                )));
+            }
+        }
+        Ok((TableSchemaRefExt::create(fields), table_info))
+    }
+
+    /// Bind the `ON CONFLICT` clause, returns the PRIMARY KEY and UNIQUE constraints the
+    /// clause applies to, which are all the keys of the table if the columns are not specified.
+    fn bind_insert_on_conflict(
+        &self,
+        table: &dyn Table,
+        on_conflict: &InsertOnConflict,
+    ) -> Result<Vec<TableConstraint>> {
+        let keys = get_table_constraints(table.options())?
+            .into_iter()
+            .filter(|c| c.key_columns().is_some())
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return Err(ErrorCode::SemanticError(format!(
+                "ON CONFLICT is not allowed, table '{}' has no PRIMARY KEY or UNIQUE constraint",
+                table.name()
+            )));
+        }
+        if on_conflict.columns.is_empty() {
+            return Ok(keys);
+        }
+
+        let columns = on_conflict
+            .columns
+            .iter()
+            .map(|ident| normalize_identifier(ident, &self.name_resolution_ctx).name)
+            .collect::<HashSet<_>>();
+        let targets = keys
+            .into_iter()
+            .filter(|c| {
+                let key_columns = c.key_columns().unwrap_or_default();
+                key_columns.len() == columns.len()
+                    && key_columns.iter().all(|column| columns.contains(column))
+            })
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            return Err(ErrorCode::SemanticError(format!(
+                "There is no PRIMARY KEY or UNIQUE constraint of table '{}' matching the ON CONFLICT columns",
+                table.name()
+            )));
+        }
+        Ok(targets)
+    }
+
     #[async_backtrace::framed]
     pub(in crate::planner::binder) async fn bind_insert(
         &mut self,
@@ -78,9 +196,11 @@ impl Binder {
             catalog,
             database,
             table,
+            partition,
             columns,
             source,
             overwrite,
+            on_conflict,
             ..
         } = stmt;
 
@@ -99,7 +219,72 @@ impl Binder {
             .await
             .map_err(|err| table_identifier.not_found_suggest_error(err))?;
 
-        let schema = self.schema_project(&table.schema(), columns)?;
+        let mut ignore_conflicts = vec![];
+        if let Some(on_conflict) = on_conflict {
+            let targets = self.bind_insert_on_conflict(table.as_ref(), on_conflict)?;
+            match on_conflict.action {
+                OnConflictAction::DoNothing => {
+                    ignore_conflicts = targets.into_iter().map(|c| c.name).collect();
+                }
+                OnConflictAction::DoUpdate => {
+                    if *overwrite || !partition.is_empty() {
+                        return Err(ErrorCode::SemanticError(
+                            "ON CONFLICT DO UPDATE is not allowed with INSERT OVERWRITE or PARTITION",
+                        ));
+                    }
+                    if targets.len() != 1 {
+                        return Err(ErrorCode::SemanticError(format!(
+                            "ON CONFLICT DO UPDATE requires the conflict columns, table '{}' has multiple keys",
+                            table.name()
+                        )));
+                    }
+                    // the conflicting rows are replaced, which is a `REPLACE INTO` on the key,
+                    // the omitted columns would be reset to their default values
+                    let schema = self.schema_project(&table.schema(), columns)?;
+                    let omitted = table
+                        .schema()
+                        .fields()
+                        .iter()
+                        .filter(|f| {
+                            f.computed_expr().is_none() && schema.field_with_name(f.name()).is_err()
+                        })
+                        .map(|f| f.name().clone())
+                        .collect::<Vec<_>>();
+                    if !omitted.is_empty() {
+                        return Err(ErrorCode::SemanticError(format!(
+                            "ON CONFLICT DO UPDATE replaces the whole row, the columns {} of table '{}' must be inserted",
+                            omitted.join(", "),
+                            table.name()
+                        )));
+                    }
+                    let on_conflict_columns = targets[0]
+                        .key_columns()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|name| Identifier::from_name_with_quoted(None, name, Some('`')))
+                        .collect();
+                    let replace = ReplaceStmt {
+                        hints: stmt.hints.clone(),
+                        catalog: stmt.catalog.clone(),
+                        database: stmt.database.clone(),
+                        table: stmt.table.clone(),
+                        on_conflict_columns,
+                        columns: columns.clone(),
+                        source: source.clone(),
+                        delete_when: None,
+                    };
+                    return self.bind_replace(bind_context, &replace).await;
+                }
+            }
+        }
+
+        let (schema, table_info) = if partition.is_empty() {
+            (self.schema_project(&table.schema(), columns)?, None)
+        } else {
+            let (schema, table_info) =
+                self.bind_insert_partition(table.as_ref(), partition, columns)?;
+            (schema, Some(table_info))
+        };
 
         let input_source: Result<InsertInputSource> = match source.clone() {
             InsertSource::Values { rows } => {
@@ -123,6 +308,12 @@ impl Binder {
             InsertSource::RawValues { rest_str, start } => {
                 let values_str = rest_str.trim_end_matches(';').trim_start().to_owned();
                 match self.ctx.get_stage_attachment() {
+                    Some(_) if table_info.is_some() => Err(ErrorCode::SemanticError(
+                        "PARTITION clause is not supported when inserting with stage attachment",
+                    )),
+                    Some(_) if on_conflict.is_some() => Err(ErrorCode::SemanticError(
+                        "ON CONFLICT is not supported when inserting with stage attachment",
+                    )),
                     Some(attachment) => {
                         return self
                             .bind_copy_from_attachment(
@@ -159,7 +350,8 @@ impl Binder {
             schema,
             overwrite: *overwrite,
             source: input_source?,
-            table_info: None,
+            table_info,
+            ignore_conflicts,
         };
 
         Ok(Plan::Insert(Box::new(plan)))
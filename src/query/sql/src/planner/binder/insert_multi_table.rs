@@ -24,6 +24,7 @@ use databend_common_exception::Result;
 use databend_common_expression::types::DataType;
 use databend_common_expression::DataSchemaRef;
 use databend_common_expression::TableSchema;
+use databend_storages_common_table_meta::table::get_table_constraints;
 
 use crate::binder::ScalarBinder;
 use crate::plans::Else;
@@ -178,6 +179,14 @@ impl Binder {
                 .ctx
                 .get_table(&catalog_name, &database_name, &table_name)
                 .await?;
+            if get_table_constraints(target_table.options())?
+                .iter()
+                .any(|c| c.is_enforced())
+            {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Multi-table insert into table '{table_name}' with PRIMARY KEY, UNIQUE or CHECK constraint is not supported"
+                )));
+            }
             target_tables.insert(
                 target_table.get_id(),
                 (database_name.clone(), table_name.clone()),
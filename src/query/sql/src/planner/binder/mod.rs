@@ -35,6 +35,7 @@ mod insert_multi_table;
 mod internal_column_factory;
 mod kill;
 mod location;
+mod prepare;
 mod presign;
 mod project;
 mod project_set;
@@ -76,7 +77,9 @@ pub use internal_column_factory::INTERNAL_COLUMN_FACTORY;
 pub use location::get_storage_params_from_options;
 pub use location::parse_storage_params_from_uri;
 pub use location::parse_uri_location;
+pub(crate) use prepare::resolve_prepared_statement;
 pub use scalar::ScalarBinder;
 pub use scalar_common::*;
 pub use stream_column_factory::STREAM_COLUMN_FACTORY;
+pub use udf::udtf_return_type;
 pub use window::WindowOrderByInfo;
@@ -0,0 +1,83 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_ast::ast::DeallocateStmt;
+use databend_common_ast::ast::ExecutePreparedStmt;
+use databend_common_ast::ast::PrepareStmt;
+use databend_common_ast::ast::Statement;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+
+use crate::normalize_identifier;
+use crate::planner::binder::BindContext;
+use crate::planner::binder::Binder;
+use crate::plans::DeallocatePlan;
+use crate::plans::Plan;
+use crate::plans::PreparePlan;
+use crate::NameResolutionContext;
+use crate::PlaceholderRewriter;
+
+impl Binder {
+    #[async_backtrace::framed]
+    pub(super) async fn bind_prepare(&mut self, stmt: &PrepareStmt) -> Result<Plan> {
+        let PrepareStmt { name, statement } = stmt;
+        if matches!(
+            statement.as_ref(),
+            Statement::Prepare(_) | Statement::ExecutePrepared(_) | Statement::Deallocate(_)
+        ) {
+            return Err(ErrorCode::SyntaxException(format!(
+                "Can not prepare the statement: {statement}"
+            )));
+        }
+
+        Ok(Plan::Prepare(Box::new(PreparePlan {
+            name: normalize_identifier(name, &self.name_resolution_ctx).name,
+            statement: statement.as_ref().clone(),
+        })))
+    }
+
+    #[async_backtrace::framed]
+    pub(super) async fn bind_execute_prepared(
+        &mut self,
+        bind_context: &mut BindContext,
+        stmt: &ExecutePreparedStmt,
+    ) -> Result<Plan> {
+        let stmt = resolve_prepared_statement(self.ctx.as_ref(), &self.name_resolution_ctx, stmt)?;
+        self.bind_statement(bind_context, &stmt).await
+    }
+
+    #[async_backtrace::framed]
+    pub(super) async fn bind_deallocate(&mut self, stmt: &DeallocateStmt) -> Result<Plan> {
+        let name = stmt
+            .name
+            .as_ref()
+            .map(|name| normalize_identifier(name, &self.name_resolution_ctx).name);
+        Ok(Plan::Deallocate(Box::new(DeallocatePlan { name })))
+    }
+}
+
+/// Replace the placeholders of the prepared statement with the parameters of `EXECUTE`.
+pub(crate) fn resolve_prepared_statement(
+    ctx: &dyn TableContext,
+    name_resolution_ctx: &NameResolutionContext,
+    execute: &ExecutePreparedStmt,
+) -> Result<Statement> {
+    let name = normalize_identifier(&execute.name, name_resolution_ctx).name;
+    let mut stmt = ctx.get_prepared_statement(&name).ok_or_else(|| {
+        ErrorCode::UnknownPreparedStatement(format!("Unknown prepared statement {name}"))
+    })?;
+    PlaceholderRewriter::rewrite(&mut stmt, &execute.params)?;
+    Ok(stmt)
+}
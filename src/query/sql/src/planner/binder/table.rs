@@ -484,6 +484,8 @@ impl Binder {
     ) -> Result<NavigationPoint> {
         match travel_point {
             TimeTravelPoint::Snapshot(s) => Ok(NavigationPoint::SnapshotID(s.to_owned())),
+            // The versions of lake tables are the ids of their snapshots.
+            TimeTravelPoint::Version(v) => Ok(NavigationPoint::SnapshotID(v.to_string())),
             TimeTravelPoint::Timestamp(expr) => {
                 let mut type_checker = TypeChecker::try_create(
                     bind_context,
@@ -28,9 +28,12 @@ use databend_common_expression::udf_client::UDFFlightClient;
 use databend_common_expression::DataField;
 use databend_common_meta_app::principal::LambdaUDF;
 use databend_common_meta_app::principal::UDAFScript;
+use databend_common_meta_app::principal::UDAFServer;
 use databend_common_meta_app::principal::UDFDefinition as PlanUDFDefinition;
 use databend_common_meta_app::principal::UDFScript;
 use databend_common_meta_app::principal::UDFServer;
+use databend_common_meta_app::principal::UDTFScript;
+use databend_common_meta_app::principal::UDTFServer;
 use databend_common_meta_app::principal::UserDefinedFunction;
 
 use crate::normalize_identifier;
@@ -130,7 +133,35 @@ impl Binder {
                     created_on: Utc::now(),
                 })
             }
-            UDFDefinition::UDAFServer { .. } => unimplemented!(),
+            UDFDefinition::UDAFServer {
+                arg_types,
+                state_fields,
+                return_type,
+                address,
+                language,
+            } => {
+                UDFValidator::is_udf_server_allowed(address.as_str())?;
+
+                let arg_types = arg_types
+                    .iter()
+                    .map(|arg_type| Ok(DataType::from(&resolve_type_name_udf(arg_type)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let state_fields = resolve_udaf_state_fields(state_fields)?;
+                let return_type = DataType::from(&resolve_type_name_udf(return_type)?);
+
+                Ok(UserDefinedFunction {
+                    name,
+                    description,
+                    definition: PlanUDFDefinition::UDAFServer(UDAFServer {
+                        address: address.clone(),
+                        language: language.clone(),
+                        arg_types,
+                        state_fields,
+                        return_type,
+                    }),
+                    created_on: Utc::now(),
+                })
+            }
             UDFDefinition::UDFScript {
                 arg_types,
                 return_type,
@@ -138,6 +169,8 @@ impl Binder {
                 handler,
                 language,
                 runtime_version,
+                imports,
+                packages,
             } => {
                 UDFValidator::is_udf_script_allowed(&language.parse()?)?;
                 let definition = create_udf_definition_script(
@@ -145,6 +178,8 @@ impl Binder {
                     None,
                     return_type,
                     runtime_version,
+                    imports,
+                    packages,
                     handler,
                     language,
                     code,
@@ -163,12 +198,16 @@ impl Binder {
                 code,
                 language,
                 runtime_version,
+                imports,
+                packages,
             } => {
                 let definition = create_udf_definition_script(
                     arg_types,
                     Some(state_fields),
                     return_type,
                     runtime_version,
+                    imports,
+                    packages,
                     "",
                     language,
                     code,
@@ -180,6 +219,101 @@ impl Binder {
                     created_on: Utc::now(),
                 })
             }
+            UDFDefinition::UDTFServer {
+                arg_types,
+                return_columns,
+                address,
+                handler,
+                language,
+            } => {
+                UDFValidator::is_udf_server_allowed(address.as_str())?;
+
+                let arg_types = arg_types
+                    .iter()
+                    .map(|arg_type| Ok(DataType::from(&resolve_type_name_udf(arg_type)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let return_fields = resolve_udtf_return_fields(return_columns)?;
+
+                let connect_timeout = self
+                    .ctx
+                    .get_settings()
+                    .get_external_server_connect_timeout_secs()?;
+                let request_timeout = self
+                    .ctx
+                    .get_settings()
+                    .get_external_server_request_timeout_secs()?;
+                let batch_rows =
+                    self.ctx
+                        .get_settings()
+                        .get_external_server_request_batch_rows()? as usize;
+
+                let endpoint =
+                    UDFFlightClient::build_endpoint(address, connect_timeout, request_timeout)?;
+
+                // The server returns the rows of each input row as a list of structs.
+                let mut client = UDFFlightClient::connect(endpoint, connect_timeout, batch_rows)
+                    .await?
+                    .with_tenant(self.ctx.get_tenant().tenant_name())?
+                    .with_func_name(&name)?
+                    .with_handler_name(handler)?
+                    .with_query_id(&self.ctx.get_id())?;
+                client
+                    .check_schema(handler, &arg_types, &udtf_return_type(&return_fields))
+                    .await?;
+
+                Ok(UserDefinedFunction {
+                    name,
+                    description,
+                    definition: PlanUDFDefinition::UDTFServer(UDTFServer {
+                        address: address.clone(),
+                        handler: handler.clone(),
+                        language: language.clone(),
+                        arg_types,
+                        return_fields,
+                    }),
+                    created_on: Utc::now(),
+                })
+            }
+            UDFDefinition::UDTFScript {
+                arg_types,
+                return_columns,
+                code,
+                handler,
+                language,
+                runtime_version,
+                imports,
+                packages,
+            } => {
+                let language = check_udf_script_options(language, imports, packages)?;
+                UDFValidator::is_udf_script_allowed(&language)?;
+
+                let arg_types = arg_types
+                    .iter()
+                    .map(|arg_type| Ok(DataType::from(&resolve_type_name_udf(arg_type)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let return_fields = resolve_udtf_return_fields(return_columns)?;
+
+                let mut runtime_version = runtime_version.to_string();
+                if runtime_version.is_empty() && language == UDFLanguage::Python {
+                    runtime_version = "3.12.2".to_string();
+                }
+
+                Ok(UserDefinedFunction {
+                    name,
+                    description,
+                    definition: PlanUDFDefinition::UDTFScript(UDTFScript {
+                        code: code.clone(),
+                        handler: handler.clone(),
+                        language: language.to_string(),
+                        arg_types,
+                        return_fields,
+                        runtime_version,
+                        imports: imports.clone(),
+                        packages: packages.clone(),
+                    }),
+                    created_on: Utc::now(),
+                })
+            }
         }
     }
 
@@ -242,20 +376,99 @@ impl Binder {
     }
 }
 
+fn resolve_udaf_state_fields(fields: &[UDAFStateField]) -> Result<Vec<DataField>> {
+    let state_fields = fields
+        .iter()
+        .map(|field| {
+            Ok(DataField::new(
+                &field.name.name,
+                DataType::from(&resolve_type_name_udf(&field.type_name)?),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let state_field_names = state_fields
+        .iter()
+        .map(|f| f.name())
+        .collect::<HashSet<_>>();
+    if state_field_names.len() != state_fields.len() {
+        return Err(ErrorCode::InvalidArgument(
+            "Duplicate state field name in UDAF",
+        ));
+    }
+    Ok(state_fields)
+}
+
+fn resolve_udtf_return_fields(columns: &[(Identifier, TypeName)]) -> Result<Vec<DataField>> {
+    let return_fields = columns
+        .iter()
+        .map(|(name, type_name)| {
+            Ok(DataField::new(
+                &name.name,
+                DataType::from(&resolve_type_name_udf(type_name)?),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let column_names = return_fields
+        .iter()
+        .map(|f| f.name())
+        .collect::<HashSet<_>>();
+    if column_names.len() != return_fields.len() {
+        return Err(ErrorCode::InvalidArgument(
+            "Duplicate return column name in table function",
+        ));
+    }
+    Ok(return_fields)
+}
+
+/// The type a table function returns for each input row: the list of rows it emits.
+pub fn udtf_return_type(return_fields: &[DataField]) -> DataType {
+    DataType::Array(Box::new(DataType::Tuple(
+        return_fields
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect(),
+    )))
+}
+
+fn check_udf_script_options(
+    language: &str,
+    imports: &[String],
+    packages: &[String],
+) -> Result<UDFLanguage> {
+    let Ok(language) = language.parse::<UDFLanguage>() else {
+        return Err(ErrorCode::InvalidArgument(format!(
+            "Unallowed UDF language {language:?}, must be python, javascript or wasm"
+        )));
+    };
+
+    if language != UDFLanguage::Python && (!imports.is_empty() || !packages.is_empty()) {
+        return Err(ErrorCode::InvalidArgument(format!(
+            "IMPORTS and PACKAGES are only supported by python UDF, but got {language}"
+        )));
+    }
+    if let Some(import) = imports.iter().find(|import| !import.starts_with('@')) {
+        return Err(ErrorCode::InvalidArgument(format!(
+            "UDF import {import:?} must be a stage file like '@stage/path/module.py'"
+        )));
+    }
+
+    Ok(language)
+}
+
 fn create_udf_definition_script(
     arg_types: &[TypeName],
     state_fields: Option<&[UDAFStateField]>,
     return_type: &TypeName,
     runtime_version: &str,
+    imports: &[String],
+    packages: &[String],
     handler: &str,
     language: &str,
     code: &str,
 ) -> Result<PlanUDFDefinition> {
-    let Ok(language) = language.parse::<UDFLanguage>() else {
-        return Err(ErrorCode::InvalidArgument(format!(
-            "Unallowed UDF language {language:?}, must be python, javascript or wasm"
-        )));
-    };
+    let language = check_udf_script_options(language, imports, packages)?;
 
     let arg_types = arg_types
         .iter()
@@ -271,26 +484,7 @@ fn create_udf_definition_script(
 
     match state_fields {
         Some(fields) => {
-            let state_fields = fields
-                .iter()
-                .map(|field| {
-                    Ok(DataField::new(
-                        &field.name.name,
-                        DataType::from(&resolve_type_name_udf(&field.type_name)?),
-                    ))
-                })
-                .collect::<Result<Vec<_>>>()?;
-
-            let state_field_names = state_fields
-                .iter()
-                .map(|f| f.name())
-                .collect::<HashSet<_>>();
-            if state_field_names.len() != state_fields.len() {
-                return Err(ErrorCode::InvalidArgument(format!(
-                    "Duplicate state field name in UDAF script"
-                )));
-            }
-
+            let state_fields = resolve_udaf_state_fields(fields)?;
             Ok(PlanUDFDefinition::UDAFScript(UDAFScript {
                 code: code.to_string(),
                 arg_types,
@@ -298,6 +492,8 @@ fn create_udf_definition_script(
                 return_type,
                 language: language.to_string(),
                 runtime_version,
+                imports: imports.to_vec(),
+                packages: packages.to_vec(),
             }))
         }
         None => Ok(PlanUDFDefinition::UDFScript(UDFScript {
@@ -307,6 +503,8 @@ fn create_udf_definition_script(
             handler: handler.to_string(),
             language: language.to_string(),
             runtime_version,
+            imports: imports.to_vec(),
+            packages: packages.to_vec(),
         })),
     }
 }
@@ -81,6 +81,7 @@ impl Binder {
             | RelOperator::Limit(_)
             | RelOperator::Aggregate(_)
             | RelOperator::Window(_)
+            | RelOperator::MatchRecognize(_)
             | RelOperator::Mutation(_)
             | RelOperator::MutationSource(_)
             | RelOperator::CompactBlock(_) => {
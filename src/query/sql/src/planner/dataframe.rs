@@ -480,6 +480,7 @@ impl Dataframe {
                 join: Join {
                     op: op.clone(),
                     condition: condition.clone(),
+                    match_condition: None,
                     left: Box::new(left),
                     right: Box::new(right),
                 },
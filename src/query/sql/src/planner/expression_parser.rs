@@ -354,6 +354,91 @@ pub fn parse_computed_expr_to_string(
     Ok(format!("{:#}", ast))
 }
 
+/// Resolve the expression of a CHECK constraint against the schema of the table.
+///
+/// Returns the normalized expression and the columns referenced by it.
+pub fn parse_check_expr_to_string(
+    ctx: Arc<dyn TableContext>,
+    table_schema: TableSchemaRef,
+    ast: &AExpr,
+) -> Result<(String, Vec<String>)> {
+    let mut bind_context = BindContext::new();
+    let mut metadata = Metadata::default();
+    for (index, field) in table_schema.fields().iter().enumerate() {
+        bind_context.add_column_binding(
+            ColumnBindingBuilder::new(
+                field.name().clone(),
+                index,
+                Box::new(field.data_type().into()),
+                Visibility::Visible,
+            )
+            .build(),
+        );
+        metadata.add_base_table_column(
+            field.name().clone(),
+            field.data_type().clone(),
+            0,
+            None,
+            Some(field.column_id),
+            None,
+            None,
+        );
+    }
+
+    let settings = ctx.get_settings();
+    let name_resolution_ctx = NameResolutionContext::try_from(settings.as_ref())?;
+    let mut type_checker = TypeChecker::try_create(
+        &mut bind_context,
+        ctx,
+        &name_resolution_ctx,
+        Arc::new(RwLock::new(metadata)),
+        &[],
+        false,
+    )?;
+
+    let (scalar, data_type) = *type_checker.resolve(ast)?;
+    if !scalar.evaluable() {
+        return Err(ErrorCode::SemanticError(format!(
+            "check constraint expression `{:#}` is invalid",
+            ast
+        )));
+    }
+    if data_type.remove_nullable() != DataType::Boolean {
+        return Err(ErrorCode::SemanticError(format!(
+            "expected check constraint expression have type Boolean, but `{}` has type {}.",
+            ast, data_type,
+        )));
+    }
+    let check_expr = scalar.as_expr()?;
+    if !check_expr.is_deterministic(&BUILTIN_FUNCTIONS) {
+        return Err(ErrorCode::SemanticError(format!(
+            "check constraint expression `{}` is not deterministic.",
+            check_expr.sql_display(),
+        )));
+    }
+    let used_columns = scalar.used_columns();
+    if used_columns.is_empty() {
+        return Err(ErrorCode::SemanticError(format!(
+            "check constraint expression `{}` doesn't reference any column.",
+            check_expr.sql_display(),
+        )));
+    }
+    let columns = table_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| used_columns.contains(index))
+        .map(|(_, field)| field.name().clone())
+        .collect();
+
+    let mut ast = ast.clone();
+    let mut normalizer = IdentifierNormalizer {
+        ctx: &name_resolution_ctx,
+    };
+    ast.drive_mut(&mut normalizer);
+    Ok((format!("{:#}", ast), columns))
+}
+
 pub fn parse_lambda_expr(
     ctx: Arc<dyn TableContext>,
     lambda_context: &mut BindContext,
@@ -72,6 +72,8 @@ impl Plan {
             Plan::DropTableColumn(_) => Ok("DropTableColumn".to_string()),
             Plan::AlterTableClusterKey(_) => Ok("AlterTableClusterKey".to_string()),
             Plan::DropTableClusterKey(_) => Ok("DropTableClusterKey".to_string()),
+            Plan::AddTableConstraint(_) => Ok("AddTableConstraint".to_string()),
+            Plan::DropTableConstraint(_) => Ok("DropTableConstraint".to_string()),
             Plan::ReclusterTable(_) => Ok("ReclusterTable".to_string()),
             Plan::TruncateTable(_) => Ok("TruncateTable".to_string()),
             Plan::OptimizePurge(_) => Ok("OptimizePurge".to_string()),
@@ -95,6 +97,7 @@ impl Plan {
 
             // Dynamic Tables
             Plan::CreateDynamicTable(_) => Ok("CreateDynamicTable".to_string()),
+            Plan::RefreshDynamicTable(_) => Ok("RefreshDynamicTable".to_string()),
 
             // Indexes
             Plan::CreateIndex(_) => Ok("CreateIndex".to_string()),
@@ -144,6 +147,8 @@ impl Plan {
 
             Plan::Set(_) => Ok("Set".to_string()),
             Plan::Unset(_) => Ok("Unset".to_string()),
+            Plan::Prepare(_) => Ok("Prepare".to_string()),
+            Plan::Deallocate(_) => Ok("Deallocate".to_string()),
             Plan::SetRole(_) => Ok("SetRole".to_string()),
             Plan::SetSecondaryRoles(_) => Ok("SetSecondaryRoles".to_string()),
             Plan::UseDatabase(_) => Ok("UseDatabase".to_string()),
@@ -182,9 +187,18 @@ impl Plan {
             Plan::DescConnection(_) => Ok("DescConnection".to_string()),
             Plan::DropConnection(_) => Ok("DropConnection".to_string()),
             Plan::ShowConnections(_) => Ok("ShowConnections".to_string()),
+
+            // pipe
+            Plan::CreatePipe(_) => Ok("CreatePipe".to_string()),
+            Plan::AlterPipe(_) => Ok("AlterPipe".to_string()),
+            Plan::DropPipe(_) => Ok("DropPipe".to_string()),
+            Plan::DescPipe(_) => Ok("DescPipe".to_string()),
             Plan::Begin => Ok("Begin".to_string()),
             Plan::Commit => Ok("commit".to_string()),
             Plan::Abort => Ok("Abort".to_string()),
+            Plan::Savepoint { .. } => Ok("Savepoint".to_string()),
+            Plan::RollbackToSavepoint { .. } => Ok("RollbackToSavepoint".to_string()),
+            Plan::ReleaseSavepoint { .. } => Ok("ReleaseSavepoint".to_string()),
 
             // Notification
             Plan::CreateNotification(_) => Ok("CreateNotification".to_string()),
@@ -30,6 +30,8 @@ use crate::plans::Filter;
 use crate::plans::Join;
 use crate::plans::JoinType;
 use crate::plans::Limit;
+use crate::plans::MatchFunction;
+use crate::plans::MatchRecognize;
 use crate::plans::RelOperator;
 use crate::plans::ScalarExpr;
 use crate::plans::Scan;
@@ -148,6 +150,7 @@ pub(super) fn to_format_tree<I: IdHumanizer<ColumnId = IndexType, TableId = Inde
         RelOperator::Filter(op) => filter_to_format_tree(id_humanizer, op),
         RelOperator::Aggregate(op) => aggregate_to_format_tree(id_humanizer, op),
         RelOperator::Window(op) => window_to_format_tree(id_humanizer, op),
+        RelOperator::MatchRecognize(op) => match_recognize_to_format_tree(id_humanizer, op),
         RelOperator::Udf(op) => udf_to_format_tree(id_humanizer, op),
         RelOperator::AsyncFunction(op) => async_func_to_format_tree(id_humanizer, op),
         RelOperator::Sort(op) => sort_to_format_tree(id_humanizer, op),
@@ -212,6 +215,8 @@ fn format_join(op: &Join) -> String {
         JoinType::RightMark => "RightMark".to_string(),
         JoinType::LeftSingle => "LeftSingle".to_string(),
         JoinType::RightSingle => "RightSingle".to_string(),
+        JoinType::Asof => "Asof".to_string(),
+        JoinType::LeftAsof => "LeftAsof".to_string(),
     };
 
     format!("Join({})", join_type)
@@ -297,6 +302,42 @@ fn window_to_format_tree<I: IdHumanizer<ColumnId = IndexType, TableId = IndexTyp
     ])
 }
 
+fn match_recognize_to_format_tree<I: IdHumanizer<ColumnId = IndexType, TableId = IndexType>>(
+    _id_humanizer: &I,
+    op: &MatchRecognize,
+) -> FormatTreeNode {
+    let partition_by_items = op
+        .partition_by
+        .iter()
+        .map(|item| format_scalar(&item.scalar))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let measures = op
+        .measures
+        .iter()
+        .map(|measure| {
+            let func = match &measure.func {
+                MatchFunction::First(arg) => format!("first(#{arg})"),
+                MatchFunction::Last(arg) => format!("last(#{arg})"),
+                MatchFunction::Aggregate(agg) => agg.display_name.clone(),
+                MatchFunction::MatchNumber => "match_number()".to_string(),
+                MatchFunction::Classifier => "classifier()".to_string(),
+            };
+            format!("{} AS (#{})", func, measure.index)
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    FormatTreeNode::with_children("MatchRecognize".to_string(), vec![
+        FormatTreeNode::new(format!("partition items: [{}]", partition_by_items)),
+        FormatTreeNode::new(format!("pattern: ({})", op.pattern_display)),
+        FormatTreeNode::new(format!("measures: [{}]", measures)),
+        FormatTreeNode::new(format!("rows per match: {:?}", op.rows_per_match)),
+        FormatTreeNode::new(format!("after match skip: {:?}", op.after_match_skip)),
+    ])
+}
+
 fn udf_to_format_tree<I: IdHumanizer<ColumnId = IndexType, TableId = IndexType>>(
     _id_humanizer: &I,
     op: &Udf,
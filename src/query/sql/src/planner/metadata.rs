@@ -33,6 +33,8 @@ use databend_common_expression::TableField;
 use parking_lot::RwLock;
 
 use crate::optimizer::SExpr;
+use crate::ColumnBinding;
+use crate::ParameterBindings;
 use crate::ScalarExpr;
 
 /// Planner use [`usize`] as it's index type.
@@ -79,6 +81,9 @@ pub struct Metadata {
     next_scan_id: usize,
     /// Mappings from base column index to scan id.
     base_column_scan_id: HashMap<IndexType, usize>,
+
+    /// The parameter columns of the prepared statement being bound.
+    parameters: Option<Arc<ParameterBindings>>,
 }
 
 impl Metadata {
@@ -468,6 +473,19 @@ impl Metadata {
         }
     }
 
+    pub fn set_parameters(&mut self, parameters: ParameterBindings) {
+        self.parameters = Some(Arc::new(parameters));
+    }
+
+    /// The parameter column bound to the placeholder of the prepared statement.
+    pub fn parameter_column(&self, expr: &Expr) -> Option<ColumnBinding> {
+        self.parameters.as_ref()?.column(expr).cloned()
+    }
+
+    pub fn parameters(&self) -> Option<&ParameterBindings> {
+        self.parameters.as_deref()
+    }
+
     pub fn set_max_column_position(&mut self, max_pos: usize) {
         self.max_column_position = max_pos
     }
@@ -27,6 +27,7 @@ pub mod optimizer;
 mod planner_cache;
 pub mod plans;
 mod stream_column;
+mod substrait_binder;
 mod udf_validator;
 
 pub use binder::execute_commit_statement;
@@ -53,3 +54,4 @@ pub use plans::INSERT_NAME;
 pub use plans::UPDATE_NAME;
 pub use semantic::*;
 pub use stream_column::*;
+pub use substrait_binder::SubstraitBinder;
@@ -138,6 +138,7 @@ pub async fn dynamic_sample(
         | RelOperator::Sort(_)
         | RelOperator::Exchange(_)
         | RelOperator::Window(_)
+        | RelOperator::MatchRecognize(_)
         | RelOperator::Udf(_)
         | RelOperator::AsyncFunction(_) => {
             dynamic_sample(ctx, metadata, s_expr.child(0)?, sample_executor).await
@@ -0,0 +1,297 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use databend_common_catalog::table::Table;
+use databend_common_exception::Result;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraintKind;
+
+use crate::optimizer::ColumnSet;
+use crate::optimizer::RelExpr;
+use crate::optimizer::SExpr;
+use crate::plans::Filter;
+use crate::plans::FunctionCall;
+use crate::plans::Join;
+use crate::plans::JoinType;
+use crate::plans::RelOperator;
+use crate::plans::Scan;
+use crate::ColumnEntry;
+use crate::IndexType;
+use crate::MetadataRef;
+use crate::ScalarExpr;
+
+// The JoinEliminationOptimizer removes the joins whose one side produces no required column
+// and doesn't change the rows of the other side, according to the table constraints:
+// 1. `t LEFT JOIN u ON t.a = u.k`, `u.k` is a PRIMARY KEY or UNIQUE constraint of `u`,
+//    each row of `t` is joined with at most one row of `u`.
+// 2. `t INNER JOIN u ON t.a = u.k`, besides, `t.a` is a FOREIGN KEY referencing `u.k`,
+//    each row of `t` whose `a` is not NULL is joined with exactly one row of `u`.
+//
+// The foreign keys are not enforced, the optimizer trusts them.
+pub struct JoinEliminationOptimizer {
+    metadata: MetadataRef,
+}
+
+impl JoinEliminationOptimizer {
+    pub fn new(metadata: MetadataRef) -> Self {
+        JoinEliminationOptimizer { metadata }
+    }
+
+    /// `required` are the columns required by the parent of the expression.
+    pub fn run(self, s_expr: &SExpr, required: &ColumnSet) -> Result<SExpr> {
+        self.eliminate(s_expr, required.clone())
+    }
+
+    #[recursive::recursive]
+    fn eliminate(&self, s_expr: &SExpr, mut required: ColumnSet) -> Result<SExpr> {
+        match s_expr.plan.as_ref() {
+            RelOperator::Join(join) => {
+                if let Some(s_expr) = self.try_eliminate_join(s_expr, join, &required)? {
+                    return self.eliminate(&s_expr, required);
+                }
+                required.extend(join.used_columns()?);
+            }
+            RelOperator::EvalScalar(eval_scalar) => required.extend(eval_scalar.used_columns()?),
+            RelOperator::Filter(filter) => required.extend(filter.used_columns()?),
+            RelOperator::Aggregate(aggregate) => required.extend(aggregate.used_columns()?),
+            RelOperator::Sort(sort) => {
+                required.extend(sort.used_columns());
+                required.extend(sort.pre_projection.iter().flatten());
+            }
+            RelOperator::Limit(_) => {}
+            // the columns required by other operators are unknown
+            _ => return Ok(s_expr.clone()),
+        }
+
+        let mut children_changed = false;
+        let mut children = Vec::with_capacity(s_expr.arity());
+        for child in s_expr.children() {
+            let new_child = self.eliminate(child, required.clone())?;
+            if !new_child.eq(child) {
+                children_changed = true;
+            }
+            children.push(Arc::new(new_child));
+        }
+        if children_changed {
+            return Ok(s_expr.replace_children(children));
+        }
+        Ok(s_expr.clone())
+    }
+
+    fn try_eliminate_join(
+        &self,
+        s_expr: &SExpr,
+        join: &Join,
+        required: &ColumnSet,
+    ) -> Result<Option<SExpr>> {
+        if join.equi_conditions.is_empty()
+            || !join.non_equi_conditions.is_empty()
+            || join.equi_conditions.iter().any(|c| c.is_null_equal)
+            || join.marker_index.is_some()
+            || join.from_correlated_subquery
+            || join.is_lateral
+            || join.single_to_inner.is_some()
+            || join.build_side_cache_info.is_some()
+        {
+            return Ok(None);
+        }
+
+        let left_keys = join
+            .equi_conditions
+            .iter()
+            .map(|c| c.left.clone())
+            .collect::<Vec<_>>();
+        let right_keys = join
+            .equi_conditions
+            .iter()
+            .map(|c| c.right.clone())
+            .collect::<Vec<_>>();
+        let (left, right) = (s_expr.child(0)?, s_expr.child(1)?);
+        match join.join_type {
+            JoinType::Left => {
+                if self.is_unique_side(right, &right_keys, required, true)? {
+                    return Ok(Some(left.clone()));
+                }
+            }
+            JoinType::Right => {
+                if self.is_unique_side(left, &left_keys, required, true)? {
+                    return Ok(Some(right.clone()));
+                }
+            }
+            JoinType::Inner => {
+                for (unique_side, unique_keys, other_side, other_keys) in [
+                    (right, &right_keys, left, &left_keys),
+                    (left, &left_keys, right, &right_keys),
+                ] {
+                    if self.is_unique_side(unique_side, unique_keys, required, false)?
+                        && self.is_foreign_key(other_keys, unique_keys)?
+                    {
+                        return Ok(Some(Self::filter_null_keys(other_side, other_keys)));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    // Whether the side of the join produces no required column, and each row of the other side
+    // is joined with at most one of its rows.
+    fn is_unique_side(
+        &self,
+        s_expr: &SExpr,
+        keys: &[ScalarExpr],
+        required: &ColumnSet,
+        allow_filter: bool,
+    ) -> Result<bool> {
+        let Some(scan) = Self::scan_of(s_expr, allow_filter) else {
+            return Ok(false);
+        };
+        let prop = RelExpr::with_s_expr(s_expr).derive_relational_prop()?;
+        if !prop.output_columns.is_disjoint(required) {
+            return Ok(false);
+        }
+        let Some((table_index, columns)) = self.base_table_columns(keys) else {
+            return Ok(false);
+        };
+        if table_index != scan.table_index {
+            return Ok(false);
+        }
+
+        let metadata = self.metadata.read();
+        let table = metadata.table(table_index).table();
+        let constraints = get_table_constraints(table.get_table_info().options())?;
+        let columns = columns.iter().collect::<HashSet<_>>();
+        Ok(constraints.iter().any(|c| {
+            c.key_columns()
+                .is_some_and(|key| key.iter().all(|column| columns.contains(column)))
+        }))
+    }
+
+    // Whether the keys of the other side are a foreign key referencing the unique keys.
+    fn is_foreign_key(&self, keys: &[ScalarExpr], ref_keys: &[ScalarExpr]) -> Result<bool> {
+        let (Some((table_index, columns)), Some((ref_table_index, ref_columns))) = (
+            self.base_table_columns(keys),
+            self.base_table_columns(ref_keys),
+        ) else {
+            return Ok(false);
+        };
+
+        let metadata = self.metadata.read();
+        let table_entry = metadata.table(table_index);
+        let ref_table_entry = metadata.table(ref_table_index);
+        if table_entry.catalog() != ref_table_entry.catalog() {
+            return Ok(false);
+        }
+        let pairs = columns
+            .iter()
+            .zip(ref_columns.iter())
+            .collect::<HashSet<_>>();
+        let constraints = get_table_constraints(table_entry.table().get_table_info().options())?;
+        Ok(constraints.iter().any(|c| match &c.kind {
+            TableConstraintKind::ForeignKey {
+                columns,
+                ref_database,
+                ref_table,
+                ref_columns,
+            } => {
+                ref_database == ref_table_entry.database()
+                    && ref_table == ref_table_entry.name()
+                    && columns
+                        .iter()
+                        .zip(ref_columns.iter())
+                        .collect::<HashSet<_>>()
+                        == pairs
+            }
+            _ => false,
+        }))
+    }
+
+    // The scan of the side, which may be filtered if `allow_filter` is true. The rows of the
+    // scan must be the rows of the table.
+    fn scan_of(s_expr: &SExpr, allow_filter: bool) -> Option<&Scan> {
+        match s_expr.plan.as_ref() {
+            RelOperator::Scan(scan) => {
+                if scan.change_type.is_some() || scan.agg_index.is_some() {
+                    return None;
+                }
+                if !allow_filter
+                    && (scan.push_down_predicates.is_some()
+                        || scan.limit.is_some()
+                        || scan.prewhere.is_some()
+                        || scan.sample.is_some()
+                        || scan.inverted_index.is_some())
+                {
+                    return None;
+                }
+                Some(scan)
+            }
+            RelOperator::Filter(_) if allow_filter => Self::scan_of(s_expr.child(0).ok()?, true),
+            _ => None,
+        }
+    }
+
+    // The table and the names of the columns if all the keys are columns of the same table.
+    fn base_table_columns(&self, keys: &[ScalarExpr]) -> Option<(IndexType, Vec<String>)> {
+        let metadata = self.metadata.read();
+        let mut table_index = None;
+        let mut columns = Vec::with_capacity(keys.len());
+        for key in keys {
+            let ScalarExpr::BoundColumnRef(column_ref) = key else {
+                return None;
+            };
+            let ColumnEntry::BaseTableColumn(column) = metadata.column(column_ref.column.index)
+            else {
+                return None;
+            };
+            if column.path_indices.is_some() || column.virtual_expr.is_some() {
+                return None;
+            }
+            if table_index.is_some_and(|index| index != column.table_index) {
+                return None;
+            }
+            table_index = Some(column.table_index);
+            columns.push(column.column_name.clone());
+        }
+        Some((table_index?, columns))
+    }
+
+    // The rows whose foreign key is NULL are not joined.
+    fn filter_null_keys(s_expr: &SExpr, keys: &[ScalarExpr]) -> SExpr {
+        let predicates = keys
+            .iter()
+            .filter(|key| {
+                key.data_type()
+                    .is_ok_and(|data_type| data_type.is_nullable())
+            })
+            .map(|key| {
+                ScalarExpr::FunctionCall(FunctionCall {
+                    span: None,
+                    func_name: "is_not_null".to_string(),
+                    params: vec![],
+                    arguments: vec![key.clone()],
+                })
+            })
+            .collect::<Vec<_>>();
+        if predicates.is_empty() {
+            return s_expr.clone();
+        }
+        SExpr::create_unary(
+            Arc::new(RelOperator::Filter(Filter { predicates })),
+            Arc::new(s_expr.clone()),
+        )
+    }
+}
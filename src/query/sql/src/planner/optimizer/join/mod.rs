@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod join_elimination;
 mod single_to_inner;
 
+pub use join_elimination::JoinEliminationOptimizer;
 pub use single_to_inner::SingleToInnerOptimizer;
@@ -37,11 +37,13 @@ use crate::optimizer::distributed::SortAndLimitPushDownOptimizer;
 use crate::optimizer::filter::DeduplicateJoinConditionOptimizer;
 use crate::optimizer::filter::PullUpFilterOptimizer;
 use crate::optimizer::hyper_dp::DPhpy;
+use crate::optimizer::join::JoinEliminationOptimizer;
 use crate::optimizer::join::SingleToInnerOptimizer;
 use crate::optimizer::rule::TransformResult;
 use crate::optimizer::statistics::CollectStatisticsOptimizer;
 use crate::optimizer::util::contains_local_table_scan;
 use crate::optimizer::util::contains_warehouse_table_scan;
+use crate::optimizer::ColumnSet;
 use crate::optimizer::RuleFactory;
 use crate::optimizer::RuleID;
 use crate::optimizer::SExpr;
@@ -76,6 +78,8 @@ pub struct OptimizerContext {
     planning_agg_index: bool,
     #[educe(Debug(ignore))]
     pub(crate) sample_executor: Option<Arc<dyn QueryExecutor>>,
+    // The output columns of the query, the joins producing none of them may be eliminated.
+    required_columns: Option<ColumnSet>,
 }
 
 impl OptimizerContext {
@@ -90,6 +94,7 @@ impl OptimizerContext {
             max_push_down_limit: 10000,
             sample_executor: None,
             planning_agg_index: false,
+            required_columns: None,
         }
     }
 
@@ -201,14 +206,17 @@ pub async fn optimize(mut opt_ctx: OptimizerContext, plan: Plan) -> Result<Plan>
             rewrite_kind,
             formatted_ast,
             ignore_result,
-        } => Ok(Plan::Query {
-            s_expr: Box::new(optimize_query(&mut opt_ctx, *s_expr).await?),
-            bind_context,
-            metadata,
-            rewrite_kind,
-            formatted_ast,
-            ignore_result,
-        }),
+        } => {
+            opt_ctx.required_columns = Some(bind_context.columns.iter().map(|c| c.index).collect());
+            Ok(Plan::Query {
+                s_expr: Box::new(optimize_query(&mut opt_ctx, *s_expr).await?),
+                bind_context,
+                metadata,
+                rewrite_kind,
+                formatted_ast,
+                ignore_result,
+            })
+        }
         Plan::Explain { kind, config, plan } => match kind {
             ExplainKind::Ast(_) | ExplainKind::Syntax(_) => {
                 Ok(Plan::Explain { config, kind, plan })
@@ -420,6 +428,12 @@ pub async fn optimize_query(opt_ctx: &mut OptimizerContext, mut s_expr: SExpr) -
     // Run default rewrite rules
     s_expr = RecursiveOptimizer::new(&DEFAULT_REWRITE_RULES, opt_ctx).run(&s_expr)?;
 
+    // Eliminate the joins according to the table constraints.
+    if let Some(required_columns) = &opt_ctx.required_columns {
+        s_expr = JoinEliminationOptimizer::new(opt_ctx.metadata.clone())
+            .run(&s_expr, required_columns)?;
+    }
+
     // Run post rewrite rules
     s_expr = RecursiveOptimizer::new(&[RuleID::SplitAggregate], opt_ctx).run(&s_expr)?;
 
@@ -134,6 +134,15 @@ pub fn try_push_down_filter_join(s_expr: &SExpr, metadata: MetadataRef) -> Resul
             break;
         }
         let pred = JoinPredicate::new(&predicate, &left_prop, &right_prop);
+        if join.join_type.is_asof_join() {
+            // Filtering the right side of an asof join changes the closest matched rows,
+            // so only the predicates of the left side can be pushed down.
+            match pred {
+                JoinPredicate::Left(_) => left_push_down.push(predicate),
+                _ => original_predicates.push(predicate),
+            }
+            continue;
+        }
         match pred {
             JoinPredicate::ALL(_) => {
                 push_down_predicates.push(predicate);
@@ -200,7 +209,11 @@ pub fn try_push_down_filter_join(s_expr: &SExpr, metadata: MetadataRef) -> Resul
         return Ok((false, s_expr.clone()));
     }
 
-    if !matches!(join.join_type, JoinType::Full) && !join.has_null_equi_condition() {
+    if !matches!(
+        join.join_type,
+        JoinType::Full | JoinType::Asof | JoinType::LeftAsof
+    ) && !join.has_null_equi_condition()
+    {
         // Infer new predicate and push down filter.
         for equi_condition in join.equi_conditions.iter() {
             let left = equi_condition.left.clone();
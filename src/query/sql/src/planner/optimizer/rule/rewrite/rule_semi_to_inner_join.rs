@@ -152,6 +152,7 @@ fn find_group_by_keys(
         | RelOperator::RecursiveCteScan(_)
         | RelOperator::Mutation(_)
         | RelOperator::MutationSource(_)
+        | RelOperator::MatchRecognize(_)
         | RelOperator::CompactBlock(_) => {}
     }
     Ok(())
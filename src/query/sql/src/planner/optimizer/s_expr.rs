@@ -334,6 +334,13 @@ impl SExpr {
                     });
                 }
             }
+            RelOperator::MatchRecognize(op) => {
+                for condition in op.variables.iter().filter_map(|v| v.condition.as_ref()) {
+                    get_udf_names(condition)?.iter().for_each(|udf| {
+                        udfs.insert(*udf);
+                    });
+                }
+            }
             RelOperator::Limit(_)
             | RelOperator::UnionAll(_)
             | RelOperator::Sort(_)
@@ -483,6 +490,16 @@ fn find_subquery(rel_op: &RelOperator) -> bool {
             .iter()
             .any(|expr| find_subquery_in_expr(&expr.scalar)),
         RelOperator::MutationSource(_) => false,
+        RelOperator::MatchRecognize(op) => {
+            op.partition_by
+                .iter()
+                .any(|expr| find_subquery_in_expr(&expr.scalar))
+                || op
+                    .variables
+                    .iter()
+                    .filter_map(|v| v.condition.as_ref())
+                    .any(find_subquery_in_expr)
+        }
     }
 }
 
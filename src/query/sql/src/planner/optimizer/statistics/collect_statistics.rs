@@ -15,12 +15,17 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use databend_common_catalog::statistics::BasicColumnStatistics;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table::TableStatistics;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::Result;
 use databend_common_expression::types::NumberScalar;
 use databend_common_expression::types::F64;
 use databend_common_expression::ColumnId;
 use databend_common_expression::Scalar;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraintKind;
 
 use crate::optimizer::SExpr;
 use crate::plans::ConstantExpr;
@@ -30,6 +35,7 @@ use crate::plans::RelOperator;
 use crate::plans::Statistics;
 use crate::BaseTableColumn;
 use crate::ColumnEntry;
+use crate::IndexType;
 use crate::MetadataRef;
 use crate::ScalarExpr;
 
@@ -92,6 +98,16 @@ impl CollectStatisticsOptimizer {
                     }
                 }
 
+                if scan.change_type.is_none() {
+                    self.tighten_ndv_by_constraints(
+                        table.as_ref(),
+                        &columns,
+                        table_stats.as_ref(),
+                        &mut column_stats,
+                    )
+                    .await?;
+                }
+
                 let mut scan = scan.clone();
                 scan.statistics = Arc::new(Statistics {
                     table_stats,
@@ -150,4 +166,81 @@ impl CollectStatisticsOptimizer {
             }
         }
     }
+
+    // The number of distinct values of a single column key is the number of its not NULL
+    // values, and the one of a single column foreign key is at most the number of rows of the
+    // referenced table.
+    async fn tighten_ndv_by_constraints(
+        &self,
+        table: &dyn Table,
+        columns: &[ColumnEntry],
+        table_stats: Option<&TableStatistics>,
+        column_stats: &mut HashMap<IndexType, Option<BasicColumnStatistics>>,
+    ) -> Result<()> {
+        let constraints = get_table_constraints(table.get_table_info().options())?;
+        let column_index = |name: &str| {
+            columns.iter().find_map(|column| match column {
+                ColumnEntry::BaseTableColumn(BaseTableColumn {
+                    column_index,
+                    column_name,
+                    path_indices: None,
+                    virtual_expr: None,
+                    ..
+                }) if column_name == name => Some(*column_index),
+                _ => None,
+            })
+        };
+        for constraint in constraints {
+            match &constraint.kind {
+                TableConstraintKind::PrimaryKey { columns }
+                | TableConstraintKind::Unique { columns }
+                    if columns.len() == 1 =>
+                {
+                    let (Some(index), Some(num_rows)) = (
+                        column_index(&columns[0]),
+                        table_stats.and_then(|stats| stats.num_rows),
+                    ) else {
+                        continue;
+                    };
+                    if let Some(Some(col_stat)) = column_stats.get_mut(&index) {
+                        col_stat.ndv = Some(num_rows.saturating_sub(col_stat.null_count));
+                    }
+                }
+                TableConstraintKind::ForeignKey {
+                    columns,
+                    ref_database,
+                    ref_table,
+                    ..
+                } if columns.len() == 1 => {
+                    let Some(index) = column_index(&columns[0]) else {
+                        continue;
+                    };
+                    let Some(Some(col_stat)) = column_stats.get_mut(&index) else {
+                        continue;
+                    };
+                    // the referenced table may have been dropped
+                    let Ok(ref_table) = self
+                        .table_ctx
+                        .get_table(table.get_table_info().catalog(), ref_database, ref_table)
+                        .await
+                    else {
+                        continue;
+                    };
+                    let ref_num_rows = ref_table
+                        .table_statistics(self.table_ctx.clone(), true, None)
+                        .await?
+                        .and_then(|stats| stats.num_rows);
+                    if let Some(ref_num_rows) = ref_num_rows {
+                        col_stat.ndv = Some(
+                            col_stat
+                                .ndv
+                                .map_or(ref_num_rows, |ndv| ndv.min(ref_num_rows)),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
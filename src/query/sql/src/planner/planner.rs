@@ -15,6 +15,7 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use databend_common_ast::ast::ExecutePreparedStmt;
 use databend_common_ast::ast::Expr;
 use databend_common_ast::ast::InsertSource;
 use databend_common_ast::ast::InsertStmt;
@@ -26,28 +27,46 @@ use databend_common_ast::parser::parse_sql;
 use databend_common_ast::parser::token::Token;
 use databend_common_ast::parser::token::TokenKind;
 use databend_common_ast::parser::token::Tokenizer;
+use databend_common_ast::parser::tokenize_sql;
 use databend_common_ast::parser::Dialect;
 use databend_common_catalog::catalog::CatalogManager;
 use databend_common_catalog::query_kind::QueryKind;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::ConstantFolder;
+use databend_common_expression::Expr as EExpr;
+use databend_common_expression::Scalar;
+use databend_common_functions::BUILTIN_FUNCTIONS;
 use derive_visitor::DriveMut;
+use itertools::Itertools;
 use log::info;
 use log::warn;
 use parking_lot::RwLock;
 
 use super::semantic::AggregateRewriter;
 use super::semantic::DistinctToGroupBy;
+use crate::normalize_identifier;
 use crate::optimizer::optimize;
 use crate::optimizer::OptimizerContext;
+use crate::planner::binder::resolve_prepared_statement;
 use crate::planner::query_executor::QueryExecutor;
 use crate::plans::Plan;
+use crate::BindContext;
 use crate::Binder;
+use crate::ColumnBindingBuilder;
 use crate::CountSetOps;
 use crate::Metadata;
 use crate::NameResolutionContext;
+use crate::ParameterBindings;
+use crate::ParameterSetter;
+use crate::PlaceholderCollector;
+use crate::PlaceholderRewriter;
+use crate::SubstraitBinder;
+use crate::TypeChecker;
 use crate::VariableNormalizer;
+use crate::Visibility;
 
 const PROBE_INSERT_INITIAL_TOKENS: usize = 128;
 const PROBE_INSERT_MAX_TOKENS: usize = 128 * 8;
@@ -223,6 +242,19 @@ impl Planner {
     #[fastrace::trace]
     pub async fn plan_stmt(&mut self, stmt: &Statement) -> Result<Plan> {
         let start = Instant::now();
+        // The prepared statement is planned as the other statements after the parameters
+        // are substituted, if its bound plan can not be reused.
+        let prepared;
+        let stmt = match stmt {
+            Statement::ExecutePrepared(execute) => {
+                if let Some(plan) = self.plan_prepared_statement(execute).await? {
+                    return Ok(plan);
+                }
+                prepared = self.bind_prepared_statement(execute)?;
+                &prepared
+            }
+            _ => stmt,
+        };
         let query_kind = get_query_kind(stmt);
         let settings = self.ctx.get_settings();
         // Step 3: Bind AST with catalog, and generate a pure logical SExpr
@@ -282,6 +314,38 @@ impl Planner {
         Ok(optimized_plan)
     }
 
+    /// Plan a serialized substrait plan, which only supports query relations.
+    #[async_backtrace::framed]
+    #[fastrace::trace]
+    pub async fn plan_substrait(&mut self, plan: &[u8]) -> Result<Plan> {
+        let start = Instant::now();
+        let settings = self.ctx.get_settings();
+        let name_resolution_ctx = NameResolutionContext::try_from(settings.as_ref())?;
+        let metadata = Arc::new(RwLock::new(Metadata::default()));
+        let binder = Binder::new(
+            self.ctx.clone(),
+            CatalogManager::instance(),
+            name_resolution_ctx,
+            metadata.clone(),
+        )
+        .with_subquery_executor(self.query_executor.clone());
+        let plan = SubstraitBinder::new(binder, metadata.clone()).bind(plan)?;
+
+        let opt_ctx = OptimizerContext::new(self.ctx.clone(), metadata.clone())
+            .with_enable_distributed_optimization(!self.ctx.get_cluster().is_empty())
+            .with_enable_join_reorder(unsafe { !settings.get_disable_join_reorder()? })
+            .with_enable_dphyp(settings.get_enable_dphyp()?)
+            .with_max_push_down_limit(settings.get_max_push_down_limit()?)
+            .with_sample_executor(self.query_executor.clone());
+        let optimized_plan = optimize(opt_ctx, plan).await?;
+
+        info!(
+            "logical plan from substrait built, time used: {:?}",
+            start.elapsed()
+        );
+        Ok(optimized_plan)
+    }
+
     fn add_max_rows_limit(&self, statement: &mut Statement) {
         let max_rows = self.ctx.get_settings().get_max_result_rows().unwrap();
         if max_rows == 0 {
@@ -298,6 +362,211 @@ impl Planner {
         }
     }
 
+    /// Plan `EXECUTE` with the plan bound once for the prepared statement and the types of the
+    /// parameters. The placeholders are bound as typed parameter columns, which are replaced by
+    /// the values of every execution before the plan is optimized.
+    ///
+    /// Returns `None` if the bound plan can not be reused, e.g. the placeholder is a `LIMIT`.
+    async fn plan_prepared_statement(
+        &mut self,
+        execute: &ExecutePreparedStmt,
+    ) -> Result<Option<Plan>> {
+        let start = Instant::now();
+        let settings = self.ctx.get_settings();
+        if !settings.get_enable_planner_cache()? {
+            return Ok(None);
+        }
+        // Check the parameters and get the statement to log.
+        let substituted = self.bind_prepared_statement(execute)?;
+        if !matches!(substituted, Statement::Query(_)) {
+            return Ok(None);
+        }
+
+        let name_resolution_ctx = NameResolutionContext::try_from(settings.as_ref())?;
+        let name = normalize_identifier(&execute.name, &name_resolution_ctx).name;
+        let Some(mut stmt) = self.ctx.get_prepared_statement(&name) else {
+            return Ok(None);
+        };
+        let placeholders = PlaceholderCollector::collect(&stmt);
+        if placeholders.num_params() == 0 {
+            return Ok(None);
+        }
+        self.add_max_rows_limit(&mut stmt);
+        let Some(params) = self.evaluate_parameters(&name_resolution_ctx, &execute.params)? else {
+            return Ok(None);
+        };
+
+        let param_types = params.iter().map(|(_, data_type)| data_type).join(", ");
+        let cache_key = Self::planner_cache_key(&format!("{stmt} USING ({param_types})"));
+        let (enable_planner_cache, cached) =
+            self.get_cache(name_resolution_ctx.clone(), &cache_key, &stmt);
+        if !enable_planner_cache {
+            return Ok(None);
+        }
+
+        let query_kind = get_query_kind(&substituted);
+        self.ctx
+            .attach_query_str(query_kind, substituted.to_mask_sql());
+        let plan = match cached {
+            Some(plan_item) => {
+                info!("bound plan of prepared statement {name} from cache");
+                plan_item.plan
+            }
+            None => {
+                let metadata = Arc::new(RwLock::new(Metadata::default()));
+                let columns = params
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, data_type))| {
+                        let name = format!("${}", i + 1);
+                        let index = metadata.write().add_derived_column(
+                            name.clone(),
+                            data_type.clone(),
+                            None,
+                        );
+                        ColumnBindingBuilder::new(
+                            name,
+                            index,
+                            Box::new(data_type.clone()),
+                            Visibility::Visible,
+                        )
+                        .build()
+                    })
+                    .collect();
+                metadata
+                    .write()
+                    .set_parameters(ParameterBindings::new(placeholders, columns));
+
+                let binder = Binder::new(
+                    self.ctx.clone(),
+                    CatalogManager::instance(),
+                    name_resolution_ctx,
+                    metadata,
+                )
+                .with_subquery_executor(self.query_executor.clone());
+                // The placeholders that must be constants at bind time are not parameter
+                // columns, the statement is planned with the parameters substituted.
+                let Ok(plan) = binder.bind(&stmt).await else {
+                    return Ok(None);
+                };
+                self.ctx
+                    .attach_query_str(query_kind, substituted.to_mask_sql());
+                self.set_cache(cache_key, plan.clone());
+                plan
+            }
+        };
+
+        let Plan::Query {
+            s_expr,
+            metadata,
+            bind_context,
+            rewrite_kind,
+            formatted_ast,
+            ignore_result,
+        } = plan
+        else {
+            return Ok(None);
+        };
+        // The bound plan is shared by the executions, the optimizer works on a copy of it.
+        let metadata = Arc::new(RwLock::new(metadata.read().clone()));
+        let values = match metadata.read().parameters() {
+            Some(parameters) => parameters
+                .columns()
+                .iter()
+                .zip(params)
+                .map(|(column, (value, _))| (column.index, value))
+                .collect(),
+            None => return Ok(None),
+        };
+        let Some(s_expr) = ParameterSetter::new(values).set_parameters(&s_expr)? else {
+            return Ok(None);
+        };
+        let plan = Plan::Query {
+            s_expr: Box::new(s_expr),
+            metadata: metadata.clone(),
+            bind_context,
+            rewrite_kind,
+            // The query result cache is keyed by the statement with the values.
+            formatted_ast: formatted_ast.map(|_| substituted.to_string()),
+            ignore_result,
+        };
+
+        let opt_ctx = OptimizerContext::new(self.ctx.clone(), metadata)
+            .with_enable_distributed_optimization(!self.ctx.get_cluster().is_empty())
+            .with_enable_join_reorder(unsafe { !settings.get_disable_join_reorder()? })
+            .with_enable_dphyp(settings.get_enable_dphyp()?)
+            .with_max_push_down_limit(settings.get_max_push_down_limit()?)
+            .with_sample_executor(self.query_executor.clone());
+        let optimized_plan = optimize(opt_ctx, plan).await?;
+
+        info!(
+            "logical plan of prepared statement built, time used: {:?}",
+            start.elapsed()
+        );
+        Ok(Some(optimized_plan))
+    }
+
+    /// Evaluate the parameters of `EXECUTE`, returns `None` if some of them are not folded
+    /// into constants.
+    fn evaluate_parameters(
+        &self,
+        name_resolution_ctx: &NameResolutionContext,
+        params: &[Expr],
+    ) -> Result<Option<Vec<(Scalar, DataType)>>> {
+        let mut bind_context = BindContext::new();
+        let metadata = Arc::new(RwLock::new(Metadata::default()));
+        let mut type_checker = TypeChecker::try_create(
+            &mut bind_context,
+            self.ctx.clone(),
+            name_resolution_ctx,
+            metadata,
+            &[],
+            false,
+        )?;
+        let func_ctx = self.ctx.get_function_context()?;
+
+        let mut values = Vec::with_capacity(params.len());
+        for param in params {
+            let (scalar, _) = *type_checker.resolve(param)?;
+            let (expr, _) = ConstantFolder::fold(&scalar.as_expr()?, &func_ctx, &BUILTIN_FUNCTIONS);
+            match expr {
+                EExpr::Constant {
+                    scalar, data_type, ..
+                } => values.push((scalar, data_type)),
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(values))
+    }
+
+    /// Replace the placeholders of the prepared statement with the parameters of `EXECUTE`.
+    pub fn bind_prepared_statement(&self, execute: &ExecutePreparedStmt) -> Result<Statement> {
+        let name_resolution_ctx =
+            NameResolutionContext::try_from(self.ctx.get_settings().as_ref())?;
+        let mut stmt =
+            resolve_prepared_statement(self.ctx.as_ref(), &name_resolution_ctx, execute)?;
+        self.add_max_rows_limit(&mut stmt);
+        Ok(stmt)
+    }
+
+    /// Parse the statement prepared by the client protocols, its `?` placeholders are bound
+    /// to the parameters by `bind_placeholders` on every execution.
+    pub fn parse_prepared_sql(&self, sql: &str) -> Result<Statement> {
+        let sql_dialect = self.ctx.get_settings().get_sql_dialect()?;
+        let tokens = tokenize_sql(sql)?;
+        let (mut stmt, _) = parse_sql(&tokens, sql_dialect)?;
+        self.replace_stmt(&mut stmt)?;
+        Ok(stmt)
+    }
+
+    /// Replace the placeholders of the prepared statement with the parameters sent by the client.
+    pub fn bind_placeholders(&self, stmt: &Statement, params: &[Expr]) -> Result<Statement> {
+        let mut stmt = stmt.clone();
+        PlaceholderRewriter::rewrite(&mut stmt, params)?;
+        self.add_max_rows_limit(&mut stmt);
+        Ok(stmt)
+    }
+
     fn replace_stmt(&self, stmt: &mut Statement) -> Result<()> {
         let name_resolution_ctx =
             NameResolutionContext::try_from(self.ctx.get_settings().as_ref())?;
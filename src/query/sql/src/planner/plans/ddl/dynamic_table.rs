@@ -16,6 +16,10 @@ use databend_common_ast::ast::InitializeMode;
 use databend_common_ast::ast::RefreshMode;
 use databend_common_ast::ast::TargetLag;
 use databend_common_ast::ast::WarehouseOptions;
+use databend_common_expression::types::DataType;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::DataSchemaRefExt;
 use databend_common_expression::TableSchemaRef;
 use databend_common_meta_app::schema::CreateOption;
 use databend_common_meta_app::tenant::Tenant;
@@ -41,3 +45,17 @@ pub struct CreateDynamicTablePlan {
     pub refresh_mode: RefreshMode,
     pub initialize: InitializeMode,
 }
+
+#[derive(Clone, Debug)]
+pub struct RefreshDynamicTablePlan {
+    pub tenant: Tenant,
+    pub catalog: String,
+    pub database: String,
+    pub table: String,
+}
+
+impl RefreshDynamicTablePlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        DataSchemaRefExt::create(vec![DataField::new("refresh_action", DataType::String)])
+    }
+}
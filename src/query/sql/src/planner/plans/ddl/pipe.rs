@@ -0,0 +1,71 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_ast::ast::AlterPipeOptions;
+use databend_common_expression::types::DataType;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::DataSchemaRefExt;
+use databend_common_meta_app::schema::CreateOption;
+use databend_common_meta_app::tenant::Tenant;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatePipePlan {
+    pub create_option: CreateOption,
+    pub tenant: Tenant,
+    pub name: String,
+    pub auto_ingest: bool,
+    pub comment: String,
+    /// The `COPY INTO <table>` statement in SQL text, with the target table fully qualified.
+    pub copy_stmt: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlterPipePlan {
+    pub if_exists: bool,
+    pub tenant: Tenant,
+    pub name: String,
+    pub options: AlterPipeOptions,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropPipePlan {
+    pub if_exists: bool,
+    pub tenant: Tenant,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DescPipePlan {
+    pub tenant: Tenant,
+    pub name: String,
+}
+
+impl DescPipePlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        DataSchemaRefExt::create(vec![
+            DataField::new("name", DataType::String),
+            DataField::new("auto_ingest", DataType::Boolean),
+            DataField::new("execution_paused", DataType::Boolean),
+            DataField::new("definition", DataType::String),
+            DataField::new("comment", DataType::String),
+            DataField::new("owner", DataType::String),
+            DataField::new("created_on", DataType::Timestamp),
+            DataField::new(
+                "updated_on",
+                DataType::Nullable(Box::new(DataType::Timestamp)),
+            ),
+        ])
+    }
+}
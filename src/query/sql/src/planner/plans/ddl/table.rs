@@ -34,6 +34,7 @@ use databend_common_meta_app::schema::UndropTableReq;
 use databend_common_meta_app::storage::StorageParams;
 use databend_common_meta_app::tenant::Tenant;
 use databend_common_pipeline_core::LockGuard;
+use databend_storages_common_table_meta::table::TableConstraint;
 
 use crate::plans::Plan;
 
@@ -492,3 +493,32 @@ impl DropTableClusterKeyPlan {
         Arc::new(DataSchema::empty())
     }
 }
+
+/// Add a constraint to a table.
+#[derive(Clone, Debug)]
+pub struct AddTableConstraintPlan {
+    pub catalog: String,
+    pub database: String,
+    pub table: String,
+    pub constraint: TableConstraint,
+}
+
+impl AddTableConstraintPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        Arc::new(DataSchema::empty())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DropTableConstraintPlan {
+    pub catalog: String,
+    pub database: String,
+    pub table: String,
+    pub name: String,
+}
+
+impl DropTableConstraintPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        Arc::new(DataSchema::empty())
+    }
+}
@@ -57,6 +57,8 @@ pub struct Insert {
     pub table: String,
     pub schema: TableSchemaRef,
     pub overwrite: bool,
+    // the constraints whose conflicting rows are skipped, by ON CONFLICT DO NOTHING
+    pub ignore_conflicts: Vec<String>,
     pub source: InsertInputSource,
     // if a table with fixed table id, and version should be used,
     // it should be provided as some `table_info`.
@@ -95,6 +97,7 @@ impl Insert {
             table,
             schema,
             overwrite,
+            ignore_conflicts,
             // table_info only used create table as select.
             table_info: _,
             source,
@@ -108,11 +111,17 @@ impl Insert {
             .collect::<Vec<_>>()
             .join(",");
 
-        let children = vec![
+        let mut children = vec![
             FormatTreeNode::new(format!("table: {table_name}")),
             FormatTreeNode::new(format!("inserted columns: [{inserted_columns}]")),
             FormatTreeNode::new(format!("overwrite: {overwrite}")),
         ];
+        if !ignore_conflicts.is_empty() {
+            children.push(FormatTreeNode::new(format!(
+                "ignore conflicts: [{}]",
+                ignore_conflicts.join(", ")
+            )));
+        }
 
         let formatted_plan = format_insert_source("InsertPlan", source, verbose, children)?;
 
@@ -224,6 +233,7 @@ impl std::fmt::Debug for Insert {
             .field("table", &self.table)
             .field("schema", &self.schema)
             .field("overwrite", &self.overwrite)
+            .field("ignore_conflicts", &self.ignore_conflicts)
             .finish()
     }
 }
@@ -64,6 +64,11 @@ pub enum JoinType {
     /// Single Join is a special kind of join that is used to process correlated scalar subquery.
     LeftSingle,
     RightSingle,
+    /// Asof Join matches each left row with the closest right row satisfying the match condition,
+    /// the match condition is the only non-equi condition of the join.
+    Asof,
+    /// Left Asof Join also outputs the left rows without a matched right row.
+    LeftAsof,
 }
 
 impl JoinType {
@@ -97,6 +102,10 @@ impl JoinType {
     pub fn is_mark_join(&self) -> bool {
         matches!(self, JoinType::LeftMark | JoinType::RightMark)
     }
+
+    pub fn is_asof_join(&self) -> bool {
+        matches!(self, JoinType::Asof | JoinType::LeftAsof)
+    }
 }
 
 impl Display for JoinType {
@@ -141,6 +150,12 @@ impl Display for JoinType {
             JoinType::RightSingle => {
                 write!(f, "RIGHT SINGLE")
             }
+            JoinType::Asof => {
+                write!(f, "ASOF")
+            }
+            JoinType::LeftAsof => {
+                write!(f, "LEFT ASOF")
+            }
         }
     }
 }
@@ -445,6 +460,8 @@ impl Join {
             JoinType::RightSemi => f64::min(right_cardinality, inner_join_cardinality),
             JoinType::LeftSingle | JoinType::RightMark | JoinType::LeftAnti => left_cardinality,
             JoinType::RightSingle | JoinType::LeftMark | JoinType::RightAnti => right_cardinality,
+            JoinType::Asof => f64::min(left_cardinality, inner_join_cardinality),
+            JoinType::LeftAsof => left_cardinality,
         };
         // Derive column statistics
         let column_stats = if cardinality == 0.0 {
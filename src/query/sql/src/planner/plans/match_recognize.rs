@@ -0,0 +1,227 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::optimizer::ColumnSet;
+use crate::optimizer::Distribution;
+use crate::optimizer::RelExpr;
+use crate::optimizer::RelationalProperty;
+use crate::optimizer::RequiredProperty;
+use crate::optimizer::StatInfo;
+use crate::plans::AggregateFunction;
+use crate::plans::Operator;
+use crate::plans::RelOp;
+use crate::plans::ScalarExpr;
+use crate::plans::ScalarItem;
+use crate::IndexType;
+
+/// `MatchRecognize` finds the rows matching a row pattern in each partition of its input.
+/// The input must be sorted by the partition keys and the `ORDER BY` keys, which is done
+/// by a window partition `Sort` below it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchRecognize {
+    pub partition_by: Vec<ScalarItem>,
+    /// Columns shifted by `PREV` and `NEXT` in the `DEFINE` conditions.
+    pub navigations: Vec<MatchNavigation>,
+    /// Pattern variables in the order of their first occurrence in the pattern.
+    pub variables: Vec<MatchVariable>,
+    /// The row pattern compiled to instructions of a backtracking matcher.
+    pub pattern: Vec<MatchInstruction>,
+    pub pattern_display: String,
+    pub measures: Vec<MatchMeasure>,
+    pub rows_per_match: MatchRowsPerMatch,
+    pub after_match_skip: MatchSkip,
+}
+
+impl MatchRecognize {
+    pub fn used_columns(&self) -> ColumnSet {
+        let mut used_columns = ColumnSet::new();
+        for item in self.partition_by.iter() {
+            used_columns.insert(item.index);
+        }
+        for navigation in self.navigations.iter() {
+            used_columns.insert(navigation.index);
+            used_columns.insert(navigation.arg);
+        }
+        for variable in self.variables.iter() {
+            if let Some(condition) = &variable.condition {
+                used_columns.extend(condition.used_columns());
+            }
+        }
+        for measure in self.measures.iter() {
+            used_columns.insert(measure.index);
+            used_columns.extend(measure.func.used_columns());
+        }
+        used_columns
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchNavigation {
+    pub index: IndexType,
+    pub arg: IndexType,
+    /// Negative for `PREV`, positive for `NEXT`.
+    pub offset: i64,
+    pub data_type: Box<DataType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchVariable {
+    pub name: String,
+    /// A variable without `DEFINE` condition matches every row.
+    pub condition: Option<ScalarExpr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchMeasure {
+    pub index: IndexType,
+    pub func: MatchFunction,
+    /// Only the rows mapped to this variable are considered if it's specified.
+    pub variable: Option<usize>,
+    pub data_type: Box<DataType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MatchFunction {
+    First(IndexType),
+    Last(IndexType),
+    Aggregate(AggregateFunction),
+    MatchNumber,
+    Classifier,
+}
+
+impl MatchFunction {
+    pub fn used_columns(&self) -> ColumnSet {
+        match self {
+            MatchFunction::First(arg) | MatchFunction::Last(arg) => ColumnSet::from([*arg]),
+            MatchFunction::Aggregate(agg) => {
+                agg.exprs().flat_map(|expr| expr.used_columns()).collect()
+            }
+            MatchFunction::MatchNumber | MatchFunction::Classifier => ColumnSet::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchRowsPerMatch {
+    OneRow,
+    AllRows,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchSkip {
+    PastLastRow,
+    ToNextRow,
+    /// Skip to the first row mapped to the variable.
+    ToFirst(usize),
+    /// Skip to the last row mapped to the variable.
+    ToLast(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchInstruction {
+    /// Consume the current row if it satisfies the condition of the variable.
+    Variable(usize),
+    /// Try the first target, and backtrack to the second one on failure.
+    Split(usize, usize),
+    Jump(usize),
+    /// `^`, only matches at the start of the partition.
+    PartitionStart,
+    /// `$`, only matches at the end of the partition.
+    PartitionEnd,
+    Match,
+}
+
+impl Operator for MatchRecognize {
+    fn rel_op(&self) -> RelOp {
+        RelOp::MatchRecognize
+    }
+
+    fn compute_required_prop_child(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        _rel_expr: &RelExpr,
+        _child_index: usize,
+        required: &RequiredProperty,
+    ) -> Result<RequiredProperty> {
+        let mut required = required.clone();
+        if self.partition_by.is_empty() {
+            required.distribution = Distribution::Serial;
+        }
+        Ok(required)
+    }
+
+    fn compute_required_prop_children(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        _rel_expr: &RelExpr,
+        required: &RequiredProperty,
+    ) -> Result<Vec<Vec<RequiredProperty>>> {
+        let mut required = required.clone();
+        if self.partition_by.is_empty() {
+            required.distribution = Distribution::Serial;
+        }
+        Ok(vec![vec![required]])
+    }
+
+    fn derive_relational_prop(&self, rel_expr: &RelExpr) -> Result<Arc<RelationalProperty>> {
+        let input_prop = rel_expr.derive_relational_prop_child(0)?;
+
+        // Derive output columns
+        let mut output_columns = match self.rows_per_match {
+            MatchRowsPerMatch::OneRow => self.partition_by.iter().map(|item| item.index).collect(),
+            MatchRowsPerMatch::AllRows => input_prop.output_columns.clone(),
+        };
+        output_columns.extend(self.measures.iter().map(|measure| measure.index));
+
+        // Derive outer columns
+        let outer_columns = input_prop
+            .outer_columns
+            .difference(&output_columns)
+            .cloned()
+            .collect();
+
+        // Derive used columns
+        let mut used_columns = self.used_columns();
+        used_columns.extend(input_prop.used_columns.clone());
+
+        // Derive orderings
+        let (orderings, partition_orderings) = match self.rows_per_match {
+            MatchRowsPerMatch::OneRow => (vec![], None),
+            MatchRowsPerMatch::AllRows => (
+                input_prop.orderings.clone(),
+                input_prop.partition_orderings.clone(),
+            ),
+        };
+
+        Ok(Arc::new(RelationalProperty {
+            output_columns,
+            outer_columns,
+            used_columns,
+            orderings,
+            partition_orderings,
+        }))
+    }
+
+    fn derive_stats(&self, rel_expr: &RelExpr) -> Result<Arc<StatInfo>> {
+        rel_expr.derive_cardinality_child(0)
+    }
+}
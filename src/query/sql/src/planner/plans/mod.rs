@@ -31,11 +31,13 @@ mod insert_multi_table;
 mod join;
 mod kill;
 mod limit;
+mod match_recognize;
 mod mutation;
 mod mutation_source;
 mod operator;
 mod optimize;
 mod plan;
+mod prepare;
 mod presign;
 mod project_set;
 mod r_cte_scan;
@@ -72,6 +74,7 @@ pub use insert_multi_table::*;
 pub use join::*;
 pub use kill::KillPlan;
 pub use limit::*;
+pub use match_recognize::*;
 pub use mutation::MatchedEvaluator;
 pub use mutation::Mutation;
 pub use mutation::UnmatchedEvaluator;
@@ -82,6 +85,7 @@ pub use mutation_source::MutationSource;
 pub use operator::*;
 pub use optimize::*;
 pub use plan::*;
+pub use prepare::*;
 pub use presign::*;
 pub use project_set::*;
 pub use r_cte_scan::*;
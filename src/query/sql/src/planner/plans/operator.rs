@@ -37,6 +37,7 @@ use crate::plans::ExpressionScan;
 use crate::plans::Filter;
 use crate::plans::Join;
 use crate::plans::Limit;
+use crate::plans::MatchRecognize;
 use crate::plans::Mutation;
 use crate::plans::OptimizeCompactBlock;
 use crate::plans::ProjectSet;
@@ -117,6 +118,7 @@ pub enum RelOp {
     MergeInto,
     CompactBlock,
     MutationSource,
+    MatchRecognize,
 
     // Pattern
     Pattern,
@@ -153,6 +155,7 @@ pub enum RelOperator {
     Mutation(Mutation),
     CompactBlock(OptimizeCompactBlock),
     MutationSource(MutationSource),
+    MatchRecognize(MatchRecognize),
 }
 
 impl Operator for RelOperator {
@@ -179,6 +182,7 @@ impl Operator for RelOperator {
             RelOperator::Mutation(rel_op) => rel_op.rel_op(),
             RelOperator::CompactBlock(rel_op) => rel_op.rel_op(),
             RelOperator::MutationSource(rel_op) => rel_op.rel_op(),
+            RelOperator::MatchRecognize(rel_op) => rel_op.rel_op(),
         }
     }
 
@@ -205,6 +209,7 @@ impl Operator for RelOperator {
             RelOperator::Mutation(rel_op) => rel_op.arity(),
             RelOperator::CompactBlock(rel_op) => rel_op.arity(),
             RelOperator::MutationSource(rel_op) => rel_op.arity(),
+            RelOperator::MatchRecognize(rel_op) => rel_op.arity(),
         }
     }
 
@@ -231,6 +236,7 @@ impl Operator for RelOperator {
             RelOperator::Mutation(rel_op) => rel_op.derive_relational_prop(rel_expr),
             RelOperator::CompactBlock(rel_op) => rel_op.derive_relational_prop(rel_expr),
             RelOperator::MutationSource(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::MatchRecognize(rel_op) => rel_op.derive_relational_prop(rel_expr),
         }
     }
 
@@ -257,6 +263,7 @@ impl Operator for RelOperator {
             RelOperator::Mutation(rel_op) => rel_op.derive_physical_prop(rel_expr),
             RelOperator::CompactBlock(rel_op) => rel_op.derive_physical_prop(rel_expr),
             RelOperator::MutationSource(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::MatchRecognize(rel_op) => rel_op.derive_physical_prop(rel_expr),
         }
     }
 
@@ -283,6 +290,7 @@ impl Operator for RelOperator {
             RelOperator::Mutation(rel_op) => rel_op.derive_stats(rel_expr),
             RelOperator::CompactBlock(rel_op) => rel_op.derive_stats(rel_expr),
             RelOperator::MutationSource(rel_op) => rel_op.derive_stats(rel_expr),
+            RelOperator::MatchRecognize(rel_op) => rel_op.derive_stats(rel_expr),
         }
     }
 
@@ -357,6 +365,9 @@ impl Operator for RelOperator {
             RelOperator::MutationSource(rel_op) => {
                 rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
             }
+            RelOperator::MatchRecognize(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
         }
     }
 
@@ -430,6 +441,9 @@ impl Operator for RelOperator {
             RelOperator::MutationSource(rel_op) => {
                 rel_op.compute_required_prop_children(ctx, rel_expr, required)
             }
+            RelOperator::MatchRecognize(rel_op) => {
+                rel_op.compute_required_prop_children(ctx, rel_expr, required)
+            }
         }
     }
 }
@@ -818,3 +832,23 @@ impl TryFrom<RelOperator> for MutationSource {
         }
     }
 }
+
+impl From<MatchRecognize> for RelOperator {
+    fn from(v: MatchRecognize) -> Self {
+        Self::MatchRecognize(v)
+    }
+}
+
+impl TryFrom<RelOperator> for MatchRecognize {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::MatchRecognize(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(format!(
+                "Cannot downcast {:?} to MatchRecognize",
+                value.rel_op()
+            )))
+        }
+    }
+}
@@ -33,10 +33,12 @@ use crate::binder::ExplainConfig;
 use crate::optimizer::SExpr;
 use crate::plans::copy_into_location::CopyIntoLocationPlan;
 use crate::plans::AddTableColumnPlan;
+use crate::plans::AddTableConstraintPlan;
 use crate::plans::AddWarehouseClusterPlan;
 use crate::plans::AlterNetworkPolicyPlan;
 use crate::plans::AlterNotificationPlan;
 use crate::plans::AlterPasswordPolicyPlan;
+use crate::plans::AlterPipePlan;
 use crate::plans::AlterTableClusterKeyPlan;
 use crate::plans::AlterTaskPlan;
 use crate::plans::AlterUDFPlan;
@@ -58,6 +60,7 @@ use crate::plans::CreateIndexPlan;
 use crate::plans::CreateNetworkPolicyPlan;
 use crate::plans::CreateNotificationPlan;
 use crate::plans::CreatePasswordPolicyPlan;
+use crate::plans::CreatePipePlan;
 use crate::plans::CreateProcedurePlan;
 use crate::plans::CreateRolePlan;
 use crate::plans::CreateSequencePlan;
@@ -71,11 +74,13 @@ use crate::plans::CreateUserPlan;
 use crate::plans::CreateViewPlan;
 use crate::plans::CreateVirtualColumnPlan;
 use crate::plans::CreateWarehousePlan;
+use crate::plans::DeallocatePlan;
 use crate::plans::DescConnectionPlan;
 use crate::plans::DescDatamaskPolicyPlan;
 use crate::plans::DescNetworkPolicyPlan;
 use crate::plans::DescNotificationPlan;
 use crate::plans::DescPasswordPolicyPlan;
+use crate::plans::DescPipePlan;
 use crate::plans::DescProcedurePlan;
 use crate::plans::DescUserPlan;
 use crate::plans::DescribeTablePlan;
@@ -90,6 +95,7 @@ use crate::plans::DropIndexPlan;
 use crate::plans::DropNetworkPolicyPlan;
 use crate::plans::DropNotificationPlan;
 use crate::plans::DropPasswordPolicyPlan;
+use crate::plans::DropPipePlan;
 use crate::plans::DropProcedurePlan;
 use crate::plans::DropRolePlan;
 use crate::plans::DropSequencePlan;
@@ -97,6 +103,7 @@ use crate::plans::DropStagePlan;
 use crate::plans::DropStreamPlan;
 use crate::plans::DropTableClusterKeyPlan;
 use crate::plans::DropTableColumnPlan;
+use crate::plans::DropTableConstraintPlan;
 use crate::plans::DropTableIndexPlan;
 use crate::plans::DropTablePlan;
 use crate::plans::DropTaskPlan;
@@ -120,8 +127,10 @@ use crate::plans::ModifyTableColumnPlan;
 use crate::plans::ModifyTableCommentPlan;
 use crate::plans::OptimizeCompactSegmentPlan;
 use crate::plans::OptimizePurgePlan;
+use crate::plans::PreparePlan;
 use crate::plans::PresignPlan;
 use crate::plans::ReclusterPlan;
+use crate::plans::RefreshDynamicTablePlan;
 use crate::plans::RefreshIndexPlan;
 use crate::plans::RefreshTableIndexPlan;
 use crate::plans::RefreshVirtualColumnPlan;
@@ -247,6 +256,8 @@ pub enum Plan {
     ModifyTableColumn(Box<ModifyTableColumnPlan>),
     AlterTableClusterKey(Box<AlterTableClusterKeyPlan>),
     DropTableClusterKey(Box<DropTableClusterKeyPlan>),
+    AddTableConstraint(Box<AddTableConstraintPlan>),
+    DropTableConstraint(Box<DropTableConstraintPlan>),
     ReclusterTable(Box<ReclusterPlan>),
     RevertTable(Box<RevertTablePlan>),
     TruncateTable(Box<TruncateTablePlan>),
@@ -340,6 +351,12 @@ pub enum Plan {
     DropConnection(Box<DropConnectionPlan>),
     ShowConnections(Box<ShowConnectionsPlan>),
 
+    // Pipe
+    CreatePipe(Box<CreatePipePlan>),
+    AlterPipe(Box<AlterPipePlan>),
+    DropPipe(Box<DropPipePlan>),
+    DescPipe(Box<DescPipePlan>),
+
     // Presign
     Presign(Box<PresignPlan>),
 
@@ -350,6 +367,10 @@ pub enum Plan {
     SetPriority(Box<SetPriorityPlan>),
     System(Box<SystemPlan>),
 
+    // Prepared statements
+    Prepare(Box<PreparePlan>),
+    Deallocate(Box<DeallocatePlan>),
+
     // Data mask
     CreateDatamaskPolicy(Box<CreateDatamaskPolicyPlan>),
     DropDatamaskPolicy(Box<DropDatamaskPolicyPlan>),
@@ -377,11 +398,21 @@ pub enum Plan {
     ExecuteTask(Box<ExecuteTaskPlan>),
 
     CreateDynamicTable(Box<CreateDynamicTablePlan>),
+    RefreshDynamicTable(Box<RefreshDynamicTablePlan>),
 
     // Txn
     Begin,
     Commit,
     Abort,
+    Savepoint {
+        name: String,
+    },
+    RollbackToSavepoint {
+        name: String,
+    },
+    ReleaseSavepoint {
+        name: String,
+    },
 
     // Notifications
     CreateNotification(Box<CreateNotificationPlan>),
@@ -517,9 +548,11 @@ impl Plan {
             Plan::DescribeTask(plan) => plan.schema(),
             Plan::ShowTasks(plan) => plan.schema(),
             Plan::ExecuteTask(plan) => plan.schema(),
+            Plan::RefreshDynamicTable(plan) => plan.schema(),
             Plan::DescNotification(plan) => plan.schema(),
             Plan::DescConnection(plan) => plan.schema(),
             Plan::ShowConnections(plan) => plan.schema(),
+            Plan::DescPipe(plan) => plan.schema(),
             Plan::ExecuteImmediate(plan) => plan.schema(),
             Plan::CallProcedure(plan) => plan.schema(),
             Plan::InsertMultiTable(plan) => plan.schema(),
@@ -0,0 +1,27 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_ast::ast::Statement;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparePlan {
+    pub name: String,
+    pub statement: Statement,
+}
+
+/// Deallocate all the prepared statements of the session if the name is `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeallocatePlan {
+    pub name: Option<String>,
+}
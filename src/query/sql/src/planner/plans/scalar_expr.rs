@@ -930,6 +930,18 @@ pub struct UDFScriptCode {
     pub language: UDFLanguage,
     pub runtime_version: String,
     pub code: Arc<Box<[u8]>>,
+    pub imports: Vec<UDFScriptImport>,
+    pub packages: Vec<String>,
+    /// Column names of a table-valued UDF, the script returns the rows as
+    /// objects keyed by these names. Empty for scalar UDFs.
+    pub table_columns: Vec<String>,
+}
+
+/// A module loaded from a stage file that the script can `import` by name.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UDFScriptImport {
+    pub module: String,
+    pub code: Arc<Box<[u8]>>,
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, serde::Serialize, serde::Deserialize, EnumAsInner)]
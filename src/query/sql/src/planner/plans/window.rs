@@ -182,6 +182,7 @@ pub struct WindowFuncFrame {
     pub units: WindowFuncFrameUnits,
     pub start_bound: WindowFuncFrameBound,
     pub end_bound: WindowFuncFrameBound,
+    pub exclusion: WindowFuncFrameExclusion,
 }
 
 impl Display for WindowFuncFrame {
@@ -190,7 +191,11 @@ impl Display for WindowFuncFrame {
             f,
             "{:?}: {:?} ~ {:?}",
             self.units, self.start_bound, self.end_bound
-        )
+        )?;
+        if self.exclusion != WindowFuncFrameExclusion::NoOthers {
+            write!(f, " exclude {:?}", self.exclusion)?;
+        }
+        Ok(())
     }
 }
 
@@ -199,6 +204,21 @@ pub enum WindowFuncFrameUnits {
     #[default]
     Rows,
     Range,
+    /// The offsets are counted in peer groups.
+    Groups,
+}
+
+/// The rows removed from the frame of every row.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowFuncFrameExclusion {
+    #[default]
+    NoOthers,
+    /// The current row.
+    CurrentRow,
+    /// The current row and its peers.
+    Group,
+    /// The peers of the current row, but not the current row itself.
+    Ties,
 }
 
 #[derive(Default, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
@@ -20,6 +20,7 @@ mod distinct_to_groupby;
 mod grouping_check;
 mod lowering;
 mod name_resolution;
+mod placeholder_rewriter;
 mod type_check;
 mod udf_rewriter;
 mod view_rewriter;
@@ -41,6 +42,10 @@ pub use name_resolution::IdentifierNormalizer;
 pub use name_resolution::NameResolutionContext;
 pub use name_resolution::NameResolutionSuggest;
 pub use name_resolution::VariableNormalizer;
+pub use placeholder_rewriter::ParameterBindings;
+pub use placeholder_rewriter::ParameterSetter;
+pub use placeholder_rewriter::PlaceholderCollector;
+pub use placeholder_rewriter::PlaceholderRewriter;
 pub use type_check::resolve_type_name;
 pub use type_check::resolve_type_name_by_str;
 pub use type_check::resolve_type_name_udf;
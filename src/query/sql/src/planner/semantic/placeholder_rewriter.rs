@@ -0,0 +1,347 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use databend_common_ast::ast::ColumnID;
+use databend_common_ast::ast::ColumnRef;
+use databend_common_ast::ast::Expr;
+use databend_common_ast::ast::Statement;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::Scalar;
+use derive_visitor::Drive;
+use derive_visitor::DriveMut;
+use derive_visitor::Visitor;
+use derive_visitor::VisitorMut;
+
+use crate::optimizer::RelExpr;
+use crate::optimizer::SExpr;
+use crate::plans::walk_expr_mut;
+use crate::plans::ConstantExpr;
+use crate::plans::RelOperator;
+use crate::plans::ScalarExpr;
+use crate::plans::ScalarItem;
+use crate::plans::SubqueryExpr;
+use crate::plans::VisitorMut as ScalarVisitorMut;
+use crate::plans::WindowFuncType;
+use crate::ColumnBinding;
+use crate::IndexType;
+
+/// Collect the placeholders of a prepared statement, which are `?` or `$<n>`.
+///
+/// The `?` are bound to the parameters in the order they appear. If there is no `?`, the
+/// `$<n>` is bound to the n-th parameter, otherwise it is the column position of the
+/// staged files.
+#[derive(Debug, Clone, Default, Visitor)]
+#[visitor(Expr(enter))]
+pub struct PlaceholderCollector {
+    /// The start offsets of the `?`, some of them may be duplicated by the rewriters.
+    placeholders: Vec<usize>,
+    max_position: usize,
+}
+
+impl PlaceholderCollector {
+    pub fn collect(stmt: &Statement) -> Self {
+        let mut collector = PlaceholderCollector::default();
+        stmt.drive(&mut collector);
+        collector.placeholders.sort_unstable();
+        collector.placeholders.dedup();
+        collector
+    }
+
+    pub fn num_params(&self) -> usize {
+        if self.placeholders.is_empty() {
+            self.max_position
+        } else {
+            self.placeholders.len()
+        }
+    }
+
+    /// The index of the parameter bound to the placeholder, if the expression is one.
+    pub fn param_index(&self, expr: &Expr) -> Option<usize> {
+        match expr {
+            Expr::Placeholder { span: Some(span) } => {
+                self.placeholders.binary_search(&span.start()).ok()
+            }
+            Expr::ColumnRef {
+                column:
+                    ColumnRef {
+                        database: None,
+                        table: None,
+                        column: ColumnID::Position(position),
+                    },
+                ..
+            } if self.placeholders.is_empty() && position.pos > 0 => Some(position.pos - 1),
+            _ => None,
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Placeholder { span: Some(span) } => self.placeholders.push(span.start()),
+            Expr::ColumnRef {
+                column:
+                    ColumnRef {
+                        database: None,
+                        table: None,
+                        column: ColumnID::Position(position),
+                    },
+                ..
+            } => self.max_position = self.max_position.max(position.pos),
+            _ => {}
+        }
+    }
+}
+
+/// Replace the placeholders of a prepared statement with the parameters of `EXECUTE`.
+#[derive(Debug, Clone, VisitorMut)]
+#[visitor(Expr(exit))]
+pub struct PlaceholderRewriter {
+    placeholders: PlaceholderCollector,
+    params: Vec<Expr>,
+}
+
+impl PlaceholderRewriter {
+    pub fn rewrite(stmt: &mut Statement, params: &[Expr]) -> Result<()> {
+        let collector = PlaceholderCollector::collect(stmt);
+        if collector.num_params() != params.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Incorrect number of parameters, the prepared statement expects {}, but got {}",
+                collector.num_params(),
+                params.len()
+            )));
+        }
+        for param in params {
+            let mut checker = ParamChecker::default();
+            param.drive(&mut checker);
+            if !checker.constant {
+                return Err(ErrorCode::SemanticError(format!(
+                    "The parameter `{param}` of EXECUTE must be a constant expression"
+                ))
+                .set_span(param.span()));
+            }
+        }
+
+        let mut rewriter = PlaceholderRewriter {
+            placeholders: collector,
+            params: params.to_vec(),
+        };
+        stmt.drive_mut(&mut rewriter);
+        Ok(())
+    }
+
+    // Replace on exit, so that the parameters are not visited.
+    fn exit_expr(&mut self, expr: &mut Expr) {
+        let index = self.placeholders.param_index(expr);
+        if let Some(param) = index.and_then(|index| self.params.get(index)) {
+            *expr = param.clone();
+        }
+    }
+}
+
+#[derive(Visitor)]
+#[visitor(Expr(enter))]
+struct ParamChecker {
+    constant: bool,
+}
+
+impl Default for ParamChecker {
+    fn default() -> Self {
+        ParamChecker { constant: true }
+    }
+}
+
+impl ParamChecker {
+    fn enter_expr(&mut self, expr: &Expr) {
+        if matches!(
+            expr,
+            Expr::ColumnRef { .. }
+                | Expr::Subquery { .. }
+                | Expr::Exists { .. }
+                | Expr::InSubquery { .. }
+                | Expr::Placeholder { .. }
+                | Expr::Hole { .. }
+        ) {
+            self.constant = false;
+        }
+    }
+}
+
+/// The placeholders of a prepared statement bound to typed parameter columns, so that the
+/// bound plan is reused by the executions whose parameters have the same types.
+#[derive(Debug, Clone)]
+pub struct ParameterBindings {
+    placeholders: PlaceholderCollector,
+    columns: Vec<ColumnBinding>,
+}
+
+impl ParameterBindings {
+    pub fn new(placeholders: PlaceholderCollector, columns: Vec<ColumnBinding>) -> Self {
+        ParameterBindings {
+            placeholders,
+            columns,
+        }
+    }
+
+    /// The parameter column bound to the placeholder, if the expression is one.
+    pub fn column(&self, expr: &Expr) -> Option<&ColumnBinding> {
+        let index = self.placeholders.param_index(expr)?;
+        self.columns.get(index)
+    }
+
+    pub fn columns(&self) -> &[ColumnBinding] {
+        &self.columns
+    }
+}
+
+/// Replace the parameter columns of a bound plan with the values of `EXECUTE`.
+pub struct ParameterSetter {
+    values: HashMap<IndexType, Scalar>,
+}
+
+impl ParameterSetter {
+    pub fn new(values: HashMap<IndexType, Scalar>) -> Self {
+        ParameterSetter { values }
+    }
+
+    /// Returns `None` if some parameters are used by the operators that are not rewritten,
+    /// the statement has to be planned with the parameters substituted in that case.
+    pub fn set_parameters(&mut self, s_expr: &SExpr) -> Result<Option<SExpr>> {
+        let s_expr = self.set(s_expr)?;
+        if self.is_used(&s_expr)? {
+            return Ok(None);
+        }
+        Ok(Some(s_expr))
+    }
+
+    fn is_used(&self, s_expr: &SExpr) -> Result<bool> {
+        let prop = RelExpr::with_s_expr(s_expr).derive_relational_prop()?;
+        Ok(self
+            .values
+            .keys()
+            .any(|index| prop.used_columns.contains(index)))
+    }
+
+    #[recursive::recursive]
+    fn set(&mut self, s_expr: &SExpr) -> Result<SExpr> {
+        let mut plan = s_expr.plan().clone();
+        match &mut plan {
+            RelOperator::EvalScalar(eval_scalar) => self.set_items(&mut eval_scalar.items)?,
+            RelOperator::Filter(filter) => {
+                for predicate in filter.predicates.iter_mut() {
+                    self.visit(predicate)?;
+                }
+            }
+            RelOperator::ProjectSet(project_set) => self.set_items(&mut project_set.srfs)?,
+            RelOperator::Aggregate(aggregate) => {
+                self.set_items(&mut aggregate.group_items)?;
+                self.set_items(&mut aggregate.aggregate_functions)?;
+            }
+            RelOperator::Window(window) => {
+                self.set_items(&mut window.arguments)?;
+                self.set_items(&mut window.partition_by)?;
+                for item in window.order_by.iter_mut() {
+                    self.visit(&mut item.order_by_item.scalar)?;
+                }
+                match &mut window.function {
+                    WindowFuncType::Aggregate(aggregate) => {
+                        for expr in aggregate.exprs_mut() {
+                            self.visit(expr)?;
+                        }
+                    }
+                    WindowFuncType::LagLead(lag_lead) => {
+                        self.visit(&mut lag_lead.arg)?;
+                        if let Some(default) = lag_lead.default.as_mut() {
+                            self.visit(default)?;
+                        }
+                    }
+                    WindowFuncType::NthValue(nth_value) => self.visit(&mut nth_value.arg)?,
+                    _ => {}
+                }
+            }
+            RelOperator::Sort(sort) => {
+                if let Some(window) = sort.window_partition.as_mut() {
+                    self.set_items(&mut window.partition_by)?;
+                }
+            }
+            RelOperator::Join(join) => {
+                for condition in join.equi_conditions.iter_mut() {
+                    self.visit(&mut condition.left)?;
+                    self.visit(&mut condition.right)?;
+                }
+                for condition in join.non_equi_conditions.iter_mut() {
+                    self.visit(condition)?;
+                }
+            }
+            RelOperator::Udf(udf) => self.set_items(&mut udf.items)?,
+            RelOperator::AsyncFunction(async_function) => {
+                self.set_items(&mut async_function.items)?
+            }
+            RelOperator::ExpressionScan(expression_scan) => {
+                for row in expression_scan.values.iter_mut() {
+                    for value in row.iter_mut() {
+                        self.visit(value)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut children = Vec::with_capacity(s_expr.arity());
+        for child in s_expr.children() {
+            children.push(Arc::new(self.set(child)?));
+        }
+        Ok(SExpr::create(Arc::new(plan), children, None, None, None))
+    }
+
+    fn set_items(&mut self, items: &mut [ScalarItem]) -> Result<()> {
+        for item in items.iter_mut() {
+            self.visit(&mut item.scalar)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ScalarVisitorMut<'a> for ParameterSetter {
+    fn visit(&mut self, expr: &'a mut ScalarExpr) -> Result<()> {
+        if let ScalarExpr::BoundColumnRef(column_ref) = expr
+            && let Some(value) = self.values.get(&column_ref.column.index)
+        {
+            *expr = ConstantExpr {
+                span: column_ref.span,
+                value: value.clone(),
+            }
+            .into();
+            return Ok(());
+        }
+        walk_expr_mut(self, expr)
+    }
+
+    fn visit_subquery_expr(&mut self, subquery: &'a mut SubqueryExpr) -> Result<()> {
+        if let Some(child_expr) = subquery.child_expr.as_mut() {
+            self.visit(child_expr)?;
+        }
+        let s_expr = self.set(&subquery.subquery)?;
+        // The parameters are not outer columns of the subquery once they are set.
+        if !self.is_used(&s_expr)? {
+            subquery
+                .outer_columns
+                .retain(|index| !self.values.contains_key(index));
+        }
+        subquery.subquery = Box::new(s_expr);
+        Ok(())
+    }
+}
@@ -45,6 +45,7 @@ use databend_common_ast::ast::Weekday as ASTWeekday;
 use databend_common_ast::ast::Window;
 use databend_common_ast::ast::WindowFrame;
 use databend_common_ast::ast::WindowFrameBound;
+use databend_common_ast::ast::WindowFrameExclusion;
 use databend_common_ast::ast::WindowFrameUnits;
 use databend_common_ast::parser::parse_expr;
 use databend_common_ast::parser::tokenize_sql;
@@ -97,9 +98,12 @@ use databend_common_functions::GENERAL_WITHIN_GROUP_FUNCTIONS;
 use databend_common_functions::RANK_WINDOW_FUNCTIONS;
 use databend_common_meta_app::principal::LambdaUDF;
 use databend_common_meta_app::principal::UDAFScript;
+use databend_common_meta_app::principal::UDAFServer;
 use databend_common_meta_app::principal::UDFDefinition;
 use databend_common_meta_app::principal::UDFScript;
 use databend_common_meta_app::principal::UDFServer;
+use databend_common_meta_app::principal::UDTFScript;
+use databend_common_meta_app::principal::UDTFServer;
 use databend_common_meta_app::schema::dictionary_name_ident::DictionaryNameIdent;
 use databend_common_meta_app::schema::DictionaryIdentity;
 use databend_common_meta_app::schema::GetSequenceReq;
@@ -118,6 +122,7 @@ use super::name_resolution::NameResolutionContext;
 use super::normalize_identifier;
 use crate::binder::bind_values;
 use crate::binder::resolve_file_location;
+use crate::binder::udtf_return_type;
 use crate::binder::wrap_cast;
 use crate::binder::Binder;
 use crate::binder::ExprContext;
@@ -158,11 +163,13 @@ use crate::plans::UDFCall;
 use crate::plans::UDFField;
 use crate::plans::UDFLambdaCall;
 use crate::plans::UDFScriptCode;
+use crate::plans::UDFScriptImport;
 use crate::plans::UDFType;
 use crate::plans::Visitor as ScalarVisitor;
 use crate::plans::WindowFunc;
 use crate::plans::WindowFuncFrame;
 use crate::plans::WindowFuncFrameBound;
+use crate::plans::WindowFuncFrameExclusion;
 use crate::plans::WindowFuncFrameUnits;
 use crate::plans::WindowFuncType;
 use crate::plans::WindowOrderBy;
@@ -240,6 +247,21 @@ impl<'a> TypeChecker<'a> {
 
     #[recursive::recursive]
     pub fn resolve(&mut self, expr: &Expr) -> Result<Box<(ScalarExpr, DataType)>> {
+        if matches!(
+            expr,
+            Expr::Placeholder { .. }
+                | Expr::ColumnRef {
+                    column: ColumnRef {
+                        column: ColumnID::Position(_),
+                        ..
+                    },
+                    ..
+                }
+        ) && let Some(column) = self.metadata.read().parameter_column(expr)
+        {
+            return Ok(Self::resolve_parameter(expr.span(), column));
+        }
+
         let box (scalar, data_type): Box<(ScalarExpr, DataType)> = match expr {
             Expr::ColumnRef {
                 span,
@@ -1290,6 +1312,7 @@ impl<'a> TypeChecker<'a> {
         let units = match frame.units {
             WindowFrameUnits::Rows => WindowFuncFrameUnits::Rows,
             WindowFrameUnits::Range => WindowFuncFrameUnits::Range,
+            WindowFrameUnits::Groups => WindowFuncFrameUnits::Groups,
         };
         let start = match frame.start_bound {
             WindowFrameBound::CurrentRow => WindowFuncFrameBound::CurrentRow,
@@ -1330,6 +1353,7 @@ impl<'a> TypeChecker<'a> {
             units,
             start_bound: start,
             end_bound: end,
+            exclusion: Self::resolve_window_frame_exclusion(&frame.exclusion),
         })
     }
 
@@ -1360,6 +1384,7 @@ impl<'a> TypeChecker<'a> {
         let units = match frame.units {
             WindowFrameUnits::Rows => WindowFuncFrameUnits::Rows,
             WindowFrameUnits::Range => WindowFuncFrameUnits::Range,
+            WindowFrameUnits::Groups => WindowFuncFrameUnits::Groups,
         };
         let start = match frame.start_bound {
             WindowFrameBound::CurrentRow => WindowFuncFrameBound::CurrentRow,
@@ -1376,9 +1401,21 @@ impl<'a> TypeChecker<'a> {
             units,
             start_bound: start,
             end_bound: end,
+            exclusion: Self::resolve_window_frame_exclusion(&frame.exclusion),
         })
     }
 
+    fn resolve_window_frame_exclusion(
+        exclusion: &WindowFrameExclusion,
+    ) -> WindowFuncFrameExclusion {
+        match exclusion {
+            WindowFrameExclusion::NoOthers => WindowFuncFrameExclusion::NoOthers,
+            WindowFrameExclusion::CurrentRow => WindowFuncFrameExclusion::CurrentRow,
+            WindowFrameExclusion::Group => WindowFuncFrameExclusion::Group,
+            WindowFrameExclusion::Ties => WindowFuncFrameExclusion::Ties,
+        }
+    }
+
     fn resolve_window_frame(
         &mut self,
         span: Span,
@@ -1392,6 +1429,7 @@ impl<'a> TypeChecker<'a> {
                     units: WindowFuncFrameUnits::Rows,
                     start_bound: WindowFuncFrameBound::Preceding(None),
                     end_bound: WindowFuncFrameBound::Following(None),
+                    exclusion: WindowFuncFrameExclusion::NoOthers,
                 });
             }
             WindowFuncType::LagLead(lag_lead) if lag_lead.is_lag => {
@@ -1403,6 +1441,7 @@ impl<'a> TypeChecker<'a> {
                     end_bound: WindowFuncFrameBound::Preceding(Some(Scalar::Number(
                         NumberScalar::UInt64(lag_lead.offset),
                     ))),
+                    exclusion: WindowFuncFrameExclusion::NoOthers,
                 });
             }
             WindowFuncType::LagLead(lag_lead) => {
@@ -1414,6 +1453,7 @@ impl<'a> TypeChecker<'a> {
                     end_bound: WindowFuncFrameBound::Following(Some(Scalar::Number(
                         NumberScalar::UInt64(lag_lead.offset),
                     ))),
+                    exclusion: WindowFuncFrameExclusion::NoOthers,
                 });
             }
             WindowFuncType::Ntile(_) => {
@@ -1421,6 +1461,7 @@ impl<'a> TypeChecker<'a> {
                     units: WindowFuncFrameUnits::Rows,
                     start_bound: WindowFuncFrameBound::Preceding(None),
                     end_bound: WindowFuncFrameBound::Following(None),
+                    exclusion: WindowFuncFrameExclusion::NoOthers,
                 });
             }
             WindowFuncType::CumeDist => {
@@ -1428,6 +1469,7 @@ impl<'a> TypeChecker<'a> {
                     units: WindowFuncFrameUnits::Range,
                     start_bound: WindowFuncFrameBound::Preceding(None),
                     end_bound: WindowFuncFrameBound::Following(None),
+                    exclusion: WindowFuncFrameExclusion::NoOthers,
                 });
             }
             _ => {}
@@ -1442,6 +1484,12 @@ impl<'a> TypeChecker<'a> {
                 }
                 self.resolve_window_range_frame(frame)
             } else {
+                if frame.units.is_groups() && order_by.is_empty() {
+                    return Err(ErrorCode::SemanticError(
+                        "The GROUPS window frame requires an ORDER BY clause".to_string(),
+                    )
+                    .set_span(span));
+                }
                 self.resolve_window_rows_frame(frame)
             }
         } else if order_by.is_empty() {
@@ -1449,12 +1497,14 @@ impl<'a> TypeChecker<'a> {
                 units: WindowFuncFrameUnits::Range,
                 start_bound: WindowFuncFrameBound::Preceding(None),
                 end_bound: WindowFuncFrameBound::Following(None),
+                exclusion: WindowFuncFrameExclusion::NoOthers,
             })
         } else {
             Ok(WindowFuncFrame {
                 units: WindowFuncFrameUnits::Range,
                 start_bound: WindowFuncFrameBound::Preceding(None),
                 end_bound: WindowFuncFrameBound::CurrentRow,
+                exclusion: WindowFuncFrameExclusion::NoOthers,
             })
         }
     }
@@ -2690,6 +2740,25 @@ impl<'a> TypeChecker<'a> {
         func_name: &str,
         args: &[&Expr],
     ) -> Result<Box<(ScalarExpr, DataType)>> {
+        self.check_set_returning_context(span)?;
+
+        let original_context = self.bind_context.expr_context.clone();
+        self.bind_context
+            .set_expr_context(ExprContext::InSetReturningFunction);
+
+        let mut arguments = Vec::with_capacity(args.len());
+        for arg in args.iter() {
+            let box (scalar, _) = self.resolve(arg)?;
+            arguments.push(scalar);
+        }
+
+        // Restore the original context
+        self.bind_context.set_expr_context(original_context);
+
+        self.build_set_returning_function(span, func_name, arguments)
+    }
+
+    fn check_set_returning_context(&self, span: Span) -> Result<()> {
         match self.bind_context.expr_context {
             ExprContext::InSetReturningFunction => {
                 return Err(ErrorCode::SemanticError(
@@ -2724,20 +2793,15 @@ impl<'a> TypeChecker<'a> {
             )
             .set_span(span));
         }
+        Ok(())
+    }
 
-        let original_context = self.bind_context.expr_context.clone();
-        self.bind_context
-            .set_expr_context(ExprContext::InSetReturningFunction);
-
-        let mut arguments = Vec::with_capacity(args.len());
-        for arg in args.iter() {
-            let box (scalar, _) = self.resolve(arg)?;
-            arguments.push(scalar);
-        }
-
-        // Restore the original context
-        self.bind_context.set_expr_context(original_context);
-
+    fn build_set_returning_function(
+        &mut self,
+        span: Span,
+        func_name: &str,
+        arguments: Vec<ScalarExpr>,
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
         let srf_scalar = ScalarExpr::FunctionCall(FunctionCall {
             span,
             func_name: func_name.to_string(),
@@ -3957,12 +4021,25 @@ impl<'a> TypeChecker<'a> {
             UDFDefinition::UDFServer(udf_def) => Ok(Some(
                 self.resolve_udf_server(span, name, arguments, udf_def)?,
             )),
-            UDFDefinition::UDFScript(udf_def) => Ok(Some(
-                self.resolve_udf_script(span, name, arguments, udf_def)?,
-            )),
+            UDFDefinition::UDFScript(udf_def) => Ok(Some(self.resolve_udf_script(
+                span,
+                name,
+                arguments,
+                udf_def,
+                vec![],
+            )?)),
             UDFDefinition::UDAFScript(udf_def) => Ok(Some(
                 self.resolve_udaf_script(span, name, arguments, udf_def)?,
             )),
+            UDFDefinition::UDAFServer(udf_def) => Ok(Some(
+                self.resolve_udaf_server(span, name, arguments, udf_def)?,
+            )),
+            UDFDefinition::UDTFServer(udf_def) => Ok(Some(
+                self.resolve_udtf_server(span, name, arguments, udf_def)?,
+            )),
+            UDFDefinition::UDTFScript(udf_def) => Ok(Some(
+                self.resolve_udtf_script(span, name, arguments, udf_def)?,
+            )),
         }
     }
 
@@ -4074,12 +4151,34 @@ impl<'a> TypeChecker<'a> {
         Ok(code_blob)
     }
 
+    async fn resolve_udf_imports(&mut self, imports: Vec<String>) -> Result<Vec<UDFScriptImport>> {
+        let mut modules = Vec::with_capacity(imports.len());
+        for import in imports {
+            // `@stage/path/helper.py` (or `helper.py.gz`) is importable as `helper`
+            let file_name = import.rsplit('/').next().unwrap_or_default();
+            let module = file_name.split('.').next().unwrap_or_default().to_string();
+            if module.is_empty() {
+                return Err(ErrorCode::SemanticError(format!(
+                    "Invalid UDF import {:?}, expect a stage file like '@stage/module.py'",
+                    import
+                )));
+            }
+            let code = self.resolve_udf_with_stage(import).await?;
+            modules.push(UDFScriptImport {
+                module,
+                code: Arc::new(code.into_boxed_slice()),
+            });
+        }
+        Ok(modules)
+    }
+
     fn resolve_udf_script(
         &mut self,
         span: Span,
         name: String,
         args: &[Expr],
         udf_definition: UDFScript,
+        table_columns: Vec<String>,
     ) -> Result<Box<(ScalarExpr, DataType)>> {
         let UDFScript {
             code,
@@ -4088,6 +4187,8 @@ impl<'a> TypeChecker<'a> {
             arg_types,
             return_type,
             runtime_version,
+            imports,
+            packages,
         } = udf_definition;
 
         let language = language.parse()?;
@@ -4104,10 +4205,14 @@ impl<'a> TypeChecker<'a> {
 
         let code_blob = databend_common_base::runtime::block_on(self.resolve_udf_with_stage(code))?
             .into_boxed_slice();
+        let imports = databend_common_base::runtime::block_on(self.resolve_udf_imports(imports))?;
         let udf_type = UDFType::Script(UDFScriptCode {
             language,
             runtime_version,
             code: code_blob.into(),
+            imports,
+            packages,
+            table_columns,
         });
 
         let arg_names = args.iter().map(|arg| format!("{arg}")).join(", ");
@@ -4145,14 +4250,20 @@ impl<'a> TypeChecker<'a> {
             state_fields,
             return_type,
             runtime_version,
+            imports,
+            packages,
         } = udf_definition;
         let language = language.parse()?;
         let code_blob = databend_common_base::runtime::block_on(self.resolve_udf_with_stage(code))?
             .into_boxed_slice();
+        let imports = databend_common_base::runtime::block_on(self.resolve_udf_imports(imports))?;
         let udf_type = UDFType::Script(UDFScriptCode {
             language,
             runtime_version,
             code: code_blob.into(),
+            imports,
+            packages,
+            table_columns: vec![],
         });
 
         let arguments = args
@@ -4197,6 +4308,148 @@ impl<'a> TypeChecker<'a> {
         )))
     }
 
+    fn resolve_udaf_server(
+        &mut self,
+        span: Span,
+        name: String,
+        args: &[Expr],
+        udf_definition: UDAFServer,
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
+        let UDAFServer {
+            address,
+            arg_types,
+            state_fields,
+            return_type,
+            ..
+        } = udf_definition;
+        UDFValidator::is_udf_server_allowed(&address)?;
+        if args.len() != arg_types.len() {
+            return Err(ErrorCode::InvalidArgument(format!(
+                "Require {} parameters, but got: {}",
+                arg_types.len(),
+                args.len()
+            ))
+            .set_span(span));
+        }
+
+        let arguments = args
+            .iter()
+            .zip(arg_types.iter())
+            .map(|(argument, dest_type)| {
+                let box (arg, ty) = self.resolve(argument)?;
+                Ok(if ty == *dest_type {
+                    arg
+                } else {
+                    wrap_cast(&arg, dest_type)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let display_name = format!(
+            "{name}({})",
+            args.iter().map(|arg| format!("{:#}", arg)).join(", ")
+        );
+
+        self.ctx.set_cacheable(false);
+        Ok(Box::new((
+            UDAFCall {
+                span,
+                name,
+                display_name,
+                arg_types,
+                state_fields: state_fields
+                    .iter()
+                    .map(|f| UDFField {
+                        name: f.name().to_string(),
+                        data_type: f.data_type().clone(),
+                    })
+                    .collect(),
+                return_type: Box::new(return_type.clone()),
+                udf_type: UDFType::Server(address),
+                arguments,
+            }
+            .into(),
+            return_type,
+        )))
+    }
+
+    fn resolve_udtf_server(
+        &mut self,
+        span: Span,
+        name: String,
+        arguments: &[Expr],
+        udf_definition: UDTFServer,
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
+        let UDTFServer {
+            address,
+            handler,
+            language,
+            arg_types,
+            return_fields,
+        } = udf_definition;
+        let udf_definition = UDFServer {
+            address,
+            handler,
+            language,
+            arg_types,
+            return_type: udtf_return_type(&return_fields),
+        };
+        self.resolve_udtf_call(span, |type_checker| {
+            type_checker.resolve_udf_server(span, name, arguments, udf_definition)
+        })
+    }
+
+    fn resolve_udtf_script(
+        &mut self,
+        span: Span,
+        name: String,
+        arguments: &[Expr],
+        udf_definition: UDTFScript,
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
+        let UDTFScript {
+            code,
+            handler,
+            language,
+            arg_types,
+            return_fields,
+            runtime_version,
+            imports,
+            packages,
+        } = udf_definition;
+        let table_columns = return_fields.iter().map(|f| f.name().clone()).collect();
+        let udf_definition = UDFScript {
+            code,
+            handler,
+            language,
+            arg_types,
+            return_type: udtf_return_type(&return_fields),
+            runtime_version,
+            imports,
+            packages,
+        };
+        self.resolve_udtf_call(span, |type_checker| {
+            type_checker.resolve_udf_script(span, name, arguments, udf_definition, table_columns)
+        })
+    }
+
+    /// A table-valued UDF returns an array with the rows of each input row,
+    /// the call is unnested like a set-returning function.
+    fn resolve_udtf_call(
+        &mut self,
+        span: Span,
+        resolve_udf: impl FnOnce(&mut Self) -> Result<Box<(ScalarExpr, DataType)>>,
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
+        self.check_set_returning_context(span)?;
+
+        let original_context = self.bind_context.expr_context.clone();
+        self.bind_context
+            .set_expr_context(ExprContext::InSetReturningFunction);
+        let box (udf_scalar, _) = resolve_udf(self)?;
+        self.bind_context.set_expr_context(original_context);
+
+        self.build_set_returning_function(span, "unnest", vec![udf_scalar])
+    }
+
     fn resolve_lambda_udf(
         &mut self,
         span: Span,
@@ -5334,6 +5587,21 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// The parameter of the prepared statement is bound to its column, which is replaced by
+    /// the value on every execution. It is wrapped in a cast to its own type, so that it is
+    /// not taken as a column of the input, e.g. as the output column of `SELECT ?`.
+    fn resolve_parameter(span: Span, column: ColumnBinding) -> Box<(ScalarExpr, DataType)> {
+        let data_type = *column.data_type.clone();
+        let scalar = CastExpr {
+            span,
+            is_try: false,
+            argument: Box::new(BoundColumnRef { span, column }.into()),
+            target_type: Box::new(data_type.clone()),
+        }
+        .into();
+        Box::new((scalar, data_type))
+    }
+
     fn try_fold_constant<Index: ColumnIndex>(
         &self,
         expr: &EExpr<Index>,
@@ -0,0 +1,1094 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use databend_common_ast::ast::Identifier;
+use databend_common_ast::ast::TableReference;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::decimal::DecimalDataType;
+use databend_common_expression::types::decimal::DecimalScalar;
+use databend_common_expression::types::decimal::DecimalSize;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::types::F32;
+use databend_common_expression::types::F64;
+use databend_common_expression::Scalar;
+use databend_common_functions::aggregates::AggregateFunctionFactory;
+use prost::Message;
+use substrait::proto::aggregate_function::AggregationInvocation;
+use substrait::proto::aggregate_rel::Grouping;
+use substrait::proto::expression::field_reference::ReferenceType;
+use substrait::proto::expression::field_reference::RootType;
+use substrait::proto::expression::literal::LiteralType;
+use substrait::proto::expression::reference_segment;
+use substrait::proto::expression::FieldReference;
+use substrait::proto::expression::Literal;
+use substrait::proto::expression::RexType;
+use substrait::proto::extensions::simple_extension_declaration::MappingType;
+use substrait::proto::fetch_rel::CountMode;
+use substrait::proto::fetch_rel::OffsetMode;
+use substrait::proto::function_argument::ArgType;
+use substrait::proto::join_rel::JoinType as SubstraitJoinType;
+use substrait::proto::plan_rel;
+use substrait::proto::r#type::Kind;
+use substrait::proto::r#type::Nullability;
+use substrait::proto::read_rel::ReadType;
+use substrait::proto::rel::RelType;
+use substrait::proto::rel_common::EmitKind;
+use substrait::proto::sort_field::SortDirection;
+use substrait::proto::sort_field::SortKind;
+use substrait::proto::AggregateRel;
+use substrait::proto::CrossRel;
+use substrait::proto::Expression;
+use substrait::proto::FetchRel;
+use substrait::proto::FilterRel;
+use substrait::proto::FunctionArgument;
+use substrait::proto::JoinRel;
+use substrait::proto::ProjectRel;
+use substrait::proto::ReadRel;
+use substrait::proto::Rel;
+use substrait::proto::RelCommon;
+use substrait::proto::SortRel;
+use substrait::proto::Type;
+
+use crate::format_scalar;
+use crate::optimizer::SExpr;
+use crate::plans::Aggregate;
+use crate::plans::AggregateFunction;
+use crate::plans::AggregateMode;
+use crate::plans::BoundColumnRef;
+use crate::plans::CastExpr;
+use crate::plans::ConstantExpr;
+use crate::plans::EvalScalar;
+use crate::plans::Filter;
+use crate::plans::FunctionCall;
+use crate::plans::Join;
+use crate::plans::JoinEquiCondition;
+use crate::plans::JoinType;
+use crate::plans::Limit;
+use crate::plans::Plan;
+use crate::plans::ScalarExpr;
+use crate::plans::ScalarItem;
+use crate::plans::Sort;
+use crate::plans::SortItem;
+use crate::BindContext;
+use crate::Binder;
+use crate::ColumnBinding;
+use crate::ColumnBindingBuilder;
+use crate::ColumnSet;
+use crate::MetadataRef;
+use crate::Visibility;
+
+/// Binds a serialized Substrait plan to a logical `SExpr`.
+///
+/// Columns are addressed by ordinal in Substrait, so every bound relation
+/// keeps the column bindings of its output in order.
+pub struct SubstraitBinder {
+    binder: Binder,
+    metadata: MetadataRef,
+    // Function anchor -> function name, declared in the plan extensions.
+    functions: HashMap<u32, String>,
+}
+
+struct BoundRel {
+    s_expr: SExpr,
+    columns: Vec<ColumnBinding>,
+}
+
+impl SubstraitBinder {
+    pub fn new(binder: Binder, metadata: MetadataRef) -> Self {
+        SubstraitBinder {
+            binder,
+            metadata,
+            functions: HashMap::new(),
+        }
+    }
+
+    pub fn bind(mut self, plan: &[u8]) -> Result<Plan> {
+        let plan = substrait::proto::Plan::decode(plan)
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid substrait plan: {}", e)))?;
+
+        for extension in &plan.extensions {
+            if let Some(MappingType::ExtensionFunction(function)) = &extension.mapping_type {
+                // Functions are declared with their signature, like `add:i32_i32`.
+                let name = function.name.split(':').next().unwrap_or_default();
+                self.functions
+                    .insert(function.function_anchor, name.to_lowercase());
+            }
+        }
+
+        let [relation] = plan.relations.as_slice() else {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Substrait plan must contain exactly one relation, but got {}",
+                plan.relations.len()
+            )));
+        };
+
+        let (rel, names) = match &relation.rel_type {
+            Some(plan_rel::RelType::Root(root)) => (root.input.as_ref(), root.names.as_slice()),
+            Some(plan_rel::RelType::Rel(rel)) => (Some(rel), [].as_slice()),
+            None => (None, [].as_slice()),
+        };
+        let rel = rel.ok_or_else(|| missing_field("relation"))?;
+        let mut bound = self.bind_rel(rel)?;
+
+        // Output names also contain the names of nested fields,
+        // they are only applied if they match the top level columns.
+        if names.len() == bound.columns.len() {
+            for (column, name) in bound.columns.iter_mut().zip(names) {
+                column.column_name = name.clone();
+            }
+        }
+
+        let mut bind_context = BindContext::new();
+        bind_context.columns = bound.columns;
+
+        Ok(Plan::Query {
+            s_expr: Box::new(bound.s_expr),
+            metadata: self.metadata.clone(),
+            bind_context: Box::new(bind_context),
+            rewrite_kind: None,
+            formatted_ast: None,
+            ignore_result: false,
+        })
+    }
+
+    fn bind_rel(&mut self, rel: &Rel) -> Result<BoundRel> {
+        let rel_type = rel
+            .rel_type
+            .as_ref()
+            .ok_or_else(|| missing_field("rel_type"))?;
+
+        let (bound, common) = match rel_type {
+            RelType::Read(read) => (self.bind_read(read)?, &read.common),
+            RelType::Filter(filter) => (self.bind_filter(filter)?, &filter.common),
+            RelType::Project(project) => (self.bind_project(project)?, &project.common),
+            RelType::Aggregate(aggregate) => (self.bind_aggregate(aggregate)?, &aggregate.common),
+            RelType::Sort(sort) => (self.bind_sort(sort)?, &sort.common),
+            RelType::Join(join) => (self.bind_join(join)?, &join.common),
+            RelType::Cross(cross) => (self.bind_cross(cross)?, &cross.common),
+            RelType::Fetch(fetch) => (self.bind_fetch(fetch)?, &fetch.common),
+            other => {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Substrait {} relation is not supported",
+                    variant_name(other)
+                )));
+            }
+        };
+
+        apply_emit(bound, common)
+    }
+
+    fn bind_input(&mut self, input: &Option<Box<Rel>>) -> Result<BoundRel> {
+        let input = input.as_ref().ok_or_else(|| missing_field("input"))?;
+        self.bind_rel(input)
+    }
+
+    fn bind_read(&mut self, read: &ReadRel) -> Result<BoundRel> {
+        let Some(ReadType::NamedTable(named_table)) = &read.read_type else {
+            return Err(ErrorCode::Unimplemented(
+                "Substrait read relation only supports named tables",
+            ));
+        };
+
+        let (catalog, database, table) = match named_table.names.as_slice() {
+            [table] => (None, None, table),
+            [database, table] => (None, Some(database), table),
+            [catalog, database, table] => (Some(catalog), Some(database), table),
+            names => {
+                return Err(ErrorCode::SemanticError(format!(
+                    "Invalid table name '{}' in substrait plan",
+                    names.join(".")
+                )));
+            }
+        };
+
+        let table_ref = TableReference::Table {
+            span: None,
+            catalog: catalog.map(|name| Identifier::from_name(None, name)),
+            database: database.map(|name| Identifier::from_name(None, name)),
+            table: Identifier::from_name(None, table),
+            alias: None,
+            temporal: None,
+            with_options: None,
+            pivot: None,
+            unpivot: None,
+            sample: None,
+        };
+
+        let mut bind_context = BindContext::new();
+        let (mut s_expr, table_context) = self
+            .binder
+            .bind_table_reference(&mut bind_context, &table_ref)?;
+        let table_columns = table_context
+            .columns
+            .into_iter()
+            .filter(|column| column.visibility == Visibility::Visible)
+            .collect::<Vec<_>>();
+
+        let mut columns = match &read.base_schema {
+            Some(schema) => {
+                let num_fields = schema.r#struct.as_ref().map_or(0, |s| s.types.len());
+                if schema.names.len() != num_fields {
+                    return Err(ErrorCode::Unimplemented(
+                        "Substrait read relation does not support nested fields in base schema",
+                    ));
+                }
+                schema
+                    .names
+                    .iter()
+                    .map(|name| {
+                        table_columns
+                            .iter()
+                            .find(|column| column.column_name.eq_ignore_ascii_case(name))
+                            .cloned()
+                            .ok_or_else(|| {
+                                ErrorCode::SemanticError(format!(
+                                    "Column '{}' does not exist in table '{}'",
+                                    name, table
+                                ))
+                            })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            None => table_columns,
+        };
+
+        if let Some(filter) = &read.filter {
+            let predicate = self.bind_expression(filter, &columns)?;
+            s_expr = filter_s_expr(s_expr, predicate);
+        }
+
+        if let Some(select) = read
+            .projection
+            .as_ref()
+            .and_then(|mask| mask.select.as_ref())
+        {
+            columns = select
+                .struct_items
+                .iter()
+                .map(|item| {
+                    if item.child.is_some() {
+                        return Err(ErrorCode::Unimplemented(
+                            "Substrait read relation does not support projection of nested fields",
+                        ));
+                    }
+                    field(&columns, item.field)
+                })
+                .collect::<Result<Vec<_>>>()?;
+        }
+
+        Ok(BoundRel { s_expr, columns })
+    }
+
+    fn bind_filter(&mut self, filter: &FilterRel) -> Result<BoundRel> {
+        let input = self.bind_input(&filter.input)?;
+        let condition = filter
+            .condition
+            .as_ref()
+            .ok_or_else(|| missing_field("condition"))?;
+        let predicate = self.bind_expression(condition, &input.columns)?;
+
+        Ok(BoundRel {
+            s_expr: filter_s_expr(input.s_expr, predicate),
+            columns: input.columns,
+        })
+    }
+
+    fn bind_project(&mut self, project: &ProjectRel) -> Result<BoundRel> {
+        let input = self.bind_input(&project.input)?;
+
+        // The expressions of a project relation are appended to its input columns.
+        let mut items = vec![];
+        let mut columns = input.columns.clone();
+        for expression in &project.expressions {
+            let scalar = self.bind_expression(expression, &input.columns)?;
+            columns.push(self.derive_column(scalar, &mut items)?);
+        }
+
+        Ok(BoundRel {
+            s_expr: eval_scalar_s_expr(input.s_expr, items),
+            columns,
+        })
+    }
+
+    fn bind_aggregate(&mut self, aggregate: &AggregateRel) -> Result<BoundRel> {
+        if aggregate.groupings.len() > 1 {
+            return Err(ErrorCode::Unimplemented(
+                "Substrait aggregate relation does not support grouping sets",
+            ));
+        }
+
+        let input = self.bind_input(&aggregate.input)?;
+
+        // Group keys and arguments of aggregate functions are evaluated before aggregation.
+        let mut pre_items = vec![];
+        let mut group_columns = vec![];
+        if let Some(grouping) = aggregate.groupings.first() {
+            for expression in grouping_expressions(aggregate, grouping)? {
+                let scalar = self.bind_expression(expression, &input.columns)?;
+                group_columns.push(self.derive_column(scalar, &mut pre_items)?);
+            }
+        }
+
+        let mut aggregate_items = vec![];
+        let mut aggregate_columns = vec![];
+        for measure in &aggregate.measures {
+            if measure.filter.is_some() {
+                return Err(ErrorCode::Unimplemented(
+                    "Substrait aggregate measure does not support filter",
+                ));
+            }
+            let function = measure
+                .measure
+                .as_ref()
+                .ok_or_else(|| missing_field("measure"))?;
+            if !function.sorts.is_empty() {
+                return Err(ErrorCode::Unimplemented(
+                    "Substrait aggregate measure does not support sorts",
+                ));
+            }
+
+            let mut args = vec![];
+            for scalar in self.bind_arguments(&function.arguments, &input.columns)? {
+                args.push(self.derive_column(scalar, &mut pre_items)?);
+            }
+
+            // Rewrite `xxx(distinct)` to `xxx_distinct(...)`, same as the type checker.
+            let func_name = self.function_name(function.function_reference)?;
+            let distinct = function.invocation() == AggregationInvocation::Distinct;
+            let func_name = match (func_name.as_str(), distinct) {
+                ("count", true) => "count_distinct".to_string(),
+                (name, true) => format!("{}_distinct", name),
+                (name, false) => name.to_string(),
+            };
+
+            let arg_types = args
+                .iter()
+                .map(|column| *column.data_type.clone())
+                .collect();
+            let return_type = AggregateFunctionFactory::instance()
+                .get(&func_name, vec![], arg_types, vec![])?
+                .return_type()?;
+            let display_name = format!(
+                "{}({})",
+                func_name,
+                args.iter()
+                    .map(|column| column.column_name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+
+            let scalar = ScalarExpr::AggregateFunction(AggregateFunction {
+                span: None,
+                func_name,
+                distinct: false,
+                params: vec![],
+                args: args.into_iter().map(column_ref).collect(),
+                return_type: Box::new(return_type.clone()),
+                sort_descs: vec![],
+                display_name: display_name.clone(),
+            });
+            let index = self.metadata.write().add_derived_column(
+                display_name.clone(),
+                return_type.clone(),
+                Some(scalar.clone()),
+            );
+            aggregate_items.push(ScalarItem { scalar, index });
+            aggregate_columns.push(
+                ColumnBindingBuilder::new(
+                    display_name,
+                    index,
+                    Box::new(return_type),
+                    Visibility::Visible,
+                )
+                .build(),
+            );
+        }
+
+        let aggregate_plan = Aggregate {
+            mode: AggregateMode::Initial,
+            group_items: group_columns
+                .iter()
+                .map(|column| ScalarItem {
+                    scalar: column_ref(column.clone()),
+                    index: column.index,
+                })
+                .collect(),
+            aggregate_functions: aggregate_items,
+            ..Default::default()
+        };
+        let s_expr = SExpr::create_unary(
+            Arc::new(aggregate_plan.into()),
+            Arc::new(eval_scalar_s_expr(input.s_expr, pre_items)),
+        );
+
+        // The output of an aggregate relation is the group keys followed by the measures.
+        let mut columns = group_columns;
+        columns.extend(aggregate_columns);
+        Ok(BoundRel { s_expr, columns })
+    }
+
+    fn bind_sort(&mut self, sort: &SortRel) -> Result<BoundRel> {
+        let input = self.bind_input(&sort.input)?;
+
+        let mut pre_items = vec![];
+        let mut sort_items = vec![];
+        for sort_field in &sort.sorts {
+            let expression = sort_field
+                .expr
+                .as_ref()
+                .ok_or_else(|| missing_field("expr"))?;
+            let scalar = self.bind_expression(expression, &input.columns)?;
+            let column = self.derive_column(scalar, &mut pre_items)?;
+
+            let (asc, nulls_first) = match &sort_field.sort_kind {
+                None => (true, false),
+                Some(SortKind::Direction(direction)) => match SortDirection::try_from(*direction) {
+                    Ok(SortDirection::Unspecified) | Ok(SortDirection::AscNullsLast) => {
+                        (true, false)
+                    }
+                    Ok(SortDirection::AscNullsFirst) => (true, true),
+                    Ok(SortDirection::DescNullsFirst) => (false, true),
+                    Ok(SortDirection::DescNullsLast) => (false, false),
+                    _ => {
+                        return Err(ErrorCode::Unimplemented(format!(
+                            "Substrait sort direction {} is not supported",
+                            direction
+                        )));
+                    }
+                },
+                Some(SortKind::ComparisonFunctionReference(_)) => {
+                    return Err(ErrorCode::Unimplemented(
+                        "Substrait sort relation does not support comparison functions",
+                    ));
+                }
+            };
+            sort_items.push(SortItem {
+                index: column.index,
+                asc,
+                nulls_first,
+            });
+        }
+
+        let sort_plan = Sort {
+            items: sort_items,
+            limit: None,
+            after_exchange: None,
+            pre_projection: None,
+            window_partition: None,
+        };
+        Ok(BoundRel {
+            s_expr: SExpr::create_unary(
+                Arc::new(sort_plan.into()),
+                Arc::new(eval_scalar_s_expr(input.s_expr, pre_items)),
+            ),
+            columns: input.columns,
+        })
+    }
+
+    fn bind_join(&mut self, join: &JoinRel) -> Result<BoundRel> {
+        let mut join_type = match join.r#type() {
+            SubstraitJoinType::Inner => JoinType::Inner,
+            SubstraitJoinType::Outer => JoinType::Full,
+            SubstraitJoinType::Left => JoinType::Left,
+            SubstraitJoinType::Right => JoinType::Right,
+            SubstraitJoinType::LeftSemi => JoinType::LeftSemi,
+            SubstraitJoinType::LeftAnti => JoinType::LeftAnti,
+            SubstraitJoinType::RightSemi => JoinType::RightSemi,
+            SubstraitJoinType::RightAnti => JoinType::RightAnti,
+            other => {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Substrait join type {} is not supported",
+                    other.as_str_name()
+                )));
+            }
+        };
+
+        let left = self.bind_input(&join.left)?;
+        let right = self.bind_input(&join.right)?;
+
+        let mut equi_conditions = vec![];
+        let mut non_equi_conditions = vec![];
+        if let Some(expression) = &join.expression {
+            let mut columns = left.columns.clone();
+            columns.extend(right.columns.iter().cloned());
+            let condition = self.bind_expression(expression, &columns)?;
+
+            let left_columns: ColumnSet = left.columns.iter().map(|c| c.index).collect();
+            let right_columns: ColumnSet = right.columns.iter().map(|c| c.index).collect();
+            for predicate in split_conjunctions(condition) {
+                match equi_condition(&predicate, &left_columns, &right_columns) {
+                    Some(condition) => equi_conditions.push(condition),
+                    None => non_equi_conditions.push(predicate),
+                }
+            }
+        }
+        if join_type == JoinType::Inner
+            && equi_conditions.is_empty()
+            && non_equi_conditions.is_empty()
+        {
+            join_type = JoinType::Cross;
+        }
+
+        let columns = match join_type {
+            JoinType::LeftSemi | JoinType::LeftAnti => left.columns,
+            JoinType::RightSemi | JoinType::RightAnti => right.columns,
+            _ => {
+                let nullable_left = matches!(join_type, JoinType::Right | JoinType::Full);
+                let nullable_right = matches!(join_type, JoinType::Left | JoinType::Full);
+                let mut columns = wrap_nullable_columns(left.columns, nullable_left);
+                columns.extend(wrap_nullable_columns(right.columns, nullable_right));
+                columns
+            }
+        };
+
+        let join_plan = Join {
+            equi_conditions,
+            non_equi_conditions,
+            join_type,
+            ..Default::default()
+        };
+        let mut s_expr = SExpr::create_binary(
+            Arc::new(join_plan.into()),
+            Arc::new(left.s_expr),
+            Arc::new(right.s_expr),
+        );
+
+        if let Some(post_join_filter) = &join.post_join_filter {
+            let predicate = self.bind_expression(post_join_filter, &columns)?;
+            s_expr = filter_s_expr(s_expr, predicate);
+        }
+
+        Ok(BoundRel { s_expr, columns })
+    }
+
+    fn bind_cross(&mut self, cross: &CrossRel) -> Result<BoundRel> {
+        let left = self.bind_input(&cross.left)?;
+        let right = self.bind_input(&cross.right)?;
+
+        let s_expr = SExpr::create_binary(
+            Arc::new(Join::default().into()),
+            Arc::new(left.s_expr),
+            Arc::new(right.s_expr),
+        );
+        let mut columns = left.columns;
+        columns.extend(right.columns);
+        Ok(BoundRel { s_expr, columns })
+    }
+
+    fn bind_fetch(&mut self, fetch: &FetchRel) -> Result<BoundRel> {
+        let input = self.bind_input(&fetch.input)?;
+
+        let offset = match &fetch.offset_mode {
+            None => 0,
+            Some(OffsetMode::Offset(offset)) => *offset,
+            Some(OffsetMode::OffsetExpr(expression)) => self.bind_fetch_value(expression)?,
+        };
+        if offset < 0 {
+            return Err(ErrorCode::SemanticError(format!(
+                "Substrait fetch offset must not be negative, but got {}",
+                offset
+            )));
+        }
+        // A negative count means fetching all the rows.
+        let count = match &fetch.count_mode {
+            None => -1,
+            Some(CountMode::Count(count)) => *count,
+            Some(CountMode::CountExpr(expression)) => self.bind_fetch_value(expression)?,
+        };
+
+        let limit = Limit {
+            before_exchange: false,
+            limit: (count >= 0).then_some(count as usize),
+            offset: offset as usize,
+        };
+        Ok(BoundRel {
+            s_expr: SExpr::create_unary(Arc::new(limit.into()), Arc::new(input.s_expr)),
+            columns: input.columns,
+        })
+    }
+
+    fn bind_fetch_value(&mut self, expression: &Expression) -> Result<i64> {
+        let value = match self.bind_expression(expression, &[])? {
+            ScalarExpr::ConstantExpr(ConstantExpr {
+                value: Scalar::Number(number),
+                ..
+            }) => number.integer_to_i128(),
+            _ => None,
+        };
+        value
+            .and_then(|value| i64::try_from(value).ok())
+            .ok_or_else(|| {
+                ErrorCode::Unimplemented(
+                    "Substrait fetch relation only supports integer literals for offset and count",
+                )
+            })
+    }
+
+    fn bind_expression(
+        &mut self,
+        expression: &Expression,
+        columns: &[ColumnBinding],
+    ) -> Result<ScalarExpr> {
+        let rex_type = expression
+            .rex_type
+            .as_ref()
+            .ok_or_else(|| missing_field("rex_type"))?;
+
+        match rex_type {
+            RexType::Literal(literal) => bind_literal(literal),
+            RexType::Selection(reference) => bind_field_reference(reference, columns),
+            RexType::ScalarFunction(function) => {
+                let func_name = self.function_name(function.function_reference)?;
+                let arguments = self.bind_arguments(&function.arguments, columns)?;
+                function_call(&func_name, arguments)
+            }
+            RexType::Cast(cast) => {
+                let input = cast.input.as_ref().ok_or_else(|| missing_field("input"))?;
+                let target_type = cast.r#type.as_ref().ok_or_else(|| missing_field("type"))?;
+                Ok(ScalarExpr::CastExpr(CastExpr {
+                    span: None,
+                    is_try: cast.failure_behavior()
+                        == substrait::proto::expression::cast::FailureBehavior::ReturnNull,
+                    argument: Box::new(self.bind_expression(input, columns)?),
+                    target_type: Box::new(bind_data_type(target_type)?),
+                }))
+            }
+            RexType::IfThen(if_then) => {
+                // `if(cond1, then1, cond2, then2, ..., else)`
+                let mut arguments = vec![];
+                for clause in &if_then.ifs {
+                    let condition = clause.r#if.as_ref().ok_or_else(|| missing_field("if"))?;
+                    let then = clause.then.as_ref().ok_or_else(|| missing_field("then"))?;
+                    arguments.push(self.bind_expression(condition, columns)?);
+                    arguments.push(self.bind_expression(then, columns)?);
+                }
+                arguments.push(match &if_then.r#else {
+                    Some(r#else) => self.bind_expression(r#else, columns)?,
+                    None => constant(Scalar::Null),
+                });
+                function_call("if", arguments)
+            }
+            RexType::SingularOrList(list) => {
+                let value = list.value.as_ref().ok_or_else(|| missing_field("value"))?;
+                let value = self.bind_expression(value, columns)?;
+                let mut predicates = vec![];
+                for option in &list.options {
+                    let option = self.bind_expression(option, columns)?;
+                    predicates.push(function_call("eq", vec![value.clone(), option])?);
+                }
+                if predicates.is_empty() {
+                    return Ok(constant(Scalar::Boolean(false)));
+                }
+                function_call("or", predicates)
+            }
+            other => Err(ErrorCode::Unimplemented(format!(
+                "Substrait {} expression is not supported",
+                variant_name(other)
+            ))),
+        }
+    }
+
+    fn bind_arguments(
+        &mut self,
+        arguments: &[FunctionArgument],
+        columns: &[ColumnBinding],
+    ) -> Result<Vec<ScalarExpr>> {
+        arguments
+            .iter()
+            .map(|argument| match &argument.arg_type {
+                Some(ArgType::Value(expression)) => self.bind_expression(expression, columns),
+                _ => Err(ErrorCode::Unimplemented(
+                    "Substrait function only supports value arguments",
+                )),
+            })
+            .collect()
+    }
+
+    fn function_name(&self, function_reference: u32) -> Result<String> {
+        let name = self.functions.get(&function_reference).ok_or_else(|| {
+            ErrorCode::SemanticError(format!(
+                "Function reference {} is not declared in substrait plan",
+                function_reference
+            ))
+        })?;
+
+        // Map the names of Substrait standard extensions to the builtin functions.
+        let name = match name.as_str() {
+            "add" => "plus",
+            "subtract" | "negate" => "minus",
+            "modulus" => "modulo",
+            "equal" => "eq",
+            "not_equal" => "noteq",
+            "any_value" => "any",
+            name => name,
+        };
+        Ok(name.to_string())
+    }
+
+    // Use the column directly if the scalar is a column reference,
+    // otherwise evaluate it to a derived column.
+    fn derive_column(
+        &mut self,
+        scalar: ScalarExpr,
+        items: &mut Vec<ScalarItem>,
+    ) -> Result<ColumnBinding> {
+        if let ScalarExpr::BoundColumnRef(column_ref) = &scalar {
+            return Ok(column_ref.column.clone());
+        }
+
+        let data_type = scalar.data_type()?;
+        let name = format_scalar(&scalar);
+        let index = self.metadata.write().add_derived_column(
+            name.clone(),
+            data_type.clone(),
+            Some(scalar.clone()),
+        );
+        items.push(ScalarItem { scalar, index });
+        Ok(
+            ColumnBindingBuilder::new(name, index, Box::new(data_type), Visibility::Visible)
+                .build(),
+        )
+    }
+}
+
+fn apply_emit(bound: BoundRel, common: &Option<RelCommon>) -> Result<BoundRel> {
+    let Some(EmitKind::Emit(emit)) = common.as_ref().and_then(|c| c.emit_kind.as_ref()) else {
+        return Ok(bound);
+    };
+
+    let columns = emit
+        .output_mapping
+        .iter()
+        .map(|index| field(&bound.columns, *index))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(BoundRel {
+        s_expr: bound.s_expr,
+        columns,
+    })
+}
+
+#[allow(deprecated)]
+fn grouping_expressions<'a>(
+    aggregate: &'a AggregateRel,
+    grouping: &'a Grouping,
+) -> Result<Vec<&'a Expression>> {
+    if grouping.expression_references.is_empty() {
+        return Ok(grouping.grouping_expressions.iter().collect());
+    }
+
+    grouping
+        .expression_references
+        .iter()
+        .map(|reference| {
+            aggregate
+                .grouping_expressions
+                .get(*reference as usize)
+                .ok_or_else(|| {
+                    ErrorCode::SemanticError(format!(
+                        "Grouping expression reference {} is out of range",
+                        reference
+                    ))
+                })
+        })
+        .collect()
+}
+
+fn field(columns: &[ColumnBinding], index: i32) -> Result<ColumnBinding> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| columns.get(index))
+        .cloned()
+        .ok_or_else(|| {
+            ErrorCode::SemanticError(format!(
+                "Field reference {} is out of range, the input has {} columns",
+                index,
+                columns.len()
+            ))
+        })
+}
+
+fn bind_field_reference(
+    reference: &FieldReference,
+    columns: &[ColumnBinding],
+) -> Result<ScalarExpr> {
+    if matches!(reference.root_type, Some(RootType::OuterReference(_))) {
+        return Err(ErrorCode::Unimplemented(
+            "Substrait outer references are not supported",
+        ));
+    }
+
+    match &reference.reference_type {
+        Some(ReferenceType::DirectReference(segment)) => match &segment.reference_type {
+            Some(reference_segment::ReferenceType::StructField(struct_field))
+                if struct_field.child.is_none() =>
+            {
+                Ok(column_ref(field(columns, struct_field.field)?))
+            }
+            _ => Err(ErrorCode::Unimplemented(
+                "Substrait field reference only supports top level fields",
+            )),
+        },
+        _ => Err(ErrorCode::Unimplemented(
+            "Substrait field reference only supports direct references",
+        )),
+    }
+}
+
+fn bind_literal(literal: &Literal) -> Result<ScalarExpr> {
+    let literal_type = literal
+        .literal_type
+        .as_ref()
+        .ok_or_else(|| missing_field("literal_type"))?;
+
+    let value = match literal_type {
+        LiteralType::Boolean(v) => Scalar::Boolean(*v),
+        LiteralType::I8(v) => Scalar::Number(NumberScalar::Int8(*v as i8)),
+        LiteralType::I16(v) => Scalar::Number(NumberScalar::Int16(*v as i16)),
+        LiteralType::I32(v) => Scalar::Number(NumberScalar::Int32(*v)),
+        LiteralType::I64(v) => Scalar::Number(NumberScalar::Int64(*v)),
+        LiteralType::Fp32(v) => Scalar::Number(NumberScalar::Float32(F32::from(*v))),
+        LiteralType::Fp64(v) => Scalar::Number(NumberScalar::Float64(F64::from(*v))),
+        LiteralType::String(v) | LiteralType::FixedChar(v) => Scalar::String(v.clone()),
+        LiteralType::VarChar(v) => Scalar::String(v.value.clone()),
+        LiteralType::Binary(v) | LiteralType::FixedBinary(v) => Scalar::Binary(v.clone()),
+        LiteralType::Date(v) => Scalar::Date(*v),
+        LiteralType::PrecisionTimestamp(v) => {
+            Scalar::Timestamp(timestamp_micros(v.value, v.precision)?)
+        }
+        LiteralType::PrecisionTimestampTz(v) => {
+            Scalar::Timestamp(timestamp_micros(v.value, v.precision)?)
+        }
+        LiteralType::Decimal(v) => {
+            // Decimal values are 16 bytes little-endian two's complement integers.
+            let bytes: [u8; 16] =
+                v.value.as_slice().try_into().map_err(|_| {
+                    ErrorCode::BadBytes("Invalid decimal literal in substrait plan")
+                })?;
+            let size = DecimalSize {
+                precision: v.precision as u8,
+                scale: v.scale as u8,
+            };
+            Scalar::Decimal(DecimalScalar::Decimal128(i128::from_le_bytes(bytes), size))
+        }
+        LiteralType::Null(data_type) => {
+            let target_type = bind_data_type(data_type)?.wrap_nullable();
+            return Ok(ScalarExpr::CastExpr(CastExpr {
+                span: None,
+                is_try: false,
+                argument: Box::new(constant(Scalar::Null)),
+                target_type: Box::new(target_type),
+            }));
+        }
+        other => {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Substrait {} literal is not supported",
+                variant_name(other)
+            )));
+        }
+    };
+
+    Ok(constant(value))
+}
+
+fn bind_data_type(data_type: &Type) -> Result<DataType> {
+    let kind = data_type
+        .kind
+        .as_ref()
+        .ok_or_else(|| missing_field("kind"))?;
+
+    let (data_type, nullability) = match kind {
+        Kind::Bool(t) => (DataType::Boolean, t.nullability),
+        Kind::I8(t) => (DataType::Number(NumberDataType::Int8), t.nullability),
+        Kind::I16(t) => (DataType::Number(NumberDataType::Int16), t.nullability),
+        Kind::I32(t) => (DataType::Number(NumberDataType::Int32), t.nullability),
+        Kind::I64(t) => (DataType::Number(NumberDataType::Int64), t.nullability),
+        Kind::Fp32(t) => (DataType::Number(NumberDataType::Float32), t.nullability),
+        Kind::Fp64(t) => (DataType::Number(NumberDataType::Float64), t.nullability),
+        Kind::String(t) => (DataType::String, t.nullability),
+        Kind::Varchar(t) => (DataType::String, t.nullability),
+        Kind::FixedChar(t) => (DataType::String, t.nullability),
+        Kind::Binary(t) => (DataType::Binary, t.nullability),
+        Kind::FixedBinary(t) => (DataType::Binary, t.nullability),
+        Kind::Date(t) => (DataType::Date, t.nullability),
+        Kind::PrecisionTimestamp(t) => (DataType::Timestamp, t.nullability),
+        Kind::PrecisionTimestampTz(t) => (DataType::Timestamp, t.nullability),
+        Kind::Decimal(t) => {
+            let size = DecimalSize {
+                precision: t.precision as u8,
+                scale: t.scale as u8,
+            };
+            (
+                DataType::Decimal(DecimalDataType::from_size(size)?),
+                t.nullability,
+            )
+        }
+        other => {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Substrait {} type is not supported",
+                variant_name(other)
+            )));
+        }
+    };
+
+    if nullability == Nullability::Nullable as i32 {
+        Ok(data_type.wrap_nullable())
+    } else {
+        Ok(data_type)
+    }
+}
+
+// Timestamps are stored in 10^-precision seconds, convert them to microseconds.
+fn timestamp_micros(value: i64, precision: i32) -> Result<i64> {
+    match precision {
+        0..=6 => Ok(value * 10_i64.pow((6 - precision) as u32)),
+        7..=12 => Ok(value / 10_i64.pow((precision - 6) as u32)),
+        _ => Err(ErrorCode::BadBytes(format!(
+            "Invalid timestamp precision {} in substrait plan",
+            precision
+        ))),
+    }
+}
+
+fn function_call(func_name: &str, arguments: Vec<ScalarExpr>) -> Result<ScalarExpr> {
+    match func_name {
+        // Substrait boolean functions are variadic, but the builtin ones are binary.
+        "and" | "or" => arguments
+            .into_iter()
+            .reduce(|left, right| {
+                ScalarExpr::FunctionCall(FunctionCall {
+                    span: None,
+                    func_name: func_name.to_string(),
+                    params: vec![],
+                    arguments: vec![left, right],
+                })
+            })
+            .ok_or_else(|| {
+                ErrorCode::SemanticError(format!("Function '{}' requires arguments", func_name))
+            }),
+        "between" => {
+            let [value, low, high]: [ScalarExpr; 3] = arguments
+                .try_into()
+                .map_err(|_| ErrorCode::SemanticError("Function 'between' requires 3 arguments"))?;
+            function_call("and", vec![
+                function_call("gte", vec![value.clone(), low])?,
+                function_call("lte", vec![value, high])?,
+            ])
+        }
+        _ => Ok(ScalarExpr::FunctionCall(FunctionCall {
+            span: None,
+            func_name: func_name.to_string(),
+            params: vec![],
+            arguments,
+        })),
+    }
+}
+
+fn split_conjunctions(scalar: ScalarExpr) -> Vec<ScalarExpr> {
+    match scalar {
+        ScalarExpr::FunctionCall(func) if func.func_name == "and" => func
+            .arguments
+            .into_iter()
+            .flat_map(split_conjunctions)
+            .collect(),
+        _ => vec![scalar],
+    }
+}
+
+fn equi_condition(
+    predicate: &ScalarExpr,
+    left_columns: &ColumnSet,
+    right_columns: &ColumnSet,
+) -> Option<JoinEquiCondition> {
+    let ScalarExpr::FunctionCall(func) = predicate else {
+        return None;
+    };
+    let [left, right] = func.arguments.as_slice() else {
+        return None;
+    };
+    if func.func_name != "eq" {
+        return None;
+    }
+
+    let (left_used, right_used) = (left.used_columns(), right.used_columns());
+    if left_used.is_empty() || right_used.is_empty() {
+        return None;
+    }
+    if left_used.is_subset(left_columns) && right_used.is_subset(right_columns) {
+        Some(JoinEquiCondition::new(left.clone(), right.clone(), false))
+    } else if left_used.is_subset(right_columns) && right_used.is_subset(left_columns) {
+        Some(JoinEquiCondition::new(right.clone(), left.clone(), false))
+    } else {
+        None
+    }
+}
+
+fn wrap_nullable_columns(columns: Vec<ColumnBinding>, nullable: bool) -> Vec<ColumnBinding> {
+    if !nullable {
+        return columns;
+    }
+    columns
+        .into_iter()
+        .map(|mut column| {
+            if !column.data_type.is_nullable_or_null() {
+                column.data_type = Box::new(column.data_type.wrap_nullable());
+            }
+            column
+        })
+        .collect()
+}
+
+fn filter_s_expr(input: SExpr, predicate: ScalarExpr) -> SExpr {
+    let filter = Filter {
+        predicates: vec![predicate],
+    };
+    SExpr::create_unary(Arc::new(filter.into()), Arc::new(input))
+}
+
+fn eval_scalar_s_expr(input: SExpr, items: Vec<ScalarItem>) -> SExpr {
+    if items.is_empty() {
+        return input;
+    }
+    SExpr::create_unary(Arc::new(EvalScalar { items }.into()), Arc::new(input))
+}
+
+fn column_ref(column: ColumnBinding) -> ScalarExpr {
+    ScalarExpr::BoundColumnRef(BoundColumnRef { span: None, column })
+}
+
+fn constant(value: Scalar) -> ScalarExpr {
+    ScalarExpr::ConstantExpr(ConstantExpr { span: None, value })
+}
+
+fn missing_field(name: &str) -> ErrorCode {
+    ErrorCode::BadBytes(format!(
+        "Invalid substrait plan: missing required field '{}'",
+        name
+    ))
+}
+
+// The name of a variant of the generated protobuf enums, e.g. `Set` of `RelType::Set(..)`.
+fn variant_name<T: std::fmt::Debug>(value: &T) -> String {
+    let debug = format!("{:?}", value);
+    debug
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
@@ -19,7 +19,12 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use chrono::Duration;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::principal::OwnershipObject;
 use databend_common_meta_app::principal::StageInfo;
+use databend_common_meta_app::schema::CommitTableMetaReq;
+use databend_common_meta_app::schema::DropTableByIdReq;
 use databend_common_meta_app::schema::TableCopiedFileInfo;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
@@ -44,6 +49,9 @@ pub struct TxnManager {
     state: TxnState,
     txn_buffer: TxnBuffer,
     txn_id: String,
+    /// The savepoints of the transaction in the order they are created,
+    /// with the buffer to restore when rolling back to them.
+    savepoints: Vec<(String, TxnBuffer)>,
 }
 
 pub type TxnManagerRef = Arc<Mutex<TxnManager>>;
@@ -69,6 +77,25 @@ pub struct TxnBuffer {
 
     temp_table_desc_to_id: HashMap<String, u64>,
     mutated_temp_tables: HashMap<u64, TempTable>,
+
+    // the version of the tables when they are first read in the transaction
+    read_tables: HashMap<u64, TableInfo>,
+    created_tables: HashMap<u64, CreatedTable>,
+    dropped_tables: HashMap<u64, DroppedTable>,
+}
+
+/// A table created in the transaction, it is invisible to others until the transaction commits.
+#[derive(Debug, Clone)]
+pub struct CreatedTable {
+    pub table_info: TableInfo,
+    pub req: CommitTableMetaReq,
+}
+
+/// A table dropped in the transaction, it is dropped when the transaction commits.
+#[derive(Debug, Clone)]
+pub struct DroppedTable {
+    pub req: DropTableByIdReq,
+    pub owner_object: OwnershipObject,
 }
 
 #[derive(Debug, Clone)]
@@ -131,6 +158,7 @@ impl TxnManager {
             state: TxnState::AutoCommit,
             txn_buffer: TxnBuffer::default(),
             txn_id: "".to_string(),
+            savepoints: vec![],
         }))
     }
 
@@ -149,6 +177,76 @@ impl TxnManager {
         self.state = TxnState::AutoCommit;
         self.txn_buffer.clear();
         self.txn_id = "".to_string();
+        self.savepoints.clear();
+    }
+
+    /// Create a savepoint with the changes made so far, replacing the savepoint of the same name.
+    pub fn create_savepoint(&mut self, name: &str) -> Result<()> {
+        self.check_savepoint_allowed("SAVEPOINT")?;
+        if self.is_fail() {
+            return Err(ErrorCode::CurrentTransactionIsAborted(
+                "current transaction is aborted, commands ignored until end of transaction block",
+            ));
+        }
+        self.savepoints.retain(|(savepoint, _)| savepoint != name);
+        self.savepoints
+            .push((name.to_string(), self.txn_buffer.clone()));
+        Ok(())
+    }
+
+    /// Discard the changes made after the savepoint, the savepoint is kept.
+    ///
+    /// A failed transaction becomes active again, as the failed statement is discarded.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> Result<()> {
+        self.check_savepoint_allowed("ROLLBACK TO SAVEPOINT")?;
+        let position = self.savepoint_position(name)?;
+        self.savepoints.truncate(position + 1);
+
+        // The tables keep the versions read, to read the same data as before.
+        let read_tables = std::mem::take(&mut self.txn_buffer.read_tables);
+        self.txn_buffer = self.savepoints[position].1.clone();
+        for (table_id, table) in &read_tables {
+            if !self.txn_buffer.dropped_tables.contains_key(table_id) {
+                self.txn_buffer
+                    .table_desc_to_id
+                    .entry(table.desc.clone())
+                    .or_insert(*table_id);
+            }
+        }
+        self.txn_buffer.read_tables = read_tables;
+        self.state = TxnState::Active;
+        Ok(())
+    }
+
+    /// Remove the savepoint and the savepoints created after it, the changes are kept.
+    pub fn release_savepoint(&mut self, name: &str) -> Result<()> {
+        self.check_savepoint_allowed("RELEASE SAVEPOINT")?;
+        if self.is_fail() {
+            return Err(ErrorCode::CurrentTransactionIsAborted(
+                "current transaction is aborted, commands ignored until end of transaction block",
+            ));
+        }
+        let position = self.savepoint_position(name)?;
+        self.savepoints.truncate(position);
+        Ok(())
+    }
+
+    fn check_savepoint_allowed(&self, command: &str) -> Result<()> {
+        if matches!(self.state, TxnState::AutoCommit) {
+            return Err(ErrorCode::NoActiveTransaction(format!(
+                "{command} can only be used in transaction blocks"
+            )));
+        }
+        Ok(())
+    }
+
+    fn savepoint_position(&self, name: &str) -> Result<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|(savepoint, _)| savepoint == name)
+            .ok_or_else(|| {
+                ErrorCode::UnknownSavepoint(format!("savepoint '{name}' does not exist"))
+            })
     }
 
     pub fn set_fail(&mut self) {
@@ -215,6 +313,95 @@ impl TxnManager {
             .cloned()
     }
 
+    /// Keep the version of a table read in the transaction, the table is read at
+    /// the same version in the transaction, unless the transaction changes it.
+    pub fn pin_read_table(&mut self, table: TableInfo) {
+        let table_id = table.ident.table_id;
+        if let Entry::Vacant(e) = self.txn_buffer.read_tables.entry(table_id) {
+            self.txn_buffer
+                .table_desc_to_id
+                .insert(table.desc.clone(), table_id);
+            e.insert(table);
+        }
+    }
+
+    /// The version of the table when it is first read in the transaction.
+    pub fn get_read_table(&self, table_id: u64) -> Option<TableInfo> {
+        self.txn_buffer.read_tables.get(&table_id).cloned()
+    }
+
+    /// The table is created in the transaction, it is made visible when the transaction commits.
+    pub fn add_created_table(&mut self, table_info: TableInfo, req: CommitTableMetaReq) {
+        let table_id = table_info.ident.table_id;
+        self.txn_buffer
+            .table_desc_to_id
+            .insert(table_info.desc.clone(), table_id);
+        self.txn_buffer
+            .created_tables
+            .insert(table_id, CreatedTable { table_info, req });
+    }
+
+    /// The table is dropped in the transaction, it is dropped when the transaction commits.
+    ///
+    /// A table created in the transaction is not made visible instead, returns true in this case.
+    pub fn add_dropped_table(
+        &mut self,
+        req: DropTableByIdReq,
+        owner_object: OwnershipObject,
+    ) -> bool {
+        let table_id = req.tb_id;
+        let desc = format!("'{}'.'{}'", req.db_name, req.table_name);
+        if self.remove_created_table(table_id).is_some() {
+            return true;
+        }
+        if self.txn_buffer.table_desc_to_id.get(&desc) == Some(&table_id) {
+            self.txn_buffer.table_desc_to_id.remove(&desc);
+        }
+        self.txn_buffer.mutated_tables.remove(&table_id);
+        self.txn_buffer.copied_files.remove(&table_id);
+        self.txn_buffer
+            .dropped_tables
+            .insert(table_id, DroppedTable { req, owner_object });
+        false
+    }
+
+    /// Discard a table created in the transaction, with the changes made to it.
+    pub fn remove_created_table(&mut self, table_id: u64) -> Option<CreatedTable> {
+        let created = self.txn_buffer.created_tables.remove(&table_id)?;
+        self.txn_buffer
+            .table_desc_to_id
+            .remove(&created.table_info.desc);
+        self.txn_buffer.mutated_tables.remove(&table_id);
+        self.txn_buffer.copied_files.remove(&table_id);
+        Some(created)
+    }
+
+    pub fn get_created_table(&self, db_name: &str, table_name: &str) -> Option<CreatedTable> {
+        let desc = format!("'{}'.'{}'", db_name, table_name);
+        self.txn_buffer
+            .table_desc_to_id
+            .get(&desc)
+            .and_then(|id| self.txn_buffer.created_tables.get(id))
+            .cloned()
+    }
+
+    /// Whether the table of the name is dropped in the transaction, and not created again.
+    pub fn is_table_dropped(&self, db_name: &str, table_name: &str) -> bool {
+        let desc = format!("'{}'.'{}'", db_name, table_name);
+        !self.txn_buffer.table_desc_to_id.contains_key(&desc)
+            && self.txn_buffer.dropped_tables.values().any(|dropped| {
+                dropped.req.db_name == db_name && dropped.req.table_name == table_name
+            })
+    }
+
+    pub fn created_tables(&self) -> Vec<CreatedTable> {
+        self.txn_buffer.created_tables.values().cloned().collect()
+    }
+
+    pub fn dropped_tables(&self) -> Vec<DroppedTable> {
+        self.txn_buffer.dropped_tables.values().cloned().collect()
+    }
+
     pub fn get_table_from_buffer(
         &self,
         _tenant: &Tenant,
@@ -239,7 +426,18 @@ impl TxnManager {
         self.txn_buffer
             .table_desc_to_id
             .get(&desc)
-            .and_then(|id| self.txn_buffer.mutated_tables.get(id))
+            .and_then(|id| {
+                self.txn_buffer
+                    .mutated_tables
+                    .get(id)
+                    .or_else(|| {
+                        self.txn_buffer
+                            .created_tables
+                            .get(id)
+                            .map(|created| &created.table_info)
+                    })
+                    .or_else(|| self.txn_buffer.read_tables.get(id))
+            })
             .cloned()
     }
 
@@ -316,6 +514,18 @@ impl TxnManager {
                     desc: format!("'{}'.'{}'", t.db_name, t.table_name),
                 })
                 .collect(),
+            drop_tables: self
+                .txn_buffer
+                .dropped_tables
+                .values()
+                .map(|dropped| dropped.req.clone())
+                .collect(),
+            commit_tables: self
+                .txn_buffer
+                .created_tables
+                .values()
+                .map(|created| created.req.clone())
+                .collect(),
         }
     }
 
@@ -0,0 +1,149 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+
+// The constraints of a table, stored as a JSON of `Vec<TableConstraint>`.
+pub const OPT_KEY_CONSTRAINTS: &str = "constraints";
+
+pub const CONSTRAINT_TYPE_PRIMARY_KEY: &str = "PRIMARY KEY";
+pub const CONSTRAINT_TYPE_UNIQUE: &str = "UNIQUE";
+pub const CONSTRAINT_TYPE_CHECK: &str = "CHECK";
+pub const CONSTRAINT_TYPE_FOREIGN_KEY: &str = "FOREIGN KEY";
+
+/// A named constraint of a table.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TableConstraint {
+    pub name: String,
+    pub kind: TableConstraintKind,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TableConstraintKind {
+    /// The key columns are unique and not null, at most one per table.
+    PrimaryKey { columns: Vec<String> },
+    /// The key columns are unique, rows with a NULL key column never conflict.
+    Unique { columns: Vec<String> },
+    /// The rows for which the expression is false are rejected, `columns` are the columns
+    /// referenced by the expression.
+    Check { expr: String, columns: Vec<String> },
+    /// The key columns reference a primary key or unique constraint of another table.
+    ///
+    /// The constraint is informational, it is not enforced but trusted by the optimizer.
+    ForeignKey {
+        columns: Vec<String>,
+        ref_database: String,
+        ref_table: String,
+        ref_columns: Vec<String>,
+    },
+}
+
+impl TableConstraint {
+    pub fn constraint_type(&self) -> &'static str {
+        match &self.kind {
+            TableConstraintKind::PrimaryKey { .. } => CONSTRAINT_TYPE_PRIMARY_KEY,
+            TableConstraintKind::Unique { .. } => CONSTRAINT_TYPE_UNIQUE,
+            TableConstraintKind::Check { .. } => CONSTRAINT_TYPE_CHECK,
+            TableConstraintKind::ForeignKey { .. } => CONSTRAINT_TYPE_FOREIGN_KEY,
+        }
+    }
+
+    /// The columns of the key if it is a primary key or unique constraint.
+    pub fn key_columns(&self) -> Option<&[String]> {
+        match &self.kind {
+            TableConstraintKind::PrimaryKey { columns }
+            | TableConstraintKind::Unique { columns } => Some(columns),
+            TableConstraintKind::Check { .. } | TableConstraintKind::ForeignKey { .. } => None,
+        }
+    }
+
+    /// The columns of the table referenced by the constraint.
+    pub fn columns(&self) -> &[String] {
+        match &self.kind {
+            TableConstraintKind::PrimaryKey { columns }
+            | TableConstraintKind::Unique { columns }
+            | TableConstraintKind::Check { columns, .. }
+            | TableConstraintKind::ForeignKey { columns, .. } => columns,
+        }
+    }
+
+    /// Whether the constraint is checked when the rows of the table are written.
+    pub fn is_enforced(&self) -> bool {
+        !matches!(self.kind, TableConstraintKind::ForeignKey { .. })
+    }
+
+    pub fn is_primary_key(&self) -> bool {
+        matches!(self.kind, TableConstraintKind::PrimaryKey { .. })
+    }
+
+    pub fn is_check(&self) -> bool {
+        matches!(self.kind, TableConstraintKind::Check { .. })
+    }
+
+    /// The expression if it is a check constraint.
+    pub fn check_expr(&self) -> Option<&str> {
+        match &self.kind {
+            TableConstraintKind::Check { expr, .. } => Some(expr),
+            _ => None,
+        }
+    }
+
+    /// Whether the constraint references the column.
+    pub fn references_column(&self, column: &str) -> bool {
+        self.columns().iter().any(|c| c == column)
+    }
+
+    pub fn rename_column(&mut self, old_column: &str, new_column: &str) {
+        match &mut self.kind {
+            TableConstraintKind::PrimaryKey { columns }
+            | TableConstraintKind::Unique { columns }
+            | TableConstraintKind::ForeignKey { columns, .. } => {
+                for column in columns.iter_mut().filter(|c| *c == old_column) {
+                    *column = new_column.to_string();
+                }
+            }
+            // the expression is not rewritten, renaming a column referenced by it is rejected
+            TableConstraintKind::Check { .. } => {}
+        }
+    }
+}
+
+/// Get the constraints of a table from its options.
+pub fn get_table_constraints(options: &BTreeMap<String, String>) -> Result<Vec<TableConstraint>> {
+    match options.get(OPT_KEY_CONSTRAINTS) {
+        Some(constraints) => serde_json::from_str(constraints).map_err(|e| {
+            ErrorCode::Internal(format!("Invalid constraints of table: {e}, {constraints}"))
+        }),
+        None => Ok(vec![]),
+    }
+}
+
+/// Set the constraints of a table to its options, the option is removed if there is none.
+pub fn set_table_constraints(
+    options: &mut BTreeMap<String, String>,
+    constraints: &[TableConstraint],
+) -> Result<()> {
+    if constraints.is_empty() {
+        options.remove(OPT_KEY_CONSTRAINTS);
+    } else {
+        options.insert(
+            OPT_KEY_CONSTRAINTS.to_string(),
+            serde_json::to_string(constraints)?,
+        );
+    }
+    Ok(())
+}
@@ -12,9 +12,57 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::DateTime;
+use chrono::Utc;
+
 pub const OPT_KEY_AS_QUERY: &str = "as_query";
 pub const OPT_KEY_TARGET_LAG: &str = "target_lag";
 pub const OPT_KEY_REFRESH_MODE: &str = "refresh_mode";
 pub const OPT_KEY_INITIALIZE: &str = "initialize";
 pub const OPT_KEY_WAREHOUSE: &str = "warehouse";
 pub const OPT_KEY_LIFECYCLE: &str = "lifecycle";
+// The refresh state of a dynamic table, stored as a JSON of `DynamicTableRefreshState`.
+pub const OPT_KEY_REFRESH_STATE: &str = "refresh_state";
+
+pub const REFRESH_ACTION_FULL: &str = "FULL";
+pub const REFRESH_ACTION_INCREMENTAL: &str = "INCREMENTAL";
+pub const REFRESH_ACTION_NO_DATA: &str = "NO_DATA";
+
+/// The state of a dynamic table kept by its refreshes.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DynamicTableRefreshState {
+    /// The tables read by the query of the dynamic table.
+    pub sources: Vec<DynamicTableSource>,
+    /// The snapshot of the dynamic table written by the last refresh.
+    ///
+    /// If the table has another snapshot, the offsets of the sources can not be trusted,
+    /// and the next refresh is a full refresh.
+    pub snapshot_location: Option<String>,
+    /// When the sources were read by the last successful refresh.
+    pub data_timestamp: Option<DateTime<Utc>>,
+    /// When the last successful refresh finished.
+    pub refreshed_at: Option<DateTime<Utc>>,
+    /// `FULL`, `INCREMENTAL` or `NO_DATA`, the action of the last successful refresh.
+    pub refresh_action: Option<String>,
+    /// The number of successful refreshes writing the table.
+    pub refresh_count: u64,
+    /// The error of the last refresh if it failed.
+    pub last_error: Option<String>,
+}
+
+/// A source table of a dynamic table, along with the offset it has been refreshed to.
+///
+/// Like a stream, the offset is a snapshot of the source table, the changes after it are
+/// the changes not applied to the dynamic table yet.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DynamicTableSource {
+    pub catalog: String,
+    pub database: String,
+    pub table: String,
+    pub table_id: u64,
+    /// The id of the snapshot read by the last refresh, None if the table was empty.
+    pub snapshot_id: Option<String>,
+    /// Whether the changes after the snapshot can be read with `CHANGES`,
+    /// i.e., the snapshot is committed after change tracking is enabled.
+    pub trackable: bool,
+}
@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod constraint_keys;
 mod dynamic_table_keys;
 mod stream_keys;
 mod table_compression;
 mod table_keys;
 mod table_prefix;
 
+pub use constraint_keys::*;
 pub use dynamic_table_keys::*;
 pub use stream_keys::*;
 pub use table_compression::TableCompression;
@@ -16,6 +16,10 @@ use std::collections::HashSet;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::sync::LazyLock;
+
+use crate::table::OPT_KEY_CONSTRAINTS;
+use crate::table::OPT_KEY_REFRESH_STATE;
+
 pub const OPT_KEY_DATABASE_ID: &str = "database_id";
 pub const OPT_KEY_STORAGE_PREFIX: &str = "storage_prefix";
 pub const OPT_KEY_TEMP_PREFIX: &str = "temp_prefix";
@@ -35,6 +39,12 @@ pub const OPT_KEY_TABLE_ATTACHED_DATA_URI: &str = "table_data_uri";
 // the following are used in for delta and iceberg engine
 pub const OPT_KEY_LOCATION: &str = "location";
 pub const OPT_KEY_CONNECTION_NAME: &str = "connection_name";
+// The partition spec of an iceberg table, e.g. `day(ts), bucket[16](id)`.
+pub const OPT_KEY_PARTITION_BY: &str = "partition_by";
+// The historical version of a delta table to read, e.g. `version_as_of = 3`.
+pub const OPT_KEY_VERSION_AS_OF: &str = "version_as_of";
+// Read the latest version of a delta table committed at or before the timestamp.
+pub const OPT_KEY_TIMESTAMP_AS_OF: &str = "timestamp_as_of";
 // TableMeta need to contain all info needed to create a Table, store them under this internal key as a JSON.
 // e.g. the partition columns of a Delta table
 pub const OPT_KEY_ENGINE_META: &str = "engine_meta";
@@ -66,6 +76,8 @@ pub static RESERVED_TABLE_OPTION_KEYS: LazyLock<HashSet<&'static str>> = LazyLoc
     let mut r = HashSet::new();
     r.insert(OPT_KEY_DATABASE_ID);
     r.insert(OPT_KEY_LEGACY_SNAPSHOT_LOC);
+    r.insert(OPT_KEY_REFRESH_STATE);
+    r.insert(OPT_KEY_CONSTRAINTS);
     r
 });
 
@@ -77,6 +89,8 @@ pub static INTERNAL_TABLE_OPTION_KEYS: LazyLock<HashSet<&'static str>> = LazyLoc
     r.insert(OPT_KEY_ENGINE_META);
     r.insert(OPT_KEY_CHANGE_TRACKING_BEGIN_VER);
     r.insert(OPT_KEY_TEMP_PREFIX);
+    r.insert(OPT_KEY_REFRESH_STATE);
+    r.insert(OPT_KEY_CONSTRAINTS);
     r
 });
 
@@ -0,0 +1,505 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use databend_common_catalog::plan::DataSourcePlan;
+use databend_common_catalog::plan::PartInfo;
+use databend_common_catalog::plan::PartInfoPtr;
+use databend_common_catalog::plan::PartStatistics;
+use databend_common_catalog::plan::Partitions;
+use databend_common_catalog::plan::PartitionsShuffleKind;
+use databend_common_catalog::plan::PushDownInfo;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_args::TableArgs;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::BlockEntry;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchema;
+use databend_common_expression::DataSchemaRef;
+use databend_common_expression::FieldIndex;
+use databend_common_expression::Scalar;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchema;
+use databend_common_expression::TableSchemaRef;
+use databend_common_expression::Value;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_pipeline_core::processors::OutputPort;
+use databend_common_pipeline_core::processors::ProcessorPtr;
+use databend_common_pipeline_core::Pipeline;
+use databend_common_pipeline_sources::AsyncSource;
+use databend_common_pipeline_sources::AsyncSourcer;
+use databend_common_storage::init_operator;
+use databend_common_storages_parquet::ParquetFileReader;
+use databend_common_storages_parquet::ParquetRSFullReader;
+use databend_common_storages_parquet::ParquetRSReaderBuilder;
+use databend_storages_common_table_meta::table::OPT_KEY_LOCATION;
+use deltalake::kernel::Action;
+use deltalake::kernel::DeletionVectorDescriptor;
+use deltalake::logstore::get_actions;
+use deltalake::logstore::LogStoreRef;
+use opendal::Operator;
+use parquet::arrow::async_reader::ParquetRecordBatchStream;
+
+use crate::deletion_vector::read_deletion_vector;
+use crate::table::parse_partition_values;
+use crate::table_source::check_block_schema;
+
+/// The column of the change data files which stores the change type of the rows.
+pub const CHANGE_TYPE_COLUMN: &str = "_change_type";
+pub const COMMIT_VERSION_COLUMN: &str = "_commit_version";
+pub const COMMIT_TIMESTAMP_COLUMN: &str = "_commit_timestamp";
+
+/// The change data feed of a delta table between two versions.
+///
+/// The changes of a commit are read from its change data files if it has any, otherwise
+/// the added files are read as inserted rows and the removed files as deleted rows.
+pub struct DeltaChangesTable {
+    info: TableInfo,
+    source_schema: TableSchemaRef,
+    partition_fields: Vec<TableField>,
+    log_store: LogStoreRef,
+    start: i64,
+    end: i64,
+}
+
+impl DeltaChangesTable {
+    pub fn create(
+        source: &TableInfo,
+        partition_fields: Vec<TableField>,
+        log_store: LogStoreRef,
+        start: i64,
+        end: i64,
+    ) -> Self {
+        let source_schema = source.meta.schema.clone();
+        let mut fields = source_schema.fields().clone();
+        fields.push(TableField::new(
+            CHANGE_TYPE_COLUMN,
+            TableDataType::String.wrap_nullable(),
+        ));
+        fields.push(TableField::new(
+            COMMIT_VERSION_COLUMN,
+            TableDataType::Number(NumberDataType::Int64),
+        ));
+        fields.push(TableField::new(
+            COMMIT_TIMESTAMP_COLUMN,
+            TableDataType::Timestamp,
+        ));
+
+        let mut info = source.clone();
+        info.desc = format!("{} changes from version {start} to {end}", source.desc);
+        info.meta.schema = Arc::new(TableSchema::new(fields));
+        Self {
+            info,
+            source_schema,
+            partition_fields,
+            log_store,
+            start,
+            end,
+        }
+    }
+
+    #[async_backtrace::framed]
+    async fn read_changes(&self) -> Result<Vec<DeltaChangePartInfo>> {
+        let mut parts = vec![];
+        for version in self.start..=self.end {
+            let commit = self
+                .log_store
+                .read_commit_entry(version)
+                .await
+                .map_err(|err| {
+                    ErrorCode::ReadTableDataError(format!(
+                        "Read version {version} of delta table {} failed: {err:?}",
+                        self.info.name
+                    ))
+                })?
+                .ok_or_else(|| {
+                    ErrorCode::TableHistoricalDataNotFound(format!(
+                        "Version {version} of delta table {} not found",
+                        self.info.name
+                    ))
+                })?;
+            let actions = get_actions(version, commit).await.map_err(|err| {
+                ErrorCode::ReadTableDataError(format!(
+                    "Parse version {version} of delta table {} failed: {err:?}",
+                    self.info.name
+                ))
+            })?;
+
+            let commit_timestamp = actions
+                .iter()
+                .find_map(|action| match action {
+                    Action::CommitInfo(info) => info.timestamp,
+                    _ => None,
+                })
+                .unwrap_or_default()
+                * 1000;
+            let has_change_data = actions
+                .iter()
+                .any(|action| matches!(action, Action::Cdc(_)));
+            for action in actions {
+                let part = match action {
+                    Action::Cdc(cdc) => DeltaChangePartInfo {
+                        partition_values: parse_partition_values(
+                            |name| cdc.partition_values.get(name),
+                            &self.partition_fields,
+                        )?,
+                        path: cdc.path,
+                        size: Some(cdc.size as u64),
+                        deletion_vector: None,
+                        change_type: None,
+                        commit_version: version,
+                        commit_timestamp,
+                    },
+                    Action::Add(add) if !has_change_data && add.data_change => {
+                        DeltaChangePartInfo {
+                            partition_values: parse_partition_values(
+                                |name| add.partition_values.get(name),
+                                &self.partition_fields,
+                            )?,
+                            path: add.path,
+                            size: Some(add.size as u64),
+                            deletion_vector: add.deletion_vector,
+                            change_type: Some("insert".to_string()),
+                            commit_version: version,
+                            commit_timestamp,
+                        }
+                    }
+                    Action::Remove(remove) if !has_change_data && remove.data_change => {
+                        DeltaChangePartInfo {
+                            partition_values: parse_partition_values(
+                                |name| remove.partition_values.as_ref().and_then(|v| v.get(name)),
+                                &self.partition_fields,
+                            )?,
+                            path: remove.path,
+                            size: remove.size.map(|v| v as u64),
+                            deletion_vector: remove.deletion_vector,
+                            change_type: Some("delete".to_string()),
+                            commit_version: version,
+                            commit_timestamp,
+                        }
+                    }
+                    _ => continue,
+                };
+                parts.push(part);
+            }
+        }
+        Ok(parts)
+    }
+
+    fn build_reader(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        op: Operator,
+        with_change_type: bool,
+    ) -> Result<Arc<ParquetRSFullReader>> {
+        let mut fields = self
+            .source_schema
+            .fields()
+            .iter()
+            .filter(|field| !self.partition_fields.iter().any(|p| p.name == field.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        if with_change_type {
+            fields.push(TableField::new(
+                CHANGE_TYPE_COLUMN,
+                TableDataType::String.wrap_nullable(),
+            ));
+        }
+        let table_schema = Arc::new(TableSchema::new(fields));
+        let arrow_schema = table_schema.as_ref().into();
+        let mut builder = ParquetRSReaderBuilder::create(ctx, op, table_schema, arrow_schema)?;
+        Ok(Arc::new(builder.build_full_reader(false)?))
+    }
+}
+
+#[async_trait]
+impl Table for DeltaChangesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.info
+    }
+
+    #[async_backtrace::framed]
+    async fn read_partitions(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        _push_downs: Option<PushDownInfo>,
+        _dry_run: bool,
+    ) -> Result<(PartStatistics, Partitions)> {
+        let changes = self.read_changes().await?;
+        let read_bytes = changes
+            .iter()
+            .map(|v| v.size.unwrap_or_default() as usize)
+            .sum();
+        let parts = changes
+            .into_iter()
+            .map(|v| Arc::new(Box::new(v) as Box<dyn PartInfo>))
+            .collect::<Vec<_>>();
+        Ok((
+            PartStatistics::new_estimated(None, 0, read_bytes, parts.len(), parts.len()),
+            Partitions::create(PartitionsShuffleKind::Seq, parts),
+        ))
+    }
+
+    fn read_data(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        plan: &DataSourcePlan,
+        pipeline: &mut Pipeline,
+        _put_cache: bool,
+    ) -> Result<()> {
+        let sp = self.info.meta.storage_params.as_ref().ok_or_else(|| {
+            ErrorCode::BadArguments(format!(
+                "Delta table {} must have storage parameters",
+                self.info.name
+            ))
+        })?;
+        let op = init_operator(sp)?;
+        let data_reader = self.build_reader(ctx.clone(), op.clone(), false)?;
+        let change_data_reader = self.build_reader(ctx.clone(), op.clone(), true)?;
+        let location = self
+            .info
+            .meta
+            .options
+            .get(OPT_KEY_LOCATION)
+            .cloned()
+            .unwrap_or_default();
+
+        // Partition columns are inserted at their positions in the source schema.
+        let partition_columns = self
+            .source_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, field)| {
+                self.partition_fields
+                    .iter()
+                    .position(|p| p.name == field.name)
+                    .map(|pi| (index, pi))
+            })
+            .collect::<Vec<_>>();
+
+        let output_schema = Arc::new(DataSchema::from(plan.schema()));
+        let max_threads = ctx.get_settings().get_max_threads()? as usize;
+        let max_threads = std::cmp::min(plan.parts.len(), max_threads);
+        pipeline.add_source(
+            |output| {
+                DeltaChangesSource::create(ctx.clone(), output, DeltaChangesSource {
+                    ctx: ctx.clone(),
+                    op: op.clone(),
+                    location: location.clone(),
+                    data_reader: data_reader.clone(),
+                    change_data_reader: change_data_reader.clone(),
+                    partition_fields: self.partition_fields.clone(),
+                    partition_columns: partition_columns.clone(),
+                    output_schema: output_schema.clone(),
+                    current: None,
+                })
+            },
+            max_threads.max(1),
+        )
+    }
+
+    fn table_args(&self) -> Option<TableArgs> {
+        None
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct DeltaChangePartInfo {
+    pub path: String,
+    /// The size of the file, `None` if it's not recorded in the remove action.
+    pub size: Option<u64>,
+    pub partition_values: Vec<Scalar>,
+    pub deletion_vector: Option<DeletionVectorDescriptor>,
+    /// The change type of all the rows in the file, `None` for change data files which
+    /// store the change type of each row.
+    pub change_type: Option<String>,
+    pub commit_version: i64,
+    /// The commit timestamp in microseconds.
+    pub commit_timestamp: i64,
+}
+
+impl DeltaChangePartInfo {
+    pub fn from_part(info: &PartInfoPtr) -> Result<&DeltaChangePartInfo> {
+        info.as_any()
+            .downcast_ref::<DeltaChangePartInfo>()
+            .ok_or_else(|| {
+                ErrorCode::Internal("Cannot downcast from PartInfo to DeltaChangePartInfo.")
+            })
+    }
+}
+
+#[typetag::serde(name = "delta_change")]
+impl PartInfo for DeltaChangePartInfo {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn equals(&self, info: &Box<dyn PartInfo>) -> bool {
+        info.as_any()
+            .downcast_ref::<DeltaChangePartInfo>()
+            .is_some_and(|other| self == other)
+    }
+
+    fn hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.path.hash(&mut s);
+        self.commit_version.hash(&mut s);
+        s.finish()
+    }
+}
+
+struct DeltaChangesSource {
+    ctx: Arc<dyn TableContext>,
+
+    // Used to read the files.
+    op: Operator,
+    location: String,
+    data_reader: Arc<ParquetRSFullReader>,
+    change_data_reader: Arc<ParquetRSFullReader>,
+
+    partition_fields: Vec<TableField>,
+    // The index in the output schema and the index in the partition fields.
+    partition_columns: Vec<(FieldIndex, usize)>,
+    output_schema: DataSchemaRef,
+
+    current: Option<(
+        DeltaChangePartInfo,
+        ParquetRecordBatchStream<ParquetFileReader>,
+    )>,
+}
+
+impl DeltaChangesSource {
+    fn create(
+        ctx: Arc<dyn TableContext>,
+        output: Arc<OutputPort>,
+        inner: DeltaChangesSource,
+    ) -> Result<ProcessorPtr> {
+        AsyncSourcer::create(ctx, output, inner)
+    }
+
+    fn reader(&self, part: &DeltaChangePartInfo) -> &Arc<ParquetRSFullReader> {
+        match part.change_type {
+            Some(_) => &self.data_reader,
+            None => &self.change_data_reader,
+        }
+    }
+
+    async fn open(
+        &self,
+        part: &DeltaChangePartInfo,
+    ) -> Result<ParquetRecordBatchStream<ParquetFileReader>> {
+        let size = match part.size {
+            Some(size) => size,
+            None => self.op.stat(&part.path).await?.content_length(),
+        };
+        let deleted_rows = match &part.deletion_vector {
+            Some(dv) => Some(read_deletion_vector(&self.op, &self.location, dv).await?),
+            None => None,
+        };
+        let partition_fields = self
+            .partition_fields
+            .iter()
+            .cloned()
+            .zip(part.partition_values.iter().cloned())
+            .collect::<Vec<_>>();
+        self.reader(part)
+            .prepare_data_stream(
+                &part.path,
+                size,
+                Some(&partition_fields),
+                deleted_rows.as_deref(),
+            )
+            .await
+    }
+
+    fn output_block(&self, part: &DeltaChangePartInfo, block: DataBlock) -> Result<DataBlock> {
+        let num_rows = block.num_rows();
+        let mut columns = block.columns().to_vec();
+        let change_type = match &part.change_type {
+            Some(change_type) => BlockEntry::new(
+                DataType::String.wrap_nullable(),
+                Value::Scalar(Scalar::String(change_type.clone())),
+            ),
+            None => columns.pop().ok_or_else(|| {
+                ErrorCode::Internal(format!("Missing {CHANGE_TYPE_COLUMN} in {}", part.path))
+            })?,
+        };
+        for (index, pi) in self.partition_columns.iter() {
+            let field = &self.partition_fields[*pi];
+            columns.insert(
+                *index,
+                BlockEntry::new(
+                    field.data_type().into(),
+                    Value::Scalar(part.partition_values[*pi].clone()),
+                ),
+            );
+        }
+        columns.push(change_type);
+        columns.push(BlockEntry::new(
+            DataType::Number(NumberDataType::Int64),
+            Value::Scalar(Scalar::Number(NumberScalar::Int64(part.commit_version))),
+        ));
+        columns.push(BlockEntry::new(
+            DataType::Timestamp,
+            Value::Scalar(Scalar::Timestamp(part.commit_timestamp)),
+        ));
+        check_block_schema(&self.output_schema, DataBlock::new(columns, num_rows))
+    }
+}
+
+#[async_trait]
+impl AsyncSource for DeltaChangesSource {
+    const NAME: &'static str = "DeltaChangesSource";
+
+    #[async_backtrace::framed]
+    async fn generate(&mut self) -> Result<Option<DataBlock>> {
+        loop {
+            if let Some((part, mut stream)) = self.current.take() {
+                if let Some(block) = self
+                    .reader(&part)
+                    .read_block_from_stream(&mut stream)
+                    .await?
+                {
+                    let block = self.output_block(&part, block)?;
+                    self.current = Some((part, stream));
+                    return Ok(Some(block));
+                }
+            }
+
+            let Some(part) = self.ctx.get_partition() else {
+                return Ok(None);
+            };
+            let part = DeltaChangePartInfo::from_part(&part)?.clone();
+            let stream = self.open(&part).await?;
+            self.current = Some((part, stream));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read the deletion vectors of delta tables.
+//!
+//! See <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#deletion-vector-format>.
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use deltalake::kernel::DeletionVectorDescriptor;
+use deltalake::kernel::StorageType;
+use opendal::Operator;
+use roaring::RoaringTreemap;
+use uuid::Uuid;
+
+const DELETION_VECTOR_MAGIC: u32 = 1681511377;
+
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Read the deletion vector, returns the sorted positions of the deleted rows.
+///
+/// `location` is the root location of the table, which is used to resolve absolute paths.
+pub async fn read_deletion_vector(
+    op: &Operator,
+    location: &str,
+    dv: &DeletionVectorDescriptor,
+) -> Result<Vec<u64>> {
+    let size = dv.size_in_bytes as usize;
+    let data = match dv.storage_type {
+        StorageType::Inline => {
+            let mut data = z85_decode(&dv.path_or_inline_dv)?;
+            data.truncate(size);
+            data
+        }
+        StorageType::UuidRelativePath | StorageType::AbsolutePath => {
+            let path = deletion_vector_path(location, dv)?;
+            // The deletion vector is prefixed with its size in big endian.
+            let offset = dv.offset.unwrap_or(1) as u64;
+            let buffer = op
+                .read_with(&path)
+                .range(offset..offset + 4 + size as u64)
+                .await?
+                .to_vec();
+            if buffer.len() != 4 + size
+                || u32::from_be_bytes(buffer[0..4].try_into().unwrap()) as usize != size
+            {
+                return Err(ErrorCode::StorageOther(format!(
+                    "Invalid deletion vector in {path} at offset {offset}"
+                )));
+            }
+            buffer[4..].to_vec()
+        }
+    };
+
+    if data.len() < 4 || u32::from_le_bytes(data[0..4].try_into().unwrap()) != DELETION_VECTOR_MAGIC
+    {
+        return Err(ErrorCode::StorageOther(format!(
+            "Invalid deletion vector {}: wrong magic number",
+            dv.path_or_inline_dv
+        )));
+    }
+    let bitmap = RoaringTreemap::deserialize_from(&data[4..]).map_err(|err| {
+        ErrorCode::StorageOther(format!(
+            "Invalid deletion vector {}: {err}",
+            dv.path_or_inline_dv
+        ))
+    })?;
+    Ok(bitmap.into_iter().collect())
+}
+
+/// The path of the deletion vector file relative to the table `location`.
+pub fn deletion_vector_path(location: &str, dv: &DeletionVectorDescriptor) -> Result<String> {
+    match dv.storage_type {
+        StorageType::UuidRelativePath => {
+            // `<random prefix><base85 encoded uuid>`
+            let encoded = &dv.path_or_inline_dv;
+            if encoded.len() < 20 {
+                return Err(ErrorCode::StorageOther(format!(
+                    "Invalid deletion vector path {encoded}"
+                )));
+            }
+            let (prefix, uuid) = encoded.split_at(encoded.len() - 20);
+            let uuid = Uuid::from_slice(&z85_decode(uuid)?).map_err(|err| {
+                ErrorCode::StorageOther(format!("Invalid deletion vector path {encoded}: {err}"))
+            })?;
+            Ok(if prefix.is_empty() {
+                format!("deletion_vector_{uuid}.bin")
+            } else {
+                format!("{prefix}/deletion_vector_{uuid}.bin")
+            })
+        }
+        StorageType::AbsolutePath => {
+            let location = location.trim_end_matches('/');
+            dv.path_or_inline_dv
+                .strip_prefix(location)
+                .map(|path| path.trim_start_matches('/').to_string())
+                .ok_or_else(|| {
+                    ErrorCode::Unimplemented(format!(
+                        "Deletion vector {} outside of the table location {location} is not supported",
+                        dv.path_or_inline_dv
+                    ))
+                })
+        }
+        StorageType::Inline => unreachable!("inline deletion vector has no path"),
+    }
+}
+
+/// Decode the [Z85](https://rfc.zeromq.org/spec/32/) encoded string of the deletion vectors.
+pub fn z85_decode(encoded: &str) -> Result<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    if encoded.len() % 5 != 0 {
+        return Err(ErrorCode::StorageOther(format!(
+            "Invalid z85 encoded string of length {}",
+            encoded.len()
+        )));
+    }
+
+    let mut decoded = Vec::with_capacity(encoded.len() / 5 * 4);
+    for chunk in encoded.chunks(5) {
+        let mut value = 0u32;
+        for c in chunk {
+            let digit = Z85_ALPHABET.iter().position(|v| v == c).ok_or_else(|| {
+                ErrorCode::StorageOther(format!("Invalid z85 character {}", *c as char))
+            })?;
+            value = value
+                .checked_mul(85)
+                .and_then(|v| v.checked_add(digit as u32))
+                .ok_or_else(|| ErrorCode::StorageOther("Invalid z85 encoded string"))?;
+        }
+        decoded.extend_from_slice(&value.to_be_bytes());
+    }
+    Ok(decoded)
+}
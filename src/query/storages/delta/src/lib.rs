@@ -15,8 +15,16 @@
 #![feature(impl_trait_in_assoc_type)]
 #![allow(clippy::diverging_sub_expression)]
 
+mod changes;
+mod deletion_vector;
 mod partition;
 mod table;
+mod table_commit;
+mod table_sink;
 mod table_source;
 
+pub use deletion_vector::deletion_vector_path;
+pub use deletion_vector::read_deletion_vector;
+pub use deletion_vector::z85_decode;
 pub use table::DeltaTable;
+pub use table::DeltaVersion;
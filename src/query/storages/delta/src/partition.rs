@@ -20,12 +20,15 @@ use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::Scalar;
 use databend_common_storages_parquet::ParquetPart;
+use deltalake::kernel::DeletionVectorDescriptor;
 
 /// only support parquet for now: https://github.com/delta-io/delta/issues/87
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct DeltaPartInfo {
     pub data: ParquetPart,
     pub partition_values: Vec<Scalar>,
+    /// The rows deleted from the data file.
+    pub deletion_vector: Option<DeletionVectorDescriptor>,
 }
 
 impl DeltaPartInfo {
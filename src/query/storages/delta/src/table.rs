@@ -13,10 +13,14 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use arrow_schema::Schema as ArrowSchema;
 use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::NaiveDateTime;
+use chrono::Utc;
 use databend_common_catalog::catalog::StorageDescription;
 use databend_common_catalog::partition_columns::get_pushdown_without_partition_columns;
 use databend_common_catalog::partition_columns::str_to_scalar;
@@ -27,8 +31,11 @@ use databend_common_catalog::plan::Partitions;
 use databend_common_catalog::plan::PartitionsShuffleKind;
 use databend_common_catalog::plan::PushDownInfo;
 use databend_common_catalog::table::DistributionLevel;
+use databend_common_catalog::table::NavigationPoint;
 use databend_common_catalog::table::Table;
+use databend_common_catalog::table::TimeNavigation;
 use databend_common_catalog::table_args::TableArgs;
+use databend_common_catalog::table_context::AbortChecker;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
@@ -39,8 +46,13 @@ use databend_common_expression::TableField;
 use databend_common_expression::TableSchema;
 use databend_common_functions::BUILTIN_FUNCTIONS;
 use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::UpdateStreamMetaReq;
+use databend_common_meta_app::schema::UpsertTableCopiedFileReq;
 use databend_common_meta_app::storage::StorageParams;
+use databend_common_pipeline_core::processors::ProcessorPtr;
 use databend_common_pipeline_core::Pipeline;
+use databend_common_pipeline_sinks::AsyncSinker;
+use databend_common_pipeline_transforms::processors::AsyncAccumulatingTransformer;
 use databend_common_storage::init_operator;
 use databend_common_storages_parquet::ParquetFilesPart;
 use databend_common_storages_parquet::ParquetPart;
@@ -48,7 +60,12 @@ use databend_common_storages_parquet::ParquetRSPruner;
 use databend_common_storages_parquet::ParquetRSReaderBuilder;
 use databend_storages_common_pruner::partition_prunner::FetchPartitionScalars;
 use databend_storages_common_pruner::partition_prunner::PartitionPruner;
+use databend_storages_common_table_meta::meta::SnapshotId;
+use databend_storages_common_table_meta::meta::TableMetaTimestamps;
 use databend_storages_common_table_meta::table::OPT_KEY_ENGINE_META;
+use databend_storages_common_table_meta::table::OPT_KEY_LOCATION;
+use databend_storages_common_table_meta::table::OPT_KEY_TIMESTAMP_AS_OF;
+use databend_storages_common_table_meta::table::OPT_KEY_VERSION_AS_OF;
 use deltalake::kernel::Add;
 use deltalake::DeltaTableBuilder;
 use object_store_opendal::OpendalStore;
@@ -57,22 +74,88 @@ use serde::Serialize;
 use tokio::sync::OnceCell;
 use url::Url;
 
+use crate::changes::DeltaChangesTable;
 use crate::partition::DeltaPartInfo;
+use crate::table_commit::DeltaTableCommitter;
+use crate::table_sink::DeltaTableSink;
 use crate::table_source::DeltaTableSource;
 
 pub const DELTA_ENGINE: &str = "DELTA";
 
+#[derive(Clone)]
 pub struct DeltaTable {
     info: TableInfo,
     table: OnceCell<deltalake::table::DeltaTable>,
     meta: DeltaTableMeta,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DeltaTableMeta {
     partition_columns: Vec<String>,
 }
 
+/// The historical version of a delta table to load.
+#[derive(Debug, Clone, Copy)]
+pub enum DeltaVersion {
+    Version(i64),
+    /// The latest version committed at or before the timestamp.
+    Timestamp(DateTime<Utc>),
+}
+
+impl DeltaVersion {
+    /// Get the version from the table options `version_as_of` or `timestamp_as_of`.
+    pub fn from_options(options: &BTreeMap<String, String>) -> Result<Option<DeltaVersion>> {
+        match (
+            options.get(OPT_KEY_VERSION_AS_OF),
+            options.get(OPT_KEY_TIMESTAMP_AS_OF),
+        ) {
+            (Some(_), Some(_)) => Err(ErrorCode::TableOptionInvalid(format!(
+                "Only one of {OPT_KEY_VERSION_AS_OF} and {OPT_KEY_TIMESTAMP_AS_OF} can be specified"
+            ))),
+            (Some(version), None) => {
+                let version = version.parse::<i64>().map_err(|_| {
+                    ErrorCode::TableOptionInvalid(format!(
+                        "Invalid {OPT_KEY_VERSION_AS_OF} '{version}', expects a non-negative integer"
+                    ))
+                })?;
+                Ok(Some(DeltaVersion::Version(version)))
+            }
+            (None, Some(timestamp)) => {
+                let timestamp = DateTime::parse_from_rfc3339(timestamp)
+                    .map(|v| v.with_timezone(&Utc))
+                    .or_else(|_| {
+                        NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f")
+                            .map(|v| v.and_utc())
+                    })
+                    .map_err(|_| {
+                        ErrorCode::TableOptionInvalid(format!(
+                            "Invalid {OPT_KEY_TIMESTAMP_AS_OF} '{timestamp}', expects a timestamp like '2024-01-01 00:00:00'"
+                        ))
+                    })?;
+                Ok(Some(DeltaVersion::Timestamp(timestamp)))
+            }
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn from_navigation_point(point: &NavigationPoint) -> Result<DeltaVersion> {
+        match point {
+            NavigationPoint::SnapshotID(version) => {
+                let version = version.parse::<i64>().map_err(|_| {
+                    ErrorCode::BadArguments(format!(
+                        "Invalid version {version} of delta table, expects a non-negative integer"
+                    ))
+                })?;
+                Ok(DeltaVersion::Version(version))
+            }
+            NavigationPoint::TimePoint(time_point) => Ok(DeltaVersion::Timestamp(*time_point)),
+            NavigationPoint::StreamInfo(_) => Err(ErrorCode::Unimplemented(
+                "Delta table does not support time travel to a stream",
+            )),
+        }
+    }
+}
+
 /// In a delta table, partition columns are not stored in parquet file.
 /// so it needs a few efforts to make pushdown work:
 ///
@@ -115,7 +198,7 @@ impl DeltaTable {
         }
     }
 
-    fn get_storage_params(&self) -> Result<&StorageParams> {
+    pub(crate) fn get_storage_params(&self) -> Result<&StorageParams> {
         self.info.meta.storage_params.as_ref().ok_or_else(|| {
             ErrorCode::BadArguments(format!(
                 "Delta table {} must have storage parameters",
@@ -124,6 +207,10 @@ impl DeltaTable {
         })
     }
 
+    pub(crate) fn partition_columns(&self) -> &[String] {
+        &self.meta.partition_columns
+    }
+
     fn get_partition_fields(&self) -> Result<Vec<TableField>> {
         self.meta
             .partition_columns
@@ -157,8 +244,12 @@ impl DeltaTable {
         Ok((schema, meta))
     }
 
+    /// Load the delta table, the latest version is loaded if `version` is not specified.
     #[async_backtrace::framed]
-    pub async fn load(sp: &StorageParams) -> Result<deltalake::table::DeltaTable> {
+    pub async fn load(
+        sp: &StorageParams,
+        version: Option<DeltaVersion>,
+    ) -> Result<deltalake::table::DeltaTable> {
         let op = init_operator(sp)?;
         let opendal_store = Arc::new(OpendalStore::new(op));
 
@@ -169,22 +260,111 @@ impl DeltaTable {
                 ErrorCode::ReadTableDataError(format!("Delta table load failed: {err:?}"))
             })?;
 
-        table.load().await.map_err(|err| {
-            ErrorCode::ReadTableDataError(format!("Delta table load failed: {err:?}"))
+        let loaded = match version {
+            None => table.load().await,
+            Some(DeltaVersion::Version(version)) => table.load_version(version).await,
+            Some(DeltaVersion::Timestamp(timestamp)) => table.load_with_datetime(timestamp).await,
+        };
+        loaded.map_err(|err| match version {
+            None => ErrorCode::ReadTableDataError(format!("Delta table load failed: {err:?}")),
+            Some(version) => ErrorCode::TableHistoricalDataNotFound(format!(
+                "Delta table load {version:?} failed: {err:?}"
+            )),
         })?;
         Ok(table)
     }
 
     #[async_backtrace::framed]
-    async fn table(&self) -> Result<&deltalake::table::DeltaTable> {
+    pub(crate) async fn table(&self) -> Result<&deltalake::table::DeltaTable> {
         self.table
             .get_or_try_init(|| async {
                 let sp = self.get_storage_params()?;
-                Self::load(sp).await
+                let version = DeltaVersion::from_options(&self.info.meta.options)?;
+                Self::load(sp, version).await
             })
             .await
     }
 
+    fn location(&self) -> &str {
+        self.info
+            .meta
+            .options
+            .get(OPT_KEY_LOCATION)
+            .map(|v| v.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Returns the table at the given version, whose schema and partition columns are
+    /// those of the version.
+    #[async_backtrace::framed]
+    async fn navigate_to_version(&self, version: DeltaVersion) -> Result<DeltaTable> {
+        let sp = self.get_storage_params()?;
+        let table = Self::load(sp, Some(version)).await?;
+        let (schema, meta_string) = Self::get_meta(&table).await?;
+        let meta: DeltaTableMeta = serde_json::from_str(&meta_string).map_err(|e| {
+            ErrorCode::Internal(format!(
+                "fail to deserialize DeltaTableMeta({meta_string}): {e:?}"
+            ))
+        })?;
+
+        let mut info = self.info.clone();
+        info.meta.schema = Arc::new(schema);
+        info.meta
+            .engine_options
+            .insert(OPT_KEY_ENGINE_META.to_string(), meta_string);
+        // Pin the loaded version, so that the table can be rebuilt from the table info.
+        info.meta.options.remove(OPT_KEY_TIMESTAMP_AS_OF);
+        info.meta.options.insert(
+            OPT_KEY_VERSION_AS_OF.to_string(),
+            table.version().to_string(),
+        );
+        Ok(DeltaTable {
+            info,
+            table: OnceCell::from(table),
+            meta,
+        })
+    }
+
+    /// Returns the change data feed of the versions in `[start, end]`, `end` defaults to
+    /// the loaded version.
+    #[async_backtrace::framed]
+    async fn changes(
+        &self,
+        start: &NavigationPoint,
+        end: Option<&NavigationPoint>,
+    ) -> Result<Arc<dyn Table>> {
+        let table = self.table().await?;
+        let start = self.resolve_version(start).await?;
+        let end = match end {
+            Some(end) => self.resolve_version(end).await?,
+            None => table.version(),
+        };
+        if start > end {
+            return Err(ErrorCode::BadArguments(format!(
+                "The start version {start} of delta changes is greater than the end version {end}"
+            )));
+        }
+
+        Ok(Arc::new(DeltaChangesTable::create(
+            &self.info,
+            self.get_partition_fields()?,
+            table.log_store(),
+            start,
+            end,
+        )))
+    }
+
+    #[async_backtrace::framed]
+    async fn resolve_version(&self, point: &NavigationPoint) -> Result<i64> {
+        match DeltaVersion::from_navigation_point(point)? {
+            DeltaVersion::Version(version) => Ok(version),
+            version => {
+                let sp = self.get_storage_params()?;
+                Ok(Self::load(sp, Some(version)).await?.version())
+            }
+        }
+    }
+
     pub fn do_read_data(
         &self,
         ctx: Arc<dyn TableContext>,
@@ -248,7 +428,7 @@ impl DeltaTable {
             None
         };
         let mut builder =
-            ParquetRSReaderBuilder::create(ctx.clone(), op, table_schema, arrow_schema)?
+            ParquetRSReaderBuilder::create(ctx.clone(), op.clone(), table_schema, arrow_schema)?
                 .with_options(read_options)
                 .with_push_downs(push_downs.as_ref())
                 .with_pruner(Some(pruner))
@@ -265,6 +445,8 @@ impl DeltaTable {
                     output_schema.clone(),
                     parquet_reader.clone(),
                     self.get_partition_fields()?,
+                    op.clone(),
+                    self.location().to_string(),
                 )
             },
             max_threads.max(1),
@@ -332,11 +514,16 @@ impl DeltaTable {
                         _ => None,
                     }
                     ).unwrap_or(1);
-                read_rows += num_records as usize;
+                let num_deleted = add
+                    .deletion_vector
+                    .as_ref()
+                    .map_or(0, |dv| dv.cardinality);
+                read_rows += (num_records - num_deleted).max(0) as usize;
                 read_bytes += add.size as usize;
                 let partition_values = get_partition_values(add, &partition_fields)?;
                 Ok(Arc::new(Box::new(DeltaPartInfo {
                         partition_values,
+                        deletion_vector: add.deletion_vector.clone(),
                         data: ParquetPart::ParquetFiles(
                             ParquetFilesPart {
                                 files: vec![(add.path.clone(), add.size as u64)],
@@ -401,6 +588,50 @@ impl Table for DeltaTable {
         self.do_read_data(ctx, plan, pipeline)
     }
 
+    fn append_data(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        _table_meta_timestamps: TableMetaTimestamps,
+    ) -> Result<()> {
+        pipeline.add_transform(|input, output| {
+            Ok(ProcessorPtr::create(AsyncAccumulatingTransformer::create(
+                input,
+                output,
+                DeltaTableSink::try_create(self)?,
+            )))
+        })
+    }
+
+    fn commit_insertion(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        _copied_files: Option<UpsertTableCopiedFileReq>,
+        update_stream_meta: Vec<UpdateStreamMetaReq>,
+        overwrite: bool,
+        _prev_snapshot_id: Option<SnapshotId>,
+        _deduplicated_label: Option<String>,
+        _table_meta_timestamps: TableMetaTimestamps,
+    ) -> Result<()> {
+        // The files are committed to the delta log, the offsets of the streams can not be
+        // updated in the same commit.
+        if !update_stream_meta.is_empty() {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Insert into delta table {} from stream is not supported",
+                self.name()
+            )));
+        }
+
+        pipeline.try_resize(1)?;
+        pipeline.add_sink(|input| {
+            Ok(ProcessorPtr::create(AsyncSinker::create(
+                input,
+                DeltaTableCommitter::create(self.clone(), overwrite),
+            )))
+        })
+    }
+
     fn table_args(&self) -> Option<TableArgs> {
         None
     }
@@ -412,12 +643,45 @@ impl Table for DeltaTable {
     fn support_prewhere(&self) -> bool {
         true
     }
+
+    fn support_copied_files(&self) -> bool {
+        false
+    }
+
+    /// A historical version of the table can not be modified.
+    fn is_read_only(&self) -> bool {
+        self.info.meta.options.contains_key(OPT_KEY_VERSION_AS_OF)
+            || self.info.meta.options.contains_key(OPT_KEY_TIMESTAMP_AS_OF)
+    }
+
+    #[async_backtrace::framed]
+    async fn navigate_to(
+        &self,
+        navigation: &TimeNavigation,
+        _abort_checker: AbortChecker,
+    ) -> Result<Arc<dyn Table>> {
+        match navigation {
+            TimeNavigation::TimeTravel(point) => {
+                let version = DeltaVersion::from_navigation_point(point)?;
+                Ok(Arc::new(self.navigate_to_version(version).await?))
+            }
+            TimeNavigation::Changes { at, end, .. } => self.changes(at, end.as_ref()).await,
+        }
+    }
 }
 
 pub fn get_partition_values(add: &Add, fields: &[TableField]) -> Result<Vec<Scalar>> {
+    parse_partition_values(|name| add.partition_values.get(name), fields)
+}
+
+/// Parse the partition values of the actions of the delta log.
+pub fn parse_partition_values<'a>(
+    get: impl Fn(&str) -> Option<&'a Option<String>>,
+    fields: &[TableField],
+) -> Result<Vec<Scalar>> {
     let mut values = Vec::with_capacity(fields.len());
     for f in fields {
-        match add.partition_values.get(&f.name) {
+        match get(&f.name) {
             Some(Some(v)) => values.push(str_to_scalar(v, &f.data_type().into())?),
             Some(None) => values.push(Scalar::Null),
             None => {
@@ -0,0 +1,149 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_pipeline_sinks::AsyncSink;
+use deltalake::errors::DeltaTableError;
+use deltalake::kernel::Action;
+use deltalake::kernel::Add;
+use deltalake::kernel::Remove;
+use deltalake::operations::transaction::CommitBuilder;
+use deltalake::operations::transaction::TableReference;
+use deltalake::operations::transaction::TransactionError;
+use deltalake::protocol::DeltaOperation;
+use deltalake::protocol::SaveMode;
+use log::info;
+
+use crate::table_sink::DeltaDataFiles;
+use crate::DeltaTable;
+
+/// Collects the data files written by all the [`crate::table_sink::DeltaTableSink`]s
+/// and commits them to the `_delta_log` of the table in a new version.
+///
+/// The commit is checked against the versions committed concurrently since the table
+/// was loaded, and fails if they conflict.
+pub struct DeltaTableCommitter {
+    table: DeltaTable,
+    overwrite: bool,
+    adds: Vec<Add>,
+}
+
+impl DeltaTableCommitter {
+    pub fn create(table: DeltaTable, overwrite: bool) -> Self {
+        Self {
+            table,
+            overwrite,
+            adds: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncSink for DeltaTableCommitter {
+    const NAME: &'static str = "DeltaTableCommitter";
+
+    #[async_backtrace::framed]
+    async fn consume(&mut self, data_block: DataBlock) -> Result<bool> {
+        if let Some(meta) = data_block
+            .get_owned_meta()
+            .and_then(DeltaDataFiles::downcast_from)
+        {
+            self.adds.extend(meta.adds);
+        }
+        Ok(false)
+    }
+
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self) -> Result<()> {
+        if self.adds.is_empty() && !self.overwrite {
+            return Ok(());
+        }
+
+        let table = self.table.table().await?;
+        let snapshot = table.snapshot().map_err(|err| {
+            ErrorCode::ReadTableDataError(format!("Cannot read delta table snapshot: {err:?}"))
+        })?;
+
+        let mut actions = vec![];
+        if self.overwrite {
+            let deletion_timestamp = chrono::Utc::now().timestamp_millis();
+            let files = snapshot.file_actions().map_err(|err| {
+                ErrorCode::ReadTableDataError(format!("Cannot read file_actions: {err:?}"))
+            })?;
+            actions.extend(files.into_iter().map(|add| {
+                Action::Remove(Remove {
+                    path: add.path,
+                    data_change: true,
+                    deletion_timestamp: Some(deletion_timestamp),
+                    extended_file_metadata: Some(true),
+                    partition_values: Some(add.partition_values),
+                    size: Some(add.size),
+                    tags: add.tags,
+                    deletion_vector: add.deletion_vector,
+                    base_row_id: add.base_row_id,
+                    default_row_commit_version: add.default_row_commit_version,
+                })
+            }));
+        }
+        let num_adds = self.adds.len();
+        actions.extend(std::mem::take(&mut self.adds).into_iter().map(Action::Add));
+
+        let partition_columns = self.table.partition_columns();
+        let operation = DeltaOperation::Write {
+            mode: if self.overwrite {
+                SaveMode::Overwrite
+            } else {
+                SaveMode::Append
+            },
+            partition_by: (!partition_columns.is_empty()).then(|| partition_columns.to_vec()),
+            predicate: None,
+        };
+
+        let commit = CommitBuilder::default()
+            .with_actions(actions)
+            .build(
+                Some(snapshot as &dyn TableReference),
+                table.log_store(),
+                operation,
+            )
+            .await
+            .map_err(|err| match err {
+                DeltaTableError::Transaction {
+                    source:
+                        source @ (TransactionError::CommitConflict(_)
+                        | TransactionError::VersionAlreadyExists(_)),
+                } => ErrorCode::TableVersionMismatched(format!(
+                    "Delta commit table {} conflicts with a concurrent commit: {source}",
+                    self.table.name()
+                )),
+                err => ErrorCode::StorageOther(format!(
+                    "Delta commit table {} failed: {err:?}",
+                    self.table.name()
+                )),
+            })?;
+
+        info!(
+            "Delta commit {} data files to table {} in version {}",
+            num_adds,
+            self.table.name(),
+            commit.version()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_array::UInt32Array;
+use arrow_cast::display::ArrayFormatter;
+use arrow_cast::display::FormatOptions;
+use arrow_schema::Schema as ArrowSchema;
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::BlockMetaInfo;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_expression::TableSchemaRef;
+use databend_common_pipeline_transforms::processors::AsyncAccumulatingTransform;
+use databend_common_storage::init_operator;
+use deltalake::kernel::Add;
+use opendal::Operator;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use uuid::Uuid;
+
+use crate::DeltaTable;
+
+/// Data files are closed once their size reaches this threshold.
+const MAX_FILE_SIZE: usize = 128 * 1024 * 1024;
+
+/// The add actions of the data files written by [`DeltaTableSink`], which are committed by
+/// [`crate::table_commit::DeltaTableCommitter`] in a new version.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DeltaDataFiles {
+    pub adds: Vec<Add>,
+}
+
+#[typetag::serde(name = "delta_data_files")]
+impl BlockMetaInfo for DeltaDataFiles {
+    fn equals(&self, info: &Box<dyn BlockMetaInfo>) -> bool {
+        Self::downcast_ref_from(info).is_some_and(|other| self == other)
+    }
+
+    fn clone_self(&self) -> Box<dyn BlockMetaInfo> {
+        Box::new(self.clone())
+    }
+}
+
+/// Writes the appended blocks to parquet data files of the delta table, one data file writer
+/// for each partition. The add actions of the written data files are sent downstream in a
+/// single block with [`DeltaDataFiles`] meta on finish.
+///
+/// As in the files written by spark, the partition columns are not stored in the data files,
+/// their values are recorded in the add actions instead.
+pub struct DeltaTableSink {
+    table: DeltaTable,
+    op: Operator,
+    table_schema: TableSchemaRef,
+    /// The arrow schema of the delta table, loaded on the first block.
+    arrow_schema: Option<ArrowSchemaRef>,
+    /// The index of the partition columns in the table schema.
+    partition_indices: Vec<usize>,
+    /// The index of the columns stored in the data files.
+    data_indices: Vec<usize>,
+    writers: HashMap<Vec<Option<String>>, DeltaFileWriter>,
+    adds: Vec<Add>,
+}
+
+struct DeltaFileWriter {
+    writer: ArrowWriter<Vec<u8>>,
+    num_rows: usize,
+}
+
+impl DeltaTableSink {
+    pub fn try_create(table: &DeltaTable) -> Result<Self> {
+        let table_schema = table.schema();
+        let partition_columns = table.partition_columns();
+        let partition_indices = partition_columns
+            .iter()
+            .map(|name| table_schema.index_of(name))
+            .collect::<Result<Vec<_>>>()?;
+        let data_indices = (0..table_schema.num_fields())
+            .filter(|i| !partition_indices.contains(i))
+            .collect();
+
+        Ok(Self {
+            table: table.clone(),
+            op: init_operator(table.get_storage_params()?)?,
+            table_schema,
+            arrow_schema: None,
+            partition_indices,
+            data_indices,
+            writers: HashMap::new(),
+            adds: vec![],
+        })
+    }
+
+    #[async_backtrace::framed]
+    async fn arrow_schema(&mut self) -> Result<ArrowSchemaRef> {
+        if let Some(schema) = &self.arrow_schema {
+            return Ok(schema.clone());
+        }
+
+        let table = self.table.table().await?;
+        let delta_schema = table.get_schema().map_err(|err| {
+            ErrorCode::ReadTableDataError(format!("Cannot convert table metadata: {err:?}"))
+        })?;
+        let schema: ArrowSchema = delta_schema.try_into().map_err(|err| {
+            ErrorCode::ReadTableDataError(format!("Cannot convert table metadata: {err:?}"))
+        })?;
+        let schema = Arc::new(schema);
+        self.arrow_schema = Some(schema.clone());
+        Ok(schema)
+    }
+
+    /// Convert the block to a record batch in the arrow schema of the delta table.
+    #[async_backtrace::framed]
+    async fn to_record_batch(&mut self, block: DataBlock) -> Result<RecordBatch> {
+        let arrow_schema = self.arrow_schema().await?;
+        let batch = block.to_record_batch(&self.table_schema)?;
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(arrow_schema.fields())
+            .map(|(column, field)| {
+                if column.data_type() == field.data_type() {
+                    Ok(column.clone())
+                } else {
+                    arrow_cast::cast(column, field.data_type()).map_err(|err| {
+                        ErrorCode::Internal(format!(
+                            "Cannot cast column {} to delta type {}: {err}",
+                            field.name(),
+                            field.data_type()
+                        ))
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        RecordBatch::try_new(arrow_schema, columns).map_err(|err| {
+            ErrorCode::Internal(format!("Cannot build record batch for delta: {err}"))
+        })
+    }
+
+    /// Returns the partition values and the row indices of each partition.
+    fn partition(&self, batch: &RecordBatch) -> Result<Vec<(Vec<Option<String>>, UInt32Array)>> {
+        // See https://github.com/delta-io/delta/blob/master/PROTOCOL.md#partition-value-serialization
+        let options = FormatOptions::new()
+            .with_date_format(Some("%Y-%m-%d"))
+            .with_timestamp_format(Some("%Y-%m-%d %H:%M:%S%.6f"))
+            .with_timestamp_tz_format(Some("%Y-%m-%d %H:%M:%S%.6f"));
+        let formatters = self
+            .partition_indices
+            .iter()
+            .map(|index| {
+                ArrayFormatter::try_new(batch.column(*index).as_ref(), &options).map_err(|err| {
+                    ErrorCode::Unimplemented(format!(
+                        "Delta partition column {} is not supported: {err}",
+                        self.table_schema.field(*index).name
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut partitions: Vec<(Vec<Option<String>>, Vec<u32>)> = vec![];
+        let mut partition_indices: HashMap<Vec<Option<String>>, usize> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let values = self
+                .partition_indices
+                .iter()
+                .zip(formatters.iter())
+                .map(|(index, formatter)| {
+                    (!batch.column(*index).is_null(row)).then(|| formatter.value(row).to_string())
+                })
+                .collect::<Vec<_>>();
+
+            match partition_indices.get(&values) {
+                Some(index) => partitions[*index].1.push(row as u32),
+                None => {
+                    partition_indices.insert(values.clone(), partitions.len());
+                    partitions.push((values, vec![row as u32]));
+                }
+            }
+        }
+
+        Ok(partitions
+            .into_iter()
+            .map(|(values, rows)| (values, UInt32Array::from(rows)))
+            .collect())
+    }
+
+    #[async_backtrace::framed]
+    async fn write(&mut self, partition: Vec<Option<String>>, batch: RecordBatch) -> Result<()> {
+        let batch = batch
+            .project(&self.data_indices)
+            .map_err(|err| ErrorCode::Internal(format!("Cannot project record batch: {err}")))?;
+
+        if !self.writers.contains_key(&partition) {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(properties))
+                .map_err(|err| {
+                    ErrorCode::Internal(format!("Delta create data file writer failed: {err}"))
+                })?;
+            self.writers.insert(partition.clone(), DeltaFileWriter {
+                writer,
+                num_rows: 0,
+            });
+        }
+
+        let file_writer = self.writers.get_mut(&partition).unwrap();
+        file_writer.writer.write(&batch).map_err(|err| {
+            ErrorCode::Internal(format!(
+                "Delta write table {} failed: {err}",
+                self.table.name()
+            ))
+        })?;
+        file_writer.num_rows += batch.num_rows();
+
+        if file_writer.writer.bytes_written() + file_writer.writer.in_progress_size()
+            >= MAX_FILE_SIZE
+        {
+            let file_writer = self.writers.remove(&partition).unwrap();
+            self.close(partition, file_writer).await?;
+        }
+        Ok(())
+    }
+
+    /// Upload the data file and record its add action.
+    #[async_backtrace::framed]
+    async fn close(
+        &mut self,
+        partition: Vec<Option<String>>,
+        writer: DeltaFileWriter,
+    ) -> Result<()> {
+        let data = writer.writer.into_inner().map_err(|err| {
+            ErrorCode::Internal(format!("Delta close data file writer failed: {err}"))
+        })?;
+        // Data files are kept in the table root, so that partition values need not be escaped in paths.
+        let path = format!("part-{}-c000.snappy.parquet", Uuid::now_v7());
+        let size = data.len() as i64;
+        self.op.write(&path, data).await?;
+
+        let partition_values = self
+            .table
+            .partition_columns()
+            .iter()
+            .cloned()
+            .zip(partition)
+            .collect();
+        self.adds.push(Add {
+            path,
+            partition_values,
+            size,
+            modification_time: chrono::Utc::now().timestamp_millis(),
+            data_change: true,
+            stats: Some(serde_json::json!({ "numRecords": writer.num_rows }).to_string()),
+            ..Default::default()
+        });
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncAccumulatingTransform for DeltaTableSink {
+    const NAME: &'static str = "DeltaTableSink";
+
+    #[async_backtrace::framed]
+    async fn transform(&mut self, data: DataBlock) -> Result<Option<DataBlock>> {
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let batch = self.to_record_batch(data).await?;
+        if self.partition_indices.is_empty() {
+            self.write(vec![], batch).await?;
+            return Ok(None);
+        }
+
+        for (partition, indices) in self.partition(&batch)? {
+            let batch = arrow_select::take::take_record_batch(&batch, &indices)
+                .map_err(|err| ErrorCode::Internal(format!("Cannot split record batch: {err}")))?;
+            self.write(partition, batch).await?;
+        }
+        Ok(None)
+    }
+
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self, _output: bool) -> Result<Option<DataBlock>> {
+        let writers = std::mem::take(&mut self.writers);
+        for (partition, writer) in writers {
+            self.close(partition, writer).await?;
+        }
+
+        Ok(Some(DataBlock::empty_with_meta(Box::new(DeltaDataFiles {
+            adds: std::mem::take(&mut self.adds),
+        }))))
+    }
+}
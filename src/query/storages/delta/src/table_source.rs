@@ -36,8 +36,10 @@ use databend_common_pipeline_core::processors::ProcessorPtr;
 use databend_common_storages_parquet::ParquetFileReader;
 use databend_common_storages_parquet::ParquetPart;
 use databend_common_storages_parquet::ParquetRSFullReader;
+use opendal::Operator;
 use parquet::arrow::async_reader::ParquetRecordBatchStream;
 
+use crate::deletion_vector::read_deletion_vector;
 use crate::partition::DeltaPartInfo;
 
 pub type PartitionColumnIndex = usize;
@@ -63,6 +65,10 @@ pub struct DeltaTableSource {
     // Used to check schema
     output_schema: DataSchemaRef,
 
+    // Used to read deletion vectors.
+    op: Operator,
+    location: String,
+
     // Per partition
     stream: Option<ParquetRecordBatchStream<ParquetFileReader>>,
     partition_block_entries: Vec<BlockEntry>,
@@ -75,6 +81,8 @@ impl DeltaTableSource {
         output_schema: DataSchemaRef,
         parquet_reader: Arc<ParquetRSFullReader>,
         partition_fields: Vec<TableField>,
+        op: Operator,
+        location: String,
     ) -> Result<ProcessorPtr> {
         let output_partition_columns = output_schema
             .fields()
@@ -96,6 +104,8 @@ impl DeltaTableSource {
             output_schema,
             partition_fields,
             output_partition_columns,
+            op,
+            location,
             stream: None,
             generated_data: None,
             is_finished: false,
@@ -186,12 +196,17 @@ impl Processor for DeltaTableSource {
                             BlockEntry::new(f.data_type().into(), Value::Scalar(v.clone()))
                         })
                         .collect::<Vec<_>>();
+                    let deleted_rows = match &part.deletion_vector {
+                        Some(dv) => Some(read_deletion_vector(&self.op, &self.location, dv).await?),
+                        None => None,
+                    };
                     let stream = self
                         .parquet_reader
                         .prepare_data_stream(
                             &files.files[0].0,
                             files.files[0].1,
                             Some(&partition_fields),
+                            deleted_rows.as_deref(),
                         )
                         .await?;
                     self.stream = Some(stream);
@@ -206,7 +221,7 @@ impl Processor for DeltaTableSource {
     }
 }
 
-fn check_block_schema(schema: &DataSchema, mut block: DataBlock) -> Result<DataBlock> {
+pub(crate) fn check_block_schema(schema: &DataSchema, mut block: DataBlock) -> Result<DataBlock> {
     // Check if the schema of the data block is matched with the schema of the table.
     if block.num_columns() != schema.num_fields() {
         return Err(ErrorCode::TableSchemaMismatch(format!(
@@ -0,0 +1,184 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_base::base::tokio;
+use databend_common_exception::ErrorCode;
+use databend_common_storages_delta::deletion_vector_path;
+use databend_common_storages_delta::read_deletion_vector;
+use databend_common_storages_delta::z85_decode;
+use deltalake::kernel::DeletionVectorDescriptor;
+use deltalake::kernel::StorageType;
+use opendal::services::Memory;
+use opendal::Operator;
+use roaring::RoaringTreemap;
+
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+fn z85_encode(data: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in data.chunks(4) {
+        let mut value = u32::from_be_bytes(chunk.try_into().unwrap());
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = Z85_ALPHABET[(value % 85) as usize];
+            value /= 85;
+        }
+        encoded.push_str(std::str::from_utf8(&digits).unwrap());
+    }
+    encoded
+}
+
+/// The magic number followed by the bitmap, padded to a multiple of 4 bytes like inline vectors.
+fn serialize_deletion_vector(rows: &[u64]) -> (Vec<u8>, usize) {
+    let bitmap = rows.iter().copied().collect::<RoaringTreemap>();
+    let mut data = 1681511377u32.to_le_bytes().to_vec();
+    bitmap.serialize_into(&mut data).unwrap();
+    let size = data.len();
+    data.resize(size.div_ceil(4) * 4, 0);
+    (data, size)
+}
+
+fn descriptor(
+    storage_type: StorageType,
+    path_or_inline_dv: &str,
+    offset: Option<i32>,
+    size: usize,
+    cardinality: usize,
+) -> DeletionVectorDescriptor {
+    DeletionVectorDescriptor {
+        storage_type,
+        path_or_inline_dv: path_or_inline_dv.to_string(),
+        offset,
+        size_in_bytes: size as i32,
+        cardinality: cardinality as i64,
+    }
+}
+
+#[test]
+fn test_z85_decode() {
+    // The test vector of the specification.
+    assert_eq!(z85_decode("HelloWorld").unwrap(), vec![
+        0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B
+    ]);
+    assert_eq!(z85_decode("").unwrap(), Vec::<u8>::new());
+
+    let data = (0..=255).collect::<Vec<u8>>();
+    assert_eq!(z85_decode(&z85_encode(&data)).unwrap(), data);
+
+    for invalid in ["Hello", "HelloWorl", "Hello~orld", "%%%%%"] {
+        let err = z85_decode(invalid).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::STORAGE_OTHER, "{invalid}");
+    }
+}
+
+#[test]
+fn test_deletion_vector_path() {
+    // The example of the protocol.
+    let dv = descriptor(
+        StorageType::UuidRelativePath,
+        "ab^-aqEH.-t@S}K{vb[*k^",
+        Some(4),
+        40,
+        6,
+    );
+    assert_eq!(
+        deletion_vector_path("s3://mytable/", &dv).unwrap(),
+        "ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"
+    );
+
+    let dv = descriptor(
+        StorageType::UuidRelativePath,
+        "^-aqEH.-t@S}K{vb[*k^",
+        Some(4),
+        40,
+        6,
+    );
+    assert_eq!(
+        deletion_vector_path("s3://mytable/", &dv).unwrap(),
+        "deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"
+    );
+
+    let dv = descriptor(StorageType::UuidRelativePath, "ab^-aqEH", Some(4), 40, 6);
+    assert!(deletion_vector_path("s3://mytable/", &dv).is_err());
+
+    let dv = descriptor(
+        StorageType::AbsolutePath,
+        "s3://mytable/dv/deletion_vector.bin",
+        Some(4),
+        40,
+        6,
+    );
+    assert_eq!(
+        deletion_vector_path("s3://mytable/", &dv).unwrap(),
+        "dv/deletion_vector.bin"
+    );
+    assert_eq!(
+        deletion_vector_path("s3://mytable", &dv).unwrap(),
+        "dv/deletion_vector.bin"
+    );
+    let err = deletion_vector_path("s3://other/", &dv).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UNIMPLEMENTED);
+}
+
+#[tokio::test]
+async fn test_read_deletion_vector() {
+    let op = Operator::new(Memory::default()).unwrap().finish();
+    let rows = vec![0, 3, 4095, 4096, 70000, 1 << 33];
+
+    let (data, size) = serialize_deletion_vector(&rows);
+    let dv = descriptor(StorageType::Inline, &z85_encode(&data), None, size, 6);
+    assert_eq!(read_deletion_vector(&op, "/", &dv).await.unwrap(), rows);
+
+    // A file of two deletion vectors, each of them is prefixed with its size and followed by a checksum.
+    let (first, first_size) = serialize_deletion_vector(&[1, 2]);
+    let (second, second_size) = serialize_deletion_vector(&rows);
+    let mut file = vec![1u8];
+    for (data, size) in [(&first, first_size), (&second, second_size)] {
+        file.extend_from_slice(&(size as u32).to_be_bytes());
+        file.extend_from_slice(&data[..size]);
+        file.extend_from_slice(&[0; 4]);
+    }
+    op.write(
+        "dv/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin",
+        file,
+    )
+    .await
+    .unwrap();
+
+    let path = "dv^-aqEH.-t@S}K{vb[*k^";
+    let dv = descriptor(StorageType::UuidRelativePath, path, None, first_size, 2);
+    assert_eq!(read_deletion_vector(&op, "/", &dv).await.unwrap(), vec![
+        1, 2
+    ]);
+    let offset = 1 + 4 + first_size + 4;
+    let dv = descriptor(
+        StorageType::UuidRelativePath,
+        path,
+        Some(offset as i32),
+        second_size,
+        6,
+    );
+    assert_eq!(read_deletion_vector(&op, "/", &dv).await.unwrap(), rows);
+
+    // The size does not match the one in the file.
+    let dv = descriptor(
+        StorageType::UuidRelativePath,
+        path,
+        Some(offset as i32),
+        first_size,
+        2,
+    );
+    assert!(read_deletion_vector(&op, "/", &dv).await.is_err());
+}
@@ -62,6 +62,7 @@ use databend_common_io::constants::DEFAULT_BLOCK_MAX_ROWS;
 use databend_common_io::constants::DEFAULT_BLOCK_PER_SEGMENT;
 use databend_common_meta_app::schema::DatabaseType;
 use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
 use databend_common_meta_app::schema::UpdateStreamMetaReq;
 use databend_common_meta_app::schema::UpsertTableCopiedFileReq;
 use databend_common_pipeline_core::Pipeline;
@@ -683,7 +684,9 @@ impl Table for FuseTable {
     }
 
     fn support_distributed_insert(&self) -> bool {
-        true
+        // the keys of the rows inserted on different nodes can not be checked against each other
+        self.key_constraints()
+            .is_ok_and(|constraints| constraints.is_empty())
     }
 
     fn has_exact_total_row_count(&self) -> bool {
@@ -1099,6 +1102,16 @@ impl Table for FuseTable {
         self.do_revert_to(ctx, point).await
     }
 
+    #[async_backtrace::framed]
+    async fn rebase_txn_mutation(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        base: &TableInfo,
+        latest: &TableInfo,
+    ) -> Result<TableMeta> {
+        self.do_rebase_txn_mutation(ctx, base, latest).await
+    }
+
     fn support_prewhere(&self) -> bool {
         matches!(self.storage_format, FuseStorageFormat::Native)
     }
@@ -271,6 +271,8 @@ impl FuseTable {
                 copied_files: copied_files_req,
                 deduplicated_labels: deduplicated_label.into_iter().collect(),
                 update_temp_tables,
+                drop_tables: vec![],
+                commit_tables: vec![],
             })
             .await?;
 
@@ -127,6 +127,8 @@ impl AsyncSink for CommitMultiTableInsert {
                 update_stream_metas: self.update_stream_meta.clone(),
                 deduplicated_labels: self.deduplicated_label.clone().into_iter().collect(),
                 update_temp_tables: std::mem::take(&mut update_temp_tables),
+                drop_tables: vec![],
+                commit_tables: vec![],
             };
 
             let update_meta_result = match self
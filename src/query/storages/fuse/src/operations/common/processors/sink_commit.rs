@@ -94,6 +94,8 @@ pub struct CommitSink<F: SnapshotGenerator> {
     backoff: ExponentialBackoff,
 
     new_segment_locs: Vec<Location>,
+    // the snapshot read when the commit begins, which the transaction is based on
+    base_snapshot: Option<Arc<TableSnapshot>>,
     start_time: Instant,
     prev_snapshot_id: Option<SnapshotId>,
 
@@ -152,6 +154,7 @@ where F: SnapshotGenerator + Send + Sync + 'static
             max_retry_elapsed,
             input,
             new_segment_locs: vec![],
+            base_snapshot: None,
             start_time: Instant::now(),
             prev_snapshot_id,
             change_tracking: table.change_tracking_enabled(),
@@ -448,6 +451,7 @@ where F: SnapshotGenerator + Send + Sync + 'static
                     self.snapshot_gen
                         .fill_default_values(schema, &previous)
                         .await?;
+                    self.base_snapshot = previous.clone();
 
                     self.state = State::GenerateSnapshot {
                         previous,
@@ -461,6 +465,24 @@ where F: SnapshotGenerator + Send + Sync + 'static
                 snapshot,
                 table_info,
             } => {
+                if self.retries > 0 {
+                    // the unique keys of the new segments are checked against the base snapshot,
+                    // check them against the segments merged from the concurrent commits too
+                    let fuse_table = FuseTable::try_from_table(self.table.as_ref())?;
+                    if let Err(e) = fuse_table
+                        .check_concurrent_unique_keys(
+                            self.ctx.clone(),
+                            &snapshot,
+                            self.base_snapshot.as_deref(),
+                            &self.new_segment_locs,
+                        )
+                        .await
+                    {
+                        self.state = State::Abort(e);
+                        return Ok(());
+                    }
+                }
+
                 let location = self
                     .location_gen
                     .snapshot_location_from_uuid(&snapshot.snapshot_id, TableSnapshot::VERSION)?;
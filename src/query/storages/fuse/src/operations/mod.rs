@@ -34,6 +34,8 @@ mod replace;
 mod replace_into;
 mod revert;
 mod truncate;
+mod txn_rebase;
+mod unique_key;
 mod util;
 
 mod snapshot_hint;
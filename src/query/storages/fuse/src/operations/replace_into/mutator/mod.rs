@@ -16,9 +16,11 @@ mod column_hash;
 mod deletion_accumulator;
 mod replace_into_mutator;
 mod replace_into_operation_agg;
+mod unique_key_checker;
 
 pub use column_hash::row_hash_of_columns;
 pub use deletion_accumulator::BlockDeletionKeys;
 pub use deletion_accumulator::DeletionAccumulator;
 pub use replace_into_mutator::ReplaceIntoMutator;
 pub use replace_into_operation_agg::ReplaceIntoOperationAggregator;
+pub use unique_key_checker::UniqueKeyChecker;
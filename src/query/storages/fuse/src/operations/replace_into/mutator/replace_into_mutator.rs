@@ -263,7 +263,7 @@ impl ReplaceIntoMutator {
         Ok(res)
     }
 
-    fn extract_col_value_for_err_message(scalar: ScalarRef) -> String {
+    pub(super) fn extract_col_value_for_err_message(scalar: ScalarRef) -> String {
         // unfortunately, lifetime issues, we can not return a &str
         match scalar {
             // for nested types, just return the type name as a hint.
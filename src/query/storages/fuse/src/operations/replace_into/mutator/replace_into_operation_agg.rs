@@ -81,7 +81,7 @@ use crate::operations::replace_into::mutator::row_hash_of_columns;
 use crate::operations::replace_into::mutator::DeletionAccumulator;
 use crate::FuseTable;
 
-struct AggregationContext {
+pub(super) struct AggregationContext {
     segment_locations: AHashMap<SegmentIndex, Location>,
     block_slots_in_charge: Option<BlockSlotDescription>,
     // the fields specified in ON CONFLICT clause
@@ -611,7 +611,7 @@ impl AggregationContext {
 
     // if any item of `column_min_max` does NOT overlap with the corresponding item of `column_stats`
     // returns false, otherwise returns true.
-    fn check_overlap(
+    pub(super) fn check_overlap(
         on_conflict_fields: &[OnConflictField],
         column_stats: &HashMap<ColumnId, ColumnStatistics>,
         columns_min_max: &[(Scalar, Scalar)],
@@ -0,0 +1,361 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ahash::HashSet;
+use ahash::HashSetExt;
+use databend_common_catalog::plan::Projection;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::ColumnId;
+use databend_common_expression::DataBlock;
+use databend_common_expression::FieldIndex;
+use databend_common_expression::FunctionContext;
+use databend_common_expression::Scalar;
+use databend_common_expression::TableSchema;
+use databend_common_sql::executor::physical_plans::OnConflictField;
+use databend_storages_common_cache::LoadParams;
+use databend_storages_common_index::filters::Filter;
+use databend_storages_common_index::BloomIndex;
+use databend_storages_common_io::ReadSettings;
+use databend_storages_common_table_meta::meta::BlockMeta;
+use databend_storages_common_table_meta::meta::ColumnStatistics;
+use databend_storages_common_table_meta::meta::CompactSegmentInfo;
+use databend_storages_common_table_meta::meta::Location;
+use log::warn;
+use opendal::Operator;
+
+use crate::io::read::bloom::block_filter_reader::BloomBlockFilterReader;
+use crate::io::BlockReader;
+use crate::io::CompactSegmentInfoReader;
+use crate::io::MetaReaders;
+use crate::operations::read_block;
+use crate::operations::replace_into::meta::UniqueKeyDigest;
+use crate::operations::replace_into::mutator::column_hash::RowScalarValue;
+use crate::operations::replace_into::mutator::replace_into_operation_agg::AggregationContext;
+use crate::operations::replace_into::mutator::row_hash_of_columns;
+use crate::operations::replace_into::mutator::ReplaceIntoMutator;
+use crate::FuseStorageFormat;
+use crate::FuseTable;
+
+/// Looks up the keys of a PRIMARY KEY or UNIQUE constraint in the stored blocks of a table.
+///
+/// The blocks are pruned by the range index and the bloom index of the key columns, in the same
+/// way as the replace into pipeline does.
+///
+/// The keys are passed in as "key blocks", which contain only the key columns, in the order of
+/// the key.
+pub struct UniqueKeyChecker {
+    // the name of the constraint
+    name: String,
+    key_fields: Vec<OnConflictField>,
+    // the indexes of `key_fields` which we should apply bloom filtering, if any
+    bloom_filter_column_indexes: Vec<FieldIndex>,
+    table_range_index: HashMap<ColumnId, ColumnStatistics>,
+    segment_locations: Vec<Location>,
+    // reader that reads the key fields
+    key_column_reader: Arc<BlockReader>,
+    segment_reader: CompactSegmentInfoReader,
+    data_accessor: Operator,
+    storage_format: FuseStorageFormat,
+    read_settings: ReadSettings,
+    func_ctx: FunctionContext,
+}
+
+impl UniqueKeyChecker {
+    /// The fields of the key columns, with the field indexes in `table_schema`.
+    pub fn resolve_key_fields(
+        table_schema: &TableSchema,
+        columns: &[String],
+    ) -> Result<Vec<OnConflictField>> {
+        columns
+            .iter()
+            .map(|name| {
+                let field_index = table_schema.index_of(name)?;
+                Ok(OnConflictField {
+                    table_field: table_schema.fields()[field_index].clone(),
+                    field_index,
+                })
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_create(
+        ctx: Arc<dyn TableContext>,
+        table: &FuseTable,
+        name: String,
+        key_fields: Vec<OnConflictField>,
+        bloom_filter_column_indexes: Vec<FieldIndex>,
+        segment_locations: Vec<Location>,
+        table_range_index: HashMap<ColumnId, ColumnStatistics>,
+    ) -> Result<Self> {
+        let data_accessor = table.get_operator();
+        let table_schema = table.schema_with_stream();
+        let segment_reader =
+            MetaReaders::segment_info_reader(data_accessor.clone(), table_schema.clone());
+
+        let key_column_reader = {
+            let projection =
+                Projection::Columns(key_fields.iter().map(|f| f.field_index).collect());
+            BlockReader::create(
+                ctx.clone(),
+                data_accessor.clone(),
+                table_schema,
+                projection,
+                false,
+                false,
+                false,
+            )
+        }?;
+
+        Ok(Self {
+            name,
+            key_fields,
+            bloom_filter_column_indexes,
+            table_range_index,
+            segment_locations,
+            key_column_reader,
+            segment_reader,
+            data_accessor,
+            storage_format: table.get_write_settings().storage_format,
+            read_settings: ReadSettings::from_ctx(&ctx)?,
+            func_ctx: ctx.get_function_context()?,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn key_fields(&self) -> &[OnConflictField] {
+        &self.key_fields
+    }
+
+    /// The digests of the keys, `None` if any key column of the row is NULL.
+    pub fn key_hashes(&self, keys: &DataBlock) -> Result<Vec<Option<UniqueKeyDigest>>> {
+        let columns = keys.columns().iter().map(|c| &c.value).collect::<Vec<_>>();
+        (0..keys.num_rows())
+            .map(|row| row_hash_of_columns(&columns, row))
+            .collect()
+    }
+
+    /// The error of a key which is not unique.
+    pub fn violation(&self, keys: &DataBlock, row: usize) -> Result<ErrorCode> {
+        let mut names = Vec::with_capacity(self.key_fields.len());
+        let mut values = Vec::with_capacity(self.key_fields.len());
+        for (field, entry) in self.key_fields.iter().zip(keys.columns()) {
+            names.push(field.table_field.name.clone());
+            let value = entry.value.row_scalar(row)?;
+            values.push(ReplaceIntoMutator::extract_col_value_for_err_message(value));
+        }
+        Ok(ErrorCode::ConstraintViolation(format!(
+            "Duplicate key value violates unique constraint '{}': ({})=({})",
+            self.name,
+            names.join(", "),
+            values.join(", ")
+        )))
+    }
+
+    /// Read the key columns of a stored block.
+    pub async fn read_keys(&self, block_meta: &BlockMeta) -> Result<DataBlock> {
+        read_block(
+            self.storage_format,
+            &self.key_column_reader,
+            block_meta,
+            &self.read_settings,
+        )
+        .await
+    }
+
+    pub async fn read_segment(&self, location: &Location) -> Result<Arc<CompactSegmentInfo>> {
+        let load_param = LoadParams {
+            location: location.0.clone(),
+            len_hint: None,
+            ver: location.1,
+            put_cache: true,
+        };
+        self.segment_reader.read(&load_param).await
+    }
+
+    /// Find the digests of the keys which are stored in the segments of the checker.
+    #[async_backtrace::framed]
+    pub async fn stored_keys(
+        &self,
+        keys: &DataBlock,
+        hashes: &[Option<UniqueKeyDigest>],
+    ) -> Result<HashSet<UniqueKeyDigest>> {
+        let mut stored = HashSet::new();
+        if self.segment_locations.is_empty() {
+            return Ok(stored);
+        }
+
+        // rows that definitely have no conflict are pruned by the table level range index
+        let mut rows = Vec::new();
+        for (row, hash) in hashes.iter().enumerate() {
+            if hash.is_some() && self.within_table_range(keys, row)? {
+                rows.push(row);
+            }
+        }
+        if rows.is_empty() {
+            return Ok(stored);
+        }
+
+        let probe = rows
+            .iter()
+            .filter_map(|row| hashes[*row])
+            .collect::<HashSet<_>>();
+        let columns_min_max = Self::columns_min_max(keys, &rows)?;
+        let bloom_hashes = self.bloom_hashes(keys, &rows)?;
+
+        for location in &self.segment_locations {
+            let segment = self.read_segment(location).await?;
+            if !AggregationContext::check_overlap(
+                &self.key_fields,
+                &segment.summary.col_stats,
+                &columns_min_max,
+            ) {
+                continue;
+            }
+            for block_meta in segment.block_metas()? {
+                if !AggregationContext::check_overlap(
+                    &self.key_fields,
+                    &block_meta.col_stats,
+                    &columns_min_max,
+                ) || self.bloom_pruned(&block_meta, &bloom_hashes).await
+                {
+                    continue;
+                }
+                let block_keys = self.read_keys(&block_meta).await?;
+                for hash in self.key_hashes(&block_keys)?.into_iter().flatten() {
+                    if probe.contains(&hash) {
+                        stored.insert(hash);
+                    }
+                }
+            }
+        }
+        Ok(stored)
+    }
+
+    fn within_table_range(&self, keys: &DataBlock, row: usize) -> Result<bool> {
+        for (field, entry) in self.key_fields.iter().zip(keys.columns()) {
+            if let Some(stats) = self.table_range_index.get(&field.table_field.column_id) {
+                let value = entry.value.row_scalar(row)?;
+                if value < stats.min().as_ref() || value > stats.max().as_ref() {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn columns_min_max(keys: &DataBlock, rows: &[usize]) -> Result<Vec<(Scalar, Scalar)>> {
+        let mut res = Vec::with_capacity(keys.num_columns());
+        for entry in keys.columns() {
+            let mut min = entry.value.row_scalar(rows[0])?;
+            let mut max = min.clone();
+            for row in &rows[1..] {
+                let value = entry.value.row_scalar(*row)?;
+                if value < min {
+                    min = value;
+                } else if value > max {
+                    max = value;
+                }
+            }
+            res.push((min.to_owned(), max.to_owned()));
+        }
+        Ok(res)
+    }
+
+    // the bloom digests of the rows, for each column being pruned with
+    fn bloom_hashes(&self, keys: &DataBlock, rows: &[usize]) -> Result<Vec<Vec<u64>>> {
+        self.bloom_filter_column_indexes
+            .iter()
+            .map(|idx| {
+                let entry = keys.get_by_offset(*idx);
+                let column = entry
+                    .value
+                    .convert_to_full_column(&entry.data_type, keys.num_rows());
+                let (digests, _) = BloomIndex::calculate_nullable_column_digest(
+                    &self.func_ctx,
+                    &column,
+                    &entry.data_type,
+                )?;
+                Ok(rows.iter().map(|row| digests[*row]).collect())
+            })
+            .collect()
+    }
+
+    // return true if the block is pruned, otherwise false
+    async fn bloom_pruned(&self, block_meta: &BlockMeta, bloom_hashes: &[Vec<u64>]) -> bool {
+        if bloom_hashes.is_empty() {
+            return false;
+        }
+        let Some(location) = &block_meta.bloom_filter_index_location else {
+            return false;
+        };
+
+        let col_names = match self
+            .bloom_filter_column_indexes
+            .iter()
+            .map(|idx| {
+                BloomIndex::build_filter_column_name(location.1, &self.key_fields[*idx].table_field)
+            })
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(col_names) => col_names,
+            Err(e) => {
+                warn!("failed to build bloom index column name: {}", e);
+                return false;
+            }
+        };
+        let block_filter = match location
+            .read_block_filter(
+                self.data_accessor.clone(),
+                &col_names,
+                block_meta.bloom_filter_index_size,
+            )
+            .await
+        {
+            Ok(block_filter) => block_filter,
+            Err(e) => {
+                // broken index should not stop us
+                warn!("failed to load bloom filter of block {}: {}", location.0, e);
+                return false;
+            }
+        };
+        let filters = col_names
+            .iter()
+            .map(|name| {
+                block_filter
+                    .filter_schema
+                    .index_of(name)
+                    .ok()
+                    .map(|idx| block_filter.filters[idx].clone())
+            })
+            .collect::<Vec<_>>();
+
+        // the block is pruned if, for every row, any column is not contained by its filter
+        (0..bloom_hashes[0].len()).all(|row| {
+            filters.iter().zip(bloom_hashes).any(|(filter, hashes)| {
+                filter
+                    .as_ref()
+                    .is_some_and(|filter| !filter.contains_digest(hashes[row]))
+            })
+        })
+    }
+}
@@ -18,7 +18,11 @@ mod processor_broadcast;
 mod processor_replace_into;
 mod processor_unbranched_replace_into;
 mod transform_replace_into_mutation_aggregator;
+mod transform_unique_key_check;
 
 pub use processor_broadcast::BroadcastProcessor;
 pub use processor_replace_into::ReplaceIntoProcessor;
 pub use processor_unbranched_replace_into::UnbranchedReplaceIntoProcessor;
+pub use transform_unique_key_check::AppendedKeys;
+pub use transform_unique_key_check::TransformMutationUniqueKeyCheck;
+pub use transform_unique_key_check::TransformUniqueKeyCheck;
@@ -0,0 +1,288 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ahash::HashSet;
+use ahash::HashSetExt;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::types::MutableBitmap;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_expression::FieldIndex;
+use databend_common_pipeline_transforms::processors::AsyncTransform;
+use databend_storages_common_table_meta::meta::TableSnapshot;
+use log::info;
+use parking_lot::Mutex;
+
+use crate::operations::common::CommitMeta;
+use crate::operations::common::ConflictResolveContext;
+use crate::operations::replace_into::meta::UniqueKeyDigest;
+use crate::operations::replace_into::mutator::UniqueKeyChecker;
+use crate::FuseTable;
+
+struct UniqueKey {
+    checker: UniqueKeyChecker,
+    // the field indexes of the key columns in the input blocks
+    field_indexes: Vec<FieldIndex>,
+    // skip the conflicting rows, instead of failing the insertion
+    ignore_conflicts: bool,
+}
+
+/// The keys of the rows appended so far, one set per key constraint, shared by the
+/// transforms checking the blocks of an insertion in parallel.
+#[derive(Clone, Default)]
+pub struct AppendedKeys(Arc<Mutex<Vec<HashSet<UniqueKeyDigest>>>>);
+
+/// Checks the PRIMARY KEY and UNIQUE constraints of the rows being appended to a table.
+///
+/// A row conflicts if its key is stored in the table already, or is the key of a row appended
+/// before it. Rows with a NULL key column never conflict.
+///
+/// The stored keys are looked up by each transform independently, only the check against the
+/// appended keys is serialized on the shared `AppendedKeys`.
+pub struct TransformUniqueKeyCheck {
+    ctx: Arc<dyn TableContext>,
+    table: FuseTable,
+    // the names of the constraints whose conflicting rows are skipped (ON CONFLICT DO NOTHING)
+    ignore_conflicts: Vec<String>,
+    // the stored rows are replaced, only the appended rows are checked against each other
+    overwrite: bool,
+    keys: Vec<UniqueKey>,
+    appended_keys: AppendedKeys,
+}
+
+impl TransformUniqueKeyCheck {
+    pub fn new(
+        ctx: Arc<dyn TableContext>,
+        table: FuseTable,
+        ignore_conflicts: Vec<String>,
+        overwrite: bool,
+        appended_keys: AppendedKeys,
+    ) -> Self {
+        Self {
+            ctx,
+            table,
+            ignore_conflicts,
+            overwrite,
+            keys: vec![],
+            appended_keys,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransform for TransformUniqueKeyCheck {
+    const NAME: &'static str = "TransformUniqueKeyCheck";
+
+    #[async_backtrace::framed]
+    async fn on_start(&mut self) -> Result<()> {
+        let snapshot = if self.overwrite {
+            None
+        } else {
+            self.table.read_table_snapshot().await?
+        };
+        let (segments, table_range_index) = match &snapshot {
+            Some(snapshot) => (
+                snapshot.segments.clone(),
+                snapshot.summary.col_stats.clone(),
+            ),
+            None => (vec![], HashMap::new()),
+        };
+
+        // the input blocks are in the order of the table schema, without the virtual computed columns
+        let input_schema = self.table.schema().remove_virtual_computed_fields();
+        let checkers = self
+            .table
+            .build_unique_key_checkers(self.ctx.clone(), &segments, &table_range_index)
+            .await?;
+        for checker in checkers {
+            let field_indexes = checker
+                .key_fields()
+                .iter()
+                .map(|field| input_schema.index_of(&field.table_field.name))
+                .collect::<Result<Vec<_>>>()?;
+            let ignore_conflicts = self.ignore_conflicts.iter().any(|c| c == checker.name());
+            self.keys.push(UniqueKey {
+                checker,
+                field_indexes,
+                ignore_conflicts,
+            });
+        }
+
+        let mut appended_keys = self.appended_keys.0.lock();
+        if appended_keys.is_empty() {
+            appended_keys.resize_with(self.keys.len(), HashSet::new);
+        }
+        Ok(())
+    }
+
+    #[async_backtrace::framed]
+    async fn transform(&mut self, data_block: DataBlock) -> Result<DataBlock> {
+        let num_rows = data_block.num_rows();
+        if num_rows == 0 || self.keys.is_empty() {
+            return Ok(data_block);
+        }
+
+        let mut key_blocks = Vec::with_capacity(self.keys.len());
+        let mut hashes = Vec::with_capacity(self.keys.len());
+        let mut stored = Vec::with_capacity(self.keys.len());
+        for key in &self.keys {
+            let columns = key
+                .field_indexes
+                .iter()
+                .map(|idx| data_block.get_by_offset(*idx).clone())
+                .collect();
+            let key_block = DataBlock::new(columns, num_rows);
+            let key_hashes = key.checker.key_hashes(&key_block)?;
+            stored.push(key.checker.stored_keys(&key_block, &key_hashes).await?);
+            hashes.push(key_hashes);
+            key_blocks.push(key_block);
+        }
+
+        let mut appended_keys = self.appended_keys.0.lock();
+        let mut bitmap = MutableBitmap::new();
+        for row in 0..num_rows {
+            let conflicts = |idx: usize, appended: &HashSet<UniqueKeyDigest>| {
+                hashes[idx][row].is_some_and(|h| appended.contains(&h) || stored[idx].contains(&h))
+            };
+            // a row conflicting on any key of ON CONFLICT DO NOTHING is skipped, otherwise
+            // the conflict on any key fails the insertion
+            if self
+                .keys
+                .iter()
+                .enumerate()
+                .any(|(idx, key)| key.ignore_conflicts && conflicts(idx, &appended_keys[idx]))
+            {
+                bitmap.push(false);
+                continue;
+            }
+            if let Some(idx) =
+                (0..self.keys.len()).find(|idx| conflicts(*idx, &appended_keys[*idx]))
+            {
+                return Err(self.keys[idx].checker.violation(&key_blocks[idx], row)?);
+            }
+            for (idx, appended) in appended_keys.iter_mut().enumerate() {
+                if let Some(hash) = hashes[idx][row] {
+                    appended.insert(hash);
+                }
+            }
+            bitmap.push(true);
+        }
+
+        drop(appended_keys);
+
+        let num_skipped = bitmap.null_count();
+        if num_skipped == 0 {
+            return Ok(data_block);
+        }
+        info!(
+            "{} conflicting rows skipped while appending to table {}",
+            num_skipped,
+            self.table.name()
+        );
+        data_block.filter_with_bitmap(&bitmap.into())
+    }
+}
+
+/// Checks the PRIMARY KEY and UNIQUE constraints of the blocks written by a mutation, before the
+/// mutation is committed.
+///
+/// The input is the commit meta generated by the `TableMutationAggregator`, the keys of the
+/// segments appended or replaced by the mutation are checked against each other, and against the
+/// segments of the base snapshot which are kept by the mutation.
+pub struct TransformMutationUniqueKeyCheck {
+    ctx: Arc<dyn TableContext>,
+    table: FuseTable,
+    base_snapshot: Option<Arc<TableSnapshot>>,
+}
+
+impl TransformMutationUniqueKeyCheck {
+    pub fn new(
+        ctx: Arc<dyn TableContext>,
+        table: FuseTable,
+        base_snapshot: Option<Arc<TableSnapshot>>,
+    ) -> Self {
+        Self {
+            ctx,
+            table,
+            base_snapshot,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransform for TransformMutationUniqueKeyCheck {
+    const NAME: &'static str = "TransformMutationUniqueKeyCheck";
+
+    #[async_backtrace::framed]
+    async fn transform(&mut self, data_block: DataBlock) -> Result<DataBlock> {
+        let Some(ConflictResolveContext::ModifiedSegmentExistsInLatest(changes)) = data_block
+            .get_meta()
+            .and_then(CommitMeta::downcast_ref_from)
+            .map(|meta| &meta.conflict_resolve_context)
+        else {
+            return Ok(data_block);
+        };
+
+        let (kept_segments, table_range_index) = match &self.base_snapshot {
+            Some(snapshot) => (
+                snapshot
+                    .segments
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| {
+                        !changes.replaced_segments.contains_key(idx)
+                            && !changes.removed_segment_indexes.contains(idx)
+                    })
+                    .map(|(_, location)| location.clone())
+                    .collect::<Vec<_>>(),
+                snapshot.summary.col_stats.clone(),
+            ),
+            None => (vec![], HashMap::new()),
+        };
+
+        let checkers = self
+            .table
+            .build_unique_key_checkers(self.ctx.clone(), &kept_segments, &table_range_index)
+            .await?;
+        for checker in checkers {
+            let mut key_saw = HashSet::new();
+            for location in changes
+                .appended_segments
+                .iter()
+                .chain(changes.replaced_segments.values())
+            {
+                let segment = checker.read_segment(location).await?;
+                for block_meta in segment.block_metas()? {
+                    let keys = checker.read_keys(&block_meta).await?;
+                    let hashes = checker.key_hashes(&keys)?;
+                    let stored = checker.stored_keys(&keys, &hashes).await?;
+                    for (row, hash) in hashes.iter().enumerate() {
+                        let Some(hash) = hash else {
+                            continue;
+                        };
+                        if stored.contains(hash) || !key_saw.insert(*hash) {
+                            return Err(checker.violation(&keys, row)?);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(data_block)
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_common_sql::executor::physical_plans::MutationKind;
+use databend_storages_common_table_meta::meta::Location;
+use databend_storages_common_table_meta::meta::Statistics;
+use databend_storages_common_table_meta::meta::TableSnapshot;
+use databend_storages_common_table_meta::meta::Versioned;
+use databend_storages_common_table_meta::table::OPT_KEY_LEGACY_SNAPSHOT_LOC;
+use databend_storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
+use log::info;
+
+use crate::io::MetaWriter;
+use crate::io::SegmentsIO;
+use crate::operations::common::ConflictResolveContext;
+use crate::operations::common::MutationGenerator;
+use crate::operations::common::SnapshotChanges;
+use crate::operations::common::SnapshotGenerator;
+use crate::statistics::reducers::merge_statistics_mut;
+use crate::FuseTable;
+
+impl FuseTable {
+    /// Rebase the segments added and removed by a transaction onto the latest snapshot.
+    ///
+    /// `self` is the table changed by the transaction, `base` is the version of the table the
+    /// transaction read first, and `latest` is the version committed by others since then.
+    /// The changes conflict if the definition of the table is changed since `base`, a segment
+    /// removed by the transaction is removed by others too, or a unique key appended by the
+    /// transaction is appended by others too.
+    #[async_backtrace::framed]
+    pub async fn do_rebase_txn_mutation(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        base: &TableInfo,
+        latest: &TableInfo,
+    ) -> Result<TableMeta> {
+        if !same_definition(&base.meta, &latest.meta) {
+            return Err(ErrorCode::UnresolvableConflict(format!(
+                "The definition of table '{}' is changed by another transaction",
+                self.name()
+            )));
+        }
+
+        let base_table = FuseTable::do_create(base.clone())?;
+        let latest_table = FuseTable::do_create(latest.clone())?;
+        let base_snapshot = base_table.read_table_snapshot().await?;
+        let latest_snapshot = latest_table.read_table_snapshot().await?;
+        let txn_snapshot = self.read_table_snapshot().await?;
+
+        let base_segments = base_snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.segments.clone())
+            .unwrap_or_default();
+        let txn_segments = txn_snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.segments.clone())
+            .unwrap_or_default();
+        let changes = self
+            .collect_txn_changes(&base_segments, &txn_segments)
+            .await?;
+        info!(
+            "rebase transaction changes of table {}: {} segments appended, {} segments removed",
+            self.name(),
+            changes.appended_segments.len(),
+            changes.removed_segment_indexes.len()
+        );
+
+        let appended_segments = changes.appended_segments.clone();
+        let mut generator = MutationGenerator::new(base_snapshot.clone(), MutationKind::Update);
+        generator.set_conflict_resolve_context(
+            ConflictResolveContext::ModifiedSegmentExistsInLatest(changes),
+        );
+        let table_meta_timestamps =
+            ctx.get_table_meta_timestamps(latest_table.as_ref(), latest_snapshot.clone())?;
+        let snapshot = generator.do_generate_new_snapshot(
+            self.schema().as_ref().clone(),
+            latest_table.cluster_key_id(),
+            &latest_snapshot,
+            Some(latest.ident.seq),
+            table_meta_timestamps,
+            self.name(),
+        )?;
+
+        // The keys appended by the transaction are checked against the table it read,
+        // check them against the segments committed by others since then too.
+        self.check_concurrent_unique_keys(
+            ctx,
+            &snapshot,
+            base_snapshot.as_deref(),
+            &appended_segments,
+        )
+        .await?;
+
+        let location = self
+            .meta_location_generator()
+            .snapshot_location_from_uuid(&snapshot.snapshot_id, TableSnapshot::VERSION)?;
+        snapshot
+            .write_meta(self.get_operator_ref(), &location)
+            .await?;
+
+        // The meta of the transaction is committed, which keeps the columns and comments
+        // altered by the transaction, as the definition is not changed by others.
+        FuseTable::build_new_table_meta(&self.table_info.meta, &location, &snapshot)
+    }
+
+    /// Collect the segments appended and removed by the transaction, with their statistics.
+    async fn collect_txn_changes(
+        &self,
+        base_segments: &[Location],
+        txn_segments: &[Location],
+    ) -> Result<SnapshotChanges> {
+        let base_set = base_segments.iter().collect::<HashSet<_>>();
+        let txn_set = txn_segments.iter().collect::<HashSet<_>>();
+        let cluster_key_id = self.cluster_key_id();
+        let schema = self.schema();
+
+        let mut merged_statistics = Statistics::default();
+        let mut appended_segments = vec![];
+        for location in txn_segments {
+            if base_set.contains(location) {
+                continue;
+            }
+            let segment = SegmentsIO::read_compact_segment(
+                self.get_operator(),
+                location.clone(),
+                schema.clone(),
+                false,
+            )
+            .await?;
+            merge_statistics_mut(&mut merged_statistics, &segment.summary, cluster_key_id);
+            appended_segments.push(location.clone());
+        }
+
+        let mut removed_statistics = Statistics::default();
+        let mut removed_segment_indexes = vec![];
+        for (index, location) in base_segments.iter().enumerate() {
+            if txn_set.contains(location) {
+                continue;
+            }
+            let segment = SegmentsIO::read_compact_segment(
+                self.get_operator(),
+                location.clone(),
+                schema.clone(),
+                false,
+            )
+            .await?;
+            merge_statistics_mut(&mut removed_statistics, &segment.summary, cluster_key_id);
+            removed_segment_indexes.push(index);
+        }
+
+        Ok(SnapshotChanges {
+            appended_segments,
+            replaced_segments: HashMap::new(),
+            removed_segment_indexes,
+            merged_statistics,
+            removed_statistics,
+        })
+    }
+}
+
+/// Whether the definition of the table is the same, regardless of its data.
+fn same_definition(l: &TableMeta, r: &TableMeta) -> bool {
+    let data_options = [OPT_KEY_SNAPSHOT_LOCATION, OPT_KEY_LEGACY_SNAPSHOT_LOC];
+    let options = |meta: &TableMeta| {
+        meta.options
+            .iter()
+            .filter(|(k, _)| !data_options.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>()
+    };
+    l.schema == r.schema
+        && l.cluster_key == r.cluster_key
+        && l.cluster_key_seq == r.cluster_key_seq
+        && l.comment == r.comment
+        && l.field_comments == r.field_comments
+        && l.indexes == r.indexes
+        && options(l) == options(r)
+}
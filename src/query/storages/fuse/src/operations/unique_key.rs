@@ -0,0 +1,178 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::ColumnId;
+use databend_common_pipeline_core::Pipeline;
+use databend_common_pipeline_transforms::processors::TransformPipelineHelper;
+use databend_storages_common_table_meta::meta::ColumnStatistics;
+use databend_storages_common_table_meta::meta::Location;
+use databend_storages_common_table_meta::meta::TableSnapshot;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraint;
+
+use crate::operations::replace_into::AppendedKeys;
+use crate::operations::replace_into::TransformMutationUniqueKeyCheck;
+use crate::operations::replace_into::TransformUniqueKeyCheck;
+use crate::operations::replace_into::UniqueKeyChecker;
+use crate::FuseTable;
+
+impl FuseTable {
+    /// The PRIMARY KEY and UNIQUE constraints of the table.
+    pub fn key_constraints(&self) -> Result<Vec<TableConstraint>> {
+        Ok(get_table_constraints(self.table_info.options())?
+            .into_iter()
+            .filter(|constraint| constraint.key_columns().is_some())
+            .collect())
+    }
+
+    /// Build the checkers of the key constraints, which look up the keys in the given segments.
+    #[async_backtrace::framed]
+    pub async fn build_unique_key_checkers(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        segment_locations: &[Location],
+        table_range_index: &HashMap<ColumnId, ColumnStatistics>,
+    ) -> Result<Vec<UniqueKeyChecker>> {
+        let max_bloom_columns = ctx
+            .get_settings()
+            .get_replace_into_bloom_pruning_max_column_number()?;
+        let schema = self.schema();
+        let mut checkers = vec![];
+        for constraint in self.key_constraints()? {
+            let key_columns = constraint.key_columns().unwrap_or_default();
+            let key_fields = UniqueKeyChecker::resolve_key_fields(&schema, key_columns)?;
+            let bloom_filter_column_indexes = self
+                .choose_bloom_filter_columns(ctx.clone(), &key_fields, max_bloom_columns)
+                .await?;
+            checkers.push(UniqueKeyChecker::try_create(
+                ctx.clone(),
+                self,
+                constraint.name,
+                key_fields,
+                bloom_filter_column_indexes,
+                segment_locations.to_vec(),
+                table_range_index.clone(),
+            )?);
+        }
+        Ok(checkers)
+    }
+
+    /// Check the key constraints of the rows being appended, the rows conflicting on the
+    /// constraints of `ignore_conflicts` are skipped.
+    pub fn add_unique_key_check(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        ignore_conflicts: &[String],
+        overwrite: bool,
+    ) -> Result<()> {
+        if self.key_constraints()?.is_empty() {
+            return Ok(());
+        }
+
+        // the stored keys are looked up in parallel, the keys of all the appended rows are
+        // checked against each other through the shared key sets
+        let appended_keys = AppendedKeys::default();
+        pipeline.add_async_transformer(|| {
+            TransformUniqueKeyCheck::new(
+                ctx.clone(),
+                self.clone(),
+                ignore_conflicts.to_vec(),
+                overwrite,
+                appended_keys.clone(),
+            )
+        });
+        Ok(())
+    }
+
+    /// Check the key constraints of the blocks written by a mutation of `base_snapshot`,
+    /// the pipeline outputs the commit meta of the mutation.
+    pub fn add_mutation_unique_key_check(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        base_snapshot: Option<Arc<TableSnapshot>>,
+    ) -> Result<()> {
+        if self.key_constraints()?.is_empty() {
+            return Ok(());
+        }
+
+        pipeline.add_async_transformer(|| {
+            TransformMutationUniqueKeyCheck::new(ctx.clone(), self.clone(), base_snapshot.clone())
+        });
+        Ok(())
+    }
+
+    /// Check the keys of the segments written by a transaction against the segments committed
+    /// concurrently, which are in the `snapshot` to commit, but neither in the base snapshot of
+    /// the transaction nor written by it.
+    ///
+    /// The keys are checked against the base snapshot before committing, this check is needed
+    /// only if the commit is retried, after the concurrent commits are merged.
+    #[async_backtrace::framed]
+    pub async fn check_concurrent_unique_keys(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        snapshot: &TableSnapshot,
+        base_snapshot: Option<&TableSnapshot>,
+        new_segments: &[Location],
+    ) -> Result<()> {
+        if new_segments.is_empty() || self.key_constraints()?.is_empty() {
+            return Ok(());
+        }
+
+        let committed = base_snapshot
+            .iter()
+            .flat_map(|base| base.segments.iter())
+            .chain(new_segments)
+            .collect::<HashSet<_>>();
+        let concurrent_segments = snapshot
+            .segments
+            .iter()
+            .filter(|location| !committed.contains(location))
+            .cloned()
+            .collect::<Vec<_>>();
+        if concurrent_segments.is_empty() {
+            return Ok(());
+        }
+
+        let checkers = self
+            .build_unique_key_checkers(ctx, &concurrent_segments, &snapshot.summary.col_stats)
+            .await?;
+        for checker in checkers {
+            for location in new_segments {
+                let segment = checker.read_segment(location).await?;
+                for block_meta in segment.block_metas()? {
+                    let keys = checker.read_keys(&block_meta).await?;
+                    let hashes = checker.key_hashes(&keys)?;
+                    let stored = checker.stored_keys(&keys, &hashes).await?;
+                    if let Some(row) = hashes
+                        .iter()
+                        .position(|hash| hash.is_some_and(|hash| stored.contains(&hash)))
+                    {
+                        return Err(checker.violation(&keys, row)?);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
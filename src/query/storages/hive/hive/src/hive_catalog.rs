@@ -18,6 +18,7 @@ use std::net::ToSocketAddrs;
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::Utc;
 use databend_common_catalog::catalog::Catalog;
 use databend_common_catalog::catalog::CatalogCreator;
 use databend_common_catalog::catalog::StorageDescription;
@@ -239,6 +240,53 @@ impl HiveCatalog {
         Ok(partition_names.into_iter().map(|v| v.to_string()).collect())
     }
 
+    /// Register the partitions of the table in the metastore, each with the values of the
+    /// partition columns and the location of the partition.
+    ///
+    /// The storage descriptor of the partitions is inherited from the table.
+    #[fastrace::trace]
+    #[async_backtrace::framed]
+    pub async fn add_partitions(
+        &self,
+        db: String,
+        table: String,
+        partitions: Vec<(Vec<String>, String)>,
+    ) -> Result<()> {
+        let table_meta = self
+            .client
+            .get_table(FastStr::new(&db), FastStr::new(&table))
+            .await
+            .map(from_thrift_exception)
+            .map_err(from_thrift_error)??;
+
+        let create_time = Utc::now().timestamp() as i32;
+        let partitions = partitions
+            .into_iter()
+            .map(|(values, location)| {
+                let mut sd = table_meta.sd.clone();
+                if let Some(sd) = sd.as_mut() {
+                    sd.location = Some(FastStr::new(location));
+                }
+                Partition {
+                    values: Some(values.into_iter().map(FastStr::new).collect()),
+                    db_name: Some(FastStr::new(&db)),
+                    table_name: Some(FastStr::new(&table)),
+                    create_time: Some(create_time),
+                    last_access_time: Some(0),
+                    sd,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.client
+            .add_partitions(partitions)
+            .await
+            .map(from_thrift_exception)
+            .map_err(from_thrift_error)??;
+        Ok(())
+    }
+
     fn handle_table_meta(table_meta: &hive_metastore::Table) -> Result<()> {
         if let Some(sd) = table_meta.sd.as_ref() {
             if let Some(input_format) = sd.input_format.as_ref() {
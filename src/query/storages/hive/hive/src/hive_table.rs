@@ -17,6 +17,7 @@ use std::time::Instant;
 
 use async_recursion::async_recursion;
 use databend_common_base::base::tokio::sync::Semaphore;
+use databend_common_base::runtime::GlobalIORuntime;
 use databend_common_catalog::catalog_kind::CATALOG_HIVE;
 use databend_common_catalog::partition_columns::get_pushdown_without_partition_columns;
 use databend_common_catalog::plan::DataSourcePlan;
@@ -44,11 +45,15 @@ use databend_common_functions::BUILTIN_FUNCTIONS;
 use databend_common_meta_app::schema::TableInfo;
 use databend_common_meta_app::schema::UpdateStreamMetaReq;
 use databend_common_meta_app::schema::UpsertTableCopiedFileReq;
+use databend_common_pipeline_core::always_callback;
 use databend_common_pipeline_core::processors::OutputPort;
 use databend_common_pipeline_core::processors::ProcessorPtr;
+use databend_common_pipeline_core::ExecutionInfo;
 use databend_common_pipeline_core::Pipeline;
+use databend_common_pipeline_sinks::AsyncSinker;
 use databend_common_pipeline_sources::SyncSource;
 use databend_common_pipeline_sources::SyncSourcer;
+use databend_common_pipeline_transforms::processors::AsyncAccumulatingTransformer;
 use databend_common_storage::init_operator;
 use databend_common_storage::DataOperator;
 use databend_common_storages_parquet::ParquetRSPruner;
@@ -65,6 +70,8 @@ use opendal::Operator;
 
 use super::hive_catalog::HiveCatalog;
 use super::hive_table_options::HiveTableOptions;
+use crate::hive_table_commit::HiveTableCommitter;
+use crate::hive_table_sink::HiveTableSink;
 use crate::hive_table_source::HiveTableSource;
 use crate::utils::HiveFetchPartitionScalars;
 use crate::HivePartInfo;
@@ -72,7 +79,11 @@ use crate::HivePartitionFiller;
 
 pub const HIVE_TABLE_ENGINE: &str = "hive";
 pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+/// The prefix of the hidden directory under the table location, where the files of an
+/// INSERT are written before they are committed.
+pub const HIVE_STAGING_DIR_PREFIX: &str = ".databend-staging-";
 
+#[derive(Clone)]
 pub struct HiveTable {
     table_info: TableInfo,
     table_options: HiveTableOptions,
@@ -110,6 +121,44 @@ impl HiveTable {
             .collect()
     }
 
+    pub(crate) fn table_location(&self) -> Result<&str> {
+        self.table_options.location.as_deref().ok_or_else(|| {
+            ErrorCode::TableInfoError(format!("{}, table location is empty", self.table_info.name))
+        })
+    }
+
+    /// Returns the database and table name in the hive metastore.
+    pub(crate) fn database_and_table_name(&self) -> (String, String) {
+        let names = self.table_info.desc.split('.').collect::<Vec<&str>>();
+        (names[0].to_string(), names[1].to_string())
+    }
+
+    #[async_backtrace::framed]
+    pub(crate) async fn get_catalog(&self, ctx: &Arc<dyn TableContext>) -> Result<HiveCatalog> {
+        let catalog = ctx.get_catalog(self.table_info.catalog()).await?;
+        let hive_catalog = catalog
+            .as_any()
+            .downcast_ref::<HiveCatalog>()
+            .ok_or_else(|| {
+                ErrorCode::Internal(format!(
+                    "Catalog {} of table {} is not a hive catalog",
+                    self.table_info.catalog(),
+                    self.table_info.name
+                ))
+            })?;
+        Ok(hive_catalog.clone())
+    }
+
+    pub(crate) fn operator(&self) -> &Operator {
+        &self.dal
+    }
+
+    /// Returns the staging directory of the query, which is hidden from the readers.
+    pub(crate) fn staging_dir(&self, query_id: &str) -> Result<String> {
+        let table_dir = convert_hdfs_path(self.table_location()?, true);
+        Ok(format!("{table_dir}{HIVE_STAGING_DIR_PREFIX}{query_id}/"))
+    }
+
     fn no_partition_schema(&self) -> Arc<TableSchema> {
         let non_partition_fields = self
             .schema()
@@ -291,9 +340,7 @@ impl HiveTable {
         ctx: Arc<dyn TableContext>,
         push_downs: &Option<PushDownInfo>,
     ) -> Result<Vec<(String, Option<String>)>> {
-        let path = self.table_options.location.as_ref().ok_or_else(|| {
-            ErrorCode::TableInfoError(format!("{}, table location is empty", self.table_info.name))
-        })?;
+        let path = self.table_location()?;
 
         if let Some(partition_keys) = &self.table_options.partition_keys {
             if !partition_keys.is_empty() {
@@ -403,7 +450,7 @@ impl Table for HiveTable {
     }
 
     fn as_any(&self) -> &(dyn std::any::Any + 'static) {
-        todo!()
+        self
     }
 
     fn get_table_info(&self) -> &TableInfo {
@@ -442,30 +489,85 @@ impl Table for HiveTable {
         self.do_read_data(ctx, plan, pipeline)
     }
 
+    fn partition_columns(&self) -> Vec<String> {
+        self.table_options
+            .partition_keys
+            .clone()
+            .unwrap_or_default()
+    }
+
+    fn append_data(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        _table_meta_timestamps: TableMetaTimestamps,
+    ) -> Result<()> {
+        pipeline.add_transform(|input, output| {
+            Ok(ProcessorPtr::create(AsyncAccumulatingTransformer::create(
+                input,
+                output,
+                HiveTableSink::try_create(ctx.clone(), self)?,
+            )))
+        })
+    }
+
     fn commit_insertion(
         &self,
-        _ctx: Arc<dyn TableContext>,
-        _pipeline: &mut Pipeline,
+        ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
         _copied_files: Option<UpsertTableCopiedFileReq>,
-        _update_stream_meta: Vec<UpdateStreamMetaReq>,
-        _overwrite: bool,
+        update_stream_meta: Vec<UpdateStreamMetaReq>,
+        overwrite: bool,
         _prev_snapshot_id: Option<SnapshotId>,
         _deduplicated_label: Option<String>,
         _table_meta_timestamps: TableMetaTimestamps,
     ) -> Result<()> {
-        Err(ErrorCode::Unimplemented(format!(
-            "commit_insertion operation for table {} is not implemented, table engine is {}",
-            self.name(),
-            self.get_table_info().meta.engine
-        )))
+        // The files are committed to the hive metastore, the offsets of the streams can not
+        // be updated in the same commit.
+        if !update_stream_meta.is_empty() {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Insert into hive table {} from stream is not supported",
+                self.name()
+            )));
+        }
+
+        // Remove the staged files if the insertion fails, they are moved into the table
+        // by the committer otherwise.
+        let operator = self.dal.clone();
+        let staging_dir = self.staging_dir(&ctx.get_id())?;
+        pipeline.set_on_finished(always_callback(move |info: &ExecutionInfo| {
+            if info.res.is_err() {
+                GlobalIORuntime::instance().block_on(async move {
+                    operator.remove_all(&staging_dir).await?;
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        }));
+
+        pipeline.try_resize(1)?;
+        pipeline.add_sink(|input| {
+            Ok(ProcessorPtr::create(AsyncSinker::create(
+                input,
+                HiveTableCommitter::create(ctx.clone(), self.clone(), overwrite),
+            )))
+        })
     }
 
+    /// Remove the data files of the table, the partitions are kept in the metastore.
     #[async_backtrace::framed]
-    async fn truncate(&self, _ctx: Arc<dyn TableContext>, _pipeline: &mut Pipeline) -> Result<()> {
-        Err(ErrorCode::Unimplemented(format!(
-            "truncate for table {} is not implemented",
-            self.name()
-        )))
+    async fn truncate(&self, ctx: Arc<dyn TableContext>, _pipeline: &mut Pipeline) -> Result<()> {
+        let dirs = self.get_query_locations(ctx, &None).await?;
+        let files = self.list_files_from_dirs(dirs).await?;
+        for file in &files {
+            self.dal.delete(&file.filename).await?;
+        }
+        info!(
+            "truncate hive table {}, {} files removed",
+            self.name(),
+            files.len()
+        );
+        Ok(())
     }
 
     #[async_backtrace::framed]
@@ -492,6 +594,10 @@ impl Table for HiveTable {
     fn support_prewhere(&self) -> bool {
         true
     }
+
+    fn support_copied_files(&self) -> bool {
+        false
+    }
 }
 
 // Dummy Impl
@@ -610,8 +716,10 @@ async fn do_list_files_from_dir(
         let meta = de.metadata();
 
         let path = de.path();
-        let file_offset = path.rfind('/').unwrap_or_default() + 1;
-        if path[file_offset..].starts_with('.') || path[file_offset..].starts_with('_') {
+        // The hidden directories are skipped too, like the staging directory of INSERT.
+        let name = path.trim_end_matches('/');
+        let file_offset = name.rfind('/').map_or(0, |i| i + 1);
+        if name[file_offset..].starts_with('.') || name[file_offset..].starts_with('_') {
             continue;
         }
         // Ignore the location itself
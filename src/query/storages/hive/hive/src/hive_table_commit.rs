@@ -0,0 +1,169 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_pipeline_sinks::AsyncSink;
+use futures::TryStreamExt;
+use log::info;
+use opendal::EntryMode;
+
+use crate::hive_table::convert_hdfs_path;
+use crate::hive_table_sink::make_partition_name;
+use crate::hive_table_sink::HiveDataFile;
+use crate::hive_table_sink::HiveDataFiles;
+use crate::HiveTable;
+
+/// Collects the files written by all the [`crate::hive_table_sink::HiveTableSink`]s, moves
+/// them from the staging directory into the table, and registers the new partitions in the
+/// metastore.
+///
+/// On overwrite, the existing files of the written partitions are removed, which is the
+/// dynamic partition overwrite of hive. The whole table is overwritten if it is not
+/// partitioned.
+pub struct HiveTableCommitter {
+    ctx: Arc<dyn TableContext>,
+    table: HiveTable,
+    overwrite: bool,
+    files: Vec<HiveDataFile>,
+}
+
+impl HiveTableCommitter {
+    pub fn create(ctx: Arc<dyn TableContext>, table: HiveTable, overwrite: bool) -> Self {
+        Self {
+            ctx,
+            table,
+            overwrite,
+            files: vec![],
+        }
+    }
+
+    /// Remove the data files in the directory.
+    #[async_backtrace::framed]
+    async fn remove_old_files(&self, dir: &str) -> Result<()> {
+        let op = self.table.operator();
+        let mut lister = op.lister_with(dir).await?;
+        while let Some(entry) = lister.try_next().await? {
+            let path = entry.path();
+            let name = path[path.rfind('/').unwrap_or_default() + 1..].to_string();
+            // Hidden files are not data files, same as in listing the files to read.
+            if entry.metadata().mode() != EntryMode::FILE
+                || name.starts_with('.')
+                || name.starts_with('_')
+            {
+                continue;
+            }
+            op.delete(path).await?;
+        }
+        Ok(())
+    }
+
+    /// Move the file from the staging directory into the table.
+    #[async_backtrace::framed]
+    async fn move_file(&self, file: &HiveDataFile) -> Result<()> {
+        let op = self.table.operator();
+        if op.info().full_capability().rename {
+            op.rename(&file.staging_path, &file.path).await?;
+        } else {
+            op.copy(&file.staging_path, &file.path).await?;
+            op.delete(&file.staging_path).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncSink for HiveTableCommitter {
+    const NAME: &'static str = "HiveTableCommitter";
+
+    #[async_backtrace::framed]
+    async fn consume(&mut self, data_block: DataBlock) -> Result<bool> {
+        if let Some(meta) = data_block
+            .get_owned_meta()
+            .and_then(HiveDataFiles::downcast_from)
+        {
+            self.files.extend(meta.files);
+        }
+        Ok(false)
+    }
+
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self) -> Result<()> {
+        let partition_columns = self.table.partition_columns();
+        let mut partitions = BTreeMap::new();
+        for file in &self.files {
+            partitions.insert(file.partition.clone(), file.dir.clone());
+        }
+
+        if self.overwrite {
+            if partition_columns.is_empty() && partitions.is_empty() {
+                let table_dir = convert_hdfs_path(self.table.table_location()?, true);
+                self.remove_old_files(&table_dir).await?;
+            }
+            for dir in partitions.values() {
+                self.remove_old_files(dir).await?;
+            }
+        }
+
+        for file in &self.files {
+            self.move_file(file).await?;
+        }
+        let staging_dir = self.table.staging_dir(&self.ctx.get_id())?;
+        self.table.operator().remove_all(&staging_dir).await?;
+
+        if !partition_columns.is_empty() && !partitions.is_empty() {
+            let (db, table) = self.table.database_and_table_name();
+            let catalog = self.table.get_catalog(&self.ctx).await?;
+            let existing = catalog
+                .get_partition_names(db.clone(), table.clone(), -1)
+                .await?
+                .into_iter()
+                .collect::<HashSet<_>>();
+            let table_location = self.table.table_location()?.trim_end_matches('/');
+            let new_partitions = partitions
+                .into_keys()
+                .filter_map(|values| {
+                    let name = make_partition_name(&partition_columns, &values);
+                    (!existing.contains(&name))
+                        .then(|| (values, format!("{table_location}/{name}")))
+                })
+                .collect::<Vec<_>>();
+
+            if !new_partitions.is_empty() {
+                info!(
+                    "Hive add {} partitions to table {}.{}",
+                    new_partitions.len(),
+                    db,
+                    table
+                );
+                catalog.add_partitions(db, table, new_partitions).await?;
+            }
+        }
+
+        info!(
+            "Hive commit {} files to table {}",
+            self.files.len(),
+            self.table.name()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_array::UInt32Array;
+use arrow_cast::display::ArrayFormatter;
+use arrow_cast::display::FormatOptions;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::BlockMetaInfo;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_expression::TableSchemaRef;
+use databend_common_pipeline_transforms::processors::AsyncAccumulatingTransform;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use uuid::Uuid;
+
+use crate::hive_table::convert_hdfs_path;
+use crate::hive_table::HIVE_DEFAULT_PARTITION;
+use crate::HiveTable;
+
+/// Data files are closed once their size reaches this threshold.
+const MAX_FILE_SIZE: usize = 128 * 1024 * 1024;
+
+/// The data files written by [`HiveTableSink`], which are committed by
+/// [`crate::hive_table_commit::HiveTableCommitter`].
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct HiveDataFiles {
+    pub files: Vec<HiveDataFile>,
+}
+
+#[typetag::serde(name = "hive_data_files")]
+impl BlockMetaInfo for HiveDataFiles {
+    fn equals(&self, info: &Box<dyn BlockMetaInfo>) -> bool {
+        Self::downcast_ref_from(info).is_some_and(|other| self == other)
+    }
+
+    fn clone_self(&self) -> Box<dyn BlockMetaInfo> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct HiveDataFile {
+    /// The values of the partition columns, empty if the table is not partitioned.
+    pub partition: Vec<String>,
+    /// The directory of the partition, or of the table if it is not partitioned.
+    pub dir: String,
+    /// The path in the directory of the partition, where the file is moved to on commit.
+    pub path: String,
+    /// The path in the staging directory, where the file is written to.
+    pub staging_path: String,
+}
+
+/// Writes the appended blocks to parquet files of the hive table, one file writer for each
+/// partition. The written files are sent downstream in a single block with [`HiveDataFiles`]
+/// meta on finish.
+///
+/// The partition columns are not stored in the files. The files are written to the hidden
+/// staging directory of the query first, and moved by the committer to the location of the
+/// partition in the metastore, or to the sub directory of the table location named after the
+/// partition for a new one, like `c_region=ASIA/c_nation=CHINA`.
+pub struct HiveTableSink {
+    ctx: Arc<dyn TableContext>,
+    table: HiveTable,
+    table_schema: TableSchemaRef,
+    /// The index of the partition columns in the table schema.
+    partition_indices: Vec<usize>,
+    /// The index of the columns stored in the files.
+    data_indices: Vec<usize>,
+    staging_dir: String,
+    /// The directories of the partitions written.
+    dirs: HashMap<Vec<String>, String>,
+    writers: HashMap<Vec<String>, ArrowWriter<Vec<u8>>>,
+    files: Vec<HiveDataFile>,
+}
+
+impl HiveTableSink {
+    pub fn try_create(ctx: Arc<dyn TableContext>, table: &HiveTable) -> Result<Self> {
+        let table_schema = table.schema();
+        let partition_indices = table
+            .partition_columns()
+            .iter()
+            .map(|name| table_schema.index_of(name))
+            .collect::<Result<Vec<_>>>()?;
+        let data_indices = (0..table_schema.num_fields())
+            .filter(|i| !partition_indices.contains(i))
+            .collect();
+        let staging_dir = table.staging_dir(&ctx.get_id())?;
+
+        Ok(Self {
+            ctx,
+            table: table.clone(),
+            table_schema,
+            partition_indices,
+            data_indices,
+            staging_dir,
+            dirs: HashMap::new(),
+            writers: HashMap::new(),
+            files: vec![],
+        })
+    }
+
+    /// Returns the partition values and the row indices of each partition.
+    fn partition(&self, batch: &RecordBatch) -> Result<Vec<(Vec<String>, UInt32Array)>> {
+        let options = FormatOptions::new()
+            .with_date_format(Some("%Y-%m-%d"))
+            .with_timestamp_format(Some("%Y-%m-%d %H:%M:%S%.f"));
+        let formatters = self
+            .partition_indices
+            .iter()
+            .map(|index| {
+                ArrayFormatter::try_new(batch.column(*index).as_ref(), &options).map_err(|err| {
+                    ErrorCode::Unimplemented(format!(
+                        "Hive partition column {} is not supported: {err}",
+                        self.table_schema.field(*index).name
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut partitions: Vec<(Vec<String>, Vec<u32>)> = vec![];
+        let mut partition_indices: HashMap<Vec<String>, usize> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let values = self
+                .partition_indices
+                .iter()
+                .zip(formatters.iter())
+                .map(|(index, formatter)| {
+                    let value = formatter.value(row).to_string();
+                    if batch.column(*index).is_null(row) || value.is_empty() {
+                        HIVE_DEFAULT_PARTITION.to_string()
+                    } else {
+                        value
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            match partition_indices.get(&values) {
+                Some(index) => partitions[*index].1.push(row as u32),
+                None => {
+                    partition_indices.insert(values.clone(), partitions.len());
+                    partitions.push((values, vec![row as u32]));
+                }
+            }
+        }
+
+        Ok(partitions
+            .into_iter()
+            .map(|(values, rows)| (values, UInt32Array::from(rows)))
+            .collect())
+    }
+
+    /// Returns the directory to write the files of the partition to.
+    #[async_backtrace::framed]
+    async fn partition_dir(&mut self, partition: &[String]) -> Result<String> {
+        if let Some(dir) = self.dirs.get(partition) {
+            return Ok(dir.clone());
+        }
+
+        let table_dir = convert_hdfs_path(self.table.table_location()?, true);
+        let dir = if partition.is_empty() {
+            table_dir
+        } else {
+            let name = make_partition_name(&self.table.partition_columns(), partition);
+            let (db, table) = self.table.database_and_table_name();
+            let catalog = self.table.get_catalog(&self.ctx).await?;
+            let existing = catalog
+                .get_partitions(db, table, vec![name.clone()])
+                .await?;
+            match existing
+                .into_iter()
+                .next()
+                .and_then(|p| p.sd)
+                .and_then(|sd| sd.location)
+            {
+                Some(location) => convert_hdfs_path(&location, true),
+                None => format!("{table_dir}{name}/"),
+            }
+        };
+        self.dirs.insert(partition.to_vec(), dir.clone());
+        Ok(dir)
+    }
+
+    #[async_backtrace::framed]
+    async fn write(&mut self, partition: Vec<String>, batch: RecordBatch) -> Result<()> {
+        let batch = batch
+            .project(&self.data_indices)
+            .map_err(|err| ErrorCode::Internal(format!("Cannot project record batch: {err}")))?;
+
+        if !self.writers.contains_key(&partition) {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(properties))
+                .map_err(|err| {
+                    ErrorCode::Internal(format!("Hive create file writer failed: {err}"))
+                })?;
+            self.writers.insert(partition.clone(), writer);
+        }
+
+        let writer = self.writers.get_mut(&partition).unwrap();
+        writer.write(&batch).map_err(|err| {
+            ErrorCode::Internal(format!(
+                "Hive write table {} failed: {err}",
+                self.table.name()
+            ))
+        })?;
+
+        if writer.bytes_written() + writer.in_progress_size() >= MAX_FILE_SIZE {
+            let writer = self.writers.remove(&partition).unwrap();
+            self.close(partition, writer).await?;
+        }
+        Ok(())
+    }
+
+    /// Upload the file to the staging directory.
+    #[async_backtrace::framed]
+    async fn close(&mut self, partition: Vec<String>, writer: ArrowWriter<Vec<u8>>) -> Result<()> {
+        let data = writer
+            .into_inner()
+            .map_err(|err| ErrorCode::Internal(format!("Hive close file writer failed: {err}")))?;
+        let dir = self.partition_dir(&partition).await?;
+        let name = format!("{}.parquet", Uuid::now_v7().simple());
+        let staging_path = format!("{}{name}", self.staging_dir);
+        self.table.operator().write(&staging_path, data).await?;
+
+        self.files.push(HiveDataFile {
+            partition,
+            path: format!("{dir}{name}"),
+            dir,
+            staging_path,
+        });
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncAccumulatingTransform for HiveTableSink {
+    const NAME: &'static str = "HiveTableSink";
+
+    #[async_backtrace::framed]
+    async fn transform(&mut self, data: DataBlock) -> Result<Option<DataBlock>> {
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let batch = data.to_record_batch(&self.table_schema)?;
+        if self.partition_indices.is_empty() {
+            self.write(vec![], batch).await?;
+            return Ok(None);
+        }
+
+        for (partition, indices) in self.partition(&batch)? {
+            let batch = arrow_select::take::take_record_batch(&batch, &indices)
+                .map_err(|err| ErrorCode::Internal(format!("Cannot split record batch: {err}")))?;
+            self.write(partition, batch).await?;
+        }
+        Ok(None)
+    }
+
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self, _output: bool) -> Result<Option<DataBlock>> {
+        let writers = std::mem::take(&mut self.writers);
+        for (partition, writer) in writers {
+            self.close(partition, writer).await?;
+        }
+
+        Ok(Some(DataBlock::empty_with_meta(Box::new(HiveDataFiles {
+            files: std::mem::take(&mut self.files),
+        }))))
+    }
+}
+
+/// Make the partition name like `c_region=ASIA/c_nation=CHINA`, as
+/// `org.apache.hadoop.hive.common.FileUtils#makePartName` does.
+pub fn make_partition_name(columns: &[String], values: &[String]) -> String {
+    columns
+        .iter()
+        .zip(values)
+        .map(|(column, value)| {
+            format!(
+                "{}={}",
+                escape_path_name(&column.to_lowercase()),
+                escape_path_name(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Escape the characters not allowed in the path, as
+/// `org.apache.hadoop.hive.common.FileUtils#escapePathName` does.
+fn escape_path_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(
+            c,
+            '\u{01}'
+                ..='\u{1F}'
+                    | '"'
+                    | '#'
+                    | '%'
+                    | '\''
+                    | '*'
+                    | '/'
+                    | ':'
+                    | '='
+                    | '?'
+                    | '\\'
+                    | '\u{7F}'
+                    | '{'
+                    | '['
+                    | ']'
+                    | '^'
+        ) {
+            escaped.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::make_partition_name;
+
+    #[test]
+    fn test_make_partition_name() {
+        let columns = vec!["c_region".to_string(), "C_Date".to_string()];
+        let values = vec!["ASIA".to_string(), "2024-01-01 00:00:00".to_string()];
+        assert_eq!(
+            make_partition_name(&columns, &values),
+            "c_region=ASIA/c_date=2024-01-01 00%3A00%3A00"
+        );
+
+        let values = vec![
+            "a/b=c".to_string(),
+            "__HIVE_DEFAULT_PARTITION__".to_string(),
+        ];
+        assert_eq!(
+            make_partition_name(&columns, &values),
+            "c_region=a%2Fb%3Dc/c_date=__HIVE_DEFAULT_PARTITION__"
+        );
+    }
+}
@@ -182,7 +182,7 @@ impl Processor for HiveTableSource {
                 .collect::<Vec<_>>();
             let stream = self
                 .parquet_reader
-                .prepare_data_stream(&part.filename, part.filesize, Some(&partition_fields))
+                .prepare_data_stream(&part.filename, part.filesize, Some(&partition_fields), None)
                 .await?;
             self.stream = Some(stream);
         } else {
@@ -22,7 +22,9 @@ mod hive_database;
 mod hive_partition;
 mod hive_partition_filler;
 mod hive_table;
+mod hive_table_commit;
 mod hive_table_options;
+mod hive_table_sink;
 mod hive_table_source;
 mod utils;
 
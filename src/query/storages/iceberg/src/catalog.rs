@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -565,7 +566,10 @@ impl Catalog for IcebergCatalog {
         _db_name: &str,
         _req: GetTableCopiedFileReq,
     ) -> Result<GetTableCopiedFileReply> {
-        unimplemented!()
+        // Iceberg catalogs do not keep track of the copied files, COPY requires FORCE.
+        Ok(GetTableCopiedFileReply {
+            file_info: BTreeMap::new(),
+        })
     }
 
     #[async_backtrace::framed]
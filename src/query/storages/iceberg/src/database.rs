@@ -15,6 +15,7 @@
 //! Wrapping of the parent directory containing iceberg tables
 
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use arrow_schema::Field;
@@ -36,8 +37,11 @@ use databend_common_meta_app::schema::DropTableByIdReq;
 use databend_common_meta_app::schema::DropTableReply;
 use databend_common_meta_app::tenant::Tenant;
 use databend_common_meta_types::seq_value::SeqV;
+use databend_storages_common_table_meta::table::OPT_KEY_PARTITION_BY;
 use iceberg::arrow::arrow_schema_to_schema;
 use iceberg::spec::Schema as IcebergSchema;
+use iceberg::spec::Transform;
+use iceberg::spec::UnboundPartitionSpec;
 use iceberg::TableCreation;
 use iceberg::TableIdent;
 
@@ -169,7 +173,7 @@ impl Database for IcebergDatabase {
             }
         }
 
-        let table_create_option = TableCreation::builder()
+        let mut table_create_option = TableCreation::builder()
             .name(req.table_name().to_string())
             .properties(HashMap::new())
             .schema(convert_table_schema(
@@ -178,6 +182,12 @@ impl Database for IcebergDatabase {
                 req.table_name(),
             )?)
             .build();
+        if let Some(partition_by) = req.table_meta.options.get(OPT_KEY_PARTITION_BY) {
+            table_create_option.partition_spec = Some(convert_partition_spec(
+                partition_by,
+                &table_create_option.schema,
+            )?);
+        }
 
         let _ = self
             .ctl
@@ -258,3 +268,56 @@ fn convert_table_schema(
 
     Ok(schema) // Return the converted Iceberg schema
 }
+
+/// Convert the `partition_by` table option to the partition spec of iceberg.
+///
+/// The option is a comma separated list of `transform(column)` or `column`, where the
+/// transforms are named as in the iceberg spec, e.g. `day(ts), bucket[16](id), name`.
+fn convert_partition_spec(
+    partition_by: &str,
+    schema: &IcebergSchema,
+) -> Result<UnboundPartitionSpec> {
+    let mut builder = UnboundPartitionSpec::builder();
+    for item in partition_by
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        let (transform, column) = match item.split_once('(') {
+            Some((transform, column)) => {
+                let column = column.strip_suffix(')').ok_or_else(|| {
+                    ErrorCode::TableOptionInvalid(format!(
+                        "invalid partition field `{item}` in table option {OPT_KEY_PARTITION_BY}"
+                    ))
+                })?;
+                let transform = Transform::from_str(transform.trim()).map_err(|err| {
+                    ErrorCode::TableOptionInvalid(format!(
+                        "invalid partition transform `{transform}` in table option {OPT_KEY_PARTITION_BY}: {err:?}"
+                    ))
+                })?;
+                (transform, column.trim())
+            }
+            None => (Transform::Identity, item),
+        };
+
+        let field = schema.field_by_name(column).ok_or_else(|| {
+            ErrorCode::TableOptionInvalid(format!(
+                "unknown partition column `{column}` in table option {OPT_KEY_PARTITION_BY}"
+            ))
+        })?;
+        let name = match transform {
+            Transform::Identity => column.to_string(),
+            Transform::Bucket(_) => format!("{column}_bucket"),
+            Transform::Truncate(_) => format!("{column}_trunc"),
+            _ => format!("{column}_{transform}"),
+        };
+        builder = builder
+            .add_partition_field(field.id, name, transform)
+            .map_err(|err| {
+                ErrorCode::TableOptionInvalid(format!(
+                    "invalid partition field `{item}` in table option {OPT_KEY_PARTITION_BY}: {err:?}"
+                ))
+            })?;
+    }
+    Ok(builder.build())
+}
@@ -0,0 +1,422 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merge-on-read support of the position and equality delete files of iceberg.
+//!
+//! The scan of iceberg only plans the data files, so the snapshots with delete files are
+//! planned by [`plan_files_with_deletes`], which attaches the delete files to the data files
+//! they apply to. The rows of the data files are then filtered by [`IcebergDeleteFilter`]
+//! while reading.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::Array;
+use arrow_array::ArrayRef;
+use arrow_array::BooleanArray;
+use arrow_array::RecordBatch;
+use arrow_cast::display::ArrayFormatter;
+use arrow_cast::display::FormatOptions;
+use arrow_schema::Schema as ArrowSchema;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use iceberg::scan::FileScanTask;
+use iceberg::spec::DataContentType;
+use iceberg::spec::DataFile;
+use iceberg::spec::ManifestContentType;
+use iceberg::spec::ManifestStatus;
+use iceberg::spec::SnapshotRef;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+
+use crate::partition::IcebergDeleteFile;
+use crate::partition::IcebergPartInfo;
+
+/// Reserved field id of the `file_path` column of position delete files.
+const POSITION_DELETE_FILE_PATH_FIELD_ID: i32 = 2147483546;
+/// Reserved field id of the `pos` column of position delete files.
+const POSITION_DELETE_POS_FIELD_ID: i32 = 2147483545;
+
+struct PlannedFile {
+    spec_id: i32,
+    unpartitioned: bool,
+    sequence_number: i64,
+    data_file: DataFile,
+}
+
+impl PlannedFile {
+    fn same_partition(&self, other: &PlannedFile) -> bool {
+        self.spec_id == other.spec_id && self.data_file.partition() == other.data_file.partition()
+    }
+
+    /// Returns the delete file if it should be applied to the data file.
+    fn delete_for(&self, data: &PlannedFile) -> Option<IcebergDeleteFile> {
+        let file_path = self.data_file.file_path().to_string();
+        match self.data_file.content_type() {
+            DataContentType::PositionDeletes => (data.sequence_number <= self.sequence_number
+                && self.same_partition(data))
+            .then_some(IcebergDeleteFile::Position { file_path }),
+            DataContentType::EqualityDeletes => {
+                // Equality deletes in an unpartitioned spec are global deletes.
+                (data.sequence_number < self.sequence_number
+                    && (self.unpartitioned || self.same_partition(data)))
+                .then(|| IcebergDeleteFile::Equality {
+                    file_path,
+                    equality_ids: self.data_file.equality_ids().to_vec(),
+                })
+            }
+            DataContentType::Data => None,
+        }
+    }
+}
+
+/// Plan the data files of the snapshot together with the delete files to apply.
+///
+/// Returns `None` if the snapshot has no delete files.
+pub async fn plan_files_with_deletes(
+    table: &iceberg::table::Table,
+    snapshot: &SnapshotRef,
+    project_field_ids: Vec<i32>,
+) -> Result<Option<Vec<IcebergPartInfo>>> {
+    let metadata = table.metadata();
+    let manifest_list = snapshot
+        .load_manifest_list(table.file_io(), metadata)
+        .await
+        .map_err(|err| ErrorCode::Internal(format!("load manifest list error: {err:?}")))?;
+    if !manifest_list
+        .entries()
+        .iter()
+        .any(|manifest_file| manifest_file.content == ManifestContentType::Deletes)
+    {
+        return Ok(None);
+    }
+
+    let schema = snapshot
+        .schema(metadata)
+        .map_err(|err| ErrorCode::Internal(format!("load snapshot schema error: {err:?}")))?;
+
+    let mut data_files = vec![];
+    let mut delete_files = vec![];
+    for manifest_file in manifest_list.entries() {
+        let manifest = manifest_file
+            .load_manifest(table.file_io())
+            .await
+            .map_err(|err| ErrorCode::Internal(format!("load manifest file error: {err:?}")))?;
+        for entry in manifest.entries() {
+            if entry.status() == ManifestStatus::Deleted {
+                continue;
+            }
+            let file = PlannedFile {
+                spec_id: manifest_file.partition_spec_id,
+                unpartitioned: metadata
+                    .partition_spec_by_id(manifest_file.partition_spec_id)
+                    .is_none_or(|spec| spec.fields().is_empty()),
+                sequence_number: entry
+                    .sequence_number()
+                    .unwrap_or(manifest_file.sequence_number),
+                data_file: entry.data_file().clone(),
+            };
+            match entry.content_type() {
+                DataContentType::Data => data_files.push(file),
+                _ => delete_files.push(file),
+            }
+        }
+    }
+
+    let parts = data_files
+        .iter()
+        .map(|data| {
+            let deletes = delete_files
+                .iter()
+                .filter_map(|delete| delete.delete_for(data))
+                .collect();
+            let data_file = &data.data_file;
+            let task = FileScanTask {
+                start: 0,
+                length: data_file.file_size_in_bytes(),
+                record_count: Some(data_file.record_count()),
+                data_file_path: data_file.file_path().to_string(),
+                data_file_content: DataContentType::Data,
+                data_file_format: data_file.file_format(),
+                schema: schema.clone(),
+                project_field_ids: project_field_ids.clone(),
+                predicate: None,
+            };
+            IcebergPartInfo::with_deletes(task, deletes)
+        })
+        .collect();
+    Ok(Some(parts))
+}
+
+type EqualityKey = Vec<Option<String>>;
+
+/// Loads the delete files, the loaded files are cached since a delete file is usually
+/// applied to many data files.
+#[derive(Default)]
+pub struct IcebergDeleteLoader {
+    /// The deleted positions of each data file in a position delete file.
+    positions: HashMap<String, Arc<HashMap<String, Vec<i64>>>>,
+    /// The deleted keys in an equality delete file.
+    equalities: HashMap<String, Arc<HashSet<EqualityKey>>>,
+}
+
+impl IcebergDeleteLoader {
+    async fn read(&self, table: &iceberg::table::Table, path: &str) -> Result<Vec<RecordBatch>> {
+        let input = table
+            .file_io()
+            .new_input(path)
+            .map_err(|err| ErrorCode::Internal(format!("open delete file {path}: {err:?}")))?;
+        let bytes = input
+            .read()
+            .await
+            .map_err(|err| ErrorCode::Internal(format!("read delete file {path}: {err:?}")))?;
+        ParquetRecordBatchReaderBuilder::try_new(bytes)
+            .and_then(|builder| builder.build())
+            .map_err(|err| ErrorCode::Internal(format!("read delete file {path}: {err:?}")))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|err| ErrorCode::Internal(format!("read delete file {path}: {err:?}")))
+    }
+
+    async fn load_positions(
+        &mut self,
+        table: &iceberg::table::Table,
+        path: &str,
+    ) -> Result<Arc<HashMap<String, Vec<i64>>>> {
+        if let Some(positions) = self.positions.get(path) {
+            return Ok(positions.clone());
+        }
+
+        let mut positions: HashMap<String, Vec<i64>> = HashMap::new();
+        for batch in self.read(table, path).await? {
+            let schema = batch.schema();
+            let file_paths = column_by_field_id(
+                &batch,
+                &schema,
+                POSITION_DELETE_FILE_PATH_FIELD_ID,
+                "file_path",
+            )?;
+            let file_paths = arrow_cast::cast(&file_paths, &arrow_schema::DataType::Utf8)
+                .map_err(|err| ErrorCode::Internal(format!("read delete file {path}: {err}")))?;
+            let file_paths = file_paths.as_string::<i32>();
+            let pos = column_by_field_id(&batch, &schema, POSITION_DELETE_POS_FIELD_ID, "pos")?;
+            let pos = arrow_cast::cast(&pos, &arrow_schema::DataType::Int64)
+                .map_err(|err| ErrorCode::Internal(format!("read delete file {path}: {err}")))?;
+            let pos = pos.as_primitive::<Int64Type>();
+            for row in 0..batch.num_rows() {
+                positions
+                    .entry(file_paths.value(row).to_string())
+                    .or_default()
+                    .push(pos.value(row));
+            }
+        }
+
+        let positions = Arc::new(positions);
+        self.positions.insert(path.to_string(), positions.clone());
+        Ok(positions)
+    }
+
+    async fn load_equalities(
+        &mut self,
+        table: &iceberg::table::Table,
+        path: &str,
+        fields: &[(i32, String, arrow_schema::DataType)],
+    ) -> Result<Arc<HashSet<EqualityKey>>> {
+        if let Some(keys) = self.equalities.get(path) {
+            return Ok(keys.clone());
+        }
+
+        let mut keys = HashSet::new();
+        for batch in self.read(table, path).await? {
+            let schema = batch.schema();
+            let columns = fields
+                .iter()
+                .map(|(field_id, name, data_type)| {
+                    let column = column_by_field_id(&batch, &schema, *field_id, name)?;
+                    arrow_cast::cast(&column, data_type).map_err(|err| {
+                        ErrorCode::Internal(format!("read delete file {path}: {err}"))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            keys.extend(equality_keys(&columns, batch.num_rows())?);
+        }
+
+        let keys = Arc::new(keys);
+        self.equalities.insert(path.to_string(), keys.clone());
+        Ok(keys)
+    }
+}
+
+/// Filters out the deleted rows of a data file.
+pub struct IcebergDeleteFilter {
+    /// The number of rows read from the data file, used as the position of the next row.
+    position: i64,
+    positions: HashSet<i64>,
+    /// The indices of the equality fields in the batch and the deleted keys.
+    equalities: Vec<(Vec<usize>, Arc<HashSet<EqualityKey>>)>,
+    /// The equality fields that are not in the projection are appended to the batch,
+    /// which should be removed after filtering.
+    num_output_columns: usize,
+}
+
+impl IcebergDeleteFilter {
+    /// Adds the equality fields that are not projected to the task, and returns the filter
+    /// of the deletes.
+    pub async fn try_create(
+        loader: &mut IcebergDeleteLoader,
+        table: &iceberg::table::Table,
+        task: &mut FileScanTask,
+        deletes: &[IcebergDeleteFile],
+    ) -> Result<Self> {
+        let num_output_columns = task.project_field_ids.len();
+        let arrow_schema = iceberg::arrow::schema_to_arrow_schema(&task.schema).map_err(|err| {
+            ErrorCode::Internal(format!("Cannot convert table metadata: {err:?}"))
+        })?;
+
+        let mut positions = HashSet::new();
+        let mut equalities = vec![];
+        for delete in deletes {
+            match delete {
+                IcebergDeleteFile::Position { file_path } => {
+                    let deleted = loader.load_positions(table, file_path).await?;
+                    if let Some(deleted) = deleted.get(&task.data_file_path) {
+                        positions.extend(deleted.iter().copied());
+                    }
+                }
+                IcebergDeleteFile::Equality {
+                    file_path,
+                    equality_ids,
+                } => {
+                    let mut indices = Vec::with_capacity(equality_ids.len());
+                    let mut fields = Vec::with_capacity(equality_ids.len());
+                    for field_id in equality_ids {
+                        let field = task.schema.field_by_id(*field_id).ok_or_else(|| {
+                            ErrorCode::Internal(format!(
+                                "Iceberg equality delete file {file_path} refers to unknown field {field_id}"
+                            ))
+                        })?;
+                        let data_type = arrow_schema
+                            .field_with_name(&field.name)
+                            .map_err(|_| {
+                                ErrorCode::Unimplemented(format!(
+                                    "Iceberg equality delete on nested field {} is not supported",
+                                    field.name
+                                ))
+                            })?
+                            .data_type()
+                            .clone();
+                        let index =
+                            match task.project_field_ids.iter().position(|id| id == field_id) {
+                                Some(index) => index,
+                                None => {
+                                    task.project_field_ids.push(*field_id);
+                                    task.project_field_ids.len() - 1
+                                }
+                            };
+                        indices.push(index);
+                        fields.push((*field_id, field.name.clone(), data_type));
+                    }
+                    let keys = loader.load_equalities(table, file_path, &fields).await?;
+                    equalities.push((indices, keys));
+                }
+            }
+        }
+
+        Ok(Self {
+            position: 0,
+            positions,
+            equalities,
+            num_output_columns,
+        })
+    }
+
+    pub fn filter(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let mut keep = (0..num_rows)
+            .map(|row| !self.positions.contains(&(self.position + row as i64)))
+            .collect::<Vec<_>>();
+        self.position += num_rows as i64;
+
+        for (indices, deleted) in self.equalities.iter() {
+            let columns = indices
+                .iter()
+                .map(|index| batch.column(*index).clone())
+                .collect::<Vec<_>>();
+            for (row, key) in equality_keys(&columns, num_rows)?.into_iter().enumerate() {
+                if keep[row] && deleted.contains(&key) {
+                    keep[row] = false;
+                }
+            }
+        }
+
+        let batch = if keep.iter().all(|v| *v) {
+            batch
+        } else {
+            arrow_select::filter::filter_record_batch(&batch, &BooleanArray::from(keep))
+                .map_err(|err| ErrorCode::Internal(format!("apply iceberg deletes: {err}")))?
+        };
+        if batch.num_columns() == self.num_output_columns {
+            return Ok(batch);
+        }
+        batch
+            .project(&(0..self.num_output_columns).collect::<Vec<_>>())
+            .map_err(|err| ErrorCode::Internal(format!("apply iceberg deletes: {err}")))
+    }
+}
+
+fn column_by_field_id(
+    batch: &RecordBatch,
+    schema: &ArrowSchema,
+    field_id: i32,
+    name: &str,
+) -> Result<ArrayRef> {
+    let index = schema
+        .fields()
+        .iter()
+        .position(|field| {
+            field
+                .metadata()
+                .get(PARQUET_FIELD_ID_META_KEY)
+                .is_some_and(|id| id == &field_id.to_string())
+        })
+        .or_else(|| schema.index_of(name).ok())
+        .ok_or_else(|| {
+            ErrorCode::Internal(format!(
+                "Iceberg delete file misses column {name} (field id {field_id})"
+            ))
+        })?;
+    Ok(batch.column(index).clone())
+}
+
+fn equality_keys(columns: &[ArrayRef], num_rows: usize) -> Result<Vec<EqualityKey>> {
+    let options = FormatOptions::default();
+    let formatters = columns
+        .iter()
+        .map(|column| ArrayFormatter::try_new(column.as_ref(), &options))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|err| ErrorCode::Internal(format!("apply iceberg deletes: {err}")))?;
+    Ok((0..num_rows)
+        .map(|row| {
+            columns
+                .iter()
+                .zip(formatters.iter())
+                .map(|(column, formatter)| {
+                    (!column.is_null(row)).then(|| formatter.value(row).to_string())
+                })
+                .collect()
+        })
+        .collect())
+}
@@ -21,11 +21,14 @@
 
 mod catalog;
 mod database;
+mod deletes;
 mod iceberg_inspect;
 mod partition;
 mod predicate;
 mod statistics;
 mod table;
+mod table_commit;
+mod table_sink;
 mod table_source;
 
 pub use catalog::IcebergCatalog;
@@ -23,22 +23,48 @@ use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
-pub struct IcebergPartInfo(iceberg::scan::FileScanTask);
+pub struct IcebergPartInfo {
+    task: iceberg::scan::FileScanTask,
+    /// The delete files that should be applied to the data file of the task.
+    pub deletes: Vec<IcebergDeleteFile>,
+}
 
 impl PartialEq for IcebergPartInfo {
     fn eq(&self, other: &Self) -> bool {
-        self.0.data_file_path == other.0.data_file_path
-            && self.0.start == other.0.start
-            && self.0.length == other.0.length
-            && self.0.predicate == other.0.predicate
-            && self.0.schema == other.0.schema
-            && self.0.project_field_ids == other.0.project_field_ids
+        self.task.data_file_path == other.task.data_file_path
+            && self.task.start == other.task.start
+            && self.task.length == other.task.length
+            && self.task.predicate == other.task.predicate
+            && self.task.schema == other.task.schema
+            && self.task.project_field_ids == other.task.project_field_ids
+            && self.deletes == other.deletes
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IcebergDeleteFile {
+    /// Deletes the rows at the positions of the data file.
+    Position { file_path: String },
+    /// Deletes the rows whose values of the equality fields equal to any row of the file.
+    Equality {
+        file_path: String,
+        equality_ids: Vec<i32>,
+    },
+}
+
 impl IcebergPartInfo {
     pub fn new(task: iceberg::scan::FileScanTask) -> Self {
-        Self(task)
+        Self {
+            task,
+            deletes: vec![],
+        }
+    }
+
+    pub fn with_deletes(
+        task: iceberg::scan::FileScanTask,
+        deletes: Vec<IcebergDeleteFile>,
+    ) -> Self {
+        Self { task, deletes }
     }
 
     pub fn from_part(info: &PartInfoPtr) -> Result<&IcebergPartInfo> {
@@ -48,7 +74,7 @@ impl IcebergPartInfo {
     }
 
     pub fn to_task(&self) -> iceberg::scan::FileScanTask {
-        self.0.clone()
+        self.task.clone()
     }
 }
 
@@ -66,9 +92,9 @@ impl PartInfo for IcebergPartInfo {
 
     fn hash(&self) -> u64 {
         let mut s = DefaultHasher::new();
-        self.0.data_file_path.hash(&mut s);
-        self.0.start.hash(&mut s);
-        self.0.length.hash(&mut s);
+        self.task.data_file_path.hash(&mut s);
+        self.task.start.hash(&mut s);
+        self.task.length.hash(&mut s);
         s.finish()
     }
 }
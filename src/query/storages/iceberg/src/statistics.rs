@@ -48,15 +48,25 @@ pub struct IcebergStatistics {
     pub number_of_manifest_files: u64,
     /// Number of data files in this table
     pub number_of_data_files: u64,
+    /// Number of position and equality delete files in this table
+    pub number_of_delete_files: u64,
 
     /// Computed statistics for each column
     pub computed_statistics: HashMap<ColumnId, statistics::BasicColumnStatistics>,
 }
 
 impl IcebergStatistics {
-    /// Get statistics of an iceberg table.
-    pub async fn parse(table: &iceberg::table::Table) -> Result<IcebergStatistics> {
-        let Some(snapshot) = table.metadata().current_snapshot() else {
+    /// Get statistics of an iceberg table at the given snapshot, or the current snapshot if
+    /// it's not specified.
+    pub async fn parse(
+        table: &iceberg::table::Table,
+        snapshot_id: Option<i64>,
+    ) -> Result<IcebergStatistics> {
+        let snapshot = match snapshot_id {
+            Some(snapshot_id) => table.metadata().snapshot_by_id(snapshot_id),
+            None => table.metadata().current_snapshot(),
+        };
+        let Some(snapshot) = snapshot else {
             return Ok(IcebergStatistics::default());
         };
 
@@ -108,6 +118,7 @@ impl IcebergStatistics {
                 }
                 let data_file = entry.data_file();
                 if data_file.content_type() != DataContentType::Data {
+                    statistics.number_of_delete_files += 1;
                     return;
                 }
 
@@ -165,7 +176,7 @@ impl IcebergStatistics {
         }
 
         let mut computed_statistics = HashMap::new();
-        for field in IcebergTable::get_schema(table)?.fields() {
+        for field in IcebergTable::get_schema(table, snapshot_id)?.fields() {
             let column_stats = get_column_stats(
                 field,
                 &column_sizes,
@@ -29,9 +29,12 @@ use databend_common_catalog::plan::PartitionsShuffleKind;
 use databend_common_catalog::plan::PushDownInfo;
 use databend_common_catalog::table::ColumnStatisticsProvider;
 use databend_common_catalog::table::DistributionLevel;
+use databend_common_catalog::table::NavigationPoint;
 use databend_common_catalog::table::Table;
 use databend_common_catalog::table::TableStatistics;
+use databend_common_catalog::table::TimeNavigation;
 use databend_common_catalog::table_args::TableArgs;
+use databend_common_catalog::table_context::AbortChecker;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
@@ -40,16 +43,27 @@ use databend_common_expression::TableSchema;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
 use databend_common_meta_app::schema::TableMeta;
+use databend_common_meta_app::schema::UpdateStreamMetaReq;
+use databend_common_meta_app::schema::UpsertTableCopiedFileReq;
+use databend_common_pipeline_core::processors::ProcessorPtr;
 use databend_common_pipeline_core::Pipeline;
+use databend_common_pipeline_sinks::AsyncSinker;
+use databend_common_pipeline_transforms::processors::AsyncAccumulatingTransformer;
+use databend_storages_common_table_meta::meta::SnapshotId;
+use databend_storages_common_table_meta::meta::TableMetaTimestamps;
 use databend_storages_common_table_meta::table::ChangeType;
 use futures::TryStreamExt;
 use iceberg::arrow::schema_to_arrow_schema;
 use iceberg::io::FileIOBuilder;
+use iceberg::spec::SnapshotRef;
 
+use crate::deletes::plan_files_with_deletes;
 use crate::partition::IcebergPartInfo;
 use crate::predicate::PredicateBuilder;
 use crate::statistics;
 use crate::statistics::IcebergStatistics;
+use crate::table_commit::IcebergTableCommitter;
+use crate::table_sink::IcebergTableSink;
 use crate::table_source::IcebergTableSource;
 use crate::IcebergCatalog;
 
@@ -61,16 +75,20 @@ pub struct IcebergTable {
     info: TableInfo,
 
     pub table: iceberg::table::Table,
+    /// The snapshot to read if the table is time travelled, otherwise the current snapshot is read.
+    pub snapshot_id: Option<i64>,
     statistics: IcebergStatistics,
 }
 
 impl IcebergTable {
     /// create a new table on the table directory
     pub fn try_create(info: TableInfo) -> Result<Box<dyn Table>> {
-        let (table, statistics) = Self::parse_engine_options(&info.meta.engine_options)?;
+        let (table, snapshot_id, statistics) =
+            Self::parse_engine_options(&info.meta.engine_options)?;
         Ok(Box::new(Self {
             info,
             table,
+            snapshot_id,
             statistics,
         }))
     }
@@ -99,11 +117,22 @@ impl IcebergTable {
         Ok(table)
     }
 
-    pub fn get_schema(table: &iceberg::table::Table) -> Result<TableSchema> {
+    /// Get the schema of the table at the given snapshot, or the current schema if the
+    /// snapshot is not specified.
+    pub fn get_schema(
+        table: &iceberg::table::Table,
+        snapshot_id: Option<i64>,
+    ) -> Result<TableSchema> {
         let meta = table.metadata();
+        let schema = match snapshot_id.and_then(|id| meta.snapshot_by_id(id)) {
+            Some(snapshot) => snapshot.schema(meta).map_err(|e| {
+                ErrorCode::ReadTableDataError(format!("Cannot load snapshot schema: {e:?}"))
+            })?,
+            None => meta.current_schema().clone(),
+        };
 
         // Build arrow schema from iceberg metadata.
-        let arrow_schema = schema_to_arrow_schema(meta.current_schema().as_ref()).map_err(|e| {
+        let arrow_schema = schema_to_arrow_schema(schema.as_ref()).map_err(|e| {
             ErrorCode::ReadTableDataError(format!("Cannot convert table metadata: {e:?}"))
         })?;
         TableSchema::try_from(&arrow_schema)
@@ -116,6 +145,7 @@ impl IcebergTable {
     /// As long as you make sure both [`build_engine_options`] and [`parse_engine_options`] been updated.
     pub fn build_engine_options(
         table: &iceberg::table::Table,
+        snapshot_id: Option<i64>,
         statistics: &statistics::IcebergStatistics,
     ) -> Result<BTreeMap<String, String>> {
         let (file_io_scheme, file_io_props) = table.file_io().clone().into_builder().into_parts();
@@ -128,14 +158,18 @@ impl IcebergTable {
         let identifier = serde_json::to_string(table.identifier())?;
         let statistics = serde_json::to_string(statistics)?;
 
-        Ok(BTreeMap::from_iter([
+        let mut options = BTreeMap::from_iter([
             ("iceberg.file_io.scheme".to_string(), file_io_scheme),
             ("iceberg.file_io.props".to_string(), file_io_props),
             ("iceberg.metadata_location".to_string(), metadata_location),
             ("iceberg.metadata".to_string(), metadata),
             ("iceberg.identifier".to_string(), identifier),
             ("iceberg.statistics".to_string(), statistics),
-        ]))
+        ]);
+        if let Some(snapshot_id) = snapshot_id {
+            options.insert("iceberg.snapshot_id".to_string(), snapshot_id.to_string());
+        }
+        Ok(options)
     }
 
     /// parse_engine_options will parse `engine_options` to [`BTreeMap`] so that we can rebuild the table.
@@ -143,7 +177,11 @@ impl IcebergTable {
     /// See [`build_engine_options`] for more information.
     pub fn parse_engine_options(
         options: &BTreeMap<String, String>,
-    ) -> Result<(iceberg::table::Table, statistics::IcebergStatistics)> {
+    ) -> Result<(
+        iceberg::table::Table,
+        Option<i64>,
+        statistics::IcebergStatistics,
+    )> {
         let file_io_scheme = options.get("iceberg.file_io.scheme").ok_or_else(|| {
             ErrorCode::ReadTableDataError(
                 "Rebuild iceberg table failed: Missing iceberg.file_io.scheme",
@@ -183,6 +221,17 @@ impl IcebergTable {
                 )
             })?)?;
 
+        let snapshot_id = options
+            .get("iceberg.snapshot_id")
+            .map(|v| {
+                v.parse::<i64>().map_err(|err| {
+                    ErrorCode::ReadTableDataError(format!(
+                        "Rebuild iceberg table failed: Invalid iceberg.snapshot_id: {err:?}"
+                    ))
+                })
+            })
+            .transpose()?;
+
         let file_io = FileIOBuilder::new(file_io_scheme)
             .with_props(file_io_props)
             .build()
@@ -202,7 +251,7 @@ impl IcebergTable {
                 ErrorCode::ReadTableDataError(format!("Rebuild iceberg table failed: {err:?}"))
             })?;
 
-        Ok((table, statistics))
+        Ok((table, snapshot_id, statistics))
     }
 
     pub fn try_from_table(tbl: &dyn Table) -> Result<&Self> {
@@ -222,10 +271,10 @@ impl IcebergTable {
         table_name: &str,
     ) -> Result<IcebergTable> {
         let table = Self::load_iceberg_table(&ctl, database_name, table_name).await?;
-        let table_schema = Self::get_schema(&table)?;
-        let statistics = statistics::IcebergStatistics::parse(&table).await?;
+        let table_schema = Self::get_schema(&table, None)?;
+        let statistics = statistics::IcebergStatistics::parse(&table, None).await?;
 
-        let engine_options = Self::build_engine_options(&table, &statistics)?;
+        let engine_options = Self::build_engine_options(&table, None, &statistics)?;
 
         // construct table info
         let info = TableInfo {
@@ -246,10 +295,35 @@ impl IcebergTable {
         Ok(Self {
             info,
             table,
+            snapshot_id: None,
             statistics,
         })
     }
 
+    /// Returns the snapshot to read, `None` if the table is empty.
+    fn snapshot(&self) -> Option<&SnapshotRef> {
+        let metadata = self.table.metadata();
+        match self.snapshot_id {
+            Some(snapshot_id) => metadata.snapshot_by_id(snapshot_id),
+            None => metadata.current_snapshot(),
+        }
+    }
+
+    /// Returns a copy of the table which reads the given snapshot.
+    async fn with_snapshot(&self, snapshot_id: i64) -> Result<Arc<dyn Table>> {
+        let statistics = IcebergStatistics::parse(&self.table, Some(snapshot_id)).await?;
+        let mut info = self.info.clone();
+        info.meta.schema = Arc::new(Self::get_schema(&self.table, Some(snapshot_id))?);
+        info.meta.engine_options =
+            Self::build_engine_options(&self.table, Some(snapshot_id), &statistics)?;
+        Ok(Arc::new(Self {
+            info,
+            table: self.table.clone(),
+            snapshot_id: Some(snapshot_id),
+            statistics,
+        }))
+    }
+
     pub fn do_read_data(
         &self,
         ctx: Arc<dyn TableContext>,
@@ -276,18 +350,67 @@ impl IcebergTable {
         _: Arc<dyn TableContext>,
         push_downs: Option<PushDownInfo>,
     ) -> Result<(PartStatistics, Partitions)> {
-        let mut scan = self.table.scan();
+        let Some(snapshot) = self.snapshot() else {
+            return Ok((PartStatistics::default(), Partitions::default()));
+        };
+
+        // Snapshots with delete files are planned by ourselves since the scan of iceberg
+        // only supports data files.
+        let projection = push_downs
+            .as_ref()
+            .and_then(|v| v.projection.as_ref())
+            .map(|v| v.project_schema(&self.schema()));
+        let schema = snapshot
+            .schema(self.table.metadata())
+            .map_err(|err| ErrorCode::Internal(format!("iceberg load snapshot schema: {err:?}")))?;
+        let project_field_ids = match &projection {
+            Some(projection) => projection
+                .fields
+                .iter()
+                .map(|v| {
+                    schema.field_id_by_name(&v.name).ok_or_else(|| {
+                        ErrorCode::Internal(format!("iceberg field {} not found", v.name))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => schema.as_struct().fields().iter().map(|v| v.id).collect(),
+        };
+        let tasks = match plan_files_with_deletes(&self.table, snapshot, project_field_ids).await? {
+            Some(parts) => parts,
+            None => self.plan_files(snapshot, projection, push_downs).await?,
+        };
 
+        let mut read_rows = 0;
+        let mut read_bytes = 0;
+        let total_files = tasks.len();
+        let parts: Vec<_> = tasks
+            .into_iter()
+            .map(|v: IcebergPartInfo| {
+                let task = v.to_task();
+                read_rows += task.record_count.unwrap_or_default() as usize;
+                read_bytes += task.length as usize;
+                Arc::new(Box::new(v) as Box<dyn PartInfo>)
+            })
+            .collect();
+
+        Ok((
+            PartStatistics::new_exact(read_rows, read_bytes, parts.len(), total_files),
+            Partitions::create(PartitionsShuffleKind::Mod, parts),
+        ))
+    }
+
+    async fn plan_files(
+        &self,
+        snapshot: &SnapshotRef,
+        projection: Option<TableSchema>,
+        push_downs: Option<PushDownInfo>,
+    ) -> Result<Vec<IcebergPartInfo>> {
+        let mut scan = self.table.scan().snapshot_id(snapshot.snapshot_id());
+
+        if let Some(projection) = projection {
+            scan = scan.select(projection.fields.iter().map(|v| v.name.clone()));
+        }
         if let Some(push_downs) = &push_downs {
-            if let Some(projection) = &push_downs.projection {
-                scan = scan.select(
-                    projection
-                        .project_schema(&self.schema())
-                        .fields
-                        .iter()
-                        .map(|v| v.name.clone()),
-                );
-            }
             if let Some(filter) = &push_downs.filters {
                 let (_, predicate) = PredicateBuilder::build(&filter.filter);
                 scan = scan.with_filter(predicate)
@@ -304,22 +427,7 @@ impl IcebergTable {
             .await
             .map_err(|err| ErrorCode::Internal(format!("iceberg table scan collect: {err:?}")))?;
 
-        let mut read_rows = 0;
-        let mut read_bytes = 0;
-        let total_files = tasks.len();
-        let parts: Vec<_> = tasks
-            .into_iter()
-            .map(|v: iceberg::scan::FileScanTask| {
-                read_rows += v.record_count.unwrap_or_default() as usize;
-                read_bytes += v.length as usize;
-                Arc::new(Box::new(IcebergPartInfo::new(v)) as Box<dyn PartInfo>)
-            })
-            .collect();
-
-        Ok((
-            PartStatistics::new_exact(read_rows, read_bytes, parts.len(), total_files),
-            Partitions::create(PartitionsShuffleKind::Mod, parts),
-        ))
+        Ok(tasks.into_iter().map(IcebergPartInfo::new).collect())
     }
 }
 
@@ -347,8 +455,7 @@ impl Table for IcebergTable {
         _require_fresh: bool,
         _change_type: Option<ChangeType>,
     ) -> Result<Option<TableStatistics>> {
-        let table = self.table.clone();
-        if table.metadata().current_snapshot().is_none() {
+        if self.snapshot().is_none() {
             return Ok(None);
         };
 
@@ -390,6 +497,56 @@ impl Table for IcebergTable {
         self.do_read_data(ctx, plan, pipeline)
     }
 
+    fn append_data(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        _table_meta_timestamps: TableMetaTimestamps,
+    ) -> Result<()> {
+        pipeline.add_transform(|input, output| {
+            Ok(ProcessorPtr::create(AsyncAccumulatingTransformer::create(
+                input,
+                output,
+                IcebergTableSink::try_create(self)?,
+            )))
+        })
+    }
+
+    fn commit_insertion(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        pipeline: &mut Pipeline,
+        _copied_files: Option<UpsertTableCopiedFileReq>,
+        update_stream_meta: Vec<UpdateStreamMetaReq>,
+        overwrite: bool,
+        _prev_snapshot_id: Option<SnapshotId>,
+        _deduplicated_label: Option<String>,
+        _table_meta_timestamps: TableMetaTimestamps,
+    ) -> Result<()> {
+        if overwrite {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Overwrite is not supported for iceberg table {}",
+                self.name()
+            )));
+        }
+        // The snapshot of iceberg is committed to the iceberg catalog, the offsets of the
+        // streams can not be updated in the same commit.
+        if !update_stream_meta.is_empty() {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Insert into iceberg table {} from stream is not supported",
+                self.name()
+            )));
+        }
+
+        pipeline.try_resize(1)?;
+        pipeline.add_sink(|input| {
+            Ok(ProcessorPtr::create(AsyncSinker::create(
+                input,
+                IcebergTableCommitter::create(ctx.clone(), self.clone()),
+            )))
+        })
+    }
+
     fn table_args(&self) -> Option<TableArgs> {
         None
     }
@@ -398,11 +555,65 @@ impl Table for IcebergTable {
         true
     }
 
+    fn support_copied_files(&self) -> bool {
+        false
+    }
+
     fn support_prewhere(&self) -> bool {
         false
     }
 
     fn has_exact_total_row_count(&self) -> bool {
-        true
+        // The record count of the data files includes the deleted rows.
+        self.statistics.number_of_delete_files == 0
+    }
+
+    #[async_backtrace::framed]
+    async fn navigate_to(
+        &self,
+        navigation: &TimeNavigation,
+        _abort_checker: AbortChecker,
+    ) -> Result<Arc<dyn Table>> {
+        let metadata = self.table.metadata();
+        let snapshot_id = match navigation {
+            TimeNavigation::TimeTravel(NavigationPoint::SnapshotID(snapshot_id)) => {
+                let snapshot_id = snapshot_id.parse::<i64>().map_err(|_| {
+                    ErrorCode::BadArguments(format!(
+                        "Invalid snapshot id {snapshot_id} of iceberg table {}, expects an integer",
+                        self.name()
+                    ))
+                })?;
+                if metadata.snapshot_by_id(snapshot_id).is_none() {
+                    return Err(ErrorCode::TableHistoricalDataNotFound(format!(
+                        "No snapshot {snapshot_id} found in iceberg table {}",
+                        self.name()
+                    )));
+                }
+                snapshot_id
+            }
+            TimeNavigation::TimeTravel(NavigationPoint::TimePoint(time_point)) => {
+                let timestamp_ms = time_point.timestamp_millis();
+                metadata
+                    .history()
+                    .iter()
+                    .filter(|log| log.timestamp_ms <= timestamp_ms)
+                    .max_by_key(|log| log.timestamp_ms)
+                    .map(|log| log.snapshot_id)
+                    .ok_or_else(|| {
+                        ErrorCode::TableHistoricalDataNotFound(format!(
+                            "No snapshot found in iceberg table {} at or before {time_point}",
+                            self.name()
+                        ))
+                    })?
+            }
+            _ => {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Iceberg table {} only supports time travel by snapshot id or timestamp",
+                    self.name()
+                )));
+            }
+        };
+
+        self.with_snapshot(snapshot_id).await
     }
 }
@@ -0,0 +1,128 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_pipeline_sinks::AsyncSink;
+use iceberg::transaction::Transaction;
+use log::info;
+
+use crate::table_sink::IcebergDataFile;
+use crate::table_sink::IcebergDataFiles;
+use crate::IcebergCatalog;
+use crate::IcebergTable;
+
+/// Collects the data files written by all the [`crate::table_sink::IcebergTableSink`]s
+/// and appends them to the iceberg table in a new snapshot through the catalog.
+pub struct IcebergTableCommitter {
+    ctx: Arc<dyn TableContext>,
+    table: IcebergTable,
+    data_files: Vec<IcebergDataFile>,
+}
+
+impl IcebergTableCommitter {
+    pub fn create(ctx: Arc<dyn TableContext>, table: IcebergTable) -> Self {
+        Self {
+            ctx,
+            table,
+            data_files: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncSink for IcebergTableCommitter {
+    const NAME: &'static str = "IcebergTableCommitter";
+
+    #[async_backtrace::framed]
+    async fn consume(&mut self, data_block: DataBlock) -> Result<bool> {
+        if let Some(meta) = data_block
+            .get_owned_meta()
+            .and_then(IcebergDataFiles::downcast_from)
+        {
+            self.data_files.extend(meta.data_files);
+        }
+        Ok(false)
+    }
+
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self) -> Result<()> {
+        if self.data_files.is_empty() {
+            return Ok(());
+        }
+
+        let table_info = self.table.get_table_info();
+        let catalog = self
+            .ctx
+            .get_catalog(table_info.catalog_info.catalog_name())
+            .await?;
+        let catalog = catalog
+            .as_any()
+            .downcast_ref::<IcebergCatalog>()
+            .ok_or_else(|| {
+                ErrorCode::Internal(format!(
+                    "expects iceberg catalog, but got {}",
+                    table_info.catalog_info.catalog_name()
+                ))
+            })?;
+
+        let table = &self.table.table;
+        let metadata = table.metadata();
+        let partition_type = metadata
+            .default_partition_spec()
+            .partition_type(metadata.current_schema())
+            .map_err(|err| {
+                ErrorCode::Internal(format!("Iceberg build partition type failed: {err:?}"))
+            })?;
+        let data_files = self
+            .data_files
+            .iter()
+            .map(|data_file| data_file.to_data_file(&partition_type))
+            .collect::<Result<Vec<_>>>()?;
+
+        info!(
+            "Iceberg append {} data files to table {}",
+            data_files.len(),
+            table.identifier()
+        );
+        let mut action = Transaction::new(table)
+            .fast_append(None, vec![])
+            .map_err(|err| {
+                ErrorCode::Internal(format!("Iceberg create append action failed: {err:?}"))
+            })?;
+        action.add_data_files(data_files).map_err(|err| {
+            ErrorCode::Internal(format!("Iceberg add data files failed: {err:?}"))
+        })?;
+        let transaction = action.apply().await.map_err(|err| {
+            ErrorCode::Internal(format!("Iceberg apply append action failed: {err:?}"))
+        })?;
+        transaction
+            .commit(catalog.iceberg_catalog().as_ref())
+            .await
+            .map_err(|err| {
+                ErrorCode::StorageOther(format!(
+                    "Iceberg commit table {} failed: {err:?}",
+                    table.identifier()
+                ))
+            })?;
+        Ok(())
+    }
+}
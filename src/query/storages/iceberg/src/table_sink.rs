@@ -0,0 +1,423 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::Date32Type;
+use arrow_array::types::Decimal128Type;
+use arrow_array::types::Int32Type;
+use arrow_array::types::Int64Type;
+use arrow_array::types::TimestampMicrosecondType;
+use arrow_array::ArrayRef;
+use arrow_array::RecordBatch;
+use arrow_array::UInt32Array;
+use arrow_schema::DataType as ArrowDataType;
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use arrow_schema::TimeUnit;
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::BlockMetaInfo;
+use databend_common_expression::BlockMetaInfoDowncast;
+use databend_common_expression::DataBlock;
+use databend_common_expression::TableSchemaRef;
+use databend_common_pipeline_transforms::processors::AsyncAccumulatingTransform;
+use iceberg::arrow::schema_to_arrow_schema;
+use iceberg::spec::DataContentType;
+use iceberg::spec::DataFile;
+use iceberg::spec::DataFileBuilder;
+use iceberg::spec::DataFileFormat;
+use iceberg::spec::Datum;
+use iceberg::spec::Literal;
+use iceberg::spec::Struct;
+use iceberg::spec::StructType;
+use iceberg::spec::Type;
+use iceberg::transform::create_transform_function;
+use iceberg::transform::BoxedTransformFunction;
+use iceberg::writer::base_writer::data_file_writer::DataFileWriter;
+use iceberg::writer::base_writer::data_file_writer::DataFileWriterBuilder;
+use iceberg::writer::file_writer::location_generator::DefaultFileNameGenerator;
+use iceberg::writer::file_writer::location_generator::DefaultLocationGenerator;
+use iceberg::writer::file_writer::ParquetWriterBuilder;
+use iceberg::writer::IcebergWriter;
+use iceberg::writer::IcebergWriterBuilder;
+use parquet::file::properties::WriterProperties;
+use uuid::Uuid;
+
+use crate::IcebergTable;
+
+type IcebergDataFileWriter =
+    DataFileWriter<ParquetWriterBuilder<DefaultLocationGenerator, DefaultFileNameGenerator>>;
+
+/// The data files written by [`IcebergTableSink`], which are committed by
+/// [`crate::table_commit::IcebergTableCommitter`] in a new snapshot.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct IcebergDataFiles {
+    pub data_files: Vec<IcebergDataFile>,
+}
+
+#[typetag::serde(name = "iceberg_data_files")]
+impl BlockMetaInfo for IcebergDataFiles {
+    fn equals(&self, info: &Box<dyn BlockMetaInfo>) -> bool {
+        Self::downcast_ref_from(info).is_some_and(|other| self == other)
+    }
+
+    fn clone_self(&self) -> Box<dyn BlockMetaInfo> {
+        Box::new(self.clone())
+    }
+}
+
+/// A serializable [`DataFile`], so that it can be sent across nodes.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct IcebergDataFile {
+    pub file_path: String,
+    pub file_size_in_bytes: u64,
+    pub record_count: u64,
+    /// The json representation of the partition values.
+    pub partition: serde_json::Value,
+    pub column_sizes: HashMap<i32, u64>,
+    pub value_counts: HashMap<i32, u64>,
+    pub null_value_counts: HashMap<i32, u64>,
+    pub nan_value_counts: HashMap<i32, u64>,
+    pub lower_bounds: HashMap<i32, Datum>,
+    pub upper_bounds: HashMap<i32, Datum>,
+    pub split_offsets: Vec<i64>,
+}
+
+impl IcebergDataFile {
+    pub fn try_create(data_file: DataFile, partition_type: &StructType) -> Result<Self> {
+        let partition = Literal::Struct(data_file.partition().clone())
+            .try_into_json(&Type::Struct(partition_type.clone()))
+            .map_err(|err| {
+                ErrorCode::Internal(format!("Iceberg serialize partition failed: {err:?}"))
+            })?;
+        Ok(Self {
+            file_path: data_file.file_path().to_string(),
+            file_size_in_bytes: data_file.file_size_in_bytes(),
+            record_count: data_file.record_count(),
+            partition,
+            column_sizes: data_file.column_sizes().clone(),
+            value_counts: data_file.value_counts().clone(),
+            null_value_counts: data_file.null_value_counts().clone(),
+            nan_value_counts: data_file.nan_value_counts().clone(),
+            lower_bounds: data_file.lower_bounds().clone(),
+            upper_bounds: data_file.upper_bounds().clone(),
+            split_offsets: data_file.split_offsets().to_vec(),
+        })
+    }
+
+    pub fn to_data_file(&self, partition_type: &StructType) -> Result<DataFile> {
+        let partition = match Literal::try_from_json(
+            self.partition.clone(),
+            &Type::Struct(partition_type.clone()),
+        )
+        .map_err(|err| {
+            ErrorCode::Internal(format!("Iceberg deserialize partition failed: {err:?}"))
+        })? {
+            Some(Literal::Struct(partition)) => partition,
+            _ => Struct::empty(),
+        };
+
+        DataFileBuilder::default()
+            .content(DataContentType::Data)
+            .file_path(self.file_path.clone())
+            .file_format(DataFileFormat::Parquet)
+            .partition(partition)
+            .record_count(self.record_count)
+            .file_size_in_bytes(self.file_size_in_bytes)
+            .column_sizes(self.column_sizes.clone())
+            .value_counts(self.value_counts.clone())
+            .null_value_counts(self.null_value_counts.clone())
+            .nan_value_counts(self.nan_value_counts.clone())
+            .lower_bounds(self.lower_bounds.clone())
+            .upper_bounds(self.upper_bounds.clone())
+            .split_offsets(self.split_offsets.clone())
+            .build()
+            .map_err(|err| ErrorCode::Internal(format!("Iceberg build data file failed: {err:?}")))
+    }
+}
+
+/// Writes the appended blocks to parquet data files of the iceberg table, one data file
+/// writer for each partition. The written data files are sent downstream in a single block
+/// with [`IcebergDataFiles`] meta on finish.
+pub struct IcebergTableSink {
+    table: iceberg::table::Table,
+    table_schema: TableSchemaRef,
+    arrow_schema: ArrowSchemaRef,
+    partitioner: Option<IcebergPartitioner>,
+    writer_builder: ParquetWriterBuilder<DefaultLocationGenerator, DefaultFileNameGenerator>,
+    writers: HashMap<String, IcebergDataFileWriter>,
+}
+
+impl IcebergTableSink {
+    pub fn try_create(table: &IcebergTable) -> Result<Self> {
+        let metadata = table.table.metadata();
+        let iceberg_schema = metadata.current_schema().clone();
+        let arrow_schema = Arc::new(schema_to_arrow_schema(&iceberg_schema).map_err(|err| {
+            ErrorCode::Internal(format!("Cannot convert table metadata: {err:?}"))
+        })?);
+
+        let partition_spec = metadata.default_partition_spec();
+        let partitioner = if partition_spec.is_unpartitioned() {
+            None
+        } else {
+            let partition_type = partition_spec
+                .partition_type(&iceberg_schema)
+                .map_err(|err| {
+                    ErrorCode::Internal(format!("Iceberg build partition type failed: {err:?}"))
+                })?;
+            let mut fields = Vec::with_capacity(partition_spec.fields().len());
+            for field in partition_spec.fields() {
+                let source = iceberg_schema.field_by_id(field.source_id).ok_or_else(|| {
+                    ErrorCode::Internal(format!(
+                        "Iceberg partition field {} refers to unknown column {}",
+                        field.name, field.source_id
+                    ))
+                })?;
+                let index = arrow_schema.index_of(&source.name).map_err(|_| {
+                    ErrorCode::Unimplemented(format!(
+                        "Iceberg partition on nested column {} is not supported",
+                        source.name
+                    ))
+                })?;
+                let transform = create_transform_function(&field.transform).map_err(|err| {
+                    ErrorCode::Internal(format!(
+                        "Iceberg create partition transform failed: {err:?}"
+                    ))
+                })?;
+                fields.push((index, transform));
+            }
+            Some(IcebergPartitioner {
+                partition_type,
+                fields,
+            })
+        };
+
+        let location_generator =
+            DefaultLocationGenerator::new(metadata.clone()).map_err(|err| {
+                ErrorCode::Internal(format!("Iceberg create location generator failed: {err:?}"))
+            })?;
+        let file_name_generator = DefaultFileNameGenerator::new(
+            Uuid::now_v7().simple().to_string(),
+            None,
+            DataFileFormat::Parquet,
+        );
+        let writer_builder = ParquetWriterBuilder::new(
+            WriterProperties::default(),
+            iceberg_schema,
+            table.table.file_io().clone(),
+            location_generator,
+            file_name_generator,
+        );
+
+        Ok(Self {
+            table: table.table.clone(),
+            table_schema: table.schema(),
+            arrow_schema,
+            partitioner,
+            writer_builder,
+            writers: HashMap::new(),
+        })
+    }
+
+    /// Convert the block to a record batch in the arrow schema of the iceberg table.
+    fn to_record_batch(&self, block: DataBlock) -> Result<RecordBatch> {
+        let batch = block.to_record_batch(&self.table_schema)?;
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(self.arrow_schema.fields())
+            .map(|(column, field)| {
+                if column.data_type() == field.data_type() {
+                    Ok(column.clone())
+                } else {
+                    arrow_cast::cast(column, field.data_type()).map_err(|err| {
+                        ErrorCode::Internal(format!(
+                            "Cannot cast column {} to iceberg type {}: {err}",
+                            field.name(),
+                            field.data_type()
+                        ))
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        RecordBatch::try_new(self.arrow_schema.clone(), columns).map_err(|err| {
+            ErrorCode::Internal(format!("Cannot build record batch for iceberg: {err}"))
+        })
+    }
+
+    async fn write(
+        &mut self,
+        key: String,
+        partition: Option<Struct>,
+        batch: RecordBatch,
+    ) -> Result<()> {
+        if !self.writers.contains_key(&key) {
+            let writer = DataFileWriterBuilder::new(self.writer_builder.clone(), partition)
+                .build()
+                .await
+                .map_err(|err| {
+                    ErrorCode::Internal(format!("Iceberg create data file writer failed: {err:?}"))
+                })?;
+            self.writers.insert(key.clone(), writer);
+        }
+
+        let writer = self.writers.get_mut(&key).unwrap();
+        writer.write(batch).await.map_err(|err| {
+            ErrorCode::Internal(format!(
+                "Iceberg write table {} failed: {err:?}",
+                self.table.identifier()
+            ))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncAccumulatingTransform for IcebergTableSink {
+    const NAME: &'static str = "IcebergTableSink";
+
+    #[async_backtrace::framed]
+    async fn transform(&mut self, data: DataBlock) -> Result<Option<DataBlock>> {
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let batch = self.to_record_batch(data)?;
+        let Some(partitioner) = &self.partitioner else {
+            self.write(String::new(), None, batch).await?;
+            return Ok(None);
+        };
+
+        for (key, partition, indices) in partitioner.partition(&batch)? {
+            let batch = arrow_select::take::take_record_batch(&batch, &indices)
+                .map_err(|err| ErrorCode::Internal(format!("Cannot split record batch: {err}")))?;
+            self.write(key, Some(partition), batch).await?;
+        }
+        Ok(None)
+    }
+
+    #[async_backtrace::framed]
+    async fn on_finish(&mut self, _output: bool) -> Result<Option<DataBlock>> {
+        let partition_type = self
+            .partitioner
+            .as_ref()
+            .map(|partitioner| partitioner.partition_type.clone())
+            .unwrap_or_else(|| StructType::new(vec![]));
+
+        let mut data_files = vec![];
+        for (_, mut writer) in self.writers.drain() {
+            let files = writer.close().await.map_err(|err| {
+                ErrorCode::Internal(format!(
+                    "Iceberg close data file writer of table {} failed: {err:?}",
+                    self.table.identifier()
+                ))
+            })?;
+            for data_file in files {
+                data_files.push(IcebergDataFile::try_create(data_file, &partition_type)?);
+            }
+        }
+
+        Ok(Some(DataBlock::empty_with_meta(Box::new(
+            IcebergDataFiles { data_files },
+        ))))
+    }
+}
+
+/// Split the rows of a record batch by the partition spec of the table.
+struct IcebergPartitioner {
+    partition_type: StructType,
+    /// The index of the source column in the record batch and the partition transform.
+    fields: Vec<(usize, BoxedTransformFunction)>,
+}
+
+impl IcebergPartitioner {
+    /// Returns the partition key, partition values and the row indices of each partition.
+    fn partition(&self, batch: &RecordBatch) -> Result<Vec<(String, Struct, UInt32Array)>> {
+        let columns = self
+            .fields
+            .iter()
+            .map(|(index, transform)| {
+                transform
+                    .transform(batch.column(*index).clone())
+                    .map_err(|err| {
+                        ErrorCode::Internal(format!("Iceberg partition transform failed: {err:?}"))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let partition_type = Type::Struct(self.partition_type.clone());
+        let mut partitions: Vec<(String, Struct, Vec<u32>)> = vec![];
+        let mut partition_indices: HashMap<String, usize> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let partition = columns
+                .iter()
+                .map(|column| arrow_value_to_literal(column, row))
+                .collect::<Result<Struct>>()?;
+            let key = Literal::Struct(partition.clone())
+                .try_into_json(&partition_type)
+                .map_err(|err| {
+                    ErrorCode::Internal(format!("Iceberg serialize partition failed: {err:?}"))
+                })?
+                .to_string();
+
+            match partition_indices.get(&key) {
+                Some(index) => partitions[*index].2.push(row as u32),
+                None => {
+                    partition_indices.insert(key.clone(), partitions.len());
+                    partitions.push((key, partition, vec![row as u32]));
+                }
+            }
+        }
+
+        Ok(partitions
+            .into_iter()
+            .map(|(key, partition, rows)| (key, partition, UInt32Array::from(rows)))
+            .collect())
+    }
+}
+
+fn arrow_value_to_literal(array: &ArrayRef, row: usize) -> Result<Option<Literal>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+
+    let literal = match array.data_type() {
+        ArrowDataType::Boolean => Literal::bool(array.as_boolean().value(row)),
+        ArrowDataType::Int32 => Literal::int(array.as_primitive::<Int32Type>().value(row)),
+        ArrowDataType::Int64 => Literal::long(array.as_primitive::<Int64Type>().value(row)),
+        ArrowDataType::Date32 => Literal::date(array.as_primitive::<Date32Type>().value(row)),
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => {
+            Literal::timestamp(array.as_primitive::<TimestampMicrosecondType>().value(row))
+        }
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(_)) => {
+            Literal::timestamptz(array.as_primitive::<TimestampMicrosecondType>().value(row))
+        }
+        ArrowDataType::Decimal128(_, _) => {
+            Literal::decimal(array.as_primitive::<Decimal128Type>().value(row))
+        }
+        ArrowDataType::Utf8 => Literal::string(array.as_string::<i32>().value(row)),
+        ArrowDataType::LargeUtf8 => Literal::string(array.as_string::<i64>().value(row)),
+        ArrowDataType::Utf8View => Literal::string(array.as_string_view().value(row)),
+        ArrowDataType::Binary => Literal::binary(array.as_binary::<i32>().value(row).to_vec()),
+        ArrowDataType::LargeBinary => Literal::binary(array.as_binary::<i64>().value(row).to_vec()),
+        data_type => {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Iceberg partition value of type {data_type} is not supported"
+            )));
+        }
+    };
+    Ok(Some(literal))
+}
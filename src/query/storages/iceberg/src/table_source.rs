@@ -34,6 +34,8 @@ use futures::stream;
 use futures::StreamExt;
 use iceberg::scan::ArrowRecordBatchStream;
 
+use crate::deletes::IcebergDeleteFilter;
+use crate::deletes::IcebergDeleteLoader;
 use crate::partition::IcebergPartInfo;
 use crate::IcebergTable;
 
@@ -51,6 +53,10 @@ pub struct IcebergTableSource {
     // Used to read parquet.
     output_schema: DataSchemaRef,
     stream: Option<ArrowRecordBatchStream>,
+
+    // Used to apply the delete files.
+    delete_loader: IcebergDeleteLoader,
+    delete_filter: Option<IcebergDeleteFilter>,
 }
 
 impl IcebergTableSource {
@@ -68,6 +74,8 @@ impl IcebergTableSource {
             ctx,
             output_schema,
             stream: None,
+            delete_loader: IcebergDeleteLoader::default(),
+            delete_filter: None,
             generated_data: None,
             is_finished: false,
         })))
@@ -124,6 +132,10 @@ impl Processor for IcebergTableSource {
                     ErrorCode::Internal(format!("iceberg data stream read: {err:?}"))
                 })?
             {
+                let batch = match self.delete_filter.as_mut() {
+                    Some(filter) => filter.filter(batch)?,
+                    None => batch,
+                };
                 let block = transform_record_batch(&self.output_schema, &batch, &None)?;
                 let block = check_block_schema(&self.output_schema, block)?;
 
@@ -135,6 +147,20 @@ impl Processor for IcebergTableSource {
             // And we should try to build another stream (in next event loop).
         } else if let Some(part) = self.ctx.get_partition() {
             let part = IcebergPartInfo::from_part(&part)?;
+            let mut task = part.to_task();
+            self.delete_filter = if part.deletes.is_empty() {
+                None
+            } else {
+                Some(
+                    IcebergDeleteFilter::try_create(
+                        &mut self.delete_loader,
+                        &self.table.table,
+                        &mut task,
+                        &part.deletes,
+                    )
+                    .await?,
+                )
+            };
             let reader = self
                 .table
                 .table
@@ -144,7 +170,7 @@ impl Processor for IcebergTableSource {
                 .build();
             // TODO: don't use stream here.
             let stream = reader
-                .read(Box::pin(stream::iter([Ok(part.to_task())])))
+                .read(Box::pin(stream::iter([Ok(task)])))
                 .await
                 .map_err(|err| ErrorCode::Internal(format!("iceberg data stream read: {err:?}")))?;
             self.stream = Some(stream);
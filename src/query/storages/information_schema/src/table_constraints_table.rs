@@ -0,0 +1,55 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use databend_common_catalog::table::Table;
+use databend_common_meta_app::schema::TableIdent;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_common_storages_view::view_table::ViewTable;
+use databend_common_storages_view::view_table::QUERY;
+
+pub struct TableConstraintsTable {}
+
+impl TableConstraintsTable {
+    pub fn create(table_id: u64) -> Arc<dyn Table> {
+        let query = "SELECT DISTINCT
+            'default' AS constraint_catalog,
+            database AS constraint_schema,
+            name AS constraint_name,
+            database AS table_schema,
+            table AS table_name,
+            constraint_type AS constraint_type,
+            IF(enforced, 'YES', 'NO') AS enforced
+        FROM default.system.constraints;";
+
+        let mut options = BTreeMap::new();
+        options.insert(QUERY.to_string(), query.to_string());
+        let table_info = TableInfo {
+            desc: "'default'.'information_schema'.'table_constraints'".to_string(),
+            name: "table_constraints".to_string(),
+            ident: TableIdent::new(table_id, 0),
+            meta: TableMeta {
+                options,
+                engine: "VIEW".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        ViewTable::create(table_info)
+    }
+}
@@ -28,6 +28,7 @@ pub use meta::read_metadata_async_cached;
 pub use meta::read_metas_in_parallel;
 pub use meta::read_metas_in_parallel_for_copy;
 pub use meta::read_parquet_metas_batch;
+pub use parquet_reader::deleted_rows_selection;
 pub use parquet_reader::transform_record_batch;
 pub use parquet_reader::InMemoryRowGroup;
 pub use parquet_reader::ParquetFileReader;
@@ -20,6 +20,7 @@ mod topk;
 mod utils;
 
 pub use read_policy::*;
+pub use reader::deleted_rows_selection;
 pub use reader::ParquetFileReader;
 pub use reader::ParquetRSFullReader;
 pub use reader::ParquetRSReaderBuilder;
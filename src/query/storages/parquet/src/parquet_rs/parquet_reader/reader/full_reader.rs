@@ -35,6 +35,8 @@ use parquet::arrow::arrow_reader::ArrowPredicateFn;
 use parquet::arrow::arrow_reader::ArrowReaderOptions;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::arrow_reader::RowFilter;
+use parquet::arrow::arrow_reader::RowSelection;
+use parquet::arrow::arrow_reader::RowSelector;
 use parquet::arrow::async_reader::AsyncFileReader;
 use parquet::arrow::async_reader::MetadataLoader;
 use parquet::arrow::async_reader::ParquetRecordBatchStream;
@@ -78,11 +80,13 @@ pub struct ParquetRSFullReader {
 
 impl ParquetRSFullReader {
     // partition_fields is only used for delta table engine.
+    // deleted_rows are the sorted positions of the rows in the file to skip, e.g. the deletion vectors of delta.
     pub async fn prepare_data_stream(
         &self,
         loc: &str,
         size: u64,
         partition_fields: Option<&[(TableField, Scalar)]>,
+        deleted_rows: Option<&[u64]>,
     ) -> Result<ParquetRecordBatchStream<ParquetFileReader>> {
         let partition_values_map = partition_fields.map(|arr| {
             arr.iter()
@@ -102,30 +106,40 @@ impl ParquetRSFullReader {
         let mut all_pruned = false;
 
         let file_meta = builder.metadata().clone();
+        let mut selected_row_groups = (0..file_meta.num_row_groups()).collect::<Vec<_>>();
+        let mut row_selection = None;
 
         // Prune row groups.
         if let Some(pruner) = &self.pruner {
-            let (selected_row_groups, omits, _) =
+            let (row_groups, omits, _) =
                 pruner.prune_row_groups(&file_meta, None, partition_values_map.as_ref())?;
             all_pruned = omits.iter().all(|x| *x);
-            builder = builder.with_row_groups(selected_row_groups.clone());
+            builder = builder.with_row_groups(row_groups.clone());
+            selected_row_groups = row_groups;
 
             if !all_pruned {
-                let row_selection = pruner.prune_pages(
+                row_selection = pruner.prune_pages(
                     &file_meta,
                     &selected_row_groups,
                     partition_values_map.as_ref(),
                 )?;
-
-                if let Some(row_selection) = row_selection {
-                    builder = builder.with_row_selection(row_selection);
-                }
             } else {
                 metrics_inc_omit_filter_rowgroups(file_meta.num_row_groups() as u64);
                 metrics_inc_omit_filter_rows(file_meta.file_metadata().num_rows() as u64);
             }
         }
 
+        if let Some(deleted_rows) = deleted_rows.filter(|rows| !rows.is_empty()) {
+            let selection = deleted_rows_selection(&file_meta, &selected_row_groups, deleted_rows);
+            row_selection = Some(match row_selection {
+                Some(row_selection) => row_selection.intersection(&selection),
+                None => selection,
+            });
+        }
+        if let Some(row_selection) = row_selection {
+            builder = builder.with_row_selection(row_selection);
+        }
+
         if !all_pruned {
             if let Some(predicate) = self.predicate.as_ref() {
                 let projection = predicate.projection().clone();
@@ -292,3 +306,36 @@ impl AsyncFileReader for ParquetFileReader {
         })
     }
 }
+
+/// Build the [`RowSelection`] of the selected row groups which skips the deleted rows.
+pub fn deleted_rows_selection(
+    file_meta: &ParquetMetaData,
+    row_groups: &[usize],
+    deleted_rows: &[u64],
+) -> RowSelection {
+    let mut row_group_offsets = Vec::with_capacity(file_meta.num_row_groups());
+    let mut offset = 0;
+    for row_group in file_meta.row_groups() {
+        row_group_offsets.push(offset);
+        offset += row_group.num_rows() as u64;
+    }
+
+    let mut selectors = vec![];
+    for row_group in row_groups {
+        let start = row_group_offsets[*row_group];
+        let end = start + file_meta.row_group(*row_group).num_rows() as u64;
+        let first = deleted_rows.partition_point(|row| *row < start);
+        let mut pos = start;
+        for row in deleted_rows[first..].iter().take_while(|row| **row < end) {
+            if *row > pos {
+                selectors.push(RowSelector::select((*row - pos) as usize));
+            }
+            selectors.push(RowSelector::skip(1));
+            pos = *row + 1;
+        }
+        if end > pos {
+            selectors.push(RowSelector::select((end - pos) as usize));
+        }
+    }
+    RowSelection::from(selectors)
+}
@@ -0,0 +1,98 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow_array::Array;
+use arrow_array::Int64Array;
+use arrow_array::RecordBatch;
+use arrow_schema::DataType;
+use arrow_schema::Field;
+use arrow_schema::Schema;
+use bytes::Bytes;
+use databend_common_storages_parquet::deleted_rows_selection;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+
+/// A file of 10 rows `id = 0..10` in the row groups `[0, 4)`, `[4, 8)` and `[8, 10)`.
+fn write_file() -> Bytes {
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(
+        Int64Array::from_iter_values(0..10),
+    )])
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(4)
+        .build();
+
+    let mut buf = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buf, schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    Bytes::from(buf)
+}
+
+fn read_ids(data: &Bytes, row_groups: &[usize], deleted_rows: &[u64]) -> Vec<i64> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data.clone()).unwrap();
+    let selection = deleted_rows_selection(builder.metadata(), row_groups, deleted_rows);
+    let reader = builder
+        .with_row_groups(row_groups.to_vec())
+        .with_row_selection(selection)
+        .build()
+        .unwrap();
+
+    let mut ids = vec![];
+    for batch in reader {
+        let batch = batch.unwrap();
+        let column = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        ids.extend(column.values().iter());
+    }
+    ids
+}
+
+#[test]
+fn test_deleted_rows_selection() {
+    let data = write_file();
+    let metadata = ParquetRecordBatchReaderBuilder::try_new(data.clone())
+        .unwrap()
+        .metadata()
+        .clone();
+    assert_eq!(metadata.num_row_groups(), 3);
+
+    // The first and the last rows of the row groups.
+    assert_eq!(read_ids(&data, &[0, 1, 2], &[0, 3, 4, 9]), vec![
+        1, 2, 5, 6, 7, 8
+    ]);
+    assert_eq!(
+        read_ids(&data, &[0, 1, 2], &[]),
+        (0..10).collect::<Vec<_>>()
+    );
+
+    // The positions are relative to the file, not to the selected row groups.
+    assert_eq!(read_ids(&data, &[1, 2], &[0, 3, 5, 7, 8]), vec![4, 6, 9]);
+    assert_eq!(read_ids(&data, &[0, 2], &[4, 5, 6, 7, 9]), vec![
+        0, 1, 2, 3, 8
+    ]);
+
+    // All the rows of a row group are deleted.
+    assert_eq!(read_ids(&data, &[1], &[4, 5, 6, 7]), Vec::<i64>::new());
+    assert_eq!(read_ids(&data, &[0, 1, 2], &[4, 5, 6, 7]), vec![
+        0, 1, 2, 3, 8, 9
+    ]);
+}
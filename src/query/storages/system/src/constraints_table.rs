@@ -0,0 +1,198 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_catalog::plan::PushDownInfo;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::types::BooleanType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::StringType;
+use databend_common_expression::types::UInt64Type;
+use databend_common_expression::utils::FromData;
+use databend_common_expression::DataBlock;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchemaRefExt;
+use databend_common_meta_app::schema::TableIdent;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_storages_common_table_meta::table::get_table_constraints;
+use databend_storages_common_table_meta::table::TableConstraintKind;
+use log::warn;
+
+use crate::table::AsyncOneBlockSystemTable;
+use crate::table::AsyncSystemTable;
+
+/// The constraints of the tables, one row for each column of a key, or each column referenced
+/// by a check expression.
+pub struct ConstraintsTable {
+    table_info: TableInfo,
+}
+
+#[async_trait::async_trait]
+impl AsyncSystemTable for ConstraintsTable {
+    const NAME: &'static str = "system.constraints";
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.table_info
+    }
+
+    #[async_backtrace::framed]
+    async fn get_full_data(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        _push_downs: Option<PushDownInfo>,
+    ) -> Result<DataBlock> {
+        let tenant = ctx.get_tenant();
+        let catalog = ctx.get_default_catalog()?;
+        let catalog_name = catalog.name();
+        let visibility_checker = ctx.get_visibility_checker(false).await?;
+
+        let mut databases = vec![];
+        let mut tables = vec![];
+        let mut names = vec![];
+        let mut constraint_types = vec![];
+        let mut column_names = vec![];
+        let mut ordinal_positions = vec![];
+        let mut referenced_databases = vec![];
+        let mut referenced_tables = vec![];
+        let mut referenced_column_names = vec![];
+        let mut check_expressions = vec![];
+        let mut enforced = vec![];
+        for db in catalog.list_databases(&tenant).await? {
+            let db_id = db.get_db_info().database_id.db_id;
+            if !visibility_checker.check_database_visibility(&catalog_name, db.name(), db_id) {
+                continue;
+            }
+            let db_tables = match catalog.list_tables(&tenant, db.name()).await {
+                Ok(tables) => tables,
+                Err(err) => {
+                    let msg = format!("Failed to list tables in database: {}, {}", db.name(), err);
+                    warn!("{}", msg);
+                    ctx.push_warning(msg);
+                    continue;
+                }
+            };
+
+            for table in db_tables {
+                let constraints = get_table_constraints(table.get_table_info().options())?;
+                if constraints.is_empty()
+                    || !visibility_checker.check_table_visibility(
+                        &catalog_name,
+                        db.name(),
+                        table.name(),
+                        db_id,
+                        table.get_id(),
+                    )
+                {
+                    continue;
+                }
+
+                for constraint in constraints {
+                    for (idx, column) in constraint.columns().iter().enumerate() {
+                        databases.push(db.name().to_string());
+                        tables.push(table.name().to_string());
+                        names.push(constraint.name.clone());
+                        constraint_types.push(constraint.constraint_type().to_string());
+                        column_names.push(column.clone());
+                        ordinal_positions.push(idx as u64 + 1);
+                        match &constraint.kind {
+                            TableConstraintKind::ForeignKey {
+                                ref_database,
+                                ref_table,
+                                ref_columns,
+                                ..
+                            } => {
+                                referenced_databases.push(Some(ref_database.clone()));
+                                referenced_tables.push(Some(ref_table.clone()));
+                                referenced_column_names.push(ref_columns.get(idx).cloned());
+                            }
+                            _ => {
+                                referenced_databases.push(None);
+                                referenced_tables.push(None);
+                                referenced_column_names.push(None);
+                            }
+                        }
+                        check_expressions.push(constraint.check_expr().map(|e| e.to_string()));
+                        enforced.push(constraint.is_enforced());
+                    }
+                }
+            }
+        }
+
+        Ok(DataBlock::new_from_columns(vec![
+            StringType::from_data(databases),
+            StringType::from_data(tables),
+            StringType::from_data(names),
+            StringType::from_data(constraint_types),
+            StringType::from_data(column_names),
+            UInt64Type::from_data(ordinal_positions),
+            StringType::from_opt_data(referenced_databases),
+            StringType::from_opt_data(referenced_tables),
+            StringType::from_opt_data(referenced_column_names),
+            StringType::from_opt_data(check_expressions),
+            BooleanType::from_data(enforced),
+        ]))
+    }
+}
+
+impl ConstraintsTable {
+    pub fn create(table_id: u64) -> Arc<dyn Table> {
+        let schema = TableSchemaRefExt::create(vec![
+            TableField::new("database", TableDataType::String),
+            TableField::new("table", TableDataType::String),
+            TableField::new("name", TableDataType::String),
+            TableField::new("constraint_type", TableDataType::String),
+            TableField::new("column_name", TableDataType::String),
+            TableField::new(
+                "ordinal_position",
+                TableDataType::Number(NumberDataType::UInt64),
+            ),
+            TableField::new(
+                "referenced_database",
+                TableDataType::Nullable(Box::new(TableDataType::String)),
+            ),
+            TableField::new(
+                "referenced_table",
+                TableDataType::Nullable(Box::new(TableDataType::String)),
+            ),
+            TableField::new(
+                "referenced_column_name",
+                TableDataType::Nullable(Box::new(TableDataType::String)),
+            ),
+            TableField::new(
+                "check_expression",
+                TableDataType::Nullable(Box::new(TableDataType::String)),
+            ),
+            TableField::new("enforced", TableDataType::Boolean),
+        ]);
+
+        let table_info = TableInfo {
+            desc: "'system'.'constraints'".to_string(),
+            name: "constraints".to_string(),
+            ident: TableIdent::new(table_id, 0),
+            meta: TableMeta {
+                schema,
+                engine: "SystemConstraints".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        AsyncOneBlockSystemTable::create(ConstraintsTable { table_info })
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_catalog::plan::PushDownInfo;
+use databend_common_catalog::table::Table;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::StringType;
+use databend_common_expression::types::TimestampType;
+use databend_common_expression::types::UInt64Type;
+use databend_common_expression::utils::FromData;
+use databend_common_expression::DataBlock;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchemaRefExt;
+use databend_common_meta_app::schema::TableIdent;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_storages_common_table_meta::table::DynamicTableRefreshState;
+use databend_storages_common_table_meta::table::OPT_KEY_AS_QUERY;
+use databend_storages_common_table_meta::table::OPT_KEY_INITIALIZE;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_MODE;
+use databend_storages_common_table_meta::table::OPT_KEY_REFRESH_STATE;
+use databend_storages_common_table_meta::table::OPT_KEY_TARGET_LAG;
+use log::warn;
+
+use crate::table::AsyncOneBlockSystemTable;
+use crate::table::AsyncSystemTable;
+
+pub struct DynamicTablesTable {
+    table_info: TableInfo,
+}
+
+#[async_trait::async_trait]
+impl AsyncSystemTable for DynamicTablesTable {
+    const NAME: &'static str = "system.dynamic_tables";
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.table_info
+    }
+
+    #[async_backtrace::framed]
+    async fn get_full_data(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        _push_downs: Option<PushDownInfo>,
+    ) -> Result<DataBlock> {
+        let tenant = ctx.get_tenant();
+        let catalog = ctx.get_default_catalog()?;
+        let catalog_name = catalog.name();
+        let visibility_checker = ctx.get_visibility_checker(false).await?;
+
+        let mut databases = vec![];
+        let mut names = vec![];
+        let mut table_ids = vec![];
+        let mut target_lags = vec![];
+        let mut refresh_modes = vec![];
+        let mut initializes = vec![];
+        let mut query_texts = vec![];
+        let mut source_tables = vec![];
+        let mut refresh_actions = vec![];
+        let mut data_timestamps = vec![];
+        let mut refreshed_ons = vec![];
+        let mut refresh_counts = vec![];
+        let mut last_errors = vec![];
+        for db in catalog.list_databases(&tenant).await? {
+            let db_id = db.get_db_info().database_id.db_id;
+            if !visibility_checker.check_database_visibility(&catalog_name, db.name(), db_id) {
+                continue;
+            }
+            let tables = match catalog.list_tables(&tenant, db.name()).await {
+                Ok(tables) => tables,
+                Err(err) => {
+                    let msg = format!("Failed to list tables in database: {}, {}", db.name(), err);
+                    warn!("{}", msg);
+                    ctx.push_warning(msg);
+                    continue;
+                }
+            };
+
+            for table in tables {
+                let options = table.get_table_info().options();
+                let Some(query_text) = options.get(OPT_KEY_AS_QUERY) else {
+                    continue;
+                };
+                if !visibility_checker.check_table_visibility(
+                    &catalog_name,
+                    db.name(),
+                    table.name(),
+                    db_id,
+                    table.get_id(),
+                ) {
+                    continue;
+                }
+                let state = match options.get(OPT_KEY_REFRESH_STATE) {
+                    Some(state) => serde_json::from_str(state)?,
+                    None => DynamicTableRefreshState::default(),
+                };
+
+                databases.push(db.name().to_string());
+                names.push(table.name().to_string());
+                table_ids.push(table.get_id());
+                target_lags.push(options.get(OPT_KEY_TARGET_LAG).cloned().unwrap_or_default());
+                refresh_modes.push(
+                    options
+                        .get(OPT_KEY_REFRESH_MODE)
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+                initializes.push(options.get(OPT_KEY_INITIALIZE).cloned().unwrap_or_default());
+                query_texts.push(query_text.clone());
+                source_tables.push(
+                    state
+                        .sources
+                        .iter()
+                        .map(|source| format!("{}.{}", source.database, source.table))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                );
+                refresh_actions.push(state.refresh_action);
+                data_timestamps.push(state.data_timestamp.map(|t| t.timestamp_micros()));
+                refreshed_ons.push(state.refreshed_at.map(|t| t.timestamp_micros()));
+                refresh_counts.push(state.refresh_count);
+                last_errors.push(state.last_error);
+            }
+        }
+
+        Ok(DataBlock::new_from_columns(vec![
+            StringType::from_data(databases),
+            StringType::from_data(names),
+            UInt64Type::from_data(table_ids),
+            StringType::from_data(target_lags),
+            StringType::from_data(refresh_modes),
+            StringType::from_data(initializes),
+            StringType::from_data(query_texts),
+            StringType::from_data(source_tables),
+            StringType::from_opt_data(refresh_actions),
+            TimestampType::from_opt_data(data_timestamps),
+            TimestampType::from_opt_data(refreshed_ons),
+            UInt64Type::from_data(refresh_counts),
+            StringType::from_opt_data(last_errors),
+        ]))
+    }
+}
+
+impl DynamicTablesTable {
+    pub fn create(table_id: u64) -> Arc<dyn Table> {
+        let schema = TableSchemaRefExt::create(vec![
+            TableField::new("database", TableDataType::String),
+            TableField::new("name", TableDataType::String),
+            TableField::new("table_id", TableDataType::Number(NumberDataType::UInt64)),
+            TableField::new("target_lag", TableDataType::String),
+            TableField::new("refresh_mode", TableDataType::String),
+            TableField::new("initialize", TableDataType::String),
+            TableField::new("query_text", TableDataType::String),
+            TableField::new("source_tables", TableDataType::String),
+            TableField::new(
+                "last_refresh_action",
+                TableDataType::Nullable(Box::new(TableDataType::String)),
+            ),
+            TableField::new(
+                "data_timestamp",
+                TableDataType::Nullable(Box::new(TableDataType::Timestamp)),
+            ),
+            TableField::new(
+                "last_refreshed_on",
+                TableDataType::Nullable(Box::new(TableDataType::Timestamp)),
+            ),
+            TableField::new(
+                "refresh_count",
+                TableDataType::Number(NumberDataType::UInt64),
+            ),
+            TableField::new(
+                "last_error",
+                TableDataType::Nullable(Box::new(TableDataType::String)),
+            ),
+        ]);
+
+        let table_info = TableInfo {
+            desc: "'system'.'dynamic_tables'".to_string(),
+            name: "dynamic_tables".to_string(),
+            ident: TableIdent::new(table_id, 0),
+            meta: TableMeta {
+                schema,
+                engine: "SystemDynamicTables".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        AsyncOneBlockSystemTable::create(DynamicTablesTable { table_info })
+    }
+}
@@ -30,10 +30,12 @@ mod clustering_history_table;
 mod clusters_table;
 mod columns_table;
 mod configs_table;
+mod constraints_table;
 mod contributors_table;
 mod credits_table;
 mod databases_table;
 mod dictionaries_table;
+mod dynamic_tables_table;
 mod engines_table;
 mod functions_table;
 mod indexes_table;
@@ -48,6 +50,7 @@ mod notification_history_table;
 mod notifications_table;
 mod one_table;
 mod password_policies_table;
+mod pipes_table;
 mod procedures_table;
 mod processes_table;
 mod queries_profiling;
@@ -81,12 +84,14 @@ pub use clustering_history_table::ClusteringHistoryTable;
 pub use clusters_table::ClustersTable;
 pub use columns_table::ColumnsTable;
 pub use configs_table::ConfigsTable;
+pub use constraints_table::ConstraintsTable;
 pub use contributors_table::ContributorsTable;
 pub use credits_table::CreditsTable;
 pub use databases_table::DatabasesTable;
 pub use databases_table::DatabasesTableWithHistory;
 pub use databases_table::DatabasesTableWithoutHistory;
 pub use dictionaries_table::DictionariesTable;
+pub use dynamic_tables_table::DynamicTablesTable;
 pub use engines_table::EnginesTable;
 pub use functions_table::FunctionsTable;
 pub use indexes_table::IndexesTable;
@@ -104,6 +109,7 @@ pub use notifications_table::parse_notifications_to_datablock;
 pub use notifications_table::NotificationsTable;
 pub use one_table::OneTable;
 pub use password_policies_table::PasswordPoliciesTable;
+pub use pipes_table::PipesTable;
 pub use procedures_table::ProceduresTable;
 pub use processes_table::ProcessesTable;
 pub use queries_profiling::ProfilesLogElement;
@@ -127,9 +133,15 @@ pub use tables_table::TablesTableWithHistory;
 pub use tables_table::TablesTableWithoutHistory;
 pub use tables_table::ViewsTableWithHistory;
 pub use tables_table::ViewsTableWithoutHistory;
+pub use task_history_table::list_local_task_runs;
 pub use task_history_table::parse_task_runs_to_datablock;
+pub use task_history_table::task_run_to_pb;
+pub use task_history_table::LocalTaskRunFilter;
 pub use task_history_table::TaskHistoryTable;
+pub use tasks_table::list_local_tasks;
 pub use tasks_table::parse_tasks_to_datablock;
+pub use tasks_table::task_info_to_pb;
+pub use tasks_table::task_schedule_to_pb;
 pub use tasks_table::TasksTable;
 pub use temp_files_table::TempFilesTable;
 pub use temporary_tables_table::TemporaryTablesTable;
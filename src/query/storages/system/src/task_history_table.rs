@@ -14,6 +14,8 @@
 
 use std::sync::Arc;
 
+use chrono::DateTime;
+use chrono::Utc;
 use databend_common_catalog::plan::PushDownInfo;
 use databend_common_catalog::table::Table;
 use databend_common_catalog::table_context::TableContext;
@@ -21,8 +23,8 @@ use databend_common_cloud_control::client_config::build_client_config;
 use databend_common_cloud_control::cloud_api::CloudControlApiProvider;
 use databend_common_cloud_control::pb::ShowTaskRunsRequest;
 use databend_common_cloud_control::pb::TaskRun;
+use databend_common_cloud_control::pb::WarehouseOptions;
 use databend_common_config::GlobalConfig;
-use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::date_helper::DateConverter;
 use databend_common_expression::infer_table_schema;
@@ -36,14 +38,17 @@ use databend_common_expression::DataBlock;
 use databend_common_expression::FromData;
 use databend_common_expression::Scalar;
 use databend_common_functions::BUILTIN_FUNCTIONS;
+use databend_common_meta_app::principal as mt;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
 use databend_common_meta_app::schema::TableMeta;
 use databend_common_sql::plans::task_run_schema;
+use databend_common_users::UserApiProvider;
 use jiff::tz::TimeZone;
 
 use crate::table::AsyncOneBlockSystemTable;
 use crate::table::AsyncSystemTable;
+use crate::task_schedule_to_pb;
 use crate::util::find_eq_filter;
 use crate::util::find_gt_filter;
 use crate::util::find_lt_filter;
@@ -112,6 +117,84 @@ pub fn parse_task_runs_to_datablock(task_runs: Vec<TaskRun>) -> Result<DataBlock
     ]))
 }
 
+/// Convert a task run of the built-in task scheduler to the message of the cloud control plane,
+/// so that both are displayed in the same way.
+pub fn task_run_to_pb(run: mt::TaskRun) -> TaskRun {
+    TaskRun {
+        task_id: run.task_id,
+        task_name: run.task_name,
+        query_text: run.query_text,
+        comment: run.comment,
+        owner: run.owner,
+        schedule_options: run.schedule_options.as_ref().map(task_schedule_to_pb),
+        run_id: run.run_id,
+        attempt_number: run.attempt_number as i32,
+        warehouse_options: Some(WarehouseOptions {
+            warehouse: run.warehouse,
+            using_warehouse_size: None,
+        }),
+        state: run.state as i32,
+        error_code: run.error_code,
+        error_message: run.error_message,
+        scheduled_time: run.scheduled_at.to_rfc3339(),
+        completed_time: run.completed_at.map(|t| t.to_rfc3339()),
+        query_id: run.query_id,
+        condition_text: run.condition_text,
+        root_task_id: run.root_task_id.to_string(),
+        session_parameters: run.session_params,
+    }
+}
+
+/// Filters on the task runs recorded by the built-in task scheduler.
+#[derive(Clone, Debug, Default)]
+pub struct LocalTaskRunFilter {
+    pub task_name: Option<String>,
+    pub scheduled_time_start: Option<DateTime<Utc>>,
+    pub scheduled_time_end: Option<DateTime<Utc>>,
+    pub error_only: bool,
+    pub root_task_id: Option<u64>,
+    pub result_limit: Option<usize>,
+}
+
+/// List the task runs recorded by the built-in task scheduler, latest first,
+/// of the tasks owned by the available roles of the current user.
+pub async fn list_local_task_runs(
+    ctx: &Arc<dyn TableContext>,
+    filter: &LocalTaskRunFilter,
+) -> Result<Vec<TaskRun>> {
+    let owners = ctx
+        .get_all_available_roles()
+        .await?
+        .into_iter()
+        .map(|x| x.identity().to_string())
+        .collect::<Vec<_>>();
+    let mut runs = UserApiProvider::instance()
+        .get_task_runs(&ctx.get_tenant())
+        .await?
+        .into_iter()
+        .filter(|run| {
+            owners.contains(&run.owner)
+                && filter
+                    .task_name
+                    .as_ref()
+                    .is_none_or(|name| &run.task_name == name)
+                && filter
+                    .scheduled_time_start
+                    .is_none_or(|start| run.scheduled_at >= start)
+                && filter
+                    .scheduled_time_end
+                    .is_none_or(|end| run.scheduled_at <= end)
+                && (!filter.error_only || run.state == mt::TaskRunState::Failed)
+                && filter.root_task_id.is_none_or(|id| run.root_task_id == id)
+        })
+        .collect::<Vec<_>>();
+    runs.sort_by(|a, b| b.scheduled_at.cmp(&a.scheduled_at));
+    if let Some(limit) = filter.result_limit {
+        runs.truncate(limit);
+    }
+    Ok(runs.into_iter().map(task_run_to_pb).collect())
+}
+
 pub struct TaskHistoryTable {
     table_info: TableInfo,
 }
@@ -131,11 +214,7 @@ impl AsyncSystemTable for TaskHistoryTable {
         push_downs: Option<PushDownInfo>,
     ) -> Result<DataBlock> {
         let config = GlobalConfig::instance();
-        if config.query.cloud_control_grpc_server_address.is_none() {
-            return Err(ErrorCode::CloudControlNotEnabled(
-                "cannot view system.task_history table without cloud control enabled, please set cloud_control_grpc_server_address in config",
-            ));
-        }
+        let local = config.query.cloud_control_grpc_server_address.is_none();
 
         let tenant = ctx.get_tenant();
         let query_id = ctx.get_id();
@@ -178,6 +257,17 @@ impl AsyncSystemTable for TaskHistoryTable {
                 });
             }
         }
+        if local {
+            // Task runs are recorded by the built-in task scheduler. The pushed down filters
+            // are evaluated again on the result, only the task name is used to prune here.
+            let filter = LocalTaskRunFilter {
+                task_name,
+                ..Default::default()
+            };
+            let trs = list_local_task_runs(&ctx, &filter).await?;
+            return parse_task_runs_to_datablock(trs);
+        }
+
         let req = ShowTaskRunsRequest {
             tenant_id: tenant.tenant_name().to_string(),
             scheduled_time_start: scheduled_time_start.unwrap_or("".to_string()),
@@ -14,16 +14,19 @@
 
 use std::sync::Arc;
 
+use chrono::Utc;
 use databend_common_catalog::plan::PushDownInfo;
 use databend_common_catalog::table::Table;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_cloud_control::client_config::build_client_config;
 use databend_common_cloud_control::client_config::make_request;
 use databend_common_cloud_control::cloud_api::CloudControlApiProvider;
+use databend_common_cloud_control::pb::schedule_options::ScheduleType;
+use databend_common_cloud_control::pb::ScheduleOptions;
 use databend_common_cloud_control::pb::ShowTasksRequest;
 use databend_common_cloud_control::pb::Task;
+use databend_common_cloud_control::pb::WarehouseOptions;
 use databend_common_config::GlobalConfig;
-use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::infer_table_schema;
 use databend_common_expression::types::StringType;
@@ -32,10 +35,14 @@ use databend_common_expression::types::UInt64Type;
 use databend_common_expression::types::VariantType;
 use databend_common_expression::DataBlock;
 use databend_common_expression::FromData;
+use databend_common_meta_app::principal::TaskInfo;
+use databend_common_meta_app::principal::TaskSchedule;
+use databend_common_meta_app::principal::TaskStatus;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
 use databend_common_meta_app::schema::TableMeta;
 use databend_common_sql::plans::task_schema;
+use databend_common_users::UserApiProvider;
 
 use crate::table::AsyncOneBlockSystemTable;
 use crate::table::AsyncSystemTable;
@@ -102,6 +109,76 @@ pub fn parse_tasks_to_datablock(tasks: Vec<Task>) -> Result<DataBlock> {
     ]))
 }
 
+pub fn task_schedule_to_pb(schedule: &TaskSchedule) -> ScheduleOptions {
+    match schedule {
+        TaskSchedule::Interval { secs, millis } => ScheduleOptions {
+            interval: Some(*secs as i32),
+            milliseconds_interval: if *millis == 0 { None } else { Some(*millis) },
+            cron: None,
+            time_zone: None,
+            schedule_type: i32::from(ScheduleType::IntervalType),
+        },
+        TaskSchedule::Cron { expr, time_zone } => ScheduleOptions {
+            interval: None,
+            milliseconds_interval: None,
+            cron: Some(expr.clone()),
+            time_zone: time_zone.clone(),
+            schedule_type: i32::from(ScheduleType::CronType),
+        },
+    }
+}
+
+/// Convert a task of the built-in task scheduler to the message of the cloud control plane,
+/// so that both are displayed in the same way.
+pub fn task_info_to_pb(task: TaskInfo) -> Task {
+    let next_scheduled_at = match (&task.schedule_options, task.status) {
+        (Some(schedule), TaskStatus::Started) => {
+            schedule.next_time_after(Utc::now()).map(|t| t.to_rfc3339())
+        }
+        _ => None,
+    };
+    Task {
+        task_id: task.task_id,
+        task_name: task.task_name,
+        query_text: task.query_text,
+        comment: task.comment,
+        owner: task.owner,
+        schedule_options: task.schedule_options.as_ref().map(task_schedule_to_pb),
+        warehouse_options: Some(WarehouseOptions {
+            warehouse: task.warehouse,
+            using_warehouse_size: None,
+        }),
+        next_scheduled_at,
+        suspend_task_after_num_failures: task.suspend_task_after_num_failures.map(|n| n as i32),
+        status: task.status as i32,
+        created_at: task.created_at.to_rfc3339(),
+        updated_at: task.updated_at.to_rfc3339(),
+        last_suspended_at: task.last_suspended_at.map(|t| t.to_rfc3339()),
+        after: task.after,
+        when_condition: task.when_condition,
+        session_parameters: task.session_params,
+        error_integration: task.error_integration,
+    }
+}
+
+/// List the tasks of the built-in task scheduler owned by the available roles of the current user.
+pub async fn list_local_tasks(ctx: &Arc<dyn TableContext>) -> Result<Vec<Task>> {
+    let owners = ctx
+        .get_all_available_roles()
+        .await?
+        .into_iter()
+        .map(|x| x.identity().to_string())
+        .collect::<Vec<_>>();
+    let mut tasks = UserApiProvider::instance()
+        .get_tasks(&ctx.get_tenant())
+        .await?
+        .into_iter()
+        .filter(|task| owners.contains(&task.owner))
+        .collect::<Vec<_>>();
+    tasks.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+    Ok(tasks.into_iter().map(task_info_to_pb).collect())
+}
+
 pub struct TasksTable {
     table_info: TableInfo,
 }
@@ -122,9 +199,9 @@ impl AsyncSystemTable for TasksTable {
     ) -> Result<DataBlock> {
         let config = GlobalConfig::instance();
         if config.query.cloud_control_grpc_server_address.is_none() {
-            return Err(ErrorCode::CloudControlNotEnabled(
-                "cannot view system.tasks table without cloud control enabled, please set cloud_control_grpc_server_address in config",
-            ));
+            // Tasks are run by the built-in task scheduler.
+            let tasks = list_local_tasks(&ctx).await?;
+            return parse_tasks_to_datablock(tasks);
         }
 
         let tenant = ctx.get_tenant();
@@ -0,0 +1,84 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use databend_common_exception::Result;
+use databend_common_meta_app::tenant::Tenant;
+
+use crate::UserApiProvider;
+
+/// Prefix of the key of the lease held by the node refreshing the dynamic tables of a tenant.
+const DYNAMIC_TABLE_SCHEDULER_LEASE_PREFIX: &str = "__fd_dynamic_table_scheduler_lease";
+
+/// Prefix of the key of the lease held by the query refreshing a dynamic table.
+const DYNAMIC_TABLE_REFRESH_LEASE_PREFIX: &str = "__fd_dynamic_table_refresh_lease";
+
+/// dynamic table operations.
+impl UserApiProvider {
+    /// Acquire or renew the lease of the dynamic table scheduler of the tenant for `ttl`.
+    ///
+    /// Returns true if `node_id` holds the lease after this call.
+    #[async_backtrace::framed]
+    pub async fn acquire_dynamic_table_scheduler_lease(
+        &self,
+        tenant: &Tenant,
+        node_id: &str,
+        ttl: Duration,
+    ) -> Result<bool> {
+        let key = format!(
+            "{}/{}",
+            DYNAMIC_TABLE_SCHEDULER_LEASE_PREFIX,
+            tenant.tenant_name()
+        );
+        self.acquire_lease(&key, node_id, ttl).await
+    }
+
+    /// Acquire or renew the lease of refreshing the dynamic table `table_id` for `ttl`.
+    ///
+    /// A table is refreshed by one query at a time on all the nodes.
+    /// Returns true if `query_id` holds the lease after this call.
+    #[async_backtrace::framed]
+    pub async fn acquire_dynamic_table_refresh_lease(
+        &self,
+        tenant: &Tenant,
+        table_id: u64,
+        query_id: &str,
+        ttl: Duration,
+    ) -> Result<bool> {
+        let key = dynamic_table_refresh_lease_key(tenant, table_id);
+        self.acquire_lease(&key, query_id, ttl).await
+    }
+
+    /// Release the lease of refreshing the dynamic table `table_id` if `query_id` holds it.
+    #[async_backtrace::framed]
+    pub async fn release_dynamic_table_refresh_lease(
+        &self,
+        tenant: &Tenant,
+        table_id: u64,
+        query_id: &str,
+    ) -> Result<()> {
+        let key = dynamic_table_refresh_lease_key(tenant, table_id);
+        self.release_lease(&key, query_id).await
+    }
+}
+
+fn dynamic_table_refresh_lease_key(tenant: &Tenant, table_id: u64) -> String {
+    format!(
+        "{}/{}/{}",
+        DYNAMIC_TABLE_REFRESH_LEASE_PREFIX,
+        tenant.tenant_name(),
+        table_id
+    )
+}
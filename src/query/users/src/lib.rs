@@ -17,10 +17,13 @@
 
 extern crate core;
 
+mod dynamic_table;
 mod jwt;
 mod network_policy;
 mod password_policy;
+mod pipe;
 mod role_mgr;
+mod task;
 mod user;
 mod user_api;
 mod user_mgr;
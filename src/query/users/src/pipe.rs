@@ -0,0 +1,151 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_api::crud::CrudError;
+use databend_common_meta_app::principal::PipeInfo;
+use databend_common_meta_app::schema::CreateOption;
+use databend_common_meta_app::tenant::Tenant;
+use databend_common_meta_types::MatchSeq;
+
+use crate::UserApiProvider;
+
+/// Prefix of the key of the lease held by the node ingesting a pipe.
+const PIPE_INGEST_LEASE_PREFIX: &str = "__fd_pipe_ingest_lease";
+
+/// user pipe operations.
+impl UserApiProvider {
+    // Add a new pipe.
+    #[async_backtrace::framed]
+    pub async fn add_pipe(
+        &self,
+        tenant: &Tenant,
+        pipe: PipeInfo,
+        create_option: &CreateOption,
+    ) -> Result<()> {
+        let client = self.pipe_api(tenant);
+        client.add(pipe, create_option).await?;
+        Ok(())
+    }
+
+    // Update the options of a pipe, returns None if the pipe does not exist and `if_exists` is true.
+    #[async_backtrace::framed]
+    pub async fn update_pipe(
+        &self,
+        tenant: &Tenant,
+        name: &str,
+        execution_paused: Option<bool>,
+        comment: Option<String>,
+        if_exists: bool,
+    ) -> Result<Option<u64>> {
+        let client = self.pipe_api(tenant);
+        let seq_pipe = match client.get(name, MatchSeq::GE(0)).await {
+            Ok(seq_pipe) => seq_pipe,
+            Err(e) => match e {
+                CrudError::ApiError(meta_err) => {
+                    return Err(ErrorCode::from(meta_err).add_message_back(" (while alter pipe)"));
+                }
+                CrudError::Business(unknown) => {
+                    if if_exists {
+                        return Ok(None);
+                    } else {
+                        return Err(
+                            ErrorCode::from(unknown).add_message_back(" (while alter pipe)")
+                        );
+                    }
+                }
+            },
+        };
+
+        let seq = seq_pipe.seq;
+        let mut pipe = seq_pipe.data;
+        if let Some(execution_paused) = execution_paused {
+            pipe.execution_paused = execution_paused;
+        }
+        if let Some(comment) = comment {
+            pipe.comment = comment;
+        }
+        pipe.updated_on = Some(Utc::now());
+
+        match client.update(pipe, MatchSeq::Exact(seq)).await {
+            Ok(res) => Ok(Some(res)),
+            Err(e) => Err(ErrorCode::from(e).add_message_back(" (while alter pipe).")),
+        }
+    }
+
+    // Drop a pipe by name.
+    #[async_backtrace::framed]
+    pub async fn drop_pipe(&self, tenant: &Tenant, name: &str, if_exists: bool) -> Result<()> {
+        let client = self.pipe_api(tenant);
+        match client.remove(name, MatchSeq::GE(1)).await {
+            Ok(res) => Ok(res),
+            Err(e) => match e {
+                CrudError::ApiError(meta_err) => {
+                    Err(ErrorCode::from(meta_err).add_message_back(" (while drop pipe)"))
+                }
+                CrudError::Business(unknown) => {
+                    if if_exists {
+                        Ok(())
+                    } else {
+                        Err(ErrorCode::from(unknown).add_message_back(" (while drop pipe)"))
+                    }
+                }
+            },
+        }
+    }
+
+    // Get a pipe by name.
+    #[async_backtrace::framed]
+    pub async fn get_pipe(&self, tenant: &Tenant, name: &str) -> Result<PipeInfo> {
+        let client = self.pipe_api(tenant);
+        let pipe = client.get(name, MatchSeq::GE(0)).await?.data;
+        Ok(pipe)
+    }
+
+    // Get all pipes by tenant.
+    #[async_backtrace::framed]
+    pub async fn get_pipes(&self, tenant: &Tenant) -> Result<Vec<PipeInfo>> {
+        let client = self.pipe_api(tenant);
+        let pipes = client.list().await.map_err(|e| {
+            let e = ErrorCode::from(e);
+            e.add_message_back(" (while get pipes).")
+        })?;
+        Ok(pipes)
+    }
+
+    /// Acquire or renew the lease of ingesting the pipe for `ttl`.
+    ///
+    /// The lease is shared by all the clusters of the tenant, so a pipe is ingested
+    /// by one node at a time. Returns true if `node_id` holds the lease after this call.
+    #[async_backtrace::framed]
+    pub async fn acquire_pipe_lease(
+        &self,
+        tenant: &Tenant,
+        name: &str,
+        node_id: &str,
+        ttl: Duration,
+    ) -> Result<bool> {
+        let key = format!(
+            "{}/{}/{}",
+            PIPE_INGEST_LEASE_PREFIX,
+            tenant.tenant_name(),
+            name
+        );
+        self.acquire_lease(&key, node_id, ttl).await
+    }
+}
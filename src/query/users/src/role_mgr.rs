@@ -158,6 +158,15 @@ impl UserApiProvider {
             .map_err(|e| e.add_message_back("(while set role ownership)"))
     }
 
+    #[async_backtrace::framed]
+    pub async fn revoke_ownership(&self, tenant: &Tenant, object: &OwnershipObject) -> Result<()> {
+        let client = self.role_api(tenant);
+        client
+            .revoke_ownership(object)
+            .await
+            .map_err(|e| e.add_message_back("(while revoke ownership)"))
+    }
+
     #[async_backtrace::framed]
     pub async fn get_ownership(
         &self,
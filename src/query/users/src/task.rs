@@ -0,0 +1,205 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_api::crud::CrudError;
+use databend_common_meta_api::fetch_id;
+use databend_common_meta_app::id_generator::IdGenerator;
+use databend_common_meta_app::principal::TaskInfo;
+use databend_common_meta_app::principal::TaskRun;
+use databend_common_meta_app::schema::CreateOption;
+use databend_common_meta_app::tenant::Tenant;
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_types::MatchSeq;
+use databend_common_meta_types::UpsertKV;
+use databend_common_meta_types::With;
+
+use crate::UserApiProvider;
+
+/// How long the history of a task run is kept.
+const TASK_RUN_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Prefix of the key of the lease held by the node running the task scheduler of a tenant.
+const TASK_SCHEDULER_LEASE_PREFIX: &str = "__fd_task_scheduler_lease";
+
+/// user task operations.
+impl UserApiProvider {
+    // Add a new task, a new task id is assigned to it.
+    #[async_backtrace::framed]
+    pub async fn add_task(
+        &self,
+        tenant: &Tenant,
+        mut task: TaskInfo,
+        create_option: &CreateOption,
+    ) -> Result<()> {
+        task.task_id = fetch_id(self.client.as_ref(), IdGenerator::task_id()).await?;
+        let client = self.task_api(tenant);
+        client.add(task, create_option).await?;
+        Ok(())
+    }
+
+    // Update a task with `f`, returns None if the task does not exist and `if_exists` is true.
+    #[async_backtrace::framed]
+    pub async fn update_task(
+        &self,
+        tenant: &Tenant,
+        name: &str,
+        if_exists: bool,
+        f: impl FnOnce(&mut TaskInfo) -> Result<()>,
+    ) -> Result<Option<TaskInfo>> {
+        let client = self.task_api(tenant);
+        let seq_task = match client.get(name, MatchSeq::GE(0)).await {
+            Ok(seq_task) => seq_task,
+            Err(e) => match e {
+                CrudError::ApiError(meta_err) => {
+                    return Err(ErrorCode::from(meta_err).add_message_back(" (while alter task)"));
+                }
+                CrudError::Business(unknown) => {
+                    if if_exists {
+                        return Ok(None);
+                    } else {
+                        return Err(
+                            ErrorCode::from(unknown).add_message_back(" (while alter task)")
+                        );
+                    }
+                }
+            },
+        };
+
+        let seq = seq_task.seq;
+        let mut task = seq_task.data;
+        f(&mut task)?;
+        task.updated_at = Utc::now();
+
+        match client.update(task.clone(), MatchSeq::Exact(seq)).await {
+            Ok(_) => Ok(Some(task)),
+            Err(e) => Err(ErrorCode::from(e).add_message_back(" (while alter task).")),
+        }
+    }
+
+    // Drop a task by name.
+    #[async_backtrace::framed]
+    pub async fn drop_task(&self, tenant: &Tenant, name: &str, if_exists: bool) -> Result<()> {
+        let client = self.task_api(tenant);
+        match client.remove(name, MatchSeq::GE(1)).await {
+            Ok(res) => Ok(res),
+            Err(e) => match e {
+                CrudError::ApiError(meta_err) => {
+                    Err(ErrorCode::from(meta_err).add_message_back(" (while drop task)"))
+                }
+                CrudError::Business(unknown) => {
+                    if if_exists {
+                        Ok(())
+                    } else {
+                        Err(ErrorCode::from(unknown).add_message_back(" (while drop task)"))
+                    }
+                }
+            },
+        }
+    }
+
+    // Get a task by name.
+    #[async_backtrace::framed]
+    pub async fn get_task(&self, tenant: &Tenant, name: &str) -> Result<TaskInfo> {
+        let client = self.task_api(tenant);
+        let task = client.get(name, MatchSeq::GE(0)).await?.data;
+        Ok(task)
+    }
+
+    // Get all tasks by tenant.
+    #[async_backtrace::framed]
+    pub async fn get_tasks(&self, tenant: &Tenant) -> Result<Vec<TaskInfo>> {
+        let client = self.task_api(tenant);
+        let tasks = client.list().await.map_err(|e| {
+            let e = ErrorCode::from(e);
+            e.add_message_back(" (while get tasks).")
+        })?;
+        Ok(tasks)
+    }
+
+    // Record the state of a task run, the record expires after `TASK_RUN_TTL`.
+    #[async_backtrace::framed]
+    pub async fn upsert_task_run(&self, tenant: &Tenant, run: TaskRun) -> Result<()> {
+        let client = self.task_run_api(tenant);
+        client
+            .add_with_ttl(run, Some(TASK_RUN_TTL), &CreateOption::CreateOrReplace)
+            .await?;
+        Ok(())
+    }
+
+    // Get all the recorded task runs by tenant.
+    #[async_backtrace::framed]
+    pub async fn get_task_runs(&self, tenant: &Tenant) -> Result<Vec<TaskRun>> {
+        let client = self.task_run_api(tenant);
+        let runs = client.list().await.map_err(|e| {
+            let e = ErrorCode::from(e);
+            e.add_message_back(" (while get task runs).")
+        })?;
+        Ok(runs)
+    }
+
+    /// Acquire or renew the lease of the task scheduler of the tenant for `ttl`.
+    ///
+    /// Returns true if `node_id` holds the lease after this call. The lease of another node
+    /// can only be taken over after it expires, i.e., that node stopped renewing it.
+    #[async_backtrace::framed]
+    pub async fn acquire_task_scheduler_lease(
+        &self,
+        tenant: &Tenant,
+        node_id: &str,
+        ttl: Duration,
+    ) -> Result<bool> {
+        let key = format!("{}/{}", TASK_SCHEDULER_LEASE_PREFIX, tenant.tenant_name());
+        self.acquire_lease(&key, node_id, ttl).await
+    }
+
+    /// Acquire or renew the lease stored at `key` for `ttl`,
+    /// returns true if `node_id` holds the lease after this call.
+    #[async_backtrace::framed]
+    pub(crate) async fn acquire_lease(
+        &self,
+        key: &str,
+        node_id: &str,
+        ttl: Duration,
+    ) -> Result<bool> {
+        let seq = match self.client.get_kv(key).await? {
+            None => MatchSeq::Exact(0),
+            Some(holder) if holder.data == node_id.as_bytes() => MatchSeq::Exact(holder.seq),
+            Some(_) => return Ok(false),
+        };
+
+        let upsert = UpsertKV::update(key, node_id.as_bytes())
+            .with(seq)
+            .with_ttl(ttl);
+        let res = self.client.upsert_kv(upsert).await?;
+        Ok(res.is_changed())
+    }
+
+    /// Release the lease stored at `key` if `node_id` holds it.
+    #[async_backtrace::framed]
+    pub(crate) async fn release_lease(&self, key: &str, node_id: &str) -> Result<()> {
+        match self.client.get_kv(key).await? {
+            Some(holder) if holder.data == node_id.as_bytes() => {
+                let delete = UpsertKV::delete(key).with(MatchSeq::Exact(holder.seq));
+                self.client.upsert_kv(delete).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -25,6 +25,7 @@ use databend_common_management::ConnectionMgr;
 use databend_common_management::FileFormatMgr;
 use databend_common_management::NetworkPolicyMgr;
 use databend_common_management::PasswordPolicyMgr;
+use databend_common_management::PipeMgr;
 use databend_common_management::ProcedureMgr;
 use databend_common_management::QuotaApi;
 use databend_common_management::QuotaMgr;
@@ -33,6 +34,8 @@ use databend_common_management::RoleMgr;
 use databend_common_management::SettingMgr;
 use databend_common_management::StageApi;
 use databend_common_management::StageMgr;
+use databend_common_management::TaskMgr;
+use databend_common_management::TaskRunMgr;
 use databend_common_management::UserApi;
 use databend_common_management::UserMgr;
 use databend_common_meta_app::principal::AuthInfo;
@@ -166,6 +169,18 @@ impl UserApiProvider {
         PasswordPolicyMgr::create(self.client.clone(), tenant)
     }
 
+    pub fn pipe_api(&self, tenant: &Tenant) -> PipeMgr {
+        PipeMgr::create(self.client.clone(), tenant)
+    }
+
+    pub fn task_api(&self, tenant: &Tenant) -> TaskMgr {
+        TaskMgr::create(self.client.clone(), tenant)
+    }
+
+    pub fn task_run_api(&self, tenant: &Tenant) -> TaskRunMgr {
+        TaskRunMgr::create(self.client.clone(), tenant)
+    }
+
     pub fn client_session_api(&self, tenant: &Tenant) -> ClientSessionMgr {
         ClientSessionMgr::create(self.client.clone(), tenant)
     }
@@ -69,6 +69,7 @@ async fn test_user_network_policy() -> Result<()> {
         hash_value: Vec::from(pwd),
         hash_method: PasswordHashMethod::Sha256,
         need_change: false,
+        scram_verifier: None,
     };
 
     let mut user_info = UserInfo::new(username, hostname, auth_info.clone());
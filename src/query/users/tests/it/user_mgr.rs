@@ -55,6 +55,7 @@ async fn test_user_manager() -> Result<()> {
         hash_value: Vec::from(pwd),
         hash_method: PasswordHashMethod::Sha256,
         need_change: false,
+        scram_verifier: None,
     };
 
     // add user hostname.
@@ -191,6 +192,7 @@ async fn test_user_manager() -> Result<()> {
             hash_value: Vec::from(pwd),
             hash_method: PasswordHashMethod::Sha256,
             need_change: false,
+            scram_verifier: None,
         };
         let user_info: UserInfo = UserInfo::new(user, hostname, auth_info.clone());
         user_mgr
@@ -206,6 +208,7 @@ async fn test_user_manager() -> Result<()> {
             hash_value: Vec::from(new_pwd),
             hash_method: PasswordHashMethod::Sha256,
             need_change: false,
+            scram_verifier: None,
         };
         user_mgr
             .update_user(&tenant, user_info.identity(), Some(auth_info), None)
@@ -226,6 +229,7 @@ async fn test_user_manager() -> Result<()> {
             hash_value: Vec::from(new_new_pwd),
             hash_method: PasswordHashMethod::Sha256,
             need_change: false,
+            scram_verifier: None,
         };
         user_mgr
             .update_user(&tenant, user_info.identity(), Some(auth_info.clone()), None)
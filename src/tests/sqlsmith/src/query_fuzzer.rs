@@ -32,6 +32,7 @@ use databend_common_ast::ast::Window;
 use databend_common_ast::ast::WindowDesc;
 use databend_common_ast::ast::WindowFrame;
 use databend_common_ast::ast::WindowFrameBound;
+use databend_common_ast::ast::WindowFrameExclusion;
 use databend_common_ast::ast::WindowFrameUnits;
 use databend_common_ast::Span;
 use derive_visitor::Drive;
@@ -783,17 +784,24 @@ impl QueryVisitor {
             self.fuzz_order_by(&mut window_spec.order_by);
             // fuzz window frame
             if self.rng.gen_bool(0.4) {
-                let units = if self.rng.gen_bool(0.5) {
-                    WindowFrameUnits::Rows
-                } else {
-                    WindowFrameUnits::Range
+                let units = match self.rng.gen_range(0..3) {
+                    0 => WindowFrameUnits::Rows,
+                    1 => WindowFrameUnits::Range,
+                    _ => WindowFrameUnits::Groups,
                 };
                 let start_bound = self.fuzz_bound();
                 let end_bound = self.fuzz_bound();
+                let exclusion = match self.rng.gen_range(0..4) {
+                    0 => WindowFrameExclusion::CurrentRow,
+                    1 => WindowFrameExclusion::Group,
+                    2 => WindowFrameExclusion::Ties,
+                    _ => WindowFrameExclusion::NoOthers,
+                };
                 let window_frame = WindowFrame {
                     units,
                     start_bound,
                     end_bound,
+                    exclusion,
                 };
                 window_spec.window_frame = Some(window_frame);
             }
@@ -279,7 +279,7 @@ impl<R: Rng> SqlGenerator<'_, R> {
             };
             column_defs.push(column_def);
         }
-        CreateTableSource::Columns(column_defs, None)
+        CreateTableSource::Columns(column_defs, None, None)
     }
 }
 
@@ -86,11 +86,13 @@ impl<'a, R: Rng + 'a> SqlGenerator<'a, R> {
                 catalog: None,
                 database: table.db_name.clone(),
                 table: table.name.clone(),
+                partition: vec![],
                 // TODO
                 columns: vec![],
                 source,
                 // TODO
                 overwrite: false,
+                on_conflict: None,
             };
             insert_stmts.push(insert_stmt);
         }
@@ -529,9 +531,11 @@ impl<'a, R: Rng + 'a> SqlGenerator<'a, R> {
                 catalog: None,
                 database: table.db_name.clone(),
                 table: table.name.clone(),
+                partition: vec![],
                 columns,
                 source,
                 overwrite: false,
+                on_conflict: None,
             })
         } else {
             None
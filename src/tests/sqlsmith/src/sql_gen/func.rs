@@ -24,6 +24,7 @@ use databend_common_ast::ast::Window;
 use databend_common_ast::ast::WindowDesc;
 use databend_common_ast::ast::WindowFrame;
 use databend_common_ast::ast::WindowFrameBound;
+use databend_common_ast::ast::WindowFrameExclusion;
 use databend_common_ast::ast::WindowFrameUnits;
 use databend_common_ast::ast::WindowRef;
 use databend_common_ast::ast::WindowSpec;
@@ -728,6 +729,7 @@ impl<R: Rng> SqlGenerator<'_, R> {
                     units: WindowFrameUnits::Rows,
                     start_bound: WindowFrameBound::Preceding(None),
                     end_bound: WindowFrameBound::CurrentRow,
+                    exclusion: WindowFrameExclusion::NoOthers,
                 })
             },
         }
@@ -833,6 +833,7 @@ impl<R: Rng> SqlGenerator<'_, R> {
         let join = Join {
             op,
             condition,
+            match_condition: None,
             left: Box::new(left_table),
             right: Box::new(right_table),
         };
@@ -0,0 +1,40 @@
+#!/usr/bin/env python3
+
+import os
+import psycopg2
+
+tcp_port = os.getenv("QUERY_POSTGRES_HANDLER_PORT")
+if tcp_port is None:
+    port = "5433"
+else:
+    port = tcp_port
+
+conn = psycopg2.connect(
+    host="127.0.0.1", user="root", password="root", port=port, dbname="default"
+)
+conn.autocommit = True
+cursor = conn.cursor()
+cursor.execute("DROP DATABASE IF EXISTS pg_db")
+cursor.execute("CREATE DATABASE pg_db")
+cursor.execute("USE pg_db")
+cursor.execute("CREATE TABLE t1(a INT, b VARCHAR, c BOOLEAN, d DATE)")
+cursor.execute(
+    "INSERT INTO t1 VALUES (%s, %s, %s, %s), (%s, %s, %s, %s)",
+    (1, "it's", True, "2024-01-02", 2, None, False, "2024-03-04"),
+)
+print(cursor.rowcount)
+
+cursor.execute("SELECT a, b, c, d FROM t1 ORDER BY a")
+for row in cursor.fetchall():
+    print(row)
+
+cursor.execute("SELECT count(*) FROM t1 WHERE b = %s", ("it's",))
+print(cursor.fetchone()[0])
+
+try:
+    cursor.execute("SELECT * FROM not_exists_table")
+except psycopg2.Error as e:
+    print(e.pgcode)
+
+cursor.execute("DROP DATABASE pg_db")
+conn.close()
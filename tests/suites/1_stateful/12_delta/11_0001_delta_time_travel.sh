@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+ROOT=$(realpath "$CURDIR"/../../../data/delta/partitioned/)
+
+stmt "drop table if exists test_delta;"
+stmt "drop table if exists test_delta_v2;"
+
+echo ">>>> create table test_delta engine = delta location = 'fs://\${ROOT}/';"
+echo "create table test_delta engine = delta location = 'fs://${ROOT}/';" | $BENDSQL_CLIENT_CONNECT
+query "select c1 from test_delta at (version => 3) order by c1;"
+query "select c1 from test_delta at (snapshot => 2) order by c1;"
+stmt "select c1 from test_delta at (version => 100);"
+
+echo ">>>> create table test_delta_v2 engine = delta location = 'fs://\${ROOT}/' version_as_of = 2;"
+echo "create table test_delta_v2 engine = delta location = 'fs://${ROOT}/' version_as_of = 2;" | $BENDSQL_CLIENT_CONNECT
+query "select * from test_delta_v2;"
+
+query "select c1, p0, _change_type, _commit_version from delta_changes('default', 'test_delta', 4) order by c1;"
+query "select c1, _change_type, _commit_version from delta_changes('default', 'test_delta', 2, 3) order by c1;"
+
+stmt "drop table test_delta;"
+stmt "drop table test_delta_v2;"
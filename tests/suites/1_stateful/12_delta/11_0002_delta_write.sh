@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+# Write to a copy of the table, to keep the test data unchanged.
+ROOT=$(mktemp -d)
+cp -r "$CURDIR"/../../../data/delta/partitioned/. "$ROOT"/
+
+stmt "drop table if exists test_delta;"
+
+echo ">>>> create table test_delta engine = delta location = 'fs://\${ROOT}/';"
+echo "create table test_delta engine = delta location = 'fs://${ROOT}/';" | $BENDSQL_CLIENT_CONNECT
+
+stmt "insert into test_delta values (30, 51, 12, 53, 54, 55), (30, 61, 62, 63, 64, 65), (10, 71, 12, 73, 14, 75);"
+query "select * from test_delta order by c5;"
+query "select c1 from test_delta where p0 = 30 order by c1;"
+query "select c1 from test_delta where p2 = 12 and p4 = 14 order by c1;"
+query "select c1, _change_type, _commit_version from delta_changes('default', 'test_delta', 6) order by c1;"
+query "select c1 from test_delta at (version => 5) order by c1;"
+
+stmt "insert into test_delta select p0 + 1, c1 + 1, p2, c3, p4, c5 + 100 from test_delta where p0 = 30;"
+query "select p0, c1, c5 from test_delta where p0 = 31 order by c1;"
+
+stmt "insert overwrite test_delta values (40, 81, 82, 83, 84, 85);"
+query "select * from test_delta;"
+query "select count() from test_delta at (version => 7);"
+
+stmt "create or replace table test_delta_src(p0 int, c1 int, p2 int, c3 int, p4 int, c5 int);"
+stmt "create or replace stream test_delta_s on table test_delta_src;"
+stmt "insert into test_delta_src values (50, 91, 92, 93, 94, 95);"
+stmt "insert into test_delta select * from test_delta_s;"
+query "select count() from test_delta_s;"
+stmt "drop stream test_delta_s;"
+stmt "drop table test_delta_src;"
+
+stmt "drop table test_delta;"
+
+echo ">>>> create table test_delta_v2 engine = delta location = 'fs://\${ROOT}/' version_as_of = 5;"
+echo "create table test_delta_v2 engine = delta location = 'fs://${ROOT}/' version_as_of = 5;" | $BENDSQL_CLIENT_CONNECT
+stmt "insert into test_delta_v2 values (30, 51, 12, 53, 54, 55);"
+stmt "drop table test_delta_v2;"
+
+rm -rf "$ROOT"
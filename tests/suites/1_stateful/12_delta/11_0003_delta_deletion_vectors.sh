@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+ROOT=$(realpath "$CURDIR"/../../../data/delta/deletion_vectors/)
+
+stmt "drop table if exists test_delta;"
+
+echo ">>>> create table test_delta engine = delta location = 'fs://\${ROOT}/';"
+echo "create table test_delta engine = delta location = 'fs://${ROOT}/';" | $BENDSQL_CLIENT_CONNECT
+
+# version 1 deletes 3, 499, 500, 501, 999 by a deletion vector file and 1000, 1005 by an inline deletion vector.
+query "select count(*), sum(id) from test_delta;"
+for page_index in 1 0; do
+	query "settings (enable_parquet_page_index = ${page_index}) select id from test_delta where id < 5 order by id;"
+	query "settings (enable_parquet_page_index = ${page_index}) select id from test_delta where id between 495 and 505 order by id;"
+	query "settings (enable_parquet_page_index = ${page_index}) select id from test_delta where id >= 995 order by id;"
+done
+
+query "select count(*), sum(id) from test_delta at (version => 0);"
+query "select id from test_delta at (version => 0) where id between 499 and 501 order by id;"
+
+stmt "drop table test_delta;"
@@ -0,0 +1,18 @@
+insert into hive.default.t_write partition (dt = '2024-01-01', hr = 1) values (1, 'a'), (2, 'b');
+insert into hive.default.t_write partition (dt = '2024-01-01', hr) values (3, 'c', 1), (4, 'd', 2);
+insert into hive.default.t_write partition (dt, hr) values (5, 'e', '2024-01-02', 1), (6, 'f', NULL, NULL);
+insert into hive.default.t_write select 7, 'g', '2024-01-02', 1;
+select * from hive.default.t_write order by id;
+select dt, hr, count(*) from hive.default.t_write group by dt, hr order by dt nulls last, hr;
+insert overwrite hive.default.t_write partition (dt = '2024-01-01', hr = 1) values (8, 'h');
+select * from hive.default.t_write order by id;
+select * from hive.default.t_write where dt = '2024-01-02' order by id;
+insert into hive.default.t_write_np values (1, 'a'), (2, 'b');
+insert into hive.default.t_write_np values (3, 'c');
+select * from hive.default.t_write_np order by id;
+insert overwrite hive.default.t_write_np values (4, 'd');
+select * from hive.default.t_write_np order by id;
+truncate table hive.default.t_write_np;
+select count(*) from hive.default.t_write_np;
+truncate table hive.default.t_write;
+select count(*) from hive.default.t_write;
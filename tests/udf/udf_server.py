@@ -18,6 +18,8 @@ from decimal import Decimal
 import time
 from typing import List, Dict, Any, Tuple, Optional
 
+import pyarrow as pa
+
 # https://github.com/datafuselabs/databend-udf
 from databend_udf import udf, UDFServer
 
@@ -371,8 +373,100 @@ def ping(s: str) -> str:
     return s
 
 
+@udf(
+    input_types=["VARCHAR", "VARCHAR"],
+    result_type="ARRAY(TUPLE(VARCHAR NULL, INT NULL)) NOT NULL",
+    skip_null=False,
+)
+def split_words(s: str, sep: str) -> List[Tuple[str, int]]:
+    """
+    The table function `split_words(STRING, STRING) RETURNS TABLE (word, pos)`,
+    the rows of an input row are returned as a list, a NULL or empty string has none.
+    """
+    if not s:
+        return []
+    return [(word, pos) for pos, word in enumerate(s.split(sep or " "))]
+
+
+class PyAvg:
+    """
+    The aggregate `py_avg(INT) STATE { sum BIGINT, count BIGINT } RETURNS DOUBLE`,
+    the NULL arguments are skipped.
+    """
+
+    name = "py_avg"
+    state_type = pa.struct([("sum", pa.int64()), ("count", pa.int64())])
+    result_type = pa.float64()
+
+    def accumulate(self, groups, values):
+        states = [[0, 0] for _ in range(max(groups) + 1)]
+        for group, value in zip(groups, values):
+            if value is not None:
+                states[group][0] += value
+                states[group][1] += 1
+        return self._states(states)
+
+    def merge(self, groups, partials):
+        states = [[0, 0] for _ in range(max(groups) + 1)]
+        for group, partial in zip(groups, partials):
+            if partial is not None:
+                states[group][0] += partial["sum"]
+                states[group][1] += partial["count"]
+        return self._states(states)
+
+    def finish(self, states):
+        result = [
+            state["sum"] / state["count"] if state and state["count"] else None
+            for state in states
+        ]
+        return pa.array(result, type=self.result_type)
+
+    def _states(self, states):
+        return pa.array(
+            [{"sum": s, "count": c} for s, c in states], type=self.state_type
+        )
+
+
+class AggregateUDFServer(UDFServer):
+    """
+    Serves the aggregates along with the scalar functions. An aggregate phase is
+    called by `do_exchange` with the descriptor path `[name, phase]`, the wire
+    protocol is described in `udaf_server.rs`.
+    """
+
+    def __init__(self, location):
+        super().__init__(location)
+        self._aggregates = {}
+
+    def add_aggregate(self, aggregate):
+        self._aggregates[aggregate.name] = aggregate
+
+    def do_exchange(self, context, descriptor, reader, writer):
+        if len(descriptor.path) != 2:
+            return super().do_exchange(context, descriptor, reader, writer)
+
+        name, phase = (part.decode("utf-8") for part in descriptor.path)
+        if name not in self._aggregates:
+            raise ValueError(f"aggregate function {name} does not exist")
+        aggregate = self._aggregates[name]
+
+        columns = [column.to_pylist() for column in reader.read_all().columns]
+        if phase == "accumulate":
+            output = aggregate.accumulate(columns[0], columns[1])
+        elif phase == "merge":
+            output = aggregate.merge(columns[0], columns[1])
+        elif phase == "finish":
+            output = aggregate.finish(columns[0])
+        else:
+            raise ValueError(f"unknown phase {phase} of aggregate function {name}")
+
+        batch = pa.RecordBatch.from_arrays([output], names=["output"])
+        writer.begin(batch.schema)
+        writer.write_batch(batch)
+
+
 if __name__ == "__main__":
-    udf_server = UDFServer("0.0.0.0:8815")
+    udf_server = AggregateUDFServer("0.0.0.0:8815")
     udf_server.add_function(add_signed)
     udf_server.add_function(add_unsigned)
     udf_server.add_function(add_float)
@@ -397,6 +491,8 @@ if __name__ == "__main__":
     udf_server.add_function(wait)
     udf_server.add_function(wait_concurrent)
     udf_server.add_function(url_len)
+    udf_server.add_function(split_words)
+    udf_server.add_aggregate(PyAvg())
 
     # Built-in function
     udf_server.add_function(ping)